@@ -0,0 +1,44 @@
+//! A stand-in for `mootimerd` that takes `--delay-ms` to start listening,
+//! answers `system.hello` and exits on `daemon.shutdown`.
+
+use mootimer_client::mock::{MockReply, MockServer};
+use serde_json::json;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut socket = None;
+    let mut delay = Duration::ZERO;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--socket" => socket = args.next().map(PathBuf::from),
+            "--delay-ms" => delay = Duration::from_millis(args.next().unwrap_or_default().parse()?),
+            _ => {}
+        }
+    }
+    let socket = socket.ok_or("--socket is required")?;
+
+    tokio::time::sleep(delay).await;
+    let _ = std::fs::remove_file(&socket);
+    let server = MockServer::start_at(&socket).await?;
+    server
+        .on("system.hello", json!({ "name": "fake-mootimerd" }))
+        .await;
+    let exit_socket = socket.clone();
+    server
+        .on_call("daemon.shutdown", move |_| {
+            let socket = exit_socket.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                let _ = std::fs::remove_file(socket);
+                std::process::exit(0);
+            });
+            MockReply::Result(json!({ "status": "shutting_down" }))
+        })
+        .await;
+
+    std::future::pending::<()>().await;
+    Ok(())
+}
@@ -0,0 +1,249 @@
+//! zstd compression for large IPC messages (feature `compression`).
+//!
+//! A client opts in with `"accept_encoding": ["zstd"]` in the params of
+//! `system.hello`. A daemon built with compression answers with
+//! `"compression": { "encoding": "zstd", "threshold_bytes": .. }`, and from
+//! then on sends each response of at least that many bytes of JSON as an
+//! envelope on a line of its own:
+//!
+//! ```text
+//! {"compressed":true,"encoding":"zstd","data":"<base64 of the zstd-compressed JSON>"}
+//! ```
+//!
+//! A daemon without the feature leaves `compression` out and keeps sending
+//! plain lines, as it does to clients that never ask.
+//!
+//! Before `system.hello` negotiation, a client opted in by sending
+//! `"x-accept-encoding": "zstd"` with its first request. The daemon still
+//! honours that, sending large responses as binary frames instead of lines:
+//!
+//! ```text
+//! 0x00 | payload length (u32, big-endian) | zstd-compressed JSON
+//! ```
+//!
+//! Plain messages are JSON objects and always start with `{`, so the first
+//! byte is enough to tell a frame apart. Small responses and notifications
+//! stay uncompressed either way.
+//!
+//! Readers cap both the compressed payload and what it decompresses to, so
+//! a bad message can't make them allocate without bound.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// Request field a client sets to ask for compressed frames.
+pub const ACCEPT_ENCODING_FIELD: &str = "x-accept-encoding";
+
+/// `system.hello` param listing the encodings a client reads envelopes in.
+pub const HELLO_ACCEPT_ENCODING: &str = "accept_encoding";
+
+/// Field of the `system.hello` result confirming envelopes.
+pub const HELLO_COMPRESSION: &str = "compression";
+
+/// The only encoding on offer.
+pub const ZSTD: &str = "zstd";
+
+/// First byte of a compressed frame.
+pub const FRAME_MARKER: u8 = 0x00;
+
+/// Responses shorter than this (in bytes of JSON) are sent as plain lines.
+pub const COMPRESSION_THRESHOLD: usize = 16 * 1024;
+
+/// Largest compressed payload a reader accepts.
+pub const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// Largest JSON a frame may decompress to.
+pub const MAX_DECOMPRESSED_LEN: usize = 256 * 1024 * 1024;
+
+const LEVEL: i32 = 3;
+
+/// A compressed response sent as a JSON line.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Envelope {
+    pub compressed: bool,
+    pub encoding: String,
+    /// Base64 of the compressed JSON.
+    pub data: String,
+}
+
+/// How every envelope line starts, as serialized: lets readers pass over
+/// plain lines without parsing them twice.
+const ENVELOPE_PREFIX: &str = r#"{"compressed":true,"#;
+
+/// The `compression` field of the `system.hello` result.
+pub fn hello_compression() -> serde_json::Value {
+    serde_json::json!({ "encoding": ZSTD, "threshold_bytes": COMPRESSION_THRESHOLD })
+}
+
+/// Wraps `json` in an envelope line, without the newline.
+pub fn encode_envelope(json: &[u8]) -> io::Result<String> {
+    let envelope = Envelope {
+        compressed: true,
+        encoding: ZSTD.to_string(),
+        data: BASE64.encode(zstd::encode_all(json, LEVEL)?),
+    };
+    serde_json::to_string(&envelope).map_err(io::Error::other)
+}
+
+/// The JSON inside `line` if it is an envelope, or `None` for a plain
+/// message.
+pub fn decode_envelope(line: &str) -> Option<io::Result<Vec<u8>>> {
+    let line = line.trim_end();
+    if !line.starts_with(ENVELOPE_PREFIX) {
+        return None;
+    }
+    Some(open_envelope(line))
+}
+
+fn open_envelope(line: &str) -> io::Result<Vec<u8>> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let envelope: Envelope =
+        serde_json::from_str(line).map_err(|e| invalid(format!("bad envelope: {}", e)))?;
+    if envelope.encoding != ZSTD {
+        return Err(invalid(format!("unknown encoding {}", envelope.encoding)));
+    }
+    // Base64 is 4 bytes for every 3.
+    if envelope.data.len() / 4 * 3 > MAX_FRAME_LEN {
+        return Err(invalid(format!(
+            "envelope of {} bytes exceeds limit",
+            envelope.data.len()
+        )));
+    }
+    let payload = BASE64
+        .decode(&envelope.data)
+        .map_err(|e| invalid(format!("bad envelope data: {}", e)))?;
+    decode_payload(&payload)
+}
+
+/// Wraps `json` in a compressed frame, marker and length prefix included.
+pub fn encode_frame(json: &[u8]) -> io::Result<Vec<u8>> {
+    let payload = zstd::encode_all(json, LEVEL)?;
+    let len = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "frame too large"))?;
+
+    let mut frame = Vec::with_capacity(payload.len() + 5);
+    frame.push(FRAME_MARKER);
+    frame.extend_from_slice(&len.to_be_bytes());
+    frame.extend_from_slice(&payload);
+    Ok(frame)
+}
+
+/// Decompresses a frame payload (the bytes after the length prefix).
+pub fn decode_payload(payload: &[u8]) -> io::Result<Vec<u8>> {
+    decode_bounded(payload, MAX_DECOMPRESSED_LEN)
+}
+
+fn decode_bounded(payload: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+    let mut json = Vec::new();
+    zstd::Decoder::new(payload)?
+        .take(limit as u64 + 1)
+        .read_to_end(&mut json)?;
+    if json.len() > limit {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("compressed frame decompresses to more than {} bytes", limit),
+        ));
+    }
+    Ok(json)
+}
+
+/// Reads the rest of a frame after its marker byte and returns the JSON.
+pub async fn read_frame<R>(reader: &mut R) -> io::Result<Vec<u8>>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let len = reader.read_u32().await? as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("compressed frame of {} bytes exceeds limit", len),
+        ));
+    }
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload).await?;
+    decode_payload(&payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_frame_round_trip() {
+        let json = format!(r#"{{"result":"{}"}}"#, "x".repeat(COMPRESSION_THRESHOLD));
+        let frame = encode_frame(json.as_bytes()).unwrap();
+        assert_eq!(frame[0], FRAME_MARKER);
+        assert!(frame.len() < json.len());
+
+        let mut rest = &frame[1..];
+        assert_eq!(read_frame(&mut rest).await.unwrap(), json.as_bytes());
+    }
+
+    #[test]
+    fn test_envelope_round_trip() {
+        let json = format!(r#"{{"result":"{}"}}"#, "x".repeat(COMPRESSION_THRESHOLD));
+        let line = encode_envelope(json.as_bytes()).unwrap();
+        assert!(line.len() < json.len());
+        assert!(!line.contains('\n'));
+        assert_eq!(
+            decode_envelope(&format!("{}\n", line)).unwrap().unwrap(),
+            json.as_bytes()
+        );
+
+        assert!(decode_envelope(&json).is_none());
+        let unknown = line.replace(ZSTD, "brotli");
+        assert!(decode_envelope(&unknown).unwrap().is_err());
+    }
+
+    #[test]
+    fn test_decompressed_size_is_capped() {
+        let zeros = zstd::encode_all(&[0u8; 4096][..], LEVEL).unwrap();
+        assert_eq!(decode_bounded(&zeros, 4096).unwrap().len(), 4096);
+        let err = decode_bounded(&zeros, 4095).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    /// A month of entries from every profile is the largest reply there
+    /// is; 10k of them as `entry.list` returns them.
+    #[test]
+    fn test_compresses_entry_lists() {
+        use chrono::{Duration, TimeZone, Utc};
+        use mootimer_core::models::{Entry, TimerMode};
+
+        let start = Utc.with_ymd_and_hms(2024, 5, 1, 8, 0, 0).unwrap();
+        let entries: Vec<Entry> = (0..10_000)
+            .map(|i| {
+                let begin = start + Duration::minutes(i * 45);
+                let mut entry = Entry::create_completed(
+                    Some(format!("task-{}", i % 40)),
+                    Some(format!("Entry {} with a reasonably long description", i)),
+                    begin,
+                    begin + Duration::minutes(30),
+                    TimerMode::Pomodoro,
+                )
+                .unwrap();
+                entry.tags = vec!["client".to_string()];
+                entry
+            })
+            .collect();
+        let json =
+            serde_json::to_vec(&serde_json::json!({"jsonrpc": "2.0", "result": entries, "id": 1}))
+                .unwrap();
+
+        let frame = encode_frame(&json).unwrap();
+        let ratio = frame.len() as f64 / json.len() as f64;
+        assert!(ratio < 0.2, "compressed to {:.1}%", ratio * 100.0);
+        assert_eq!(decode_payload(&frame[5..]).unwrap(), json);
+
+        // Base64 costs a third on top, which still leaves envelopes far
+        // smaller than the JSON.
+        let envelope = encode_envelope(&json).unwrap();
+        let ratio = envelope.len() as f64 / json.len() as f64;
+        assert!(ratio < 0.3, "enveloped to {:.1}%", ratio * 100.0);
+        assert_eq!(decode_envelope(&envelope).unwrap().unwrap(), json);
+    }
+}
@@ -0,0 +1,199 @@
+//! Finding a running daemon, or starting one.
+//!
+//! [`ensure_daemon`] is what a front end calls before anything else: it
+//! checks whether `mootimerd` answers on the socket and, if not, starts it
+//! and waits until it does.
+//!
+//! ```no_run
+//! # async fn demo() -> anyhow::Result<()> {
+//! use mootimer_client::daemon::{SpawnOptions, ensure_daemon};
+//!
+//! let daemon = ensure_daemon("/tmp/mootimer.sock", SpawnOptions::default()).await?;
+//! if daemon.spawned() {
+//!     eprintln!("Started mootimerd (PID {:?})", daemon.pid());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{Result, anyhow, bail};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::{Child, Command};
+use tokio::time::Instant;
+
+use crate::MooTimerClient;
+
+/// How a spawned daemon relates to the process that started it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpawnMode {
+    /// Keeps running after the caller exits, in its own process group so a
+    /// Ctrl+C in the caller's terminal doesn't reach it.
+    #[default]
+    Detached,
+    /// Killed when the [`DaemonHandle`] is dropped.
+    Child,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpawnOptions {
+    /// Start the daemon if none answers; otherwise fail.
+    pub spawn: bool,
+    /// Looked up on `PATH` unless it contains a slash.
+    pub binary: PathBuf,
+    /// Passed after `--socket <path>`.
+    pub args: Vec<String>,
+    pub mode: SpawnMode,
+    /// How long a spawned daemon gets to start answering.
+    pub ready_timeout: Duration,
+    /// How often readiness is checked until then.
+    pub poll_interval: Duration,
+}
+
+impl Default for SpawnOptions {
+    fn default() -> Self {
+        Self {
+            spawn: true,
+            binary: PathBuf::from("mootimerd"),
+            args: Vec::new(),
+            mode: SpawnMode::default(),
+            ready_timeout: Duration::from_secs(5),
+            poll_interval: Duration::from_millis(50),
+        }
+    }
+}
+
+/// How long each readiness or liveness probe may take.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// A daemon known to be answering on `socket_path`.
+#[derive(Debug)]
+pub struct DaemonHandle {
+    socket_path: String,
+    /// Set when `ensure_daemon` started the daemon itself.
+    child: Option<Child>,
+}
+
+impl DaemonHandle {
+    pub fn socket_path(&self) -> &str {
+        &self.socket_path
+    }
+
+    /// Whether `ensure_daemon` had to start the daemon.
+    pub fn spawned(&self) -> bool {
+        self.child.is_some()
+    }
+
+    /// The spawned daemon's process id, if it was spawned here.
+    pub fn pid(&self) -> Option<u32> {
+        self.child.as_ref().and_then(Child::id)
+    }
+
+    /// A client for the daemon. It connects on its first call.
+    pub fn client(&self) -> MooTimerClient {
+        MooTimerClient::new(self.socket_path.as_str())
+    }
+
+    /// Asks the daemon to exit with `daemon.shutdown` and waits up to
+    /// `timeout` for it to stop answering. A daemon spawned here that is
+    /// still up by then is killed.
+    pub async fn shutdown(mut self, timeout: Duration) -> Result<()> {
+        let requested = self
+            .client()
+            .with_request_timeout(PROBE_TIMEOUT)
+            .call("daemon.shutdown", None)
+            .await;
+
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            if !answers(&self.socket_path).await {
+                if let Some(child) = self.child.as_mut() {
+                    let _ = child.wait().await;
+                }
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        match self.child.as_mut() {
+            Some(child) => {
+                child.kill().await?;
+                Ok(())
+            }
+            None => match requested {
+                Ok(_) => bail!("Daemon still running {:?} after shutdown", timeout),
+                Err(e) => Err(e.context("Daemon did not accept daemon.shutdown")),
+            },
+        }
+    }
+}
+
+async fn answers(socket_path: &str) -> bool {
+    MooTimerClient::new(socket_path)
+        .with_request_timeout(PROBE_TIMEOUT)
+        .health_check()
+        .await
+        .is_ok()
+}
+
+/// Returns a handle to the daemon on `socket_path`, starting it first if
+/// nothing answers there and `options.spawn` allows it.
+pub async fn ensure_daemon(socket_path: &str, options: SpawnOptions) -> Result<DaemonHandle> {
+    if answers(socket_path).await {
+        return Ok(DaemonHandle {
+            socket_path: socket_path.to_string(),
+            child: None,
+        });
+    }
+    if !options.spawn {
+        bail!("No daemon is answering on {}", socket_path);
+    }
+
+    let mut command = Command::new(&options.binary);
+    command
+        .arg("--socket")
+        .arg(socket_path)
+        .args(&options.args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    match options.mode {
+        SpawnMode::Detached => {
+            command.process_group(0);
+        }
+        SpawnMode::Child => {
+            command.kill_on_drop(true);
+        }
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| anyhow!("Failed to start {}: {}", options.binary.display(), e))?;
+
+    let deadline = Instant::now() + options.ready_timeout;
+    loop {
+        if answers(socket_path).await {
+            return Ok(DaemonHandle {
+                socket_path: socket_path.to_string(),
+                child: Some(child),
+            });
+        }
+        if let Some(status) = child.try_wait()? {
+            bail!(
+                "{} exited during startup ({})",
+                options.binary.display(),
+                status
+            );
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill().await;
+            bail!(
+                "{} did not answer on {} within {:?}",
+                options.binary.display(),
+                socket_path,
+                options.ready_timeout
+            );
+        }
+        tokio::time::sleep(options.poll_interval).await;
+    }
+}
@@ -1,4 +1,6 @@
 use anyhow::Result;
+use mootimer_core::models::TaskStatus;
+use mootimer_core::search::TagMode;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
@@ -7,12 +9,29 @@ use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::UnixStream;
 use tokio::sync::{RwLock, mpsc};
 
+#[cfg(feature = "compression")]
+pub mod compression;
+pub mod daemon;
+#[cfg(feature = "mock")]
+pub mod mock;
+
+/// How long `call` waits for a response unless overridden.
+pub const DEFAULT_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Request {
     jsonrpc: String,
     pub method: String,
     pub params: Option<Value>,
     pub id: RequestId,
+    /// `"zstd"` on the first request of a connection whose client can read
+    /// compressed responses.
+    #[serde(
+        rename = "x-accept-encoding",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub accept_encoding: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +74,7 @@ impl Request {
             method: method.into(),
             params,
             id,
+            accept_encoding: None,
         }
     }
 }
@@ -64,23 +84,65 @@ struct PersistentConnection {
     pending_responses: Arc<RwLock<HashMap<i64, mpsc::Sender<Response>>>>,
 }
 
+/// Clones share the connection, so a clone can wait on a slow call in a
+/// spawned task while the original keeps making others.
+#[derive(Clone)]
 pub struct MooTimerClient {
     socket_path: String,
-    request_counter: std::sync::atomic::AtomicI64,
+    request_counter: Arc<std::sync::atomic::AtomicI64>,
+    request_timeout: std::time::Duration,
     conn: Arc<RwLock<Option<PersistentConnection>>>,
     notif_tx: Arc<RwLock<Option<mpsc::Sender<Notification>>>>,
+    /// The daemon's answer to the last `events.subscribe`/`unsubscribe`,
+    /// replayed as `events.subscribe` params whenever the connection is
+    /// re-established.
+    subscription: Arc<RwLock<Option<Value>>>,
+    /// `system.hello`'s method list, fetched by `supports` once per
+    /// connection since a reconnect may reach a different daemon.
+    methods: Arc<RwLock<Option<Vec<String>>>>,
+    /// Sent in `system.hello` on every new connection, so the daemon can
+    /// tell front ends apart in its usage counts.
+    client_name: Option<String>,
+    #[cfg(feature = "compression")]
+    compression: bool,
 }
 
 impl MooTimerClient {
     pub fn new(socket_path: impl Into<String>) -> Self {
         Self {
             socket_path: socket_path.into(),
-            request_counter: std::sync::atomic::AtomicI64::new(1),
+            request_counter: Arc::new(std::sync::atomic::AtomicI64::new(1)),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
             conn: Arc::new(RwLock::new(None)),
             notif_tx: Arc::new(RwLock::new(None)),
+            subscription: Arc::new(RwLock::new(None)),
+            methods: Arc::new(RwLock::new(None)),
+            client_name: None,
+            #[cfg(feature = "compression")]
+            compression: false,
         }
     }
 
+    pub fn with_request_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Name this program for the daemon, e.g. `mootimer-tui`.
+    pub fn with_client_name(mut self, name: impl Into<String>) -> Self {
+        self.client_name = Some(name.into());
+        self
+    }
+
+    /// Ask the daemon to zstd-compress large responses, in the
+    /// `system.hello` sent on every new connection. Takes effect from the
+    /// next connection; see [`compression`] for the wire format.
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
     async fn ensure_connected(&self) -> Result<PersistentConnection> {
         {
             let conn_lock = self.conn.read().await;
@@ -101,6 +163,7 @@ impl MooTimerClient {
         }
 
         let stream = UnixStream::connect(&self.socket_path).await?;
+        *self.methods.write().await = None;
         let (read_half, mut write_half) = tokio::io::split(stream);
         let mut reader = BufReader::new(read_half);
 
@@ -136,7 +199,7 @@ impl MooTimerClient {
             let mut line = String::new();
             loop {
                 line.clear();
-                match reader.read_line(&mut line).await {
+                match read_message(&mut reader, &mut line).await {
                     Ok(0) | Err(_) => break,
                     Ok(_) => {
                         if let Ok(response) = serde_json::from_str::<Response>(&line) {
@@ -160,6 +223,21 @@ impl MooTimerClient {
             *c = None;
         });
 
+        // The daemon learns the client name and whether to compress per
+        // connection, so say hello first. Like the resubscribe below, the
+        // reply is not waited for.
+        if let Some(params) = self.hello_params() {
+            let hello = Request::new("system.hello", Some(params), self.next_id());
+            let _ = req_tx.send(hello).await;
+        }
+
+        // Subscriptions belong to the connection, so a new one starts with
+        // none. The reply is not waited for; a failure only costs events.
+        if let Some(subscription) = self.subscription.read().await.clone() {
+            let resubscribe = Request::new("events.subscribe", Some(subscription), self.next_id());
+            let _ = req_tx.send(resubscribe).await;
+        }
+
         let conn = PersistentConnection {
             writer: req_tx,
             pending_responses,
@@ -172,6 +250,8 @@ impl MooTimerClient {
         Ok(conn)
     }
 
+    /// Channel for daemon notifications. Only categories subscribed to with
+    /// [`events_subscribe`](Self::events_subscribe) are delivered.
     pub async fn subscribe_notifications(&self) -> Result<mpsc::Receiver<Notification>> {
         let (tx, rx) = mpsc::channel(100);
         let mut nt = self.notif_tx.write().await;
@@ -202,15 +282,14 @@ impl MooTimerClient {
             anyhow::bail!("Failed to send request");
         }
 
-        let response =
-            match tokio::time::timeout(tokio::time::Duration::from_secs(5), rx.recv()).await {
-                Ok(Some(r)) => r,
-                _ => {
-                    let mut pending = conn.pending_responses.write().await;
-                    pending.remove(&req_id);
-                    anyhow::bail!("Request timed out or connection closed");
-                }
-            };
+        let response = match tokio::time::timeout(self.request_timeout, rx.recv()).await {
+            Ok(Some(r)) => r,
+            _ => {
+                let mut pending = conn.pending_responses.write().await;
+                pending.remove(&req_id);
+                anyhow::bail!("Request timed out or connection closed");
+            }
+        };
 
         {
             let mut pending = conn.pending_responses.write().await;
@@ -231,6 +310,149 @@ impl MooTimerClient {
         RequestId::Number(id)
     }
 
+    /// Daemon name, version and the methods it will currently accept.
+    pub async fn daemon_info(&self) -> Result<Value> {
+        self.call("daemon.info", None).await
+    }
+
+    pub async fn config_set_device_name(&self, name: &str) -> Result<Value> {
+        self.call(
+            "config.set_device_name",
+            Some(serde_json::json!({ "name": name })),
+        )
+        .await
+    }
+
+    /// The whole config, to back up or carry to another machine.
+    pub async fn config_export(&self) -> Result<Value> {
+        self.call("config.export", None).await
+    }
+
+    /// Replaces the config with one from `config_export`. Unknown keys
+    /// are refused; out-of-range numbers are clamped and listed.
+    pub async fn config_import(&self, config: Value) -> Result<Value> {
+        self.call(
+            "config.import",
+            Some(serde_json::json!({ "config": config })),
+        )
+        .await
+    }
+
+    pub async fn system_hello(&self) -> Result<Value> {
+        self.call("system.hello", self.hello_params()).await
+    }
+
+    fn hello_params(&self) -> Option<Value> {
+        let mut params = serde_json::Map::new();
+        if let Some(name) = &self.client_name {
+            params.insert("client".to_string(), name.clone().into());
+        }
+        #[cfg(feature = "compression")]
+        if self.compression {
+            params.insert(
+                compression::HELLO_ACCEPT_ENCODING.to_string(),
+                serde_json::json!([compression::ZSTD]),
+            );
+        }
+        (!params.is_empty()).then_some(Value::Object(params))
+    }
+
+    /// Params schemas and result descriptions for every method.
+    pub async fn system_describe(&self) -> Result<Value> {
+        self.call("system.describe", None).await
+    }
+
+    /// Notification text by key in `locale`, or in the daemon's locale when
+    /// `None`.
+    pub async fn system_messages(&self, locale: Option<&str>) -> Result<Value> {
+        self.call(
+            "system.messages",
+            Some(serde_json::json!({ "locale": locale })),
+        )
+        .await
+    }
+
+    /// Whether the daemon accepts `method`: it knows it and config doesn't
+    /// disable it. Lets front ends hide features an older daemon lacks.
+    pub async fn supports(&self, method: &str) -> Result<bool> {
+        if let Some(methods) = self.methods.read().await.as_ref() {
+            return Ok(methods.iter().any(|m| m == method));
+        }
+
+        let hello = self.system_hello().await?;
+        let methods: Vec<String> = hello["methods"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m.as_str().map(str::to_string))
+            .collect();
+        let supported = methods.iter().any(|m| m == method);
+        *self.methods.write().await = Some(methods);
+        Ok(supported)
+    }
+
+    /// Succeeds if the daemon is reachable and answering requests.
+    pub async fn health_check(&self) -> Result<()> {
+        self.system_hello().await.map(|_| ())
+    }
+
+    /// Whether the persistent connection is currently open. It is dropped
+    /// as soon as the daemon closes the socket, e.g. on restart.
+    pub async fn is_connected(&self) -> bool {
+        self.conn.read().await.is_some()
+    }
+
+    /// Starts receiving notifications for these event categories (`timer`,
+    /// `task`, `entry`, `profile`, `config`, or `*` for all). Returns the
+    /// categories now subscribed to.
+    pub async fn events_subscribe(&self, categories: &[&str]) -> Result<Value> {
+        let result = self
+            .call(
+                "events.subscribe",
+                Some(serde_json::json!({ "categories": categories })),
+            )
+            .await?;
+        self.remember_subscription(&result).await;
+        Ok(result)
+    }
+
+    /// Like [`events_subscribe`](Self::events_subscribe), but timer ticks
+    /// arrive at most once every `tick_interval_seconds` per timer. Other
+    /// timer events are still delivered as they happen.
+    pub async fn events_subscribe_throttled(
+        &self,
+        categories: &[&str],
+        tick_interval_seconds: u64,
+    ) -> Result<Value> {
+        let result = self
+            .call(
+                "events.subscribe",
+                Some(serde_json::json!({
+                    "categories": categories,
+                    "tick_interval_seconds": tick_interval_seconds,
+                })),
+            )
+            .await?;
+        self.remember_subscription(&result).await;
+        Ok(result)
+    }
+
+    /// Stops notifications for these categories, or for all of them when
+    /// `categories` is `None`.
+    pub async fn events_unsubscribe(&self, categories: Option<&[&str]>) -> Result<Value> {
+        let params = categories.map(|c| serde_json::json!({ "categories": c }));
+        let result = self.call("events.unsubscribe", params).await?;
+        self.remember_subscription(&result).await;
+        Ok(result)
+    }
+
+    async fn remember_subscription(&self, result: &Value) {
+        let subscribed = result["categories"]
+            .as_array()
+            .is_some_and(|c| !c.is_empty());
+        *self.subscription.write().await = subscribed.then(|| result.clone());
+    }
+
     pub async fn timer_start_manual(
         &self,
         profile_id: &str,
@@ -266,6 +488,25 @@ impl MooTimerClient {
         self.call("timer.start_pomodoro", Some(params)).await
     }
 
+    /// Start a pomodoro with an explicit override object, e.g.
+    /// `{"work_duration": 1500, "auto_start_next_session": true}`.
+    pub async fn timer_start_pomodoro_with_config(
+        &self,
+        profile_id: &str,
+        task_id: Option<&str>,
+        config: Value,
+    ) -> Result<Value> {
+        self.call(
+            "timer.start_pomodoro",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "task_id": task_id,
+                "config": config,
+            })),
+        )
+        .await
+    }
+
     pub async fn timer_start_countdown(
         &self,
         profile_id: &str,
@@ -303,6 +544,17 @@ impl MooTimerClient {
         .await
     }
 
+    pub async fn timer_set_auto_start(&self, timer_id: &str, enabled: bool) -> Result<Value> {
+        self.call(
+            "timer.set_auto_start",
+            Some(serde_json::json!({
+                "timer_id": timer_id,
+                "enabled": enabled,
+            })),
+        )
+        .await
+    }
+
     pub async fn timer_stop(&self, timer_id: &str) -> Result<Value> {
         self.call(
             "timer.stop",
@@ -313,6 +565,18 @@ impl MooTimerClient {
         .await
     }
 
+    /// Ends the current pomodoro break early; the daemon records it as
+    /// skipped.
+    pub async fn timer_skip_phase(&self, timer_id: &str) -> Result<Value> {
+        self.call(
+            "timer.skip_phase",
+            Some(serde_json::json!({
+                "timer_id": timer_id,
+            })),
+        )
+        .await
+    }
+
     pub async fn timer_cancel(&self, timer_id: &str) -> Result<Value> {
         self.call(
             "timer.cancel",
@@ -345,6 +609,18 @@ impl MooTimerClient {
         .await
     }
 
+    /// The profile's current timer in a few fields, cheap enough to poll
+    /// every second from a status bar.
+    pub async fn timer_peek(&self, profile_id: &str) -> Result<Value> {
+        self.call(
+            "timer.peek",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+            })),
+        )
+        .await
+    }
+
     /// List all timers for a specific profile
     pub async fn timer_list_by_profile(&self, profile_id: &str) -> Result<Value> {
         self.call(
@@ -361,6 +637,23 @@ impl MooTimerClient {
         self.call("timer.list", None).await
     }
 
+    /// One-line summary of the active timer (`{ text, timer_id }`); `text`
+    /// is empty when nothing is running.
+    pub async fn status_summary(
+        &self,
+        profile_id: Option<&str>,
+        format: Option<&str>,
+    ) -> Result<Value> {
+        self.call(
+            "status.summary",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "format": format,
+            })),
+        )
+        .await
+    }
+
     pub async fn profile_create(
         &self,
         id: &str,
@@ -429,6 +722,76 @@ impl MooTimerClient {
         .await
     }
 
+    pub async fn task_add_subtask(
+        &self,
+        profile_id: &str,
+        parent_task_id: &str,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<Value> {
+        self.call(
+            "task.add_subtask",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "parent_task_id": parent_task_id,
+                "title": title,
+                "description": description,
+            })),
+        )
+        .await
+    }
+
+    /// Copies a task within its profile; `new_title` defaults to
+    /// "Copy of <title>".
+    pub async fn task_copy(
+        &self,
+        profile_id: &str,
+        task_id: &str,
+        new_title: Option<&str>,
+    ) -> Result<Value> {
+        self.call(
+            "task.copy",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "task_id": task_id,
+                "new_title": new_title,
+            })),
+        )
+        .await
+    }
+
+    /// Moves a task within its Kanban column, just above `before` or just
+    /// below `after`.
+    pub async fn task_reorder(
+        &self,
+        profile_id: &str,
+        task_id: &str,
+        before: Option<&str>,
+        after: Option<&str>,
+    ) -> Result<Value> {
+        self.call(
+            "task.reorder",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "task_id": task_id,
+                "before": before,
+                "after": after,
+            })),
+        )
+        .await
+    }
+
+    /// The task linked to the profile's running timer, or `null`.
+    pub async fn task_get_active(&self, profile_id: &str) -> Result<Value> {
+        self.call(
+            "task.get_active",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+            })),
+        )
+        .await
+    }
+
     pub async fn task_list(&self, profile_id: &str) -> Result<Value> {
         self.call(
             "task.list",
@@ -439,6 +802,65 @@ impl MooTimerClient {
         .await
     }
 
+    /// Tasks fuzzily matching `query` and tagged with all or any of `tags`,
+    /// best first, each with a `match` object holding its score and the
+    /// matched character spans.
+    pub async fn task_search(
+        &self,
+        profile_id: &str,
+        query: &str,
+        tags: &[String],
+        tag_mode: TagMode,
+        case_sensitive: bool,
+        exclude_archived: bool,
+    ) -> Result<Value> {
+        self.call(
+            "task.search",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "query": query,
+                "tags": tags,
+                "tag_mode": tag_mode,
+                "case_sensitive": case_sensitive,
+                "exclude_archived": exclude_archived,
+            })),
+        )
+        .await
+    }
+
+    /// Open tasks not worked on in the last `days` days (daemon default 30).
+    pub async fn task_stale(&self, profile_id: &str, days: Option<u32>) -> Result<Value> {
+        self.call(
+            "task.stale",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "days": days,
+            })),
+        )
+        .await
+    }
+
+    /// Open tasks sorted by due date, overdue first. `days_ahead` limits
+    /// how far ahead to look; `None` means no limit.
+    pub async fn task_list_by_due_date(
+        &self,
+        profile_id: &str,
+        include_overdue: bool,
+        include_no_due_date: bool,
+        days_ahead: Option<u32>,
+    ) -> Result<Value> {
+        self.call(
+            "task.list_by_due_date",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "include_overdue": include_overdue,
+                "include_no_due_date": include_no_due_date,
+                "days_ahead": days_ahead,
+            })),
+        )
+        .await
+    }
+
     pub async fn task_get(&self, profile_id: &str, task_id: &str) -> Result<Value> {
         self.call(
             "task.get",
@@ -450,6 +872,18 @@ impl MooTimerClient {
         .await
     }
 
+    /// The tasks blocking `task_id`, directly or through other blockers.
+    pub async fn task_blockers(&self, profile_id: &str, task_id: &str) -> Result<Value> {
+        self.call(
+            "task.blockers",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "task_id": task_id,
+            })),
+        )
+        .await
+    }
+
     pub async fn task_update(&self, profile_id: &str, task: Value) -> Result<Value> {
         self.call(
             "task.update",
@@ -461,6 +895,58 @@ impl MooTimerClient {
         .await
     }
 
+    /// Asks for one `task.status_reached` notification when the task moves
+    /// into `target_status`. The watch lasts until it fires or the
+    /// connection closes; it is not restored on reconnect.
+    pub async fn task_watch_status(
+        &self,
+        task_id: &str,
+        target_status: TaskStatus,
+    ) -> Result<Value> {
+        self.call(
+            "task.watch_status",
+            Some(serde_json::json!({
+                "task_id": task_id,
+                "target_status": target_status,
+            })),
+        )
+        .await
+    }
+
+    pub async fn task_set_description(
+        &self,
+        profile_id: &str,
+        task_id: &str,
+        description: &str,
+    ) -> Result<Value> {
+        self.call(
+            "task.set_description",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "task_id": task_id,
+                "description": description,
+            })),
+        )
+        .await
+    }
+
+    pub async fn task_set_notes(
+        &self,
+        profile_id: &str,
+        task_id: &str,
+        notes: &str,
+    ) -> Result<Value> {
+        self.call(
+            "task.set_notes",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "task_id": task_id,
+                "notes": notes,
+            })),
+        )
+        .await
+    }
+
     pub async fn task_delete(&self, profile_id: &str, task_id: &str) -> Result<Value> {
         self.call(
             "task.delete",
@@ -491,6 +977,109 @@ impl MooTimerClient {
         .await
     }
 
+    /// Move every task in `from_status` to `to_status`, optionally narrowed by
+    /// a filter (`tags`, `created_after`, `created_before`)
+    pub async fn task_bulk_status_update(
+        &self,
+        profile_id: &str,
+        from_status: TaskStatus,
+        to_status: TaskStatus,
+        filter: Option<Value>,
+    ) -> Result<Value> {
+        self.call(
+            "task.bulk_status_update",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "from_status": from_status,
+                "to_status": to_status,
+                "filter": filter,
+            })),
+        )
+        .await
+    }
+
+    /// Add `tags` to every task in `task_ids`
+    pub async fn task_bulk_tag(
+        &self,
+        profile_id: &str,
+        task_ids: &[String],
+        tags: &[String],
+    ) -> Result<Value> {
+        self.call(
+            "task.bulk_tag",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "task_ids": task_ids,
+                "tags": tags,
+            })),
+        )
+        .await
+    }
+
+    /// Remove `tags` from every task in `task_ids`
+    pub async fn task_bulk_untag(
+        &self,
+        profile_id: &str,
+        task_ids: &[String],
+        tags: &[String],
+    ) -> Result<Value> {
+        self.call(
+            "task.bulk_untag",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "task_ids": task_ids,
+                "tags": tags,
+            })),
+        )
+        .await
+    }
+
+    /// The profile's time budgets, each with its use in the current period.
+    pub async fn budget_list(&self, profile_id: &str) -> Result<Value> {
+        self.call(
+            "budget.list",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+            })),
+        )
+        .await
+    }
+
+    /// `scope` is `{"type": "task", "task_id": ..}`, `{"type": "tag",
+    /// "tag": ..}` or `{"type": "profile"}`; `period` is `day`, `week` or
+    /// `month`; `action` is `warn` or `block_new_timers`.
+    pub async fn budget_create(
+        &self,
+        profile_id: &str,
+        scope: Value,
+        period: &str,
+        limit_seconds: u64,
+        action: &str,
+    ) -> Result<Value> {
+        self.call(
+            "budget.create",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "scope": scope,
+                "period": period,
+                "limit_seconds": limit_seconds,
+                "action": action,
+            })),
+        )
+        .await
+    }
+
+    pub async fn budget_delete(&self, profile_id: &str, budget_id: &str) -> Result<Value> {
+        self.call(
+            "budget.delete",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "budget_id": budget_id,
+            })),
+        )
+        .await
+    }
+
     pub async fn entry_list(&self, profile_id: &str) -> Result<Value> {
         self.call(
             "entry.list",
@@ -501,6 +1090,55 @@ impl MooTimerClient {
         .await
     }
 
+    pub async fn entry_list_invalid(&self, profile_id: &str) -> Result<Value> {
+        self.call(
+            "entry.list_invalid",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+            })),
+        )
+        .await
+    }
+
+    /// The most recently completed entry, or null.
+    pub async fn entry_get_by_id(&self, profile_id: &str, entry_id: &str) -> Result<Value> {
+        self.call(
+            "entry.get_by_id",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "entry_id": entry_id,
+            })),
+        )
+        .await
+    }
+
+    pub async fn entry_last(&self, profile_id: &str) -> Result<Value> {
+        self.call(
+            "entry.last",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+            })),
+        )
+        .await
+    }
+
+    pub async fn entry_find_duplicates(
+        &self,
+        profile_id: &str,
+        start_date: Option<String>,
+        end_date: Option<String>,
+    ) -> Result<Value> {
+        self.call(
+            "entry.find_duplicates",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "start_date": start_date,
+                "end_date": end_date,
+            })),
+        )
+        .await
+    }
+
     pub async fn entry_filter(
         &self,
         profile_id: &str,
@@ -522,6 +1160,19 @@ impl MooTimerClient {
         .await
     }
 
+    /// Entries matching a query string such as
+    /// `task:api tag:billable after:monday`.
+    pub async fn entry_query(&self, profile_id: &str, query: &str) -> Result<Value> {
+        self.call(
+            "entry.filter",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "query": query,
+            })),
+        )
+        .await
+    }
+
     pub async fn entry_today(&self, profile_id: &str) -> Result<Value> {
         self.call(
             "entry.today",
@@ -542,6 +1193,89 @@ impl MooTimerClient {
         .await
     }
 
+    /// Stats for two periods side by side. Each period is a `(start, end)`
+    /// pair of `YYYY-MM-DD` dates, both included; the delta is `period_b`
+    /// minus `period_a`.
+    pub async fn entry_stats_compare(
+        &self,
+        profile_id: &str,
+        period_a: (&str, &str),
+        period_b: (&str, &str),
+    ) -> Result<Value> {
+        self.call(
+            "entry.stats_compare",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "period_a": { "start": period_a.0, "end": period_a.1 },
+                "period_b": { "start": period_b.0, "end": period_b.1 },
+            })),
+        )
+        .await
+    }
+
+    /// Average session length per hour of the day, for hours 0 to 23.
+    /// Dates are `YYYY-MM-DD`, both included; `None` leaves that end open.
+    pub async fn entry_stats_hourly(
+        &self,
+        profile_id: &str,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+    ) -> Result<Value> {
+        self.call(
+            "entry.stats_hourly",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "start_date": start_date,
+                "end_date": end_date,
+            })),
+        )
+        .await
+    }
+
+    /// Stats for each window (`today`, `7d`, `YYYY-MM-DD..YYYY-MM-DD`, ...)
+    /// of each profile, or of every profile when `profile_ids` is `None`.
+    pub async fn stats_bulk(
+        &self,
+        profile_ids: Option<&[&str]>,
+        windows: &[&str],
+    ) -> Result<Value> {
+        self.call(
+            "stats.bulk",
+            Some(serde_json::json!({
+                "profile_ids": profile_ids,
+                "windows": windows,
+            })),
+        )
+        .await
+    }
+
+    pub async fn admin_stats(&self) -> Result<Value> {
+        self.call("admin.stats", None).await
+    }
+
+    /// Call counts for `month` (`YYYY-MM`, the current month when `None`),
+    /// recorded while `daemon.usage_metrics` is on.
+    pub async fn usage_summary(&self, month: Option<&str>) -> Result<Value> {
+        self.call("usage.summary", Some(serde_json::json!({ "month": month })))
+            .await
+    }
+
+    /// The daemon's record of recent mutating calls, newest first.
+    pub async fn admin_journal(&self) -> Result<Value> {
+        self.call("admin.journal", None).await
+    }
+
+    /// Undoes the journaled call `op_id`, or the newest one not yet undone.
+    pub async fn admin_undo(&self, op_id: Option<u64>) -> Result<Value> {
+        self.call(
+            "admin.undo",
+            Some(serde_json::json!({
+                "op_id": op_id,
+            })),
+        )
+        .await
+    }
+
     pub async fn entry_delete(&self, profile_id: &str, entry_id: &str) -> Result<Value> {
         self.call(
             "entry.delete",
@@ -564,6 +1298,29 @@ impl MooTimerClient {
         .await
     }
 
+    /// Copies an entry into another profile, or moves it when `move_entry`
+    /// is set. `task_id` picks the target profile's task to log it against.
+    pub async fn entry_copy_to_profile(
+        &self,
+        source_profile_id: &str,
+        entry_id: &str,
+        target_profile_id: &str,
+        move_entry: bool,
+        task_id: Option<&str>,
+    ) -> Result<Value> {
+        self.call(
+            "entry.copy_to_profile",
+            Some(serde_json::json!({
+                "source_profile_id": source_profile_id,
+                "entry_id": entry_id,
+                "target_profile_id": target_profile_id,
+                "move": move_entry,
+                "task_id": task_id,
+            })),
+        )
+        .await
+    }
+
     pub async fn entry_create(
         &self,
         profile_id: &str,
@@ -585,6 +1342,47 @@ impl MooTimerClient {
         .await
     }
 
+    /// Logs `minutes` of work that just ended, for when the timer was
+    /// forgotten.
+    pub async fn entry_log(
+        &self,
+        profile_id: &str,
+        task_id: Option<&str>,
+        minutes: u64,
+        description: Option<&str>,
+    ) -> Result<Value> {
+        self.call(
+            "entry.create",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "start_offset_minutes_ago": minutes,
+                "task_id": task_id,
+                "description": description,
+            })),
+        )
+        .await
+    }
+
+    /// Creates many entries in one call. Each of `entries` is an object with
+    /// `start_time`, `end_time` and optional `task_id`, `description` and
+    /// `tags`. With `partial`, invalid entries are skipped and reported.
+    pub async fn entry_create_batch(
+        &self,
+        profile_id: &str,
+        entries: &[Value],
+        partial: bool,
+    ) -> Result<Value> {
+        self.call(
+            "entry.create_batch",
+            Some(serde_json::json!({
+                "profile_id": profile_id,
+                "entries": entries,
+                "partial": partial,
+            })),
+        )
+        .await
+    }
+
     pub async fn sync_status(&self) -> Result<Value> {
         self.call("sync.status", None).await
     }
@@ -592,6 +1390,91 @@ impl MooTimerClient {
     pub async fn sync_sync(&self) -> Result<Value> {
         self.call("sync.sync", None).await
     }
+
+    pub async fn sync_diff(&self) -> Result<Value> {
+        self.call("sync.diff", None).await
+    }
+
+    pub async fn sync_diff_full(&self) -> Result<Value> {
+        self.call("sync.diff_full", None).await
+    }
+
+    pub async fn sync_history(&self, limit: usize) -> Result<Value> {
+        self.call("sync.history", Some(serde_json::json!({ "limit": limit })))
+            .await
+    }
+
+    pub async fn sync_conflicts(&self) -> Result<Value> {
+        self.call("sync.conflicts", None).await
+    }
+
+    /// `resolution` is `local`, `remote` or `merged`; `merged` is the record
+    /// to keep for the last.
+    pub async fn sync_resolve_conflict(
+        &self,
+        conflict_id: &str,
+        resolution: &str,
+        merged: Option<Value>,
+    ) -> Result<Value> {
+        self.call(
+            "sync.resolve_conflict",
+            Some(serde_json::json!({
+                "conflict_id": conflict_id,
+                "resolution": resolution,
+                "merged": merged,
+            })),
+        )
+        .await
+    }
+
+    pub async fn sync_list_branches(&self) -> Result<Value> {
+        self.call("sync.list_branches", None).await
+    }
+
+    pub async fn sync_switch_branch(&self, branch: &str) -> Result<Value> {
+        self.call(
+            "sync.switch_branch",
+            Some(serde_json::json!({
+                "branch": branch,
+            })),
+        )
+        .await
+    }
+
+    /// Puts uncommitted changes aside, e.g. to pull by hand.
+    pub async fn sync_stash(&self) -> Result<Value> {
+        self.call("sync.stash", None).await
+    }
+
+    /// Brings back the last `sync_stash`.
+    pub async fn sync_stash_pop(&self) -> Result<Value> {
+        self.call("sync.stash_pop", None).await
+    }
+}
+
+/// Reads the next message into `line`: a JSON line, or with the
+/// `compression` feature, a compressed envelope or frame decoded back to
+/// JSON. Returns 0 at end of stream, like `read_line`.
+async fn read_message<R>(reader: &mut R, line: &mut String) -> std::io::Result<usize>
+where
+    R: tokio::io::AsyncBufRead + Unpin,
+{
+    #[cfg(feature = "compression")]
+    if reader.fill_buf().await?.first() == Some(&compression::FRAME_MARKER) {
+        reader.consume(1);
+        let json = compression::read_frame(reader).await?;
+        *line = String::from_utf8(json)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        return Ok(line.len());
+    }
+
+    let read = reader.read_line(line).await?;
+    #[cfg(feature = "compression")]
+    if let Some(json) = compression::decode_envelope(line) {
+        *line = String::from_utf8(json?)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    }
+    Ok(read)
 }
 
 #[cfg(test)]
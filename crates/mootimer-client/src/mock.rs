@@ -0,0 +1,269 @@
+//! A scriptable stand-in for the daemon (feature `mock`).
+//!
+//! [`MockServer`] listens on a Unix socket in a temp dir and speaks the same
+//! line-delimited JSON-RPC as `mootimerd`. Register a reply per method, point
+//! a [`MooTimerClient`] at it, push notifications whenever the test wants,
+//! and check which requests arrived:
+//!
+//! ```no_run
+//! # async fn demo() -> anyhow::Result<()> {
+//! use mootimer_client::mock::MockServer;
+//! use serde_json::json;
+//!
+//! let server = MockServer::start().await?;
+//! server.on("profile.list", json!([{ "id": "work" }])).await;
+//!
+//! let client = server.client();
+//! assert_eq!(client.profile_list().await?[0]["id"], "work");
+//! server.assert_received(&["profile.list"]).await;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Methods without a registered reply get a "method not found" error.
+
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{Mutex, mpsc};
+use tokio::task::JoinHandle;
+
+use crate::{MooTimerClient, Notification, Request, Response, ResponseError};
+
+const METHOD_NOT_FOUND: i32 = -32601;
+
+/// What the mock sends back for a request.
+#[derive(Debug, Clone)]
+pub enum MockReply {
+    Result(Value),
+    Error {
+        code: i32,
+        message: String,
+    },
+    /// Never answer, so the client's request times out.
+    Silence,
+}
+
+type Handler = Box<dyn Fn(Option<Value>) -> MockReply + Send + Sync>;
+
+struct Connection {
+    outgoing: mpsc::UnboundedSender<String>,
+    task: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct State {
+    handlers: HashMap<String, Handler>,
+    received: Vec<Request>,
+    connections: Vec<Connection>,
+    accepted: usize,
+}
+
+pub struct MockServer {
+    socket_path: PathBuf,
+    state: Arc<Mutex<State>>,
+    listener: JoinHandle<()>,
+    _dir: Option<TempDir>,
+}
+
+impl MockServer {
+    pub async fn start() -> Result<Self> {
+        let dir = TempDir::new()?;
+        let mut server = Self::start_at(&dir.path().join("mock.sock")).await?;
+        server._dir = Some(dir);
+        Ok(server)
+    }
+
+    /// Like `start`, listening on `socket_path` instead of a temporary
+    /// directory, e.g. to stand in for `mootimerd` at its usual path.
+    pub async fn start_at(socket_path: &Path) -> Result<Self> {
+        let socket_path = socket_path.to_path_buf();
+        let listener = UnixListener::bind(&socket_path)?;
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let accept_state = state.clone();
+        let listener = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (outgoing, rx) = mpsc::unbounded_channel();
+                let task = tokio::spawn(serve(stream, accept_state.clone(), rx));
+                let mut state = accept_state.lock().await;
+                state.accepted += 1;
+                state.connections.push(Connection { outgoing, task });
+            }
+        });
+
+        Ok(Self {
+            socket_path,
+            state,
+            listener,
+            _dir: None,
+        })
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// A new client for this server. It connects on its first call.
+    pub fn client(&self) -> MooTimerClient {
+        MooTimerClient::new(self.socket_path.to_string_lossy())
+    }
+
+    /// Answers every `method` request with `result`.
+    pub async fn on(&self, method: &str, result: Value) {
+        self.on_call(method, move |_| MockReply::Result(result.clone()))
+            .await;
+    }
+
+    /// Answers every `method` request with an RPC error.
+    pub async fn on_error(&self, method: &str, code: i32, message: &str) {
+        let message = message.to_string();
+        self.on_call(method, move |_| MockReply::Error {
+            code,
+            message: message.clone(),
+        })
+        .await;
+    }
+
+    /// Leaves `method` requests unanswered.
+    pub async fn silence(&self, method: &str) {
+        self.on_call(method, |_| MockReply::Silence).await;
+    }
+
+    /// Answers `method` requests by calling `handler` with their params.
+    /// Replaces any earlier reply for the same method.
+    pub async fn on_call(
+        &self,
+        method: &str,
+        handler: impl Fn(Option<Value>) -> MockReply + Send + Sync + 'static,
+    ) {
+        self.state
+            .lock()
+            .await
+            .handlers
+            .insert(method.to_string(), Box::new(handler));
+    }
+
+    /// Sends a notification to every connected client.
+    pub async fn notify(&self, method: &str, params: Value) {
+        let notification = Notification {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+        };
+        let line = serde_json::to_string(&notification).expect("notification serializes");
+        let state = self.state.lock().await;
+        for connection in &state.connections {
+            let _ = connection.outgoing.send(line.clone());
+        }
+    }
+
+    /// Closes every open connection, as if the daemon restarted.
+    pub async fn disconnect_all(&self) {
+        let mut state = self.state.lock().await;
+        for connection in state.connections.drain(..) {
+            connection.task.abort();
+        }
+    }
+
+    /// Connections accepted so far, including closed ones.
+    pub async fn connection_count(&self) -> usize {
+        self.state.lock().await.accepted
+    }
+
+    /// Every request received so far, in arrival order.
+    pub async fn received(&self) -> Vec<Request> {
+        self.state.lock().await.received.clone()
+    }
+
+    /// Asserts that exactly these methods were called, in this order.
+    pub async fn assert_received(&self, methods: &[&str]) {
+        let received = self.received().await;
+        let actual: Vec<&str> = received.iter().map(|r| r.method.as_str()).collect();
+        assert_eq!(actual, methods, "unexpected requests to mock daemon");
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.listener.abort();
+        if let Ok(mut state) = self.state.try_lock() {
+            for connection in state.connections.drain(..) {
+                connection.task.abort();
+            }
+        }
+    }
+}
+
+async fn serve(
+    stream: UnixStream,
+    state: Arc<Mutex<State>>,
+    mut outgoing: mpsc::UnboundedReceiver<String>,
+) {
+    let (read_half, mut write_half) = tokio::io::split(stream);
+    let mut lines = BufReader::new(read_half).lines();
+
+    loop {
+        let line = tokio::select! {
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => match respond(&state, &line).await {
+                    Some(response) => response,
+                    None => continue,
+                },
+                _ => break,
+            },
+            line = outgoing.recv() => match line {
+                Some(line) => line,
+                None => break,
+            },
+        };
+
+        if write_half.write_all(line.as_bytes()).await.is_err()
+            || write_half.write_all(b"\n").await.is_err()
+            || write_half.flush().await.is_err()
+        {
+            break;
+        }
+    }
+}
+
+/// Records the request and builds the response line, if one is due.
+async fn respond(state: &Mutex<State>, line: &str) -> Option<String> {
+    let request: Request = serde_json::from_str(line).ok()?;
+    let mut state = state.lock().await;
+    state.received.push(request.clone());
+
+    let reply = match state.handlers.get(&request.method) {
+        Some(handler) => handler(request.params.clone()),
+        None => MockReply::Error {
+            code: METHOD_NOT_FOUND,
+            message: format!("Method not found: {}", request.method),
+        },
+    };
+
+    let (result, error) = match reply {
+        MockReply::Result(value) => (Some(value), None),
+        MockReply::Error { code, message } => (
+            None,
+            Some(ResponseError {
+                code,
+                message,
+                data: None,
+            }),
+        ),
+        MockReply::Silence => return None,
+    };
+
+    let response = Response {
+        jsonrpc: "2.0".to_string(),
+        result,
+        error,
+        id: request.id,
+    };
+    serde_json::to_string(&response).ok()
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use mootimer_client::daemon::{SpawnMode, SpawnOptions, ensure_daemon};
+use mootimer_client::mock::MockServer;
+use serde_json::json;
+use std::path::PathBuf;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn fake_daemon(delay_ms: u64, ready_timeout: Duration) -> SpawnOptions {
+    SpawnOptions {
+        binary: PathBuf::from(env!("CARGO_BIN_EXE_fake-mootimerd")),
+        args: vec!["--delay-ms".to_string(), delay_ms.to_string()],
+        mode: SpawnMode::Child,
+        ready_timeout,
+        ..SpawnOptions::default()
+    }
+}
+
+#[tokio::test]
+async fn test_uses_running_daemon_without_spawning() -> Result<()> {
+    let server = MockServer::start().await?;
+    server.on("system.hello", json!({ "name": "mock" })).await;
+    let socket = server.socket_path().to_string_lossy().to_string();
+
+    let options = SpawnOptions {
+        binary: PathBuf::from("/nonexistent/mootimerd"),
+        ..SpawnOptions::default()
+    };
+    let daemon = ensure_daemon(&socket, options).await?;
+    assert!(!daemon.spawned());
+    assert_eq!(daemon.pid(), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_spawns_slow_daemon_and_shuts_it_down() -> Result<()> {
+    let dir = TempDir::new()?;
+    let socket = dir.path().join("d.sock").to_string_lossy().to_string();
+
+    let daemon = ensure_daemon(&socket, fake_daemon(300, Duration::from_secs(5))).await?;
+    assert!(daemon.spawned());
+    assert!(daemon.pid().is_some());
+    daemon.client().health_check().await?;
+
+    daemon.shutdown(Duration::from_secs(2)).await?;
+    assert!(
+        ensure_daemon(
+            &socket,
+            SpawnOptions {
+                spawn: false,
+                ..SpawnOptions::default()
+            }
+        )
+        .await
+        .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gives_up_at_ready_deadline() -> Result<()> {
+    let dir = TempDir::new()?;
+    let socket = dir.path().join("d.sock").to_string_lossy().to_string();
+
+    let err = ensure_daemon(&socket, fake_daemon(5_000, Duration::from_millis(200)))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("did not answer"), "{err}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_missing_binary_fails() -> Result<()> {
+    let dir = TempDir::new()?;
+    let socket = dir.path().join("d.sock").to_string_lossy().to_string();
+
+    let options = SpawnOptions {
+        binary: PathBuf::from("/nonexistent/mootimerd"),
+        ..SpawnOptions::default()
+    };
+    assert!(ensure_daemon(&socket, options).await.is_err());
+    Ok(())
+}
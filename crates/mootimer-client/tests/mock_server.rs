@@ -0,0 +1,186 @@
+use anyhow::Result;
+use mootimer_client::mock::{MockReply, MockServer};
+use serde_json::json;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_call_returns_result_and_sends_params() -> Result<()> {
+    let server = MockServer::start().await?;
+    server
+        .on_call("task.get", |params| {
+            let params = params.unwrap_or_default();
+            MockReply::Result(json!({ "id": params["task_id"], "title": "Echo" }))
+        })
+        .await;
+
+    let client = server.client();
+    let task = client.task_get("work", "t1").await?;
+    assert_eq!(task["id"], "t1");
+    assert_eq!(task["title"], "Echo");
+
+    let received = server.received().await;
+    assert_eq!(
+        received[0].params,
+        Some(json!({ "profile_id": "work", "task_id": "t1" }))
+    );
+    server.assert_received(&["task.get"]).await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rpc_errors_are_returned() -> Result<()> {
+    let server = MockServer::start().await?;
+    server
+        .on_error("profile.get", -32602, "Profile not found")
+        .await;
+
+    let client = server.client();
+    let error = client.profile_get("missing").await.unwrap_err();
+    assert!(error.to_string().contains("Profile not found"));
+
+    let error = client.call("no.such_method", None).await.unwrap_err();
+    assert!(error.to_string().contains("-32601"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_request_times_out_and_connection_stays_usable() -> Result<()> {
+    let server = MockServer::start().await?;
+    server.silence("timer.list").await;
+    server
+        .on("system.hello", json!({ "name": "mootimerd" }))
+        .await;
+
+    let client = server
+        .client()
+        .with_request_timeout(Duration::from_millis(100));
+    let error = client.timer_list().await.unwrap_err();
+    assert!(error.to_string().contains("timed out"));
+
+    // A late or missing reply must not poison later calls.
+    client.health_check().await?;
+    assert_eq!(server.connection_count().await, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reconnects_after_connection_drops() -> Result<()> {
+    let server = MockServer::start().await?;
+    server
+        .on("system.hello", json!({ "name": "mootimerd" }))
+        .await;
+
+    let client = server.client();
+    client.health_check().await?;
+    assert!(client.is_connected().await);
+
+    server.disconnect_all().await;
+    for _ in 0..50 {
+        if !client.is_connected().await {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(!client.is_connected().await);
+
+    client.health_check().await?;
+    assert_eq!(server.connection_count().await, 2);
+    server
+        .assert_received(&["system.hello", "system.hello"])
+        .await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_supports_reads_hello_once_per_connection() -> Result<()> {
+    let server = MockServer::start().await?;
+    server
+        .on(
+            "system.hello",
+            json!({ "methods": ["entry.last", "timer.list"] }),
+        )
+        .await;
+
+    let client = server.client();
+    assert!(client.supports("entry.last").await?);
+    assert!(!client.supports("entry.summarize").await?);
+    server.assert_received(&["system.hello"]).await;
+
+    server.disconnect_all().await;
+    for _ in 0..50 {
+        if !client.is_connected().await {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    client.timer_list().await.ok();
+    assert!(client.supports("timer.list").await?);
+    server
+        .assert_received(&["system.hello", "timer.list", "system.hello"])
+        .await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_notifications_are_dispatched_to_subscriber() -> Result<()> {
+    let server = MockServer::start().await?;
+    server.on("system.hello", json!({})).await;
+
+    let client = server.client();
+    let mut notifications = client.subscribe_notifications().await?;
+    // Interleave a call so responses and notifications share the stream.
+    client.health_check().await?;
+
+    server
+        .notify("timer.event", json!({ "event_type": { "type": "tick" } }))
+        .await;
+    server
+        .notify("task.event", json!({ "profile_id": "work" }))
+        .await;
+
+    let first = tokio::time::timeout(Duration::from_secs(1), notifications.recv())
+        .await?
+        .expect("notification");
+    assert_eq!(first.method, "timer.event");
+    assert_eq!(first.params["event_type"]["type"], "tick");
+
+    let second = tokio::time::timeout(Duration::from_secs(1), notifications.recv())
+        .await?
+        .expect("notification");
+    assert_eq!(second.method, "task.event");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscriptions_are_replayed_after_reconnect() -> Result<()> {
+    let server = MockServer::start().await?;
+    server
+        .on(
+            "events.subscribe",
+            json!({ "categories": ["timer"], "tick_interval_seconds": 30 }),
+        )
+        .await;
+    server.on("system.hello", json!({})).await;
+
+    let client = server.client();
+    client.events_subscribe_throttled(&["timer"], 30).await?;
+
+    server.disconnect_all().await;
+    for _ in 0..50 {
+        if !client.is_connected().await {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    client.health_check().await?;
+    server
+        .assert_received(&["events.subscribe", "events.subscribe", "system.hello"])
+        .await;
+    let received = server.received().await;
+    assert_eq!(
+        received[1].params,
+        Some(json!({ "categories": ["timer"], "tick_interval_seconds": 30 }))
+    );
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use mootimer_core::models::Task;
+use mootimer_core::search::rank_tasks;
+use std::hint::black_box;
+
+const WORDS: [&str; 12] = [
+    "fix", "login", "bug", "update", "docs", "review", "deploy", "api", "cache", "sync", "release",
+    "invoice",
+];
+
+fn make_tasks(count: usize) -> Vec<Task> {
+    (0..count)
+        .map(|i| {
+            let title = format!(
+                "{} {} {} #{}",
+                WORDS[i % 12],
+                WORDS[i / 12 % 12],
+                WORDS[i / 144 % 12],
+                i
+            );
+            let mut task = Task::new(title).unwrap();
+            task.tags = vec![WORDS[i % 7].to_string()];
+            if i % 3 == 0 {
+                task.description = Some(format!("Follow-up from the {} meeting", WORDS[i % 5]));
+            }
+            task
+        })
+        .collect()
+}
+
+/// A few milliseconds at most for 5k tasks keeps search-as-you-type
+/// responsive.
+fn bench_rank_tasks(c: &mut Criterion) {
+    let tasks = make_tasks(5_000);
+    let mut group = c.benchmark_group("rank_tasks_5k");
+    for query in ["lgn bug", "deploy", "zzz"] {
+        group.bench_function(query, |b| {
+            b.iter(|| black_box(rank_tasks(black_box(query), &tasks, false).len()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_rank_tasks);
+criterion_main!(benches);
@@ -0,0 +1,92 @@
+use chrono::{Duration, Utc};
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use mootimer_core::models::{Entry, Task, TimerMode};
+use mootimer_core::storage::{EntryStorage, TaskStorage};
+use std::hint::black_box;
+use tempfile::TempDir;
+
+const PROFILE: &str = "bench";
+
+fn make_entries(count: usize) -> Vec<Entry> {
+    let base = Utc::now() - Duration::days(365);
+    (0..count)
+        .map(|i| {
+            let start = base + Duration::minutes(i as i64 * 30);
+            let mut entry = Entry::create_completed(
+                Some(format!("task-{}", i % 50)),
+                Some(format!("Task {}", i % 50)),
+                start,
+                start + Duration::minutes(25),
+                TimerMode::Pomodoro,
+            )
+            .unwrap();
+            entry.tags = vec!["bench".to_string()];
+            entry
+        })
+        .collect()
+}
+
+fn bench_entry_load(c: &mut Criterion) {
+    let mut group = c.benchmark_group("entry_storage_load");
+    group.sample_size(10);
+
+    for count in [1_000, 10_000, 100_000] {
+        let dir = TempDir::new().unwrap();
+        let storage = EntryStorage::new(dir.path().to_path_buf());
+        storage.save_all(PROFILE, &make_entries(count)).unwrap();
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(count),
+            &storage,
+            |b, storage| b.iter(|| black_box(storage.load(PROFILE).unwrap())),
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_entry_append(c: &mut Criterion) {
+    let mut group = c.benchmark_group("entry_storage_append");
+    group.throughput(Throughput::Elements(1));
+
+    let dir = TempDir::new().unwrap();
+    let storage = EntryStorage::new(dir.path().to_path_buf());
+    storage.save_all(PROFILE, &make_entries(1_000)).unwrap();
+
+    group.bench_function("append", |b| {
+        b.iter_batched(
+            || make_entries(1).remove(0),
+            |entry| storage.append(PROFILE, &entry).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+fn bench_task_load(c: &mut Criterion) {
+    let dir = TempDir::new().unwrap();
+    let storage = TaskStorage::new(dir.path().to_path_buf());
+    let tasks: Vec<Task> = (0..500)
+        .map(|i| {
+            let mut task = Task::new(format!("Task {}", i)).unwrap();
+            task.description = Some("A task created for benchmarking".to_string());
+            task.tags = vec!["bench".to_string(), format!("group-{}", i % 10)];
+            task
+        })
+        .collect();
+    storage.save(PROFILE, &tasks).unwrap();
+
+    c.bench_function("task_storage_load_500", |b| {
+        b.iter(|| black_box(storage.load(PROFILE).unwrap()))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_entry_load,
+    bench_entry_append,
+    bench_task_load
+);
+criterion_main!(benches);
@@ -1,9 +1,59 @@
 use crate::{Error, Result};
 use git2::{
-    Cred, FetchOptions, IndexAddOption, Oid, PushOptions, RemoteCallbacks, Repository, Signature,
-    StatusOptions,
+    AnnotatedCommit, Branch, BranchType, Cred, Diff, DiffFormat, DiffOptions, FetchOptions, Index,
+    IndexAddOption, IndexConflict, Oid, Patch, PushOptions, RemoteCallbacks, Repository, Signature,
+    StashFlags, StatusOptions,
 };
-use std::path::PathBuf;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BranchInfo {
+    /// Short name: `work` for a local branch, `origin/work` for a remote one.
+    pub name: String,
+    pub remote: bool,
+    pub current: bool,
+}
+
+/// Lines added and removed in one file since the last commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// A commit as `log` returns it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommitInfo {
+    pub id: String,
+    pub author: String,
+    /// Seconds since the Unix epoch.
+    pub time: i64,
+    pub message: String,
+}
+
+/// How `pull` brought in the remote branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PullOutcome {
+    UpToDate,
+    FastForward,
+    /// Both sides had new commits; a merge commit was made.
+    Merged,
+}
+
+/// A file both sides changed in ways git could not merge line by line,
+/// with its three versions. `None` where the file does not exist.
+#[derive(Debug, Clone)]
+pub struct ConflictedFile {
+    pub path: String,
+    pub ancestor: Option<Vec<u8>>,
+    pub ours: Option<Vec<u8>>,
+    pub theirs: Option<Vec<u8>>,
+}
+
+/// Git keeps an index entry's merge stage in these flag bits.
+const INDEX_STAGE_MASK: u16 = 0x3000;
 
 pub struct GitOperations {
     repo_path: PathBuf,
@@ -108,6 +158,71 @@ impl GitOperations {
         Ok(!statuses.is_empty())
     }
 
+    /// Uncommitted changes against HEAD, staged or not, untracked files
+    /// included. Before the first commit everything counts as added.
+    fn uncommitted_diff(repo: &Repository) -> Result<Diff<'_>> {
+        let head_tree = match repo.head() {
+            Ok(head) => Some(
+                head.peel_to_tree()
+                    .map_err(|e| Error::InvalidData(format!("Failed to get HEAD tree: {}", e)))?,
+            ),
+            Err(_) => None,
+        };
+
+        let mut opts = DiffOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+
+        repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut opts))
+            .map_err(|e| Error::InvalidData(format!("Failed to diff: {}", e)))
+    }
+
+    pub fn diff_stats(&self) -> Result<Vec<FileChange>> {
+        let repo = self.get_repo()?;
+        let diff = Self::uncommitted_diff(&repo)?;
+
+        let mut changes = Vec::new();
+        for idx in 0..diff.deltas().len() {
+            let patch = Patch::from_diff(&diff, idx)
+                .map_err(|e| Error::InvalidData(format!("Failed to read diff: {}", e)))?;
+            let Some(patch) = patch else { continue };
+            let delta = patch.delta();
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let (_, insertions, deletions) = patch
+                .line_stats()
+                .map_err(|e| Error::InvalidData(format!("Failed to read diff: {}", e)))?;
+            changes.push(FileChange {
+                path,
+                insertions,
+                deletions,
+            });
+        }
+        Ok(changes)
+    }
+
+    /// The same changes as `diff_stats`, as `git diff` prints them.
+    pub fn diff_full(&self) -> Result<String> {
+        let repo = self.get_repo()?;
+        let diff = Self::uncommitted_diff(&repo)?;
+
+        let mut out = String::new();
+        diff.print(DiffFormat::Patch, |_, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                out.push(line.origin());
+            }
+            out.push_str(&String::from_utf8_lossy(line.content()));
+            true
+        })
+        .map_err(|e| Error::InvalidData(format!("Failed to print diff: {}", e)))?;
+        Ok(out)
+    }
+
     pub fn add_remote(&self, name: &str, url: &str) -> Result<()> {
         let repo = self.get_repo()?;
 
@@ -119,7 +234,15 @@ impl GitOperations {
         Ok(())
     }
 
-    pub fn pull(&self, remote_name: &str, branch: &str) -> Result<()> {
+    /// Fetches `branch` and brings it in. When both sides have new commits
+    /// they are merged, and `resolve` decides the contents of every file
+    /// git cannot merge by itself (`None` deletes it).
+    pub fn pull(
+        &self,
+        remote_name: &str,
+        branch: &str,
+        resolve: impl FnMut(&ConflictedFile) -> Result<Option<Vec<u8>>>,
+    ) -> Result<PullOutcome> {
         let repo = self.get_repo()?;
 
         let mut remote = repo
@@ -151,7 +274,7 @@ impl GitOperations {
             .map_err(|e| Error::InvalidData(format!("Failed to analyze merge: {}", e)))?;
 
         if analysis.0.is_up_to_date() {
-            return Ok(());
+            return Ok(PullOutcome::UpToDate);
         } else if analysis.0.is_fast_forward() {
             let refname = format!("refs/heads/{}", branch);
             let mut reference = repo
@@ -168,11 +291,192 @@ impl GitOperations {
             repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))
                 .map_err(|e| Error::InvalidData(format!("Failed to checkout: {}", e)))?;
         } else {
-            return Err(Error::InvalidData(
-                "Merge conflicts detected. Please resolve manually.".to_string(),
-            ));
+            if self.has_changes()? {
+                return Err(Error::InvalidData(
+                    "Commit local changes before merging the remote branch".to_string(),
+                ));
+            }
+            let message = format!("Merge {}/{}", remote_name, branch);
+            Self::merge(&repo, &fetch_commit, &message, resolve)?;
+            return Ok(PullOutcome::Merged);
         }
 
+        Ok(PullOutcome::FastForward)
+    }
+
+    fn merge(
+        repo: &Repository,
+        theirs: &AnnotatedCommit,
+        message: &str,
+        resolve: impl FnMut(&ConflictedFile) -> Result<Option<Vec<u8>>>,
+    ) -> Result<()> {
+        let ours = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .map_err(|e| Error::InvalidData(format!("Failed to get HEAD commit: {}", e)))?;
+        let theirs = repo
+            .find_commit(theirs.id())
+            .map_err(|e| Error::InvalidData(format!("Failed to get fetched commit: {}", e)))?;
+
+        let mut index = repo
+            .merge_commits(&ours, &theirs, None)
+            .map_err(|e| Error::InvalidData(format!("Failed to merge: {}", e)))?;
+        Self::resolve_conflicts(repo, &mut index, resolve)?;
+
+        let tree_id = index
+            .write_tree_to(repo)
+            .map_err(|e| Error::InvalidData(format!("Failed to write tree: {}", e)))?;
+        let tree = repo
+            .find_tree(tree_id)
+            .map_err(|e| Error::InvalidData(format!("Failed to find tree: {}", e)))?;
+        let signature = Signature::now("MooTimer", "mootimer@local")
+            .map_err(|e| Error::InvalidData(format!("Failed to create signature: {}", e)))?;
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &[&ours, &theirs],
+        )
+        .map_err(|e| Error::InvalidData(format!("Failed to create merge commit: {}", e)))?;
+
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))
+            .map_err(|e| Error::InvalidData(format!("Failed to checkout: {}", e)))?;
+        Ok(())
+    }
+
+    /// Replaces every conflicted entry in a merge index with what `resolve`
+    /// decides, dropping it for `None`.
+    fn resolve_conflicts(
+        repo: &Repository,
+        index: &mut Index,
+        mut resolve: impl FnMut(&ConflictedFile) -> Result<Option<Vec<u8>>>,
+    ) -> Result<()> {
+        let conflicts: Vec<IndexConflict> = index
+            .conflicts()
+            .and_then(|conflicts| conflicts.collect())
+            .map_err(|e| Error::InvalidData(format!("Failed to read conflicts: {}", e)))?;
+        for conflict in conflicts {
+            let content = |entry: &Option<git2::IndexEntry>| -> Result<Option<Vec<u8>>> {
+                entry
+                    .as_ref()
+                    .map(|entry| {
+                        repo.find_blob(entry.id)
+                            .map(|blob| blob.content().to_vec())
+                            .map_err(|e| Error::InvalidData(format!("Failed to read blob: {}", e)))
+                    })
+                    .transpose()
+            };
+            let ancestor = content(&conflict.ancestor)?;
+            let ours = content(&conflict.our)?;
+            let theirs = content(&conflict.their)?;
+            let Some(mut template) = conflict.our.or(conflict.their).or(conflict.ancestor) else {
+                continue;
+            };
+            let file = ConflictedFile {
+                path: String::from_utf8_lossy(&template.path).into_owned(),
+                ancestor,
+                ours,
+                theirs,
+            };
+
+            let resolved = resolve(&file)?;
+            index
+                .remove_path(Path::new(&file.path))
+                .map_err(|e| Error::InvalidData(format!("Failed to resolve conflict: {}", e)))?;
+            if let Some(resolved) = resolved {
+                // The merge index is not backed by the repository, so the
+                // blob is written first and the entry added by id.
+                template.id = repo
+                    .blob(&resolved)
+                    .map_err(|e| Error::InvalidData(format!("Failed to write blob: {}", e)))?;
+                template.file_size = resolved.len() as u32;
+                template.flags &= !INDEX_STAGE_MASK;
+                index.add(&template).map_err(|e| {
+                    Error::InvalidData(format!("Failed to resolve conflict: {}", e))
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Puts uncommitted changes, untracked files included, aside so the
+    /// work tree matches HEAD. `None` if there was nothing to stash.
+    pub fn stash(&self, message: &str) -> Result<Option<String>> {
+        if !self.has_changes()? {
+            return Ok(None);
+        }
+        let mut repo = self.get_repo()?;
+        let signature = Signature::now("MooTimer", "mootimer@local")
+            .map_err(|e| Error::InvalidData(format!("Failed to create signature: {}", e)))?;
+        let id = repo
+            .stash_save(&signature, message, Some(StashFlags::INCLUDE_UNTRACKED))
+            .map_err(|e| Error::InvalidData(format!("Failed to stash: {}", e)))?;
+        Ok(Some(id.to_string()))
+    }
+
+    /// Brings the newest stash back as uncommitted changes and drops it.
+    /// HEAD may have moved since: files both changed are merged against
+    /// the commit the stash was made on, with the stash as `ours` and HEAD
+    /// as `theirs`, and `resolve` decides those git cannot merge.
+    pub fn stash_pop(
+        &self,
+        resolve: impl FnMut(&ConflictedFile) -> Result<Option<Vec<u8>>>,
+    ) -> Result<()> {
+        let mut repo = self.get_repo()?;
+        let stash_id = repo
+            .refname_to_id("refs/stash")
+            .map_err(|_| Error::NotFound("No stashed changes".to_string()))?;
+
+        {
+            let read_error =
+                |e: git2::Error| Error::InvalidData(format!("Failed to read stash: {}", e));
+            let stash = repo.find_commit(stash_id).map_err(read_error)?;
+            let base = stash.parent(0).and_then(|c| c.tree()).map_err(read_error)?;
+            let head = repo
+                .head()
+                .and_then(|h| h.peel_to_tree())
+                .map_err(read_error)?;
+
+            // Untracked files are kept in a third parent; fold them in.
+            let mut stashed = Index::new().map_err(read_error)?;
+            stashed
+                .read_tree(&stash.tree().map_err(read_error)?)
+                .map_err(read_error)?;
+            if let Ok(untracked) = stash.parent(2) {
+                let mut extra = Index::new().map_err(read_error)?;
+                extra
+                    .read_tree(&untracked.tree().map_err(read_error)?)
+                    .map_err(read_error)?;
+                for entry in extra.iter() {
+                    stashed.add(&entry).map_err(read_error)?;
+                }
+            }
+            let stashed = stashed
+                .write_tree_to(&repo)
+                .and_then(|id| repo.find_tree(id))
+                .map_err(|e| Error::InvalidData(format!("Failed to write tree: {}", e)))?;
+
+            let mut index = repo
+                .merge_trees(&base, &stashed, &head, None)
+                .map_err(|e| Error::InvalidData(format!("Failed to merge stash: {}", e)))?;
+            Self::resolve_conflicts(&repo, &mut index, resolve)?;
+
+            // Written to the work tree only, leaving the changes uncommitted.
+            repo.checkout_index(
+                Some(&mut index),
+                Some(
+                    git2::build::CheckoutBuilder::default()
+                        .force()
+                        .update_index(false),
+                ),
+            )
+            .map_err(|e| Error::InvalidData(format!("Failed to apply stash: {}", e)))?;
+        }
+
+        repo.stash_drop(0)
+            .map_err(|e| Error::InvalidData(format!("Failed to drop stash: {}", e)))?;
         Ok(())
     }
 
@@ -214,6 +518,107 @@ impl GitOperations {
         Ok(branch.to_string())
     }
 
+    /// Local branches, then remote-tracking ones, each sorted by name.
+    pub fn list_branches(&self) -> Result<Vec<BranchInfo>> {
+        let repo = self.get_repo()?;
+        let branches = repo
+            .branches(None)
+            .map_err(|e| Error::InvalidData(format!("Failed to list branches: {}", e)))?;
+
+        let mut list = Vec::new();
+        for branch in branches {
+            let (branch, kind) =
+                branch.map_err(|e| Error::InvalidData(format!("Failed to read branch: {}", e)))?;
+            let Ok(Some(name)) = branch.name() else {
+                continue;
+            };
+            // `origin/HEAD` points at another remote branch.
+            if kind == BranchType::Remote && name.ends_with("/HEAD") {
+                continue;
+            }
+            list.push(BranchInfo {
+                name: name.to_string(),
+                remote: kind == BranchType::Remote,
+                current: branch.is_head(),
+            });
+        }
+
+        list.sort_by(|a, b| (a.remote, &a.name).cmp(&(b.remote, &b.name)));
+        Ok(list)
+    }
+
+    /// Checks out `name`. A remote branch such as `origin/work` is checked
+    /// out as a local `work` tracking it. Refuses to overwrite uncommitted
+    /// changes.
+    pub fn switch_branch(&self, name: &str) -> Result<()> {
+        let repo = self.get_repo()?;
+        let branch = match repo.find_branch(name, BranchType::Local) {
+            Ok(branch) => branch,
+            Err(_) => Self::track_remote(&repo, name)?,
+        };
+
+        let reference = branch.into_reference();
+        let refname = reference
+            .name()
+            .ok_or_else(|| Error::InvalidData("Branch name is not valid UTF-8".to_string()))?
+            .to_string();
+        let commit = reference
+            .peel_to_commit()
+            .map_err(|e| Error::InvalidData(format!("Failed to get commit: {}", e)))?;
+
+        repo.checkout_tree(
+            commit.as_object(),
+            Some(git2::build::CheckoutBuilder::default().safe()),
+        )
+        .map_err(|e| Error::InvalidData(format!("Failed to checkout: {}", e)))?;
+
+        repo.set_head(&refname)
+            .map_err(|e| Error::InvalidData(format!("Failed to set HEAD: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn track_remote<'r>(repo: &'r Repository, name: &str) -> Result<Branch<'r>> {
+        let remote = repo
+            .find_branch(name, BranchType::Remote)
+            .map_err(|_| Error::NotFound(format!("Branch not found: {}", name)))?;
+        let local_name = name.split_once('/').map_or(name, |(_, branch)| branch);
+
+        if let Ok(local) = repo.find_branch(local_name, BranchType::Local) {
+            return Ok(local);
+        }
+
+        let commit = remote
+            .get()
+            .peel_to_commit()
+            .map_err(|e| Error::InvalidData(format!("Failed to get commit: {}", e)))?;
+        let mut local = repo
+            .branch(local_name, &commit, false)
+            .map_err(|e| Error::InvalidData(format!("Failed to create branch: {}", e)))?;
+        local
+            .set_upstream(Some(name))
+            .map_err(|e| Error::InvalidData(format!("Failed to set upstream: {}", e)))?;
+
+        Ok(local)
+    }
+
+    /// Opens the repository and reads its index and the tree HEAD points
+    /// at, if anything was committed yet.
+    pub fn verify(&self) -> Result<()> {
+        let repo = self.get_repo()?;
+        repo.index()
+            .map_err(|e| Error::InvalidData(format!("Failed to read index: {}", e)))?;
+
+        let head = match repo.head() {
+            Ok(head) => head,
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Ok(()),
+            Err(e) => return Err(Error::InvalidData(format!("Failed to get HEAD: {}", e))),
+        };
+        head.peel_to_tree()
+            .map_err(|e| Error::InvalidData(format!("Failed to read HEAD tree: {}", e)))?;
+        Ok(())
+    }
+
     pub fn last_commit_message(&self) -> Result<String> {
         let repo = self.get_repo()?;
 
@@ -232,6 +637,37 @@ impl GitOperations {
         Ok(message.to_string())
     }
 
+    /// Up to `limit` commits reachable from HEAD, newest first. Empty
+    /// before the first commit.
+    pub fn log(&self, limit: usize) -> Result<Vec<CommitInfo>> {
+        let repo = self.get_repo()?;
+        if repo.head().is_err() {
+            return Ok(Vec::new());
+        }
+
+        let mut walk = repo
+            .revwalk()
+            .map_err(|e| Error::InvalidData(format!("Failed to walk history: {}", e)))?;
+        walk.push_head()
+            .map_err(|e| Error::InvalidData(format!("Failed to walk history: {}", e)))?;
+
+        let mut commits = Vec::new();
+        for oid in walk.take(limit) {
+            let oid =
+                oid.map_err(|e| Error::InvalidData(format!("Failed to walk history: {}", e)))?;
+            let commit = repo
+                .find_commit(oid)
+                .map_err(|e| Error::InvalidData(format!("Failed to get commit: {}", e)))?;
+            commits.push(CommitInfo {
+                id: oid.to_string(),
+                author: commit.author().name().unwrap_or_default().to_string(),
+                time: commit.time().seconds(),
+                message: commit.message().unwrap_or_default().to_string(),
+            });
+        }
+        Ok(commits)
+    }
+
     pub fn get_sync_status(&self, remote_name: &str, branch: &str) -> Result<(usize, usize)> {
         let repo = self.get_repo()?;
 
@@ -284,6 +720,17 @@ mod tests {
 
         assert!(!commit_id.is_zero());
         assert_eq!(git_ops.last_commit_message().unwrap(), "Initial commit");
+
+        std::fs::write(temp_dir.path().join("test.txt"), "Hello again").unwrap();
+        git_ops.add_all().unwrap();
+        git_ops.commit("Second commit").unwrap();
+
+        let log = git_ops.log(10).unwrap();
+        let messages: Vec<&str> = log.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(messages, ["Second commit", "Initial commit"]);
+        assert_eq!(log[1].id, commit_id.to_string());
+        assert_eq!(log[0].author, "MooTimer");
+        assert_eq!(git_ops.log(1).unwrap().len(), 1);
     }
 
     #[test]
@@ -305,6 +752,147 @@ mod tests {
         assert!(!git_ops.has_changes().unwrap());
     }
 
+    #[test]
+    fn test_diff() {
+        let temp_dir = TempDir::new().unwrap();
+        let git_ops = GitOperations::new(temp_dir.path().to_path_buf());
+
+        git_ops.init().unwrap();
+        std::fs::write(temp_dir.path().join("test.txt"), "one\ntwo\n").unwrap();
+        git_ops.add_all().unwrap();
+        git_ops.commit("Initial commit").unwrap();
+        assert!(git_ops.diff_stats().unwrap().is_empty());
+
+        std::fs::write(temp_dir.path().join("test.txt"), "one\nthree\n").unwrap();
+        std::fs::write(temp_dir.path().join("new.txt"), "hello\n").unwrap();
+
+        let stats = git_ops.diff_stats().unwrap();
+        assert_eq!(
+            stats,
+            vec![
+                FileChange {
+                    path: "new.txt".to_string(),
+                    insertions: 1,
+                    deletions: 0,
+                },
+                FileChange {
+                    path: "test.txt".to_string(),
+                    insertions: 1,
+                    deletions: 1,
+                },
+            ]
+        );
+
+        let full = git_ops.diff_full().unwrap();
+        assert!(full.contains("diff --git a/test.txt b/test.txt"));
+        assert!(full.lines().any(|l| l == "-two"));
+        assert!(full.lines().any(|l| l == "+three"));
+        assert!(full.lines().any(|l| l == "+hello"));
+    }
+
+    #[test]
+    fn test_pull_merges_diverged_branches() {
+        let remote_dir = TempDir::new().unwrap();
+        let remote = GitOperations::new(remote_dir.path().to_path_buf());
+        remote.init().unwrap();
+        std::fs::write(remote_dir.path().join("shared.txt"), "base\n").unwrap();
+        remote.add_all().unwrap();
+        remote.commit("Base").unwrap();
+        let branch = remote.current_branch().unwrap();
+
+        let local_dir = TempDir::new().unwrap();
+        Repository::clone(remote_dir.path().to_str().unwrap(), local_dir.path()).unwrap();
+        let local = GitOperations::new(local_dir.path().to_path_buf());
+
+        std::fs::write(remote_dir.path().join("shared.txt"), "remote\n").unwrap();
+        std::fs::write(remote_dir.path().join("remote_only.txt"), "new\n").unwrap();
+        remote.add_all().unwrap();
+        remote.commit("Remote change").unwrap();
+
+        std::fs::write(local_dir.path().join("shared.txt"), "local\n").unwrap();
+        local.add_all().unwrap();
+        local.commit("Local change").unwrap();
+
+        let mut seen = Vec::new();
+        let outcome = local
+            .pull("origin", &branch, |file| {
+                seen.push(file.clone());
+                Ok(Some(b"merged\n".to_vec()))
+            })
+            .unwrap();
+        assert_eq!(outcome, PullOutcome::Merged);
+
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].path, "shared.txt");
+        assert_eq!(seen[0].ancestor.as_deref(), Some(&b"base\n"[..]));
+        assert_eq!(seen[0].ours.as_deref(), Some(&b"local\n"[..]));
+        assert_eq!(seen[0].theirs.as_deref(), Some(&b"remote\n"[..]));
+
+        let read = |name: &str| std::fs::read_to_string(local_dir.path().join(name)).unwrap();
+        assert_eq!(read("shared.txt"), "merged\n");
+        assert_eq!(read("remote_only.txt"), "new\n");
+        assert!(!local.has_changes().unwrap());
+        assert_eq!(
+            local.log(1).unwrap()[0].message,
+            format!("Merge origin/{}", branch)
+        );
+
+        let outcome = local.pull("origin", &branch, |_| unreachable!()).unwrap();
+        assert_eq!(outcome, PullOutcome::UpToDate);
+    }
+
+    #[test]
+    fn test_stash_pull_and_pop_merges_local_changes() {
+        let remote_dir = TempDir::new().unwrap();
+        let remote = GitOperations::new(remote_dir.path().to_path_buf());
+        remote.init().unwrap();
+        std::fs::write(remote_dir.path().join("shared.txt"), "base\n").unwrap();
+        std::fs::write(remote_dir.path().join("local_only.txt"), "base\n").unwrap();
+        remote.add_all().unwrap();
+        remote.commit("Base").unwrap();
+        let branch = remote.current_branch().unwrap();
+
+        let local_dir = TempDir::new().unwrap();
+        Repository::clone(remote_dir.path().to_str().unwrap(), local_dir.path()).unwrap();
+        let local = GitOperations::new(local_dir.path().to_path_buf());
+        assert_eq!(local.stash("Nothing").unwrap(), None);
+
+        std::fs::write(remote_dir.path().join("shared.txt"), "remote\n").unwrap();
+        remote.add_all().unwrap();
+        remote.commit("Remote change").unwrap();
+
+        std::fs::write(local_dir.path().join("shared.txt"), "local\n").unwrap();
+        std::fs::write(local_dir.path().join("local_only.txt"), "edited\n").unwrap();
+        std::fs::write(local_dir.path().join("untracked.txt"), "new\n").unwrap();
+        assert!(local.stash("Before pull").unwrap().is_some());
+        assert!(!local.has_changes().unwrap());
+
+        let outcome = local.pull("origin", &branch, |_| unreachable!()).unwrap();
+        assert_eq!(outcome, PullOutcome::FastForward);
+
+        let mut seen = Vec::new();
+        local
+            .stash_pop(|file| {
+                seen.push(file.clone());
+                Ok(Some(b"merged\n".to_vec()))
+            })
+            .unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].path, "shared.txt");
+        assert_eq!(seen[0].ours.as_deref(), Some(&b"local\n"[..]));
+        assert_eq!(seen[0].theirs.as_deref(), Some(&b"remote\n"[..]));
+
+        let read = |name: &str| std::fs::read_to_string(local_dir.path().join(name)).unwrap();
+        assert_eq!(read("shared.txt"), "merged\n");
+        assert_eq!(read("local_only.txt"), "edited\n");
+        assert_eq!(read("untracked.txt"), "new\n");
+        assert!(local.has_changes().unwrap());
+        assert!(matches!(
+            local.stash_pop(|_| unreachable!()),
+            Err(Error::NotFound(_))
+        ));
+    }
+
     #[test]
     fn test_current_branch() {
         let temp_dir = TempDir::new().unwrap();
@@ -319,4 +907,39 @@ mod tests {
         let branch = git_ops.current_branch().unwrap();
         assert!(branch == "main" || branch == "master");
     }
+
+    #[test]
+    fn test_list_and_switch_branches() {
+        let temp_dir = TempDir::new().unwrap();
+        let git_ops = GitOperations::new(temp_dir.path().to_path_buf());
+
+        git_ops.init().unwrap();
+        std::fs::write(temp_dir.path().join("test.txt"), "Hello").unwrap();
+        git_ops.add_all().unwrap();
+        git_ops.commit("Initial commit").unwrap();
+        let main = git_ops.current_branch().unwrap();
+
+        let repo = Repository::open(temp_dir.path()).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("work", &head, false).unwrap();
+
+        git_ops.switch_branch("work").unwrap();
+        std::fs::write(temp_dir.path().join("test.txt"), "Work").unwrap();
+        git_ops.add_all().unwrap();
+        git_ops.commit("Work commit").unwrap();
+
+        let branches = git_ops.list_branches().unwrap();
+        let names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
+        assert!(names.contains(&main.as_str()) && names.contains(&"work"));
+        assert!(branches.iter().any(|b| b.name == "work" && b.current));
+
+        git_ops.switch_branch(&main).unwrap();
+        assert_eq!(git_ops.current_branch().unwrap(), main);
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("test.txt")).unwrap(),
+            "Hello"
+        );
+
+        assert!(git_ops.switch_branch("nowhere").is_err());
+    }
 }
@@ -1,6 +1,10 @@
 pub mod error;
 pub mod git;
+pub mod messages;
 pub mod models;
+pub mod query;
+pub mod report;
+pub mod search;
 pub mod storage;
 
 pub use error::{Error, Result};
@@ -0,0 +1,180 @@
+//! Notification text shown to the user, keyed by locale.
+//!
+//! Every key has an English string; other locales may leave keys out and
+//! those fall back to English. Placeholders are written `{name}` and filled
+//! in by [`render`].
+
+use std::collections::BTreeMap;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Locales with a message table, valid for `daemon.locale` and `tui.locale`.
+pub const LOCALES: &[&str] = &["en", "de"];
+
+const EN: &[(&str, &str)] = &[
+    ("timer.five_minutes_left.title", "⏰ 5 Minutes Left"),
+    (
+        "timer.five_minutes_left.body",
+        "Your countdown timer is almost complete!",
+    ),
+    ("timer.countdown_completed.title", "⏰ Countdown Complete!"),
+    (
+        "timer.countdown_completed.body",
+        "Your countdown timer has finished.",
+    ),
+    ("phase.work_completed.title", "🍅 Work Complete!"),
+    ("phase.work_completed.body", "Time for a break!"),
+    ("phase.short_break_completed.title", "☕ Break Over"),
+    ("phase.short_break_completed.body", "Ready to focus again?"),
+    ("phase.long_break_completed.title", "🎉 Long Break Over"),
+    ("phase.long_break_completed.body", "Let's get back to work!"),
+    ("phase.completed.title", "🍅 Pomodoro"),
+    ("phase.completed.body", "Phase completed"),
+    ("phase.short_break_started.title", "☕ Short Break"),
+    (
+        "phase.short_break_started.body",
+        "Take a quick {minutes}-minute break!",
+    ),
+    ("phase.long_break_started.title", "🎉 Long Break!"),
+    (
+        "phase.long_break_started.body",
+        "You've earned a longer break!",
+    ),
+    (
+        "sync.entry_commit.summary",
+        "Add entry: {task} - {minutes}m ({time})",
+    ),
+    ("sync.entry_commit.task", "task {task_id}"),
+    ("sync.entry_commit.no_task", "no task"),
+];
+
+const DE: &[(&str, &str)] = &[
+    ("timer.five_minutes_left.title", "⏰ Noch 5 Minuten"),
+    (
+        "timer.five_minutes_left.body",
+        "Dein Countdown ist fast abgelaufen!",
+    ),
+    (
+        "timer.countdown_completed.title",
+        "⏰ Countdown abgelaufen!",
+    ),
+    (
+        "timer.countdown_completed.body",
+        "Dein Countdown ist beendet.",
+    ),
+    ("phase.work_completed.title", "🍅 Arbeitsphase geschafft!"),
+    ("phase.work_completed.body", "Zeit für eine Pause!"),
+    ("phase.short_break_completed.title", "☕ Pause vorbei"),
+    (
+        "phase.short_break_completed.body",
+        "Bereit, dich wieder zu konzentrieren?",
+    ),
+    ("phase.long_break_completed.title", "🎉 Lange Pause vorbei"),
+    ("phase.long_break_completed.body", "Zurück an die Arbeit!"),
+    ("phase.completed.body", "Phase abgeschlossen"),
+    ("phase.short_break_started.title", "☕ Kurze Pause"),
+    (
+        "phase.short_break_started.body",
+        "Gönn dir {minutes} Minuten Pause!",
+    ),
+    ("phase.long_break_started.title", "🎉 Lange Pause!"),
+    (
+        "phase.long_break_started.body",
+        "Du hast dir eine längere Pause verdient!",
+    ),
+    (
+        "sync.entry_commit.summary",
+        "Eintrag hinzugefügt: {task} - {minutes}m ({time})",
+    ),
+    ("sync.entry_commit.task", "Aufgabe {task_id}"),
+    ("sync.entry_commit.no_task", "keine Aufgabe"),
+];
+
+fn table(locale: &str) -> Option<&'static [(&'static str, &'static str)]> {
+    match locale {
+        "en" => Some(EN),
+        "de" => Some(DE),
+        _ => None,
+    }
+}
+
+fn find(table: &[(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    table.iter().find(|(k, _)| *k == key).map(|(_, text)| *text)
+}
+
+/// The text for `key` in `locale`. Falls back to English when the locale
+/// or the key is unknown, and to `key` itself when English lacks it too.
+pub fn lookup<'a>(locale: &str, key: &'a str) -> &'a str {
+    table(locale)
+        .and_then(|t| find(t, key))
+        .or_else(|| find(EN, key))
+        .unwrap_or(key)
+}
+
+/// [`lookup`] with its placeholders filled in from `args`.
+pub fn render(locale: &str, key: &str, args: &[(&str, &str)]) -> String {
+    fill(lookup(locale, key), args)
+}
+
+/// Replaces each `{name}` in `text` with its value from `args`.
+/// Placeholders without a matching arg are left as written.
+pub fn fill(text: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// Every key with its text in `locale`, English filling the gaps.
+pub fn catalog(locale: &str) -> BTreeMap<&'static str, &'static str> {
+    let mut messages: BTreeMap<_, _> = EN.iter().copied().collect();
+    if let Some(table) = table(locale) {
+        messages.extend(table.iter().copied());
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_locale_key_exists_in_english() {
+        for locale in LOCALES {
+            for (key, _) in table(locale).unwrap() {
+                assert!(
+                    find(EN, key).is_some(),
+                    "{} has unknown key {}",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_missing_keys_fall_back() {
+        assert_eq!(
+            lookup("de", "phase.work_completed.body"),
+            "Zeit für eine Pause!"
+        );
+        // Not translated in the German table.
+        assert_eq!(lookup("de", "phase.completed.title"), "🍅 Pomodoro");
+        assert_eq!(lookup("fr", "phase.completed.body"), "Phase completed");
+        assert_eq!(lookup("de", "no.such.key"), "no.such.key");
+
+        assert_eq!(catalog("de").len(), EN.len());
+        assert_eq!(catalog("xx"), catalog("en"));
+    }
+
+    #[test]
+    fn test_render_fills_placeholders() {
+        assert_eq!(
+            render("en", "phase.short_break_started.body", &[("minutes", "5")]),
+            "Take a quick 5-minute break!"
+        );
+        assert_eq!(
+            render("de", "sync.entry_commit.task", &[]),
+            "Aufgabe {task_id}"
+        );
+    }
+}
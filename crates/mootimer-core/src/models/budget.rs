@@ -0,0 +1,207 @@
+use crate::{Error, Result};
+use chrono::{DateTime, Datelike, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::Task;
+
+/// A cap on the time tracked against a task, a tag or a whole profile over
+/// a day, week or month, e.g. "10h on 'meetings' this week".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Budget {
+    pub id: String,
+    pub scope: BudgetScope,
+    pub period: BudgetPeriod,
+    pub limit_seconds: u64,
+    #[serde(default)]
+    pub action: BudgetAction,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BudgetScope {
+    Task {
+        task_id: String,
+    },
+    /// Entries tagged `tag`, or logged against a task tagged `tag`.
+    Tag {
+        tag: String,
+    },
+    Profile,
+}
+
+/// Calendar periods in UTC, matching `entry.stats_today/week/month`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Day,
+    /// Since Monday.
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetAction {
+    /// Only alert at 80% and 100%.
+    #[default]
+    Warn,
+    /// Also refuse to start timers the budget covers once it is used up.
+    BlockNewTimers,
+}
+
+impl Budget {
+    pub fn new(
+        scope: BudgetScope,
+        period: BudgetPeriod,
+        limit_seconds: u64,
+        action: BudgetAction,
+    ) -> Result<Self> {
+        let budget = Self {
+            id: Uuid::new_v4().to_string(),
+            scope,
+            period,
+            limit_seconds,
+            action,
+            created_at: Utc::now(),
+        };
+        budget.validate()?;
+        Ok(budget)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.limit_seconds == 0 {
+            return Err(Error::Validation(
+                "Budget limit must be greater than zero".to_string(),
+            ));
+        }
+        match &self.scope {
+            BudgetScope::Task { task_id } if task_id.trim().is_empty() => Err(Error::Validation(
+                "Budget task ID cannot be empty".to_string(),
+            )),
+            BudgetScope::Tag { tag } if tag.trim().is_empty() => {
+                Err(Error::Validation("Budget tag cannot be empty".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether time tracked on `task` counts against this budget. Profile
+    /// budgets cover everything, including time without a task.
+    pub fn covers_task(&self, task: Option<&Task>) -> bool {
+        match &self.scope {
+            BudgetScope::Profile => true,
+            BudgetScope::Task { task_id } => task.is_some_and(|t| t.id == *task_id),
+            BudgetScope::Tag { tag } => task.is_some_and(|t| t.tags.contains(tag)),
+        }
+    }
+
+    /// Like `covers_task`, also counting the entry's own tags.
+    pub fn covers_entry(&self, entry_tags: &[String], task: Option<&Task>) -> bool {
+        match &self.scope {
+            BudgetScope::Tag { tag } if entry_tags.contains(tag) => true,
+            _ => self.covers_task(task),
+        }
+    }
+
+    /// "tag 'meetings' this week", for alerts and errors.
+    pub fn describe(&self, task_title: Option<&str>) -> String {
+        let scope = match &self.scope {
+            BudgetScope::Task { task_id } => format!("'{}'", task_title.unwrap_or(task_id)),
+            BudgetScope::Tag { tag } => format!("tag '{}'", tag),
+            BudgetScope::Profile => "this profile".to_string(),
+        };
+        format!("{} {}", scope, self.period.label())
+    }
+}
+
+impl BudgetPeriod {
+    /// When the period containing `now` began.
+    pub fn start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let today = now.date_naive();
+        let first = match self {
+            Self::Day => today,
+            Self::Week => today - Duration::days(today.weekday().num_days_from_monday() as i64),
+            Self::Month => today.with_day(1).unwrap_or(today),
+        };
+        first.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Day => "today",
+            Self::Week => "this week",
+            Self::Month => "this month",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_period_start() {
+        // A Thursday.
+        let now = Utc.with_ymd_and_hms(2025, 5, 15, 14, 30, 0).unwrap();
+        assert_eq!(
+            BudgetPeriod::Day.start(now),
+            Utc.with_ymd_and_hms(2025, 5, 15, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            BudgetPeriod::Week.start(now),
+            Utc.with_ymd_and_hms(2025, 5, 12, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            BudgetPeriod::Month.start(now),
+            Utc.with_ymd_and_hms(2025, 5, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_budget_coverage() {
+        let mut task = Task::new("Standup".to_string()).unwrap();
+        task.add_tag("meetings".to_string());
+        let other = Task::new("Code review".to_string()).unwrap();
+
+        let tag = BudgetScope::Tag {
+            tag: "meetings".to_string(),
+        };
+        let budget = Budget::new(tag, BudgetPeriod::Week, 36_000, BudgetAction::Warn).unwrap();
+        assert!(budget.covers_task(Some(&task)));
+        assert!(!budget.covers_task(Some(&other)));
+        assert!(!budget.covers_task(None));
+        assert!(budget.covers_entry(&["meetings".to_string()], None));
+
+        let by_task = BudgetScope::Task {
+            task_id: task.id.clone(),
+        };
+        let budget = Budget::new(by_task, BudgetPeriod::Day, 3600, BudgetAction::Warn).unwrap();
+        assert!(budget.covers_task(Some(&task)));
+        assert!(!budget.covers_entry(&["meetings".to_string()], Some(&other)));
+
+        let profile = Budget::new(
+            BudgetScope::Profile,
+            BudgetPeriod::Month,
+            3600,
+            BudgetAction::BlockNewTimers,
+        )
+        .unwrap();
+        assert!(profile.covers_task(None));
+
+        assert!(
+            Budget::new(
+                BudgetScope::Profile,
+                BudgetPeriod::Day,
+                0,
+                BudgetAction::Warn
+            )
+            .is_err()
+        );
+    }
+}
@@ -1,19 +1,121 @@
+use crate::messages::{DEFAULT_LOCALE, LOCALES};
+use crate::models::TaskPriority;
+use crate::storage::durability::{DURABILITY_LEVELS, Durability};
 use crate::{Error, Result};
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+/// Upper bound for pomodoro work and break durations, in seconds.
+pub const MAX_DURATION_SECONDS: u64 = 7200;
+
+/// Upper bound for `daemon.max_timers_per_profile`.
+pub const MAX_TIMERS_PER_PROFILE: usize = 20;
+
+/// Most entries `tui.countdown_presets` may hold, one per number key.
+pub const MAX_COUNTDOWN_PRESETS: usize = 9;
+
+/// Upper bound for a countdown preset, in minutes.
+pub const MAX_COUNTDOWN_PRESET_MINUTES: u64 = 180;
+
+pub const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+/// Variables the TUI substitutes into `tui.status_bar_format`.
+pub const STATUS_BAR_VARIABLES: &[&str] = &[
+    "timer_elapsed",
+    "timer_task",
+    "timer_state",
+    "today_total",
+    "profile",
+    "view",
+    "hints",
+];
+
+pub const DEFAULT_STATUS_BAR_FORMAT: &str = "👤 {profile}  │  🎯 {timer_task}  │ {hints}";
+
+/// Color names accepted by `TuiColor::Named`, matching ratatui's palette.
+pub const NAMED_COLORS: &[&str] = &[
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "gray",
+    "dark_gray",
+    "light_red",
+    "light_green",
+    "light_yellow",
+    "light_blue",
+    "light_magenta",
+    "light_cyan",
+    "white",
+];
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Config {
     pub version: String,
     pub default_profile: Option<String>,
+    /// Display name of the person using this install, set during first-run
+    /// setup.
+    #[serde(default)]
+    pub current_user: Option<String>,
     pub daemon: DaemonConfig,
     pub pomodoro: PomodoroConfig,
     pub sync: SyncConfig,
+    #[serde(default)]
+    pub tui: TuiConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DaemonConfig {
     pub socket_path: String,
     pub log_level: String,
+    /// RPC methods the daemon refuses to run. Entries may use `*` as a
+    /// wildcard, e.g. `sync.*`.
+    #[serde(default)]
+    pub disabled_methods: Vec<String>,
+    /// How many timers one profile may run side by side.
+    #[serde(default = "default_max_timers_per_profile")]
+    pub max_timers_per_profile: usize,
+    /// Refuse to start timers on tasks that are blocked by unfinished
+    /// tasks, instead of starting them with a warning.
+    #[serde(default)]
+    pub strict_task_blocking: bool,
+    /// Language of text the daemon writes itself, such as auto-commit
+    /// messages. One of `LOCALES`.
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    /// Count RPC calls per method, day and client in the data directory's
+    /// `usage/` folder. Nothing is sent anywhere.
+    #[serde(default)]
+    pub usage_metrics: bool,
+    /// Keep entries encrypted with a passphrase. Needs a daemon built with
+    /// the `encrypt` feature, and existing entries converted with
+    /// `mootimerd storage migrate --encrypt`.
+    #[serde(default)]
+    pub encryption: bool,
+    /// How much fsyncing storage does before a write returns; see
+    /// `storage::durability`.
+    #[serde(default)]
+    pub durability: Durability,
+    /// Entries starting before this day are rejected as mistyped dates.
+    #[serde(default = "default_earliest_entry_date")]
+    pub earliest_entry_date: NaiveDate,
+}
+
+fn default_locale() -> String {
+    DEFAULT_LOCALE.to_string()
+}
+
+fn default_earliest_entry_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2000, 1, 1).unwrap()
+}
+
+fn default_max_timers_per_profile() -> usize {
+    3
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -24,6 +126,10 @@ pub struct PomodoroConfig {
     pub sessions_until_long_break: u32,
     #[serde(default = "default_countdown")]
     pub countdown_default: u64,
+    /// Start the next work session as soon as a break ends instead of
+    /// pausing until the user resumes.
+    #[serde(default)]
+    pub auto_start_next_session: bool,
 }
 
 fn default_countdown() -> u64 {
@@ -37,11 +143,109 @@ pub struct SyncConfig {
     pub remote_url: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TuiConfig {
+    /// Template for the TUI status bar. `{name}` is replaced with one of
+    /// `STATUS_BAR_VARIABLES`; everything else is shown as written.
+    #[serde(default = "default_status_bar_format")]
+    pub status_bar_format: String,
+    /// Colors of the priority marker in task lists. Unknown color names
+    /// fall back to the default for that priority.
+    #[serde(default = "default_priority_colors")]
+    pub priority_colors: HashMap<TaskPriority, TuiColor>,
+    /// IANA timezone name (e.g. `Europe/Berlin`) the TUI shows times in
+    /// and the daemon reads profiles' working hours in. `None` follows the
+    /// system timezone.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Countdown lengths in minutes offered by the TUI's preset picker.
+    #[serde(default = "default_countdown_presets")]
+    pub countdown_presets: Vec<u64>,
+    /// The Reports view highlights time outside working hours once it
+    /// is more than this percentage of the total.
+    #[serde(default = "default_outside_hours_warn_percent")]
+    pub outside_hours_warn_percent: u8,
+    /// Dashboard animation for pomodoro timers: `tomato`, `cow`, `none`
+    /// or the name of a file in the TUI's `animations/` config folder.
+    #[serde(default = "default_pomodoro_animation")]
+    pub pomodoro_animation: String,
+    /// Dashboard animation for countdown timers, chosen the same way as
+    /// `pomodoro_animation`.
+    #[serde(default = "default_countdown_animation")]
+    pub countdown_animation: String,
+    /// Language of the TUI's notifications. `None` follows `daemon.locale`.
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Remapped TUI keys, from action name (`"timer.stop"`) to key
+    /// (`"s"`, `"ctrl+s"`, `"f5"`). The TUI lists the actions in its help
+    /// and ignores entries it can't parse.
+    #[serde(default)]
+    pub keybindings: HashMap<String, String>,
+}
+
+/// A terminal color, written either as a name from `NAMED_COLORS` or as
+/// `{ "r": 255, "g": 136, "b": 0 }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TuiColor {
+    Rgb { r: u8, g: u8, b: u8 },
+    Named(String),
+}
+
+impl TuiColor {
+    pub fn named(name: &str) -> Self {
+        Self::Named(name.to_string())
+    }
+
+    /// The color name in `NAMED_COLORS` form, so `Light-Red` and
+    /// `light red` are accepted too. `None` for RGB colors.
+    pub fn normalized_name(&self) -> Option<String> {
+        match self {
+            Self::Named(name) => Some(name.trim().to_lowercase().replace(['-', ' '], "_")),
+            Self::Rgb { .. } => None,
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        self.normalized_name()
+            .is_none_or(|name| NAMED_COLORS.contains(&name.as_str()))
+    }
+}
+
+fn default_status_bar_format() -> String {
+    DEFAULT_STATUS_BAR_FORMAT.to_string()
+}
+
+fn default_outside_hours_warn_percent() -> u8 {
+    20
+}
+
+fn default_pomodoro_animation() -> String {
+    "tomato".to_string()
+}
+
+fn default_countdown_animation() -> String {
+    "cow".to_string()
+}
+
+fn default_countdown_presets() -> Vec<u64> {
+    vec![15, 30, 60]
+}
+
+fn default_priority_colors() -> HashMap<TaskPriority, TuiColor> {
+    HashMap::from([
+        (TaskPriority::Low, TuiColor::named("blue")),
+        (TaskPriority::Medium, TuiColor::named("yellow")),
+        (TaskPriority::High, TuiColor::named("red")),
+    ])
+}
+
 impl Config {
     pub fn validate(&self) -> Result<()> {
         self.daemon.validate()?;
         self.pomodoro.validate()?;
         self.sync.validate()?;
+        self.tui.validate()?;
         Ok(())
     }
 }
@@ -51,9 +255,11 @@ impl Default for Config {
         Self {
             version: "1.0.0".to_string(),
             default_profile: None,
+            current_user: None,
             daemon: DaemonConfig::default(),
             pomodoro: PomodoroConfig::default(),
             sync: SyncConfig::default(),
+            tui: TuiConfig::default(),
         }
     }
 }
@@ -64,17 +270,64 @@ impl DaemonConfig {
             return Err(Error::Validation("Socket path cannot be empty".to_string()));
         }
 
-        let valid_log_levels = ["error", "warn", "info", "debug", "trace"];
-        if !valid_log_levels.contains(&self.log_level.as_str()) {
+        if !LOG_LEVELS.contains(&self.log_level.as_str()) {
             return Err(Error::Validation(format!(
                 "Invalid log level '{}'. Must be one of: {}",
                 self.log_level,
-                valid_log_levels.join(", ")
+                LOG_LEVELS.join(", ")
+            )));
+        }
+
+        if self.disabled_methods.iter().any(|m| m.trim().is_empty()) {
+            return Err(Error::Validation(
+                "Disabled method patterns cannot be empty".to_string(),
+            ));
+        }
+
+        if !(1..=MAX_TIMERS_PER_PROFILE).contains(&self.max_timers_per_profile) {
+            return Err(Error::Validation(format!(
+                "Max timers per profile must be between 1 and {}",
+                MAX_TIMERS_PER_PROFILE
+            )));
+        }
+
+        if !LOCALES.contains(&self.locale.as_str()) {
+            return Err(Error::Validation(format!(
+                "Invalid locale '{}'. Must be one of: {}",
+                self.locale,
+                LOCALES.join(", ")
             )));
         }
 
         Ok(())
     }
+
+    pub fn is_method_disabled(&self, method: &str) -> bool {
+        self.disabled_methods
+            .iter()
+            .any(|pattern| glob_match(pattern, method))
+    }
+}
+
+/// Matches `text` against a pattern where `*` stands for any run of
+/// characters (including none). No other metacharacters are recognised.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == text;
+    };
+    let Some(mut remaining) = text.strip_prefix(prefix) else {
+        return false;
+    };
+
+    let mut parts: Vec<&str> = rest.split('*').collect();
+    let suffix = parts.pop().unwrap_or_default();
+    for part in parts {
+        match remaining.find(part) {
+            Some(i) => remaining = &remaining[i + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.len() >= suffix.len() && remaining.ends_with(suffix)
 }
 
 impl Default for DaemonConfig {
@@ -82,6 +335,14 @@ impl Default for DaemonConfig {
         Self {
             socket_path: "/tmp/mootimer.sock".to_string(),
             log_level: "info".to_string(),
+            disabled_methods: Vec::new(),
+            max_timers_per_profile: default_max_timers_per_profile(),
+            strict_task_blocking: false,
+            locale: default_locale(),
+            usage_metrics: false,
+            encryption: false,
+            durability: Durability::default(),
+            earliest_entry_date: default_earliest_entry_date(),
         }
     }
 }
@@ -112,28 +373,33 @@ impl PomodoroConfig {
             ));
         }
 
-        const MAX_DURATION: u64 = 7200;
-        if self.work_duration > MAX_DURATION {
+        if self.work_duration > MAX_DURATION_SECONDS {
             return Err(Error::Validation(format!(
                 "Work duration too long (max {} seconds)",
-                MAX_DURATION
+                MAX_DURATION_SECONDS
             )));
         }
 
-        if self.short_break > MAX_DURATION {
+        if self.short_break > MAX_DURATION_SECONDS {
             return Err(Error::Validation(format!(
                 "Short break too long (max {} seconds)",
-                MAX_DURATION
+                MAX_DURATION_SECONDS
             )));
         }
 
-        if self.long_break > MAX_DURATION {
+        if self.long_break > MAX_DURATION_SECONDS {
             return Err(Error::Validation(format!(
                 "Long break too long (max {} seconds)",
-                MAX_DURATION
+                MAX_DURATION_SECONDS
             )));
         }
 
+        if self.countdown_default == 0 {
+            return Err(Error::Validation(
+                "Countdown default must be greater than 0".to_string(),
+            ));
+        }
+
         Ok(())
     }
 
@@ -158,6 +424,7 @@ impl Default for PomodoroConfig {
             long_break: 900,
             sessions_until_long_break: 4,
             countdown_default: 1800,
+            auto_start_next_session: false,
         }
     }
 }
@@ -190,6 +457,610 @@ impl Default for SyncConfig {
     }
 }
 
+impl TuiConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.status_bar_format.trim().is_empty() {
+            return Err(Error::Validation(
+                "Status bar format cannot be empty".to_string(),
+            ));
+        }
+
+        let mut rest = self.status_bar_format.as_str();
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            let name = &rest[start + 1..start + len];
+            if !STATUS_BAR_VARIABLES.contains(&name) {
+                return Err(Error::Validation(format!(
+                    "Unknown status bar variable '{{{}}}'. Must be one of: {}",
+                    name,
+                    STATUS_BAR_VARIABLES.join(", ")
+                )));
+            }
+            rest = &rest[start + len + 1..];
+        }
+
+        if let Some(ref timezone) = self.timezone
+            && timezone.trim().is_empty()
+        {
+            return Err(Error::Validation("Timezone cannot be empty".to_string()));
+        }
+
+        if self.countdown_presets.len() > MAX_COUNTDOWN_PRESETS {
+            return Err(Error::Validation(format!(
+                "At most {} countdown presets are allowed",
+                MAX_COUNTDOWN_PRESETS
+            )));
+        }
+        if self
+            .countdown_presets
+            .iter()
+            .any(|m| !(1..=MAX_COUNTDOWN_PRESET_MINUTES).contains(m))
+        {
+            return Err(Error::Validation(format!(
+                "Countdown presets must be between 1 and {} minutes",
+                MAX_COUNTDOWN_PRESET_MINUTES
+            )));
+        }
+
+        if self.outside_hours_warn_percent > 100 {
+            return Err(Error::Validation(
+                "Outside hours warning must be a percentage from 0 to 100".to_string(),
+            ));
+        }
+
+        for animation in [&self.pomodoro_animation, &self.countdown_animation] {
+            if animation.trim().is_empty() || animation.contains(['/', '\\']) {
+                return Err(Error::Validation(format!(
+                    "Invalid animation '{}'. Use a built-in name or an animation file's name \
+                     without its folder",
+                    animation
+                )));
+            }
+        }
+
+        if let Some(ref locale) = self.locale
+            && !LOCALES.contains(&locale.as_str())
+        {
+            return Err(Error::Validation(format!(
+                "Invalid locale '{}'. Must be one of: {}",
+                locale,
+                LOCALES.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl TuiConfig {
+    /// The configured priority colors, with unknown names replaced by the
+    /// defaults. Also returns a message for each replacement, for logging.
+    pub fn resolved_priority_colors(&self) -> (HashMap<TaskPriority, TuiColor>, Vec<String>) {
+        let mut colors = default_priority_colors();
+        let mut warnings = Vec::new();
+
+        for (priority, color) in &self.priority_colors {
+            if color.is_known() {
+                colors.insert(*priority, color.clone());
+            } else {
+                warnings.push(format!(
+                    "Unknown color {:?} for {} priority, using the default",
+                    color,
+                    priority.as_str()
+                ));
+            }
+        }
+
+        (colors, warnings)
+    }
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self {
+            status_bar_format: default_status_bar_format(),
+            priority_colors: default_priority_colors(),
+            timezone: None,
+            countdown_presets: default_countdown_presets(),
+            outside_hours_warn_percent: default_outside_hours_warn_percent(),
+            pomodoro_animation: default_pomodoro_animation(),
+            countdown_animation: default_countdown_animation(),
+            locale: None,
+            keybindings: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldType {
+    String,
+    Bool,
+    Integer,
+    /// Integer number of seconds.
+    Duration,
+    Enum,
+    StringList,
+    /// List of integers, each held to `min` and `max`.
+    IntegerList,
+}
+
+/// Metadata for a single config leaf, as returned by `config.describe`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldDescriptor {
+    /// Dotted path into the serialized config, e.g. `pomodoro.work_duration`.
+    pub path: &'static str,
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+    pub label: &'static str,
+    pub default: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<u64>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub options: &'static [&'static str],
+    pub nullable: bool,
+    pub requires_restart: bool,
+    /// What choosing one value over another costs, where that isn't
+    /// obvious from the label.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<&'static str>,
+}
+
+impl FieldDescriptor {
+    fn new(path: &'static str, field_type: FieldType, label: &'static str, default: Value) -> Self {
+        Self {
+            path,
+            field_type,
+            label,
+            default,
+            min: None,
+            max: None,
+            options: &[],
+            nullable: false,
+            requires_restart: false,
+            help: None,
+        }
+    }
+
+    fn range(mut self, min: u64, max: Option<u64>) -> Self {
+        self.min = Some(min);
+        self.max = max;
+        self
+    }
+
+    fn options(mut self, options: &'static [&'static str]) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn nullable(mut self) -> Self {
+        self.nullable = true;
+        self
+    }
+
+    fn requires_restart(mut self) -> Self {
+        self.requires_restart = true;
+        self
+    }
+
+    fn help(mut self, help: &'static str) -> Self {
+        self.help = Some(help);
+        self
+    }
+
+    /// Checks a candidate value against this field's type and constraints.
+    pub fn check(&self, value: &Value) -> Result<()> {
+        if value.is_null() {
+            if self.nullable {
+                return Ok(());
+            }
+            return Err(Error::Validation(format!("{} cannot be null", self.path)));
+        }
+
+        match self.field_type {
+            FieldType::Bool if !value.is_boolean() => Err(Error::Validation(format!(
+                "{} must be a boolean",
+                self.path
+            ))),
+            FieldType::String if !value.is_string() => {
+                Err(Error::Validation(format!("{} must be a string", self.path)))
+            }
+            FieldType::Enum => match value.as_str() {
+                Some(s) if self.options.contains(&s) => Ok(()),
+                _ => Err(Error::Validation(format!(
+                    "{} must be one of: {}",
+                    self.path,
+                    self.options.join(", ")
+                ))),
+            },
+            FieldType::StringList => match value.as_array() {
+                Some(items) if items.iter().all(|v| v.is_string()) => Ok(()),
+                _ => Err(Error::Validation(format!(
+                    "{} must be a list of strings",
+                    self.path
+                ))),
+            },
+            FieldType::IntegerList => match value.as_array() {
+                Some(items) => items.iter().try_for_each(|item| self.check_integer(item)),
+                None => Err(Error::Validation(format!(
+                    "{} must be a list of integers",
+                    self.path
+                ))),
+            },
+            FieldType::Integer | FieldType::Duration => self.check_integer(value),
+            _ => Ok(()),
+        }
+    }
+
+    /// Moves integers below `min` or above `max` to that bound, saying
+    /// what was changed. Values of the wrong type are left to `check`.
+    fn clamp(&self, value: &mut Value) -> Option<String> {
+        let clamp = |n: &Value| {
+            let n = n.as_i64().or_else(|| n.as_u64().map(|_| i64::MAX))?;
+            let bounded = match (self.min, self.max) {
+                (Some(min), _) if n < min as i64 => min,
+                (_, Some(max)) if n > max as i64 => max,
+                _ if n < 0 => 0,
+                _ => return None,
+            };
+            Some(Value::from(bounded))
+        };
+
+        let before = value.clone();
+        match (self.field_type, &mut *value) {
+            (FieldType::Integer | FieldType::Duration, n) => *n = clamp(n)?,
+            (FieldType::IntegerList, Value::Array(items)) => {
+                let mut changed = false;
+                for item in items.iter_mut() {
+                    if let Some(bounded) = clamp(item) {
+                        *item = bounded;
+                        changed = true;
+                    }
+                }
+                if !changed {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+        Some(format!(
+            "{} clamped from {} to {}",
+            self.path, before, value
+        ))
+    }
+
+    fn check_integer(&self, value: &Value) -> Result<()> {
+        let Some(n) = value.as_u64() else {
+            return Err(Error::Validation(format!(
+                "{} must be a non-negative integer",
+                self.path
+            )));
+        };
+        if let Some(min) = self.min
+            && n < min
+        {
+            return Err(Error::Validation(format!(
+                "{} must be at least {}",
+                self.path, min
+            )));
+        }
+        if let Some(max) = self.max
+            && n > max
+        {
+            return Err(Error::Validation(format!(
+                "{} must be at most {}",
+                self.path, max
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Describes every leaf of the serialized config.
+    pub fn describe() -> Vec<FieldDescriptor> {
+        let defaults = Config::default();
+        let max = Some(MAX_DURATION_SECONDS);
+
+        vec![
+            FieldDescriptor::new(
+                "version",
+                FieldType::String,
+                "Config version",
+                json!(defaults.version),
+            ),
+            FieldDescriptor::new(
+                "default_profile",
+                FieldType::String,
+                "Default profile",
+                json!(defaults.default_profile),
+            )
+            .nullable(),
+            FieldDescriptor::new(
+                "current_user",
+                FieldType::String,
+                "Your name",
+                json!(defaults.current_user),
+            )
+            .nullable(),
+            FieldDescriptor::new(
+                "daemon.socket_path",
+                FieldType::String,
+                "Socket path",
+                json!(defaults.daemon.socket_path),
+            )
+            .requires_restart(),
+            FieldDescriptor::new(
+                "daemon.log_level",
+                FieldType::Enum,
+                "Log level",
+                json!(defaults.daemon.log_level),
+            )
+            .options(LOG_LEVELS)
+            .requires_restart(),
+            FieldDescriptor::new(
+                "daemon.disabled_methods",
+                FieldType::StringList,
+                "Disabled methods",
+                json!(defaults.daemon.disabled_methods),
+            ),
+            FieldDescriptor::new(
+                "daemon.max_timers_per_profile",
+                FieldType::Integer,
+                "Max timers per profile",
+                json!(defaults.daemon.max_timers_per_profile),
+            )
+            .range(1, Some(MAX_TIMERS_PER_PROFILE as u64)),
+            FieldDescriptor::new(
+                "daemon.strict_task_blocking",
+                FieldType::Bool,
+                "Refuse timers on blocked tasks",
+                json!(defaults.daemon.strict_task_blocking),
+            ),
+            FieldDescriptor::new(
+                "daemon.locale",
+                FieldType::Enum,
+                "Language",
+                json!(defaults.daemon.locale),
+            )
+            .options(LOCALES),
+            FieldDescriptor::new(
+                "daemon.usage_metrics",
+                FieldType::Bool,
+                "Record local usage counts",
+                json!(defaults.daemon.usage_metrics),
+            ),
+            FieldDescriptor::new(
+                "daemon.encryption",
+                FieldType::Bool,
+                "Encrypt stored entries",
+                json!(defaults.daemon.encryption),
+            )
+            .requires_restart(),
+            FieldDescriptor::new(
+                "daemon.durability",
+                FieldType::Enum,
+                "Write durability",
+                json!(defaults.daemon.durability),
+            )
+            .options(DURABILITY_LEVELS)
+            .requires_restart()
+            .help(
+                "none: fastest; writes are left to the OS, so a crash or power cut can lose or \
+                 cut short the last writes. normal: fsyncs every entry append and saves whole \
+                 files through a rename, so a crash never leaves a half-written file; a little \
+                 slower on each write. paranoid: normal, plus fsyncing the directory after \
+                 renames and new files so they survive a power cut; slowest.",
+            ),
+            FieldDescriptor::new(
+                "daemon.earliest_entry_date",
+                FieldType::String,
+                "Earliest entry date",
+                json!(defaults.daemon.earliest_entry_date),
+            )
+            .help("Entries starting before this day (YYYY-MM-DD) are rejected."),
+            FieldDescriptor::new(
+                "pomodoro.work_duration",
+                FieldType::Duration,
+                "Work duration",
+                json!(defaults.pomodoro.work_duration),
+            )
+            .range(1, max),
+            FieldDescriptor::new(
+                "pomodoro.short_break",
+                FieldType::Duration,
+                "Short break",
+                json!(defaults.pomodoro.short_break),
+            )
+            .range(1, max),
+            FieldDescriptor::new(
+                "pomodoro.long_break",
+                FieldType::Duration,
+                "Long break",
+                json!(defaults.pomodoro.long_break),
+            )
+            .range(1, max),
+            FieldDescriptor::new(
+                "pomodoro.sessions_until_long_break",
+                FieldType::Integer,
+                "Sessions until long break",
+                json!(defaults.pomodoro.sessions_until_long_break),
+            )
+            .range(1, Some(u32::MAX as u64)),
+            FieldDescriptor::new(
+                "pomodoro.countdown_default",
+                FieldType::Duration,
+                "Default countdown",
+                json!(defaults.pomodoro.countdown_default),
+            )
+            .range(1, None),
+            FieldDescriptor::new(
+                "pomodoro.auto_start_next_session",
+                FieldType::Bool,
+                "Auto-start next session",
+                json!(defaults.pomodoro.auto_start_next_session),
+            ),
+            FieldDescriptor::new(
+                "sync.auto_commit",
+                FieldType::Bool,
+                "Auto-commit",
+                json!(defaults.sync.auto_commit),
+            ),
+            FieldDescriptor::new(
+                "sync.auto_push",
+                FieldType::Bool,
+                "Auto-push",
+                json!(defaults.sync.auto_push),
+            ),
+            FieldDescriptor::new(
+                "sync.remote_url",
+                FieldType::String,
+                "Remote URL",
+                json!(defaults.sync.remote_url),
+            )
+            .nullable(),
+            FieldDescriptor::new(
+                "tui.status_bar_format",
+                FieldType::String,
+                "Status bar format",
+                json!(defaults.tui.status_bar_format),
+            ),
+            FieldDescriptor::new(
+                "tui.priority_colors.low",
+                FieldType::String,
+                "Low priority color",
+                json!(defaults.tui.priority_colors[&TaskPriority::Low]),
+            ),
+            FieldDescriptor::new(
+                "tui.priority_colors.medium",
+                FieldType::String,
+                "Medium priority color",
+                json!(defaults.tui.priority_colors[&TaskPriority::Medium]),
+            ),
+            FieldDescriptor::new(
+                "tui.priority_colors.high",
+                FieldType::String,
+                "High priority color",
+                json!(defaults.tui.priority_colors[&TaskPriority::High]),
+            ),
+            FieldDescriptor::new(
+                "tui.timezone",
+                FieldType::String,
+                "Timezone",
+                json!(defaults.tui.timezone),
+            )
+            .nullable()
+            .requires_restart(),
+            FieldDescriptor::new(
+                "tui.countdown_presets",
+                FieldType::IntegerList,
+                "Countdown presets (minutes)",
+                json!(defaults.tui.countdown_presets),
+            )
+            .range(1, Some(MAX_COUNTDOWN_PRESET_MINUTES)),
+            FieldDescriptor::new(
+                "tui.outside_hours_warn_percent",
+                FieldType::Integer,
+                "Outside hours warning (%)",
+                json!(defaults.tui.outside_hours_warn_percent),
+            )
+            .range(0, Some(100)),
+            FieldDescriptor::new(
+                "tui.pomodoro_animation",
+                FieldType::String,
+                "Pomodoro animation",
+                json!(defaults.tui.pomodoro_animation),
+            ),
+            FieldDescriptor::new(
+                "tui.countdown_animation",
+                FieldType::String,
+                "Countdown animation",
+                json!(defaults.tui.countdown_animation),
+            ),
+            FieldDescriptor::new(
+                "tui.locale",
+                FieldType::Enum,
+                "Notification language",
+                json!(defaults.tui.locale),
+            )
+            .options(LOCALES)
+            .nullable(),
+        ]
+    }
+
+    pub fn field(path: &str) -> Option<FieldDescriptor> {
+        Self::describe().into_iter().find(|f| f.path == path)
+    }
+
+    /// Reads a config exported with `config.export`, e.g. on another
+    /// machine. Keys this version doesn't know are rejected, integers out
+    /// of their field's range are clamped into it, and keys left out take
+    /// their defaults. Also returns a note for every clamped value.
+    pub fn import(value: &Value) -> Result<(Config, Vec<String>)> {
+        let fields = Self::describe();
+        let mut merged = serde_json::to_value(Config::default())?;
+        let mut clamped = Vec::new();
+        import_object(value, "", &fields, &mut merged, &mut clamped)?;
+
+        let config: Config = serde_json::from_value(merged)?;
+        config.validate()?;
+        Ok((config, clamped))
+    }
+}
+
+/// Maps imported as a whole and left to `Config::validate`. Priority
+/// colors may be RGB objects, which `describe` can't express.
+const FREE_FORM_KEYS: &[&str] = &["tui.keybindings", "tui.priority_colors"];
+
+/// Copies the keys of `value`, an object at `prefix`, into `target`.
+fn import_object(
+    value: &Value,
+    prefix: &str,
+    fields: &[FieldDescriptor],
+    target: &mut Value,
+    clamped: &mut Vec<String>,
+) -> Result<()> {
+    let Some(object) = value.as_object() else {
+        return Err(Error::Validation(format!(
+            "{} must be an object",
+            if prefix.is_empty() { "Config" } else { prefix }
+        )));
+    };
+
+    for (key, value) in object {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        let slot = &mut target[key.as_str()];
+
+        if let Some(field) = fields.iter().find(|f| f.path == path) {
+            let mut value = value.clone();
+            if let Some(note) = field.clamp(&mut value) {
+                clamped.push(note);
+            }
+            field.check(&value)?;
+            *slot = value;
+        } else if FREE_FORM_KEYS.contains(&path.as_str()) {
+            *slot = value.clone();
+        } else if fields.iter().any(|f| {
+            f.path
+                .strip_prefix(path.as_str())
+                .is_some_and(|rest| rest.starts_with('.'))
+        }) {
+            import_object(value, &path, fields, slot, clamped)?;
+        } else {
+            return Err(Error::Validation(format!("Unknown config key '{}'", path)));
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,6 +1084,18 @@ mod tests {
         config.socket_path = "/tmp/test.sock".to_string();
         config.log_level = "invalid".to_string();
         assert!(config.validate().is_err());
+
+        config.log_level = "info".to_string();
+        config.max_timers_per_profile = 0;
+        assert!(config.validate().is_err());
+        config.max_timers_per_profile = MAX_TIMERS_PER_PROFILE + 1;
+        assert!(config.validate().is_err());
+
+        config.max_timers_per_profile = 3;
+        config.locale = "de".to_string();
+        assert!(config.validate().is_ok());
+        config.locale = "klingon".to_string();
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -251,4 +1134,201 @@ mod tests {
         config_with_push.remote_url = Some("git@github.com:user/repo.git".to_string());
         assert!(config_with_push.validate().is_ok());
     }
+
+    #[test]
+    fn test_tui_config_validation() {
+        assert!(TuiConfig::default().validate().is_ok());
+
+        let mut config = TuiConfig {
+            status_bar_format: "{timer_state} {timer_elapsed} – {timer_task}".to_string(),
+            ..TuiConfig::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.status_bar_format = "{timer} {view}".to_string();
+        assert!(config.validate().is_err());
+
+        config.status_bar_format = "   ".to_string();
+        assert!(config.validate().is_err());
+
+        config = TuiConfig {
+            countdown_animation: "../cow".to_string(),
+            ..TuiConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_priority_colors_fall_back_to_defaults() {
+        let config: TuiConfig = serde_json::from_value(json!({
+            "priority_colors": {
+                "high": { "r": 255, "g": 136, "b": 0 },
+                "medium": "Light-Magenta",
+                "low": "chartreuse"
+            }
+        }))
+        .unwrap();
+
+        let (colors, warnings) = config.resolved_priority_colors();
+        assert_eq!(
+            colors[&TaskPriority::High],
+            TuiColor::Rgb {
+                r: 255,
+                g: 136,
+                b: 0
+            }
+        );
+        assert_eq!(
+            colors[&TaskPriority::Medium],
+            TuiColor::named("Light-Magenta")
+        );
+        assert_eq!(colors[&TaskPriority::Low], TuiColor::named("blue"));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("chartreuse"));
+    }
+
+    fn collect_leaves(prefix: &str, value: &Value, out: &mut Vec<(String, Value)>) {
+        match value {
+            Value::Object(map) => {
+                for (k, v) in map {
+                    let path = if prefix.is_empty() {
+                        k.clone()
+                    } else {
+                        format!("{}.{}", prefix, k)
+                    };
+                    collect_leaves(&path, v, out);
+                }
+            }
+            _ => out.push((prefix.to_string(), value.clone())),
+        }
+    }
+
+    #[test]
+    fn test_describe_covers_every_field() {
+        let mut leaves = Vec::new();
+        collect_leaves(
+            "",
+            &serde_json::to_value(Config::default()).unwrap(),
+            &mut leaves,
+        );
+
+        let descriptors = Config::describe();
+        assert_eq!(leaves.len(), descriptors.len());
+
+        for (path, default) in leaves {
+            let field = Config::field(&path)
+                .unwrap_or_else(|| panic!("no descriptor for config field '{}'", path));
+            assert_eq!(field.default, default, "default mismatch for '{}'", path);
+            assert!(
+                field.check(&default).is_ok(),
+                "default rejected for '{}'",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_field_check() {
+        let work = Config::field("pomodoro.work_duration").unwrap();
+        assert!(work.check(&json!(1500)).is_ok());
+        assert!(work.check(&json!(0)).is_err());
+        assert!(work.check(&json!(MAX_DURATION_SECONDS + 1)).is_err());
+        assert!(work.check(&json!("25m")).is_err());
+
+        let level = Config::field("daemon.log_level").unwrap();
+        assert!(level.check(&json!("debug")).is_ok());
+        assert!(level.check(&json!("loud")).is_err());
+
+        let remote = Config::field("sync.remote_url").unwrap();
+        assert!(remote.check(&Value::Null).is_ok());
+        assert!(remote.check(&json!(true)).is_err());
+
+        let presets = Config::field("tui.countdown_presets").unwrap();
+        assert!(presets.check(&json!([15, 30, 52])).is_ok());
+        assert!(presets.check(&json!([0])).is_err());
+        assert!(presets.check(&json!(["15"])).is_err());
+        assert!(presets.check(&json!(15)).is_err());
+    }
+
+    #[test]
+    fn test_disabled_method_globs() {
+        let daemon = DaemonConfig {
+            disabled_methods: vec![
+                "profile.delete".to_string(),
+                "sync.*".to_string(),
+                "*.reset".to_string(),
+            ],
+            ..DaemonConfig::default()
+        };
+
+        assert!(daemon.is_method_disabled("profile.delete"));
+        assert!(daemon.is_method_disabled("sync.sync"));
+        assert!(daemon.is_method_disabled("sync.set_remote"));
+        assert!(daemon.is_method_disabled("config.reset"));
+        assert!(!daemon.is_method_disabled("profile.create"));
+        assert!(!daemon.is_method_disabled("config.reset_all"));
+        assert!(!daemon.is_method_disabled("async.sync"));
+
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("task.*_update", "task.bulk_status_update"));
+        assert!(!glob_match("a*a", "a"));
+
+        let field = Config::field("daemon.disabled_methods").unwrap();
+        assert!(field.check(&json!(["sync.*"])).is_ok());
+        assert!(field.check(&json!("sync.*")).is_err());
+    }
+
+    #[test]
+    fn test_import_round_trips_and_clamps() {
+        let mut config = Config::default();
+        config.pomodoro.work_duration = 1500;
+        config
+            .tui
+            .keybindings
+            .insert("timer.stop".into(), "s".into());
+        config
+            .tui
+            .priority_colors
+            .insert(TaskPriority::High, TuiColor::Rgb { r: 255, g: 0, b: 0 });
+        let exported = serde_json::to_value(&config).unwrap();
+        assert_eq!(Config::import(&exported).unwrap(), (config, vec![]));
+
+        let (imported, clamped) = Config::import(&json!({
+            "pomodoro": { "work_duration": 9000, "short_break": 0 },
+            "tui": { "countdown_presets": [5, 500] },
+        }))
+        .unwrap();
+        assert_eq!(imported.pomodoro.work_duration, MAX_DURATION_SECONDS);
+        assert_eq!(imported.pomodoro.short_break, 1);
+        assert_eq!(imported.tui.countdown_presets, [5, 180]);
+        assert_eq!(
+            imported.pomodoro.long_break,
+            PomodoroConfig::default().long_break
+        );
+        assert_eq!(
+            clamped,
+            [
+                "pomodoro.short_break clamped from 0 to 1",
+                "pomodoro.work_duration clamped from 9000 to 7200",
+                "tui.countdown_presets clamped from [5,500] to [5,180]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_rejects_unknown_keys_and_bad_values() {
+        let err = |value: Value| Config::import(&value).unwrap_err().to_string();
+        assert!(
+            err(json!({ "pomodoro": { "work_time": 1500 } }))
+                .contains("Unknown config key 'pomodoro.work_time'")
+        );
+        assert!(err(json!({ "theme": "dark" })).contains("Unknown config key 'theme'"));
+        assert!(err(json!({ "sync": true })).contains("sync must be an object"));
+        assert!(err(json!({ "daemon": { "log_level": "loud" } })).contains("daemon.log_level"));
+        assert!(
+            err(json!({ "pomodoro": { "work_duration": "long" } }))
+                .contains("non-negative integer")
+        );
+        assert!(err(json!([])).contains("Config must be an object"));
+    }
 }
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The machine a daemon runs on. Every entry it creates is stamped with
+/// it, so data merged from several machines can be told apart. Kept in
+/// the config directory, which is not synced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Device {
+    pub id: String,
+    pub name: String,
+}
+
+impl Device {
+    /// A new identity, named after the host if the environment says what
+    /// it is called.
+    pub fn new() -> Self {
+        let name = ["HOSTNAME", "COMPUTERNAME"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok())
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| "unnamed device".to_string());
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+        }
+    }
+}
+
+impl Default for Device {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,9 +1,18 @@
-use crate::{Error, Result};
-use chrono::{DateTime, Utc};
+use crate::{Error, Result, models::Device};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use uuid::Uuid;
 
+/// Longest duration a single entry may have: one day.
+pub const MAX_ENTRY_SECONDS: u64 = 86_400;
+
+/// How far past the daemon's clock an entry may end, to allow for entries
+/// made on a device whose clock runs a little fast.
+pub const MAX_CLOCK_SKEW_SECONDS: i64 = 120;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(from = "EntryRecord")]
 pub struct Entry {
     pub id: String,
     pub task_id: Option<String>,
@@ -14,6 +23,19 @@ pub struct Entry {
     pub mode: TimerMode,
     pub description: Option<String>,
     pub tags: Vec<String>,
+    pub source: EntrySource,
+    /// How a pomodoro timer's work phases went. `None` for other modes.
+    pub pomodoro: Option<PomodoroOutcome>,
+    /// The `Device` whose daemon created the entry. `None` for entries
+    /// made before devices were recorded.
+    pub device_id: Option<String>,
+    pub device_name: Option<String>,
+    /// The wall clock jumped while the timer ran, so the duration was
+    /// measured on the monotonic clock and `end_time` derived from it.
+    pub clock_anomaly: bool,
+    /// Time the timer ran past `MAX_ENTRY_SECONDS`, cut from the end of
+    /// the entry so it could be saved. 0 for entries that were not cut.
+    pub truncated_seconds: u64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -24,6 +46,110 @@ pub enum TimerMode {
     Countdown,
 }
 
+impl fmt::Display for TimerMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TimerMode::Manual => "manual",
+            TimerMode::Pomodoro => "pomodoro",
+            TimerMode::Countdown => "countdown",
+        })
+    }
+}
+
+/// Where an entry came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EntrySource {
+    /// Recorded by a running timer.
+    Timer { timer_mode: TimerMode },
+    /// Entered by hand through `entry.create`.
+    Manual,
+    /// Brought in from another tool; `origin` names it, e.g. `toggl`.
+    Import { origin: String },
+    /// Created from a calendar event.
+    Calendar,
+}
+
+impl EntrySource {
+    pub const KINDS: &'static [&'static str] = &["timer", "manual", "import", "calendar"];
+
+    /// The variant name, as used by `entry.filter` and the stats breakdown.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EntrySource::Timer { .. } => "timer",
+            EntrySource::Manual => "manual",
+            EntrySource::Import { .. } => "import",
+            EntrySource::Calendar => "calendar",
+        }
+    }
+
+    pub fn is_timer(&self) -> bool {
+        matches!(self, EntrySource::Timer { .. })
+    }
+}
+
+/// What happened to the work phases of a stopped pomodoro timer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PomodoroOutcome {
+    /// Work phases that ran to the end.
+    pub completed_sessions: u32,
+    /// `false` if the timer was stopped partway through a work phase.
+    pub completed: bool,
+    /// How far into the abandoned work phase the timer was stopped.
+    pub interrupted_at_seconds: Option<u64>,
+}
+
+/// `Entry` as stored before `source` existed; such entries were all
+/// recorded by timers.
+#[derive(Deserialize)]
+struct EntryRecord {
+    id: String,
+    task_id: Option<String>,
+    task_title: Option<String>,
+    start_time: DateTime<Utc>,
+    end_time: Option<DateTime<Utc>>,
+    duration_seconds: u64,
+    mode: TimerMode,
+    description: Option<String>,
+    tags: Vec<String>,
+    #[serde(default)]
+    source: Option<EntrySource>,
+    #[serde(default)]
+    pomodoro: Option<PomodoroOutcome>,
+    #[serde(default)]
+    device_id: Option<String>,
+    #[serde(default)]
+    device_name: Option<String>,
+    #[serde(default)]
+    clock_anomaly: bool,
+    #[serde(default)]
+    truncated_seconds: u64,
+}
+
+impl From<EntryRecord> for Entry {
+    fn from(record: EntryRecord) -> Self {
+        Self {
+            source: record.source.unwrap_or(EntrySource::Timer {
+                timer_mode: record.mode,
+            }),
+            id: record.id,
+            task_id: record.task_id,
+            task_title: record.task_title,
+            start_time: record.start_time,
+            end_time: record.end_time,
+            duration_seconds: record.duration_seconds,
+            mode: record.mode,
+            description: record.description,
+            tags: record.tags,
+            pomodoro: record.pomodoro,
+            device_id: record.device_id,
+            device_name: record.device_name,
+            clock_anomaly: record.clock_anomaly,
+            truncated_seconds: record.truncated_seconds,
+        }
+    }
+}
+
 impl Entry {
     pub fn new(task_id: Option<String>, task_title: Option<String>, mode: TimerMode) -> Self {
         Self {
@@ -36,6 +162,12 @@ impl Entry {
             mode,
             description: None,
             tags: Vec::new(),
+            source: EntrySource::Timer { timer_mode: mode },
+            pomodoro: None,
+            device_id: None,
+            device_name: None,
+            clock_anomaly: false,
+            truncated_seconds: 0,
         }
     }
 
@@ -67,9 +199,24 @@ impl Entry {
             mode,
             description: None,
             tags: Vec::new(),
+            source: EntrySource::Timer { timer_mode: mode },
+            pomodoro: None,
+            device_id: None,
+            device_name: None,
+            clock_anomaly: false,
+            truncated_seconds: 0,
         })
     }
 
+    /// Records `device` as where the entry was made, unless it already
+    /// names one.
+    pub fn stamp_device(&mut self, device: &Device) {
+        if self.device_id.is_none() {
+            self.device_id = Some(device.id.clone());
+            self.device_name = Some(device.name.clone());
+        }
+    }
+
     pub fn finish(&mut self) {
         let end_time = Utc::now();
         let duration = end_time
@@ -98,15 +245,107 @@ impl Entry {
         Ok(())
     }
 
+    /// Cuts a finished entry off `seconds` after it started and returns the
+    /// rest as a new entry for the same task, or `None` if it is no longer
+    /// than that.
+    pub fn split_at(&mut self, seconds: u64) -> Option<Entry> {
+        let end_time = self.end_time?;
+        if seconds == 0 || self.duration_seconds <= seconds {
+            return None;
+        }
+        let split_time = self.start_time + chrono::Duration::seconds(seconds as i64);
+        if split_time >= end_time {
+            return None;
+        }
+
+        let mut rest = self.clone();
+        rest.id = Uuid::new_v4().to_string();
+        rest.start_time = split_time;
+        rest.duration_seconds = self.duration_seconds - seconds;
+        rest.pomodoro = None;
+
+        self.end_time = Some(split_time);
+        self.duration_seconds = seconds;
+        Some(rest)
+    }
+
+    /// Checks that this is a finished entry that could really have
+    /// happened: it ends after it starts, lasts between one second and
+    /// `MAX_ENTRY_SECONDS`, and did not start in the future.
     pub fn validate(&self) -> Result<()> {
-        if let Some(end_time) = self.end_time
-            && end_time <= self.start_time
-        {
+        let Some(end_time) = self.end_time else {
+            return Err(Error::Validation("Entry has no end time".to_string()));
+        };
+
+        if end_time <= self.start_time {
             return Err(Error::Validation(
                 "End time must be after start time".to_string(),
             ));
         }
 
+        if self.duration_seconds == 0 {
+            return Err(Error::Validation(
+                "Duration must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.duration_seconds > MAX_ENTRY_SECONDS {
+            return Err(Error::Validation(format!(
+                "Duration too long (max {} seconds)",
+                MAX_ENTRY_SECONDS
+            )));
+        }
+
+        if self.start_time > Utc::now() {
+            return Err(Error::Validation(
+                "Start time cannot be in the future".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks the entry's timestamps against the clock: it must not end
+    /// more than `MAX_CLOCK_SKEW_SECONDS` after `now` or start before
+    /// `earliest`, and its duration must match its span to within a
+    /// second. Timer entries may be shorter than their span, since time
+    /// spent paused is not counted.
+    pub fn check_timestamps(&self, now: DateTime<Utc>, earliest: NaiveDate) -> Result<()> {
+        if let Some(end_time) = self.end_time
+            && end_time > now + chrono::Duration::seconds(MAX_CLOCK_SKEW_SECONDS)
+        {
+            return Err(Error::Validation(format!(
+                "end_time is {}s in the future; at most {}s of clock skew is allowed",
+                (end_time - now).num_seconds(),
+                MAX_CLOCK_SKEW_SECONDS
+            )));
+        }
+
+        if self.start_time.date_naive() < earliest {
+            return Err(Error::Validation(format!(
+                "start_time {} is before the earliest allowed date {}",
+                self.start_time.format("%Y-%m-%d"),
+                earliest
+            )));
+        }
+
+        if let Some(end_time) = self.end_time {
+            let span = (end_time - self.start_time).num_seconds();
+            let duration = self.duration_seconds as i64;
+            if duration > span + 1 {
+                return Err(Error::Validation(format!(
+                    "duration_seconds {} is longer than the {}s between start_time and end_time",
+                    duration, span
+                )));
+            }
+            if duration < span - 1 && !self.source.is_timer() {
+                return Err(Error::Validation(format!(
+                    "duration_seconds {} is shorter than the {}s between start_time and end_time",
+                    duration, span
+                )));
+            }
+        }
+
         Ok(())
     }
 
@@ -197,6 +436,30 @@ mod tests {
         assert!(!entry.is_completed());
     }
 
+    #[test]
+    fn test_split_at() {
+        let start = Utc::now() - ChronoDuration::hours(3);
+        let mut entry = Entry::create_completed(
+            Some("standup".to_string()),
+            None,
+            start,
+            start + ChronoDuration::hours(3),
+            TimerMode::Manual,
+        )
+        .unwrap();
+
+        let rest = entry.split_at(1800).unwrap();
+        assert_eq!(entry.duration_seconds, 1800);
+        assert_eq!(entry.end_time, Some(start + ChronoDuration::minutes(30)));
+        assert_eq!(rest.start_time, start + ChronoDuration::minutes(30));
+        assert_eq!(rest.end_time, Some(start + ChronoDuration::hours(3)));
+        assert_eq!(rest.duration_seconds, 9000);
+        assert_eq!(rest.task_id.as_deref(), Some("standup"));
+        assert_ne!(rest.id, entry.id);
+
+        assert!(entry.split_at(1800).is_none());
+    }
+
     #[test]
     fn test_finish_entry() {
         let mut entry = Entry::new(None, None, TimerMode::Pomodoro);
@@ -237,6 +500,132 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_validate_rejects_impossible_entries() {
+        let end = Utc::now();
+        let valid = Entry::create_completed(
+            None,
+            None,
+            end - ChronoDuration::hours(1),
+            end,
+            TimerMode::Manual,
+        )
+        .unwrap();
+        assert!(valid.validate().is_ok());
+
+        assert!(
+            Entry::new(None, None, TimerMode::Manual)
+                .validate()
+                .is_err()
+        );
+
+        let mut zero = valid.clone();
+        zero.duration_seconds = 0;
+        assert!(zero.validate().is_err());
+
+        let mut same_time = valid.clone();
+        same_time.end_time = Some(same_time.start_time);
+        assert!(same_time.validate().is_err());
+
+        let mut too_long = valid.clone();
+        too_long.start_time = end - ChronoDuration::hours(25);
+        too_long.duration_seconds = 25 * 3600;
+        assert!(too_long.validate().is_err());
+
+        let mut future = valid;
+        future.start_time = end + ChronoDuration::hours(1);
+        future.end_time = Some(end + ChronoDuration::hours(2));
+        assert!(future.validate().is_err());
+    }
+
+    #[test]
+    fn test_check_timestamps() {
+        let now = Utc::now();
+        let earliest = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let ending_at = |end: DateTime<Utc>| {
+            let mut entry = Entry::create_completed(
+                None,
+                None,
+                end - ChronoDuration::minutes(30),
+                end,
+                TimerMode::Manual,
+            )
+            .unwrap();
+            entry.source = EntrySource::Manual;
+            entry
+        };
+
+        let skew = ChronoDuration::seconds(MAX_CLOCK_SKEW_SECONDS);
+        assert!(
+            ending_at(now + skew)
+                .check_timestamps(now, earliest)
+                .is_ok()
+        );
+        let err = ending_at(now + skew + ChronoDuration::seconds(1))
+            .check_timestamps(now, earliest)
+            .unwrap_err();
+        assert!(err.to_string().contains("end_time"));
+
+        let mut old = ending_at(now);
+        old.start_time = DateTime::parse_from_rfc3339("1999-12-31T23:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        old.end_time = Some(old.start_time + ChronoDuration::minutes(30));
+        let err = old.check_timestamps(now, earliest).unwrap_err();
+        assert!(err.to_string().contains("start_time"));
+
+        let mut off_by_one = ending_at(now);
+        off_by_one.duration_seconds += 1;
+        assert!(off_by_one.check_timestamps(now, earliest).is_ok());
+
+        let mut padded = ending_at(now);
+        padded.duration_seconds += 2;
+        let err = padded.check_timestamps(now, earliest).unwrap_err();
+        assert!(err.to_string().contains("duration_seconds"));
+
+        let mut paused = ending_at(now);
+        paused.duration_seconds -= 600;
+        assert!(paused.check_timestamps(now, earliest).is_err());
+        paused.source = EntrySource::Timer {
+            timer_mode: TimerMode::Manual,
+        };
+        assert!(paused.check_timestamps(now, earliest).is_ok());
+    }
+
+    #[test]
+    fn test_source_defaults_to_timer_for_old_entries() {
+        let json = serde_json::json!({
+            "id": "e1",
+            "task_id": null,
+            "task_title": null,
+            "start_time": "2024-01-01T09:00:00Z",
+            "end_time": "2024-01-01T09:25:00Z",
+            "duration_seconds": 1500,
+            "mode": "pomodoro",
+            "description": null,
+            "tags": [],
+        });
+        let entry: Entry = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            entry.source,
+            EntrySource::Timer {
+                timer_mode: TimerMode::Pomodoro
+            }
+        );
+
+        let mut imported = entry.clone();
+        imported.source = EntrySource::Import {
+            origin: "toggl".to_string(),
+        };
+        let value = serde_json::to_value(&imported).unwrap();
+        assert_eq!(
+            value["source"],
+            serde_json::json!({ "type": "import", "origin": "toggl" })
+        );
+        let back: Entry = serde_json::from_value(value).unwrap();
+        assert_eq!(back, imported);
+    }
+
     #[test]
     fn test_duration_formatting() {
         let start = Utc::now();
@@ -1,11 +1,22 @@
+pub mod budget;
 pub mod config;
+pub mod device;
 pub mod entry;
 pub mod profile;
 pub mod task;
 pub mod timer;
+pub mod working_hours;
 
-pub use config::{Config, DaemonConfig, PomodoroConfig, SyncConfig};
-pub use entry::{Entry, TimerMode};
-pub use profile::Profile;
-pub use task::{Task, TaskSource, TaskStatus};
-pub use timer::{ActiveTimer, PomodoroPhase, TimerState};
+pub use budget::{Budget, BudgetAction, BudgetPeriod, BudgetScope};
+pub use config::{
+    Config, DaemonConfig, FieldDescriptor, FieldType, PomodoroConfig, SyncConfig, TuiConfig,
+};
+pub use device::Device;
+pub use entry::{Entry, EntrySource, PomodoroOutcome, TimerMode};
+pub use profile::{Profile, default_color_and_icon};
+pub use task::{Task, TaskPriority, TaskSource, TaskStatus};
+pub use timer::{
+    ActiveTimer, BREAK_TAKEN_SHARE, BreakRecord, BreakStatus, DEFAULT_SUMMARY_FORMAT,
+    PomodoroPhase, SUMMARY_VARIABLES, TimerDisplay, TimerState,
+};
+pub use working_hours::{HoursSplit, WorkDay, WorkingHours};
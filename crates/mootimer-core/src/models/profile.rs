@@ -1,13 +1,46 @@
+use crate::models::config::NAMED_COLORS;
+use crate::models::working_hours::WorkingHours;
 use crate::{Error, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Colors handed out to profiles that don't pick one, chosen to stay
+/// readable on dark and light terminals.
+pub const PROFILE_COLORS: &[&str] = &[
+    "cyan",
+    "magenta",
+    "yellow",
+    "green",
+    "blue",
+    "red",
+    "light_cyan",
+    "light_magenta",
+    "light_yellow",
+    "light_green",
+    "light_blue",
+    "light_red",
+];
+
+/// Icons handed out to profiles that don't pick one.
+pub const PROFILE_ICONS: &[&str] = &["🐮", "💼", "🏠", "🚀", "📚", "🎯", "🌱", "🔧", "🎨", "🧪"];
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Profile {
     pub id: String,
     pub name: String,
     pub description: Option<String>,
+    /// A name from `NAMED_COLORS` or a hex color like `#FF5733`.
     pub color: Option<String>,
+    /// A single emoji or character shown next to the name.
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Used to tell time logged in the evening or at the weekend apart.
+    #[serde(default)]
+    pub working_hours: WorkingHours,
+    /// Overrides `pomodoro.auto_start_next_session` for pomodoros started
+    /// in this profile.
+    #[serde(default)]
+    pub auto_start_next_session: Option<bool>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -20,11 +53,23 @@ impl Profile {
             name,
             description: None,
             color: None,
+            icon: None,
+            working_hours: WorkingHours::default(),
+            auto_start_next_session: None,
             created_at: now,
             updated_at: now,
         };
         profile.validate()?;
-        Ok(profile)
+        Ok(profile.with_defaults())
+    }
+
+    /// Fills an unset color and icon with ones picked from the id, so a
+    /// profile looks the same in every client without anyone choosing.
+    pub fn with_defaults(mut self) -> Self {
+        let (color, icon) = default_color_and_icon(&self.id);
+        self.color.get_or_insert_with(|| color.to_string());
+        self.icon.get_or_insert_with(|| icon.to_string());
+        self
     }
 
     pub fn validate(&self) -> Result<()> {
@@ -49,12 +94,11 @@ impl Profile {
             ));
         }
 
-        if let Some(ref color) = self.color
-            && (!color.starts_with('#') || !(color.len() == 7 || color.len() == 4))
-        {
-            return Err(Error::Validation(
-                "Color must be a valid hex color (e.g., #FF5733 or #F73)".to_string(),
-            ));
+        if let Some(ref color) = self.color {
+            validate_color(color)?;
+        }
+        if let Some(ref icon) = self.icon {
+            validate_icon(icon)?;
         }
 
         Ok(())
@@ -77,23 +121,81 @@ impl Profile {
     }
 
     pub fn update_color(&mut self, color: Option<String>) -> Result<()> {
-        if let Some(ref c) = color
-            && (!c.starts_with('#') || !(c.len() == 7 || c.len() == 4))
-        {
-            return Err(Error::Validation(
-                "Color must be a valid hex color (e.g., #FF5733 or #F73)".to_string(),
-            ));
+        if let Some(ref c) = color {
+            validate_color(c)?;
         }
         self.color = color;
         self.updated_at = Utc::now();
         Ok(())
     }
 
+    pub fn update_icon(&mut self, icon: Option<String>) -> Result<()> {
+        if let Some(ref i) = icon {
+            validate_icon(i)?;
+        }
+        self.icon = icon;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
     pub fn touch(&mut self) {
         self.updated_at = Utc::now();
     }
 }
 
+/// The color and icon a profile with `id` gets when it has none set.
+pub fn default_color_and_icon(id: &str) -> (&'static str, &'static str) {
+    let hash = id_hash(id);
+    // Different halves of the hash, so color and icon vary independently.
+    (
+        PROFILE_COLORS[hash as usize % PROFILE_COLORS.len()],
+        PROFILE_ICONS[(hash >> 32) as usize % PROFILE_ICONS.len()],
+    )
+}
+
+fn validate_color(color: &str) -> Result<()> {
+    let hex = color.strip_prefix('#').is_some_and(|digits| {
+        matches!(digits.len(), 3 | 6) && digits.chars().all(|c| c.is_ascii_hexdigit())
+    });
+    if hex || NAMED_COLORS.contains(&color) {
+        return Ok(());
+    }
+    Err(Error::Validation(format!(
+        "Color must be a hex color (e.g., #FF5733 or #F73) or one of: {}",
+        NAMED_COLORS.join(", ")
+    )))
+}
+
+/// One visible character: a base character, optionally followed by
+/// variation selectors, skin tones or zero-width-joined parts, as in 👩‍💻.
+fn validate_icon(icon: &str) -> Result<()> {
+    let mut chars = icon.chars();
+    let base_ok = chars
+        .next()
+        .is_some_and(|c| !c.is_whitespace() && !c.is_control());
+    let mut joined = false;
+    let rest_ok = chars.all(|c| {
+        let ok =
+            joined || matches!(c, '\u{200D}' | '\u{FE00}'..='\u{FE0F}' | '\u{1F3FB}'..='\u{1F3FF}');
+        joined = c == '\u{200D}';
+        ok
+    });
+    if base_ok && rest_ok && icon.chars().count() <= 8 {
+        return Ok(());
+    }
+    Err(Error::Validation(
+        "Icon must be a single emoji or character".to_string(),
+    ))
+}
+
+/// FNV-1a, which unlike `DefaultHasher` gives the same value in every
+/// build, so default colors don't change between versions.
+fn id_hash(id: &str) -> u64 {
+    id.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,7 +206,37 @@ mod tests {
         assert_eq!(profile.id, "test_profile");
         assert_eq!(profile.name, "Test Profile");
         assert!(profile.description.is_none());
-        assert!(profile.color.is_none());
+        assert!(profile.color.is_some() && profile.icon.is_some());
+    }
+
+    #[test]
+    fn test_default_color_and_icon_follow_id() {
+        let a = Profile::new("work".to_string(), "Work".to_string()).unwrap();
+        let b = Profile::new("work".to_string(), "Other".to_string()).unwrap();
+        assert_eq!((&a.color, &a.icon), (&b.color, &b.icon));
+        assert!(PROFILE_COLORS.contains(&a.color.as_deref().unwrap()));
+
+        let mut chosen = a.clone();
+        chosen.color = Some("#123456".to_string());
+        chosen.icon = None;
+        let filled = chosen.with_defaults();
+        assert_eq!(filled.color.as_deref(), Some("#123456"));
+        assert_eq!(filled.icon, a.icon);
+    }
+
+    #[test]
+    fn test_profile_update_icon() {
+        let mut profile = Profile::new("test".to_string(), "Test".to_string()).unwrap();
+        for icon in ["🚀", "⏱️", "👩‍💻", "W"] {
+            profile.update_icon(Some(icon.to_string())).unwrap();
+        }
+        for icon in ["", "ab", "🚀🚀", " "] {
+            assert!(
+                profile.update_icon(Some(icon.to_string())).is_err(),
+                "{}",
+                icon
+            );
+        }
     }
 
     #[test]
@@ -1,5 +1,5 @@
 use crate::{Error, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -8,11 +8,41 @@ pub struct Task {
     pub id: String,
     pub title: String,
     pub description: Option<String>,
+    /// Free-form working notes, kept apart from the description.
+    #[serde(default)]
+    pub notes: Option<String>,
     pub source: TaskSource,
     pub source_id: Option<String>,
     pub url: Option<String>,
     pub status: TaskStatus,
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub priority: TaskPriority,
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+    /// The task this one is a subtask of. Subtasks have no subtasks of
+    /// their own.
+    #[serde(default)]
+    pub parent_task_id: Option<String>,
+    /// Pinned tasks are listed first in the TUI.
+    #[serde(default)]
+    pub pinned: bool,
+    /// End of the most recent entry logged against this task; maintained
+    /// by the daemon.
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Timer sessions on this task are cut off at this length when they
+    /// stop; the daemon asks what to do with the rest.
+    #[serde(default)]
+    pub max_session_seconds: Option<u64>,
+    /// Tasks in the same profile that must be finished before this one can
+    /// start. Ids are removed as those tasks are completed.
+    #[serde(default)]
+    pub blocked_by: Vec<String>,
+    /// Position within its status column, lowest first; set by
+    /// `task.reorder`. Ties fall back to creation time.
+    #[serde(default)]
+    pub order: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -23,11 +53,29 @@ pub enum TaskSource {
     Manual,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPriority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+}
+
+/// Where a task is in its life. Always written in snake_case; the legacy
+/// `"completed"` still reads as `Done` and is rewritten on the next save.
+/// Ordered as the columns of a board, `Archived` last.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum TaskStatus {
+    #[default]
     Todo,
     InProgress,
+    #[serde(alias = "completed")]
     Done,
     Archived,
 }
@@ -39,11 +87,20 @@ impl Task {
             id: Uuid::new_v4().to_string(),
             title,
             description: None,
+            notes: None,
             source: TaskSource::Manual,
             source_id: None,
             url: None,
             status: TaskStatus::Todo,
             tags: Vec::new(),
+            priority: TaskPriority::None,
+            due_date: None,
+            parent_task_id: None,
+            pinned: false,
+            last_used_at: None,
+            max_session_seconds: None,
+            blocked_by: Vec::new(),
+            order: 0,
             created_at: now,
             updated_at: now,
         };
@@ -51,6 +108,14 @@ impl Task {
         Ok(task)
     }
 
+    /// Board order: by status, then position in the column, then age.
+    pub fn board_cmp(&self, other: &Task) -> std::cmp::Ordering {
+        self.status
+            .cmp(&other.status)
+            .then(self.order.cmp(&other.order))
+            .then(self.created_at.cmp(&other.created_at))
+    }
+
     pub fn validate(&self) -> Result<()> {
         if self.title.trim().is_empty() {
             return Err(Error::Validation("Task title cannot be empty".to_string()));
@@ -69,6 +134,12 @@ impl Task {
             ));
         }
 
+        if self.blocked_by.contains(&self.id) {
+            return Err(Error::Validation(
+                "A task cannot be blocked by itself".to_string(),
+            ));
+        }
+
         Ok(())
     }
 
@@ -86,6 +157,11 @@ impl Task {
         self.updated_at = Utc::now();
     }
 
+    pub fn update_notes(&mut self, notes: Option<String>) {
+        self.notes = notes;
+        self.updated_at = Utc::now();
+    }
+
     pub fn update_status(&mut self, status: TaskStatus) {
         self.status = status;
         self.updated_at = Utc::now();
@@ -142,12 +218,58 @@ impl Task {
         self.status == TaskStatus::Done
     }
 
+    pub fn is_blocked(&self) -> bool {
+        !self.blocked_by.is_empty()
+    }
+
+    /// Records work on the task at `at`. Older timestamps are ignored, so
+    /// entries may be replayed in any order. Returns whether it changed.
+    pub fn mark_used(&mut self, at: DateTime<Utc>) -> bool {
+        if self.last_used_at.is_some_and(|last| last >= at) {
+            return false;
+        }
+        self.last_used_at = Some(at);
+        true
+    }
+
     pub fn touch(&mut self) {
         self.updated_at = Utc::now();
     }
 }
 
+impl TaskPriority {
+    pub const ALL: [TaskPriority; 4] = [
+        TaskPriority::None,
+        TaskPriority::Low,
+        TaskPriority::Medium,
+        TaskPriority::High,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskPriority::None => "None",
+            TaskPriority::Low => "Low",
+            TaskPriority::Medium => "Medium",
+            TaskPriority::High => "High",
+        }
+    }
+
+    /// The next priority, wrapping from `High` back to `None`.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|p| *p == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(self) -> Self {
+        let index = Self::ALL.iter().position(|p| *p == self).unwrap_or(0);
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
 impl TaskStatus {
+    /// Statuses written by older versions, read as their current form.
+    pub const LEGACY_NAMES: [&'static str; 1] = ["completed"];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             TaskStatus::Todo => "To Do",
@@ -156,6 +278,24 @@ impl TaskStatus {
             TaskStatus::Archived => "Archived",
         }
     }
+
+    /// The name on the wire and in `tasks.json`, e.g. `in_progress`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            TaskStatus::Todo => "todo",
+            TaskStatus::InProgress => "in_progress",
+            TaskStatus::Done => "done",
+            TaskStatus::Archived => "archived",
+        }
+    }
+
+    /// The status in a task's JSON, accepting legacy names. A missing or
+    /// unknown status counts as `Todo`.
+    pub fn of(task: &serde_json::Value) -> Self {
+        task.get("status")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
 }
 
 #[cfg(test)]
@@ -239,4 +379,64 @@ mod tests {
         assert_eq!(TaskStatus::InProgress.as_str(), "In Progress");
         assert_eq!(TaskStatus::Done.as_str(), "Done");
     }
+
+    #[test]
+    fn test_mark_used_only_moves_forward() {
+        let mut task = Task::new("Test".to_string()).unwrap();
+        let now = Utc::now();
+        let earlier = now - chrono::Duration::hours(1);
+
+        assert!(task.mark_used(now));
+        assert!(!task.mark_used(earlier));
+        assert!(!task.mark_used(now));
+        assert_eq!(task.last_used_at, Some(now));
+    }
+
+    #[test]
+    fn test_task_priority_cycle() {
+        assert_eq!(TaskPriority::default(), TaskPriority::None);
+        assert_eq!(TaskPriority::None.next(), TaskPriority::Low);
+        assert_eq!(TaskPriority::High.next(), TaskPriority::None);
+        assert_eq!(TaskPriority::None.previous(), TaskPriority::High);
+    }
+
+    #[test]
+    fn test_legacy_task_defaults_new_fields() {
+        let task = Task::new("Test".to_string()).unwrap();
+        let mut value = serde_json::to_value(&task).unwrap();
+        let obj = value.as_object_mut().unwrap();
+        obj.remove("priority");
+        obj.remove("due_date");
+        obj.remove("pinned");
+        obj.remove("notes");
+        obj.remove("last_used_at");
+
+        let loaded: Task = serde_json::from_value(value).unwrap();
+        assert_eq!(loaded.priority, TaskPriority::None);
+        assert_eq!(loaded.due_date, None);
+        assert!(!loaded.pinned);
+        assert_eq!(loaded.notes, None);
+        assert_eq!(loaded.last_used_at, None);
+
+        let mut task = task;
+        task.priority = TaskPriority::High;
+        task.due_date = NaiveDate::from_ymd_opt(2024, 7, 1);
+        let value = serde_json::to_value(&task).unwrap();
+        assert_eq!(value["priority"], "high");
+        assert_eq!(value["due_date"], "2024-07-01");
+    }
+
+    #[test]
+    fn test_legacy_completed_status_reads_as_done() {
+        let task = Task::new("Test".to_string()).unwrap();
+        let mut value = serde_json::to_value(&task).unwrap();
+        value["status"] = "completed".into();
+
+        let loaded: Task = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(loaded.status, TaskStatus::Done);
+        assert_eq!(serde_json::to_value(&loaded).unwrap()["status"], "done");
+        assert_eq!(TaskStatus::of(&value), TaskStatus::Done);
+        assert_eq!(TaskStatus::of(&serde_json::json!({})), TaskStatus::Todo);
+        assert_eq!(TaskStatus::InProgress.key(), "in_progress");
+    }
 }
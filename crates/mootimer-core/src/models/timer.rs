@@ -1,9 +1,17 @@
 use crate::{Error, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use uuid::Uuid;
 
-use super::{PomodoroConfig, TimerMode};
+use super::{PomodoroConfig, PomodoroOutcome, TimerMode};
+
+/// Placeholders understood by [`ActiveTimer::summary`].
+pub const SUMMARY_VARIABLES: &[&str] = &["icon", "time", "task", "phase", "state"];
+
+/// One line fit for a terminal title or tmux's `status-right`, e.g.
+/// `🍅 17:32 — Fix login bug`.
+pub const DEFAULT_SUMMARY_FORMAT: &str = "{icon} {time} — {task}";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ActiveTimer {
@@ -20,6 +28,11 @@ pub struct ActiveTimer {
     pub accumulated_work_time: u64,
     pub pomodoro_state: Option<PomodoroState>,
     pub target_duration: Option<u64>,
+    /// How far the wall clock has jumped since the timer started, in
+    /// seconds; see [`shift_clock`](Self::shift_clock). Zero unless the
+    /// clock was changed under a running timer.
+    #[serde(default)]
+    pub clock_skew_seconds: i64,
 }
 
 fn generate_timer_id() -> String {
@@ -40,6 +53,10 @@ pub struct PomodoroState {
     pub current_session: u32,
     pub phase: PomodoroPhase,
     pub phase_start_time: DateTime<Utc>,
+    /// Work phases run to the end so far. Unlike `current_session`, this
+    /// does not reset after a long break.
+    #[serde(default)]
+    pub completed_sessions: u32,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -50,6 +67,85 @@ pub enum PomodoroPhase {
     LongBreak,
 }
 
+/// Values derived from a timer for showing it, so clients don't each redo
+/// the phase and countdown math. The daemon attaches one to every timer it
+/// returns as `display`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimerDisplay {
+    /// Time worked: the whole run for manual and countdown timers, the
+    /// finished and current work phases for pomodoro.
+    pub elapsed_seconds: u64,
+    /// Time left in the pomodoro phase or countdown.
+    pub remaining_seconds: Option<u64>,
+    /// Length of the pomodoro phase or countdown.
+    pub total_seconds: Option<u64>,
+    /// Share of `total_seconds` done, from 0 to 1.
+    pub progress_ratio: Option<f64>,
+    /// `Manual`, `Countdown`, or the pomodoro phase and session, e.g.
+    /// `Work (Session 2)`.
+    pub phase_display: String,
+    /// The pomodoro phase that comes next.
+    pub next_phase_display: Option<String>,
+}
+
+impl TimerDisplay {
+    /// This display `seconds` later, for clients that advance it between
+    /// reports. `counts_elapsed` is false while a pomodoro break runs,
+    /// since breaks don't add to the time worked.
+    pub fn advanced(&self, seconds: u64, counts_elapsed: bool) -> TimerDisplay {
+        let remaining_seconds = self.remaining_seconds.map(|r| r.saturating_sub(seconds));
+        TimerDisplay {
+            elapsed_seconds: self.elapsed_seconds + if counts_elapsed { seconds } else { 0 },
+            remaining_seconds,
+            progress_ratio: progress_ratio(remaining_seconds, self.total_seconds),
+            ..self.clone()
+        }
+    }
+}
+
+fn progress_ratio(remaining: Option<u64>, total: Option<u64>) -> Option<f64> {
+    match (remaining, total) {
+        (Some(_), Some(0)) => Some(1.0),
+        (Some(remaining), Some(total)) => {
+            Some(total.saturating_sub(remaining) as f64 / total as f64)
+        }
+        _ => None,
+    }
+}
+
+/// A break counts as taken if it lasted at least this share of its length.
+pub const BREAK_TAKEN_SHARE: f64 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakStatus {
+    /// Ran its full length.
+    Completed,
+    /// Ended early with `timer.skip_phase`.
+    Skipped,
+    /// Ended by stopping or cancelling the timer.
+    Stopped,
+}
+
+/// A pomodoro break that was offered, and how much of it was taken.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakRecord {
+    pub timer_id: String,
+    pub task_id: Option<String>,
+    pub phase: PomodoroPhase,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub planned_seconds: u64,
+    pub taken_seconds: u64,
+    pub status: BreakStatus,
+}
+
+impl BreakRecord {
+    pub fn is_taken(&self) -> bool {
+        self.taken_seconds as f64 >= self.planned_seconds as f64 * BREAK_TAKEN_SHARE
+    }
+}
+
 impl ActiveTimer {
     pub fn new_manual(
         profile_id: String,
@@ -69,6 +165,7 @@ impl ActiveTimer {
             accumulated_work_time: 0,
             pomodoro_state: None,
             target_duration: None,
+            clock_skew_seconds: 0,
         }
     }
 
@@ -95,8 +192,10 @@ impl ActiveTimer {
                 current_session: 1,
                 phase: PomodoroPhase::Work,
                 phase_start_time: now,
+                completed_sessions: 0,
             }),
             target_duration: None,
+            clock_skew_seconds: 0,
         }
     }
 
@@ -119,6 +218,7 @@ impl ActiveTimer {
             accumulated_work_time: 0,
             pomodoro_state: None,
             target_duration: Some(duration_minutes * 60),
+            clock_skew_seconds: 0,
         }
     }
 
@@ -155,6 +255,27 @@ impl ActiveTimer {
         Ok(())
     }
 
+    /// Moves the timer's wall-clock timestamps by `seconds` after the clock
+    /// jumped by that much, so elapsed times read from the new clock match
+    /// what actually passed.
+    pub fn shift_clock(&mut self, seconds: i64) {
+        let shift = chrono::Duration::seconds(seconds);
+        self.start_time += shift;
+        if let Some(pause_time) = self.pause_time.as_mut() {
+            *pause_time += shift;
+        }
+        if let Some(pomo) = self.pomodoro_state.as_mut() {
+            pomo.phase_start_time += shift;
+        }
+        self.clock_skew_seconds += seconds;
+    }
+
+    /// When the timer started by the clock as it was then, before any
+    /// [`shift_clock`](Self::shift_clock).
+    pub fn original_start_time(&self) -> DateTime<Utc> {
+        self.start_time - chrono::Duration::seconds(self.clock_skew_seconds)
+    }
+
     pub fn stop(&mut self) {
         self.elapsed_seconds = self.current_elapsed();
         self.state = TimerState::Stopped;
@@ -238,19 +359,10 @@ impl ActiveTimer {
         if pomo.phase.is_work() {
             let duration = pomo.phase.duration(&pomo.config);
             self.accumulated_work_time += duration;
+            pomo.completed_sessions += 1;
         }
 
-        let (next_phase, next_session) = match pomo.phase {
-            PomodoroPhase::Work => {
-                if pomo.current_session >= pomo.config.sessions_until_long_break {
-                    (PomodoroPhase::LongBreak, pomo.current_session)
-                } else {
-                    (PomodoroPhase::ShortBreak, pomo.current_session)
-                }
-            }
-            PomodoroPhase::ShortBreak => (PomodoroPhase::Work, pomo.current_session + 1),
-            PomodoroPhase::LongBreak => (PomodoroPhase::Work, 1),
-        };
+        let (next_phase, next_session) = pomo.upcoming();
 
         pomo.phase = next_phase;
         pomo.current_session = next_session;
@@ -259,6 +371,119 @@ impl ActiveTimer {
         Ok(())
     }
 
+    /// Classifies the work phases of a pomodoro timer being stopped now.
+    /// Stopping during a break, or before a held work phase was resumed,
+    /// abandons nothing; stopping partway through work abandons that phase.
+    pub fn pomodoro_outcome(&self) -> Option<PomodoroOutcome> {
+        let pomo = self.pomodoro_state.as_ref()?;
+        let into_work = if pomo.phase.is_work() {
+            self.current_phase_elapsed()
+        } else {
+            0
+        };
+
+        Some(PomodoroOutcome {
+            completed_sessions: pomo.completed_sessions,
+            completed: into_work == 0,
+            interrupted_at_seconds: (into_work > 0).then_some(into_work),
+        })
+    }
+
+    /// The break in progress, ended now with `status`. `None` outside a
+    /// break.
+    pub fn break_record(&self, status: BreakStatus) -> Option<BreakRecord> {
+        let pomo = self.pomodoro_state.as_ref()?;
+        if !pomo.phase.is_break() {
+            return None;
+        }
+        let planned_seconds = pomo.phase.duration(&pomo.config);
+
+        Some(BreakRecord {
+            timer_id: self.id.clone(),
+            task_id: self.task_id.clone(),
+            phase: pomo.phase,
+            started_at: pomo.phase_start_time,
+            ended_at: Utc::now(),
+            planned_seconds,
+            taken_seconds: self.current_phase_elapsed().min(planned_seconds),
+            status,
+        })
+    }
+
+    /// The [`TimerDisplay`] for this timer as of now.
+    pub fn display(&self) -> TimerDisplay {
+        let (remaining_seconds, total_seconds) = match (&self.pomodoro_state, self.target_duration)
+        {
+            (Some(pomo), _) => (
+                self.remaining_seconds(),
+                Some(pomo.phase.duration(&pomo.config)),
+            ),
+            (None, Some(target)) => (
+                Some(target.saturating_sub(self.current_elapsed())),
+                Some(target),
+            ),
+            (None, None) => (None, None),
+        };
+        let (phase_display, next_phase_display) = match &self.pomodoro_state {
+            Some(pomo) => (
+                format!("{} (Session {})", pomo.phase.as_str(), pomo.current_session),
+                Some(pomo.upcoming().0.as_str().to_string()),
+            ),
+            None if self.mode == TimerMode::Countdown => ("Countdown".to_string(), None),
+            None => ("Manual".to_string(), None),
+        };
+
+        TimerDisplay {
+            elapsed_seconds: self.current_elapsed(),
+            remaining_seconds,
+            total_seconds,
+            progress_ratio: progress_ratio(remaining_seconds, total_seconds),
+            phase_display,
+            next_phase_display,
+        }
+    }
+
+    /// What a glanceable clock shows: time left in the pomodoro phase or
+    /// countdown, otherwise time elapsed.
+    pub fn clock_seconds(&self) -> u64 {
+        match (self.remaining_seconds(), self.target_duration) {
+            (Some(remaining), _) => remaining,
+            (None, Some(target)) => target.saturating_sub(self.current_elapsed()),
+            (None, None) => self.current_elapsed(),
+        }
+    }
+
+    fn summary_icon(&self) -> &'static str {
+        if self.is_paused() {
+            return "⏸";
+        }
+        match (&self.pomodoro_state, self.mode) {
+            (Some(pomo), _) if pomo.phase.is_break() => "☕",
+            (Some(_), _) => "🍅",
+            (None, TimerMode::Countdown) => "⏳",
+            (None, _) => "⏱",
+        }
+    }
+
+    /// Fills the [`SUMMARY_VARIABLES`] in `format` with this timer's state.
+    /// Separators left dangling at the end by an empty `{task}` are dropped.
+    pub fn summary(&self, format: &str) -> String {
+        let phase = self
+            .pomodoro_state
+            .as_ref()
+            .map(|p| p.phase.as_str())
+            .unwrap_or("");
+
+        format
+            .replace("{icon}", self.summary_icon())
+            .replace("{time}", &clock_text(self.clock_seconds()))
+            .replace("{task}", self.task_title.as_deref().unwrap_or(""))
+            .replace("{phase}", phase)
+            .replace("{state}", &self.state.to_string())
+            .trim_end_matches(|c: char| c.is_whitespace() || matches!(c, '—' | '-' | '|'))
+            .to_string()
+    }
+
     pub fn is_pomodoro(&self) -> bool {
         self.pomodoro_state.is_some()
     }
@@ -276,6 +501,73 @@ impl ActiveTimer {
     }
 }
 
+/// `MM:SS`, or `H:MM:SS` from an hour up.
+fn clock_text(seconds: u64) -> String {
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    } else {
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// A one-liner for logs, e.g.
+/// `[RUNNING pomodoro:work 15:23 remaining | task: Fix login bug]`.
+impl fmt::Display for ActiveTimer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{} {}",
+            self.state.to_string().to_uppercase(),
+            self.mode
+        )?;
+        if let Some(pomo) = &self.pomodoro_state {
+            write!(f, ":{}", pomo.phase)?;
+        }
+        let clock = if self.remaining_seconds().is_some() || self.target_duration.is_some() {
+            "remaining"
+        } else {
+            "elapsed"
+        };
+        write!(f, " {} {}", clock_text(self.clock_seconds()), clock)?;
+        if let Some(task) = self.task_title.as_deref().or(self.task_id.as_deref()) {
+            write!(f, " | task: {}", task)?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl fmt::Display for TimerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TimerState::Running => "running",
+            TimerState::Paused => "paused",
+            TimerState::Stopped => "stopped",
+        })
+    }
+}
+
+impl PomodoroState {
+    /// The phase and session that follow the current phase.
+    fn upcoming(&self) -> (PomodoroPhase, u32) {
+        match self.phase {
+            PomodoroPhase::Work => {
+                if self.current_session >= self.config.sessions_until_long_break {
+                    (PomodoroPhase::LongBreak, self.current_session)
+                } else {
+                    (PomodoroPhase::ShortBreak, self.current_session)
+                }
+            }
+            PomodoroPhase::ShortBreak => (PomodoroPhase::Work, self.current_session + 1),
+            PomodoroPhase::LongBreak => (PomodoroPhase::Work, 1),
+        }
+    }
+}
+
 impl PomodoroPhase {
     pub fn duration(&self, config: &PomodoroConfig) -> u64 {
         match self {
@@ -302,6 +594,18 @@ impl PomodoroPhase {
     }
 }
 
+/// The snake_case name used on the wire, e.g. `short_break`. See
+/// [`PomodoroPhase::as_str`] for the title-case label.
+impl fmt::Display for PomodoroPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PomodoroPhase::Work => "work",
+            PomodoroPhase::ShortBreak => "short_break",
+            PomodoroPhase::LongBreak => "long_break",
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -390,6 +694,7 @@ mod tests {
             long_break: 1,
             sessions_until_long_break: 2,
             countdown_default: 0,
+            auto_start_next_session: false,
         };
 
         let mut timer = ActiveTimer::new_pomodoro("test".to_string(), None, None, config);
@@ -421,4 +726,162 @@ mod tests {
         );
         assert_eq!(timer.pomodoro_state.as_ref().unwrap().current_session, 1);
     }
+
+    #[test]
+    fn test_pomodoro_outcome_depends_on_phase_at_stop() {
+        let mut timer =
+            ActiveTimer::new_pomodoro("test".to_string(), None, None, PomodoroConfig::default());
+        let ten_minutes_in = Utc::now() - chrono::Duration::minutes(10);
+        timer.pomodoro_state.as_mut().unwrap().phase_start_time = ten_minutes_in;
+
+        let outcome = timer.pomodoro_outcome().unwrap();
+        assert!(!outcome.completed);
+        assert_eq!(outcome.completed_sessions, 0);
+        assert!((599..=601).contains(&outcome.interrupted_at_seconds.unwrap()));
+
+        timer.next_phase().unwrap();
+        let outcome = timer.pomodoro_outcome().unwrap();
+        assert!(outcome.completed);
+        assert_eq!(outcome.completed_sessions, 1);
+        assert_eq!(outcome.interrupted_at_seconds, None);
+
+        assert_eq!(
+            ActiveTimer::new_manual("test".to_string(), None, None).pomodoro_outcome(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_break_record_only_during_breaks() {
+        let mut timer =
+            ActiveTimer::new_pomodoro("test".to_string(), None, None, PomodoroConfig::default());
+        assert!(timer.break_record(BreakStatus::Skipped).is_none());
+
+        timer.next_phase().unwrap();
+        let pomo = timer.pomodoro_state.as_mut().unwrap();
+        let planned = pomo.phase.duration(&pomo.config);
+        pomo.phase_start_time = Utc::now() - chrono::Duration::seconds(planned as i64 / 2);
+
+        let record = timer.break_record(BreakStatus::Skipped).unwrap();
+        assert_eq!(record.phase, PomodoroPhase::ShortBreak);
+        assert_eq!(record.planned_seconds, planned);
+        assert!(!record.is_taken());
+
+        let full = BreakRecord {
+            taken_seconds: planned * 4 / 5,
+            ..record
+        };
+        assert!(full.is_taken());
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut timer = ActiveTimer::new_pomodoro(
+            "test".to_string(),
+            None,
+            Some("Fix login bug".to_string()),
+            PomodoroConfig::default(),
+        );
+        let pomo = timer.pomodoro_state.as_mut().unwrap();
+        pomo.phase_start_time = Utc::now() - chrono::Duration::seconds(150);
+        assert_eq!(
+            timer.summary(DEFAULT_SUMMARY_FORMAT),
+            "🍅 22:30 — Fix login bug"
+        );
+        assert_eq!(timer.summary("{phase} ({state})"), "Work (running)");
+
+        // Without a task the separator goes too.
+        let manual = ActiveTimer::new_manual("test".to_string(), None, None);
+        assert_eq!(manual.summary(DEFAULT_SUMMARY_FORMAT), "⏱ 00:00");
+
+        let mut countdown = ActiveTimer::new_countdown("test".to_string(), None, None, 90);
+        countdown.pause().unwrap();
+        assert_eq!(countdown.summary("{icon} {time}"), "⏸ 1:30:00");
+    }
+
+    #[test]
+    fn test_display_per_mode() {
+        let mut manual = ActiveTimer::new_manual("test".to_string(), None, None);
+        manual.start_time = Utc::now() - chrono::Duration::seconds(100);
+        let display = manual.display();
+        assert_eq!(display.elapsed_seconds, 100);
+        assert_eq!(display.remaining_seconds, None);
+        assert_eq!(display.progress_ratio, None);
+        assert_eq!(display.phase_display, "Manual");
+        assert_eq!(display.next_phase_display, None);
+
+        let mut countdown = ActiveTimer::new_countdown("test".to_string(), None, None, 10);
+        countdown.start_time = Utc::now() - chrono::Duration::seconds(150);
+        let display = countdown.display();
+        assert_eq!(display.elapsed_seconds, 150);
+        assert_eq!(display.remaining_seconds, Some(450));
+        assert_eq!(display.total_seconds, Some(600));
+        assert_eq!(display.progress_ratio, Some(0.25));
+        assert_eq!(display.phase_display, "Countdown");
+        // An overrun countdown stays at zero and full.
+        countdown.start_time = Utc::now() - chrono::Duration::seconds(700);
+        assert_eq!(countdown.display().remaining_seconds, Some(0));
+        assert_eq!(countdown.display().progress_ratio, Some(1.0));
+
+        let mut pomodoro =
+            ActiveTimer::new_pomodoro("test".to_string(), None, None, PomodoroConfig::default());
+        let pomo = pomodoro.pomodoro_state.as_mut().unwrap();
+        pomo.phase_start_time = Utc::now() - chrono::Duration::seconds(375);
+        let display = pomodoro.display();
+        assert_eq!(display.elapsed_seconds, 375);
+        assert_eq!(display.remaining_seconds, Some(1125));
+        assert_eq!(display.total_seconds, Some(1500));
+        assert_eq!(display.progress_ratio, Some(0.25));
+        assert_eq!(display.phase_display, "Work (Session 1)");
+        assert_eq!(display.next_phase_display.as_deref(), Some("Short Break"));
+
+        // The last session before a long break says so, and a break leaves
+        // the time worked alone.
+        let pomo = pomodoro.pomodoro_state.as_mut().unwrap();
+        pomo.current_session = pomo.config.sessions_until_long_break;
+        pomodoro.next_phase().unwrap();
+        let display = pomodoro.display();
+        assert_eq!(display.phase_display, "Long Break (Session 4)");
+        assert_eq!(display.next_phase_display.as_deref(), Some("Work"));
+        assert_eq!(display.elapsed_seconds, 1500);
+    }
+
+    #[test]
+    fn test_display_advanced() {
+        let mut countdown = ActiveTimer::new_countdown("test".to_string(), None, None, 10);
+        countdown.start_time = Utc::now() - chrono::Duration::seconds(150);
+        let display = countdown.display().advanced(150, true);
+        assert_eq!(display.elapsed_seconds, 300);
+        assert_eq!(display.remaining_seconds, Some(300));
+        assert_eq!(display.progress_ratio, Some(0.5));
+        assert_eq!(display.phase_display, "Countdown");
+
+        let display = countdown.display().advanced(1000, false);
+        assert_eq!(display.elapsed_seconds, 150);
+        assert_eq!(display.remaining_seconds, Some(0));
+    }
+
+    #[test]
+    fn test_display() {
+        let mut timer = ActiveTimer::new_pomodoro(
+            "test".to_string(),
+            Some("task-1".to_string()),
+            Some("feature-x".to_string()),
+            PomodoroConfig::default(),
+        );
+        let pomo = timer.pomodoro_state.as_mut().unwrap();
+        pomo.phase_start_time = Utc::now() - chrono::Duration::seconds(37);
+        assert_eq!(
+            timer.to_string(),
+            "[RUNNING pomodoro:work 24:23 remaining | task: feature-x]"
+        );
+
+        let mut manual = ActiveTimer::new_manual("test".to_string(), None, None);
+        manual.stop();
+        assert_eq!(manual.to_string(), "[STOPPED manual 00:00 elapsed]");
+
+        assert_eq!(TimerState::Paused.to_string(), "paused");
+        assert_eq!(PomodoroPhase::LongBreak.to_string(), "long_break");
+        assert_eq!(TimerMode::Countdown.to_string(), "countdown");
+    }
 }
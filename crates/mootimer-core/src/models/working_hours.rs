@@ -0,0 +1,315 @@
+//! When a profile's work is meant to happen, so stats can tell how much of
+//! it spills into evenings and weekends.
+//!
+//! Each weekday has its hours, like `"09:00-17:00"`, or is `"off"`. Days
+//! and hours are read in the timezone the caller passes, and an entry is
+//! cut at every midnight and at the start and end of each day's hours.
+//! Entries that were paused have a `duration_seconds` shorter than their
+//! span, so the pieces are scaled down to add up to it.
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::Entry;
+use crate::{Error, Result};
+
+/// One weekday: working from `start` to `end`, or a day off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum WorkDay {
+    Off,
+    Hours { start: NaiveTime, end: NaiveTime },
+}
+
+impl FromStr for WorkDay {
+    type Err = Error;
+
+    /// `"off"` or `"HH:MM-HH:MM"`, ending after it starts on the same day.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("off") {
+            return Ok(Self::Off);
+        }
+        let invalid = || Error::Validation(format!("Invalid working hours '{}'", s));
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        let time = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| invalid());
+        let (start, end) = (time(start)?, time(end)?);
+        if end <= start {
+            return Err(Error::Validation(format!(
+                "Working hours '{}' must end after they start",
+                s
+            )));
+        }
+        Ok(Self::Hours { start, end })
+    }
+}
+
+impl fmt::Display for WorkDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Off => write!(f, "off"),
+            Self::Hours { start, end } => {
+                write!(f, "{}-{}", start.format("%H:%M"), end.format("%H:%M"))
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for WorkDay {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<WorkDay> for String {
+    fn from(day: WorkDay) -> Self {
+        day.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkingHours {
+    pub monday: WorkDay,
+    pub tuesday: WorkDay,
+    pub wednesday: WorkDay,
+    pub thursday: WorkDay,
+    pub friday: WorkDay,
+    pub saturday: WorkDay,
+    pub sunday: WorkDay,
+}
+
+/// Monday to Friday, nine to five.
+impl Default for WorkingHours {
+    fn default() -> Self {
+        let nine_to_five = WorkDay::Hours {
+            start: NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default(),
+            end: NaiveTime::from_hms_opt(17, 0, 0).unwrap_or_default(),
+        };
+        Self {
+            monday: nine_to_five,
+            tuesday: nine_to_five,
+            wednesday: nine_to_five,
+            thursday: nine_to_five,
+            friday: nine_to_five,
+            saturday: WorkDay::Off,
+            sunday: WorkDay::Off,
+        }
+    }
+}
+
+/// How an amount of logged time falls against working hours. Weekend time
+/// is counted on its own as well, whatever the hours say.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HoursSplit {
+    pub inside_seconds: u64,
+    pub outside_seconds: u64,
+    pub weekend_seconds: u64,
+}
+
+impl HoursSplit {
+    pub fn add(&mut self, other: &HoursSplit) {
+        self.inside_seconds += other.inside_seconds;
+        self.outside_seconds += other.outside_seconds;
+        self.weekend_seconds += other.weekend_seconds;
+    }
+
+    /// Takes back an earlier `add` of `other`.
+    pub fn remove(&mut self, other: &HoursSplit) {
+        self.inside_seconds = self.inside_seconds.saturating_sub(other.inside_seconds);
+        self.outside_seconds = self.outside_seconds.saturating_sub(other.outside_seconds);
+        self.weekend_seconds = self.weekend_seconds.saturating_sub(other.weekend_seconds);
+    }
+
+    /// The share of the time outside working hours, from 0 to 100.
+    pub fn outside_percent(&self) -> f64 {
+        let total = self.inside_seconds + self.outside_seconds;
+        if total == 0 {
+            0.0
+        } else {
+            self.outside_seconds as f64 * 100.0 / total as f64
+        }
+    }
+}
+
+impl WorkingHours {
+    pub fn day(&self, weekday: Weekday) -> WorkDay {
+        match weekday {
+            Weekday::Mon => self.monday,
+            Weekday::Tue => self.tuesday,
+            Weekday::Wed => self.wednesday,
+            Weekday::Thu => self.thursday,
+            Weekday::Fri => self.friday,
+            Weekday::Sat => self.saturday,
+            Weekday::Sun => self.sunday,
+        }
+    }
+
+    /// Splits a completed entry's time, with days and hours read in `tz`.
+    /// Running entries count for nothing yet.
+    pub fn split<Tz: TimeZone>(&self, entry: &Entry, tz: &Tz) -> HoursSplit {
+        let Some(end) = entry.end_time else {
+            return HoursSplit::default();
+        };
+        let start = entry.start_time;
+        let span = (end - start).num_milliseconds();
+        if span <= 0 {
+            return HoursSplit::default();
+        }
+
+        let overlap = |from: DateTime<Utc>, to: DateTime<Utc>| {
+            (end.min(to) - start.max(from)).num_milliseconds().max(0)
+        };
+        let (mut inside, mut weekend) = (0, 0);
+        let mut day = start.with_timezone(tz).date_naive();
+        loop {
+            let day_start = local_instant(tz, day, NaiveTime::MIN);
+            if day_start >= end {
+                break;
+            }
+            let Some(next_day) = day.succ_opt() else {
+                break;
+            };
+            if matches!(day.weekday(), Weekday::Sat | Weekday::Sun) {
+                weekend += overlap(day_start, local_instant(tz, next_day, NaiveTime::MIN));
+            }
+            if let WorkDay::Hours {
+                start: from,
+                end: to,
+            } = self.day(day.weekday())
+            {
+                inside += overlap(local_instant(tz, day, from), local_instant(tz, day, to));
+            }
+            day = next_day;
+        }
+
+        let scale = |ms: i64| {
+            ((entry.duration_seconds as u128 * ms as u128 + span as u128 / 2) / span as u128) as u64
+        };
+        let inside_seconds = scale(inside).min(entry.duration_seconds);
+        HoursSplit {
+            inside_seconds,
+            outside_seconds: entry.duration_seconds - inside_seconds,
+            weekend_seconds: scale(weekend).min(entry.duration_seconds),
+        }
+    }
+
+    pub fn split_all<'a, Tz: TimeZone>(
+        &self,
+        entries: impl IntoIterator<Item = &'a Entry>,
+        tz: &Tz,
+    ) -> HoursSplit {
+        let mut total = HoursSplit::default();
+        for entry in entries {
+            total.add(&self.split(entry, tz));
+        }
+        total
+    }
+}
+
+/// `time` on `day` in `tz`. Times skipped by a clock change are read as UTC.
+fn local_instant<Tz: TimeZone>(tz: &Tz, day: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    let local = day.and_time(time);
+    tz.from_local_datetime(&local)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| local.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TimerMode;
+    use chrono::{Duration, FixedOffset};
+
+    /// 2024-05-13 was a Monday.
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 5, day, hour, minute, 0).unwrap()
+    }
+
+    fn entry(start: DateTime<Utc>, end: DateTime<Utc>) -> Entry {
+        Entry::create_completed(None, None, start, end, TimerMode::Manual).unwrap()
+    }
+
+    fn split(entry: &Entry, offset_hours: i32) -> (u64, u64, u64) {
+        let tz = FixedOffset::east_opt(offset_hours * 3600).unwrap();
+        let split = WorkingHours::default().split(entry, &tz);
+        (
+            split.inside_seconds / 60,
+            split.outside_seconds / 60,
+            split.weekend_seconds / 60,
+        )
+    }
+
+    #[test]
+    fn test_work_day_parsing() {
+        assert_eq!("off".parse::<WorkDay>().unwrap(), WorkDay::Off);
+        let day: WorkDay = " 08:30-12:00 ".parse().unwrap();
+        assert_eq!(day.to_string(), "08:30-12:00");
+        assert!("17:00-09:00".parse::<WorkDay>().is_err());
+        assert!("9-5".parse::<WorkDay>().is_err());
+
+        let hours: WorkingHours =
+            serde_json::from_value(serde_json::json!({"friday": "09:00-13:00"})).unwrap();
+        assert_eq!(hours.friday.to_string(), "09:00-13:00");
+        assert_eq!(hours.monday, WorkingHours::default().monday);
+        assert_eq!(serde_json::to_value(hours).unwrap()["sunday"], "off");
+    }
+
+    #[test]
+    fn test_entry_crossing_end_of_hours() {
+        // Monday 16:00 to 18:30: one hour in, an hour and a half after.
+        assert_eq!(split(&entry(at(13, 16, 0), at(13, 18, 30)), 0), (60, 90, 0));
+        // Monday 08:00 to 17:30 covers the whole day's hours.
+        assert_eq!(split(&entry(at(13, 8, 0), at(13, 17, 30)), 0), (480, 90, 0));
+    }
+
+    #[test]
+    fn test_entry_crossing_midnight_into_weekend() {
+        // Friday 23:00 to Saturday 01:00: all outside, half on the weekend.
+        assert_eq!(split(&entry(at(17, 23, 0), at(18, 1, 0)), 0), (0, 120, 60));
+        // Sunday 23:30 to Monday 09:30: the weekend part ends at midnight
+        // and Monday's hours begin at nine.
+        assert_eq!(
+            split(&entry(at(19, 23, 30), at(20, 9, 30)), 0),
+            (30, 570, 30)
+        );
+    }
+
+    #[test]
+    fn test_days_and_hours_follow_timezone() {
+        // 07:00-09:00 UTC on Monday is 09:00-11:00 at UTC+2.
+        let morning = entry(at(13, 7, 0), at(13, 9, 0));
+        assert_eq!(split(&morning, 0), (0, 120, 0));
+        assert_eq!(split(&morning, 2), (120, 0, 0));
+
+        // 22:00 UTC on Friday is already Saturday at UTC+2.
+        let late = entry(at(17, 21, 0), at(17, 23, 0));
+        assert_eq!(split(&late, 0), (0, 120, 0));
+        assert_eq!(split(&late, 2), (0, 120, 60));
+        // And still Friday afternoon at UTC-8.
+        assert_eq!(split(&late, -8), (120, 0, 0));
+    }
+
+    #[test]
+    fn test_paused_entries_split_in_proportion() {
+        // Two hours across the end of the day, 60 minutes of them logged.
+        let mut paused = entry(at(14, 16, 0), at(14, 18, 0));
+        paused.duration_seconds = 3600;
+        assert_eq!(split(&paused, 0), (30, 30, 0));
+
+        let total = WorkingHours::default().split_all(
+            [
+                &paused,
+                &entry(at(14, 10, 0), at(14, 10, 0) + Duration::minutes(45)),
+            ],
+            &Utc,
+        );
+        assert_eq!(total.inside_seconds, 75 * 60);
+        assert!((total.outside_percent() - 100.0 * 30.0 / 105.0).abs() < 1e-9);
+    }
+}
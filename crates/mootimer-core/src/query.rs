@@ -0,0 +1,331 @@
+//! Which entries to select, and a small query language for writing it as
+//! one string.
+//!
+//! A query is whitespace-separated `key:value` terms, all of which must
+//! match:
+//!
+//! ```text
+//! task:feature-x tag:billable after:2024-01-01 mode:pomodoro
+//! ```
+//!
+//! | key      | value                                                  |
+//! |----------|--------------------------------------------------------|
+//! | `task`   | task id                                                |
+//! | `tag`    | tag; repeat it or separate with commas to match any    |
+//! | `after`  | date, entries starting on or after it                  |
+//! | `before` | date, entries starting before it                       |
+//! | `on`     | date, entries starting that day                        |
+//! | `mode`   | `manual`, `pomodoro` or `countdown`                    |
+//! | `source` | one of [`EntrySource::KINDS`]                          |
+//! | `device` | device id or name                                      |
+//!
+//! Dates are `YYYY-MM-DD`, `today`, `yesterday` or a weekday name, which
+//! means the last such day up to today. Days are local time. Values with
+//! spaces go in double quotes: `device:"Work Laptop"`.
+
+use crate::models::{Entry, EntrySource, TimerMode};
+use crate::{Error, Result};
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone, Utc, Weekday};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntryFilter {
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub task_id: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// One of `EntrySource::KINDS`.
+    pub source: Option<String>,
+    /// A device id or name.
+    pub device: Option<String>,
+    pub mode: Option<TimerMode>,
+}
+
+impl EntryFilter {
+    /// Whether `entry` passes every condition set. Both date bounds are
+    /// inclusive and compared with the entry's start.
+    pub fn matches(&self, entry: &Entry) -> bool {
+        if let Some(start) = self.start_date
+            && entry.start_time < start
+        {
+            return false;
+        }
+        if let Some(end) = self.end_date
+            && entry.start_time > end
+        {
+            return false;
+        }
+
+        if let Some(ref task_id) = self.task_id
+            && entry.task_id.as_ref() != Some(task_id)
+        {
+            return false;
+        }
+
+        if let Some(ref tags) = self.tags
+            && !tags.iter().any(|tag| entry.has_tag(tag))
+        {
+            return false;
+        }
+
+        if let Some(ref source) = self.source
+            && entry.source.kind() != source
+        {
+            return false;
+        }
+
+        if let Some(ref device) = self.device
+            && entry.device_id.as_ref() != Some(device)
+            && entry.device_name.as_ref() != Some(device)
+        {
+            return false;
+        }
+
+        if let Some(mode) = self.mode
+            && entry.mode != mode
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// Turns query strings into [`EntryFilter`]s. Relative dates are resolved
+/// against `today`.
+pub struct QueryParser {
+    today: NaiveDate,
+}
+
+impl Default for QueryParser {
+    fn default() -> Self {
+        Self::new(Local::now().date_naive())
+    }
+}
+
+impl QueryParser {
+    pub fn new(today: NaiveDate) -> Self {
+        Self { today }
+    }
+
+    pub fn parse(&self, query: &str) -> Result<EntryFilter> {
+        let mut filter = EntryFilter::default();
+
+        for term in tokenize(query)? {
+            let (key, value) = term
+                .split_once(':')
+                .ok_or_else(|| invalid(format!("expected key:value, got '{}'", term)))?;
+            if value.is_empty() {
+                return Err(invalid(format!("'{}' needs a value", key)));
+            }
+
+            match key {
+                "task" => set_once(&mut filter.task_id, key, value.to_string())?,
+                "tag" | "tags" => filter.tags.get_or_insert_with(Vec::new).extend(
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string),
+                ),
+                "after" => {
+                    let day = self.date(value)?;
+                    set_once(&mut filter.start_date, key, day_start(day))?;
+                }
+                "before" => {
+                    let day = self.date(value)?;
+                    set_once(&mut filter.end_date, key, last_instant_before(day))?;
+                }
+                "on" => {
+                    let day = self.date(value)?;
+                    set_once(&mut filter.start_date, key, day_start(day))?;
+                    set_once(
+                        &mut filter.end_date,
+                        key,
+                        last_instant_before(day + chrono::Duration::days(1)),
+                    )?;
+                }
+                "mode" => set_once(&mut filter.mode, key, parse_mode(value)?)?,
+                "source" => {
+                    if !EntrySource::KINDS.contains(&value) {
+                        return Err(invalid(format!(
+                            "unknown source '{}', expected one of: {}",
+                            value,
+                            EntrySource::KINDS.join(", ")
+                        )));
+                    }
+                    set_once(&mut filter.source, key, value.to_string())?;
+                }
+                "device" => set_once(&mut filter.device, key, value.to_string())?,
+                _ => return Err(invalid(format!("unknown key '{}'", key))),
+            }
+        }
+
+        Ok(filter)
+    }
+
+    fn date(&self, value: &str) -> Result<NaiveDate> {
+        match value.to_lowercase().as_str() {
+            "today" => return Ok(self.today),
+            "yesterday" => return Ok(self.today - chrono::Duration::days(1)),
+            _ => {}
+        }
+        if let Ok(weekday) = value.parse::<Weekday>() {
+            let back = (self.today.weekday().num_days_from_monday() + 7
+                - weekday.num_days_from_monday())
+                % 7;
+            return Ok(self.today - chrono::Duration::days(back as i64));
+        }
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+            invalid(format!(
+                "'{}' is not a date; use YYYY-MM-DD, today, yesterday or a weekday",
+                value
+            ))
+        })
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::Validation(format!("Invalid query: {}", message))
+}
+
+fn set_once<T>(slot: &mut Option<T>, key: &str, value: T) -> Result<()> {
+    if slot.is_some() {
+        return Err(invalid(format!("'{}' conflicts with an earlier term", key)));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn parse_mode(value: &str) -> Result<TimerMode> {
+    match value {
+        "manual" => Ok(TimerMode::Manual),
+        "pomodoro" => Ok(TimerMode::Pomodoro),
+        "countdown" => Ok(TimerMode::Countdown),
+        _ => Err(invalid(format!(
+            "unknown mode '{}', expected manual, pomodoro or countdown",
+            value
+        ))),
+    }
+}
+
+/// Local midnight starting `day`.
+fn day_start(day: NaiveDate) -> DateTime<Utc> {
+    let midnight = day.and_time(chrono::NaiveTime::MIN);
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+/// The last instant before `day` begins, as an inclusive end bound.
+fn last_instant_before(day: NaiveDate) -> DateTime<Utc> {
+    day_start(day) - chrono::Duration::nanoseconds(1)
+}
+
+/// Splits on whitespace, keeping double-quoted runs together and dropping
+/// the quotes.
+fn tokenize(query: &str) -> Result<Vec<String>> {
+    let mut terms = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    terms.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if quoted {
+        return Err(invalid("unclosed quote".to_string()));
+    }
+    if !current.is_empty() {
+        terms.push(current);
+    }
+    Ok(terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parser() -> QueryParser {
+        // A Wednesday.
+        QueryParser::new(NaiveDate::from_ymd_opt(2024, 5, 15).unwrap())
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_terms() {
+        let filter = parser()
+            .parse(r#"task:feature-x tags:billable,client after:2024-01-01 mode:pomodoro device:"Work Laptop""#)
+            .unwrap();
+        assert_eq!(
+            filter,
+            EntryFilter {
+                start_date: Some(day_start(date(2024, 1, 1))),
+                task_id: Some("feature-x".to_string()),
+                tags: Some(vec!["billable".to_string(), "client".to_string()]),
+                device: Some("Work Laptop".to_string()),
+                mode: Some(TimerMode::Pomodoro),
+                ..Default::default()
+            }
+        );
+        assert_eq!(parser().parse("  ").unwrap(), EntryFilter::default());
+    }
+
+    #[test]
+    fn test_relative_dates() {
+        let on_monday = parser().parse("on:monday").unwrap();
+        assert_eq!(on_monday.start_date, Some(day_start(date(2024, 5, 13))));
+        assert!(on_monday.end_date.unwrap() < day_start(date(2024, 5, 14)));
+
+        let filter = parser().parse("after:yesterday before:wed").unwrap();
+        assert_eq!(filter.start_date, Some(day_start(date(2024, 5, 14))));
+        assert_eq!(
+            filter.end_date,
+            Some(last_instant_before(date(2024, 5, 15)))
+        );
+    }
+
+    #[test]
+    fn test_invalid_queries() {
+        for query in [
+            "feature-x",
+            "task:",
+            "project:x",
+            "mode:stopwatch",
+            "source:email",
+            "after:someday",
+            "task:a task:b",
+            "on:today after:monday",
+            r#"device:"Work"#,
+        ] {
+            assert!(
+                matches!(parser().parse(query), Err(Error::Validation(_))),
+                "{} should be rejected",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let mut entry = Entry::new(Some("api".to_string()), None, TimerMode::Manual);
+        entry.tags = vec!["billable".to_string()];
+
+        let parse = |q: &str| parser().parse(q).unwrap();
+        assert!(parse("task:api tag:billable mode:manual source:timer").matches(&entry));
+        assert!(!parse("task:web").matches(&entry));
+        assert!(!parse("mode:pomodoro").matches(&entry));
+        assert!(!parse("tag:internal").matches(&entry));
+    }
+}
@@ -0,0 +1,156 @@
+//! Report tables shared by the TUI and command-line tools.
+//!
+//! The per-task breakdown totals a period's entries by task, most time
+//! first. [`task_breakdown_csv`] renders it for pasting into a
+//! spreadsheet: one row per task with hours to two decimals, the number of
+//! sessions, and the task's share of the total.
+
+use std::collections::HashMap;
+
+use crate::Result;
+
+/// One task's total in a report period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskBreakdownRow {
+    /// Set when the report spans several profiles; the same task id in two
+    /// profiles stays two rows.
+    pub profile_id: Option<String>,
+    /// `None` for time tracked without a task.
+    pub task_id: Option<String>,
+    pub seconds: u64,
+    pub sessions: usize,
+}
+
+/// Totals `(profile_id, task_id, duration_seconds)` per entry into rows,
+/// most time first, then by task id and profile.
+pub fn task_breakdown<I>(entries: I) -> Vec<TaskBreakdownRow>
+where
+    I: IntoIterator<Item = (Option<String>, Option<String>, u64)>,
+{
+    let mut totals: HashMap<(Option<String>, Option<String>), (u64, usize)> = HashMap::new();
+    for (profile_id, task_id, seconds) in entries {
+        let total = totals.entry((profile_id, task_id)).or_default();
+        total.0 += seconds;
+        total.1 += 1;
+    }
+
+    let mut rows: Vec<TaskBreakdownRow> = totals
+        .into_iter()
+        .map(
+            |((profile_id, task_id), (seconds, sessions))| TaskBreakdownRow {
+                profile_id,
+                task_id,
+                seconds,
+                sessions,
+            },
+        )
+        .collect();
+    rows.sort_by(|a, b| {
+        b.seconds
+            .cmp(&a.seconds)
+            .then_with(|| a.task_id.cmp(&b.task_id))
+            .then_with(|| a.profile_id.cmp(&b.profile_id))
+    });
+    rows
+}
+
+/// The breakdown as CSV with a header row, naming each row's task with
+/// `title`. A `profile` column leads when any row has a profile.
+pub fn task_breakdown_csv(
+    rows: &[TaskBreakdownRow],
+    title: impl Fn(&TaskBreakdownRow) -> String,
+) -> Result<String> {
+    let with_profile = rows.iter().any(|r| r.profile_id.is_some());
+    let total: u64 = rows.iter().map(|r| r.seconds).sum();
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut header = vec!["task", "hours", "sessions", "percentage"];
+    if with_profile {
+        header.insert(0, "profile");
+    }
+    writer.write_record(&header)?;
+
+    for row in rows {
+        let percentage = if total > 0 {
+            row.seconds as f64 * 100.0 / total as f64
+        } else {
+            0.0
+        };
+        let mut record = vec![
+            title(row),
+            format!("{:.2}", row.seconds as f64 / 3600.0),
+            row.sessions.to_string(),
+            format!("{:.1}", percentage),
+        ];
+        if with_profile {
+            record.insert(0, row.profile_id.clone().unwrap_or_default());
+        }
+        writer.write_record(&record)?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| crate::Error::InvalidData(format!("Failed to write CSV: {}", e)))?;
+    String::from_utf8(bytes)
+        .map_err(|e| crate::Error::InvalidData(format!("Failed to write CSV: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(task: Option<&str>, seconds: u64) -> (Option<String>, Option<String>, u64) {
+        (None, task.map(str::to_string), seconds)
+    }
+
+    #[test]
+    fn test_task_breakdown_totals_and_order() {
+        let rows = task_breakdown([
+            entry(Some("api"), 1800),
+            entry(None, 900),
+            entry(Some("api"), 1800),
+            entry(Some("docs"), 900),
+        ]);
+        assert_eq!(
+            rows.iter()
+                .map(|r| (r.task_id.as_deref(), r.seconds, r.sessions))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some("api"), 3600, 2),
+                (None, 900, 1),
+                (Some("docs"), 900, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_task_breakdown_csv_escapes_titles() {
+        let rows = task_breakdown([entry(Some("a"), 5400), entry(Some("b"), 1800)]);
+        let csv = task_breakdown_csv(&rows, |row| match row.task_id.as_deref() {
+            Some("a") => "Fix login, then \"ship\" it".to_string(),
+            _ => "Plain".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            csv,
+            "task,hours,sessions,percentage\n\
+             \"Fix login, then \"\"ship\"\" it\",1.50,1,75.0\n\
+             Plain,0.50,1,25.0\n"
+        );
+
+        // The titles come back intact when read as CSV.
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let first = reader.records().next().unwrap().unwrap();
+        assert_eq!(&first[0], "Fix login, then \"ship\" it");
+    }
+
+    #[test]
+    fn test_task_breakdown_csv_profile_column() {
+        let rows = task_breakdown([(Some("work".to_string()), None, 60)]);
+        let csv = task_breakdown_csv(&rows, |_| "No task".to_string()).unwrap();
+        assert_eq!(
+            csv,
+            "profile,task,hours,sessions,percentage\nwork,No task,0.02,1,100.0\n"
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Fuzzy task search shared by the daemon and the TUI.
+//!
+//! Each whitespace-separated word of the query has to appear in the text
+//! in order, though not necessarily side by side, so "lgn bug" finds
+//! "Fix login bug". Matches score higher when their characters are
+//! consecutive or start words, and lower for every character skipped in
+//! between, much like fzf.
+//!
+//! Searches can also be narrowed to tasks carrying some tags. Typed
+//! searches spell those as `#tag` words, which [`split_tags`] takes out.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Task;
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_CONSECUTIVE: i64 = 8;
+const BONUS_BOUNDARY: i64 = 8;
+const BONUS_CAMEL: i64 = 7;
+const PENALTY_GAP_START: i64 = 3;
+const PENALTY_GAP_EXTENSION: i64 = 1;
+
+/// Where a query matched a text and how well.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Runs of matched characters as `[start, end)` offsets in characters,
+    /// not bytes, for highlighting.
+    pub spans: Vec<(usize, usize)>,
+}
+
+/// Matches `query` against `text`. An empty query matches anything with a
+/// score of zero.
+pub fn fuzzy_match(query: &str, text: &str, case_sensitive: bool) -> Option<FuzzyMatch> {
+    let fold = |c: char| {
+        if case_sensitive {
+            c
+        } else if c.is_ascii() {
+            c.to_ascii_lowercase()
+        } else {
+            c.to_lowercase().next().unwrap_or(c)
+        }
+    };
+    // Most texts don't match at all; rule them out before allocating.
+    let mut words = query.split_whitespace();
+    if !words.all(|word| {
+        let mut text = text.chars().map(fold);
+        word.chars().map(fold).all(|c| text.any(|t| t == c))
+    }) {
+        return None;
+    }
+
+    let original: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = original.iter().map(|&c| fold(c)).collect();
+
+    let mut score = 0;
+    let mut matched = Vec::new();
+    for word in query.split_whitespace() {
+        let word: Vec<char> = word.chars().map(fold).collect();
+        let indices = match_word(&word, &folded)?;
+        score += score_indices(&indices, &original);
+        matched.extend(indices);
+    }
+    matched.sort_unstable();
+    matched.dedup();
+
+    Some(FuzzyMatch {
+        score,
+        spans: spans(&matched),
+    })
+}
+
+/// The positions `word` matched at: the first place it completes, pulled
+/// back to the shortest run ending there.
+fn match_word(word: &[char], text: &[char]) -> Option<Vec<usize>> {
+    let mut pending = word.iter().peekable();
+    let mut end = None;
+    for (i, c) in text.iter().enumerate() {
+        if pending.peek() == Some(&c) {
+            pending.next();
+            if pending.peek().is_none() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    let mut indices = Vec::with_capacity(word.len());
+    let mut pending = word.iter().rev().peekable();
+    for i in (0..=end).rev() {
+        if pending.peek() == Some(&&text[i]) {
+            pending.next();
+            indices.push(i);
+            if pending.peek().is_none() {
+                break;
+            }
+        }
+    }
+    indices.reverse();
+    Some(indices)
+}
+
+fn score_indices(indices: &[usize], text: &[char]) -> i64 {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    for (n, &i) in indices.iter().enumerate() {
+        score += SCORE_MATCH;
+        let mut bonus = match i.checked_sub(1).map(|p| text[p]) {
+            None => BONUS_BOUNDARY,
+            Some(before) if !before.is_alphanumeric() => BONUS_BOUNDARY,
+            Some(before) if before.is_lowercase() && text[i].is_uppercase() => BONUS_CAMEL,
+            Some(_) => 0,
+        };
+        match previous {
+            Some(p) if p + 1 == i => bonus = bonus.max(BONUS_CONSECUTIVE),
+            Some(p) => {
+                score -= PENALTY_GAP_START + PENALTY_GAP_EXTENSION * (i - p - 2) as i64;
+            }
+            None => {}
+        }
+        // The first character counts double, so words are found by their
+        // start rather than somewhere in the middle.
+        score += if n == 0 { bonus * 2 } else { bonus };
+        previous = Some(i);
+    }
+    score
+}
+
+fn spans(indices: &[usize]) -> Vec<(usize, usize)> {
+    let mut spans: Vec<(usize, usize)> = Vec::new();
+    for &i in indices {
+        match spans.last_mut() {
+            Some((_, end)) if *end == i => *end = i + 1,
+            _ => spans.push((i, i + 1)),
+        }
+    }
+    spans
+}
+
+/// Whether a task needs every searched tag or just one of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TagMode {
+    #[default]
+    All,
+    Any,
+}
+
+impl TagMode {
+    /// Whether a task for which `has_tag` holds passes a filter on `tags`.
+    /// No tags filter nothing out.
+    pub fn matches(self, tags: &[String], has_tag: impl Fn(&str) -> bool) -> bool {
+        tags.is_empty()
+            || match self {
+                Self::All => tags.iter().all(|tag| has_tag(tag)),
+                Self::Any => tags.iter().any(|tag| has_tag(tag)),
+            }
+    }
+}
+
+/// Splits typed search input into the text to match and the `#tag` words,
+/// so `"refactor #backend #urgent"` searches for "refactor" among tasks
+/// tagged `backend` and `urgent`.
+pub fn split_tags(input: &str) -> (String, Vec<String>) {
+    let mut words = Vec::new();
+    let mut tags = Vec::new();
+    for word in input.split_whitespace() {
+        match word.strip_prefix('#') {
+            Some(tag) if !tag.is_empty() => tags.push(tag.to_string()),
+            _ => words.push(word),
+        }
+    }
+    (words.join(" "), tags)
+}
+
+/// Which part of a task a search matched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchField {
+    Title,
+    /// The tag at this position in `Task::tags`.
+    Tag(usize),
+    Description,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TaskMatch {
+    pub field: MatchField,
+    #[serde(flatten)]
+    pub matched: FuzzyMatch,
+}
+
+/// The best match of `query` in the task's title, tags or description.
+/// Titles win ties; a description match counts for half.
+pub fn match_task(query: &str, task: &Task, case_sensitive: bool) -> Option<TaskMatch> {
+    let title = fuzzy_match(query, &task.title, case_sensitive).map(|m| (MatchField::Title, m));
+    let tags = task.tags.iter().enumerate().filter_map(|(i, tag)| {
+        fuzzy_match(query, tag, case_sensitive).map(|m| (MatchField::Tag(i), m))
+    });
+    let description = task
+        .description
+        .as_deref()
+        .and_then(|d| fuzzy_match(query, d, case_sensitive))
+        .map(|mut m| {
+            m.score /= 2;
+            (MatchField::Description, m)
+        });
+
+    title
+        .into_iter()
+        .chain(tags)
+        .chain(description)
+        .reduce(|best, next| {
+            if next.1.score > best.1.score {
+                next
+            } else {
+                best
+            }
+        })
+        .map(|(field, matched)| TaskMatch { field, matched })
+}
+
+/// The tasks matching `query`, best first; ties go to the shorter title,
+/// then to the order given.
+pub fn rank_tasks<'a>(
+    query: &str,
+    tasks: impl IntoIterator<Item = &'a Task>,
+    case_sensitive: bool,
+) -> Vec<(&'a Task, TaskMatch)> {
+    let mut ranked: Vec<_> = tasks
+        .into_iter()
+        .filter_map(|task| match_task(query, task, case_sensitive).map(|m| (task, m)))
+        .collect();
+    ranked.sort_by(|(a, am), (b, bm)| {
+        bm.matched
+            .score
+            .cmp(&am.matched.score)
+            .then_with(|| a.title.chars().count().cmp(&b.title.chars().count()))
+    });
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans_of(query: &str, text: &str) -> Option<Vec<(usize, usize)>> {
+        fuzzy_match(query, text, false).map(|m| m.spans)
+    }
+
+    fn score(query: &str, text: &str) -> i64 {
+        fuzzy_match(query, text, false).unwrap().score
+    }
+
+    #[test]
+    fn test_fuzzy_match_words_in_order() {
+        assert_eq!(
+            spans_of("lgn bug", "Fix login bug"),
+            Some(vec![(4, 5), (6, 7), (8, 9), (10, 13)])
+        );
+        assert_eq!(
+            spans_of("bug lgn", "Fix login bug"),
+            spans_of("lgn bug", "Fix login bug")
+        );
+        assert_eq!(spans_of("ngl", "Fix login bug"), None);
+        assert_eq!(spans_of("login q", "Fix login bug"), None);
+        assert_eq!(spans_of("", "anything"), Some(vec![]));
+
+        // Offsets are in characters.
+        assert_eq!(spans_of("cafe", "Café"), None);
+        assert_eq!(spans_of("mnu", "Café menu"), Some(vec![(5, 6), (7, 9)]));
+    }
+
+    #[test]
+    fn test_fuzzy_match_case_sensitivity() {
+        assert!(fuzzy_match("API", "update the api", false).is_some());
+        assert!(fuzzy_match("API", "update the api", true).is_none());
+        assert!(fuzzy_match("API", "API docs", true).is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_match_prefers_tight_and_word_start_matches() {
+        assert!(score("log", "Fix login bug") > score("log", "Follow up on grafana"));
+        assert!(score("bug", "bug triage") > score("bug", "debugging"));
+        assert!(score("gh", "GitHub sync") > score("gh", "tough call"));
+        // The shortest run is picked, not the first character seen.
+        assert_eq!(spans_of("ab", "a xab"), Some(vec![(3, 5)]));
+    }
+
+    #[test]
+    fn test_split_tags_and_tag_mode() {
+        assert_eq!(
+            split_tags("refactor #backend  auth #urgent #"),
+            (
+                "refactor auth #".to_string(),
+                vec!["backend".to_string(), "urgent".to_string()]
+            )
+        );
+        assert_eq!(split_tags("#ops"), (String::new(), vec!["ops".to_string()]));
+
+        let tags = ["backend".to_string(), "urgent".to_string()];
+        let has = |tag: &str| tag == "backend";
+        assert!(!TagMode::All.matches(&tags, has));
+        assert!(TagMode::Any.matches(&tags, has));
+        assert!(TagMode::All.matches(&[], |_| false));
+    }
+
+    #[test]
+    fn test_rank_tasks() {
+        let task = |title: &str, tags: &[&str], description: Option<&str>| {
+            let mut task = Task::new(title.to_string()).unwrap();
+            task.tags = tags.iter().map(|t| t.to_string()).collect();
+            task.description = description.map(str::to_string);
+            task
+        };
+        let tasks = [
+            task("Write docs", &["login"], None),
+            task("Plan sprint", &[], Some("talk about the login bug")),
+            task("Fix login bug", &[], None),
+            task("Groceries", &[], None),
+        ];
+
+        let ranked = rank_tasks("login", &tasks, false);
+        let titles: Vec<&str> = ranked.iter().map(|(t, _)| t.title.as_str()).collect();
+        assert_eq!(titles, ["Write docs", "Fix login bug", "Plan sprint"]);
+        assert_eq!(ranked[0].1.field, MatchField::Tag(0));
+        assert_eq!(ranked[1].1.field, MatchField::Title);
+        assert_eq!(ranked[2].1.field, MatchField::Description);
+
+        let json = serde_json::to_value(&ranked[1].1).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"field": "title", "score": ranked[1].1.matched.score, "spans": [[4, 9]]})
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{Result, models::BreakRecord};
+use std::path::PathBuf;
+
+/// Pomodoro breaks, one CSV row each in `profiles/<id>/breaks.csv`.
+pub struct BreakStorage {
+    data_dir: PathBuf,
+}
+
+impl BreakStorage {
+    pub fn new(data_dir: PathBuf) -> Self {
+        Self { data_dir }
+    }
+
+    fn path(&self, profile_id: &str) -> PathBuf {
+        self.data_dir
+            .join("profiles")
+            .join(profile_id)
+            .join("breaks.csv")
+    }
+
+    pub fn load(&self, profile_id: &str) -> Result<Vec<BreakRecord>> {
+        let path = self.path(profile_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut reader = csv::Reader::from_path(path)?;
+        let mut breaks = Vec::new();
+        for result in reader.deserialize() {
+            breaks.push(result?);
+        }
+        Ok(breaks)
+    }
+
+    pub fn append(&self, profile_id: &str, record: &BreakRecord) -> Result<()> {
+        let path = self.path(profile_id);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file_exists = path.exists();
+
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(!file_exists)
+            .from_writer(&file);
+        writer.serialize(record)?;
+        writer.flush()?;
+        drop(writer);
+
+        super::durability::appended(&file, &path, !file_exists)
+    }
+}
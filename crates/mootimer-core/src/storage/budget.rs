@@ -0,0 +1,49 @@
+use crate::{Result, models::Budget};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BudgetsFile {
+    budgets: Vec<Budget>,
+}
+
+/// Time budgets, in `profiles/<id>/budgets.json`.
+pub struct BudgetStorage {
+    data_dir: PathBuf,
+}
+
+impl BudgetStorage {
+    pub fn new(data_dir: PathBuf) -> Self {
+        Self { data_dir }
+    }
+
+    fn path(&self, profile_id: &str) -> PathBuf {
+        self.data_dir
+            .join("profiles")
+            .join(profile_id)
+            .join("budgets.json")
+    }
+
+    pub fn load(&self, profile_id: &str) -> Result<Vec<Budget>> {
+        let path = self.path(profile_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let file: BudgetsFile = serde_json::from_slice(&std::fs::read(path)?)?;
+        Ok(file.budgets)
+    }
+
+    pub fn save(&self, profile_id: &str, budgets: &[Budget]) -> Result<()> {
+        let path = self.path(profile_id);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let file = BudgetsFile {
+            budgets: budgets.to_vec(),
+        };
+        super::durability::write_file(&path, serde_json::to_vec_pretty(&file)?)?;
+        Ok(())
+    }
+}
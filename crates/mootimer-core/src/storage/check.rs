@@ -0,0 +1,335 @@
+//! A read-only look over a data directory for what manual edits tend to
+//! break, behind `mootimerd --check`.
+//!
+//! Every profile's `profile.json`, `tasks.json` and `entries.csv` is read
+//! without any of the migrations loading normally writes back. Tasks are
+//! checked one by one for fields that don't parse, such as an unknown
+//! status, and for ids used twice; entries row by row for rows that don't
+//! parse and for entries `Entry::validate` or `Entry::check_timestamps`
+//! rejects, the latter with `daemon.earliest_entry_date`. Entries of the same
+//! task that overlap are flagged too. Different tasks may overlap, since
+//! several timers can run at once. The sync repository, if there is one,
+//! has to open and have a readable HEAD.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use super::ProfileStorage;
+use super::entry::entry_rows_from_csv;
+use crate::git::GitOperations;
+use crate::models::{Entry, Task};
+
+/// One problem, and the file or record it is in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub location: String,
+    pub problem: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.problem)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub profiles: usize,
+    pub tasks: usize,
+    pub entries: usize,
+    pub issues: Vec<Issue>,
+    /// Files that were left alone, such as encrypted entries.
+    pub skipped: Vec<String>,
+}
+
+impl CheckReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn issue(&mut self, location: impl Into<String>, problem: impl fmt::Display) {
+        self.issues.push(Issue {
+            location: location.into(),
+            problem: problem.to_string(),
+        });
+    }
+}
+
+pub fn check_data_dir(data_dir: &Path, earliest_entry_date: NaiveDate) -> CheckReport {
+    let mut report = CheckReport::default();
+    let profiles_dir = data_dir.join("profiles");
+
+    let mut profile_ids = Vec::new();
+    if profiles_dir.exists() {
+        match std::fs::read_dir(&profiles_dir) {
+            Ok(dirs) => {
+                for dir in dirs.flatten() {
+                    if dir.file_type().is_ok_and(|t| t.is_dir()) {
+                        profile_ids.push(dir.file_name().to_string_lossy().into_owned());
+                    }
+                }
+            }
+            Err(e) => report.issue("profiles", e),
+        }
+    }
+    profile_ids.sort();
+
+    let profiles = ProfileStorage::new(data_dir.to_path_buf());
+    for profile_id in &profile_ids {
+        report.profiles += 1;
+        let location = format!("profiles/{}/profile.json", profile_id);
+        match profiles.load(profile_id) {
+            Ok(profile) if profile.id != *profile_id => report.issue(
+                location,
+                format!("id '{}' doesn't match its directory", profile.id),
+            ),
+            Ok(_) => {}
+            Err(e) => report.issue(location, e),
+        }
+
+        let dir = profiles_dir.join(profile_id);
+        check_tasks(&mut report, &dir, profile_id);
+        check_entries(&mut report, &dir, profile_id, earliest_entry_date);
+    }
+
+    let git = GitOperations::new(data_dir.to_path_buf());
+    if git.is_initialized()
+        && let Err(e) = git.verify()
+    {
+        report.issue(".git", e);
+    }
+
+    report
+}
+
+fn check_tasks(report: &mut CheckReport, dir: &Path, profile_id: &str) {
+    let location = format!("profiles/{}/tasks.json", profile_id);
+    let content = match std::fs::read(dir.join("tasks.json")) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) => return report.issue(location, e),
+    };
+    let file: serde_json::Value = match serde_json::from_slice(&content) {
+        Ok(file) => file,
+        Err(e) => return report.issue(location, e),
+    };
+    let Some(tasks) = file.get("tasks").and_then(|t| t.as_array()) else {
+        return report.issue(location, "no \"tasks\" list");
+    };
+
+    let mut seen = HashSet::new();
+    for (i, task) in tasks.iter().enumerate() {
+        report.tasks += 1;
+        let id = task.get("id").and_then(|v| v.as_str());
+        let task_location = format!("{} task {}", location, id.unwrap_or(&i.to_string()));
+        if let Err(e) = serde_json::from_value::<Task>(task.clone()) {
+            report.issue(&task_location, e);
+        }
+        if let Some(id) = id
+            && !seen.insert(id)
+        {
+            report.issue(task_location, "id used by an earlier task");
+        }
+    }
+}
+
+fn check_entries(
+    report: &mut CheckReport,
+    dir: &Path,
+    profile_id: &str,
+    earliest_entry_date: NaiveDate,
+) {
+    if dir.join("entries.csv.enc").exists() {
+        report.skipped.push(format!(
+            "profiles/{}/entries.csv.enc (encrypted)",
+            profile_id
+        ));
+    }
+    let location = format!("profiles/{}/entries.csv", profile_id);
+    let content = match std::fs::read(dir.join("entries.csv")) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) => return report.issue(location, e),
+    };
+
+    let now = Utc::now();
+    let mut entries: Vec<Entry> = Vec::new();
+    for (i, row) in entry_rows_from_csv(&content).into_iter().enumerate() {
+        report.entries += 1;
+        match row {
+            // Row 1 is the header.
+            Err(e) => report.issue(format!("{} line {}", location, i + 2), e),
+            Ok(entry) => {
+                if let Err(e) = entry
+                    .validate()
+                    .and_then(|_| entry.check_timestamps(now, earliest_entry_date))
+                {
+                    report.issue(format!("{} entry {}", location, entry.id), e);
+                }
+                entries.push(entry);
+            }
+        }
+    }
+
+    entries.sort_by_key(|e| e.start_time);
+    let mut last_end: HashMap<&str, (DateTime<Utc>, &str)> = HashMap::new();
+    for entry in &entries {
+        let (Some(task_id), Some(end)) = (entry.task_id.as_deref(), entry.end_time) else {
+            continue;
+        };
+        if let Some(&(previous_end, previous_id)) = last_end.get(task_id)
+            && entry.start_time < previous_end
+        {
+            report.issue(
+                format!("{} entry {}", location, entry.id),
+                format!(
+                    "overlaps entry {} of the same task by {} s",
+                    previous_id,
+                    (previous_end.min(end) - entry.start_time).num_seconds()
+                ),
+            );
+        }
+        let latest = last_end.entry(task_id).or_insert((end, &entry.id));
+        if end >= latest.0 {
+            *latest = (end, &entry.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Profile, TimerMode};
+    use crate::storage::{EntryStorage, TaskStorage};
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    fn entry(task: &str, start_minutes_ago: i64, minutes: i64) -> Entry {
+        let start = Utc::now() - Duration::minutes(start_minutes_ago);
+        Entry::create_completed(
+            Some(task.to_string()),
+            None,
+            start,
+            start + Duration::minutes(minutes),
+            TimerMode::Manual,
+        )
+        .unwrap()
+    }
+
+    fn earliest() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2000, 1, 1).unwrap()
+    }
+
+    fn problems(report: &CheckReport) -> Vec<String> {
+        report.issues.iter().map(|i| i.problem.clone()).collect()
+    }
+
+    #[test]
+    fn test_clean_data_dir() {
+        let dir = TempDir::new().unwrap();
+        let data_dir = dir.path().to_path_buf();
+        let profile = Profile::new("work".to_string(), "Work".to_string()).unwrap();
+        ProfileStorage::new(data_dir.clone())
+            .save(&profile)
+            .unwrap();
+        let task = Task::new("Write docs".to_string()).unwrap();
+        TaskStorage::new(data_dir.clone())
+            .save("work", &[task])
+            .unwrap();
+        EntryStorage::new(data_dir.clone())
+            .save_all(
+                "work",
+                &[entry("a", 120, 30), entry("a", 60, 30), entry("b", 70, 30)],
+            )
+            .unwrap();
+        GitOperations::new(data_dir.clone()).init().unwrap();
+
+        let report = check_data_dir(&data_dir, earliest());
+        assert!(report.is_clean(), "{:?}", report.issues);
+        assert_eq!((report.profiles, report.tasks, report.entries), (1, 1, 3));
+    }
+
+    #[test]
+    fn test_reports_hand_edited_files() {
+        let dir = TempDir::new().unwrap();
+        let data_dir = dir.path().to_path_buf();
+        let profile = Profile::new("work".to_string(), "Work".to_string()).unwrap();
+        ProfileStorage::new(data_dir.clone())
+            .save(&profile)
+            .unwrap();
+
+        let task = Task::new("Write docs".to_string()).unwrap();
+        let mut tasks = serde_json::json!({ "tasks": [task, task.clone()] });
+        tasks["tasks"][1]["status"] = serde_json::json!("someday");
+        std::fs::write(
+            data_dir.join("profiles/work/tasks.json"),
+            serde_json::to_vec(&tasks).unwrap(),
+        )
+        .unwrap();
+
+        let mut zero = entry("a", 300, 30);
+        zero.duration_seconds = 0;
+        let mut future = entry("b", 300, 30);
+        future.start_time = Utc::now() + Duration::hours(1);
+        future.end_time = Some(future.start_time + Duration::minutes(30));
+        EntryStorage::new(data_dir.clone())
+            .save_all(
+                "work",
+                &[zero, future, entry("c", 120, 60), entry("c", 90, 10)],
+            )
+            .unwrap();
+        let path = data_dir.join("profiles/work/entries.csv");
+        let mut csv = std::fs::read_to_string(&path).unwrap();
+        csv.push_str("not,an,entry\n");
+        std::fs::write(&path, csv).unwrap();
+
+        let report = check_data_dir(&data_dir, earliest());
+        let problems = problems(&report);
+        assert_eq!(report.issues.len(), 6, "{:#?}", report.issues);
+        assert!(problems[0].contains("unknown variant `someday`"));
+        assert_eq!(problems[1], "id used by an earlier task");
+        assert!(report.issues[1].location.ends_with(&task.id));
+        assert_eq!(
+            problems[2],
+            "Validation error: Duration must be greater than 0"
+        );
+        assert!(problems[3].contains("Start time cannot be in the future"));
+        assert!(report.issues[4].location.ends_with("line 6"));
+        assert!(problems[5].ends_with("of the same task by 600 s"));
+    }
+
+    #[test]
+    fn test_reports_entries_outside_the_allowed_dates() {
+        let dir = TempDir::new().unwrap();
+        let data_dir = dir.path().to_path_buf();
+        let profile = Profile::new("work".to_string(), "Work".to_string()).unwrap();
+        ProfileStorage::new(data_dir.clone())
+            .save(&profile)
+            .unwrap();
+
+        let mut ahead = entry("a", 25, 30);
+        ahead.end_time = Some(Utc::now() + Duration::minutes(5));
+        let mut padded = entry("b", 60, 30);
+        padded.duration_seconds += 60;
+        EntryStorage::new(data_dir.clone())
+            .save_all("work", &[ahead, padded, entry("c", 60, 30)])
+            .unwrap();
+
+        let report = check_data_dir(&data_dir, earliest());
+        let problems = problems(&report);
+        assert_eq!(report.issues.len(), 2, "{:#?}", report.issues);
+        assert!(problems.iter().any(|p| p.contains("end_time is")));
+        assert!(problems.iter().any(|p| p.contains("duration_seconds 1860")));
+
+        let report = check_data_dir(&data_dir, Utc::now().date_naive().succ_opt().unwrap());
+        assert_eq!(report.issues.len(), 3, "{:#?}", report.issues);
+        assert!(
+            report
+                .issues
+                .iter()
+                .all(|i| i.problem.contains("start_time") || i.problem.contains("end_time"))
+        );
+    }
+}
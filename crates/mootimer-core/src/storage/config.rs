@@ -1,5 +1,6 @@
 use crate::{Result, models::Config};
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 pub struct ConfigStorage {
     config_dir: PathBuf,
@@ -36,8 +37,17 @@ impl ConfigStorage {
 
         let config_path = self.config_dir.join("config.json");
         let content = serde_json::to_string_pretty(config)?;
-        std::fs::write(config_path, content)?;
+        super::durability::write_file(&config_path, content)?;
 
         Ok(())
     }
+
+    /// Modification time of the config file, or `None` if it doesn't exist yet.
+    pub fn modified(&self) -> Result<Option<SystemTime>> {
+        match std::fs::metadata(self.config_dir.join("config.json")) {
+            Ok(metadata) => Ok(Some(metadata.modified()?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
 }
@@ -0,0 +1,254 @@
+//! Passphrase encryption of stored entries, behind the `encrypt` feature.
+//!
+//! The key is derived from the passphrase with Argon2id and a salt kept in
+//! `encryption.json` in the data directory, next to a sealed check value
+//! that tells a wrong passphrase apart from damaged data. Entry rows are
+//! sealed one at a time with XChaCha20-Poly1305 and written as hex lines of
+//! `entries.csv.enc`, so recording an entry is still an append. Each row is
+//! bound to its profile id as associated data, so a line moved into
+//! another profile's file fails to open.
+//!
+//! Only entries are encrypted. Tasks, profiles and config stay readable,
+//! and entries committed to a sync repository before encryption was turned
+//! on remain in its history.
+
+use crate::models::Entry;
+use crate::storage::EntryStorage;
+use crate::{Error, Result};
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+pub const KEY_FILE: &str = "encryption.json";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const CHECK: &[u8] = b"mootimer";
+
+/// What `encryption.json` holds. Neither field is secret.
+#[derive(Serialize, Deserialize)]
+struct KeyFile {
+    salt: String,
+    /// [`CHECK`] sealed with the derived key.
+    check: String,
+}
+
+pub struct Cipher {
+    aead: XChaCha20Poly1305,
+}
+
+impl Cipher {
+    /// The cipher for the data in `data_dir`. The first call creates the
+    /// salt and check value; later ones fail if `passphrase` is not the
+    /// one used then.
+    pub fn unlock(data_dir: &Path, passphrase: &str) -> Result<Self> {
+        let path = data_dir.join(KEY_FILE);
+        if path.exists() {
+            let key_file: KeyFile = serde_json::from_slice(&std::fs::read(&path)?)?;
+            let cipher = Self::derive(passphrase, &decode(&key_file.salt)?)?;
+            if cipher.open(&key_file.check, &[]).ok().as_deref() != Some(CHECK) {
+                return Err(Error::Validation("Wrong passphrase".to_string()));
+            }
+            return Ok(cipher);
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = Self::derive(passphrase, &salt)?;
+        let key_file = KeyFile {
+            salt: hex::encode(salt),
+            check: cipher.seal(CHECK, &[])?,
+        };
+        std::fs::create_dir_all(data_dir)?;
+        super::durability::write_file(&path, serde_json::to_vec_pretty(&key_file)?)?;
+        Ok(cipher)
+    }
+
+    fn derive(passphrase: &str, salt: &[u8]) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(Error::Validation(
+                "Passphrase must not be empty".to_string(),
+            ));
+        }
+        let mut key = Key::default();
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| Error::InvalidData(format!("Key derivation failed: {}", e)))?;
+        Ok(Self {
+            aead: XChaCha20Poly1305::new(&key),
+        })
+    }
+
+    /// `plaintext` encrypted under a fresh nonce, as hex of nonce then
+    /// ciphertext. It only opens again with the same `aad`.
+    pub fn seal(&self, plaintext: &[u8], aad: &[u8]) -> Result<String> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .aead
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .map_err(|_| Error::InvalidData("Encryption failed".to_string()))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        Ok(hex::encode(sealed))
+    }
+
+    /// Reverses [`seal`](Self::seal).
+    pub fn open(&self, sealed: &str, aad: &[u8]) -> Result<Vec<u8>> {
+        let bytes = decode(sealed)?;
+        if bytes.len() < NONCE_LEN {
+            return Err(Error::InvalidData(
+                "Encrypted line is too short".to_string(),
+            ));
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        self.aead
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|_| Error::InvalidData("Encrypted line failed to decrypt".to_string()))
+    }
+}
+
+fn decode(text: &str) -> Result<Vec<u8>> {
+    hex::decode(text.trim()).map_err(|e| Error::InvalidData(format!("Invalid hex: {}", e)))
+}
+
+static INSTALLED: OnceLock<Arc<Cipher>> = OnceLock::new();
+
+/// Makes every `EntryStorage` created from now on read and write encrypted
+/// entries. Set once, at startup.
+pub fn install(cipher: Cipher) -> Result<()> {
+    INSTALLED
+        .set(Arc::new(cipher))
+        .map_err(|_| Error::AlreadyExists("An encryption key is already installed".to_string()))
+}
+
+pub fn installed() -> Option<Arc<Cipher>> {
+    INSTALLED.get().cloned()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Migration {
+    Encrypt,
+    Decrypt,
+}
+
+/// Converts the entries of every profile in `data_dir` to or from
+/// `entries.csv.enc`, removing the old file. Returns the profiles
+/// converted; those already in the wanted form are skipped.
+pub fn migrate(data_dir: &Path, cipher: Arc<Cipher>, migration: Migration) -> Result<Vec<String>> {
+    let plain = EntryStorage::new(data_dir.to_path_buf()).with_cipher(None);
+    let encrypted = EntryStorage::new(data_dir.to_path_buf()).with_cipher(Some(cipher));
+    let (from, to) = match migration {
+        Migration::Encrypt => (&plain, &encrypted),
+        Migration::Decrypt => (&encrypted, &plain),
+    };
+
+    let profiles_dir = data_dir.join("profiles");
+    if !profiles_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut migrated = Vec::new();
+    for dir in std::fs::read_dir(profiles_dir)? {
+        let dir = dir?;
+        let Some(profile_id) = dir.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if !from.entries_path(&profile_id).exists() {
+            continue;
+        }
+        let entries: Vec<Entry> = from.load(&profile_id)?;
+        to.save_all(&profile_id, &entries)?;
+        std::fs::remove_file(from.entries_path(&profile_id))?;
+        migrated.push(profile_id);
+    }
+    migrated.sort();
+    Ok(migrated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TimerMode;
+    use tempfile::TempDir;
+
+    fn entry(task: &str) -> Entry {
+        let start = chrono::Utc::now() - chrono::Duration::hours(1);
+        Entry::create_completed(
+            Some(task.to_string()),
+            Some(format!("Notes, \"quoted\"\nfor {}", task)),
+            start,
+            start + chrono::Duration::minutes(25),
+            TimerMode::Manual,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_unlock_checks_passphrase() {
+        let dir = TempDir::new().unwrap();
+        let cipher = Cipher::unlock(dir.path(), "correct horse").unwrap();
+        let sealed = cipher.seal(b"secret", b"work").unwrap();
+        assert!(!sealed.contains("secret"));
+
+        let again = Cipher::unlock(dir.path(), "correct horse").unwrap();
+        assert_eq!(again.open(&sealed, b"work").unwrap(), b"secret");
+        assert!(again.open(&sealed, b"home").is_err());
+        assert!(matches!(
+            Cipher::unlock(dir.path(), "battery staple"),
+            Err(Error::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_encrypted_entries_roundtrip_and_migrate() {
+        let dir = TempDir::new().unwrap();
+        let data_dir = dir.path();
+        let cipher = Arc::new(Cipher::unlock(data_dir, "pass").unwrap());
+        let plain = EntryStorage::new(data_dir.to_path_buf()).with_cipher(None);
+        let encrypted = EntryStorage::new(data_dir.to_path_buf()).with_cipher(Some(cipher.clone()));
+
+        plain.append("work", &entry("api")).unwrap();
+        assert!(encrypted.load("work").is_err());
+
+        let migrated = migrate(data_dir, cipher.clone(), Migration::Encrypt).unwrap();
+        assert_eq!(migrated, vec!["work".to_string()]);
+        assert!(!plain.entries_path("work").exists());
+        assert!(plain.load("work").is_err());
+
+        encrypted.append("work", &entry("web")).unwrap();
+        let content = std::fs::read_to_string(encrypted.entries_path("work")).unwrap();
+        assert!(!content.contains("api") && !content.contains("web"));
+        let entries = encrypted.load("work").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].task_id.as_deref(), Some("web"));
+        assert_eq!(
+            entries[1].task_title.as_deref(),
+            Some("Notes, \"quoted\"\nfor web")
+        );
+
+        // A row copied into another profile's file doesn't open there.
+        let home = encrypted.entries_path("home");
+        std::fs::create_dir_all(home.parent().unwrap()).unwrap();
+        std::fs::write(&home, &content).unwrap();
+        assert!(encrypted.load("home").is_err());
+        std::fs::remove_dir_all(home.parent().unwrap()).unwrap();
+
+        migrate(data_dir, cipher, Migration::Decrypt).unwrap();
+        assert_eq!(plain.load("work").unwrap(), entries);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{Result, models::Device};
+use std::path::PathBuf;
+
+pub struct DeviceStorage {
+    config_dir: PathBuf,
+}
+
+impl DeviceStorage {
+    pub fn new(config_dir: PathBuf) -> Self {
+        Self { config_dir }
+    }
+
+    /// The stored identity, or a new one saved on first use.
+    pub fn load_or_create(&self) -> Result<Device> {
+        let path = self.config_dir.join("device.json");
+        if path.exists() {
+            let content = std::fs::read_to_string(path)?;
+            return Ok(serde_json::from_str(&content)?);
+        }
+
+        let device = Device::new();
+        self.save(&device)?;
+        Ok(device)
+    }
+
+    pub fn save(&self, device: &Device) -> Result<()> {
+        std::fs::create_dir_all(&self.config_dir)?;
+        let content = serde_json::to_string_pretty(device)?;
+        super::durability::write_file(&self.config_dir.join("device.json"), content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_identity_is_kept() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = DeviceStorage::new(temp_dir.path().to_path_buf());
+
+        let mut device = storage.load_or_create().unwrap();
+        assert_eq!(storage.load_or_create().unwrap(), device);
+
+        device.name = "laptop".to_string();
+        storage.save(&device).unwrap();
+        assert_eq!(storage.load_or_create().unwrap().name, "laptop");
+    }
+}
@@ -0,0 +1,185 @@
+//! How hard storage works to get a write onto the disk before returning.
+//!
+//! With `normal`, whole-file saves go to a temporary sibling that is
+//! fsynced and renamed over the old file, so a crash leaves the old file
+//! or the new one but never a torn one, and appends are fsynced before they
+//! return. `paranoid` also fsyncs the directory after a rename or after
+//! creating a file, so the new name itself survives a power cut. `none`
+//! writes in place and leaves flushing to the OS, which is fastest, but
+//! the last writes before a crash can be lost or cut short.
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::Result;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Durability {
+    None,
+    #[default]
+    Normal,
+    Paranoid,
+}
+
+pub const DURABILITY_LEVELS: &[&str] = &["none", "normal", "paranoid"];
+
+impl Durability {
+    pub fn as_str(&self) -> &'static str {
+        DURABILITY_LEVELS[*self as usize]
+    }
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(Durability::Normal as u8);
+
+/// Sets the policy for every write from now on. Set at startup.
+pub fn install(policy: Durability) {
+    POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+pub fn current() -> Durability {
+    match POLICY.load(Ordering::Relaxed) {
+        0 => Durability::None,
+        2 => Durability::Paranoid,
+        _ => Durability::Normal,
+    }
+}
+
+/// Replaces the file at `path` with `contents`.
+pub fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let policy = current();
+    if policy == Durability::None {
+        let mut file = File::create(path)?;
+        crash_point();
+        file.write_all(contents.as_ref())?;
+        return Ok(());
+    }
+
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let mut file = File::create(&temp_path)?;
+    file.write_all(contents.as_ref())?;
+    file.sync_all()?;
+    drop(file);
+    crash_point();
+    std::fs::rename(&temp_path, path)?;
+
+    if policy == Durability::Paranoid {
+        sync_dir(path)?;
+    }
+    Ok(())
+}
+
+/// Called once rows have been appended to `file` at `path`; `created` says
+/// whether the append created the file.
+pub fn appended(file: &File, path: &Path, created: bool) -> Result<()> {
+    match current() {
+        Durability::None => {}
+        Durability::Normal => file.sync_data()?,
+        Durability::Paranoid => {
+            file.sync_data()?;
+            if created {
+                sync_dir(path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Fsyncs the directory holding `path`, so renames and new files in it
+/// are on disk. Directories can't be opened for this on Windows, where
+/// it's skipped.
+fn sync_dir(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        File::open(dir)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// The point `write_file` dies at in crash tests, once the old contents
+/// are gone from the file (`none`) or the new ones are written but not yet
+/// renamed into place.
+fn crash_point() {
+    #[cfg(test)]
+    if std::env::var_os(tests::CRASH_VAR).is_some() {
+        std::process::abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    pub(super) const CRASH_VAR: &str = "MOOTIMER_TEST_CRASH_DIR";
+    const POLICY_VAR: &str = "MOOTIMER_TEST_CRASH_POLICY";
+
+    /// Run by `test_crash_between_write_and_rename` in a child process,
+    /// which dies inside `write_file`.
+    #[test]
+    #[ignore]
+    fn crash_child() {
+        let Some(dir) = std::env::var_os(CRASH_VAR) else {
+            return;
+        };
+        let policy = match std::env::var(POLICY_VAR).unwrap().as_str() {
+            "none" => Durability::None,
+            "normal" => Durability::Normal,
+            _ => Durability::Paranoid,
+        };
+        install(policy);
+        let _ = write_file(&Path::new(&dir).join("tasks.json"), "new");
+        unreachable!("write_file should have crashed");
+    }
+
+    #[test]
+    fn test_crash_between_write_and_rename() {
+        for (policy, survivor) in [
+            (Durability::None, ""),
+            (Durability::Normal, "old"),
+            (Durability::Paranoid, "old"),
+        ] {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("tasks.json");
+            std::fs::write(&path, "old").unwrap();
+
+            let status = Command::new(std::env::current_exe().unwrap())
+                .args(["--exact", "storage::durability::tests::crash_child"])
+                .args(["--ignored", "--test-threads=1"])
+                .env(CRASH_VAR, dir.path())
+                .env(POLICY_VAR, policy.as_str())
+                .status()
+                .unwrap();
+            assert!(!status.success(), "{:?}: child did not crash", policy);
+
+            // `none` had already emptied the file; the others leave it
+            // untouched, with the new contents stranded beside it.
+            assert_eq!(
+                std::fs::read_to_string(&path).unwrap(),
+                survivor,
+                "{:?}",
+                policy
+            );
+            let temp = dir.path().join("tasks.json.tmp");
+            if policy == Durability::None {
+                assert!(!temp.exists());
+            } else {
+                assert_eq!(std::fs::read_to_string(&temp).unwrap(), "new");
+
+                // The next save goes through as usual.
+                write_file(&path, "newer").unwrap();
+                assert_eq!(std::fs::read_to_string(&path).unwrap(), "newer");
+                assert!(!temp.exists());
+            }
+        }
+    }
+}
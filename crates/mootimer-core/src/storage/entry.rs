@@ -1,11 +1,30 @@
 use crate::{
     Result,
-    models::{Entry, TimerMode},
+    models::{Entry, EntrySource, PomodoroOutcome, TimerMode},
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+const HEADERS: [&str; 16] = [
+    "id",
+    "task_id",
+    "task_title",
+    "start_time",
+    "end_time",
+    "duration_seconds",
+    "mode",
+    "description",
+    "tags",
+    "source",
+    "pomodoro_sessions",
+    "interrupted_at_seconds",
+    "device_id",
+    "device_name",
+    "clock_anomaly",
+    "truncated_seconds",
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 struct EntryCsv {
     id: String,
@@ -18,6 +37,69 @@ struct EntryCsv {
     mode: String,
     description: String,
     tags: String,
+    /// `timer`, `manual`, `import:<origin>` or `calendar`. Empty in rows
+    /// written before sources were tracked, which all came from timers.
+    #[serde(default)]
+    source: String,
+    /// Completed work phases; empty for entries not made by a pomodoro.
+    #[serde(default)]
+    pomodoro_sessions: String,
+    /// Set when the last work phase was abandoned.
+    #[serde(default)]
+    interrupted_at_seconds: String,
+    /// Empty in rows written before devices were recorded.
+    #[serde(default)]
+    device_id: String,
+    #[serde(default)]
+    device_name: String,
+    /// `true` when the wall clock jumped during the timer, else empty.
+    #[serde(default)]
+    clock_anomaly: String,
+    /// Seconds cut from an over-long timer entry, else empty.
+    #[serde(default)]
+    truncated_seconds: String,
+}
+
+fn pomodoro_from_csv(sessions: &str, interrupted_at: &str) -> Result<Option<PomodoroOutcome>> {
+    if sessions.is_empty() {
+        return Ok(None);
+    }
+    let invalid = |e: std::num::ParseIntError| crate::Error::InvalidData(e.to_string());
+
+    let interrupted_at_seconds = if interrupted_at.is_empty() {
+        None
+    } else {
+        Some(interrupted_at.parse().map_err(invalid)?)
+    };
+    Ok(Some(PomodoroOutcome {
+        completed_sessions: sessions.parse().map_err(invalid)?,
+        completed: interrupted_at_seconds.is_none(),
+        interrupted_at_seconds,
+    }))
+}
+
+fn source_to_csv(source: &EntrySource) -> String {
+    match source {
+        EntrySource::Import { origin } => format!("import:{}", origin),
+        other => other.kind().to_string(),
+    }
+}
+
+fn source_from_csv(value: &str, mode: TimerMode) -> Result<EntrySource> {
+    match value {
+        "" | "timer" => Ok(EntrySource::Timer { timer_mode: mode }),
+        "manual" => Ok(EntrySource::Manual),
+        "calendar" => Ok(EntrySource::Calendar),
+        other => match other.strip_prefix("import:") {
+            Some(origin) => Ok(EntrySource::Import {
+                origin: origin.to_string(),
+            }),
+            None => Err(crate::Error::InvalidData(format!(
+                "Invalid source: {}",
+                other
+            ))),
+        },
+    }
 }
 
 impl From<&Entry> for EntryCsv {
@@ -29,13 +111,30 @@ impl From<&Entry> for EntryCsv {
             start_time: entry.start_time.to_rfc3339(),
             end_time: entry.end_time.map(|t| t.to_rfc3339()).unwrap_or_default(),
             duration_seconds: entry.duration_seconds,
-            mode: match entry.mode {
-                TimerMode::Manual => "manual".to_string(),
-                TimerMode::Pomodoro => "pomodoro".to_string(),
-                TimerMode::Countdown => "countdown".to_string(),
-            },
+            mode: entry.mode.to_string(),
             description: entry.description.clone().unwrap_or_default(),
             tags: entry.tags.join(","),
+            source: source_to_csv(&entry.source),
+            pomodoro_sessions: entry
+                .pomodoro
+                .map(|p| p.completed_sessions.to_string())
+                .unwrap_or_default(),
+            interrupted_at_seconds: entry
+                .pomodoro
+                .and_then(|p| p.interrupted_at_seconds)
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            device_id: entry.device_id.clone().unwrap_or_default(),
+            device_name: entry.device_name.clone().unwrap_or_default(),
+            clock_anomaly: if entry.clock_anomaly {
+                "true".to_string()
+            } else {
+                String::new()
+            },
+            truncated_seconds: match entry.truncated_seconds {
+                0 => String::new(),
+                seconds => seconds.to_string(),
+            },
         }
     }
 }
@@ -44,6 +143,13 @@ impl TryFrom<EntryCsv> for Entry {
     type Error = crate::Error;
 
     fn try_from(csv: EntryCsv) -> Result<Self> {
+        let mode = match csv.mode.as_str() {
+            "manual" => TimerMode::Manual,
+            "pomodoro" => TimerMode::Pomodoro,
+            "countdown" => TimerMode::Countdown,
+            _ => TimerMode::Manual,
+        };
+
         Ok(Self {
             id: csv.id,
             task_id: if csv.task_id.is_empty() {
@@ -69,12 +175,7 @@ impl TryFrom<EntryCsv> for Entry {
                 )
             },
             duration_seconds: csv.duration_seconds,
-            mode: match csv.mode.as_str() {
-                "manual" => TimerMode::Manual,
-                "pomodoro" => TimerMode::Pomodoro,
-                "countdown" => TimerMode::Countdown,
-                _ => TimerMode::Manual,
-            },
+            mode,
             description: if csv.description.is_empty() {
                 None
             } else {
@@ -85,27 +186,133 @@ impl TryFrom<EntryCsv> for Entry {
             } else {
                 csv.tags.split(',').map(|s| s.trim().to_string()).collect()
             },
+            source: source_from_csv(&csv.source, mode)?,
+            pomodoro: pomodoro_from_csv(&csv.pomodoro_sessions, &csv.interrupted_at_seconds)?,
+            device_id: (!csv.device_id.is_empty()).then_some(csv.device_id),
+            device_name: (!csv.device_name.is_empty()).then_some(csv.device_name),
+            clock_anomaly: csv.clock_anomaly == "true",
+            truncated_seconds: if csv.truncated_seconds.is_empty() {
+                0
+            } else {
+                csv.truncated_seconds.parse().map_err(|e| {
+                    crate::Error::InvalidData(format!("Invalid truncated_seconds: {}", e))
+                })?
+            },
         })
     }
 }
 
+/// Parses the contents of an `entries.csv`, as read from git history.
+pub fn entries_from_csv(content: &[u8]) -> Result<Vec<Entry>> {
+    let mut reader = csv::Reader::from_reader(content);
+    reader
+        .deserialize::<EntryCsv>()
+        .map(|row| Entry::try_from(row?))
+        .collect()
+}
+
+/// Each row of an `entries.csv` on its own, so one bad row doesn't hide
+/// the rest.
+pub(super) fn entry_rows_from_csv(content: &[u8]) -> Vec<Result<Entry>> {
+    let mut reader = csv::Reader::from_reader(content);
+    reader
+        .deserialize::<EntryCsv>()
+        .map(|row| Entry::try_from(row?))
+        .collect()
+}
+
+/// The contents `EntryStorage::save_all` writes for `entries`.
+pub fn entries_to_csv(entries: &[Entry]) -> Result<Vec<u8>> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new());
+    writer.write_record(HEADERS)?;
+    for entry in entries {
+        writer.serialize(EntryCsv::from(entry))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| crate::Error::InvalidData(format!("Failed to write CSV: {}", e)))
+}
+
+const PLAIN_FILE: &str = "entries.csv";
+/// Where entries go when encrypted; see `storage::crypto`.
+const ENCRYPTED_FILE: &str = "entries.csv.enc";
+
 pub struct EntryStorage {
     data_dir: PathBuf,
+    #[cfg(feature = "encrypt")]
+    cipher: Option<std::sync::Arc<super::crypto::Cipher>>,
 }
 
 impl EntryStorage {
+    /// Storage under `data_dir`, encrypted if a key was installed with
+    /// `crypto::install`.
     pub fn new(data_dir: PathBuf) -> Self {
-        Self { data_dir }
+        Self {
+            data_dir,
+            #[cfg(feature = "encrypt")]
+            cipher: super::crypto::installed(),
+        }
+    }
+
+    /// Reads and writes encrypted entries with `cipher`, or plain ones
+    /// with `None`, whatever was installed.
+    #[cfg(feature = "encrypt")]
+    pub fn with_cipher(mut self, cipher: Option<std::sync::Arc<super::crypto::Cipher>>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    fn encrypted(&self) -> bool {
+        #[cfg(feature = "encrypt")]
+        return self.cipher.is_some();
+        #[cfg(not(feature = "encrypt"))]
+        false
+    }
+
+    /// The file this storage keeps `profile_id`'s entries in.
+    pub fn entries_path(&self, profile_id: &str) -> PathBuf {
+        let file = if self.encrypted() {
+            ENCRYPTED_FILE
+        } else {
+            PLAIN_FILE
+        };
+        self.data_dir.join("profiles").join(profile_id).join(file)
+    }
+
+    /// Refuses to work next to entries stored the other way, which would
+    /// otherwise look like an empty profile and be written over.
+    fn check_form(&self, profile_id: &str) -> Result<()> {
+        let profile_dir = self.data_dir.join("profiles").join(profile_id);
+        if self.entries_path(profile_id).exists() {
+            return Ok(());
+        }
+        if self.encrypted() && profile_dir.join(PLAIN_FILE).exists() {
+            return Err(crate::Error::InvalidData(format!(
+                "Entries of profile '{}' are not encrypted yet; run `mootimerd storage migrate --encrypt`",
+                profile_id
+            )));
+        }
+        if !self.encrypted() && profile_dir.join(ENCRYPTED_FILE).exists() {
+            return Err(crate::Error::InvalidData(format!(
+                "Entries of profile '{}' are encrypted; start mootimerd with --encrypted",
+                profile_id
+            )));
+        }
+        Ok(())
     }
 
     pub fn load(&self, profile_id: &str) -> Result<Vec<Entry>> {
+        self.check_form(profile_id)?;
+        #[cfg(feature = "encrypt")]
+        if let Some(cipher) = &self.cipher {
+            return self.load_encrypted(profile_id, cipher);
+        }
+
         self.migrate(profile_id)?;
 
-        let entries_path = self
-            .data_dir
-            .join("profiles")
-            .join(profile_id)
-            .join("entries.csv");
+        let entries_path = self.entries_path(profile_id);
 
         if !entries_path.exists() {
             return Ok(Vec::new());
@@ -128,7 +335,7 @@ impl EntryStorage {
             .data_dir
             .join("profiles")
             .join(profile_id)
-            .join("entries.csv");
+            .join(PLAIN_FILE);
 
         if !entries_path.exists() {
             return Ok(());
@@ -140,7 +347,8 @@ impl EntryStorage {
 
         if let Some(result) = rdr.records().next() {
             let record = result?;
-            if record.iter().any(|f| f == "task_title") {
+            // Files are migrated whenever the last column is missing.
+            if record.iter().any(|f| f == HEADERS[HEADERS.len() - 1]) {
                 return Ok(());
             }
         } else {
@@ -155,27 +363,22 @@ impl EntryStorage {
             .flexible(true)
             .from_path(&backup_path)?;
 
-        let mut wtr = csv::Writer::from_path(&entries_path)?;
+        let file = std::fs::File::create(&entries_path)?;
+        let mut wtr = csv::Writer::from_writer(&file);
 
-        wtr.write_record([
-            "id",
-            "task_id",
-            "task_title",
-            "start_time",
-            "end_time",
-            "duration_seconds",
-            "mode",
-            "description",
-            "tags",
-        ])?;
+        wtr.write_record(HEADERS)?;
 
         let mut records = rdr.records();
         let _ = records.next();
 
         for result in records {
             let record = result?;
-            if record.len() >= 9 {
+            if record.len() >= HEADERS.len() {
                 wtr.write_record(&record)?;
+            } else if record.len() >= 9 {
+                let mut new_record: Vec<String> = record.iter().map(str::to_string).collect();
+                new_record.resize(HEADERS.len(), String::new());
+                wtr.write_record(&new_record)?;
             } else if record.len() == 8 {
                 let mut new_record = Vec::new();
                 new_record.push(record[0].to_string());
@@ -184,19 +387,37 @@ impl EntryStorage {
                 for i in 2..8 {
                     new_record.push(record[i].to_string());
                 }
+                new_record.resize(HEADERS.len(), String::new());
                 wtr.write_record(&new_record)?;
             }
         }
 
         wtr.flush()?;
-        Ok(())
+        drop(wtr);
+        // Covers the rename to the backup too, as both are in this
+        // directory.
+        super::durability::appended(&file, &entries_path, true)
     }
 
     pub fn append(&self, profile_id: &str, entry: &Entry) -> Result<()> {
+        self.append_all(profile_id, std::slice::from_ref(entry))
+    }
+
+    /// Appends several entries with a single open and flush of the file.
+    pub fn append_all(&self, profile_id: &str, entries: &[Entry]) -> Result<()> {
+        self.check_form(profile_id)?;
+        #[cfg(feature = "encrypt")]
+        if let Some(cipher) = &self.cipher {
+            return self.append_encrypted(profile_id, entries, cipher);
+        }
+
+        // Rows must match the header of the file they land in.
+        self.migrate(profile_id)?;
+
         let profile_dir = self.data_dir.join("profiles").join(profile_id);
         std::fs::create_dir_all(&profile_dir)?;
 
-        let entries_path = profile_dir.join("entries.csv");
+        let entries_path = self.entries_path(profile_id);
         let file_exists = entries_path.exists();
 
         let file = std::fs::OpenOptions::new()
@@ -206,43 +427,127 @@ impl EntryStorage {
 
         let mut writer = csv::WriterBuilder::new()
             .has_headers(false)
-            .from_writer(file);
-
-        let entry_csv = EntryCsv::from(entry);
+            .from_writer(&file);
 
         if !file_exists {
-            writer.write_record([
-                "id",
-                "task_id",
-                "task_title",
-                "start_time",
-                "end_time",
-                "duration_seconds",
-                "mode",
-                "description",
-                "tags",
-            ])?;
-        }
-
-        writer.serialize(&entry_csv)?;
+            writer.write_record(HEADERS)?;
+        }
+
+        for entry in entries {
+            writer.serialize(EntryCsv::from(entry))?;
+        }
         writer.flush()?;
+        drop(writer);
 
-        Ok(())
+        super::durability::appended(&file, &entries_path, !file_exists)
     }
 
     pub fn save_all(&self, profile_id: &str, entries: &[Entry]) -> Result<()> {
         let profile_dir = self.data_dir.join("profiles").join(profile_id);
         std::fs::create_dir_all(&profile_dir)?;
 
-        let entries_path = profile_dir.join("entries.csv");
-        let mut writer = csv::Writer::from_path(entries_path)?;
+        #[cfg(feature = "encrypt")]
+        if let Some(cipher) = &self.cipher {
+            let content = entries_to_sealed(entries, profile_id, cipher)?;
+            super::durability::write_file(&self.entries_path(profile_id), content)?;
+            return Ok(());
+        }
+
+        super::durability::write_file(&self.entries_path(profile_id), entries_to_csv(entries)?)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "encrypt")]
+    fn load_encrypted(
+        &self,
+        profile_id: &str,
+        cipher: &super::crypto::Cipher,
+    ) -> Result<Vec<Entry>> {
+        let path = self.entries_path(profile_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        entries_from_sealed(&std::fs::read(path)?, profile_id, cipher)
+    }
 
+    #[cfg(feature = "encrypt")]
+    fn append_encrypted(
+        &self,
+        profile_id: &str,
+        entries: &[Entry],
+        cipher: &super::crypto::Cipher,
+    ) -> Result<()> {
+        use std::io::Write;
+
+        let path = self.entries_path(profile_id);
+        // Files with an older header are rewritten, as `migrate` does for
+        // plain ones, so every row has the same columns.
+        let header_current = match std::fs::read_to_string(&path) {
+            Ok(content) => match content.lines().next() {
+                Some(line) => cipher.open(line, profile_id.as_bytes())? == csv_row(HEADERS)?,
+                None => false,
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+            Err(e) => return Err(e.into()),
+        };
+        if !header_current {
+            let mut all = self.load_encrypted(profile_id, cipher)?;
+            all.extend_from_slice(entries);
+            return self.save_all(profile_id, &all);
+        }
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path)?;
         for entry in entries {
-            let entry_csv = EntryCsv::from(entry);
-            writer.serialize(&entry_csv)?;
+            let row = csv_row(EntryCsv::from(entry))?;
+            writeln!(file, "{}", cipher.seal(&row, profile_id.as_bytes())?)?;
         }
+        file.flush()?;
+        super::durability::appended(&file, &path, false)
+    }
+}
 
-        writer.flush()?;
-        Ok(())
+/// The entries of an `entries.csv.enc`. The header line is sealed like the
+/// rows, and decrypting every line gives back an ordinary `entries.csv`.
+#[cfg(feature = "encrypt")]
+pub fn entries_from_sealed(
+    content: &[u8],
+    profile_id: &str,
+    cipher: &super::crypto::Cipher,
+) -> Result<Vec<Entry>> {
+    let mut csv = Vec::new();
+    for line in String::from_utf8_lossy(content).lines() {
+        if !line.trim().is_empty() {
+            csv.extend(cipher.open(line, profile_id.as_bytes())?);
+        }
     }
+    entries_from_csv(&csv)
+}
+
+/// Reverses [`entries_from_sealed`].
+#[cfg(feature = "encrypt")]
+pub fn entries_to_sealed(
+    entries: &[Entry],
+    profile_id: &str,
+    cipher: &super::crypto::Cipher,
+) -> Result<Vec<u8>> {
+    let aad = profile_id.as_bytes();
+    let mut lines = vec![cipher.seal(&csv_row(HEADERS)?, aad)?];
+    for entry in entries {
+        lines.push(cipher.seal(&csv_row(EntryCsv::from(entry))?, aad)?);
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    Ok(content.into_bytes())
+}
+
+/// One CSV record, with its line ending.
+#[cfg(feature = "encrypt")]
+fn csv_row(record: impl Serialize) -> Result<Vec<u8>> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new());
+    writer.serialize(record)?;
+    writer
+        .into_inner()
+        .map_err(|e| crate::Error::InvalidData(format!("Failed to write CSV: {}", e)))
 }
@@ -1,12 +1,25 @@
+pub mod breaks;
+pub mod budget;
+pub mod check;
 pub mod config;
+#[cfg(feature = "encrypt")]
+pub mod crypto;
+pub mod device;
+pub mod durability;
 pub mod entry;
 pub mod profile;
 pub mod task;
+pub mod usage;
 
+pub use breaks::BreakStorage;
+pub use budget::BudgetStorage;
 pub use config::ConfigStorage;
+pub use device::DeviceStorage;
+pub use durability::Durability;
 pub use entry::EntryStorage;
 pub use profile::ProfileStorage;
 pub use task::TaskStorage;
+pub use usage::UsageStorage;
 
 use std::path::PathBuf;
 
@@ -19,7 +19,7 @@ impl ProfileStorage {
 
         let content = std::fs::read_to_string(profile_path)?;
         let profile: Profile = serde_json::from_str(&content)?;
-        Ok(profile)
+        Ok(profile.with_defaults())
     }
 
     pub fn save(&self, profile: &Profile) -> Result<()> {
@@ -28,7 +28,7 @@ impl ProfileStorage {
 
         let profile_path = profile_dir.join("profile.json");
         let content = serde_json::to_string_pretty(profile)?;
-        std::fs::write(profile_path, content)?;
+        super::durability::write_file(&profile_path, content)?;
 
         Ok(())
     }
@@ -1,4 +1,7 @@
-use crate::{Result, models::Task};
+use crate::{
+    Result,
+    models::{Task, TaskStatus},
+};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -7,6 +10,42 @@ struct TasksFile {
     tasks: Vec<Task>,
 }
 
+/// Parses the contents of a `tasks.json`, as read from git history.
+pub fn tasks_from_json(content: &[u8]) -> Result<Vec<Task>> {
+    let tasks_file: TasksFile = serde_json::from_slice(content)?;
+    Ok(tasks_file.tasks)
+}
+
+/// The contents `TaskStorage::save` writes for `tasks`.
+pub fn tasks_to_json(tasks: &[Task]) -> Result<Vec<u8>> {
+    let tasks_file = TasksFile {
+        tasks: tasks.to_vec(),
+    };
+    Ok(serde_json::to_vec_pretty(&tasks_file)?)
+}
+
+/// Whether a `tasks.json` still holds a status older versions wrote, so
+/// loading it should write it back in the current form.
+fn has_legacy_status(content: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(content);
+    if !TaskStatus::LEGACY_NAMES
+        .iter()
+        .any(|name| text.contains(&format!("\"{}\"", name)))
+    {
+        return false;
+    }
+    serde_json::from_slice::<serde_json::Value>(content)
+        .ok()
+        .and_then(|file| file["tasks"].as_array().cloned())
+        .is_some_and(|tasks| {
+            tasks.iter().any(|task| {
+                task["status"]
+                    .as_str()
+                    .is_some_and(|s| TaskStatus::LEGACY_NAMES.contains(&s))
+            })
+        })
+}
+
 pub struct TaskStorage {
     data_dir: PathBuf,
 }
@@ -27,9 +66,12 @@ impl TaskStorage {
             return Ok(Vec::new());
         }
 
-        let content = std::fs::read_to_string(tasks_path)?;
-        let tasks_file: TasksFile = serde_json::from_str(&content)?;
-        Ok(tasks_file.tasks)
+        let content = std::fs::read(tasks_path)?;
+        let tasks = tasks_from_json(&content)?;
+        if has_legacy_status(&content) {
+            self.save(profile_id, &tasks)?;
+        }
+        Ok(tasks)
     }
 
     pub fn save(&self, profile_id: &str, tasks: &[Task]) -> Result<()> {
@@ -37,12 +79,57 @@ impl TaskStorage {
         std::fs::create_dir_all(&profile_dir)?;
 
         let tasks_path = profile_dir.join("tasks.json");
-        let tasks_file = TasksFile {
-            tasks: tasks.to_vec(),
-        };
-        let content = serde_json::to_string_pretty(&tasks_file)?;
-        std::fs::write(tasks_path, content)?;
+        super::durability::write_file(&tasks_path, tasks_to_json(tasks)?)?;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const LEGACY_TASKS: &str = r#"{
+  "tasks": [
+    {
+      "id": "t1",
+      "title": "Ship it",
+      "source": "manual",
+      "status": "completed",
+      "tags": [],
+      "created_at": "2024-01-01T09:00:00Z",
+      "updated_at": "2024-01-02T09:00:00Z"
+    },
+    {
+      "id": "t2",
+      "title": "Not called completed",
+      "source": "manual",
+      "status": "in_progress",
+      "tags": ["completed"],
+      "created_at": "2024-01-01T09:00:00Z",
+      "updated_at": "2024-01-01T09:00:00Z"
+    }
+  ]
+}"#;
+
+    #[test]
+    fn test_load_normalizes_legacy_statuses() {
+        let dir = TempDir::new().unwrap();
+        let storage = TaskStorage::new(dir.path().to_path_buf());
+        let path = dir.path().join("profiles").join("work").join("tasks.json");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, LEGACY_TASKS).unwrap();
+
+        let tasks = storage.load("work").unwrap();
+        assert_eq!(tasks[0].status, TaskStatus::Done);
+        assert_eq!(tasks[1].status, TaskStatus::InProgress);
+
+        let rewritten = std::fs::read(&path).unwrap();
+        assert!(!has_legacy_status(&rewritten));
+        let file: serde_json::Value = serde_json::from_slice(&rewritten).unwrap();
+        assert_eq!(file["tasks"][0]["status"], "done");
+        assert_eq!(file["tasks"][1]["tags"][0], "completed");
+        assert_eq!(storage.load("work").unwrap(), tasks);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Client name recorded for calls from connections that never said who
+/// they are in `system.hello`.
+pub const UNKNOWN_CLIENT: &str = "unknown";
+
+/// One month of RPC call counts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageMonth {
+    /// `YYYY-MM`.
+    pub month: String,
+    /// Calls by local date (`YYYY-MM-DD`), then method, then client name.
+    pub days: BTreeMap<String, BTreeMap<String, BTreeMap<String, u64>>>,
+}
+
+impl UsageMonth {
+    pub fn new(month: &str) -> Self {
+        Self {
+            month: month.to_string(),
+            days: BTreeMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, day: &str, method: &str, client: &str) {
+        *self
+            .days
+            .entry(day.to_string())
+            .or_default()
+            .entry(method.to_string())
+            .or_default()
+            .entry(client.to_string())
+            .or_default() += 1;
+    }
+
+    /// Every count as `(day, method, client, calls)`.
+    pub fn counts(&self) -> impl Iterator<Item = (&str, &str, &str, u64)> {
+        self.days.iter().flat_map(|(day, methods)| {
+            methods.iter().flat_map(move |(method, clients)| {
+                clients.iter().map(move |(client, calls)| {
+                    (day.as_str(), method.as_str(), client.as_str(), *calls)
+                })
+            })
+        })
+    }
+}
+
+/// Local usage counts, in `usage/<YYYY-MM>.json`.
+pub struct UsageStorage {
+    data_dir: PathBuf,
+}
+
+impl UsageStorage {
+    pub fn new(data_dir: PathBuf) -> Self {
+        Self { data_dir }
+    }
+
+    fn dir(&self) -> PathBuf {
+        self.data_dir.join("usage")
+    }
+
+    fn path(&self, month: &str) -> PathBuf {
+        self.dir().join(format!("{}.json", month))
+    }
+
+    pub fn load(&self, month: &str) -> Result<UsageMonth> {
+        let path = self.path(month);
+        if !path.exists() {
+            return Ok(UsageMonth::new(month));
+        }
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    pub fn save(&self, usage: &UsageMonth) -> Result<()> {
+        std::fs::create_dir_all(self.dir())?;
+        super::durability::write_file(&self.path(&usage.month), serde_json::to_vec_pretty(usage)?)?;
+        Ok(())
+    }
+
+    /// Months with a file, oldest first.
+    pub fn months(&self) -> Result<Vec<String>> {
+        let dir = self.dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut months: Vec<String> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.strip_suffix(".json"))
+                    .map(str::to_string)
+            })
+            .collect();
+        months.sort();
+        Ok(months)
+    }
+
+    /// Deletes all but the newest `keep` months.
+    pub fn prune(&self, keep: usize) -> Result<()> {
+        let months = self.months()?;
+        for month in &months[..months.len().saturating_sub(keep)] {
+            std::fs::remove_file(self.path(month))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use chrono::{Duration, Utc};
+use criterion::{Criterion, criterion_group, criterion_main};
+use mootimer_core::models::{Entry, TimerMode, WorkingHours};
+use mootimer_daemon::EntryManager;
+use mootimer_daemon::entry::{DailyStats, StatsWindow};
+use std::hint::black_box;
+
+fn fixture(count: i64) -> Vec<Entry> {
+    let base = Utc::now() - Duration::days(365);
+    (0..count)
+        .map(|i| {
+            let start = base + Duration::minutes(i * 30);
+            let mode = if i % 3 == 0 {
+                TimerMode::Manual
+            } else {
+                TimerMode::Pomodoro
+            };
+            Entry::create_completed(None, None, start, start + Duration::minutes(25), mode).unwrap()
+        })
+        .collect()
+}
+
+fn bench_calculate_stats(c: &mut Criterion) {
+    let entries = fixture(10_000);
+
+    c.bench_function("entry_manager_calculate_stats_10k", |b| {
+        b.iter(|| black_box(EntryManager::calculate_stats(black_box(&entries))))
+    });
+}
+
+/// What a dashboard asking for today, 7 and 30 days costs by filtering all
+/// entries per window, against summing the cached daily totals.
+fn bench_bulk_windows(c: &mut Criterion) {
+    let entries = fixture(20_000);
+    let today = Utc::now().date_naive();
+    let windows = [
+        StatsWindow::Today,
+        StatsWindow::LastDays(7),
+        StatsWindow::LastDays(30),
+    ];
+
+    c.bench_function("stats_windows_filtered_20k", |b| {
+        b.iter(|| {
+            for window in &windows {
+                let (from, _) = window.bounds(today);
+                let in_window: Vec<Entry> = entries
+                    .iter()
+                    .filter(|e| e.start_time.date_naive() >= from)
+                    .cloned()
+                    .collect();
+                black_box(EntryManager::calculate_stats(&in_window));
+            }
+        })
+    });
+
+    let daily = DailyStats::from_entries(&entries, WorkingHours::default());
+    c.bench_function("stats_windows_daily_cache_20k", |b| {
+        b.iter(|| {
+            for window in &windows {
+                let (from, to) = window.bounds(today);
+                black_box(daily.stats(from, to));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_calculate_stats, bench_bulk_windows);
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+use mootimer_core::storage::{check, get_data_dir};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use super::{ApiError, Result};
+use crate::budget::BudgetManager;
+use crate::config::ConfigManager;
+use crate::entry::{EntryManager, EntryManagerError};
+use crate::journal::{JOURNAL_CAPACITY, Journal, UndoError};
+use crate::profile::ProfileManager;
+use crate::task::TaskManager;
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub(crate) struct UndoParams {
+    /// The newest operation not yet undone when absent.
+    op_id: Option<u64>,
+}
+
+/// Internal counters for keeping an eye on the daemon, such as how often
+/// `stats.bulk` could answer from its daily totals.
+pub async fn stats(entry_manager: &Arc<EntryManager>, _params: Option<Value>) -> Result<Value> {
+    Ok(json!({
+        "stats_cache": entry_manager.stats_cache_counters(),
+    }))
+}
+
+/// What `mootimerd --check` reports for the data directory, as
+/// `{ profiles, tasks, entries, issues: [{ location, problem }], skipped }`.
+/// Entries are read from disk, so ones stored before a rule existed show
+/// up too.
+pub async fn check(config_manager: &Arc<ConfigManager>, _params: Option<Value>) -> Result<Value> {
+    let earliest = config_manager.get().await.daemon.earliest_entry_date;
+    let report =
+        tokio::task::spawn_blocking(move || check::check_data_dir(&get_data_dir(), earliest))
+            .await
+            .map_err(|e| EntryManagerError::JoinError(e.to_string()))?;
+
+    let issues: Vec<Value> = report
+        .issues
+        .iter()
+        .map(|issue| json!({ "location": issue.location, "problem": issue.problem }))
+        .collect();
+    Ok(json!({
+        "profiles": report.profiles,
+        "tasks": report.tasks,
+        "entries": report.entries,
+        "issues": issues,
+        "skipped": report.skipped,
+    }))
+}
+
+/// The last mutating calls, newest first, as
+/// `[{ op_id, method, at, undoable, undone, partial }]`.
+pub async fn journal(journal: &Journal, _params: Option<Value>) -> Result<Value> {
+    Ok(json!({
+        "capacity": JOURNAL_CAPACITY,
+        "operations": journal.records().await,
+    }))
+}
+
+/// Reverses one journaled call. Later calls are left alone, so undoing an
+/// update whose entry was deleted since fails rather than bringing it back.
+pub async fn undo(
+    journal: &Journal,
+    entry_manager: &Arc<EntryManager>,
+    task_manager: &Arc<TaskManager>,
+    profile_manager: &Arc<ProfileManager>,
+    budget_manager: &Arc<BudgetManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: UndoParams = match params {
+        Some(params) => serde_json::from_value(params)?,
+        None => UndoParams::default(),
+    };
+
+    let record = journal
+        .undo(
+            params.op_id,
+            entry_manager,
+            task_manager,
+            profile_manager,
+            budget_manager,
+        )
+        .await
+        .map_err(|e| match e {
+            UndoError::NotFound(_) | UndoError::Empty => ApiError::InvalidParams(e.to_string()),
+            _ => ApiError::Conflict(e.to_string()),
+        })?;
+
+    Ok(json!({ "status": "undone", "operation": record }))
+}
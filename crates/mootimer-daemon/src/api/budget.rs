@@ -0,0 +1,98 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use super::{ApiError, Result};
+use crate::budget::BudgetManager;
+use crate::task::TaskManager;
+use mootimer_core::models::{Budget, BudgetAction, BudgetPeriod, BudgetScope};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ProfileIdParams {
+    profile_id: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct CreateBudgetParams {
+    profile_id: String,
+    scope: BudgetScope,
+    period: BudgetPeriod,
+    limit_seconds: u64,
+    #[serde(default)]
+    action: BudgetAction,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct DeleteBudgetParams {
+    profile_id: String,
+    budget_id: String,
+}
+
+/// The profile's budgets with their use in the current period.
+pub async fn list(manager: &Arc<BudgetManager>, params: Option<Value>) -> Result<Value> {
+    let params: ProfileIdParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let statuses = manager.status(&params.profile_id).await?;
+    Ok(serde_json::to_value(&statuses)?)
+}
+
+pub async fn create(manager: &Arc<BudgetManager>, params: Option<Value>) -> Result<Value> {
+    let params: CreateBudgetParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let budget = Budget::new(
+        params.scope,
+        params.period,
+        params.limit_seconds,
+        params.action,
+    )?;
+    let created = manager.create(&params.profile_id, budget).await?;
+    Ok(serde_json::to_value(&created)?)
+}
+
+pub async fn delete(manager: &Arc<BudgetManager>, params: Option<Value>) -> Result<Value> {
+    let params: DeleteBudgetParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    manager
+        .delete(&params.profile_id, &params.budget_id)
+        .await?;
+    Ok(json!({ "deleted": params.budget_id }))
+}
+
+/// "10h 05m".
+fn hours_minutes(seconds: u64) -> String {
+    format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60)
+}
+
+/// Refuses a timer start that a used-up `block_new_timers` budget covers.
+pub async fn check_timer_start(
+    budget_manager: &Arc<BudgetManager>,
+    task_manager: &Arc<TaskManager>,
+    params: Option<&Value>,
+) -> Result<()> {
+    let Some(profile_id) = params.and_then(|p| p["profile_id"].as_str()) else {
+        return Ok(());
+    };
+    let task = match params.and_then(|p| p["task_id"].as_str()) {
+        Some(task_id) => task_manager.get(profile_id, task_id).await.ok(),
+        None => None,
+    };
+
+    let blocking = budget_manager.blocking(profile_id, task.as_ref()).await?;
+    match blocking {
+        Some(status) => Err(ApiError::Conflict(format!(
+            "Budget for {} is used up: {} of {} (budget {})",
+            status.description,
+            hours_minutes(status.used_seconds),
+            hours_minutes(status.budget.limit_seconds),
+            status.budget.id
+        ))),
+        None => Ok(()),
+    }
+}
@@ -1,29 +1,97 @@
+use mootimer_core::messages;
+use mootimer_core::models::Config;
+use schemars::JsonSchema;
 use serde::Deserialize;
-use serde_json::Value;
+use serde_json::{Value, json};
 use std::sync::Arc;
 
 use super::{ApiError, Result};
 use crate::config::ConfigManager;
+use crate::entry::EntryManager;
 
-#[derive(Debug, Deserialize)]
-struct SetDefaultProfileParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SetDefaultProfileParams {
     profile_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct UpdatePomodoroParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SetCurrentUserParams {
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SetDeviceNameParams {
+    name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct UpdateDaemonParams {
+    socket_path: Option<String>,
+    log_level: Option<String>,
+    max_timers_per_profile: Option<usize>,
+    strict_task_blocking: Option<bool>,
+    locale: Option<String>,
+    usage_metrics: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct UpdatePomodoroParams {
     work_duration: Option<u64>,
     short_break: Option<u64>,
     long_break: Option<u64>,
     sessions_until_long_break: Option<u32>,
     countdown_default: Option<u64>,
+    auto_start_next_session: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
-struct UpdateSyncParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct UpdateSyncParams {
     auto_commit: Option<bool>,
     auto_push: Option<bool>,
     remote_url: Option<String>,
+    /// Removes the remote URL; `remote_url: null` leaves it unchanged.
+    clear_remote_url: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct UpdateTuiParams {
+    status_bar_format: Option<String>,
+    timezone: Option<String>,
+    countdown_presets: Option<Vec<u64>>,
+    pomodoro_animation: Option<String>,
+    countdown_animation: Option<String>,
+    locale: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ImportParams {
+    /// A config as returned by `config.export`.
+    config: Value,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub(crate) struct MessagesParams {
+    /// Defaults to `daemon.locale`.
+    locale: Option<String>,
+}
+
+/// Checks each provided field against its `Config::describe` descriptor, so
+/// updates are held to the same constraints `config.describe` advertises.
+/// `null` means "leave unchanged" and is skipped.
+fn check_fields(section: &str, params: &Value) -> Result<()> {
+    let Some(fields) = params.as_object() else {
+        return Ok(());
+    };
+
+    for (key, value) in fields {
+        if value.is_null() {
+            continue;
+        }
+        if let Some(field) = Config::field(&format!("{}.{}", section, key)) {
+            field.check(value)?;
+        }
+    }
+    Ok(())
 }
 
 pub async fn get(manager: &Arc<ConfigManager>, _params: Option<Value>) -> Result<Value> {
@@ -31,6 +99,28 @@ pub async fn get(manager: &Arc<ConfigManager>, _params: Option<Value>) -> Result
     Ok(serde_json::to_value(&config)?)
 }
 
+/// The notification message catalog, so front ends can show text in a
+/// locale of their own. Keys a locale lacks come back in English.
+pub async fn messages(manager: &Arc<ConfigManager>, params: Option<Value>) -> Result<Value> {
+    let params: MessagesParams = match params {
+        Some(params) => serde_json::from_value(params)?,
+        None => MessagesParams::default(),
+    };
+    let locale = match params.locale {
+        Some(locale) => locale,
+        None => manager.get().await.daemon.locale,
+    };
+
+    Ok(json!({
+        "locale": locale,
+        "messages": messages::catalog(&locale),
+    }))
+}
+
+pub async fn describe(_params: Option<Value>) -> Result<Value> {
+    Ok(serde_json::to_value(Config::describe())?)
+}
+
 pub async fn set_default_profile(
     manager: &Arc<ConfigManager>,
     params: Option<Value>,
@@ -39,18 +129,67 @@ pub async fn set_default_profile(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let config = manager
-        .set_default_profile(params.profile_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let config = manager.set_default_profile(params.profile_id).await?;
 
     Ok(serde_json::to_value(&config)?)
 }
 
-pub async fn update_pomodoro(manager: &Arc<ConfigManager>, params: Option<Value>) -> Result<Value> {
-    let params: UpdatePomodoroParams = serde_json::from_value(
+pub async fn set_current_user(
+    manager: &Arc<ConfigManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: SetCurrentUserParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
+    let name = params
+        .name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+
+    let config = manager.set_current_user(name).await?;
+
+    Ok(serde_json::to_value(&config)?)
+}
+
+/// Renames this machine. Entries already stamped keep the old name.
+pub async fn set_device_name(
+    manager: &Arc<ConfigManager>,
+    entry_manager: &Arc<EntryManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: SetDeviceNameParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let device = manager.set_device_name(&params.name)?;
+    entry_manager.set_device(device.clone());
+
+    Ok(serde_json::to_value(&device)?)
+}
+
+pub async fn update_daemon(manager: &Arc<ConfigManager>, params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?;
+    check_fields("daemon", &params)?;
+    let params: UpdateDaemonParams = serde_json::from_value(params)?;
+
+    let config = manager
+        .update_daemon_config(
+            params.socket_path,
+            params.log_level,
+            params.max_timers_per_profile,
+            params.strict_task_blocking,
+            params.locale,
+            params.usage_metrics,
+        )
+        .await?;
+
+    Ok(serde_json::to_value(&config)?)
+}
+
+pub async fn update_pomodoro(manager: &Arc<ConfigManager>, params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?;
+    check_fields("pomodoro", &params)?;
+    let params: UpdatePomodoroParams = serde_json::from_value(params)?;
 
     let config = manager
         .update_pomodoro_config(
@@ -59,31 +198,78 @@ pub async fn update_pomodoro(manager: &Arc<ConfigManager>, params: Option<Value>
             params.long_break,
             params.sessions_until_long_break,
             params.countdown_default,
+            params.auto_start_next_session,
         )
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        .await?;
 
     Ok(serde_json::to_value(&config)?)
 }
 
 pub async fn update_sync(manager: &Arc<ConfigManager>, params: Option<Value>) -> Result<Value> {
-    let params: UpdateSyncParams = serde_json::from_value(
-        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
-    )?;
+    let params = params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?;
+    check_fields("sync", &params)?;
+    let params: UpdateSyncParams = serde_json::from_value(params)?;
+
+    let remote_url = match (params.remote_url, params.clear_remote_url) {
+        (Some(_), Some(true)) => {
+            return Err(ApiError::InvalidParams(
+                "remote_url and clear_remote_url are mutually exclusive".to_string(),
+            ));
+        }
+        (_, Some(true)) => Some(None),
+        (url, _) => url.map(Some),
+    };
 
     let config = manager
-        .update_sync_config(params.auto_commit, params.auto_push, params.remote_url)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        .update_sync_config(params.auto_commit, params.auto_push, remote_url)
+        .await?;
 
     Ok(serde_json::to_value(&config)?)
 }
 
-pub async fn reset(manager: &Arc<ConfigManager>, _params: Option<Value>) -> Result<Value> {
+pub async fn update_tui(manager: &Arc<ConfigManager>, params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?;
+    check_fields("tui", &params)?;
+    let params: UpdateTuiParams = serde_json::from_value(params)?;
+
     let config = manager
-        .reset_to_default()
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        .update_tui_config(
+            params.status_bar_format,
+            params.timezone,
+            params.countdown_presets,
+            params.pomodoro_animation,
+            params.countdown_animation,
+            params.locale,
+        )
+        .await?;
+
+    Ok(serde_json::to_value(&config)?)
+}
+
+/// The whole config, for `config.import` on another machine.
+pub async fn export(manager: &Arc<ConfigManager>, _params: Option<Value>) -> Result<Value> {
+    let config = manager.get().await;
+    Ok(serde_json::to_value(&config)?)
+}
+
+/// Replaces the config with an exported one; see `Config::import`.
+pub async fn import(manager: &Arc<ConfigManager>, params: Option<Value>) -> Result<Value> {
+    let params: ImportParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+    let (config, clamped) =
+        Config::import(&params.config).map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+
+    let config = manager.update(config).await?;
+
+    Ok(json!({
+        "config": config,
+        "clamped": clamped,
+    }))
+}
+
+pub async fn reset(manager: &Arc<ConfigManager>, _params: Option<Value>) -> Result<Value> {
+    let config = manager.reset_to_default().await?;
 
     Ok(serde_json::to_value(&config)?)
 }
@@ -94,14 +280,34 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_config() {
-        let manager = Arc::new(ConfigManager::new().unwrap());
+        let manager = Arc::new(ConfigManager::default());
         let result = get(&manager, None).await.unwrap();
         assert!(result.get("version").is_some());
     }
 
+    #[tokio::test]
+    async fn test_messages_follow_daemon_locale_unless_asked() {
+        let manager = Arc::new(ConfigManager::default());
+        let result = messages(&manager, None).await.unwrap();
+        assert_eq!(result["locale"], "en");
+        assert_eq!(
+            result["messages"]["phase.work_completed.body"],
+            "Time for a break!"
+        );
+
+        let params = serde_json::json!({ "locale": "de" });
+        let result = messages(&manager, Some(params)).await.unwrap();
+        assert_eq!(
+            result["messages"]["phase.work_completed.body"],
+            "Zeit für eine Pause!"
+        );
+        // Untranslated keys come back in English.
+        assert_eq!(result["messages"]["phase.completed.title"], "🍅 Pomodoro");
+    }
+
     #[tokio::test]
     async fn test_update_pomodoro() {
-        let manager = Arc::new(ConfigManager::new().unwrap());
+        let manager = Arc::new(ConfigManager::default());
 
         let params = serde_json::json!({
             "work_duration": 1800
@@ -117,4 +323,98 @@ mod tests {
             1800
         );
     }
+
+    #[tokio::test]
+    async fn test_update_daemon_rejects_invalid_log_level() {
+        let manager = Arc::new(ConfigManager::default());
+
+        let params = serde_json::json!({
+            "log_level": "loud"
+        });
+
+        assert!(update_daemon(&manager, Some(params)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_tui_rejects_unknown_variable() {
+        let manager = Arc::new(ConfigManager::default());
+
+        let params = serde_json::json!({
+            "status_bar_format": "{profile} {weather}"
+        });
+
+        let err = update_tui(&manager, Some(params)).await.unwrap_err();
+        assert!(err.to_string().contains("{weather}"));
+    }
+
+    #[tokio::test]
+    async fn test_update_sync_rejects_setting_and_clearing_remote_url() {
+        let manager = Arc::new(ConfigManager::default());
+
+        let params = serde_json::json!({ "remote_url": "x", "clear_remote_url": true });
+        assert!(update_sync(&manager, Some(params)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_set_current_user_trims_and_clears() {
+        let manager = Arc::new(ConfigManager::default());
+
+        let params = serde_json::json!({ "name": "  Ada  " });
+        let result = set_current_user(&manager, Some(params)).await.unwrap();
+        assert_eq!(result["current_user"], "Ada");
+
+        let params = serde_json::json!({ "name": " " });
+        let result = set_current_user(&manager, Some(params)).await.unwrap();
+        assert!(result["current_user"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_describe() {
+        let result = describe(None).await.unwrap();
+        let fields = result.as_array().unwrap();
+        let work = fields
+            .iter()
+            .find(|f| f["path"] == "pomodoro.work_duration")
+            .unwrap();
+        assert_eq!(work["type"], "duration");
+        assert_eq!(work["min"], 1);
+        assert_eq!(work["requires_restart"], false);
+    }
+
+    #[tokio::test]
+    async fn test_export_then_import() {
+        let manager = Arc::new(ConfigManager::default());
+        let params = serde_json::json!({ "work_duration": 1800 });
+        update_pomodoro(&manager, Some(params)).await.unwrap();
+        let mut exported = export(&manager, None).await.unwrap();
+
+        reset(&manager, None).await.unwrap();
+        exported["pomodoro"]["long_break"] = serde_json::json!(99999);
+        let params = serde_json::json!({ "config": exported });
+        let result = import(&manager, Some(params)).await.unwrap();
+        assert_eq!(
+            result["clamped"],
+            serde_json::json!(["pomodoro.long_break clamped from 99999 to 7200"])
+        );
+        let config = manager.get().await;
+        assert_eq!(config.pomodoro.work_duration, 1800);
+        assert_eq!(config.pomodoro.long_break, 7200);
+
+        let params = serde_json::json!({ "config": { "pomodoro": { "focus": 1 } } });
+        let err = import(&manager, Some(params)).await.unwrap_err();
+        assert!(err.to_string().contains("pomodoro.focus"));
+        assert_eq!(manager.get().await.pomodoro.work_duration, 1800);
+    }
+
+    #[tokio::test]
+    async fn test_update_pomodoro_checks_descriptor() {
+        let manager = Arc::new(ConfigManager::default());
+
+        let params = serde_json::json!({
+            "work_duration": 0
+        });
+
+        let err = update_pomodoro(&manager, Some(params)).await.unwrap_err();
+        assert!(err.to_string().contains("pomodoro.work_duration"));
+    }
 }
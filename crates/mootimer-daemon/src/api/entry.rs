@@ -1,54 +1,206 @@
-use chrono::{DateTime, Utc};
-use mootimer_core::models::{Entry, TimerMode};
+use chrono::{DateTime, NaiveDate, Utc};
+use mootimer_core::models::{Entry, EntrySource, TimerMode};
+use mootimer_core::query::QueryParser;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_json::{Value, json};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use super::{ApiError, Result};
-use crate::entry::{EntryFilter, EntryManager};
+use crate::entry::{EntryFilter, EntryManager, EntryManagerError, EntryStats, StatsWindow};
 use crate::profile::ProfileManager;
 use crate::task::TaskManager;
 
-#[derive(Debug, Deserialize)]
-struct ListEntriesParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ListEntriesParams {
     profile_id: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct CreateEntryParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct CreateEntryParams {
     profile_id: String,
+    /// Required unless `start_offset_minutes_ago` is given.
+    start_time: Option<DateTime<Utc>>,
+    /// Start this many minutes before now, instead of at `start_time`.
+    /// Without `end_time` or `duration_seconds` the entry ends now.
+    start_offset_minutes_ago: Option<u64>,
+    /// Give this or `duration_seconds`.
+    end_time: Option<DateTime<Utc>>,
+    duration_seconds: Option<u64>,
+    task_id: Option<String>,
+    description: Option<String>,
+    /// Store the entry even if it looks like one already logged.
+    #[serde(default)]
+    allow_duplicate: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct CreateBatchParams {
+    profile_id: String,
+    entries: Vec<EntryInput>,
+    /// Store the valid entries and report the rest, instead of storing
+    /// nothing when any entry is invalid.
+    #[serde(default)]
+    partial: bool,
+    #[serde(default)]
+    allow_duplicate: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct EntryInput {
     start_time: DateTime<Utc>,
     end_time: DateTime<Utc>,
     task_id: Option<String>,
     description: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct FilterEntriesParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct FindDuplicatesParams {
+    profile_id: String,
+    start_date: Option<DateTime<Utc>>,
+    end_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct FilterEntriesParams {
     profile_id: String,
     start_date: Option<DateTime<Utc>>,
     end_date: Option<DateTime<Utc>>,
     task_id: Option<String>,
     tags: Option<Vec<String>>,
+    source: Option<String>,
+    /// Device id or name.
+    device: Option<String>,
+    /// The same conditions as one string, e.g.
+    /// `task:api tag:billable after:monday mode:pomodoro`. The fields above
+    /// fill in whatever the query leaves unset.
+    query: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct StatsParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct StatsParams {
     profile_id: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct DeleteEntryParams {
+/// Both days included.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct Period {
+    start: NaiveDate,
+    end: NaiveDate,
+}
+
+impl Period {
+    fn window(&self) -> Result<StatsWindow> {
+        if self.end < self.start {
+            return Err(ApiError::InvalidParams(format!(
+                "Period ends ({}) before it starts ({})",
+                self.end, self.start
+            )));
+        }
+        Ok(StatsWindow::Range {
+            from: self.start,
+            to: self.end,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct StatsCompareParams {
+    profile_id: String,
+    period_a: Period,
+    period_b: Period,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct StatsHourlyParams {
+    profile_id: String,
+    /// First day, included. Open when absent.
+    start_date: Option<NaiveDate>,
+    /// Last day, included. Open when absent.
+    end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct EntryIdParams {
     profile_id: String,
     entry_id: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct UpdateEntryParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct CopyToProfileParams {
+    source_profile_id: String,
+    entry_id: String,
+    target_profile_id: String,
+    /// Delete the original once the copy is stored.
+    #[serde(default, rename = "move")]
+    move_entry: bool,
+    /// Task in the target profile to log the entry against instead of the
+    /// entry's own.
+    task_id: Option<String>,
+    #[serde(default)]
+    allow_duplicate: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct UpdateEntryParams {
     profile_id: String,
+    /// A whole entry as `entry.list` returns it.
+    #[schemars(with = "Value")]
     entry: mootimer_core::models::Entry,
 }
 
+/// How far past the daemon's clock a computed end time may be, for
+/// clients whose clock runs slightly ahead.
+const END_TIME_SKEW_SECONDS: i64 = 60;
+
+/// The start and end of a new entry, from whichever of the time fields
+/// were given.
+fn resolve_times(
+    params: &CreateEntryParams,
+    now: DateTime<Utc>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    let invalid = |message: &str| ApiError::InvalidParams(message.to_string());
+
+    let start = match (params.start_time, params.start_offset_minutes_ago) {
+        (Some(start), None) => start,
+        (None, Some(minutes)) => i64::try_from(minutes)
+            .ok()
+            .and_then(chrono::Duration::try_minutes)
+            .and_then(|offset| now.checked_sub_signed(offset))
+            .ok_or_else(|| invalid("start_offset_minutes_ago is too large"))?,
+        (Some(_), Some(_)) => {
+            return Err(invalid(
+                "Give start_time or start_offset_minutes_ago, not both",
+            ));
+        }
+        (None, None) => {
+            return Err(invalid(
+                "start_time or start_offset_minutes_ago is required",
+            ));
+        }
+    };
+
+    let end = match (params.end_time, params.duration_seconds) {
+        (Some(end), None) => end,
+        (None, Some(seconds)) => i64::try_from(seconds)
+            .ok()
+            .and_then(chrono::Duration::try_seconds)
+            .and_then(|duration| start.checked_add_signed(duration))
+            .ok_or_else(|| invalid("duration_seconds is too large"))?,
+        (None, None) if params.start_offset_minutes_ago.is_some() => now,
+        (Some(_), Some(_)) => return Err(invalid("Give end_time or duration_seconds, not both")),
+        (None, None) => return Err(invalid("end_time or duration_seconds is required")),
+    };
+
+    if end > now + chrono::Duration::seconds(END_TIME_SKEW_SECONDS) {
+        return Err(invalid("Entry cannot end in the future"));
+    }
+    Ok((start, end))
+}
+
 pub async fn create(
     entry_manager: &Arc<EntryManager>,
     task_manager: &Arc<TaskManager>,
@@ -57,6 +209,7 @@ pub async fn create(
     let params: CreateEntryParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
+    let (start_time, end_time) = resolve_times(&params, Utc::now())?;
 
     let task_title = if let Some(ref task_id) = params.task_id {
         task_manager
@@ -71,46 +224,197 @@ pub async fn create(
     let mut entry = Entry::create_completed(
         params.task_id,
         task_title,
-        params.start_time,
-        params.end_time,
+        start_time,
+        end_time,
         TimerMode::Manual,
-    )
-    .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    )?;
+    entry.source = EntrySource::Manual;
 
     if let Some(desc) = params.description {
         entry.update_description(Some(desc));
     }
 
     let entry = entry_manager
-        .add(&params.profile_id, entry)
+        .add_with(&params.profile_id, entry, params.allow_duplicate)
         .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        .map_err(|e| match e {
+            EntryManagerError::Duplicate { .. } => ApiError::Conflict(format!(
+                "{}. Pass allow_duplicate: true to log it anyway",
+                e
+            )),
+            e => e.into(),
+        })?;
+
+    Ok(serde_json::to_value(&entry)?)
+}
+
+/// Creates many manual entries in one call. Returns
+/// `{ created, ids, skipped, errors: [{ index, message }] }`; without `partial`
+/// any invalid entry fails the call and nothing is stored.
+pub async fn create_batch(
+    entry_manager: &Arc<EntryManager>,
+    task_manager: &Arc<TaskManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: CreateBatchParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let mut task_titles: HashMap<String, Option<String>> = HashMap::new();
+    let mut entries = Vec::with_capacity(params.entries.len());
+    for input in params.entries {
+        let task_title = match input.task_id {
+            Some(ref task_id) => match task_titles.get(task_id) {
+                Some(title) => title.clone(),
+                None => {
+                    let title = task_manager
+                        .get(&params.profile_id, task_id)
+                        .await
+                        .ok()
+                        .map(|t| t.title);
+                    task_titles.insert(task_id.clone(), title.clone());
+                    title
+                }
+            },
+            None => None,
+        };
+
+        // Built field by field rather than with `create_completed`, so a bad
+        // time range is reported by `validate` with the entry's index.
+        let mut entry = Entry::new(input.task_id, task_title, TimerMode::Manual);
+        entry.source = EntrySource::Manual;
+        entry.start_time = input.start_time;
+        entry.end_time = Some(input.end_time);
+        entry.duration_seconds = input
+            .end_time
+            .signed_duration_since(input.start_time)
+            .num_seconds()
+            .max(0) as u64;
+        entry.tags = input.tags;
+        entry.update_description(input.description);
+        entries.push(entry);
+    }
+
+    let outcome = entry_manager
+        .add_batch(
+            &params.profile_id,
+            entries,
+            params.allow_duplicate,
+            params.partial,
+        )
+        .await?;
+
+    let ids: Vec<&str> = outcome.created.iter().map(|e| e.id.as_str()).collect();
+    Ok(json!({
+        "created": outcome.created.len(),
+        "ids": ids,
+        "skipped": outcome.errors.len(),
+        "errors": outcome.errors,
+    }))
+}
+
+/// Entries in the range that look like copies of earlier ones, each as
+/// `{ entry, duplicate_of }`.
+pub async fn find_duplicates(manager: &Arc<EntryManager>, params: Option<Value>) -> Result<Value> {
+    let params: FindDuplicatesParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let duplicates = manager
+        .find_duplicates(&params.profile_id, params.start_date, params.end_date)
+        .await?;
+
+    Ok(serde_json::to_value(&duplicates)?)
+}
+
+pub async fn get_by_id(manager: &Arc<EntryManager>, params: Option<Value>) -> Result<Value> {
+    let params: EntryIdParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let entry = manager.get(&params.profile_id, &params.entry_id).await?;
 
     Ok(serde_json::to_value(&entry)?)
 }
 
 pub async fn delete(manager: &Arc<EntryManager>, params: Option<Value>) -> Result<Value> {
-    let params: DeleteEntryParams = serde_json::from_value(
+    let params: EntryIdParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    manager
-        .delete(&params.profile_id, &params.entry_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    manager.delete(&params.profile_id, &params.entry_id).await?;
 
     Ok(json!({ "status": "deleted", "id": params.entry_id }))
 }
 
+/// Copies an entry into another profile, or moves it when `move` is set.
+/// Its task must exist in the target profile unless `task_id` names one
+/// that does. A copy gets a new id; a moved entry keeps its own.
+pub async fn copy_to_profile(
+    entry_manager: &Arc<EntryManager>,
+    task_manager: &Arc<TaskManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: CopyToProfileParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    if params.source_profile_id == params.target_profile_id {
+        return Err(ApiError::InvalidParams(
+            "Source and target profile are the same".to_string(),
+        ));
+    }
+
+    let mut entry = entry_manager
+        .get(&params.source_profile_id, &params.entry_id)
+        .await?;
+
+    if let Some(task_id) = params.task_id.or(entry.task_id.take()) {
+        let task = task_manager
+            .get(&params.target_profile_id, &task_id)
+            .await
+            .map_err(|_| {
+                ApiError::InvalidParams(format!(
+                    "Task {} does not exist in profile {}; pass task_id to pick one that does",
+                    task_id, params.target_profile_id
+                ))
+            })?;
+        entry.task_id = Some(task.id);
+        entry.task_title = Some(task.title);
+    }
+    if !params.move_entry {
+        entry.id = uuid::Uuid::new_v4().to_string();
+    }
+
+    let entry = entry_manager
+        .add_with(&params.target_profile_id, entry, params.allow_duplicate)
+        .await
+        .map_err(|e| match e {
+            EntryManagerError::Duplicate { .. } => ApiError::Conflict(format!(
+                "{}. Pass allow_duplicate: true to copy it anyway",
+                e
+            )),
+            e => e.into(),
+        })?;
+
+    if params.move_entry {
+        entry_manager
+            .delete(&params.source_profile_id, &params.entry_id)
+            .await?;
+    }
+
+    Ok(json!({ "entry": entry, "moved": params.move_entry }))
+}
+
 pub async fn update(manager: &Arc<EntryManager>, params: Option<Value>) -> Result<Value> {
     let params: UpdateEntryParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
     manager
-        .update(&params.profile_id, params.entry)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        .check(&params.entry)
+        .map_err(|e| EntryManagerError::Invalid(e.to_string()))?;
+    manager.update(&params.profile_id, params.entry).await?;
 
     Ok(json!({ "status": "updated" }))
 }
@@ -120,30 +424,60 @@ pub async fn list(manager: &Arc<EntryManager>, params: Option<Value>) -> Result<
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let entries = manager
-        .get_all(&params.profile_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let entries = manager.get_all(&params.profile_id).await?;
 
     Ok(serde_json::to_value(&entries)?)
 }
 
+/// The most recently completed entry, or null if there are none.
+pub async fn last(manager: &Arc<EntryManager>, params: Option<Value>) -> Result<Value> {
+    let params: ListEntriesParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let entry = manager.last(&params.profile_id).await?;
+
+    Ok(serde_json::to_value(&entry)?)
+}
+
+/// Stored entries that fail validation, each as `{ entry, problem }`.
+pub async fn list_invalid(manager: &Arc<EntryManager>, params: Option<Value>) -> Result<Value> {
+    let params: ListEntriesParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let invalid = manager.list_invalid(&params.profile_id).await?;
+
+    Ok(serde_json::to_value(&invalid)?)
+}
+
 pub async fn filter(manager: &Arc<EntryManager>, params: Option<Value>) -> Result<Value> {
     let params: FilterEntriesParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let filter = EntryFilter {
-        start_date: params.start_date,
-        end_date: params.end_date,
-        task_id: params.task_id,
-        tags: params.tags,
+    if let Some(ref source) = params.source
+        && !EntrySource::KINDS.contains(&source.as_str())
+    {
+        return Err(ApiError::InvalidParams(format!(
+            "Invalid source '{}'. Must be one of: {}",
+            source,
+            EntrySource::KINDS.join(", ")
+        )));
+    }
+
+    let mut filter = match params.query.as_deref() {
+        Some(query) => QueryParser::default().parse(query)?,
+        None => EntryFilter::default(),
     };
+    filter.start_date = filter.start_date.or(params.start_date);
+    filter.end_date = filter.end_date.or(params.end_date);
+    filter.task_id = filter.task_id.or(params.task_id);
+    filter.tags = filter.tags.or(params.tags);
+    filter.source = filter.source.or(params.source);
+    filter.device = filter.device.or(params.device);
 
-    let entries = manager
-        .filter(&params.profile_id, filter)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let entries = manager.filter(&params.profile_id, filter).await?;
 
     Ok(serde_json::to_value(&entries)?)
 }
@@ -153,10 +487,7 @@ pub async fn get_today(manager: &Arc<EntryManager>, params: Option<Value>) -> Re
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let entries = manager
-        .get_today(&params.profile_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let entries = manager.get_today(&params.profile_id).await?;
 
     Ok(serde_json::to_value(&entries)?)
 }
@@ -166,10 +497,7 @@ pub async fn get_week(manager: &Arc<EntryManager>, params: Option<Value>) -> Res
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let entries = manager
-        .get_week(&params.profile_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let entries = manager.get_week(&params.profile_id).await?;
 
     Ok(serde_json::to_value(&entries)?)
 }
@@ -179,72 +507,142 @@ pub async fn get_month(manager: &Arc<EntryManager>, params: Option<Value>) -> Re
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let entries = manager
-        .get_month(&params.profile_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let entries = manager.get_month(&params.profile_id).await?;
 
     Ok(serde_json::to_value(&entries)?)
 }
 
-pub async fn stats_today(manager: &Arc<EntryManager>, params: Option<Value>) -> Result<Value> {
+pub async fn stats_today(
+    manager: &Arc<EntryManager>,
+    profile_manager: &Arc<ProfileManager>,
+    params: Option<Value>,
+) -> Result<Value> {
     let params: StatsParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let stats = manager
-        .get_today_stats(&params.profile_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    use_working_hours(manager, profile_manager, &params.profile_id).await;
+    let stats = manager.get_today_stats(&params.profile_id).await?;
 
-    Ok(json!({
-        "total_entries": stats.total_entries,
-        "total_duration_seconds": stats.total_duration_seconds,
-        "total_duration_hours": stats.total_duration_hours,
-        "pomodoro_count": stats.pomodoro_count,
-        "manual_count": stats.manual_count,
-        "avg_duration_seconds": stats.avg_duration_seconds,
-    }))
+    Ok(stats_json(&stats))
 }
 
-pub async fn stats_week(manager: &Arc<EntryManager>, params: Option<Value>) -> Result<Value> {
+pub async fn stats_week(
+    manager: &Arc<EntryManager>,
+    profile_manager: &Arc<ProfileManager>,
+    params: Option<Value>,
+) -> Result<Value> {
     let params: StatsParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let stats = manager
-        .get_week_stats(&params.profile_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    use_working_hours(manager, profile_manager, &params.profile_id).await;
+    let stats = manager.get_week_stats(&params.profile_id).await?;
 
-    Ok(json!({
-        "total_entries": stats.total_entries,
-        "total_duration_seconds": stats.total_duration_seconds,
-        "total_duration_hours": stats.total_duration_hours,
-        "pomodoro_count": stats.pomodoro_count,
-        "manual_count": stats.manual_count,
-        "avg_duration_seconds": stats.avg_duration_seconds,
-    }))
+    Ok(stats_json(&stats))
 }
 
-pub async fn stats_month(manager: &Arc<EntryManager>, params: Option<Value>) -> Result<Value> {
+pub async fn stats_month(
+    manager: &Arc<EntryManager>,
+    profile_manager: &Arc<ProfileManager>,
+    params: Option<Value>,
+) -> Result<Value> {
     let params: StatsParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let stats = manager
-        .get_month_stats(&params.profile_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    use_working_hours(manager, profile_manager, &params.profile_id).await;
+    let stats = manager.get_month_stats(&params.profile_id).await?;
+
+    Ok(stats_json(&stats))
+}
+
+/// Stats for two runs of days side by side, e.g. last week as `period_a`
+/// and this week as `period_b`. `delta` is `period_b` minus `period_a`;
+/// `percent_change` is the change in time relative to `period_a`, `null`
+/// when `period_a` has none.
+pub async fn stats_compare(
+    manager: &Arc<EntryManager>,
+    profile_manager: &Arc<ProfileManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: StatsCompareParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+    let windows = [params.period_a.window()?, params.period_b.window()?];
+
+    use_working_hours(manager, profile_manager, &params.profile_id).await;
+
+    let stats = manager.bulk_stats(&params.profile_id, &windows).await?;
+    let (a, b) = (&stats[0], &stats[1]);
+
+    let percent_change = (a.total_duration_seconds > 0).then(|| {
+        (b.total_duration_seconds as f64 - a.total_duration_seconds as f64)
+            / a.total_duration_seconds as f64
+            * 100.0
+    });
 
     Ok(json!({
+        "period_a": stats_json(a),
+        "period_b": stats_json(b),
+        "delta": {
+            "total_seconds_delta": b.total_duration_seconds as i64 - a.total_duration_seconds as i64,
+            "session_count_delta": b.total_entries as i64 - a.total_entries as i64,
+            "percent_change": percent_change,
+        },
+    }))
+}
+
+/// Average session length for each hour of the day entries started in,
+/// hours 0 to 23 in the daemon's timezone.
+pub async fn stats_hourly(manager: &Arc<EntryManager>, params: Option<Value>) -> Result<Value> {
+    let params: StatsHourlyParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+    if let (Some(start), Some(end)) = (params.start_date, params.end_date)
+        && end < start
+    {
+        return Err(ApiError::InvalidParams(format!(
+            "end_date ({}) is before start_date ({})",
+            end, start
+        )));
+    }
+
+    let hours = manager
+        .hourly_stats(&params.profile_id, params.start_date, params.end_date)
+        .await?;
+
+    Ok(serde_json::to_value(&hours)?)
+}
+
+/// Hands the profile's working hours to the entry manager before it works
+/// out stats. Profiles that don't exist keep the default hours.
+pub(super) async fn use_working_hours(
+    manager: &Arc<EntryManager>,
+    profile_manager: &Arc<ProfileManager>,
+    profile_id: &str,
+) {
+    if let Ok(profile) = profile_manager.get(profile_id).await {
+        manager
+            .set_working_hours(profile_id, profile.working_hours)
+            .await;
+    }
+}
+
+pub(super) fn stats_json(stats: &EntryStats) -> Value {
+    json!({
         "total_entries": stats.total_entries,
         "total_duration_seconds": stats.total_duration_seconds,
         "total_duration_hours": stats.total_duration_hours,
         "pomodoro_count": stats.pomodoro_count,
         "manual_count": stats.manual_count,
         "avg_duration_seconds": stats.avg_duration_seconds,
-    }))
+        "by_source": stats.by_source,
+        "pomodoro": stats.pomodoro,
+        "break_compliance": stats.break_compliance,
+        "top_task": stats.top_task,
+        "working_hours": stats.working_hours,
+    })
 }
 
 pub async fn get_today_all_profiles(
@@ -252,18 +650,12 @@ pub async fn get_today_all_profiles(
     profile_manager: &Arc<ProfileManager>,
     _params: Option<Value>,
 ) -> Result<Value> {
-    let profiles = profile_manager
-        .list()
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let profiles = profile_manager.list().await?;
 
     let mut all_entries = Vec::new();
 
     for profile in profiles {
-        let entries = entry_manager
-            .get_today(&profile.id)
-            .await
-            .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        let entries = entry_manager.get_today(&profile.id).await?;
 
         for entry in entries {
             let mut entry_value = serde_json::to_value(&entry)?;
@@ -282,18 +674,12 @@ pub async fn get_week_all_profiles(
     profile_manager: &Arc<ProfileManager>,
     _params: Option<Value>,
 ) -> Result<Value> {
-    let profiles = profile_manager
-        .list()
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let profiles = profile_manager.list().await?;
 
     let mut all_entries = Vec::new();
 
     for profile in profiles {
-        let entries = entry_manager
-            .get_week(&profile.id)
-            .await
-            .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        let entries = entry_manager.get_week(&profile.id).await?;
 
         for entry in entries {
             let mut entry_value = serde_json::to_value(&entry)?;
@@ -312,18 +698,12 @@ pub async fn get_month_all_profiles(
     profile_manager: &Arc<ProfileManager>,
     _params: Option<Value>,
 ) -> Result<Value> {
-    let profiles = profile_manager
-        .list()
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let profiles = profile_manager.list().await?;
 
     let mut all_entries = Vec::new();
 
     for profile in profiles {
-        let entries = entry_manager
-            .get_month(&profile.id)
-            .await
-            .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        let entries = entry_manager.get_month(&profile.id).await?;
 
         for entry in entries {
             let mut entry_value = serde_json::to_value(&entry)?;
@@ -345,6 +725,54 @@ mod tests {
 
     const TEST_PROFILE: &str = "test_entry_api";
 
+    #[test]
+    fn test_resolve_times() {
+        let now = Utc::now();
+        let resolve = |params: Value| {
+            let params: CreateEntryParams = serde_json::from_value(params).unwrap();
+            resolve_times(&params, now)
+        };
+        let start = now - chrono::Duration::hours(2);
+
+        assert_eq!(
+            resolve(json!({
+                "profile_id": "p",
+                "start_time": start,
+                "duration_seconds": 1800,
+            }))
+            .unwrap(),
+            (start, start + chrono::Duration::minutes(30))
+        );
+        assert_eq!(
+            resolve(json!({ "profile_id": "p", "start_offset_minutes_ago": 45 })).unwrap(),
+            (now - chrono::Duration::minutes(45), now)
+        );
+        assert_eq!(
+            resolve(json!({
+                "profile_id": "p",
+                "start_offset_minutes_ago": 60,
+                "duration_seconds": 600,
+            }))
+            .unwrap()
+            .1,
+            now - chrono::Duration::minutes(50)
+        );
+
+        for params in [
+            json!({ "profile_id": "p", "start_time": start }),
+            json!({ "profile_id": "p", "end_time": now }),
+            json!({ "profile_id": "p", "start_time": start, "start_offset_minutes_ago": 5 }),
+            json!({ "profile_id": "p", "start_time": start, "end_time": now, "duration_seconds": 5 }),
+            json!({ "profile_id": "p", "start_offset_minutes_ago": 10, "duration_seconds": 3600 }),
+        ] {
+            assert!(
+                matches!(resolve(params.clone()), Err(ApiError::InvalidParams(_))),
+                "{}",
+                params
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_list_entries() {
         let event_manager = Arc::new(EventManager::new());
@@ -361,14 +789,143 @@ mod tests {
     #[tokio::test]
     async fn test_stats_today() {
         let event_manager = Arc::new(EventManager::new());
-        let manager = Arc::new(EntryManager::new(event_manager).unwrap());
+        let manager = Arc::new(EntryManager::new(event_manager.clone()).unwrap());
+        let profiles = Arc::new(ProfileManager::new(event_manager).unwrap());
 
         let params = json!({
             "profile_id": TEST_PROFILE
         });
 
-        let result = stats_today(&manager, Some(params)).await.unwrap();
+        let result = stats_today(&manager, &profiles, Some(params))
+            .await
+            .unwrap();
         assert!(result.get("total_entries").is_some());
         assert!(result.get("total_duration_hours").is_some());
     }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_stats_compare() {
+        use chrono::{Duration, TimeZone};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+            std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config"));
+        }
+        let event_manager = Arc::new(EventManager::new());
+        let manager = Arc::new(EntryManager::new(event_manager.clone()).unwrap());
+        let profiles = Arc::new(ProfileManager::new(event_manager).unwrap());
+
+        let add = |day: u32, minutes: i64| {
+            let start = Utc.with_ymd_and_hms(2024, 5, day, 9, 0, 0).unwrap();
+            Entry::create_completed(
+                None,
+                None,
+                start,
+                start + Duration::minutes(minutes),
+                TimerMode::Manual,
+            )
+            .unwrap()
+        };
+        for entry in [add(6, 60), add(14, 30), add(15, 60)] {
+            manager.add("compare_test", entry).await.unwrap();
+        }
+
+        let compare = |params: Value| stats_compare(&manager, &profiles, Some(params));
+        let result = compare(json!({
+            "profile_id": "compare_test",
+            "period_a": { "start": "2024-05-06", "end": "2024-05-12" },
+            "period_b": { "start": "2024-05-13", "end": "2024-05-19" },
+        }))
+        .await
+        .unwrap();
+        assert_eq!(result["period_a"]["total_duration_seconds"], 3600);
+        assert_eq!(result["period_b"]["total_duration_seconds"], 5400);
+        assert_eq!(result["delta"]["total_seconds_delta"], 1800);
+        assert_eq!(result["delta"]["session_count_delta"], 1);
+        assert_eq!(result["delta"]["percent_change"], 50.0);
+
+        let empty_a = compare(json!({
+            "profile_id": "compare_test",
+            "period_a": { "start": "2024-04-01", "end": "2024-04-07" },
+            "period_b": { "start": "2024-05-13", "end": "2024-05-19" },
+        }))
+        .await
+        .unwrap();
+        assert_eq!(empty_a["delta"]["percent_change"], Value::Null);
+
+        let backwards = compare(json!({
+            "profile_id": "compare_test",
+            "period_a": { "start": "2024-05-12", "end": "2024-05-06" },
+            "period_b": { "start": "2024-05-13", "end": "2024-05-19" },
+        }))
+        .await;
+        assert!(backwards.is_err());
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_filter_by_query() {
+        use chrono::{Duration, TimeZone};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+            std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config"));
+        }
+        let event_manager = Arc::new(EventManager::new());
+        let manager = Arc::new(EntryManager::new(event_manager).unwrap());
+
+        let add = |task: &str, day: u32, mode: TimerMode| {
+            let start = Utc.with_ymd_and_hms(2024, 5, day, 12, 0, 0).unwrap();
+            Entry::create_completed(
+                Some(task.to_string()),
+                None,
+                start,
+                start + Duration::minutes(25),
+                mode,
+            )
+            .unwrap()
+        };
+        for entry in [
+            add("api", 2, TimerMode::Pomodoro),
+            add("api", 20, TimerMode::Pomodoro),
+            add("api", 21, TimerMode::Manual),
+            add("web", 22, TimerMode::Pomodoro),
+        ] {
+            manager.add("query_test", entry).await.unwrap();
+        }
+
+        let count = |params: Value| {
+            let manager = manager.clone();
+            async move {
+                filter(&manager, Some(params))
+                    .await
+                    .map(|v| v.as_array().unwrap().len())
+            }
+        };
+        let query = |q: &str| json!({ "profile_id": "query_test", "query": q });
+
+        assert_eq!(count(query("task:api mode:pomodoro")).await.unwrap(), 2);
+        assert_eq!(
+            count(query("task:api after:2024-05-10 mode:pomodoro"))
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            count(json!({
+                "profile_id": "query_test",
+                "query": "mode:pomodoro",
+                "task_id": "web",
+            }))
+            .await
+            .unwrap(),
+            1
+        );
+        assert!(count(query("project:api")).await.is_err());
+    }
 }
@@ -1,22 +1,37 @@
+pub mod admin;
+pub mod budget;
 pub mod config;
 pub mod entry;
 pub mod profile;
+pub mod stats;
+pub mod status;
 pub mod sync;
 pub mod task;
 pub mod timer;
+pub mod usage;
 
 use serde_json::{Value, json};
 use std::sync::Arc;
 use tokio::sync::broadcast;
 
-use crate::config::ConfigManager;
-use crate::entry::EntryManager;
+use crate::budget::{BudgetManager, BudgetManagerError};
+use crate::config::{ConfigManager, ConfigManagerError};
+use crate::entry::{EntryManager, EntryManagerError};
 use crate::event_manager::EventManager;
 use crate::events::DaemonEvent;
-use crate::profile::ProfileManager;
-use crate::sync::SyncManager;
-use crate::task::TaskManager;
-use crate::timer::TimerManager;
+use crate::ipc::protocol::{
+    CONFLICT, FORBIDDEN, INTERNAL_ERROR, INVALID_PARAMS, METHOD_NOT_FOUND, NOT_CONFIGURED,
+    NOT_FOUND, STORAGE_ERROR, SYNC_FAILED,
+};
+use crate::journal::{self, Before, Journal};
+use crate::profile::{ProfileManager, ProfileManagerError};
+use crate::protocol::{self, PROTOCOL_VERSION};
+use crate::sync::history::entry_commit_message;
+use crate::sync::{SyncManager, SyncManagerError};
+use crate::task::{TaskManager, TaskManagerError};
+use crate::timer::{TimerEngineError, TimerEvent, TimerEventType, TimerManager, TimerManagerError};
+use crate::usage::{UsageManager, UsageManagerError};
+use mootimer_core::storage::durability;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
@@ -26,15 +41,118 @@ pub enum ApiError {
     #[error("Invalid params: {0}")]
     InvalidParams(String),
 
-    #[error("Timer error: {0}")]
-    Timer(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
 
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
+
+    // Manager errors are kept whole, so the wire code can follow their
+    // kind and the daemon log can show what caused them.
+    #[error(transparent)]
+    Core(#[from] mootimer_core::Error),
+
+    #[error(transparent)]
+    Timer(#[from] TimerManagerError),
+
+    #[error(transparent)]
+    Entry(#[from] EntryManagerError),
+
+    #[error(transparent)]
+    Task(#[from] TaskManagerError),
+
+    #[error(transparent)]
+    Profile(#[from] ProfileManagerError),
+
+    #[error(transparent)]
+    Budget(#[from] BudgetManagerError),
+
+    #[error(transparent)]
+    Config(#[from] ConfigManagerError),
+
+    #[error(transparent)]
+    Sync(#[from] SyncManagerError),
+
+    #[error(transparent)]
+    Usage(#[from] UsageManagerError),
 }
 
 pub type Result<T> = std::result::Result<T, ApiError>;
 
+impl ApiError {
+    /// The JSON-RPC error code sent for this error, from `ipc::protocol`.
+    pub fn code(&self) -> i32 {
+        match self {
+            Self::MethodNotFound(_) => METHOD_NOT_FOUND,
+            Self::InvalidParams(_) | Self::Json(_) => INVALID_PARAMS,
+            Self::Forbidden(_) => FORBIDDEN,
+            Self::Conflict(_) => CONFLICT,
+            Self::Core(e) => core_code(e),
+            Self::Timer(e) => match e {
+                TimerManagerError::NotFound(_) => NOT_FOUND,
+                TimerManagerError::LimitReached { .. } => CONFLICT,
+                TimerManagerError::Engine(e) => match e {
+                    TimerEngineError::NotFound(_) => NOT_FOUND,
+                    TimerEngineError::AlreadyRunning | TimerEngineError::InvalidOperation(_) => {
+                        CONFLICT
+                    }
+                    TimerEngineError::Core(e) => core_code(e),
+                },
+            },
+            Self::Entry(e) => match e {
+                EntryManagerError::NotFound(_) => NOT_FOUND,
+                EntryManagerError::Storage(e) => core_code(e),
+                EntryManagerError::Invalid(_) | EntryManagerError::InvalidBatch(_) => {
+                    INVALID_PARAMS
+                }
+                EntryManagerError::Duplicate { .. } => CONFLICT,
+                EntryManagerError::JoinError(_) => INTERNAL_ERROR,
+            },
+            Self::Task(e) => match e {
+                TaskManagerError::NotFound(_) => NOT_FOUND,
+                TaskManagerError::Storage(e) => core_code(e),
+                TaskManagerError::Invalid(_) => INVALID_PARAMS,
+            },
+            Self::Profile(e) => match e {
+                ProfileManagerError::NotFound(_) => NOT_FOUND,
+                ProfileManagerError::AlreadyExists(_) => CONFLICT,
+                ProfileManagerError::Storage(e) => core_code(e),
+                ProfileManagerError::Invalid(_) => INVALID_PARAMS,
+            },
+            Self::Budget(e) => match e {
+                BudgetManagerError::NotFound(_) => NOT_FOUND,
+                BudgetManagerError::Storage(e) => core_code(e),
+                BudgetManagerError::Invalid(_) => INVALID_PARAMS,
+            },
+            Self::Config(e) => match e {
+                ConfigManagerError::Storage(e) => core_code(e),
+                ConfigManagerError::Invalid(_) => INVALID_PARAMS,
+            },
+            Self::Sync(e) => match e {
+                SyncManagerError::Storage(e) => core_code(e),
+                SyncManagerError::Git(_) => SYNC_FAILED,
+                SyncManagerError::NotConfigured(_) => NOT_CONFIGURED,
+                SyncManagerError::ConflictNotFound(_) => NOT_FOUND,
+                SyncManagerError::JoinError(_) => INTERNAL_ERROR,
+            },
+            Self::Usage(UsageManagerError::Storage(e)) => core_code(e),
+        }
+    }
+}
+
+fn core_code(error: &mootimer_core::Error) -> i32 {
+    use mootimer_core::Error;
+    match error {
+        Error::NotFound(_) => NOT_FOUND,
+        Error::AlreadyExists(_) => CONFLICT,
+        Error::Validation(_) | Error::InvalidData(_) => INVALID_PARAMS,
+        Error::Io(_) | Error::Json(_) | Error::Csv(_) => STORAGE_ERROR,
+    }
+}
+
 pub struct ApiHandler {
     event_manager: Arc<EventManager>,
     timer_manager: Arc<TimerManager>,
@@ -43,6 +161,12 @@ pub struct ApiHandler {
     entry_manager: Arc<EntryManager>,
     config_manager: Arc<ConfigManager>,
     sync_manager: Arc<SyncManager>,
+    budget_manager: Arc<BudgetManager>,
+    usage_manager: Arc<UsageManager>,
+    journal: Journal,
+    /// Signalled by `daemon.shutdown`; the daemon's main loop waits on it
+    /// alongside SIGINT and SIGTERM.
+    shutdown_requested: tokio::sync::Notify,
 }
 
 impl ApiHandler {
@@ -55,6 +179,65 @@ impl ApiHandler {
         config_manager: Arc<ConfigManager>,
         sync_manager: Arc<SyncManager>,
     ) -> Self {
+        entry_manager.set_device(config_manager.device());
+
+        let mut timer_events = timer_manager.subscribe();
+        let em = entry_manager.clone();
+        tokio::spawn(async move {
+            loop {
+                match timer_events.recv().await {
+                    Ok(TimerEvent {
+                        event_type: TimerEventType::BreakEnded { record },
+                        profile_id,
+                        ..
+                    }) => {
+                        if let Err(e) = em.record_break(&profile_id, &record).await {
+                            tracing::error!("Failed to record break: {}", e);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        tracing::warn!("Break recorder missed {} timer events", missed);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        // Every stored change can alter what git reports.
+        let mut data_events = event_manager.subscribe();
+        let sm = sync_manager.clone();
+        tokio::spawn(async move {
+            loop {
+                match data_events.recv().await {
+                    Ok(DaemonEvent::Timer(_)) => {}
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                        sm.invalidate_status().await;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        let mut config_events = event_manager.subscribe();
+        let em = entry_manager.clone();
+        let cm = config_manager.clone();
+        tokio::spawn(async move {
+            em.set_earliest_entry_date(cm.get().await.daemon.earliest_entry_date);
+            loop {
+                match config_events.recv().await {
+                    Ok(DaemonEvent::Config(event)) => {
+                        em.set_earliest_entry_date(event.config.daemon.earliest_entry_date);
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        em.set_earliest_entry_date(cm.get().await.daemon.earliest_entry_date);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
         let tm = timer_manager.clone();
         let em = entry_manager.clone();
         let sm = sync_manager.clone();
@@ -66,7 +249,8 @@ impl ApiHandler {
                 let entries = tm.take_completed_entries().await;
                 for (profile_id, entry) in entries {
                     tracing::info!("Saving auto-completed entry for profile {}", profile_id);
-                    if let Err(e) = em.add(&profile_id, entry.clone()).await {
+                    // Timer entries skip the duplicate check, as in timer.stop.
+                    if let Err(e) = em.add_with(&profile_id, entry.clone(), true).await {
                         tracing::error!("Failed to save auto-completed entry: {}", e);
                         continue;
                     }
@@ -77,19 +261,8 @@ impl ApiHandler {
                             let _ = sm.init_repo().await;
                         }
 
-                        let task_info = entry
-                            .task_id
-                            .as_ref()
-                            .map(|id| format!("task {}", id))
-                            .unwrap_or_else(|| "no task".to_string());
-                        let duration_mins = entry.duration_seconds / 60;
-                        let commit_msg = format!(
-                            "Add entry: {} - {}m ({})",
-                            task_info,
-                            duration_mins,
-                            chrono::Local::now().format("%Y-%m-%d %H:%M")
-                        );
-
+                        let commit_msg =
+                            entry_commit_message(&config.daemon.locale, &profile_id, &entry);
                         if let Err(e) = sm.auto_commit(&commit_msg).await {
                             tracing::warn!("Failed to auto-commit: {}", e);
                         }
@@ -105,6 +278,14 @@ impl ApiHandler {
             }
         });
 
+        let budget_manager = Arc::new(BudgetManager::new(
+            event_manager.clone(),
+            entry_manager.clone(),
+            task_manager.clone(),
+            timer_manager.clone(),
+        ));
+        budget_manager.spawn_checker();
+
         Self {
             event_manager,
             timer_manager,
@@ -113,23 +294,76 @@ impl ApiHandler {
             entry_manager,
             config_manager,
             sync_manager,
+            budget_manager,
+            usage_manager: Arc::new(UsageManager::new()),
+            journal: Journal::new(),
+            shutdown_requested: tokio::sync::Notify::new(),
         }
     }
 
     pub async fn handle(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        if protocol::find(method).is_none() {
+            return Err(ApiError::MethodNotFound(method.to_string()));
+        }
+        let config = self.config_manager.get().await;
+        if config.daemon.is_method_disabled(method) {
+            return Err(ApiError::Forbidden(format!(
+                "'{}' is disabled by daemon.disabled_methods in the daemon config",
+                method
+            )));
+        }
+
+        if journal::is_journaled(method) {
+            self.journaled(method, params).await
+        } else {
+            self.dispatch(method, params).await
+        }
+    }
+
+    /// Runs a mutating method and records it in the journal, with what it
+    /// takes to undo it.
+    async fn journaled(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        let params_value = params.clone().unwrap_or(Value::Null);
+        let before = Before::capture(
+            method,
+            &params_value,
+            &self.entry_manager,
+            &self.task_manager,
+            &self.profile_manager,
+            &self.budget_manager,
+        )
+        .await;
+        let result = self.dispatch(method, params).await?;
+        let steps = journal::undo_steps(method, &params_value, before, &result);
+        self.journal.record(method, steps).await;
+        Ok(result)
+    }
+
+    async fn dispatch(&self, method: &str, params: Option<Value>) -> Result<Value> {
         match method {
+            "system.hello" => self.handle_system_hello(params).await,
+            "system.describe" => self.handle_system_describe(params).await,
+            "system.messages" => self.handle_system_messages(params).await,
+            "daemon.info" => self.handle_daemon_info(params).await,
+            "daemon.shutdown" => self.handle_daemon_shutdown(params).await,
+
             "timer.start_manual" => self.handle_timer_start_manual(params).await,
             "timer.start_pomodoro" => self.handle_timer_start_pomodoro(params).await,
             "timer.start_countdown" => self.handle_timer_start_countdown(params).await,
             "timer.pause" => self.handle_timer_pause(params).await,
             "timer.resume" => self.handle_timer_resume(params).await,
+            "timer.set_auto_start" => self.handle_timer_set_auto_start(params).await,
+            "timer.skip_phase" => self.handle_timer_skip_phase(params).await,
             "timer.stop" => self.handle_timer_stop(params).await,
             "timer.cancel" => self.handle_timer_cancel(params).await,
             "timer.get" => self.handle_timer_get(params).await,
             "timer.get_by_profile" => self.handle_timer_get_by_profile(params).await,
+            "timer.peek" => self.handle_timer_peek(params).await,
             "timer.list_by_profile" => self.handle_timer_list_by_profile(params).await,
             "timer.list" => self.handle_timer_list(params).await,
 
+            "status.summary" => self.handle_status_summary(params).await,
+
             "profile.create" => self.handle_profile_create(params).await,
             "profile.get" => self.handle_profile_get(params).await,
             "profile.list" => self.handle_profile_list(params).await,
@@ -137,32 +371,65 @@ impl ApiHandler {
             "profile.delete" => self.handle_profile_delete(params).await,
 
             "task.create" => self.handle_task_create(params).await,
+            "task.add_subtask" => self.handle_task_add_subtask(params).await,
+            "task.copy" => self.handle_task_copy(params).await,
             "task.get" => self.handle_task_get(params).await,
+            "task.blockers" => self.handle_task_blockers(params).await,
+            "task.get_active" => self.handle_task_get_active(params).await,
             "task.list" => self.handle_task_list(params).await,
             "task.update" => self.handle_task_update(params).await,
+            "task.set_description" => self.handle_task_set_description(params).await,
+            "task.set_notes" => self.handle_task_set_notes(params).await,
             "task.delete" => self.handle_task_delete(params).await,
             "task.search" => self.handle_task_search(params).await,
+            "task.stale" => self.handle_task_stale(params).await,
+            "task.list_by_due_date" => self.handle_task_list_by_due_date(params).await,
             "task.move" => self.handle_task_move(params).await,
+            "task.reorder" => self.handle_task_reorder(params).await,
+            "task.bulk_status_update" => self.handle_task_bulk_status_update(params).await,
+            "task.bulk_tag" => self.handle_task_bulk_tag(params).await,
+            "task.bulk_untag" => self.handle_task_bulk_untag(params).await,
+
+            "budget.list" => self.handle_budget_list(params).await,
+            "budget.create" => self.handle_budget_create(params).await,
+            "budget.delete" => self.handle_budget_delete(params).await,
 
             "entry.list" => self.handle_entry_list(params).await,
+            "entry.list_invalid" => self.handle_entry_list_invalid(params).await,
+            "entry.get_by_id" => self.handle_entry_get_by_id(params).await,
+            "entry.last" => self.handle_entry_last(params).await,
+            "entry.find_duplicates" => self.handle_entry_find_duplicates(params).await,
             "entry.filter" => self.handle_entry_filter(params).await,
             "entry.create" => self.handle_entry_create(params).await,
+            "entry.create_batch" => self.handle_entry_create_batch(params).await,
             "entry.delete" => self.handle_entry_delete(params).await,
             "entry.update" => self.handle_entry_update(params).await,
+            "entry.copy_to_profile" => self.handle_entry_copy_to_profile(params).await,
             "entry.today" => self.handle_entry_today(params).await,
             "entry.week" => self.handle_entry_week(params).await,
             "entry.month" => self.handle_entry_month(params).await,
             "entry.stats_today" => self.handle_entry_stats_today(params).await,
             "entry.stats_week" => self.handle_entry_stats_week(params).await,
             "entry.stats_month" => self.handle_entry_stats_month(params).await,
+            "entry.stats_compare" => self.handle_entry_stats_compare(params).await,
+            "entry.stats_hourly" => self.handle_entry_stats_hourly(params).await,
             "entry.today_all_profiles" => self.handle_entry_today_all_profiles(params).await,
             "entry.week_all_profiles" => self.handle_entry_week_all_profiles(params).await,
             "entry.month_all_profiles" => self.handle_entry_month_all_profiles(params).await,
 
+            "stats.bulk" => self.handle_stats_bulk(params).await,
+
             "config.get" => self.handle_config_get(params).await,
+            "config.describe" => self.handle_config_describe(params).await,
             "config.set_default_profile" => self.handle_config_set_default_profile(params).await,
+            "config.set_current_user" => self.handle_config_set_current_user(params).await,
+            "config.set_device_name" => self.handle_config_set_device_name(params).await,
+            "config.update_daemon" => self.handle_config_update_daemon(params).await,
             "config.update_pomodoro" => self.handle_config_update_pomodoro(params).await,
             "config.update_sync" => self.handle_config_update_sync(params).await,
+            "config.update_tui" => self.handle_config_update_tui(params).await,
+            "config.export" => self.handle_config_export(params).await,
+            "config.import" => self.handle_config_import(params).await,
             "config.reset" => self.handle_config_reset(params).await,
 
             "sync.init" => self.handle_sync_init(params).await,
@@ -170,16 +437,48 @@ impl ApiHandler {
             "sync.sync" => self.handle_sync_sync(params).await,
             "sync.commit" => self.handle_sync_commit(params).await,
             "sync.set_remote" => self.handle_sync_set_remote(params).await,
+            "sync.diff" => self.handle_sync_diff(params).await,
+            "sync.diff_full" => self.handle_sync_diff_full(params).await,
+            "sync.history" => self.handle_sync_history(params).await,
+            "sync.conflicts" => self.handle_sync_conflicts(params).await,
+            "sync.resolve_conflict" => self.handle_sync_resolve_conflict(params).await,
+            "sync.list_branches" => self.handle_sync_list_branches(params).await,
+            "sync.switch_branch" => self.handle_sync_switch_branch(params).await,
+            "sync.stash" => self.handle_sync_stash(params).await,
+            "sync.stash_pop" => self.handle_sync_stash_pop(params).await,
+
+            "admin.stats" => self.handle_admin_stats(params).await,
+            "admin.journal" => self.handle_admin_journal(params).await,
+            "admin.check" => self.handle_admin_check(params).await,
+
+            "usage.summary" => self.handle_usage_summary(params).await,
+            "admin.undo" => self.handle_admin_undo(params).await,
 
             _ => Err(ApiError::MethodNotFound(method.to_string())),
         }
     }
 
+    /// Counts a call of `method` from `client` when `daemon.usage_metrics`
+    /// is on. Unknown methods are not counted, so typos don't pile up.
+    pub async fn record_usage(&self, method: &str, client: Option<&str>) {
+        if protocol::find(method).is_none() || !self.config_manager.get().await.daemon.usage_metrics
+        {
+            return;
+        }
+        if let Err(e) = self.usage_manager.record(method, client).await {
+            tracing::warn!("Failed to record usage: {}", e);
+        }
+    }
+
     pub async fn shutdown(&self) {
+        if let Err(e) = self.usage_manager.flush().await {
+            tracing::warn!("Failed to save usage counts: {}", e);
+        }
+
         tracing::info!("Stopping all active timers...");
         let completed_timers = self.timer_manager.stop_all().await;
         for (profile_id, entry) in completed_timers {
-            if let Err(e) = self.entry_manager.add(&profile_id, entry).await {
+            if let Err(e) = self.entry_manager.add_with(&profile_id, entry, true).await {
                 tracing::error!(
                     "Failed to save entry for profile {} during shutdown: {}",
                     profile_id,
@@ -189,20 +488,108 @@ impl ApiHandler {
         }
     }
 
+    /// Resolves once a client has called `daemon.shutdown`.
+    pub async fn shutdown_requested(&self) {
+        self.shutdown_requested.notified().await;
+    }
+
     pub fn subscribe_events(&self) -> broadcast::Receiver<DaemonEvent> {
         self.event_manager.subscribe()
     }
 
+    async fn handle_system_hello(&self, _params: Option<Value>) -> Result<Value> {
+        let config = self.config_manager.get().await;
+        let methods: Vec<&str> = protocol::METHODS
+            .iter()
+            .map(|m| m.name)
+            .filter(|m| !config.daemon.is_method_disabled(m))
+            .collect();
+
+        Ok(json!({
+            "name": "mootimerd",
+            "version": env!("CARGO_PKG_VERSION"),
+            "protocol_version": PROTOCOL_VERSION,
+            "methods": methods,
+        }))
+    }
+
+    /// The full method registry: params schemas and result descriptions.
+    async fn handle_system_describe(&self, _params: Option<Value>) -> Result<Value> {
+        Ok(protocol::describe())
+    }
+
+    async fn handle_system_messages(&self, params: Option<Value>) -> Result<Value> {
+        config::messages(&self.config_manager, params).await
+    }
+
+    /// The daemon's version, the device it stamps on entries and the
+    /// durability its writes were started with.
+    async fn handle_daemon_info(&self, _params: Option<Value>) -> Result<Value> {
+        Ok(json!({
+            "name": "mootimerd",
+            "version": env!("CARGO_PKG_VERSION"),
+            "pid": std::process::id(),
+            "device": self.config_manager.device(),
+            "durability": durability::current(),
+        }))
+    }
+
+    /// Asks the daemon to exit as it would on SIGTERM, saving running
+    /// timers first. Answered before the shutdown begins.
+    async fn handle_daemon_shutdown(&self, _params: Option<Value>) -> Result<Value> {
+        tracing::info!("Shutdown requested over RPC");
+        self.shutdown_requested.notify_one();
+        Ok(json!({ "status": "shutting_down" }))
+    }
+
+    /// Picks up `daemon.max_timers_per_profile` before a timer starts, so
+    /// config edits apply without a restart.
+    async fn sync_timer_limit(&self) {
+        let max = self
+            .config_manager
+            .get()
+            .await
+            .daemon
+            .max_timers_per_profile;
+        self.timer_manager.set_max_timers_per_profile(max);
+    }
+
+    async fn blocked_task_warning(&self, params: Option<&Value>) -> Result<Option<String>> {
+        timer::blocked_task_warning(&self.task_manager, &self.config_manager, params).await
+    }
+
+    async fn check_budgets(&self, params: Option<&Value>) -> Result<()> {
+        budget::check_timer_start(&self.budget_manager, &self.task_manager, params).await
+    }
+
     async fn handle_timer_start_manual(&self, params: Option<Value>) -> Result<Value> {
-        timer::start_manual(&self.timer_manager, params).await
+        self.sync_timer_limit().await;
+        self.check_budgets(params.as_ref()).await?;
+        let warning = self.blocked_task_warning(params.as_ref()).await?;
+        let started = timer::start_manual(&self.timer_manager, params).await?;
+        Ok(timer::with_warning(started, warning))
     }
 
     async fn handle_timer_start_pomodoro(&self, params: Option<Value>) -> Result<Value> {
-        timer::start_pomodoro(&self.timer_manager, &self.config_manager, params).await
+        self.sync_timer_limit().await;
+        self.check_budgets(params.as_ref()).await?;
+        let warning = self.blocked_task_warning(params.as_ref()).await?;
+        let started = timer::start_pomodoro(
+            &self.timer_manager,
+            &self.config_manager,
+            &self.profile_manager,
+            params,
+        )
+        .await?;
+        Ok(timer::with_warning(started, warning))
     }
 
     async fn handle_timer_start_countdown(&self, params: Option<Value>) -> Result<Value> {
-        timer::start_countdown(&self.timer_manager, params).await
+        self.sync_timer_limit().await;
+        self.check_budgets(params.as_ref()).await?;
+        let warning = self.blocked_task_warning(params.as_ref()).await?;
+        let started = timer::start_countdown(&self.timer_manager, params).await?;
+        Ok(timer::with_warning(started, warning))
     }
 
     async fn handle_timer_pause(&self, params: Option<Value>) -> Result<Value> {
@@ -213,12 +600,22 @@ impl ApiHandler {
         timer::resume(&self.timer_manager, params).await
     }
 
+    async fn handle_timer_set_auto_start(&self, params: Option<Value>) -> Result<Value> {
+        timer::set_auto_start(&self.timer_manager, params).await
+    }
+
+    async fn handle_timer_skip_phase(&self, params: Option<Value>) -> Result<Value> {
+        timer::skip_phase(&self.timer_manager, params).await
+    }
+
     async fn handle_timer_stop(&self, params: Option<Value>) -> Result<Value> {
         timer::stop(
             &self.timer_manager,
             &self.entry_manager,
+            &self.task_manager,
             &self.sync_manager,
             &self.config_manager,
+            &self.event_manager,
             params,
         )
         .await
@@ -236,6 +633,10 @@ impl ApiHandler {
         timer::get_by_profile(&self.timer_manager, params).await
     }
 
+    async fn handle_timer_peek(&self, params: Option<Value>) -> Result<Value> {
+        timer::peek(&self.timer_manager, params).await
+    }
+
     async fn handle_timer_list_by_profile(&self, params: Option<Value>) -> Result<Value> {
         timer::list_by_profile(&self.timer_manager, params).await
     }
@@ -244,6 +645,10 @@ impl ApiHandler {
         timer::list(&self.timer_manager, params).await
     }
 
+    async fn handle_status_summary(&self, params: Option<Value>) -> Result<Value> {
+        status::summary(&self.timer_manager, &self.profile_manager, params).await
+    }
+
     async fn handle_profile_create(&self, params: Option<Value>) -> Result<Value> {
         profile::create(&self.profile_manager, params).await
     }
@@ -268,18 +673,58 @@ impl ApiHandler {
         task::create(&self.task_manager, params).await
     }
 
+    async fn handle_task_add_subtask(&self, params: Option<Value>) -> Result<Value> {
+        task::add_subtask(&self.task_manager, params).await
+    }
+
+    async fn handle_task_copy(&self, params: Option<Value>) -> Result<Value> {
+        task::copy(&self.task_manager, params).await
+    }
+
+    async fn handle_task_reorder(&self, params: Option<Value>) -> Result<Value> {
+        task::reorder(&self.task_manager, params).await
+    }
+
     async fn handle_task_get(&self, params: Option<Value>) -> Result<Value> {
         task::get(&self.task_manager, params).await
     }
 
+    async fn handle_task_blockers(&self, params: Option<Value>) -> Result<Value> {
+        task::blockers(&self.task_manager, params).await
+    }
+
+    async fn handle_budget_list(&self, params: Option<Value>) -> Result<Value> {
+        budget::list(&self.budget_manager, params).await
+    }
+
+    async fn handle_budget_create(&self, params: Option<Value>) -> Result<Value> {
+        budget::create(&self.budget_manager, params).await
+    }
+
+    async fn handle_budget_delete(&self, params: Option<Value>) -> Result<Value> {
+        budget::delete(&self.budget_manager, params).await
+    }
+
+    async fn handle_task_get_active(&self, params: Option<Value>) -> Result<Value> {
+        task::get_active(&self.task_manager, &self.timer_manager, params).await
+    }
+
     async fn handle_task_list(&self, params: Option<Value>) -> Result<Value> {
-        task::list(&self.task_manager, params).await
+        task::list(&self.task_manager, &self.entry_manager, params).await
     }
 
     async fn handle_task_update(&self, params: Option<Value>) -> Result<Value> {
         task::update(&self.task_manager, params).await
     }
 
+    async fn handle_task_set_description(&self, params: Option<Value>) -> Result<Value> {
+        task::set_description(&self.task_manager, params).await
+    }
+
+    async fn handle_task_set_notes(&self, params: Option<Value>) -> Result<Value> {
+        task::set_notes(&self.task_manager, params).await
+    }
+
     async fn handle_task_delete(&self, params: Option<Value>) -> Result<Value> {
         task::delete(&self.task_manager, params).await
     }
@@ -288,6 +733,26 @@ impl ApiHandler {
         task::search(&self.task_manager, params).await
     }
 
+    async fn handle_task_stale(&self, params: Option<Value>) -> Result<Value> {
+        task::stale(&self.task_manager, &self.entry_manager, params).await
+    }
+
+    async fn handle_task_list_by_due_date(&self, params: Option<Value>) -> Result<Value> {
+        task::list_by_due_date(&self.task_manager, params).await
+    }
+
+    async fn handle_task_bulk_status_update(&self, params: Option<Value>) -> Result<Value> {
+        task::bulk_status_update(&self.task_manager, params).await
+    }
+
+    async fn handle_task_bulk_tag(&self, params: Option<Value>) -> Result<Value> {
+        task::bulk_tag(&self.task_manager, params).await
+    }
+
+    async fn handle_task_bulk_untag(&self, params: Option<Value>) -> Result<Value> {
+        task::bulk_untag(&self.task_manager, params).await
+    }
+
     async fn handle_task_move(&self, params: Option<Value>) -> Result<Value> {
         task::move_task(
             &self.task_manager,
@@ -302,6 +767,22 @@ impl ApiHandler {
         entry::list(&self.entry_manager, params).await
     }
 
+    async fn handle_entry_list_invalid(&self, params: Option<Value>) -> Result<Value> {
+        entry::list_invalid(&self.entry_manager, params).await
+    }
+
+    async fn handle_entry_get_by_id(&self, params: Option<Value>) -> Result<Value> {
+        entry::get_by_id(&self.entry_manager, params).await
+    }
+
+    async fn handle_entry_last(&self, params: Option<Value>) -> Result<Value> {
+        entry::last(&self.entry_manager, params).await
+    }
+
+    async fn handle_entry_find_duplicates(&self, params: Option<Value>) -> Result<Value> {
+        entry::find_duplicates(&self.entry_manager, params).await
+    }
+
     async fn handle_entry_filter(&self, params: Option<Value>) -> Result<Value> {
         entry::filter(&self.entry_manager, params).await
     }
@@ -310,6 +791,10 @@ impl ApiHandler {
         entry::create(&self.entry_manager, &self.task_manager, params).await
     }
 
+    async fn handle_entry_create_batch(&self, params: Option<Value>) -> Result<Value> {
+        entry::create_batch(&self.entry_manager, &self.task_manager, params).await
+    }
+
     async fn handle_entry_delete(&self, params: Option<Value>) -> Result<Value> {
         entry::delete(&self.entry_manager, params).await
     }
@@ -318,6 +803,10 @@ impl ApiHandler {
         entry::update(&self.entry_manager, params).await
     }
 
+    async fn handle_entry_copy_to_profile(&self, params: Option<Value>) -> Result<Value> {
+        entry::copy_to_profile(&self.entry_manager, &self.task_manager, params).await
+    }
+
     async fn handle_entry_today(&self, params: Option<Value>) -> Result<Value> {
         entry::get_today(&self.entry_manager, params).await
     }
@@ -331,15 +820,23 @@ impl ApiHandler {
     }
 
     async fn handle_entry_stats_today(&self, params: Option<Value>) -> Result<Value> {
-        entry::stats_today(&self.entry_manager, params).await
+        entry::stats_today(&self.entry_manager, &self.profile_manager, params).await
     }
 
     async fn handle_entry_stats_week(&self, params: Option<Value>) -> Result<Value> {
-        entry::stats_week(&self.entry_manager, params).await
+        entry::stats_week(&self.entry_manager, &self.profile_manager, params).await
     }
 
     async fn handle_entry_stats_month(&self, params: Option<Value>) -> Result<Value> {
-        entry::stats_month(&self.entry_manager, params).await
+        entry::stats_month(&self.entry_manager, &self.profile_manager, params).await
+    }
+
+    async fn handle_entry_stats_compare(&self, params: Option<Value>) -> Result<Value> {
+        entry::stats_compare(&self.entry_manager, &self.profile_manager, params).await
+    }
+
+    async fn handle_entry_stats_hourly(&self, params: Option<Value>) -> Result<Value> {
+        entry::stats_hourly(&self.entry_manager, params).await
     }
 
     async fn handle_entry_today_all_profiles(&self, params: Option<Value>) -> Result<Value> {
@@ -354,14 +851,34 @@ impl ApiHandler {
         entry::get_month_all_profiles(&self.entry_manager, &self.profile_manager, params).await
     }
 
+    async fn handle_stats_bulk(&self, params: Option<Value>) -> Result<Value> {
+        stats::bulk(&self.entry_manager, &self.profile_manager, params).await
+    }
+
     async fn handle_config_get(&self, params: Option<Value>) -> Result<Value> {
         config::get(&self.config_manager, params).await
     }
 
+    async fn handle_config_describe(&self, params: Option<Value>) -> Result<Value> {
+        config::describe(params).await
+    }
+
     async fn handle_config_set_default_profile(&self, params: Option<Value>) -> Result<Value> {
         config::set_default_profile(&self.config_manager, params).await
     }
 
+    async fn handle_config_set_current_user(&self, params: Option<Value>) -> Result<Value> {
+        config::set_current_user(&self.config_manager, params).await
+    }
+
+    async fn handle_config_set_device_name(&self, params: Option<Value>) -> Result<Value> {
+        config::set_device_name(&self.config_manager, &self.entry_manager, params).await
+    }
+
+    async fn handle_config_update_daemon(&self, params: Option<Value>) -> Result<Value> {
+        config::update_daemon(&self.config_manager, params).await
+    }
+
     async fn handle_config_update_pomodoro(&self, params: Option<Value>) -> Result<Value> {
         config::update_pomodoro(&self.config_manager, params).await
     }
@@ -370,6 +887,18 @@ impl ApiHandler {
         config::update_sync(&self.config_manager, params).await
     }
 
+    async fn handle_config_update_tui(&self, params: Option<Value>) -> Result<Value> {
+        config::update_tui(&self.config_manager, params).await
+    }
+
+    async fn handle_config_export(&self, params: Option<Value>) -> Result<Value> {
+        config::export(&self.config_manager, params).await
+    }
+
+    async fn handle_config_import(&self, params: Option<Value>) -> Result<Value> {
+        config::import(&self.config_manager, params).await
+    }
+
     async fn handle_config_reset(&self, params: Option<Value>) -> Result<Value> {
         config::reset(&self.config_manager, params).await
     }
@@ -383,7 +912,16 @@ impl ApiHandler {
     }
 
     async fn handle_sync_sync(&self, params: Option<Value>) -> Result<Value> {
-        sync::sync(&self.sync_manager, &self.config_manager, params).await
+        sync::sync(
+            &self.sync_manager,
+            &self.config_manager,
+            &self.profile_manager,
+            &self.task_manager,
+            &self.entry_manager,
+            &self.event_manager,
+            params,
+        )
+        .await
     }
 
     async fn handle_sync_commit(&self, params: Option<Value>) -> Result<Value> {
@@ -395,6 +933,98 @@ impl ApiHandler {
         Ok(Value::Null)
     }
 
+    async fn handle_sync_diff(&self, params: Option<Value>) -> Result<Value> {
+        sync::diff(&self.sync_manager, params).await
+    }
+
+    async fn handle_sync_diff_full(&self, params: Option<Value>) -> Result<Value> {
+        sync::diff_full(&self.sync_manager, params).await
+    }
+
+    async fn handle_sync_history(&self, params: Option<Value>) -> Result<Value> {
+        sync::history(&self.sync_manager, params).await
+    }
+
+    async fn handle_sync_conflicts(&self, params: Option<Value>) -> Result<Value> {
+        sync::conflicts(&self.sync_manager, params).await
+    }
+
+    async fn handle_sync_resolve_conflict(&self, params: Option<Value>) -> Result<Value> {
+        sync::resolve_conflict(
+            &self.sync_manager,
+            &self.task_manager,
+            &self.entry_manager,
+            params,
+        )
+        .await
+    }
+
+    async fn handle_sync_list_branches(&self, params: Option<Value>) -> Result<Value> {
+        sync::list_branches(&self.sync_manager, params).await
+    }
+
+    async fn handle_sync_switch_branch(&self, params: Option<Value>) -> Result<Value> {
+        sync::switch_branch(
+            &self.sync_manager,
+            &self.profile_manager,
+            &self.task_manager,
+            &self.entry_manager,
+            params,
+        )
+        .await
+    }
+
+    async fn handle_sync_stash(&self, params: Option<Value>) -> Result<Value> {
+        sync::stash(
+            &self.sync_manager,
+            &self.profile_manager,
+            &self.task_manager,
+            &self.entry_manager,
+            params,
+        )
+        .await
+    }
+
+    async fn handle_sync_stash_pop(&self, params: Option<Value>) -> Result<Value> {
+        sync::stash_pop(
+            &self.sync_manager,
+            &self.profile_manager,
+            &self.task_manager,
+            &self.entry_manager,
+            &self.event_manager,
+            params,
+        )
+        .await
+    }
+
+    async fn handle_admin_stats(&self, params: Option<Value>) -> Result<Value> {
+        admin::stats(&self.entry_manager, params).await
+    }
+
+    async fn handle_usage_summary(&self, params: Option<Value>) -> Result<Value> {
+        usage::summary(&self.usage_manager, &self.config_manager, params).await
+    }
+
+    async fn handle_admin_journal(&self, params: Option<Value>) -> Result<Value> {
+        admin::journal(&self.journal, params).await
+    }
+
+    async fn handle_admin_check(&self, params: Option<Value>) -> Result<Value> {
+        admin::check(&self.config_manager, params).await
+    }
+
+    async fn handle_admin_undo(&self, params: Option<Value>) -> Result<Value> {
+        admin::undo(
+            &self.journal,
+            &self.entry_manager,
+            &self.task_manager,
+            &self.profile_manager,
+            &self.budget_manager,
+            params,
+        )
+        .await
+    }
+
     pub async fn profile_list(&self) -> Result<Value> {
         profile::list(&self.profile_manager, None).await
     }
@@ -402,6 +1032,7 @@ impl ApiHandler {
     pub async fn task_list(&self, profile_id: &str) -> Result<Value> {
         task::list(
             &self.task_manager,
+            &self.entry_manager,
             Some(json!({ "profile_id": profile_id })),
         )
         .await
@@ -413,24 +1044,24 @@ impl ApiHandler {
         title: &str,
         description: Option<&str>,
     ) -> Result<Value> {
-        task::create(
-            &self.task_manager,
+        self.journaled(
+            "task.create",
             Some(json!({ "profile_id": profile_id, "title": title, "description": description })),
         )
         .await
     }
 
     pub async fn task_update(&self, profile_id: &str, task: Value) -> Result<Value> {
-        task::update(
-            &self.task_manager,
+        self.journaled(
+            "task.update",
             Some(json!({ "profile_id": profile_id, "task": task })),
         )
         .await
     }
 
     pub async fn task_delete(&self, profile_id: &str, task_id: &str) -> Result<Value> {
-        task::delete(
-            &self.task_manager,
+        self.journaled(
+            "task.delete",
             Some(json!({ "profile_id": profile_id, "task_id": task_id })),
         )
         .await
@@ -474,7 +1105,13 @@ impl ApiHandler {
         if let Some(config) = config_override {
             params["config"] = config;
         }
-        timer::start_pomodoro(&self.timer_manager, &self.config_manager, Some(params)).await
+        timer::start_pomodoro(
+            &self.timer_manager,
+            &self.config_manager,
+            &self.profile_manager,
+            Some(params),
+        )
+        .await
     }
 
     pub async fn timer_start_countdown(
@@ -490,8 +1127,10 @@ impl ApiHandler {
         timer::stop(
             &self.timer_manager,
             &self.entry_manager,
+            &self.task_manager,
             &self.sync_manager,
             &self.config_manager,
+            &self.event_manager,
             Some(json!({ "timer_id": timer_id })),
         )
         .await
@@ -524,10 +1163,8 @@ impl ApiHandler {
         task_id: &str,
         move_entries: Option<bool>,
     ) -> Result<Value> {
-        task::move_task(
-            &self.task_manager,
-            &self.entry_manager,
-            &self.timer_manager,
+        self.journaled(
+            "task.move",
             Some(json!({
                 "source_profile_id": source_profile_id,
                 "target_profile_id": target_profile_id,
@@ -538,3 +1175,968 @@ impl ApiHandler {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mootimer_core::models::default_color_and_icon;
+    use mootimer_core::storage::{ConfigStorage, init_config_dir};
+    use serial_test::serial;
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    fn create_handler(temp_dir: &TempDir) -> ApiHandler {
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+            std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config"));
+        }
+
+        let event_manager = Arc::new(EventManager::new());
+        ApiHandler::new(
+            event_manager.clone(),
+            Arc::new(TimerManager::new(event_manager.clone())),
+            Arc::new(ProfileManager::new(event_manager.clone()).unwrap()),
+            Arc::new(TaskManager::new(event_manager.clone()).unwrap()),
+            Arc::new(EntryManager::new(event_manager.clone()).unwrap()),
+            Arc::new(ConfigManager::new(event_manager).unwrap()),
+            Arc::new(SyncManager::new().unwrap()),
+        )
+    }
+
+    async fn disable(handler: &ApiHandler, patterns: &[&str]) {
+        let mut config = handler.config_manager.get().await;
+        config.daemon.disabled_methods = patterns.iter().map(|p| p.to_string()).collect();
+        handler.config_manager.update(config).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_disabled_method_is_forbidden() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+        disable(&handler, &["profile.delete", "sync.*"]).await;
+
+        let result = handler
+            .handle("profile.delete", Some(json!({ "profile_id": "work" })))
+            .await;
+        assert!(matches!(result, Err(ApiError::Forbidden(_))));
+        assert!(matches!(
+            handler.handle("sync.status", None).await,
+            Err(ApiError::Forbidden(_))
+        ));
+        assert!(handler.handle("profile.list", None).await.is_ok());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_daemon_info_reports_durability() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+
+        let info = handler.handle("daemon.info", None).await.unwrap();
+        assert_eq!(info["durability"], "normal");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_stats_follow_profile_working_hours() {
+        use chrono::{TimeZone, Utc};
+        use mootimer_core::models::{Entry, TimerMode};
+
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+        handler
+            .handle(
+                "profile.create",
+                Some(json!({"id": "work", "name": "Work"})),
+            )
+            .await
+            .unwrap();
+        // Noon UTC on a Tuesday, so well inside the day in any timezone.
+        let start = Utc.with_ymd_and_hms(2024, 5, 14, 12, 0, 0).unwrap();
+        let entry = Entry::create_completed(
+            None,
+            None,
+            start,
+            start + chrono::Duration::hours(1),
+            TimerMode::Manual,
+        )
+        .unwrap();
+        handler.entry_manager.add("work", entry).await.unwrap();
+
+        let set_hours = |day: &'static str| {
+            let handler = &handler;
+            async move {
+                let mut profile = handler
+                    .handle("profile.get", Some(json!({"profile_id": "work"})))
+                    .await
+                    .unwrap();
+                profile["working_hours"] = json!({
+                    "monday": day, "tuesday": day, "wednesday": day, "thursday": day,
+                    "friday": day, "saturday": day, "sunday": day,
+                });
+                handler
+                    .handle("profile.update", Some(json!({ "profile": profile })))
+                    .await
+                    .unwrap();
+            }
+        };
+        let split = || async {
+            let stats = handler
+                .handle(
+                    "stats.bulk",
+                    Some(json!({"profile_ids": ["work"], "windows": ["2024-05-13..2024-05-19"]})),
+                )
+                .await
+                .unwrap();
+            stats["profiles"]["work"]["2024-05-13..2024-05-19"]["working_hours"].clone()
+        };
+
+        set_hours("off").await;
+        assert_eq!(split().await["inside_seconds"], 0);
+        assert_eq!(split().await["outside_seconds"], 3600);
+
+        // Changed hours are picked up even though the totals were cached.
+        set_hours("00:00-23:59").await;
+        assert!(split().await["inside_seconds"].as_u64().unwrap() > 0);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_overlapping_timers_all_save_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+
+        for task_id in [json!(null), json!("task1")] {
+            let mut timer_ids = Vec::new();
+            for _ in 0..2 {
+                let started = handler
+                    .handle(
+                        "timer.start_manual",
+                        Some(json!({"profile_id": "work", "task_id": task_id})),
+                    )
+                    .await
+                    .unwrap();
+                timer_ids.push(started["timer_id"].clone());
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+            for timer_id in timer_ids {
+                handler
+                    .handle("timer.stop", Some(json!({ "timer_id": timer_id })))
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let entries = handler
+            .handle("entry.list", Some(json!({"profile_id": "work"})))
+            .await
+            .unwrap();
+        assert_eq!(entries.as_array().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_pomodoro_follows_profile_auto_start() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+        let mut profile = handler
+            .handle(
+                "profile.create",
+                Some(json!({"id": "work", "name": "Work"})),
+            )
+            .await
+            .unwrap();
+        profile["auto_start_next_session"] = json!(true);
+        handler
+            .handle("profile.update", Some(json!({ "profile": profile })))
+            .await
+            .unwrap();
+
+        let auto_start = |config: Value| {
+            let handler = &handler;
+            async move {
+                let started = handler
+                    .handle(
+                        "timer.start_pomodoro",
+                        Some(json!({"profile_id": "work", "config": config})),
+                    )
+                    .await
+                    .unwrap();
+                let timer_id = started["timer_id"].clone();
+                let timer = handler
+                    .handle("timer.get", Some(json!({ "timer_id": timer_id })))
+                    .await
+                    .unwrap();
+                handler
+                    .handle("timer.cancel", Some(json!({ "timer_id": timer_id })))
+                    .await
+                    .unwrap();
+                timer["pomodoro_state"]["config"]["auto_start_next_session"].clone()
+            }
+        };
+
+        assert_eq!(auto_start(json!({})).await, json!(true));
+        // An explicit override still wins over the profile.
+        assert_eq!(
+            auto_start(json!({ "auto_start_next_session": false })).await,
+            json!(false)
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_failed_sync_is_announced() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+        let mut events = handler.subscribe_events();
+
+        let error = handler.handle("sync.sync", None).await.unwrap_err();
+        let mut announced = None;
+        while let Ok(event) = events.try_recv() {
+            if let DaemonEvent::Sync(event) = event {
+                announced = Some(event.event_type);
+            }
+        }
+        match announced {
+            Some(crate::events::SyncEventType::Failed { error: message }) => {
+                assert!(error.to_string().contains(&message), "{}", message);
+            }
+            other => panic!("expected a failed sync event, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_usage_is_recorded_only_when_enabled() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+
+        handler
+            .record_usage("timer.get", Some("mootimer-tui"))
+            .await;
+        let summary = handler.handle("usage.summary", None).await.unwrap();
+        assert_eq!(summary["enabled"], false);
+        assert_eq!(summary["total"], 0);
+        assert_eq!(summary["months"], json!([]));
+
+        let mut config = handler.config_manager.get().await;
+        config.daemon.usage_metrics = true;
+        handler.config_manager.update(config).await.unwrap();
+
+        handler
+            .record_usage("timer.get", Some("mootimer-tui"))
+            .await;
+        handler.record_usage("timer.get", None).await;
+        handler
+            .record_usage("profile.list", Some("mootimer-tui"))
+            .await;
+        handler.record_usage("timer.teleport", None).await;
+
+        let summary = handler.handle("usage.summary", None).await.unwrap();
+        assert_eq!(summary["enabled"], true);
+        assert_eq!(summary["total"], 3);
+        assert_eq!(summary["by_method"]["timer.get"], 2);
+        assert_eq!(summary["by_client"]["mootimer-tui"], 2);
+        assert_eq!(summary["by_client"]["unknown"], 1);
+        assert_eq!(summary["months"].as_array().unwrap().len(), 1);
+
+        assert!(matches!(
+            handler
+                .handle("usage.summary", Some(json!({ "month": "2024-13" })))
+                .await,
+            Err(ApiError::InvalidParams(_))
+        ));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_every_registered_method_is_dispatched() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+
+        for method in protocol::METHODS {
+            // Connection state, answered by the IPC server.
+            if method.name.starts_with("events.") || method.name == "task.watch_status" {
+                continue;
+            }
+            let result = handler.dispatch(method.name, None).await;
+            assert!(
+                !matches!(result, Err(ApiError::MethodNotFound(_))),
+                "{} is registered but not dispatched",
+                method.name
+            );
+        }
+        assert!(matches!(
+            handler.handle("timer.teleport", None).await,
+            Err(ApiError::MethodNotFound(_))
+        ));
+
+        let describe = handler.handle("system.describe", None).await.unwrap();
+        assert_eq!(describe["protocol_version"], PROTOCOL_VERSION);
+        let start = describe["methods"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|m| m["name"] == "timer.start_manual")
+            .unwrap();
+        assert_eq!(start["params"]["required"], json!(["profile_id"]));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_hello_omits_disabled_methods() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+
+        let hello = handler.handle("system.hello", None).await.unwrap();
+        let methods = hello["methods"].as_array().unwrap();
+        assert_eq!(methods.len(), protocol::METHODS.len());
+
+        disable(&handler, &["config.reset", "sync.*"]).await;
+
+        let hello = handler.handle("system.hello", None).await.unwrap();
+        let methods: Vec<&str> = hello["methods"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|m| m.as_str())
+            .collect();
+        assert!(!methods.contains(&"config.reset"));
+        assert!(!methods.iter().any(|m| m.starts_with("sync.")));
+        assert!(methods.contains(&"config.get"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_reenable_via_config_reload() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+        disable(&handler, &["profile.*"]).await;
+        assert!(handler.handle("profile.list", None).await.is_err());
+
+        let mut config = handler.config_manager.get().await;
+        config.daemon.disabled_methods.clear();
+        let config_dir = init_config_dir().unwrap();
+        ConfigStorage::new(config_dir.clone())
+            .save(&config)
+            .unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(config_dir.join("config.json"))
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+
+        assert!(handler.config_manager.reload_if_changed().await.unwrap());
+        assert!(handler.handle("profile.list", None).await.is_ok());
+    }
+
+    #[test]
+    fn test_error_codes() {
+        use mootimer_core::Error as CoreError;
+        let s = || "x".to_string();
+        let io = || CoreError::Io(std::io::Error::other("disk"));
+        let cases: Vec<(ApiError, i32)> = vec![
+            (ApiError::MethodNotFound(s()), METHOD_NOT_FOUND),
+            (ApiError::InvalidParams(s()), INVALID_PARAMS),
+            (ApiError::Forbidden(s()), FORBIDDEN),
+            (ApiError::Conflict(s()), CONFLICT),
+            (CoreError::NotFound(s()).into(), NOT_FOUND),
+            (CoreError::AlreadyExists(s()).into(), CONFLICT),
+            (CoreError::Validation(s()).into(), INVALID_PARAMS),
+            (CoreError::InvalidData(s()).into(), INVALID_PARAMS),
+            (io().into(), STORAGE_ERROR),
+            (TimerManagerError::NotFound(s()).into(), NOT_FOUND),
+            (
+                TimerManagerError::LimitReached {
+                    profile_id: s(),
+                    max: 1,
+                }
+                .into(),
+                CONFLICT,
+            ),
+            (
+                TimerManagerError::Engine(TimerEngineError::NotFound(s())).into(),
+                NOT_FOUND,
+            ),
+            (
+                TimerManagerError::Engine(TimerEngineError::AlreadyRunning).into(),
+                CONFLICT,
+            ),
+            (
+                TimerManagerError::Engine(TimerEngineError::InvalidOperation(s())).into(),
+                CONFLICT,
+            ),
+            (
+                TimerManagerError::Engine(TimerEngineError::Core(io())).into(),
+                STORAGE_ERROR,
+            ),
+            (EntryManagerError::NotFound(s()).into(), NOT_FOUND),
+            (EntryManagerError::Storage(io()).into(), STORAGE_ERROR),
+            (EntryManagerError::Invalid(s()).into(), INVALID_PARAMS),
+            (
+                EntryManagerError::Duplicate { existing_id: s() }.into(),
+                CONFLICT,
+            ),
+            (
+                EntryManagerError::InvalidBatch(vec![]).into(),
+                INVALID_PARAMS,
+            ),
+            (EntryManagerError::JoinError(s()).into(), INTERNAL_ERROR),
+            (TaskManagerError::NotFound(s()).into(), NOT_FOUND),
+            (TaskManagerError::Storage(io()).into(), STORAGE_ERROR),
+            (TaskManagerError::Invalid(s()).into(), INVALID_PARAMS),
+            (ProfileManagerError::NotFound(s()).into(), NOT_FOUND),
+            (ProfileManagerError::AlreadyExists(s()).into(), CONFLICT),
+            (ProfileManagerError::Storage(io()).into(), STORAGE_ERROR),
+            (ProfileManagerError::Invalid(s()).into(), INVALID_PARAMS),
+            (BudgetManagerError::NotFound(s()).into(), NOT_FOUND),
+            (BudgetManagerError::Storage(io()).into(), STORAGE_ERROR),
+            (BudgetManagerError::Invalid(s()).into(), INVALID_PARAMS),
+            (ConfigManagerError::Storage(io()).into(), STORAGE_ERROR),
+            (ConfigManagerError::Invalid(s()).into(), INVALID_PARAMS),
+            (SyncManagerError::Storage(io()).into(), STORAGE_ERROR),
+            (SyncManagerError::Git(s()).into(), SYNC_FAILED),
+            (SyncManagerError::NotConfigured(s()).into(), NOT_CONFIGURED),
+            (SyncManagerError::ConflictNotFound(s()).into(), NOT_FOUND),
+            (SyncManagerError::JoinError(s()).into(), INTERNAL_ERROR),
+            (UsageManagerError::Storage(io()).into(), STORAGE_ERROR),
+        ];
+        for (error, code) in cases {
+            assert_eq!(error.code(), code, "{:?}", error);
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_missing_records_are_not_found() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+
+        for (method, params) in [
+            (
+                "entry.get_by_id",
+                json!({ "profile_id": "work", "entry_id": "gone" }),
+            ),
+            ("timer.pause", json!({ "timer_id": "gone" })),
+            ("profile.get", json!({ "profile_id": "gone" })),
+        ] {
+            let error = handler.handle(method, Some(params)).await.unwrap_err();
+            assert_eq!(error.code(), NOT_FOUND, "{}: {}", method, error);
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_status_summary() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+
+        let idle = handler.handle("status.summary", None).await.unwrap();
+        assert_eq!(idle["text"], "");
+        assert!(idle["timer_id"].is_null());
+
+        handler
+            .handle(
+                "timer.start_pomodoro",
+                Some(json!({ "profile_id": "work" })),
+            )
+            .await
+            .unwrap();
+
+        let summary = handler.handle("status.summary", None).await.unwrap();
+        let text = summary["text"].as_str().unwrap();
+        assert!(text.starts_with("🍅 2"), "{}", text);
+        assert!(summary["timer_id"].is_string());
+        assert_eq!(summary["profile_id"], "work");
+        let (color, icon) = default_color_and_icon("work");
+        assert_eq!(summary["profile_color"], color);
+        assert_eq!(summary["profile_icon"], icon);
+
+        let custom = handler
+            .handle(
+                "status.summary",
+                Some(json!({ "profile_id": "work", "format": "{phase}" })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(custom["text"], "Work");
+
+        let other = handler
+            .handle("status.summary", Some(json!({ "profile_id": "home" })))
+            .await
+            .unwrap();
+        assert_eq!(other["text"], "");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_stats_bulk() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+
+        let end = chrono::Utc::now() - chrono::Duration::days(3);
+        let start = end - chrono::Duration::minutes(25);
+        handler
+            .handle(
+                "entry.create",
+                Some(json!({
+                    "profile_id": "work",
+                    "start_time": start.to_rfc3339(),
+                    "end_time": end.to_rfc3339(),
+                })),
+            )
+            .await
+            .unwrap();
+
+        let result = handler
+            .handle(
+                "stats.bulk",
+                Some(json!({ "profile_ids": ["work", "home"], "windows": ["today", "7d"] })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["profiles"]["work"]["today"]["total_entries"], 0);
+        assert_eq!(result["profiles"]["work"]["7d"]["total_entries"], 1);
+        assert_eq!(
+            result["profiles"]["work"]["7d"]["total_duration_seconds"],
+            1500
+        );
+        assert_eq!(result["profiles"]["home"]["7d"]["total_entries"], 0);
+
+        let bad = handler
+            .handle("stats.bulk", Some(json!({ "windows": ["fortnight"] })))
+            .await;
+        assert!(matches!(bad, Err(ApiError::InvalidParams(_))));
+
+        let admin = handler.handle("admin.stats", None).await.unwrap();
+        assert_eq!(admin["stats_cache"]["misses"], 2);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_admin_check_flags_stored_violators() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+        handler
+            .handle(
+                "profile.create",
+                Some(json!({"id": "work", "name": "Work"})),
+            )
+            .await
+            .unwrap();
+
+        let now = chrono::Utc::now();
+        let params = json!({
+            "profile_id": "work",
+            "start_time": (now - chrono::Duration::minutes(30)).to_rfc3339(),
+            "end_time": now.to_rfc3339(),
+        });
+        let mut entry = handler.handle("entry.create", Some(params)).await.unwrap();
+        let end = now + chrono::Duration::minutes(10);
+        entry["start_time"] = json!((end - chrono::Duration::minutes(30)).to_rfc3339());
+        entry["end_time"] = json!(end.to_rfc3339());
+        let rejected = handler
+            .handle(
+                "entry.update",
+                Some(json!({ "profile_id": "work", "entry": entry })),
+            )
+            .await;
+        assert!(matches!(
+            rejected,
+            Err(ApiError::Entry(EntryManagerError::Invalid(ref e))) if e.contains("end_time")
+        ));
+
+        // Stored before the rule existed.
+        let mut ahead = mootimer_core::models::Entry::create_completed(
+            None,
+            None,
+            end - chrono::Duration::minutes(30),
+            end,
+            mootimer_core::models::TimerMode::Manual,
+        )
+        .unwrap();
+        ahead.source = mootimer_core::models::EntrySource::Manual;
+        mootimer_core::storage::EntryStorage::new(mootimer_core::storage::get_data_dir())
+            .save_all("work", std::slice::from_ref(&ahead))
+            .unwrap();
+
+        let report = handler.handle("admin.check", None).await.unwrap();
+        assert_eq!(report["entries"], 1);
+        let issues = report["issues"].as_array().unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0]["location"].as_str().unwrap().ends_with(&ahead.id));
+        assert!(issues[0]["problem"].as_str().unwrap().contains("end_time"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_entry_copy_to_profile() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+
+        let task = handler.task_create("work", "Review", None).await.unwrap();
+        let end = chrono::Utc::now();
+        let start = end - chrono::Duration::minutes(25);
+        let entry = handler
+            .handle(
+                "entry.create",
+                Some(json!({
+                    "profile_id": "work",
+                    "start_time": start.to_rfc3339(),
+                    "end_time": end.to_rfc3339(),
+                    "task_id": task["id"],
+                })),
+            )
+            .await
+            .unwrap();
+        let entry_id = entry["id"].as_str().unwrap();
+
+        let missing_task = handler
+            .handle(
+                "entry.copy_to_profile",
+                Some(json!({
+                    "source_profile_id": "work",
+                    "entry_id": entry_id,
+                    "target_profile_id": "home",
+                })),
+            )
+            .await;
+        assert!(matches!(missing_task, Err(ApiError::InvalidParams(_))));
+
+        let home_task = handler.task_create("home", "Chores", None).await.unwrap();
+        let copied = handler
+            .handle(
+                "entry.copy_to_profile",
+                Some(json!({
+                    "source_profile_id": "work",
+                    "entry_id": entry_id,
+                    "target_profile_id": "home",
+                    "task_id": home_task["id"],
+                })),
+            )
+            .await
+            .unwrap();
+        assert_ne!(copied["entry"]["id"], entry["id"]);
+        assert_eq!(copied["entry"]["task_title"], "Chores");
+
+        let moved = handler
+            .handle(
+                "entry.copy_to_profile",
+                Some(json!({
+                    "source_profile_id": "work",
+                    "entry_id": entry_id,
+                    "target_profile_id": "side",
+                    "task_id": handler.task_create("side", "Side", None).await.unwrap()["id"],
+                    "move": true,
+                })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(moved["entry"]["id"], entry["id"]);
+        assert_eq!(moved["moved"], true);
+
+        let work = handler
+            .handle("entry.list", Some(json!({ "profile_id": "work" })))
+            .await
+            .unwrap();
+        assert_eq!(work.as_array().unwrap().len(), 0);
+        let home = handler
+            .handle("entry.list", Some(json!({ "profile_id": "home" })))
+            .await
+            .unwrap();
+        assert_eq!(home.as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_undo_middle_operation() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+
+        let end = chrono::Utc::now();
+        let start = end - chrono::Duration::minutes(30);
+        let entry = handler
+            .handle(
+                "entry.create",
+                Some(json!({
+                    "profile_id": "work",
+                    "start_time": start.to_rfc3339(),
+                    "end_time": end.to_rfc3339(),
+                })),
+            )
+            .await
+            .unwrap();
+
+        let mut edited = entry.clone();
+        edited["duration_seconds"] = json!(600);
+        edited["end_time"] = json!((start + chrono::Duration::minutes(10)).to_rfc3339());
+        handler
+            .handle(
+                "entry.update",
+                Some(json!({ "profile_id": "work", "entry": edited })),
+            )
+            .await
+            .unwrap();
+        let task = handler
+            .task_create("work", "Write report", None)
+            .await
+            .unwrap();
+
+        let journal = handler.handle("admin.journal", None).await.unwrap();
+        let ops = journal["operations"].as_array().unwrap();
+        let methods: Vec<&str> = ops.iter().map(|o| o["method"].as_str().unwrap()).collect();
+        assert_eq!(methods, ["task.create", "entry.update", "entry.create"]);
+        let update_op = ops[1]["op_id"].clone();
+
+        handler
+            .handle("admin.undo", Some(json!({ "op_id": update_op })))
+            .await
+            .unwrap();
+
+        handler.entry_manager.clear_cache().await;
+        handler.task_manager.clear_cache().await;
+        let entries = handler
+            .handle("entry.list", Some(json!({ "profile_id": "work" })))
+            .await
+            .unwrap();
+        assert_eq!(entries.as_array().unwrap().len(), 1);
+        assert_eq!(entries[0]["duration_seconds"], 1800);
+        assert!(
+            handler
+                .task_manager
+                .get("work", task["id"].as_str().unwrap())
+                .await
+                .is_ok()
+        );
+
+        let again = handler
+            .handle("admin.undo", Some(json!({ "op_id": update_op })))
+            .await;
+        assert!(matches!(again, Err(ApiError::Conflict(_))));
+
+        // Without an op id the newest undoable operation goes.
+        handler.handle("admin.undo", None).await.unwrap();
+        assert!(handler.task_manager.list("work").await.unwrap().is_empty());
+
+        handler
+            .handle(
+                "profile.create",
+                Some(json!({ "id": "scratch", "name": "Scratch" })),
+            )
+            .await
+            .unwrap();
+        handler
+            .handle("profile.delete", Some(json!({ "profile_id": "scratch" })))
+            .await
+            .unwrap();
+        let journal = handler.handle("admin.journal", None).await.unwrap();
+        let delete_op = &journal["operations"][0];
+        assert_eq!(delete_op["method"], "profile.delete");
+        assert_eq!(delete_op["undoable"], false);
+        let undo_delete = handler
+            .handle("admin.undo", Some(json!({ "op_id": delete_op["op_id"] })))
+            .await;
+        assert!(matches!(undo_delete, Err(ApiError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_undo_budget_create_and_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+        let budget_ids = || async {
+            handler
+                .handle("budget.list", Some(json!({ "profile_id": "work" })))
+                .await
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|s| s["budget"]["id"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let budget = handler
+            .handle(
+                "budget.create",
+                Some(json!({
+                    "profile_id": "work",
+                    "scope": { "type": "tag", "tag": "meetings" },
+                    "period": "week",
+                    "limit_seconds": 36000,
+                })),
+            )
+            .await
+            .unwrap();
+        let budget_id = budget["id"].as_str().unwrap().to_string();
+        handler.handle("admin.undo", None).await.unwrap();
+        assert!(budget_ids().await.is_empty());
+
+        handler
+            .handle(
+                "budget.create",
+                Some(json!({
+                    "profile_id": "work",
+                    "scope": { "type": "profile" },
+                    "period": "day",
+                    "limit_seconds": 28800,
+                })),
+            )
+            .await
+            .unwrap();
+        let kept = budget_ids().await;
+        handler
+            .handle(
+                "budget.delete",
+                Some(json!({ "profile_id": "work", "budget_id": kept[0] })),
+            )
+            .await
+            .unwrap();
+        assert!(budget_ids().await.is_empty());
+        handler.handle("admin.undo", None).await.unwrap();
+        assert_eq!(budget_ids().await, kept);
+        assert_ne!(kept[0], budget_id);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_undo_restores_blocked_by() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+
+        let a = handler.task_create("work", "Design", None).await.unwrap();
+        let b = handler
+            .handle(
+                "task.create",
+                Some(json!({ "profile_id": "work", "title": "Build", "blocked_by": [a["id"]] })),
+            )
+            .await
+            .unwrap();
+        let blocked_by = || async {
+            handler
+                .task_manager
+                .get("work", b["id"].as_str().unwrap())
+                .await
+                .unwrap()
+                .blocked_by
+        };
+        let a_id = vec![a["id"].as_str().unwrap().to_string()];
+
+        handler
+            .handle(
+                "task.delete",
+                Some(json!({ "profile_id": "work", "task_id": a["id"] })),
+            )
+            .await
+            .unwrap();
+        assert!(blocked_by().await.is_empty());
+        handler.handle("admin.undo", None).await.unwrap();
+        assert_eq!(blocked_by().await, a_id);
+
+        let mut done = a.clone();
+        done["status"] = json!("done");
+        handler
+            .handle(
+                "task.update",
+                Some(json!({ "profile_id": "work", "task": done })),
+            )
+            .await
+            .unwrap();
+        assert!(blocked_by().await.is_empty());
+        handler.handle("admin.undo", None).await.unwrap();
+        assert_eq!(blocked_by().await, a_id);
+
+        // B stays out of the bulk update; only A moves to done.
+        let mut started = b.clone();
+        started["status"] = json!("in_progress");
+        handler
+            .handle(
+                "task.update",
+                Some(json!({ "profile_id": "work", "task": started })),
+            )
+            .await
+            .unwrap();
+        handler
+            .handle(
+                "task.bulk_status_update",
+                Some(json!({
+                    "profile_id": "work",
+                    "from_status": "todo",
+                    "to_status": "done",
+                })),
+            )
+            .await
+            .unwrap();
+        assert!(blocked_by().await.is_empty());
+        handler.handle("admin.undo", None).await.unwrap();
+        assert_eq!(blocked_by().await, a_id);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_undo_resumes_after_failed_step() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = create_handler(&temp_dir);
+
+        let end = chrono::Utc::now();
+        let inputs: Vec<Value> = (1..=3)
+            .map(|hours| {
+                let start = end - chrono::Duration::hours(hours);
+                json!({
+                    "start_time": start.to_rfc3339(),
+                    "end_time": (start + chrono::Duration::minutes(30)).to_rfc3339(),
+                })
+            })
+            .collect();
+        let batch = handler
+            .handle(
+                "entry.create_batch",
+                Some(json!({ "profile_id": "work", "entries": inputs })),
+            )
+            .await
+            .unwrap();
+        let ids = batch["ids"].as_array().unwrap().clone();
+        let journal = handler.handle("admin.journal", None).await.unwrap();
+        let batch_op = journal["operations"][0]["op_id"].clone();
+
+        // With the second entry gone, undoing the batch stops there.
+        handler
+            .handle(
+                "entry.delete",
+                Some(json!({ "profile_id": "work", "entry_id": ids[1] })),
+            )
+            .await
+            .unwrap();
+        let delete_op =
+            handler.handle("admin.journal", None).await.unwrap()["operations"][0]["op_id"].clone();
+        let failed = handler
+            .handle("admin.undo", Some(json!({ "op_id": batch_op })))
+            .await;
+        assert!(matches!(failed, Err(ApiError::Conflict(_))));
+        let journal = handler.handle("admin.journal", None).await.unwrap();
+        let record = &journal["operations"][1];
+        assert_eq!(record["op_id"], batch_op);
+        assert_eq!(record["partial"], true);
+        assert_eq!(record["undone"], false);
+
+        // Once it is back, the retry skips the entry already deleted.
+        handler
+            .handle("admin.undo", Some(json!({ "op_id": delete_op })))
+            .await
+            .unwrap();
+        let undone = handler
+            .handle("admin.undo", Some(json!({ "op_id": batch_op })))
+            .await
+            .unwrap();
+        assert_eq!(undone["operation"]["undone"], true);
+        assert_eq!(undone["operation"]["partial"], false);
+        let entries = handler
+            .handle("entry.list", Some(json!({ "profile_id": "work" })))
+            .await
+            .unwrap();
+        assert!(entries.as_array().unwrap().is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_json::{Value, json};
 use std::sync::Arc;
@@ -6,21 +7,27 @@ use super::{ApiError, Result};
 use crate::profile::ProfileManager;
 use mootimer_core::models::Profile;
 
-#[derive(Debug, Deserialize)]
-struct CreateProfileParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct CreateProfileParams {
     id: String,
     name: String,
     description: Option<String>,
+    /// A color name such as `cyan`, or hex like `#FF5733`. Picked from the
+    /// id when left out.
     color: Option<String>,
+    /// A single emoji or character. Picked from the id when left out.
+    icon: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct ProfileIdParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ProfileIdParams {
     profile_id: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct UpdateProfileParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct UpdateProfileParams {
+    /// A whole profile as `profile.get` returns it.
+    #[schemars(with = "Value")]
     profile: Profile,
 }
 
@@ -29,23 +36,21 @@ pub async fn create(manager: &Arc<ProfileManager>, params: Option<Value>) -> Res
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let mut profile =
-        Profile::new(params.id, params.name).map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let mut profile = Profile::new(params.id, params.name)?;
 
     if let Some(desc) = params.description {
         profile.update_description(Some(desc));
     }
 
     if let Some(color) = params.color {
-        profile
-            .update_color(Some(color))
-            .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        profile.update_color(Some(color))?;
     }
 
-    let created = manager
-        .create(profile)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    if let Some(icon) = params.icon {
+        profile.update_icon(Some(icon))?;
+    }
+
+    let created = manager.create(profile).await?;
 
     Ok(serde_json::to_value(&created)?)
 }
@@ -55,19 +60,13 @@ pub async fn get(manager: &Arc<ProfileManager>, params: Option<Value>) -> Result
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let profile = manager
-        .get(&params.profile_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let profile = manager.get(&params.profile_id).await?;
 
     Ok(serde_json::to_value(&profile)?)
 }
 
 pub async fn list(manager: &Arc<ProfileManager>, _params: Option<Value>) -> Result<Value> {
-    let profiles = manager
-        .list()
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let profiles = manager.list().await?;
 
     Ok(serde_json::to_value(&profiles)?)
 }
@@ -77,10 +76,7 @@ pub async fn update(manager: &Arc<ProfileManager>, params: Option<Value>) -> Res
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let updated = manager
-        .update(params.profile)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let updated = manager.update(params.profile).await?;
 
     Ok(serde_json::to_value(&updated)?)
 }
@@ -90,10 +86,7 @@ pub async fn delete(manager: &Arc<ProfileManager>, params: Option<Value>) -> Res
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    manager
-        .delete(&params.profile_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    manager.delete(&params.profile_id).await?;
 
     Ok(json!({
         "status": "deleted"
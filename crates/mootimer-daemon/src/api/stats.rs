@@ -0,0 +1,67 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::sync::Arc;
+
+use super::entry::{stats_json, use_working_hours};
+use super::{ApiError, Result};
+use crate::entry::{EntryManager, StatsWindow};
+use crate::profile::ProfileManager;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct BulkParams {
+    /// Every profile when absent.
+    profile_ids: Option<Vec<String>>,
+    /// `today`, `week`, `month`, `<n>d` or `YYYY-MM-DD..YYYY-MM-DD`.
+    windows: Vec<String>,
+}
+
+/// Stats for several profiles and windows in one call, keyed by profile id
+/// and then by window as given. Each window has the same fields as
+/// `entry.stats_today`.
+pub async fn bulk(
+    entry_manager: &Arc<EntryManager>,
+    profile_manager: &Arc<ProfileManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: BulkParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    if params.windows.is_empty() {
+        return Err(ApiError::InvalidParams("No windows requested".to_string()));
+    }
+    let windows = params
+        .windows
+        .iter()
+        .map(|w| {
+            StatsWindow::parse(w)
+                .ok_or_else(|| ApiError::InvalidParams(format!("Invalid window: {}", w)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let profile_ids = match params.profile_ids {
+        Some(ids) => ids,
+        None => profile_manager
+            .list()
+            .await?
+            .into_iter()
+            .map(|p| p.id)
+            .collect(),
+    };
+
+    let mut profiles = Map::new();
+    for profile_id in profile_ids {
+        use_working_hours(entry_manager, profile_manager, &profile_id).await;
+        let all_stats = entry_manager.bulk_stats(&profile_id, &windows).await?;
+        let by_window: Map<String, Value> = params
+            .windows
+            .iter()
+            .zip(&all_stats)
+            .map(|(window, stats)| (window.clone(), stats_json(stats)))
+            .collect();
+        profiles.insert(profile_id, Value::Object(by_window));
+    }
+
+    Ok(json!({ "profiles": profiles }))
+}
@@ -0,0 +1,59 @@
+use mootimer_core::models::{ActiveTimer, DEFAULT_SUMMARY_FORMAT, default_color_and_icon};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use super::Result;
+use crate::profile::ProfileManager;
+use crate::timer::TimerManager;
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub(crate) struct SummaryParams {
+    /// Limit to one profile's timers; all profiles when absent.
+    profile_id: Option<String>,
+    /// Template using `SUMMARY_VARIABLES`; `DEFAULT_SUMMARY_FORMAT` when absent.
+    format: Option<String>,
+}
+
+/// One line describing the timer a user is most likely watching, for
+/// terminal titles and tmux's `status-right`. The text is empty when no
+/// timer is active so a status bar can show nothing at all. The timer's
+/// profile color and icon come along so a status bar can match the TUI.
+pub async fn summary(
+    manager: &Arc<TimerManager>,
+    profiles: &Arc<ProfileManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: SummaryParams = match params {
+        Some(params) => serde_json::from_value(params)?,
+        None => SummaryParams::default(),
+    };
+
+    let timers: Vec<ActiveTimer> = match &params.profile_id {
+        Some(profile_id) => manager.get_timers_by_profile(profile_id).await,
+        None => manager.get_all_timers().await.into_values().collect(),
+    };
+    // A running timer beats a paused one; among equals the newest wins.
+    let timer = timers.iter().max_by_key(|t| (t.is_running(), t.start_time));
+
+    let (color, icon) = match timer {
+        Some(t) => match profiles.get(&t.profile_id).await {
+            Ok(profile) => (profile.color, profile.icon),
+            Err(_) => {
+                let (color, icon) = default_color_and_icon(&t.profile_id);
+                (Some(color.to_string()), Some(icon.to_string()))
+            }
+        },
+        None => (None, None),
+    };
+
+    let format = params.format.as_deref().unwrap_or(DEFAULT_SUMMARY_FORMAT);
+    Ok(json!({
+        "text": timer.map(|t| t.summary(format)).unwrap_or_default(),
+        "timer_id": timer.map(|t| t.id.clone()),
+        "profile_id": timer.map(|t| t.profile_id.clone()),
+        "profile_color": color,
+        "profile_icon": icon,
+    }))
+}
@@ -1,71 +1,262 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_json::{Value, json};
 use std::sync::Arc;
 
 use super::{ApiError, Result};
 use crate::config::ConfigManager;
+use crate::entry::EntryManager;
+use crate::event_manager::EventManager;
+use crate::events::SyncEvent;
+use crate::profile::ProfileManager;
 use crate::sync::SyncManager;
+use crate::sync::merge::{ConflictKind, SyncConflict};
+use crate::task::TaskManager;
+use mootimer_core::models::{Entry, Task};
 
-#[derive(Debug, Deserialize)]
-struct SetRemoteParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SetRemoteParams {
     url: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct CommitParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SwitchBranchParams {
+    branch: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct CommitParams {
     message: String,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct HistoryParams {
+    #[serde(default = "default_history_limit")]
+    limit: usize,
+}
+
+fn default_history_limit() -> usize {
+    20
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum Resolution {
+    Local,
+    Remote,
+    Merged,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ResolveConflictParams {
+    conflict_id: String,
+    resolution: Resolution,
+    /// The record to keep for `merged`; `null` deletes it.
+    #[serde(default)]
+    merged: Option<Value>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub(crate) struct StatusParams {
+    #[serde(default)]
+    refresh: bool,
+}
+
 pub async fn init(sync_manager: &Arc<SyncManager>, _params: Option<Value>) -> Result<Value> {
-    sync_manager
-        .init_repo()
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    sync_manager.init_repo().await?;
 
     Ok(json!({
         "status": "initialized"
     }))
 }
 
+/// The repository status. It is cached between writes; pass
+/// `refresh: true` to ask git again regardless.
 pub async fn status(
     sync_manager: &Arc<SyncManager>,
     config_manager: &Arc<ConfigManager>,
-    _params: Option<Value>,
+    params: Option<Value>,
 ) -> Result<Value> {
+    let params: StatusParams = match params {
+        Some(params) => serde_json::from_value(params)?,
+        None => StatusParams::default(),
+    };
     let config = config_manager.get().await;
 
-    let status = sync_manager
-        .get_status(&config.sync)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let status = if params.refresh {
+        sync_manager.refresh_status(&config.sync).await
+    } else {
+        sync_manager.get_status(&config.sync).await
+    }?;
 
     Ok(serde_json::to_value(&status)?)
 }
 
+/// Pulls and pushes. Records both sides changed keep the local version
+/// and wait in `sync.conflicts`; a `sync.event` announces them. Each step,
+/// and how it ended, is announced as a `sync.event` too, so clients can
+/// show progress or stop waiting for the reply.
 pub async fn sync(
     sync_manager: &Arc<SyncManager>,
     config_manager: &Arc<ConfigManager>,
+    profile_manager: &Arc<ProfileManager>,
+    task_manager: &Arc<TaskManager>,
+    entry_manager: &Arc<EntryManager>,
+    event_manager: &Arc<EventManager>,
     _params: Option<Value>,
 ) -> Result<Value> {
     let config = config_manager.get().await;
 
-    let result = sync_manager
-        .sync(&config.sync)
+    let events = event_manager.clone();
+    let result = match sync_manager
+        .sync_with_progress(&config.sync, move |stage| {
+            events.emit_sync(SyncEvent::stage(stage))
+        })
         .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    {
+        Ok(result) => result,
+        Err(e) => {
+            event_manager.emit_sync(SyncEvent::failed(e.to_string()));
+            return Err(e.into());
+        }
+    };
+
+    if result.pulled {
+        // The pull may have brought in profiles, tasks and entries.
+        task_manager.clear_cache().await;
+        entry_manager.clear_cache().await;
+        profile_manager.load_all().await?;
+    }
+
+    if result.conflicts > 0 {
+        let waiting = sync_manager
+            .conflicts()
+            .await
+            .map(|c| c.len())
+            .unwrap_or(result.conflicts);
+        event_manager.emit_sync(SyncEvent::conflicts(waiting));
+    }
+    event_manager.emit_sync(SyncEvent::finished(result.pulled, result.pushed));
 
     Ok(serde_json::to_value(&result)?)
 }
 
+/// Conflicts left by earlier syncs, each with the local and remote version
+/// of the task or entry.
+pub async fn conflicts(sync_manager: &Arc<SyncManager>, _params: Option<Value>) -> Result<Value> {
+    let conflicts = sync_manager.conflicts().await?;
+
+    Ok(serde_json::to_value(&conflicts)?)
+}
+
+/// Settles one conflict by writing the local, remote or a merged version
+/// of the record, then forgets it. Other conflicts are left alone.
+pub async fn resolve_conflict(
+    sync_manager: &Arc<SyncManager>,
+    task_manager: &Arc<TaskManager>,
+    entry_manager: &Arc<EntryManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: ResolveConflictParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let conflict = sync_manager
+        .conflicts()
+        .await?
+        .into_iter()
+        .find(|c| c.id == params.conflict_id)
+        .ok_or_else(|| {
+            ApiError::InvalidParams(format!("No sync conflict {}", params.conflict_id))
+        })?;
+
+    let chosen = match params.resolution {
+        Resolution::Local => conflict.local.clone(),
+        Resolution::Remote => conflict.remote.clone(),
+        Resolution::Merged => params.merged.filter(|v| !v.is_null()),
+    };
+
+    match conflict.kind {
+        ConflictKind::Task => write_task(task_manager, &conflict, chosen).await?,
+        ConflictKind::Entry => write_entry(entry_manager, &conflict, chosen).await?,
+    }
+
+    sync_manager.remove_conflict(&conflict.id).await?;
+
+    let remaining = sync_manager.conflicts().await.map(|c| c.len()).unwrap_or(0);
+
+    Ok(json!({
+        "status": "resolved",
+        "conflict_id": conflict.id,
+        "remaining": remaining,
+    }))
+}
+
+/// Makes `chosen` the stored version of the conflicted task; `None`
+/// deletes it.
+async fn write_task(
+    task_manager: &Arc<TaskManager>,
+    conflict: &SyncConflict,
+    chosen: Option<Value>,
+) -> Result<()> {
+    let profile_id = &conflict.profile_id;
+    let exists = task_manager
+        .get(profile_id, &conflict.record_id)
+        .await
+        .is_ok();
+    let result = match chosen {
+        Some(value) => {
+            let mut task: Task = serde_json::from_value(value)?;
+            task.id = conflict.record_id.clone();
+            if exists {
+                task_manager.update(profile_id, task).await.map(|_| ())
+            } else {
+                task_manager.create(profile_id, task).await.map(|_| ())
+            }
+        }
+        None if exists => task_manager.delete(profile_id, &conflict.record_id).await,
+        None => Ok(()),
+    };
+    Ok(result?)
+}
+
+/// Makes `chosen` the stored version of the conflicted entry; `None`
+/// deletes it.
+async fn write_entry(
+    entry_manager: &Arc<EntryManager>,
+    conflict: &SyncConflict,
+    chosen: Option<Value>,
+) -> Result<()> {
+    let profile_id = &conflict.profile_id;
+    let exists = entry_manager
+        .get(profile_id, &conflict.record_id)
+        .await
+        .is_ok();
+    let result = match chosen {
+        Some(value) => {
+            let mut entry: Entry = serde_json::from_value(value)?;
+            entry.id = conflict.record_id.clone();
+            if exists {
+                entry_manager.update(profile_id, entry).await
+            } else {
+                entry_manager
+                    .add_with(profile_id, entry, true)
+                    .await
+                    .map(|_| ())
+            }
+        }
+        None if exists => entry_manager.delete(profile_id, &conflict.record_id).await,
+        None => Ok(()),
+    };
+    Ok(result?)
+}
+
 pub async fn commit(sync_manager: &Arc<SyncManager>, params: Option<Value>) -> Result<Value> {
     let params: CommitParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let commit_id = sync_manager
-        .auto_commit(&params.message)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let commit_id = sync_manager.auto_commit(&params.message).await?;
 
     Ok(json!({
         "commit_id": commit_id,
@@ -78,10 +269,7 @@ pub async fn set_remote(sync_manager: &Arc<SyncManager>, params: Option<Value>)
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    sync_manager
-        .set_remote(&params.url)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    sync_manager.set_remote(&params.url).await?;
 
     Ok(json!({
         "status": "remote_set",
@@ -89,6 +277,119 @@ pub async fn set_remote(sync_manager: &Arc<SyncManager>, params: Option<Value>)
     }))
 }
 
+/// Files changed since the last commit, with line counts and totals.
+pub async fn diff(sync_manager: &Arc<SyncManager>, _params: Option<Value>) -> Result<Value> {
+    let files = sync_manager.diff().await?;
+
+    Ok(json!({
+        "insertions": files.iter().map(|f| f.insertions).sum::<usize>(),
+        "deletions": files.iter().map(|f| f.deletions).sum::<usize>(),
+        "files": files,
+    }))
+}
+
+/// The changes since the last commit as a unified diff.
+pub async fn diff_full(sync_manager: &Arc<SyncManager>, _params: Option<Value>) -> Result<Value> {
+    let diff = sync_manager.diff_full().await?;
+
+    Ok(json!({ "diff": diff }))
+}
+
+/// The last `limit` commits (default 20), newest first. Auto-commits
+/// carry the profile, entry, task and duration they recorded.
+pub async fn history(sync_manager: &Arc<SyncManager>, params: Option<Value>) -> Result<Value> {
+    let params: HistoryParams = serde_json::from_value(params.unwrap_or_else(|| json!({})))?;
+
+    let history = sync_manager.history(params.limit).await?;
+
+    Ok(serde_json::to_value(&history)?)
+}
+
+pub async fn list_branches(
+    sync_manager: &Arc<SyncManager>,
+    _params: Option<Value>,
+) -> Result<Value> {
+    let branches = sync_manager.list_branches().await?;
+
+    Ok(serde_json::to_value(&branches)?)
+}
+
+pub async fn switch_branch(
+    sync_manager: &Arc<SyncManager>,
+    profile_manager: &Arc<ProfileManager>,
+    task_manager: &Arc<TaskManager>,
+    entry_manager: &Arc<EntryManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: SwitchBranchParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    sync_manager.switch_branch(&params.branch).await?;
+
+    // Profiles, tasks and entries on the new branch may differ.
+    task_manager.clear_cache().await;
+    entry_manager.clear_cache().await;
+    profile_manager.load_all().await?;
+
+    Ok(json!({
+        "status": "switched",
+        "branch": params.branch
+    }))
+}
+
+/// Puts uncommitted changes aside. The data files go back to the last
+/// commit, so everything is reloaded from them.
+pub async fn stash(
+    sync_manager: &Arc<SyncManager>,
+    profile_manager: &Arc<ProfileManager>,
+    task_manager: &Arc<TaskManager>,
+    entry_manager: &Arc<EntryManager>,
+    _params: Option<Value>,
+) -> Result<Value> {
+    let stash_id = sync_manager.stash().await?;
+
+    task_manager.clear_cache().await;
+    entry_manager.clear_cache().await;
+    profile_manager.load_all().await?;
+
+    Ok(json!({
+        "status": if stash_id.is_some() { "stashed" } else { "no_changes" },
+        "stash_id": stash_id,
+    }))
+}
+
+/// Brings back the last `sync.stash`. Records changed both in the stash
+/// and in commits since become conflicts, as in `sync.sync`.
+pub async fn stash_pop(
+    sync_manager: &Arc<SyncManager>,
+    profile_manager: &Arc<ProfileManager>,
+    task_manager: &Arc<TaskManager>,
+    entry_manager: &Arc<EntryManager>,
+    event_manager: &Arc<EventManager>,
+    _params: Option<Value>,
+) -> Result<Value> {
+    let conflicts = sync_manager.stash_pop().await?;
+
+    task_manager.clear_cache().await;
+    entry_manager.clear_cache().await;
+    profile_manager.load_all().await?;
+
+    if conflicts > 0 {
+        let waiting = sync_manager
+            .conflicts()
+            .await
+            .map(|c| c.len())
+            .unwrap_or(conflicts);
+        event_manager.emit_sync(SyncEvent::conflicts(waiting));
+    }
+
+    Ok(json!({
+        "status": "restored",
+        "conflicts": conflicts,
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,4 +437,258 @@ mod tests {
         assert!(status == "committed" || status == "no_changes");
         assert!(result.get("commit_id").is_some());
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_diff_and_history() {
+        use mootimer_core::storage::init_data_dir;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+            std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config"));
+        }
+
+        let manager = Arc::new(SyncManager::new().unwrap());
+        assert!(diff(&manager, None).await.is_err());
+
+        manager.init_repo().await.unwrap();
+        manager.auto_commit("Initial commit").await.unwrap();
+        std::fs::write(init_data_dir().unwrap().join("notes.txt"), "a\nb\n").unwrap();
+
+        let stats = diff(&manager, None).await.unwrap();
+        assert_eq!(stats["insertions"], 2);
+        assert_eq!(stats["deletions"], 0);
+        assert!(
+            stats["files"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|f| f["path"] == "notes.txt")
+        );
+
+        let full = diff_full(&manager, None).await.unwrap();
+        assert!(full["diff"].as_str().unwrap().contains("+a\n+b\n"));
+
+        manager.auto_commit("Add notes").await.unwrap();
+        let history = history(&manager, Some(json!({ "limit": 5 })))
+            .await
+            .unwrap();
+        assert_eq!(history[0]["summary"], "Add notes");
+        assert_eq!(history[0]["profile_id"], Value::Null);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_list_and_switch_branches() {
+        use crate::event_manager::EventManager;
+        use mootimer_core::storage::init_data_dir;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+            std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config"));
+        }
+
+        let events = Arc::new(EventManager::new());
+        let sync_manager = Arc::new(SyncManager::new().unwrap());
+        let profile_manager = Arc::new(ProfileManager::new(events.clone()).unwrap());
+        let task_manager = Arc::new(TaskManager::new(events.clone()).unwrap());
+        let entry_manager = Arc::new(EntryManager::new(events).unwrap());
+
+        assert!(list_branches(&sync_manager, None).await.is_err());
+
+        sync_manager.init_repo().await.unwrap();
+        std::fs::write(init_data_dir().unwrap().join("test.txt"), "Hello").unwrap();
+        sync_manager.auto_commit("Initial commit").await.unwrap();
+
+        let branches = list_branches(&sync_manager, None).await.unwrap();
+        let branches = branches.as_array().unwrap();
+        assert_eq!(branches.len(), 1);
+        assert_eq!(branches[0]["current"], true);
+        assert_eq!(branches[0]["remote"], false);
+
+        let current = branches[0]["name"].as_str().unwrap();
+        let result = switch_branch(
+            &sync_manager,
+            &profile_manager,
+            &task_manager,
+            &entry_manager,
+            Some(json!({ "branch": current })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["status"], "switched");
+
+        let missing = switch_branch(
+            &sync_manager,
+            &profile_manager,
+            &task_manager,
+            &entry_manager,
+            Some(json!({ "branch": "nowhere" })),
+        )
+        .await;
+        assert!(missing.is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_stash_and_pop_restore_uncommitted_entries() {
+        use crate::event_manager::EventManager;
+        use mootimer_core::models::TimerMode;
+        use mootimer_core::storage::init_data_dir;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+            std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config"));
+        }
+
+        let events = Arc::new(EventManager::new());
+        let sync_manager = Arc::new(SyncManager::new().unwrap());
+        let profile_manager = Arc::new(ProfileManager::new(events.clone()).unwrap());
+        let task_manager = Arc::new(TaskManager::new(events.clone()).unwrap());
+        let entry_manager = Arc::new(EntryManager::new(events.clone()).unwrap());
+        sync_manager.init_repo().await.unwrap();
+        std::fs::write(init_data_dir().unwrap().join("test.txt"), "Hello").unwrap();
+        sync_manager.auto_commit("Initial commit").await.unwrap();
+
+        let end = chrono::Utc::now() - chrono::Duration::hours(1);
+        let entry = Entry::create_completed(
+            None,
+            None,
+            end - chrono::Duration::minutes(30),
+            end,
+            TimerMode::Manual,
+        )
+        .unwrap();
+        entry_manager
+            .add("stash_test", entry.clone())
+            .await
+            .unwrap();
+
+        let stashed = stash(
+            &sync_manager,
+            &profile_manager,
+            &task_manager,
+            &entry_manager,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(stashed["status"], "stashed");
+        assert!(
+            entry_manager
+                .get_all("stash_test")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let restored = stash_pop(
+            &sync_manager,
+            &profile_manager,
+            &task_manager,
+            &entry_manager,
+            &events,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(restored["conflicts"], 0);
+        let entries = entry_manager.get_all("stash_test").await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, entry.id);
+
+        let nothing = stash_pop(
+            &sync_manager,
+            &profile_manager,
+            &task_manager,
+            &entry_manager,
+            &events,
+            None,
+        )
+        .await;
+        assert!(nothing.is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_resolve_conflicts_one_at_a_time() {
+        use crate::event_manager::EventManager;
+        use mootimer_core::storage::init_data_dir;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+            std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config"));
+        }
+
+        let events = Arc::new(EventManager::new());
+        let sync_manager = Arc::new(SyncManager::new().unwrap());
+        let task_manager = Arc::new(TaskManager::new(events.clone()).unwrap());
+        let entry_manager = Arc::new(EntryManager::new(events).unwrap());
+        sync_manager.init_repo().await.unwrap();
+
+        let local = Task::new("Local title".to_string()).unwrap();
+        task_manager
+            .create("sync_test", local.clone())
+            .await
+            .unwrap();
+        let mut remote = local.clone();
+        remote.title = "Remote title".to_string();
+
+        let conflict =
+            |record_id: &str, local: Option<&Task>, remote: Option<&Task>| SyncConflict {
+                id: format!("task:sync_test:{}", record_id),
+                kind: ConflictKind::Task,
+                profile_id: "sync_test".to_string(),
+                record_id: record_id.to_string(),
+                local: local.map(|t| serde_json::to_value(t).unwrap()),
+                remote: remote.map(|t| serde_json::to_value(t).unwrap()),
+                detected_at: chrono::Utc::now(),
+            };
+        let stored = vec![
+            conflict(&local.id, Some(&local), Some(&remote)),
+            conflict("gone", None, Some(&remote)),
+        ];
+        std::fs::write(
+            init_data_dir()
+                .unwrap()
+                .join(".git/mootimer-conflicts.json"),
+            serde_json::to_vec(&stored).unwrap(),
+        )
+        .unwrap();
+
+        let listed = conflicts(&sync_manager, None).await.unwrap();
+        assert_eq!(listed.as_array().unwrap().len(), 2);
+
+        let resolve = |id: String, resolution: &str| {
+            resolve_conflict(
+                &sync_manager,
+                &task_manager,
+                &entry_manager,
+                Some(json!({ "conflict_id": id, "resolution": resolution })),
+            )
+        };
+        let result = resolve(stored[0].id.clone(), "remote").await.unwrap();
+        assert_eq!(result["remaining"], 1);
+        let task = task_manager.get("sync_test", &local.id).await.unwrap();
+        assert_eq!(task.title, "Remote title");
+
+        // Keeping the local side of a remote-only record leaves it deleted.
+        let result = resolve(stored[1].id.clone(), "local").await.unwrap();
+        assert_eq!(result["remaining"], 0);
+        assert!(task_manager.get("sync_test", "gone").await.is_err());
+
+        assert!(resolve(stored[1].id.clone(), "local").await.is_err());
+    }
 }
@@ -1,58 +1,201 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_json::{Value, json};
 use std::sync::Arc;
 
 use super::{ApiError, Result};
 use crate::entry::EntryManager;
-use crate::task::TaskManager;
+use crate::task::{ReorderAnchor, TaskFilter, TaskManager, TaskSearch};
 use crate::timer::TimerManager;
-use mootimer_core::models::Task;
+use chrono::{DateTime, Utc};
+use mootimer_core::models::{Task, TaskStatus, TimerState};
+use mootimer_core::search::TagMode;
 
-#[derive(Debug, Deserialize)]
-struct CreateTaskParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct CreateTaskParams {
     profile_id: String,
     title: String,
     description: Option<String>,
     tags: Option<Vec<String>>,
+    /// Ids of tasks in the same profile that must be finished first.
+    #[serde(default)]
+    blocked_by: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct TaskIdParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct AddSubtaskParams {
+    profile_id: String,
+    parent_task_id: String,
+    title: String,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct CopyTaskParams {
     profile_id: String,
     task_id: String,
+    /// Defaults to the original title prefixed with "Copy of ".
+    new_title: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct ListTasksParams {
+/// Give exactly one of `before` and `after`.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ReorderTaskParams {
     profile_id: String,
+    task_id: String,
+    /// Put the task just above this one, which must have the same status.
+    before: Option<String>,
+    /// Put the task just below this one, which must have the same status.
+    after: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct UpdateTaskParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct TaskIdParams {
     profile_id: String,
+    task_id: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ProfileIdParams {
+    profile_id: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ListTasksParams {
+    profile_id: String,
+    sort_by: Option<TaskSortBy>,
+    /// Nest subtasks under their parents as `subtasks` instead of listing
+    /// them alongside.
+    #[serde(default)]
+    include_subtasks: bool,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum TaskSortBy {
+    /// Most recently used first; never-used tasks last, newest first.
+    LastUsed,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct StaleTasksParams {
+    profile_id: String,
+    days: Option<u32>,
+}
+
+const DEFAULT_STALE_DAYS: u32 = 30;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct DueDateParams {
+    profile_id: String,
+    #[serde(default = "default_true")]
+    include_overdue: bool,
+    #[serde(default)]
+    include_no_due_date: bool,
+    /// No limit when absent.
+    days_ahead: Option<u32>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct UpdateTaskParams {
+    profile_id: String,
+    /// A whole task as `task.get` returns it.
+    #[schemars(with = "Value")]
     task: Task,
 }
 
-#[derive(Debug, Deserialize)]
-struct SearchTasksParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SetDescriptionParams {
+    profile_id: String,
+    task_id: String,
+    description: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SetNotesParams {
     profile_id: String,
+    task_id: String,
+    notes: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SearchTasksParams {
+    profile_id: String,
+    /// Words that must each appear in order, not necessarily adjacent.
     query: String,
+    #[serde(default)]
+    case_sensitive: bool,
+    #[serde(default)]
+    exclude_archived: bool,
+    /// Only tasks carrying these tags; see `tag_mode`.
+    #[serde(default)]
+    tags: Vec<String>,
+    /// `all` (the default) needs every tag in `tags`, `any` just one.
+    #[serde(default)]
+    tag_mode: TagMode,
+    /// No limit when absent.
+    limit: Option<usize>,
 }
 
-#[derive(Debug, Deserialize)]
-struct MoveTaskParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct MoveTaskParams {
     source_profile_id: String,
     target_profile_id: String,
     task_id: String,
     move_entries: Option<bool>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+struct TaskFilterParams {
+    tags: Option<Vec<String>>,
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct BulkStatusUpdateParams {
+    profile_id: String,
+    from_status: TaskStatus,
+    to_status: TaskStatus,
+    filter: Option<TaskFilterParams>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct BulkTagParams {
+    profile_id: String,
+    task_ids: Vec<String>,
+    tags: Vec<String>,
+}
+
+impl BulkTagParams {
+    fn parse(params: Option<Value>) -> Result<Self> {
+        let mut params: Self = serde_json::from_value(
+            params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+        )?;
+        let mut tags: Vec<String> = Vec::new();
+        for tag in params.tags.iter().map(|t| t.trim()) {
+            if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+        if tags.is_empty() {
+            return Err(ApiError::InvalidParams("No tags given".to_string()));
+        }
+        params.tags = tags;
+        Ok(params)
+    }
+}
+
 pub async fn create(manager: &Arc<TaskManager>, params: Option<Value>) -> Result<Value> {
     let params: CreateTaskParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let mut task = Task::new(params.title).map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let mut task = Task::new(params.title)?;
 
     if let Some(desc) = params.description {
         task.update_description(Some(desc));
@@ -63,37 +206,246 @@ pub async fn create(manager: &Arc<TaskManager>, params: Option<Value>) -> Result
             task.add_tag(tag);
         }
     }
+    task.blocked_by = params.blocked_by;
 
-    let created = manager
-        .create(&params.profile_id, task)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let created = manager.append(&params.profile_id, task).await?;
+
+    Ok(serde_json::to_value(&created)?)
+}
+
+/// Creates a task under `parent_task_id`, which must exist and must not be
+/// a subtask itself.
+pub async fn add_subtask(manager: &Arc<TaskManager>, params: Option<Value>) -> Result<Value> {
+    let params: AddSubtaskParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let parent = check_parent(manager, &params.profile_id, &params.parent_task_id).await?;
+
+    let mut task = Task::new(params.title)?;
+    task.parent_task_id = Some(parent.id);
+    if let Some(desc) = params.description {
+        task.update_description(Some(desc));
+    }
+
+    let created = manager.append(&params.profile_id, task).await?;
 
     Ok(serde_json::to_value(&created)?)
 }
 
+/// The task that may become a parent: it has to exist and must not be a
+/// subtask itself.
+async fn check_parent(manager: &TaskManager, profile_id: &str, parent_id: &str) -> Result<Task> {
+    let parent = manager.get(profile_id, parent_id).await?;
+    if parent.parent_task_id.is_some() {
+        return Err(ApiError::InvalidParams(format!(
+            "Task {} is a subtask; subtasks cannot have subtasks",
+            parent.id
+        )));
+    }
+    Ok(parent)
+}
+
+/// Creates a copy of `task_id` in the same profile with a new id. The copy
+/// starts as `todo`, keeps the description, notes, tags and other details,
+/// and has not been used yet.
+pub async fn copy(manager: &Arc<TaskManager>, params: Option<Value>) -> Result<Value> {
+    let params: CopyTaskParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let original = manager.get(&params.profile_id, &params.task_id).await?;
+    let title = params
+        .new_title
+        .unwrap_or_else(|| format!("Copy of {}", original.title));
+    let fresh = Task::new(title)?;
+
+    let task = Task {
+        id: fresh.id,
+        title: fresh.title,
+        status: TaskStatus::Todo,
+        last_used_at: None,
+        created_at: fresh.created_at,
+        updated_at: fresh.updated_at,
+        ..original
+    };
+    let created = manager.append(&params.profile_id, task).await?;
+
+    Ok(serde_json::to_value(&created)?)
+}
+
+/// Moves a task within its status column; see `TaskManager::reorder`.
+pub async fn reorder(manager: &Arc<TaskManager>, params: Option<Value>) -> Result<Value> {
+    let params: ReorderTaskParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+    let anchor = match (params.before, params.after) {
+        (Some(id), None) => ReorderAnchor::Before(id),
+        (None, Some(id)) => ReorderAnchor::After(id),
+        _ => {
+            return Err(ApiError::InvalidParams(
+                "Give exactly one of before and after".to_string(),
+            ));
+        }
+    };
+
+    let (task, task_ids) = manager
+        .reorder(&params.profile_id, &params.task_id, &anchor)
+        .await?;
+    Ok(json!({ "task": task, "task_ids": task_ids }))
+}
+
+/// The tasks blocking `task_id`, directly or through other blockers, each
+/// with its `depth` in the chain.
+pub async fn blockers(manager: &Arc<TaskManager>, params: Option<Value>) -> Result<Value> {
+    let params: TaskIdParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let chain = manager
+        .blockers(&params.profile_id, &params.task_id)
+        .await?;
+
+    let blockers: Vec<Value> = chain
+        .into_iter()
+        .map(|(depth, task)| json!({ "depth": depth, "task": task }))
+        .collect();
+    Ok(json!({
+        "task_id": params.task_id,
+        "blocked": !blockers.is_empty(),
+        "blockers": blockers,
+    }))
+}
+
 pub async fn get(manager: &Arc<TaskManager>, params: Option<Value>) -> Result<Value> {
     let params: TaskIdParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let task = manager
-        .get(&params.profile_id, &params.task_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let task = manager.get(&params.profile_id, &params.task_id).await?;
 
     Ok(serde_json::to_value(&task)?)
 }
 
-pub async fn list(manager: &Arc<TaskManager>, params: Option<Value>) -> Result<Value> {
+/// The task linked to the profile's running timer, or `null` when no timer
+/// is running or it has no task.
+pub async fn get_active(
+    task_manager: &Arc<TaskManager>,
+    timer_manager: &Arc<TimerManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: ProfileIdParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let timers = timer_manager
+        .get_timers_by_profile(&params.profile_id)
+        .await;
+    let Some(task_id) = timers
+        .into_iter()
+        .find(|t| t.state == TimerState::Running)
+        .and_then(|t| t.task_id)
+    else {
+        return Ok(Value::Null);
+    };
+
+    // The task may have been deleted while its timer kept running.
+    match task_manager.get(&params.profile_id, &task_id).await {
+        Ok(task) => Ok(serde_json::to_value(&task)?),
+        Err(_) => Ok(Value::Null),
+    }
+}
+
+pub async fn list(
+    task_manager: &Arc<TaskManager>,
+    entry_manager: &Arc<EntryManager>,
+    params: Option<Value>,
+) -> Result<Value> {
     let params: ListTasksParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
+    task_manager
+        .backfill_last_used(&params.profile_id, entry_manager)
+        .await?;
+
+    let mut tasks = task_manager.list(&params.profile_id).await?;
+
+    match params.sort_by {
+        Some(TaskSortBy::LastUsed) => tasks.sort_by(|a, b| {
+            b.last_used_at
+                .cmp(&a.last_used_at)
+                .then(b.created_at.cmp(&a.created_at))
+        }),
+        None => {}
+    }
+
+    if params.include_subtasks {
+        return nest_subtasks(tasks);
+    }
+    Ok(serde_json::to_value(&tasks)?)
+}
+
+/// Top-level tasks, each with its subtasks in a `subtasks` array. Subtasks
+/// whose parent is gone are listed at the top level.
+fn nest_subtasks(tasks: Vec<Task>) -> Result<Value> {
+    let ids: std::collections::HashSet<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+    let (subtasks, top_level): (Vec<&Task>, Vec<&Task>) = tasks.iter().partition(|t| {
+        t.parent_task_id
+            .as_deref()
+            .is_some_and(|parent| ids.contains(parent))
+    });
+
+    let mut nested = Vec::with_capacity(top_level.len());
+    for task in top_level {
+        let children: Vec<&Task> = subtasks
+            .iter()
+            .copied()
+            .filter(|s| s.parent_task_id.as_deref() == Some(task.id.as_str()))
+            .collect();
+        let mut value = serde_json::to_value(task)?;
+        value["subtasks"] = serde_json::to_value(children)?;
+        nested.push(value);
+    }
+    Ok(Value::Array(nested))
+}
+
+pub async fn stale(
+    task_manager: &Arc<TaskManager>,
+    entry_manager: &Arc<EntryManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: StaleTasksParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    task_manager
+        .backfill_last_used(&params.profile_id, entry_manager)
+        .await?;
+
+    let days = params.days.unwrap_or(DEFAULT_STALE_DAYS);
+    let cutoff = Utc::now() - chrono::Duration::days(days as i64);
+    let tasks = task_manager.stale(&params.profile_id, cutoff).await?;
+
+    Ok(serde_json::to_value(&tasks)?)
+}
+
+/// Open tasks sorted by due date, overdue first. Dates are compared with
+/// the daemon's local date.
+pub async fn list_by_due_date(manager: &Arc<TaskManager>, params: Option<Value>) -> Result<Value> {
+    let params: DueDateParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
     let tasks = manager
-        .list(&params.profile_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        .by_due_date(
+            &params.profile_id,
+            chrono::Local::now().date_naive(),
+            params.include_overdue,
+            params.include_no_due_date,
+            params.days_ahead,
+        )
+        .await?;
 
     Ok(serde_json::to_value(&tasks)?)
 }
@@ -103,10 +455,64 @@ pub async fn update(manager: &Arc<TaskManager>, params: Option<Value>) -> Result
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
+    if let Some(parent_id) = &params.task.parent_task_id {
+        if *parent_id == params.task.id {
+            return Err(ApiError::InvalidParams(format!(
+                "Task {} cannot be its own parent",
+                parent_id
+            )));
+        }
+        check_parent(manager, &params.profile_id, parent_id).await?;
+        let tasks = manager.list(&params.profile_id).await?;
+        if tasks
+            .iter()
+            .any(|t| t.parent_task_id.as_deref() == Some(params.task.id.as_str()))
+        {
+            return Err(ApiError::InvalidParams(format!(
+                "Task {} has subtasks and cannot become a subtask",
+                params.task.id
+            )));
+        }
+    }
+
+    let updated = manager.update(&params.profile_id, params.task).await?;
+
+    Ok(serde_json::to_value(&updated)?)
+}
+
+/// Empty or whitespace-only text clears the field.
+fn non_empty(text: String) -> Option<String> {
+    if text.trim().is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+pub async fn set_description(manager: &Arc<TaskManager>, params: Option<Value>) -> Result<Value> {
+    let params: SetDescriptionParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
     let updated = manager
-        .update(&params.profile_id, params.task)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        .set_description(
+            &params.profile_id,
+            &params.task_id,
+            non_empty(params.description),
+        )
+        .await?;
+
+    Ok(serde_json::to_value(&updated)?)
+}
+
+pub async fn set_notes(manager: &Arc<TaskManager>, params: Option<Value>) -> Result<Value> {
+    let params: SetNotesParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let updated = manager
+        .set_notes(&params.profile_id, &params.task_id, non_empty(params.notes))
+        .await?;
 
     Ok(serde_json::to_value(&updated)?)
 }
@@ -116,10 +522,7 @@ pub async fn delete(manager: &Arc<TaskManager>, params: Option<Value>) -> Result
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    manager
-        .delete(&params.profile_id, &params.task_id)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    manager.delete(&params.profile_id, &params.task_id).await?;
 
     Ok(json!({
         "status": "deleted"
@@ -131,12 +534,28 @@ pub async fn search(manager: &Arc<TaskManager>, params: Option<Value>) -> Result
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let tasks = manager
-        .search(&params.profile_id, &params.query)
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+    let options = TaskSearch {
+        case_sensitive: params.case_sensitive,
+        exclude_archived: params.exclude_archived,
+        tags: params.tags,
+        tag_mode: params.tag_mode,
+    };
+    let mut results = manager
+        .search(&params.profile_id, &params.query, options)
+        .await?;
+    if let Some(limit) = params.limit {
+        results.truncate(limit);
+    }
 
-    Ok(serde_json::to_value(&tasks)?)
+    let tasks = results
+        .into_iter()
+        .map(|(task, matched)| {
+            let mut value = serde_json::to_value(&task)?;
+            value["match"] = serde_json::to_value(&matched)?;
+            Ok(value)
+        })
+        .collect::<Result<Vec<Value>>>()?;
+    Ok(Value::Array(tasks))
 }
 
 pub async fn move_task(
@@ -160,8 +579,7 @@ pub async fn move_task(
             entry_manager,
             timer_manager,
         )
-        .await
-        .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        .await?;
 
     Ok(json!({
         "status": "moved",
@@ -170,6 +588,68 @@ pub async fn move_task(
     }))
 }
 
+pub async fn bulk_status_update(
+    manager: &Arc<TaskManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: BulkStatusUpdateParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    if params.from_status == params.to_status {
+        return Err(ApiError::InvalidParams(
+            "from_status and to_status must differ".to_string(),
+        ));
+    }
+
+    let filter = params.filter.map(|f| TaskFilter {
+        tags: f.tags,
+        created_after: f.created_after,
+        created_before: f.created_before,
+    });
+
+    let updated = manager
+        .bulk_update_status(
+            &params.profile_id,
+            params.from_status,
+            params.to_status,
+            filter.as_ref(),
+        )
+        .await?;
+
+    let task_ids: Vec<&str> = updated.iter().map(|t| t.id.as_str()).collect();
+
+    Ok(json!({
+        "updated_count": task_ids.len(),
+        "task_ids": task_ids
+    }))
+}
+
+pub async fn bulk_tag(manager: &Arc<TaskManager>, params: Option<Value>) -> Result<Value> {
+    let params = BulkTagParams::parse(params)?;
+    let updated = manager
+        .bulk_tag(&params.profile_id, &params.task_ids, &params.tags)
+        .await?;
+    Ok(bulk_tag_result(&updated))
+}
+
+pub async fn bulk_untag(manager: &Arc<TaskManager>, params: Option<Value>) -> Result<Value> {
+    let params = BulkTagParams::parse(params)?;
+    let updated = manager
+        .bulk_untag(&params.profile_id, &params.task_ids, &params.tags)
+        .await?;
+    Ok(bulk_tag_result(&updated))
+}
+
+/// Counts only the tasks whose tags actually changed.
+fn bulk_tag_result(updated: &[Task]) -> Value {
+    let task_ids: Vec<&str> = updated.iter().map(|t| t.id.as_str()).collect();
+    json!({
+        "updated_count": task_ids.len(),
+        "task_ids": task_ids
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,12 +684,327 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_list_tasks() {
+    async fn test_get_active_follows_running_timer() {
+        let event_manager = Arc::new(EventManager::new());
+        let task_manager = Arc::new(TaskManager::new(event_manager.clone()).unwrap());
+        let timer_manager = Arc::new(TimerManager::new(event_manager));
+        let profile = "test_task_api_active";
+        let params = json!({ "profile_id": profile });
+
+        let none = get_active(&task_manager, &timer_manager, Some(params.clone()))
+            .await
+            .unwrap();
+        assert!(none.is_null());
+
+        let created = create(
+            &task_manager,
+            Some(json!({ "profile_id": profile, "title": "Focus" })),
+        )
+        .await
+        .unwrap();
+        let task_id = created["id"].as_str().unwrap().to_string();
+        let timer_id = timer_manager
+            .start_manual(profile.to_string(), Some(task_id.clone()))
+            .await
+            .unwrap();
+
+        let active = get_active(&task_manager, &timer_manager, Some(params.clone()))
+            .await
+            .unwrap();
+        assert_eq!(active["id"], task_id.as_str());
+
+        timer_manager.pause(&timer_id).await.unwrap();
+        let paused = get_active(&task_manager, &timer_manager, Some(params))
+            .await
+            .unwrap();
+        assert!(paused.is_null());
+
+        timer_manager.cancel(&timer_id).await.unwrap();
+        task_manager.delete(profile, &task_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_set_description_clears_on_empty() {
         let event_manager = Arc::new(EventManager::new());
         let manager = Arc::new(TaskManager::new(event_manager).unwrap());
 
+        let created = create(
+            &manager,
+            Some(json!({
+                "profile_id": TEST_PROFILE,
+                "title": "Describe me",
+                "description": "Old"
+            })),
+        )
+        .await
+        .unwrap();
+        let task_id = created["id"].as_str().unwrap();
+
+        let result = set_description(
+            &manager,
+            Some(json!({
+                "profile_id": TEST_PROFILE,
+                "task_id": task_id,
+                "description": "  "
+            })),
+        )
+        .await
+        .unwrap();
+        assert!(result["description"].is_null());
+        assert_eq!(result["title"], "Describe me");
+
+        let result = set_notes(
+            &manager,
+            Some(json!({
+                "profile_id": TEST_PROFILE,
+                "task_id": task_id,
+                "notes": "Ask about scope"
+            })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["notes"], "Ask about scope");
+    }
+
+    #[tokio::test]
+    async fn test_list_tasks() {
+        let event_manager = Arc::new(EventManager::new());
+        let manager = Arc::new(TaskManager::new(event_manager.clone()).unwrap());
+        let entries = Arc::new(EntryManager::new(event_manager).unwrap());
+
         let params = json!({ "profile_id": TEST_PROFILE });
-        let result = list(&manager, Some(params)).await.unwrap();
+        let result = list(&manager, &entries, Some(params)).await.unwrap();
         assert!(result.is_array());
+
+        let params = json!({ "profile_id": TEST_PROFILE, "sort_by": "last_used" });
+        assert!(list(&manager, &entries, Some(params)).await.is_ok());
+
+        let params = json!({ "profile_id": TEST_PROFILE, "sort_by": "color" });
+        assert!(list(&manager, &entries, Some(params)).await.is_err());
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_add_subtask_and_nested_list() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+        }
+        const PROFILE: &str = "test_subtask_api";
+        let event_manager = Arc::new(EventManager::new());
+        let manager = Arc::new(TaskManager::new(event_manager.clone()).unwrap());
+        let entries = Arc::new(EntryManager::new(event_manager).unwrap());
+
+        let parent = create(
+            &manager,
+            Some(json!({ "profile_id": PROFILE, "title": "Release" })),
+        )
+        .await
+        .unwrap();
+        let subtask = add_subtask(
+            &manager,
+            Some(json!({
+                "profile_id": PROFILE,
+                "parent_task_id": parent["id"],
+                "title": "Write changelog",
+            })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(subtask["parent_task_id"], parent["id"]);
+
+        let nested_params = json!({
+            "profile_id": PROFILE,
+            "parent_task_id": subtask["id"],
+            "title": "Too deep",
+        });
+        assert!(add_subtask(&manager, Some(nested_params)).await.is_err());
+
+        let flat = list(&manager, &entries, Some(json!({ "profile_id": PROFILE })))
+            .await
+            .unwrap();
+        assert_eq!(flat.as_array().unwrap().len(), 2);
+
+        let nested = list(
+            &manager,
+            &entries,
+            Some(json!({ "profile_id": PROFILE, "include_subtasks": true })),
+        )
+        .await
+        .unwrap();
+        let nested = nested.as_array().unwrap();
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0]["subtasks"][0]["title"], "Write changelog");
+
+        let other = create(
+            &manager,
+            Some(json!({ "profile_id": PROFILE, "title": "Blog post" })),
+        )
+        .await
+        .unwrap();
+        let reparent = |task: &Value, parent_task_id: Value| {
+            let mut task = task.clone();
+            task["parent_task_id"] = parent_task_id;
+            update(
+                &manager,
+                Some(json!({ "profile_id": PROFILE, "task": task })),
+            )
+        };
+        for bad in [
+            reparent(&other, other["id"].clone()).await,
+            reparent(&other, json!("missing")).await,
+            reparent(&other, subtask["id"].clone()).await,
+            reparent(&parent, other["id"].clone()).await,
+        ] {
+            assert!(bad.is_err());
+        }
+        let moved = reparent(&other, parent["id"].clone()).await.unwrap();
+        assert_eq!(moved["parent_task_id"], parent["id"]);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_copy_task() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+        }
+        const PROFILE: &str = "test_copy_api";
+        let manager = Arc::new(TaskManager::new(Arc::new(EventManager::new())).unwrap());
+
+        let original = create(
+            &manager,
+            Some(json!({
+                "profile_id": PROFILE,
+                "title": "Weekly review",
+                "description": "Inbox zero",
+                "tags": ["routine"],
+            })),
+        )
+        .await
+        .unwrap();
+        let task_id = original["id"].as_str().unwrap();
+        let mut done: Task = serde_json::from_value(original.clone()).unwrap();
+        done.status = TaskStatus::Done;
+        manager.update(PROFILE, done).await.unwrap();
+
+        let params = json!({ "profile_id": PROFILE, "task_id": task_id });
+        let copy_one = copy(&manager, Some(params)).await.unwrap();
+        assert_ne!(copy_one["id"], original["id"]);
+        assert_eq!(copy_one["title"], "Copy of Weekly review");
+        assert_eq!(copy_one["status"], "todo");
+        assert_eq!(copy_one["description"], "Inbox zero");
+        assert_eq!(copy_one["tags"], json!(["routine"]));
+
+        let params =
+            json!({ "profile_id": PROFILE, "task_id": task_id, "new_title": "Review W42" });
+        let copy_two = copy(&manager, Some(params)).await.unwrap();
+        assert_eq!(copy_two["title"], "Review W42");
+        assert_eq!(manager.get_all(PROFILE).await.unwrap().len(), 3);
+
+        let params = json!({ "profile_id": PROFILE, "task_id": task_id, "new_title": " " });
+        assert!(copy(&manager, Some(params)).await.is_err());
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_reorder_within_column() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+        }
+        const PROFILE: &str = "test_reorder_api";
+        let event_manager = Arc::new(EventManager::new());
+        let manager = Arc::new(TaskManager::new(event_manager.clone()).unwrap());
+        let entries = Arc::new(EntryManager::new(event_manager).unwrap());
+
+        let mut ids = Vec::new();
+        for title in ["a", "b", "c"] {
+            let task = create(
+                &manager,
+                Some(json!({ "profile_id": PROFILE, "title": title })),
+            )
+            .await
+            .unwrap();
+            ids.push(task["id"].as_str().unwrap().to_string());
+        }
+        let titles = || async {
+            let tasks = list(&manager, &entries, Some(json!({ "profile_id": PROFILE })))
+                .await
+                .unwrap();
+            tasks
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|t| t["title"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(titles().await, ["a", "b", "c"]);
+
+        let params = json!({ "profile_id": PROFILE, "task_id": ids[2], "before": ids[0] });
+        let result = reorder(&manager, Some(params)).await.unwrap();
+        assert_eq!(result["task_ids"], json!([ids[2]]));
+        assert_eq!(titles().await, ["c", "a", "b"]);
+
+        // Swapping a and b halves the gap below them each time, until the
+        // column has to be renumbered.
+        let mut renumbered = false;
+        for _ in 0..12 {
+            for (task, anchor) in [(1, 0), (0, 1)] {
+                let params =
+                    json!({ "profile_id": PROFILE, "task_id": ids[task], "before": ids[anchor] });
+                let result = reorder(&manager, Some(params)).await.unwrap();
+                renumbered |= result["task_ids"].as_array().unwrap().len() == 3;
+            }
+        }
+        assert!(renumbered);
+        assert_eq!(titles().await, ["c", "a", "b"]);
+
+        let params = json!({ "profile_id": PROFILE, "task_id": ids[0], "after": ids[1] });
+        reorder(&manager, Some(params)).await.unwrap();
+        assert_eq!(titles().await, ["c", "b", "a"]);
+
+        let mut done: Task = manager.get(PROFILE, &ids[0]).await.unwrap();
+        done.status = TaskStatus::Done;
+        manager.update(PROFILE, done).await.unwrap();
+        let other_column = json!({ "profile_id": PROFILE, "task_id": ids[1], "before": ids[0] });
+        assert!(reorder(&manager, Some(other_column)).await.is_err());
+        let both = json!({
+            "profile_id": PROFILE,
+            "task_id": ids[1],
+            "before": ids[2],
+            "after": ids[2],
+        });
+        assert!(reorder(&manager, Some(both)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bulk_status_update_rejects_same_status() {
+        let event_manager = Arc::new(EventManager::new());
+        let manager = Arc::new(TaskManager::new(event_manager).unwrap());
+
+        let params = json!({
+            "profile_id": TEST_PROFILE,
+            "from_status": "done",
+            "to_status": "done"
+        });
+        assert!(bulk_status_update(&manager, Some(params)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bulk_tag_rejects_blank_tags() {
+        let event_manager = Arc::new(EventManager::new());
+        let manager = Arc::new(TaskManager::new(event_manager).unwrap());
+
+        let params = json!({
+            "profile_id": TEST_PROFILE,
+            "task_ids": ["a"],
+            "tags": ["  ", ""]
+        });
+        let err = bulk_tag(&manager, Some(params)).await.unwrap_err();
+        assert!(err.to_string().contains("No tags"));
     }
 }
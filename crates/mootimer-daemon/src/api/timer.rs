@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_json::{Value, json};
 use std::sync::Arc;
@@ -5,40 +6,95 @@ use std::sync::Arc;
 use super::{ApiError, Result};
 use crate::config::ConfigManager;
 use crate::entry::EntryManager;
+use crate::event_manager::EventManager;
+use crate::profile::ProfileManager;
 use crate::sync::SyncManager;
-use crate::timer::TimerManager;
+use crate::sync::history::entry_commit_message;
+use crate::task::TaskManager;
+use crate::timer::{TimerEvent, TimerManager};
+use mootimer_core::models::{ActiveTimer, Entry};
 
-#[derive(Debug, Deserialize)]
-struct StartManualParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct StartManualParams {
     profile_id: String,
     task_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct StartPomodoroParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct StartPomodoroParams {
     profile_id: String,
     task_id: Option<String>,
     #[serde(default)]
     config: Option<Value>,
 }
 
-#[derive(Debug, Deserialize)]
-struct StartCountdownParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct StartCountdownParams {
     profile_id: String,
     task_id: Option<String>,
     duration_minutes: u64,
 }
 
-#[derive(Debug, Deserialize)]
-struct ProfileParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ProfileParams {
     profile_id: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct TimerParams {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct TimerParams {
     timer_id: String,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SetAutoStartParams {
+    timer_id: String,
+    enabled: bool,
+}
+
+/// A warning to attach to a timer start on `task_id` if it is blocked by
+/// unfinished tasks. With `daemon.strict_task_blocking` the start is
+/// refused instead.
+pub async fn blocked_task_warning(
+    task_manager: &Arc<TaskManager>,
+    config_manager: &Arc<ConfigManager>,
+    params: Option<&Value>,
+) -> Result<Option<String>> {
+    let (Some(profile_id), Some(task_id)) = (
+        params.and_then(|p| p["profile_id"].as_str()),
+        params.and_then(|p| p["task_id"].as_str()),
+    ) else {
+        return Ok(None);
+    };
+    let Ok(task) = task_manager.get(profile_id, task_id).await else {
+        return Ok(None);
+    };
+    if !task.is_blocked() {
+        return Ok(None);
+    }
+
+    let mut titles = Vec::new();
+    for id in &task.blocked_by {
+        match task_manager.get(profile_id, id).await {
+            Ok(blocker) => titles.push(blocker.title),
+            Err(_) => titles.push(id.clone()),
+        }
+    }
+    let message = format!("'{}' is blocked by: {}", task.title, titles.join(", "));
+
+    if config_manager.get().await.daemon.strict_task_blocking {
+        return Err(ApiError::Conflict(message));
+    }
+    Ok(Some(message))
+}
+
+/// Adds `warning` to a timer start response.
+pub fn with_warning(mut response: Value, warning: Option<String>) -> Value {
+    if let Some(warning) = warning {
+        response["warning"] = json!(warning);
+    }
+    response
+}
+
 pub async fn start_manual(manager: &Arc<TimerManager>, params: Option<Value>) -> Result<Value> {
     let params: StartManualParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
@@ -46,8 +102,7 @@ pub async fn start_manual(manager: &Arc<TimerManager>, params: Option<Value>) ->
 
     let timer_id = manager
         .start_manual(params.profile_id, params.task_id)
-        .await
-        .map_err(|e| ApiError::Timer(e.to_string()))?;
+        .await?;
 
     Ok(json!({
         "timer_id": timer_id,
@@ -55,9 +110,12 @@ pub async fn start_manual(manager: &Arc<TimerManager>, params: Option<Value>) ->
     }))
 }
 
+/// Starts a pomodoro with the config's settings, then the profile's
+/// `auto_start_next_session`, then any overrides in the params.
 pub async fn start_pomodoro(
     manager: &Arc<TimerManager>,
     config_manager: &Arc<ConfigManager>,
+    profile_manager: &Arc<ProfileManager>,
     params: Option<Value>,
 ) -> Result<Value> {
     let params: StartPomodoroParams = serde_json::from_value(
@@ -67,6 +125,12 @@ pub async fn start_pomodoro(
     let global_config = config_manager.get().await;
     let mut config = global_config.pomodoro.clone();
 
+    if let Ok(profile) = profile_manager.get(&params.profile_id).await
+        && let Some(enabled) = profile.auto_start_next_session
+    {
+        config.auto_start_next_session = enabled;
+    }
+
     if let Some(overrides) = params.config
         && let Some(obj) = overrides.as_object()
     {
@@ -85,12 +149,14 @@ pub async fn start_pomodoro(
         {
             config.sessions_until_long_break = v as u32;
         }
+        if let Some(v) = obj.get("auto_start_next_session").and_then(|v| v.as_bool()) {
+            config.auto_start_next_session = v;
+        }
     }
 
     let timer_id = manager
         .start_pomodoro(params.profile_id, params.task_id, config)
-        .await
-        .map_err(|e| ApiError::Timer(e.to_string()))?;
+        .await?;
 
     Ok(json!({
         "timer_id": timer_id,
@@ -105,8 +171,7 @@ pub async fn start_countdown(manager: &Arc<TimerManager>, params: Option<Value>)
 
     let timer_id = manager
         .start_countdown(params.profile_id, params.task_id, params.duration_minutes)
-        .await
-        .map_err(|e| ApiError::Timer(e.to_string()))?;
+        .await?;
 
     Ok(json!({
         "timer_id": timer_id,
@@ -120,10 +185,7 @@ pub async fn pause(manager: &Arc<TimerManager>, params: Option<Value>) -> Result
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    manager
-        .pause(&params.timer_id)
-        .await
-        .map_err(|e| ApiError::Timer(e.to_string()))?;
+    manager.pause(&params.timer_id).await?;
 
     Ok(json!({
         "status": "paused"
@@ -135,36 +197,106 @@ pub async fn resume(manager: &Arc<TimerManager>, params: Option<Value>) -> Resul
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    manager
-        .resume(&params.timer_id)
-        .await
-        .map_err(|e| ApiError::Timer(e.to_string()))?;
+    manager.resume(&params.timer_id).await?;
 
     Ok(json!({
         "status": "resumed"
     }))
 }
 
+pub async fn set_auto_start(manager: &Arc<TimerManager>, params: Option<Value>) -> Result<Value> {
+    let params: SetAutoStartParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    manager
+        .set_auto_start(&params.timer_id, params.enabled)
+        .await?;
+
+    Ok(json!({
+        "auto_start_next_session": params.enabled
+    }))
+}
+
+/// Ends the current pomodoro break early, recording it as skipped.
+pub async fn skip_phase(manager: &Arc<TimerManager>, params: Option<Value>) -> Result<Value> {
+    let params: TimerParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let record = manager.skip_phase(&params.timer_id).await?;
+
+    Ok(json!({
+        "status": "skipped",
+        "break": record,
+    }))
+}
+
+/// Cuts `entry` at its task's `max_session_seconds`, if it has one and
+/// ran longer, and announces the overflow with `timer.session_capped`.
+async fn cap_session(
+    task_manager: &Arc<TaskManager>,
+    event_manager: &Arc<EventManager>,
+    profile_id: &str,
+    timer_id: &str,
+    entry: &mut Entry,
+) {
+    let Some(task_id) = entry.task_id.clone() else {
+        return;
+    };
+    let Some(cap) = task_manager
+        .get(profile_id, &task_id)
+        .await
+        .ok()
+        .and_then(|task| task.max_session_seconds)
+    else {
+        return;
+    };
+    if let Some(overflow) = entry.split_at(cap) {
+        tracing::info!(
+            "Capped session on task {} at {}s; {}s over",
+            task_id,
+            cap,
+            overflow.duration_seconds
+        );
+        event_manager.emit_timer(TimerEvent::session_capped(
+            profile_id.to_string(),
+            timer_id.to_string(),
+            task_id,
+            cap,
+            &overflow,
+        ));
+    }
+}
+
 pub async fn stop(
     timer_manager: &Arc<TimerManager>,
     entry_manager: &Arc<EntryManager>,
+    task_manager: &Arc<TaskManager>,
     sync_manager: &Arc<SyncManager>,
     config_manager: &Arc<ConfigManager>,
+    event_manager: &Arc<EventManager>,
     params: Option<Value>,
 ) -> Result<Value> {
     let params: TimerParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    let (profile_id, entry) = timer_manager
-        .stop(&params.timer_id)
-        .await
-        .map_err(|e| ApiError::Timer(e.to_string()))?;
-
+    let (profile_id, mut entry) = timer_manager.stop(&params.timer_id).await?;
+    cap_session(
+        task_manager,
+        event_manager,
+        &profile_id,
+        &params.timer_id,
+        &mut entry,
+    )
+    .await;
+
+    // The timer is gone by now, so the entry must not be turned away. Two
+    // timers on one task may well overlap.
     entry_manager
-        .add(&profile_id, entry.clone())
-        .await
-        .map_err(|e| ApiError::Timer(format!("Failed to save entry: {}", e)))?;
+        .add_with(&profile_id, entry.clone(), true)
+        .await?;
 
     let config = config_manager.get().await;
     if config.sync.auto_commit {
@@ -172,19 +304,7 @@ pub async fn stop(
             let _ = sync_manager.init_repo().await;
         }
 
-        let task_info = entry
-            .task_id
-            .as_ref()
-            .map(|id| format!("task {}", id))
-            .unwrap_or_else(|| "no task".to_string());
-        let duration_mins = entry.duration_seconds / 60;
-        let commit_msg = format!(
-            "Add entry: {} - {}m ({})",
-            task_info,
-            duration_mins,
-            chrono::Local::now().format("%Y-%m-%d %H:%M")
-        );
-
+        let commit_msg = entry_commit_message(&config.daemon.locale, &profile_id, &entry);
         if let Err(e) = sync_manager.auto_commit(&commit_msg).await {
             tracing::warn!("Failed to auto-commit: {}", e);
         }
@@ -205,10 +325,7 @@ pub async fn cancel(manager: &Arc<TimerManager>, params: Option<Value>) -> Resul
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
-    manager
-        .cancel(&params.timer_id)
-        .await
-        .map_err(|e| ApiError::Timer(e.to_string()))?;
+    manager.cancel(&params.timer_id).await?;
 
     Ok(json!({
         "status": "cancelled"
@@ -221,13 +338,10 @@ pub async fn get(manager: &Arc<TimerManager>, params: Option<Value>) -> Result<V
     )?;
 
     tracing::debug!("timer.get called for timer {}", params.timer_id);
-    let timer = manager
-        .get_timer(&params.timer_id)
-        .await
-        .map_err(|e| ApiError::Timer(e.to_string()))?;
+    let timer = manager.get_timer(&params.timer_id).await?;
     tracing::debug!("timer.get returning for timer {}", params.timer_id);
 
-    Ok(serde_json::to_value(&timer)?)
+    timer_json(&timer)
 }
 
 pub async fn get_by_profile(manager: &Arc<TimerManager>, params: Option<Value>) -> Result<Value> {
@@ -244,30 +358,76 @@ pub async fn get_by_profile(manager: &Arc<TimerManager>, params: Option<Value>)
 
     // Return the first timer if exists, null otherwise (backward compatibility)
     if let Some(timer) = timers.first() {
-        Ok(serde_json::to_value(timer)?)
+        timer_json(timer)
     } else {
         Ok(Value::Null)
     }
 }
 
+/// What a status bar polling every second needs, without the rest of
+/// `timer.get_by_profile`. A profile without a timer gets
+/// `{ running: false, elapsed_seconds: 0, mode: "none" }`.
+pub async fn peek(manager: &Arc<TimerManager>, params: Option<Value>) -> Result<Value> {
+    let params: ProfileParams = serde_json::from_value(
+        params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
+    )?;
+
+    let Some(peek) = manager.peek_by_profile(&params.profile_id).await else {
+        return Ok(json!({ "running": false, "elapsed_seconds": 0, "mode": "none" }));
+    };
+    let mut result = json!({
+        "running": peek.running,
+        "elapsed_seconds": peek.elapsed_seconds,
+        "mode": peek.mode,
+    });
+    if let Some(remaining) = peek.remaining_seconds {
+        result["remaining_seconds"] = json!(remaining);
+    }
+    if let Some(title) = peek.task_title {
+        result["task_title"] = json!(title);
+    }
+    Ok(result)
+}
+
 pub async fn list_by_profile(manager: &Arc<TimerManager>, params: Option<Value>) -> Result<Value> {
     let params: ProfileParams = serde_json::from_value(
         params.ok_or_else(|| ApiError::InvalidParams("Missing params".to_string()))?,
     )?;
 
     let timers = manager.get_timers_by_profile(&params.profile_id).await;
-    Ok(serde_json::to_value(&timers)?)
+    timers_json(&timers)
 }
 
 pub async fn list(manager: &Arc<TimerManager>, _params: Option<Value>) -> Result<Value> {
     let timers = manager.get_all_timers().await;
-    Ok(serde_json::to_value(&timers)?)
+    Ok(Value::Object(
+        timers
+            .iter()
+            .map(|(id, timer)| Ok((id.clone(), timer_json(timer)?)))
+            .collect::<Result<_>>()?,
+    ))
+}
+
+/// A timer as returned to clients: its own fields plus a `display` object
+/// computed now, see `ActiveTimer::display`.
+fn timer_json(timer: &ActiveTimer) -> Result<Value> {
+    let mut value = serde_json::to_value(timer)?;
+    value["display"] = serde_json::to_value(timer.display())?;
+    Ok(value)
+}
+
+fn timers_json(timers: &[ActiveTimer]) -> Result<Value> {
+    Ok(Value::Array(
+        timers.iter().map(timer_json).collect::<Result<_>>()?,
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::event_manager::EventManager;
+    use crate::events::DaemonEvent;
+    use crate::timer::TimerEventType;
     use std::sync::Arc;
 
     #[tokio::test]
@@ -289,7 +449,8 @@ mod tests {
     async fn test_start_pomodoro_with_partial_config() {
         let event_manager = Arc::new(EventManager::new());
         let timer_manager = Arc::new(TimerManager::new(event_manager.clone()));
-        let config_manager = Arc::new(ConfigManager::new().unwrap());
+        let config_manager = Arc::new(ConfigManager::new(event_manager.clone()).unwrap());
+        let profile_manager = Arc::new(ProfileManager::new(event_manager.clone()).unwrap());
 
         let params = json!({
             "profile_id": "test",
@@ -298,7 +459,13 @@ mod tests {
             }
         });
 
-        let result = start_pomodoro(&timer_manager, &config_manager, Some(params)).await;
+        let result = start_pomodoro(
+            &timer_manager,
+            &config_manager,
+            &profile_manager,
+            Some(params),
+        )
+        .await;
 
         assert!(
             result.is_ok(),
@@ -307,6 +474,121 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_session_capped_at_task_limit() {
+        use chrono::{Duration, Utc};
+        use mootimer_core::models::{Task, TimerMode};
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+            std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config"));
+        }
+
+        let event_manager = Arc::new(EventManager::new());
+        let task_manager = Arc::new(TaskManager::new(event_manager.clone()).unwrap());
+        let mut task = Task::new("Standup".to_string()).unwrap();
+        task.max_session_seconds = Some(1800);
+        let task = task_manager.create("test", task).await.unwrap();
+
+        let start = Utc::now() - Duration::hours(2);
+        let mut entry = Entry::create_completed(
+            Some(task.id.clone()),
+            Some(task.title.clone()),
+            start,
+            start + Duration::hours(2),
+            TimerMode::Manual,
+        )
+        .unwrap();
+
+        let mut events = event_manager.subscribe();
+        cap_session(&task_manager, &event_manager, "test", "t1", &mut entry).await;
+        assert_eq!(entry.duration_seconds, 1800);
+
+        let DaemonEvent::Timer(event) = events.try_recv().unwrap() else {
+            panic!("expected a timer event");
+        };
+        match event.event_type {
+            TimerEventType::SessionCapped {
+                task_id,
+                cap_seconds,
+                overflow_start,
+                overflow_seconds,
+                ..
+            } => {
+                assert_eq!(task_id, task.id);
+                assert_eq!(cap_seconds, 1800);
+                assert_eq!(overflow_start, start + Duration::minutes(30));
+                assert_eq!(overflow_seconds, 5400);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        // A session within the cap is left alone.
+        let mut short = Entry::create_completed(
+            Some(task.id.clone()),
+            None,
+            start,
+            start + Duration::minutes(10),
+            TimerMode::Manual,
+        )
+        .unwrap();
+        cap_session(&task_manager, &event_manager, "test", "t2", &mut short).await;
+        assert_eq!(short.duration_seconds, 600);
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_stop_after_clock_set_back() {
+        use crate::timer::Clock;
+        use crate::timer::clock::testing::ManualClock;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+            std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config"));
+        }
+
+        let event_manager = Arc::new(EventManager::new());
+        let clock = Arc::new(ManualClock::default());
+        let timer_manager =
+            Arc::new(TimerManager::new(event_manager.clone()).with_clock(clock.clone()));
+        let entry_manager = Arc::new(EntryManager::new(event_manager.clone()).unwrap());
+        let task_manager = Arc::new(TaskManager::new(event_manager.clone()).unwrap());
+        let sync_manager = Arc::new(SyncManager::new().unwrap());
+        let config_manager = Arc::new(ConfigManager::new(event_manager.clone()).unwrap());
+
+        let started = start_manual(&timer_manager, Some(json!({ "profile_id": "test" })))
+            .await
+            .unwrap();
+        clock.advance(600);
+        clock.jump(-2 * 3600);
+
+        let stopped = stop(
+            &timer_manager,
+            &entry_manager,
+            &task_manager,
+            &sync_manager,
+            &config_manager,
+            &event_manager,
+            Some(json!({ "timer_id": started["timer_id"] })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(stopped["duration_seconds"], 600);
+
+        let entries = entry_manager.get_all("test").await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].clock_anomaly);
+        assert!(entries[0].end_time.unwrap() <= clock.now());
+    }
+
     #[tokio::test]
     async fn test_pause_resume() {
         let event_manager = Arc::new(EventManager::new());
@@ -339,6 +621,34 @@ mod tests {
         let result = list(&manager, None).await.unwrap();
         let timers = result.as_object().unwrap();
         assert_eq!(timers.len(), 2);
+        for timer in timers.values() {
+            assert_eq!(timer["display"]["phase_display"], "Manual");
+            assert_eq!(timer["display"]["remaining_seconds"], Value::Null);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_includes_display() {
+        let event_manager = Arc::new(EventManager::new());
+        let manager = Arc::new(TimerManager::new(event_manager));
+
+        let timer_id = manager
+            .start_countdown("test".to_string(), None, 10)
+            .await
+            .unwrap();
+
+        let timer = get(&manager, Some(json!({"timer_id": timer_id})))
+            .await
+            .unwrap();
+        assert_eq!(timer["target_duration"], 600);
+        assert_eq!(timer["display"]["phase_display"], "Countdown");
+        assert_eq!(timer["display"]["total_seconds"], 600);
+        assert!(timer["display"]["remaining_seconds"].as_u64().unwrap() <= 600);
+
+        let timers = list_by_profile(&manager, Some(json!({"profile_id": "test"})))
+            .await
+            .unwrap();
+        assert_eq!(timers[0]["display"]["total_seconds"], 600);
     }
 
     #[tokio::test]
@@ -0,0 +1,74 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::{ApiError, Result};
+use crate::config::ConfigManager;
+use crate::usage::{USAGE_RETENTION_MONTHS, UsageManager};
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub(crate) struct HelloParams {
+    /// Name of the calling program, e.g. `mootimer-tui`. Calls on the
+    /// connection are counted under it when `daemon.usage_metrics` is on.
+    pub(crate) client: Option<String>,
+    /// Encodings the client reads compressed responses in; only `zstd` is
+    /// known. The result's `compression` confirms it when the daemon was
+    /// built with compression.
+    #[serde(default)]
+    pub(crate) accept_encoding: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub(crate) struct UsageSummaryParams {
+    /// `YYYY-MM`; the current month when absent.
+    month: Option<String>,
+}
+
+/// Call counts for one month, totalled by method, client and day, plus the
+/// months on disk.
+pub async fn summary(
+    usage_manager: &Arc<UsageManager>,
+    config_manager: &Arc<ConfigManager>,
+    params: Option<Value>,
+) -> Result<Value> {
+    let params: UsageSummaryParams = match params {
+        Some(params) => serde_json::from_value(params)?,
+        None => UsageSummaryParams::default(),
+    };
+    let month = match params.month {
+        Some(month) => {
+            chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").map_err(
+                |_| ApiError::InvalidParams(format!("month must be YYYY-MM, got '{}'", month)),
+            )?;
+            month
+        }
+        None => chrono::Local::now().format("%Y-%m").to_string(),
+    };
+
+    let usage = usage_manager.month(&month).await?;
+    let months = usage_manager.months()?;
+
+    let mut total = 0;
+    let mut by_method: BTreeMap<&str, u64> = BTreeMap::new();
+    let mut by_client: BTreeMap<&str, u64> = BTreeMap::new();
+    let mut by_day: BTreeMap<&str, u64> = BTreeMap::new();
+    for (day, method, client, calls) in usage.counts() {
+        total += calls;
+        *by_method.entry(method).or_default() += calls;
+        *by_client.entry(client).or_default() += calls;
+        *by_day.entry(day).or_default() += calls;
+    }
+
+    Ok(json!({
+        "enabled": config_manager.get().await.daemon.usage_metrics,
+        "month": month,
+        "total": total,
+        "by_method": by_method,
+        "by_client": by_client,
+        "by_day": by_day,
+        "months": months,
+        "retention_months": USAGE_RETENTION_MONTHS,
+    }))
+}
@@ -0,0 +1,466 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::entry::{EntryFilter, EntryManager, StatsWindow};
+use crate::event_manager::EventManager;
+use crate::events::{BudgetEvent, BudgetEventType, DaemonEvent, EntryEventType};
+use crate::task::TaskManager;
+use crate::timer::{TimerEventType, TimerManager};
+use mootimer_core::{
+    models::{Budget, BudgetAction, BudgetPeriod, BudgetScope, Task},
+    storage::{BudgetStorage, get_data_dir},
+};
+
+/// Ticks arrive every second per timer; budgets are only re-checked on
+/// every this-many-th one.
+const TICK_CHECK_INTERVAL_SECONDS: u64 = 60;
+
+/// Share of a budget at which the first alert goes out, in percent.
+const WARNING_PERCENT: u64 = 80;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BudgetManagerError {
+    #[error("Budget not found: {0}")]
+    NotFound(String),
+
+    #[error("Storage error: {0}")]
+    Storage(#[from] mootimer_core::Error),
+
+    #[error("Invalid budget: {0}")]
+    Invalid(String),
+}
+
+pub type Result<T> = std::result::Result<T, BudgetManagerError>;
+
+/// A budget with what has been used of it in the current period, running
+/// timers included.
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetStatus {
+    pub budget: Budget,
+    pub period_start: DateTime<Utc>,
+    pub used_seconds: u64,
+    pub remaining_seconds: u64,
+    pub percent: u64,
+    pub description: String,
+}
+
+impl BudgetStatus {
+    pub fn is_exceeded(&self) -> bool {
+        self.used_seconds >= self.budget.limit_seconds
+    }
+
+    fn alert_level(&self) -> Option<BudgetEventType> {
+        if self.is_exceeded() {
+            Some(BudgetEventType::Exceeded)
+        } else if self.percent >= WARNING_PERCENT {
+            Some(BudgetEventType::Warning)
+        } else {
+            None
+        }
+    }
+}
+
+/// Alert levels already sent for a budget: 1 after the warning, 2 after
+/// the budget was exceeded. Reset when a new period starts.
+#[derive(Debug, Clone, Copy)]
+struct Alerted {
+    period_start: DateTime<Utc>,
+    level: u8,
+}
+
+pub struct BudgetManager {
+    storage: BudgetStorage,
+    cache: RwLock<HashMap<String, Vec<Budget>>>,
+    alerted: RwLock<HashMap<String, Alerted>>,
+    event_manager: Arc<EventManager>,
+    entry_manager: Arc<EntryManager>,
+    task_manager: Arc<TaskManager>,
+    timer_manager: Arc<TimerManager>,
+}
+
+impl BudgetManager {
+    pub fn new(
+        event_manager: Arc<EventManager>,
+        entry_manager: Arc<EntryManager>,
+        task_manager: Arc<TaskManager>,
+        timer_manager: Arc<TimerManager>,
+    ) -> Self {
+        Self {
+            storage: BudgetStorage::new(get_data_dir()),
+            cache: RwLock::new(HashMap::new()),
+            alerted: RwLock::new(HashMap::new()),
+            event_manager,
+            entry_manager,
+            task_manager,
+            timer_manager,
+        }
+    }
+
+    /// Re-checks a profile's budgets when entries are added or changed, and
+    /// about once a minute while one of its timers runs.
+    pub fn spawn_checker(self: &Arc<Self>) {
+        let manager = self.clone();
+        let mut events = self.event_manager.subscribe();
+        tokio::spawn(async move {
+            loop {
+                let profile_id = match events.recv().await {
+                    Ok(DaemonEvent::Entry(event))
+                        if matches!(
+                            event.event_type,
+                            EntryEventType::Added | EntryEventType::Updated
+                        ) =>
+                    {
+                        event.profile_id
+                    }
+                    Ok(DaemonEvent::Timer(event))
+                        if matches!(
+                            event.event_type,
+                            TimerEventType::Tick { elapsed_seconds, .. }
+                                if elapsed_seconds > 0
+                                    && elapsed_seconds % TICK_CHECK_INTERVAL_SECONDS == 0
+                        ) =>
+                    {
+                        event.profile_id
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Budget checker missed {} events", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                if let Err(e) = manager.check(&profile_id).await {
+                    tracing::debug!("Not checking budgets for {}: {}", profile_id, e);
+                }
+            }
+        });
+    }
+
+    pub async fn list(&self, profile_id: &str) -> Result<Vec<Budget>> {
+        if let Some(budgets) = self.cache.read().await.get(profile_id) {
+            return Ok(budgets.clone());
+        }
+        let budgets = self.storage.load(profile_id)?;
+        self.cache
+            .write()
+            .await
+            .insert(profile_id.to_string(), budgets.clone());
+        Ok(budgets)
+    }
+
+    pub async fn create(&self, profile_id: &str, budget: Budget) -> Result<Budget> {
+        budget
+            .validate()
+            .map_err(|e| BudgetManagerError::Invalid(e.to_string()))?;
+        if let BudgetScope::Task { task_id } = &budget.scope {
+            self.task_manager
+                .get(profile_id, task_id)
+                .await
+                .map_err(|e| BudgetManagerError::Invalid(e.to_string()))?;
+        }
+
+        let mut budgets = self.list(profile_id).await?;
+        budgets.push(budget.clone());
+        self.storage.save(profile_id, &budgets)?;
+        self.cache
+            .write()
+            .await
+            .insert(profile_id.to_string(), budgets);
+        Ok(budget)
+    }
+
+    pub async fn delete(&self, profile_id: &str, budget_id: &str) -> Result<()> {
+        let mut budgets = self.list(profile_id).await?;
+        let before = budgets.len();
+        budgets.retain(|b| b.id != budget_id);
+        if budgets.len() == before {
+            return Err(BudgetManagerError::NotFound(budget_id.to_string()));
+        }
+
+        self.storage.save(profile_id, &budgets)?;
+        self.cache
+            .write()
+            .await
+            .insert(profile_id.to_string(), budgets);
+        self.alerted.write().await.remove(budget_id);
+        Ok(())
+    }
+
+    /// Every budget of the profile with its use in the current period.
+    pub async fn status(&self, profile_id: &str) -> Result<Vec<BudgetStatus>> {
+        let budgets = self.list(profile_id).await?;
+        if budgets.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let now = Utc::now();
+        let tasks = self
+            .task_manager
+            .get_all(profile_id)
+            .await
+            .unwrap_or_default();
+        let timers = self.timer_manager.get_timers_by_profile(profile_id).await;
+        // Entries for task and tag budgets are read once, from the start of
+        // the longest period any of them covers.
+        let earliest = budgets
+            .iter()
+            .filter(|b| b.scope != BudgetScope::Profile)
+            .map(|b| b.period.start(now))
+            .min();
+        let entries = match earliest {
+            Some(since) => self
+                .entry_manager
+                .filter(
+                    profile_id,
+                    EntryFilter {
+                        start_date: Some(since),
+                        end_date: None,
+                        task_id: None,
+                        tags: None,
+                        source: None,
+                        device: None,
+                        mode: None,
+                    },
+                )
+                .await
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        let mut statuses = Vec::with_capacity(budgets.len());
+        for budget in budgets {
+            let period_start = budget.period.start(now);
+            let logged = if budget.scope == BudgetScope::Profile {
+                let window = match budget.period {
+                    BudgetPeriod::Day => StatsWindow::Today,
+                    BudgetPeriod::Week => StatsWindow::Week,
+                    BudgetPeriod::Month => StatsWindow::Month,
+                };
+                self.entry_manager
+                    .bulk_stats(profile_id, &[window])
+                    .await
+                    .ok()
+                    .and_then(|stats| stats.first().map(|s| s.total_duration_seconds))
+                    .unwrap_or(0)
+            } else {
+                entries
+                    .iter()
+                    .filter(|e| e.start_time >= period_start)
+                    .filter(|e| {
+                        let task = e.task_id.as_ref().and_then(|id| tasks.get(id));
+                        budget.covers_entry(&e.tags, task)
+                    })
+                    .map(|e| e.duration_seconds)
+                    .sum()
+            };
+            let running: u64 = timers
+                .iter()
+                .filter(|t| {
+                    let task = t.task_id.as_ref().and_then(|id| tasks.get(id));
+                    budget.covers_task(task)
+                })
+                .map(|t| t.current_elapsed())
+                .sum();
+
+            let task_title = match &budget.scope {
+                BudgetScope::Task { task_id } => tasks.get(task_id).map(|t| t.title.as_str()),
+                _ => None,
+            };
+            let used_seconds = logged + running;
+            statuses.push(BudgetStatus {
+                description: budget.describe(task_title),
+                period_start,
+                used_seconds,
+                remaining_seconds: budget.limit_seconds.saturating_sub(used_seconds),
+                percent: used_seconds * 100 / budget.limit_seconds,
+                budget,
+            });
+        }
+        Ok(statuses)
+    }
+
+    /// Emits a `budget.event` for each budget that crossed 80% or 100%
+    /// since the last check in the same period.
+    pub async fn check(&self, profile_id: &str) -> Result<()> {
+        let statuses = self.status(profile_id).await?;
+        let mut alerted = self.alerted.write().await;
+        for status in statuses {
+            let Some(event_type) = status.alert_level() else {
+                continue;
+            };
+            let level = match event_type {
+                BudgetEventType::Warning => 1,
+                BudgetEventType::Exceeded => 2,
+            };
+            let previous = alerted
+                .get(&status.budget.id)
+                .filter(|a| a.period_start == status.period_start)
+                .map_or(0, |a| a.level);
+            if level <= previous {
+                continue;
+            }
+
+            alerted.insert(
+                status.budget.id.clone(),
+                Alerted {
+                    period_start: status.period_start,
+                    level,
+                },
+            );
+            self.event_manager.emit_budget(BudgetEvent::new(
+                event_type,
+                profile_id.to_string(),
+                status.budget,
+                status.used_seconds,
+                status.description,
+            ));
+        }
+        Ok(())
+    }
+
+    /// The used-up `block_new_timers` budget, if any, that forbids starting
+    /// a timer on `task` (or on no task).
+    pub async fn blocking(
+        &self,
+        profile_id: &str,
+        task: Option<&Task>,
+    ) -> Result<Option<BudgetStatus>> {
+        let blocks = self
+            .list(profile_id)
+            .await?
+            .iter()
+            .any(|b| b.action == BudgetAction::BlockNewTimers && b.covers_task(task));
+        if !blocks {
+            return Ok(None);
+        }
+
+        Ok(self.status(profile_id).await?.into_iter().find(|s| {
+            s.budget.action == BudgetAction::BlockNewTimers
+                && s.is_exceeded()
+                && s.budget.covers_task(task)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::BudgetEventType;
+    use mootimer_core::models::{Entry, TimerMode};
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    const TEST_PROFILE: &str = "test_profile";
+
+    struct Fixture {
+        _temp_dir: TempDir,
+        events: Arc<EventManager>,
+        entries: Arc<EntryManager>,
+        tasks: Arc<TaskManager>,
+        budgets: BudgetManager,
+    }
+
+    fn setup() -> Fixture {
+        let temp_dir = TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+            std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config"));
+        }
+        let events = Arc::new(EventManager::new());
+        let entries = Arc::new(EntryManager::new(events.clone()).unwrap());
+        let tasks = Arc::new(TaskManager::new(events.clone()).unwrap());
+        let timers = Arc::new(TimerManager::new(events.clone()));
+        let budgets = BudgetManager::new(events.clone(), entries.clone(), tasks.clone(), timers);
+        Fixture {
+            _temp_dir: temp_dir,
+            events,
+            entries,
+            tasks,
+            budgets,
+        }
+    }
+
+    /// Logs `minutes` on `task`, starting just after midnight so the entry
+    /// falls in today however late the test runs.
+    async fn log(fixture: &Fixture, task: &Task, minutes: i64) {
+        let start = BudgetPeriod::Day.start(Utc::now()) + chrono::Duration::seconds(1);
+        let entry = Entry::create_completed(
+            Some(task.id.clone()),
+            None,
+            start,
+            start + chrono::Duration::minutes(minutes),
+            TimerMode::Manual,
+        )
+        .unwrap();
+        fixture.entries.add(TEST_PROFILE, entry).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_budget_alerts_and_blocking() {
+        let fixture = setup();
+        let mut meeting = Task::new("Standup".to_string()).unwrap();
+        meeting.add_tag("meetings".to_string());
+        let meeting = fixture.tasks.create(TEST_PROFILE, meeting).await.unwrap();
+        let coding = fixture
+            .tasks
+            .create(TEST_PROFILE, Task::new("Code".to_string()).unwrap())
+            .await
+            .unwrap();
+
+        let budget = Budget::new(
+            BudgetScope::Tag {
+                tag: "meetings".to_string(),
+            },
+            BudgetPeriod::Day,
+            3600,
+            BudgetAction::BlockNewTimers,
+        )
+        .unwrap();
+        fixture.budgets.create(TEST_PROFILE, budget).await.unwrap();
+        let mut events = fixture.events.subscribe();
+
+        log(&fixture, &coding, 120).await;
+        log(&fixture, &meeting, 50).await;
+        fixture.budgets.check(TEST_PROFILE).await.unwrap();
+        let status = &fixture.budgets.status(TEST_PROFILE).await.unwrap()[0];
+        assert_eq!(status.used_seconds, 3000);
+        assert!(!status.is_exceeded());
+
+        log(&fixture, &meeting, 20).await;
+        fixture.budgets.check(TEST_PROFILE).await.unwrap();
+        // Checking again in the same period must not repeat the alert.
+        fixture.budgets.check(TEST_PROFILE).await.unwrap();
+
+        let mut alerts = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let DaemonEvent::Budget(event) = event {
+                alerts.push(event.event_type);
+            }
+        }
+        assert!(matches!(
+            alerts.as_slice(),
+            [BudgetEventType::Warning, BudgetEventType::Exceeded]
+        ));
+
+        let blocking = fixture
+            .budgets
+            .blocking(TEST_PROFILE, Some(&meeting))
+            .await
+            .unwrap();
+        assert_eq!(blocking.unwrap().used_seconds, 4200);
+        assert!(
+            fixture
+                .budgets
+                .blocking(TEST_PROFILE, Some(&coding))
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
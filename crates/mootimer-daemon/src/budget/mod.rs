@@ -0,0 +1,3 @@
+pub mod manager;
+
+pub use manager::{BudgetManager, BudgetManagerError, BudgetStatus};
@@ -1,12 +1,16 @@
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::{Arc, RwLock as StdRwLock};
+use std::time::{Duration, SystemTime};
+use tokio::sync::{Mutex, RwLock};
 
 use mootimer_core::{
     Result as CoreResult,
-    models::Config,
-    storage::{ConfigStorage, init_config_dir},
+    models::{Config, Device},
+    storage::{ConfigStorage, DeviceStorage, init_config_dir},
 };
 
+use crate::event_manager::EventManager;
+use crate::events::ConfigEvent;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigManagerError {
     #[error("Storage error: {0}")]
@@ -21,18 +25,32 @@ pub type Result<T> = std::result::Result<T, ConfigManagerError>;
 pub struct ConfigManager {
     storage: ConfigStorage,
     config: Arc<RwLock<Config>>,
+    /// mtime of the config file as of our last load or save. Anything newer
+    /// on disk was written by someone else.
+    last_seen: Mutex<Option<SystemTime>>,
+    device_storage: DeviceStorage,
+    /// Kept apart from `config` so it is never reset or reloaded with it.
+    device: StdRwLock<Device>,
+    event_manager: Arc<EventManager>,
 }
 
 impl ConfigManager {
-    pub fn new() -> CoreResult<Self> {
+    pub fn new(event_manager: Arc<EventManager>) -> CoreResult<Self> {
         let config_dir = init_config_dir()?;
-        let storage = ConfigStorage::new(config_dir);
+        let storage = ConfigStorage::new(config_dir.clone());
+        let device_storage = DeviceStorage::new(config_dir);
 
         let config = storage.load()?;
+        let last_seen = storage.modified()?;
+        let device = device_storage.load_or_create()?;
 
         Ok(Self {
             storage,
             config: Arc::new(RwLock::new(config)),
+            last_seen: Mutex::new(last_seen),
+            device_storage,
+            device: StdRwLock::new(device),
+            event_manager,
         })
     }
 
@@ -40,43 +58,165 @@ impl ConfigManager {
         self.config.read().await.clone()
     }
 
+    /// Polls the config file and reloads it when it changes on disk.
+    pub fn spawn_watcher(self: &Arc<Self>, poll_interval: Duration) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(poll_interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = manager.reload_if_changed().await {
+                    tracing::warn!("Failed to reload config: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Reloads the config if the file was modified outside the daemon.
+    /// Returns whether the in-memory config changed. Edits that fail to parse
+    /// or validate are logged and ignored, keeping the last good config.
+    pub async fn reload_if_changed(&self) -> Result<bool> {
+        let mut last_seen = self.last_seen.lock().await;
+        self.reload_locked(&mut last_seen, false).await
+    }
+
+    /// Re-reads the config file even if its mtime looks unchanged (SIGHUP).
+    pub async fn reload(&self) -> Result<bool> {
+        let mut last_seen = self.last_seen.lock().await;
+        self.reload_locked(&mut last_seen, true).await
+    }
+
+    async fn reload_locked(&self, last_seen: &mut Option<SystemTime>, force: bool) -> Result<bool> {
+        let modified = self.storage.modified()?;
+        if !force && modified == *last_seen {
+            return Ok(false);
+        }
+        *last_seen = modified;
+
+        let config = match self.storage.load() {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable config edit: {}", e);
+                return Ok(false);
+            }
+        };
+        if let Err(e) = config.validate() {
+            tracing::warn!("Ignoring invalid config edit: {}", e);
+            return Ok(false);
+        }
+
+        {
+            let mut current = self.config.write().await;
+            if *current == config {
+                return Ok(false);
+            }
+            *current = config.clone();
+        }
+
+        tracing::info!("Config reloaded from disk");
+        self.event_manager
+            .emit_config(ConfigEvent::reloaded(config));
+        Ok(true)
+    }
+
+    /// Replaces the whole config. If the file changed on disk since it was
+    /// last loaded, this write wins and an `overwritten` event is emitted.
     pub async fn update(&self, config: Config) -> Result<Config> {
+        let mut last_seen = self.last_seen.lock().await;
+        self.save_locked(&mut last_seen, config).await
+    }
+
+    async fn save_locked(
+        &self,
+        last_seen: &mut Option<SystemTime>,
+        config: Config,
+    ) -> Result<Config> {
         config
             .validate()
             .map_err(|e| ConfigManagerError::Invalid(e.to_string()))?;
 
+        let overwrote_external = self.storage.modified()? != *last_seen;
+
         self.storage.save(&config)?;
+        *last_seen = self.storage.modified()?;
 
         {
             let mut current = self.config.write().await;
             *current = config.clone();
         }
 
+        if overwrote_external {
+            let message =
+                "Config file was edited externally; those edits were overwritten".to_string();
+            tracing::warn!("{}", message);
+            self.event_manager
+                .emit_config(ConfigEvent::overwritten(config.clone(), message));
+        } else {
+            self.event_manager
+                .emit_config(ConfigEvent::updated(config.clone()));
+        }
+
         Ok(config)
     }
 
-    pub async fn set_default_profile(&self, profile_id: Option<String>) -> Result<Config> {
+    /// Applies a partial update on top of whatever is on disk right now, so
+    /// external edits are kept rather than clobbered by a stale copy. The
+    /// whole read-modify-write runs under one lock.
+    async fn modify(&self, apply: impl FnOnce(&mut Config)) -> Result<Config> {
+        let mut last_seen = self.last_seen.lock().await;
+        if let Err(e) = self.reload_locked(&mut last_seen, false).await {
+            tracing::warn!("Failed to check config file for changes: {}", e);
+        }
+
         let mut config = self.get().await;
-        config.default_profile = profile_id;
-        self.update(config).await
+        apply(&mut config);
+        self.save_locked(&mut last_seen, config).await
+    }
+
+    pub async fn set_default_profile(&self, profile_id: Option<String>) -> Result<Config> {
+        self.modify(|config| config.default_profile = profile_id)
+            .await
+    }
+
+    pub async fn set_current_user(&self, name: Option<String>) -> Result<Config> {
+        self.modify(|config| config.current_user = name).await
     }
 
     pub async fn update_daemon_config(
         &self,
         socket_path: Option<String>,
         log_level: Option<String>,
+        max_timers_per_profile: Option<usize>,
+        strict_task_blocking: Option<bool>,
+        locale: Option<String>,
+        usage_metrics: Option<bool>,
     ) -> Result<Config> {
-        let mut config = self.get().await;
-
-        if let Some(path) = socket_path {
-            config.daemon.socket_path = path;
-        }
-
-        if let Some(level) = log_level {
-            config.daemon.log_level = level;
-        }
-
-        self.update(config).await
+        self.modify(|config| {
+            if let Some(path) = socket_path {
+                config.daemon.socket_path = path;
+            }
+
+            if let Some(level) = log_level {
+                config.daemon.log_level = level;
+            }
+
+            if let Some(max) = max_timers_per_profile {
+                config.daemon.max_timers_per_profile = max;
+            }
+
+            if let Some(strict) = strict_task_blocking {
+                config.daemon.strict_task_blocking = strict;
+            }
+
+            if let Some(locale) = locale {
+                config.daemon.locale = locale;
+            }
+
+            if let Some(enabled) = usage_metrics {
+                config.daemon.usage_metrics = enabled;
+            }
+        })
+        .await
     }
 
     pub async fn update_pomodoro_config(
@@ -86,53 +226,116 @@ impl ConfigManager {
         long_break: Option<u64>,
         sessions_until_long_break: Option<u32>,
         countdown_default: Option<u64>,
+        auto_start_next_session: Option<bool>,
     ) -> Result<Config> {
-        let mut config = self.get().await;
-
-        if let Some(duration) = work_duration {
-            config.pomodoro.work_duration = duration;
-        }
-
-        if let Some(duration) = short_break {
-            config.pomodoro.short_break = duration;
-        }
-
-        if let Some(duration) = long_break {
-            config.pomodoro.long_break = duration;
-        }
-
-        if let Some(sessions) = sessions_until_long_break {
-            config.pomodoro.sessions_until_long_break = sessions;
-        }
-
-        if let Some(duration) = countdown_default {
-            config.pomodoro.countdown_default = duration;
-        }
-
-        self.update(config).await
+        self.modify(|config| {
+            if let Some(duration) = work_duration {
+                config.pomodoro.work_duration = duration;
+            }
+
+            if let Some(duration) = short_break {
+                config.pomodoro.short_break = duration;
+            }
+
+            if let Some(duration) = long_break {
+                config.pomodoro.long_break = duration;
+            }
+
+            if let Some(sessions) = sessions_until_long_break {
+                config.pomodoro.sessions_until_long_break = sessions;
+            }
+
+            if let Some(duration) = countdown_default {
+                config.pomodoro.countdown_default = duration;
+            }
+
+            if let Some(enabled) = auto_start_next_session {
+                config.pomodoro.auto_start_next_session = enabled;
+            }
+        })
+        .await
     }
 
     pub async fn update_sync_config(
         &self,
         auto_commit: Option<bool>,
         auto_push: Option<bool>,
-        remote_url: Option<String>,
+        remote_url: Option<Option<String>>,
     ) -> Result<Config> {
-        let mut config = self.get().await;
+        self.modify(|config| {
+            if let Some(enabled) = auto_commit {
+                config.sync.auto_commit = enabled;
+            }
+
+            if let Some(enabled) = auto_push {
+                config.sync.auto_push = enabled;
+            }
+
+            if let Some(url) = remote_url {
+                config.sync.remote_url = url;
+            }
+        })
+        .await
+    }
 
-        if let Some(enabled) = auto_commit {
-            config.sync.auto_commit = enabled;
-        }
+    pub async fn update_tui_config(
+        &self,
+        status_bar_format: Option<String>,
+        timezone: Option<String>,
+        countdown_presets: Option<Vec<u64>>,
+        pomodoro_animation: Option<String>,
+        countdown_animation: Option<String>,
+        locale: Option<String>,
+    ) -> Result<Config> {
+        self.modify(|config| {
+            if let Some(format) = status_bar_format {
+                config.tui.status_bar_format = format;
+            }
+
+            if let Some(timezone) = timezone {
+                config.tui.timezone = Some(timezone);
+            }
+
+            if let Some(presets) = countdown_presets {
+                config.tui.countdown_presets = presets;
+            }
+
+            if let Some(animation) = pomodoro_animation {
+                config.tui.pomodoro_animation = animation;
+            }
+
+            if let Some(animation) = countdown_animation {
+                config.tui.countdown_animation = animation;
+            }
+
+            if let Some(locale) = locale {
+                config.tui.locale = Some(locale);
+            }
+        })
+        .await
+    }
 
-        if let Some(enabled) = auto_push {
-            config.sync.auto_push = enabled;
-        }
+    /// This machine's identity.
+    pub fn device(&self) -> Device {
+        self.device.read().unwrap().clone()
+    }
 
-        if let Some(url) = remote_url {
-            config.sync.remote_url = Some(url);
+    pub fn set_device_name(&self, name: &str) -> Result<Device> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ConfigManagerError::Invalid(
+                "Device name cannot be empty".to_string(),
+            ));
         }
 
-        self.update(config).await
+        let mut device = self.device.write().unwrap();
+        let renamed = Device {
+            name: name.to_string(),
+            ..device.clone()
+        };
+        self.device_storage.save(&renamed)?;
+        *device = renamed.clone();
+        Ok(renamed)
     }
 
     pub async fn reset_to_default(&self) -> Result<Config> {
@@ -143,13 +346,14 @@ impl ConfigManager {
 
 impl Default for ConfigManager {
     fn default() -> Self {
-        Self::new().expect("Failed to create ConfigManager")
+        Self::new(Arc::new(EventManager::new())).expect("Failed to create ConfigManager")
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::events::{ConfigEventType, DaemonEvent};
     use serial_test::serial;
     use tempfile::TempDir;
 
@@ -158,7 +362,7 @@ mod tests {
             std::env::set_var("HOME", _temp_dir.path());
             std::env::set_var("XDG_CONFIG_HOME", _temp_dir.path().join("config"));
         }
-        ConfigManager::new().unwrap()
+        ConfigManager::new(Arc::new(EventManager::new())).unwrap()
     }
 
     #[tokio::test]
@@ -190,7 +394,7 @@ mod tests {
         let manager = create_manager(&temp_dir);
 
         let updated = manager
-            .update_pomodoro_config(Some(1800), None, None, None, None)
+            .update_pomodoro_config(Some(1800), None, None, None, None, None)
             .await
             .unwrap();
 
@@ -207,7 +411,7 @@ mod tests {
             .update_sync_config(
                 Some(true),
                 Some(false),
-                Some("git@github.com:user/repo.git".to_string()),
+                Some(Some("git@github.com:user/repo.git".to_string())),
             )
             .await
             .unwrap();
@@ -217,5 +421,128 @@ mod tests {
             updated.sync.remote_url,
             Some("git@github.com:user/repo.git".to_string())
         );
+
+        let updated = manager
+            .update_sync_config(None, None, Some(None))
+            .await
+            .unwrap();
+        assert_eq!(updated.sync.remote_url, None);
+    }
+
+    /// Rewrites config.json the way a text editor would, pushing the mtime
+    /// forward so the change is visible regardless of timestamp granularity.
+    fn edit_externally(apply: impl FnOnce(&mut Config)) {
+        let path = init_config_dir().unwrap().join("config.json");
+        let mut config: Config =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        apply(&mut config);
+        std::fs::write(&path, serde_json::to_string_pretty(&config).unwrap()).unwrap();
+
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_external_edit_between_updates_is_kept() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+
+        manager
+            .update_pomodoro_config(Some(1800), None, None, None, None, None)
+            .await
+            .unwrap();
+
+        edit_externally(|config| config.pomodoro.short_break = 600);
+
+        let updated = manager
+            .update_pomodoro_config(None, None, Some(1200), None, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(updated.pomodoro.work_duration, 1800);
+        assert_eq!(updated.pomodoro.short_break, 600);
+        assert_eq!(updated.pomodoro.long_break, 1200);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_reload_emits_event() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let mut events = manager.event_manager.subscribe();
+
+        assert!(!manager.reload_if_changed().await.unwrap());
+
+        edit_externally(|config| config.pomodoro.work_duration = 3000);
+
+        assert!(manager.reload_if_changed().await.unwrap());
+        assert_eq!(manager.get().await.pomodoro.work_duration, 3000);
+        match events.recv().await.unwrap() {
+            DaemonEvent::Config(event) => {
+                assert!(matches!(event.event_type, ConfigEventType::Reloaded));
+            }
+            other => panic!("Expected config event, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_forced_reload_ignores_mtime() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+
+        let path = init_config_dir().unwrap().join("config.json");
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+        let mut config = manager.get().await;
+        config.pomodoro.long_break = 1200;
+        std::fs::write(&path, serde_json::to_string_pretty(&config).unwrap()).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        assert!(!manager.reload_if_changed().await.unwrap());
+        assert!(manager.reload().await.unwrap());
+        assert_eq!(manager.get().await.pomodoro.long_break, 1200);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_invalid_external_edit_is_ignored() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+
+        edit_externally(|config| config.pomodoro.work_duration = 0);
+
+        assert!(!manager.reload_if_changed().await.unwrap());
+        assert_eq!(manager.get().await.pomodoro.work_duration, 1500);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_full_update_overwrites_external_edit_with_warning() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let mut events = manager.event_manager.subscribe();
+        let stale = manager.get().await;
+
+        edit_externally(|config| config.pomodoro.short_break = 600);
+
+        let saved = manager.update(stale).await.unwrap();
+        assert_eq!(saved.pomodoro.short_break, 300);
+        match events.recv().await.unwrap() {
+            DaemonEvent::Config(event) => {
+                assert!(matches!(
+                    event.event_type,
+                    ConfigEventType::Overwritten { .. }
+                ));
+            }
+            other => panic!("Expected config event, got {:?}", other),
+        }
+        assert!(!manager.reload_if_changed().await.unwrap());
     }
 }
@@ -1,3 +1,3 @@
 pub mod manager;
 
-pub use manager::ConfigManager;
+pub use manager::{ConfigManager, ConfigManagerError};
@@ -0,0 +1,54 @@
+//! Getting the passphrase for encrypted entries at startup.
+
+use anyhow::{Context, Result};
+use mootimer_core::storage::crypto::{Cipher, KEY_FILE};
+use std::path::Path;
+
+const KEYRING_SERVICE: &str = "mootimer";
+const KEYRING_USER: &str = "passphrase";
+
+/// Lets scripts and services without a keychain or terminal unlock the
+/// data.
+const PASSPHRASE_ENV: &str = "MOOTIMER_PASSPHRASE";
+
+/// The cipher for `data_dir`, with the passphrase from `MOOTIMER_PASSPHRASE`,
+/// the OS keychain, or else a single prompt. A passphrase that works is
+/// saved to the keychain so later starts don't ask.
+pub fn unlock(data_dir: &Path) -> Result<Cipher> {
+    let keyring = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER).ok();
+
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Cipher::unlock(data_dir, &passphrase)
+            .with_context(|| format!("Failed to unlock entries with {}", PASSPHRASE_ENV));
+    }
+
+    if let Some(passphrase) = keyring.as_ref().and_then(|k| k.get_password().ok())
+        && let Ok(cipher) = Cipher::unlock(data_dir, &passphrase)
+    {
+        return Ok(cipher);
+    }
+
+    let passphrase = prompt(!data_dir.join(KEY_FILE).exists())?;
+    let cipher = Cipher::unlock(data_dir, &passphrase).context("Failed to unlock entries")?;
+    if let Some(keyring) = keyring
+        && let Err(e) = keyring.set_password(&passphrase)
+    {
+        eprintln!("Could not save the passphrase to the keychain: {}", e);
+    }
+    Ok(cipher)
+}
+
+/// Asks twice when setting a new passphrase, so a typo doesn't lock the
+/// data away.
+fn prompt(new: bool) -> Result<String> {
+    let passphrase =
+        rpassword::prompt_password("MooTimer passphrase: ").context("Failed to read passphrase")?;
+    if new {
+        let again = rpassword::prompt_password("Repeat passphrase: ")
+            .context("Failed to read passphrase")?;
+        if again != passphrase {
+            anyhow::bail!("Passphrases do not match");
+        }
+    }
+    Ok(passphrase)
+}
@@ -0,0 +1,302 @@
+//! Per-day totals of a profile's entries, so stats for any run of days can
+//! be summed from a few hundred buckets instead of filtering every entry.
+//!
+//! Days are UTC dates of `Entry::start_time`, the same boundaries the
+//! `get_today`/`get_week`/`get_month` filters use. The working hours split
+//! is worked out per entry as it is added, in the daemon's timezone.
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use std::collections::BTreeMap;
+
+use super::manager::{BreakCompliance, EntryStats, PomodoroStats, SourceStats, TaskTotal};
+use mootimer_core::models::{Entry, HoursSplit, TimerMode, WorkingHours};
+
+/// A run of days to report on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsWindow {
+    Today,
+    /// Since Monday.
+    Week,
+    /// Since the first of the month.
+    Month,
+    /// The last `n` days, today included.
+    LastDays(u32),
+    /// From `from` to `to`, both included.
+    Range {
+        from: NaiveDate,
+        to: NaiveDate,
+    },
+}
+
+impl StatsWindow {
+    /// Parses `today`, `week`, `month`, `<n>d` or `YYYY-MM-DD..YYYY-MM-DD`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "today" => return Some(Self::Today),
+            "week" => return Some(Self::Week),
+            "month" => return Some(Self::Month),
+            _ => {}
+        }
+        if let Some((from, to)) = s.split_once("..") {
+            let from = NaiveDate::parse_from_str(from, "%Y-%m-%d").ok()?;
+            let to = NaiveDate::parse_from_str(to, "%Y-%m-%d").ok()?;
+            return (from <= to).then_some(Self::Range { from, to });
+        }
+        let days: u32 = s.strip_suffix('d')?.parse().ok()?;
+        (days > 0).then_some(Self::LastDays(days))
+    }
+
+    /// The first day and, for ranges, the last day of the window. Windows
+    /// ending today are open so entries starting later still count, as they
+    /// do in `get_today`.
+    pub fn bounds(&self, today: NaiveDate) -> (NaiveDate, Option<NaiveDate>) {
+        match *self {
+            Self::Today => (today, None),
+            Self::Week => (
+                today - Duration::days(today.weekday().num_days_from_monday() as i64),
+                None,
+            ),
+            Self::Month => (today.with_day(1).unwrap_or(today), None),
+            Self::LastDays(n) => (today - Duration::days(n as i64 - 1), None),
+            Self::Range { from, to } => (from, Some(to)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct DayTotals {
+    entries: usize,
+    duration_seconds: u64,
+    pomodoro_count: usize,
+    by_source: BTreeMap<&'static str, SourceStats>,
+    pomodoros_completed: u32,
+    pomodoros_abandoned: u32,
+    interrupted_seconds: u64,
+    by_task: BTreeMap<String, TaskTotal>,
+    hours: HoursSplit,
+}
+
+impl DayTotals {
+    fn merge(&mut self, other: &DayTotals) {
+        self.entries += other.entries;
+        self.duration_seconds += other.duration_seconds;
+        self.pomodoro_count += other.pomodoro_count;
+        for (kind, source) in &other.by_source {
+            let stats = self.by_source.entry(kind).or_default();
+            stats.entries += source.entries;
+            stats.duration_seconds += source.duration_seconds;
+        }
+        self.pomodoros_completed += other.pomodoros_completed;
+        self.pomodoros_abandoned += other.pomodoros_abandoned;
+        self.interrupted_seconds += other.interrupted_seconds;
+        self.hours.add(&other.hours);
+        for (task_id, task) in &other.by_task {
+            let total = self.by_task.entry(task_id.clone()).or_default();
+            total.task_id.clone_from(task_id);
+            total.entries += task.entries;
+            total.duration_seconds += task.duration_seconds;
+            // Days merge oldest first, so the last title seen is the latest.
+            if task.task_title.is_some() {
+                total.task_title.clone_from(&task.task_title);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DailyStats {
+    days: BTreeMap<NaiveDate, DayTotals>,
+    working_hours: WorkingHours,
+}
+
+impl DailyStats {
+    pub fn from_entries(entries: &[Entry], working_hours: WorkingHours) -> Self {
+        let mut daily = Self {
+            days: BTreeMap::new(),
+            working_hours,
+        };
+        for entry in entries {
+            daily.add(entry);
+        }
+        daily
+    }
+
+    pub fn add(&mut self, entry: &Entry) {
+        let day = self.days.entry(entry.start_time.date_naive()).or_default();
+        day.entries += 1;
+        day.duration_seconds += entry.duration_seconds;
+        if entry.mode == TimerMode::Pomodoro {
+            day.pomodoro_count += 1;
+        }
+        let source = day.by_source.entry(entry.source.kind()).or_default();
+        source.entries += 1;
+        source.duration_seconds += entry.duration_seconds;
+        TaskTotal::add(&mut day.by_task, entry);
+        day.hours.add(&self.working_hours.split(entry, &Local));
+        if let Some(outcome) = entry.pomodoro {
+            day.pomodoros_completed += outcome.completed_sessions;
+            if let Some(seconds) = outcome.interrupted_at_seconds {
+                day.pomodoros_abandoned += 1;
+                day.interrupted_seconds += seconds;
+            }
+        }
+    }
+
+    /// Takes back an earlier `add` of the same entry.
+    pub fn remove(&mut self, entry: &Entry) {
+        let date = entry.start_time.date_naive();
+        let Some(day) = self.days.get_mut(&date) else {
+            return;
+        };
+        day.hours.remove(&self.working_hours.split(entry, &Local));
+        day.entries = day.entries.saturating_sub(1);
+        day.duration_seconds = day.duration_seconds.saturating_sub(entry.duration_seconds);
+        if entry.mode == TimerMode::Pomodoro {
+            day.pomodoro_count = day.pomodoro_count.saturating_sub(1);
+        }
+        if let Some(source) = day.by_source.get_mut(entry.source.kind()) {
+            source.entries = source.entries.saturating_sub(1);
+            source.duration_seconds = source
+                .duration_seconds
+                .saturating_sub(entry.duration_seconds);
+            if source.entries == 0 {
+                day.by_source.remove(entry.source.kind());
+            }
+        }
+        if let Some(task_id) = &entry.task_id
+            && let Some(task) = day.by_task.get_mut(task_id)
+        {
+            task.entries = task.entries.saturating_sub(1);
+            task.duration_seconds = task.duration_seconds.saturating_sub(entry.duration_seconds);
+            if task.entries == 0 {
+                day.by_task.remove(task_id);
+            }
+        }
+        if let Some(outcome) = entry.pomodoro {
+            day.pomodoros_completed = day
+                .pomodoros_completed
+                .saturating_sub(outcome.completed_sessions);
+            if let Some(seconds) = outcome.interrupted_at_seconds {
+                day.pomodoros_abandoned = day.pomodoros_abandoned.saturating_sub(1);
+                day.interrupted_seconds = day.interrupted_seconds.saturating_sub(seconds);
+            }
+        }
+        if day.entries == 0 {
+            self.days.remove(&date);
+        }
+    }
+
+    /// Stats for entries starting on `from` or later and, if given, on `to`
+    /// or earlier; the same numbers `EntryManager::calculate_stats` gives for
+    /// those entries. Break compliance is left at its default.
+    pub fn stats(&self, from: NaiveDate, to: Option<NaiveDate>) -> EntryStats {
+        let mut total = DayTotals::default();
+        let days = match to {
+            Some(to) => self.days.range(from..=to),
+            None => self.days.range(from..),
+        };
+        for (_, day) in days {
+            total.merge(day);
+        }
+
+        EntryStats {
+            total_entries: total.entries,
+            total_duration_seconds: total.duration_seconds,
+            total_duration_hours: total.duration_seconds as f64 / 3600.0,
+            pomodoro_count: total.pomodoro_count,
+            manual_count: total.entries - total.pomodoro_count,
+            avg_duration_seconds: if total.entries == 0 {
+                0
+            } else {
+                total.duration_seconds / total.entries as u64
+            },
+            by_source: total.by_source,
+            pomodoro: PomodoroStats::from_totals(
+                total.pomodoros_completed,
+                total.pomodoros_abandoned,
+                total.interrupted_seconds,
+            ),
+            break_compliance: BreakCompliance::default(),
+            top_task: TaskTotal::top(&total.by_task),
+            working_hours: total.hours,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::EntryManager;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_window_parse_and_bounds() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 16).unwrap(); // a Thursday
+        let day = |d| NaiveDate::from_ymd_opt(2024, 5, d).unwrap();
+
+        let bounds = |s: &str| StatsWindow::parse(s).unwrap().bounds(today);
+        assert_eq!(bounds("today"), (today, None));
+        assert_eq!(bounds("week"), (day(13), None));
+        assert_eq!(bounds("month"), (day(1), None));
+        assert_eq!(bounds("7d"), (day(10), None));
+        assert_eq!(bounds("2024-05-02..2024-05-04"), (day(2), Some(day(4))));
+
+        for bad in ["0d", "d", "yesterday", "2024-05-04..2024-05-02"] {
+            assert_eq!(StatsWindow::parse(bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_matches_calculate_stats() {
+        let base = Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap();
+        let entries: Vec<Entry> = (0..40)
+            .map(|i| {
+                let start = base + Duration::hours(i * 7);
+                let mode = if i % 3 == 0 {
+                    TimerMode::Manual
+                } else {
+                    TimerMode::Pomodoro
+                };
+                let task = ["api", "web", "docs"][i as usize % 3].to_string();
+                let minutes = 20 + i % 4 * 5;
+                Entry::create_completed(
+                    Some(task),
+                    None,
+                    start,
+                    start + Duration::minutes(minutes),
+                    mode,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        let hours = WorkingHours::default();
+        let mut daily = DailyStats::from_entries(&entries, hours);
+        let from = NaiveDate::from_ymd_opt(2024, 5, 3).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 5, 7).unwrap();
+        let in_range: Vec<Entry> = entries
+            .iter()
+            .filter(|e| (from..=to).contains(&e.start_time.date_naive()))
+            .cloned()
+            .collect();
+
+        let expected = EntryManager::calculate_stats(&in_range);
+        let stats = daily.stats(from, Some(to));
+        assert_eq!(stats.total_entries, expected.total_entries);
+        assert_eq!(
+            stats.total_duration_seconds,
+            expected.total_duration_seconds
+        );
+        assert_eq!(stats.pomodoro_count, expected.pomodoro_count);
+        assert_eq!(stats.by_source, expected.by_source);
+        assert!(expected.top_task.is_some());
+        assert_eq!(stats.top_task, expected.top_task);
+        assert_eq!(stats.working_hours, hours.split_all(&in_range, &Local));
+        assert!(stats.working_hours.outside_seconds > 0);
+
+        for entry in &entries {
+            daily.remove(entry);
+        }
+        assert!(daily.days.is_empty());
+    }
+}
@@ -1,15 +1,20 @@
-use chrono::{DateTime, Datelike, Utc};
-use std::collections::HashMap;
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock as StdRwLock};
 use tokio::sync::RwLock;
 
+use super::daily::{DailyStats, StatsWindow};
+use super::stats_cache::StatsCache;
 use crate::event_manager::EventManager;
 use crate::events::EntryEvent;
+pub use mootimer_core::query::EntryFilter;
 use mootimer_core::{
     Result as CoreResult,
-    models::Entry,
-    storage::{EntryStorage, init_data_dir},
+    models::{BreakRecord, DaemonConfig, Device, Entry, HoursSplit, WorkingHours},
+    storage::{BreakStorage, EntryStorage, init_data_dir},
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -23,18 +28,38 @@ pub enum EntryManagerError {
     #[error("Invalid entry: {0}")]
     Invalid(String),
 
+    #[error("Entry looks like a duplicate of {existing_id}")]
+    Duplicate { existing_id: String },
+
+    #[error("{} of the entries are invalid: {}", .0.len(), describe_batch_errors(.0))]
+    InvalidBatch(Vec<BatchError>),
+
     #[error("Task join error: {0}")]
     JoinError(String),
 }
 
 pub type Result<T> = std::result::Result<T, EntryManagerError>;
 
+/// Why the entry at `index` of a batch was not stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BatchError {
+    pub index: usize,
+    pub message: String,
+}
+
+fn describe_batch_errors(errors: &[BatchError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("#{}: {}", e.index, e.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// What `add_batch` stored and what it skipped.
 #[derive(Debug, Clone)]
-pub struct EntryFilter {
-    pub start_date: Option<DateTime<Utc>>,
-    pub end_date: Option<DateTime<Utc>>,
-    pub task_id: Option<String>,
-    pub tags: Option<Vec<String>>,
+pub struct BatchOutcome {
+    pub created: Vec<Entry>,
+    pub errors: Vec<BatchError>,
 }
 
 #[derive(Debug, Clone)]
@@ -45,11 +70,190 @@ pub struct EntryStats {
     pub pomodoro_count: usize,
     pub manual_count: usize,
     pub avg_duration_seconds: u64,
+    /// Keyed by `EntrySource::kind`.
+    pub by_source: BTreeMap<&'static str, SourceStats>,
+    pub pomodoro: PomodoroStats,
+    /// Filled in by the `get_*_stats` methods, which know the period.
+    pub break_compliance: BreakCompliance,
+    /// The task with the most time; `None` if no entry has a task.
+    pub top_task: Option<TaskTotal>,
+    /// Time inside and outside the profile's working hours, in the
+    /// daemon's timezone. Filled in by the `get_*_stats` methods.
+    pub working_hours: HoursSplit,
+}
+
+/// Time logged against one task.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TaskTotal {
+    pub task_id: String,
+    /// The title on the latest entry that has one.
+    pub task_title: Option<String>,
+    pub entries: usize,
+    pub duration_seconds: u64,
+}
+
+impl TaskTotal {
+    pub(super) fn add(totals: &mut BTreeMap<String, TaskTotal>, entry: &Entry) {
+        let Some(task_id) = &entry.task_id else {
+            return;
+        };
+        let total = totals.entry(task_id.clone()).or_insert_with(|| TaskTotal {
+            task_id: task_id.clone(),
+            ..Default::default()
+        });
+        total.entries += 1;
+        total.duration_seconds += entry.duration_seconds;
+        if entry.task_title.is_some() {
+            total.task_title = entry.task_title.clone();
+        }
+    }
+
+    /// The one with the most time, the lowest task id winning ties.
+    pub(super) fn top(totals: &BTreeMap<String, TaskTotal>) -> Option<TaskTotal> {
+        totals
+            .values()
+            .rev()
+            .max_by_key(|t| t.duration_seconds)
+            .cloned()
+    }
+}
+
+/// How many pomodoro breaks were offered and how many were really taken,
+/// i.e. lasted at least `BREAK_TAKEN_SHARE` of their length.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BreakCompliance {
+    pub offered: usize,
+    pub taken: usize,
+    /// `taken / offered`, or 0 if no breaks were offered.
+    pub rate: f64,
+}
+
+impl BreakCompliance {
+    fn from_breaks(breaks: &[BreakRecord]) -> Self {
+        let offered = breaks.len();
+        let taken = breaks.iter().filter(|b| b.is_taken()).count();
+        Self {
+            offered,
+            taken,
+            rate: if offered == 0 {
+                0.0
+            } else {
+                taken as f64 / offered as f64
+            },
+        }
+    }
+}
+
+/// How many pomodoro work phases ran to the end and how many were given up.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PomodoroStats {
+    pub completed: u32,
+    pub abandoned: u32,
+    /// Share of work phases completed, from 0 to 1; 0 if none were started.
+    pub completion_rate: f64,
+    /// Mean seconds into a work phase at which it was abandoned.
+    pub avg_interruption_seconds: Option<u64>,
+}
+
+impl PomodoroStats {
+    fn from_entries(entries: &[Entry]) -> Self {
+        let mut completed = 0;
+        let mut abandoned = 0;
+        let mut interrupted_total = 0;
+        for outcome in entries.iter().filter_map(|e| e.pomodoro) {
+            completed += outcome.completed_sessions;
+            if let Some(seconds) = outcome.interrupted_at_seconds {
+                abandoned += 1;
+                interrupted_total += seconds;
+            }
+        }
+        Self::from_totals(completed, abandoned, interrupted_total)
+    }
+
+    pub(super) fn from_totals(completed: u32, abandoned: u32, interrupted_total: u64) -> Self {
+        let mut stats = Self {
+            completed,
+            abandoned,
+            ..Self::default()
+        };
+        let started = stats.completed + stats.abandoned;
+        if started > 0 {
+            stats.completion_rate = stats.completed as f64 / started as f64;
+        }
+        if stats.abandoned > 0 {
+            stats.avg_interruption_seconds = Some(interrupted_total / stats.abandoned as u64);
+        }
+        stats
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SourceStats {
+    pub entries: usize,
+    pub duration_seconds: u64,
+}
+
+/// Entries that started in one hour of the day, in the daemon's timezone.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HourStats {
+    pub hour: u8,
+    pub avg_duration_seconds: f64,
+    pub session_count: u32,
+}
+
+/// Entries for the same task overlapping by at least this share of the
+/// longer one are taken to be the same block logged twice.
+const DUPLICATE_OVERLAP: f64 = 0.9;
+
+/// Entries for the same task whose start times and durations both differ by
+/// no more than this are duplicates even if they overlap less.
+const DUPLICATE_TOLERANCE_SECONDS: i64 = 60;
+
+/// A stored entry that looks like a second copy of an earlier one.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateEntry {
+    pub entry: Entry,
+    pub duplicate_of: String,
+}
+
+/// A stored entry that fails `Entry::validate`, with the reason.
+#[derive(Debug, Clone, Serialize)]
+pub struct InvalidEntry {
+    pub entry: Entry,
+    pub problem: String,
+}
+
+/// How often `bulk_stats` found a profile's daily totals ready and how
+/// often it had to build them from the entries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StatsCacheCounters {
+    pub hits: u64,
+    pub misses: u64,
 }
 
 pub struct EntryManager {
     data_dir: PathBuf,
     cache: Arc<RwLock<HashMap<String, Vec<Entry>>>>,
+    /// Built on first use from `cache` and kept in step with it. Always
+    /// locked after `cache`, never before.
+    daily: Arc<RwLock<HashMap<String, DailyStats>>>,
+    /// Today, week and month stats as last answered, dropped by any change
+    /// to an entry or break in their period. Always locked after `cache`,
+    /// never before.
+    stats: Arc<RwLock<HashMap<String, StatsCache>>>,
+    /// Entry id to its position in `cache`, per profile. Built on first
+    /// lookup, extended by appends and dropped whenever a profile's entries
+    /// are rewritten. Always locked after `cache`, never before.
+    id_index: Arc<RwLock<HashMap<String, HashMap<String, usize>>>>,
+    daily_hits: AtomicU64,
+    daily_misses: AtomicU64,
+    /// Stamped on every entry added; set by `ApiHandler` from the config.
+    device: StdRwLock<Option<Device>>,
+    /// Per profile, set by `ApiHandler` from the profile before stats are
+    /// asked for. Profiles not in here use the default hours.
+    working_hours: StdRwLock<HashMap<String, WorkingHours>>,
+    /// `daemon.earliest_entry_date`; kept current by `ApiHandler`.
+    earliest_entry_date: StdRwLock<NaiveDate>,
     event_manager: Arc<EventManager>,
 }
 
@@ -60,10 +264,64 @@ impl EntryManager {
         Ok(Self {
             data_dir,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            daily: Arc::new(RwLock::new(HashMap::new())),
+            stats: Arc::new(RwLock::new(HashMap::new())),
+            id_index: Arc::new(RwLock::new(HashMap::new())),
+            daily_hits: AtomicU64::new(0),
+            daily_misses: AtomicU64::new(0),
+            device: StdRwLock::new(None),
+            working_hours: StdRwLock::new(HashMap::new()),
+            earliest_entry_date: StdRwLock::new(DaemonConfig::default().earliest_entry_date),
             event_manager,
         })
     }
 
+    pub fn set_device(&self, device: Device) {
+        *self.device.write().unwrap() = Some(device);
+    }
+
+    pub fn set_earliest_entry_date(&self, date: NaiveDate) {
+        *self.earliest_entry_date.write().unwrap() = date;
+    }
+
+    /// `Entry::validate`, plus the timestamp checks against the daemon's
+    /// clock and `daemon.earliest_entry_date`.
+    pub fn check(&self, entry: &Entry) -> CoreResult<()> {
+        let earliest = *self.earliest_entry_date.read().unwrap();
+        entry.validate()?;
+        entry.check_timestamps(Utc::now(), earliest)
+    }
+
+    /// Stats worked out with other hours are dropped when they change.
+    pub async fn set_working_hours(&self, profile_id: &str, hours: WorkingHours) {
+        if self.working_hours_of(profile_id) == hours {
+            return;
+        }
+        let _cache = self.cache.write().await;
+        self.working_hours
+            .write()
+            .unwrap()
+            .insert(profile_id.to_string(), hours);
+        self.daily.write().await.remove(profile_id);
+        self.stats.write().await.remove(profile_id);
+    }
+
+    fn working_hours_of(&self, profile_id: &str) -> WorkingHours {
+        self.working_hours
+            .read()
+            .unwrap()
+            .get(profile_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Stamps the entry with this machine, unless it came from another.
+    fn stamp_device(&self, entry: &mut Entry) {
+        if let Some(device) = self.device.read().unwrap().as_ref() {
+            entry.stamp_device(device);
+        }
+    }
+
     pub async fn load_profile(&self, profile_id: &str) -> Result<()> {
         let data_dir = self.data_dir.clone();
         let profile_id_owned = profile_id.to_string();
@@ -80,16 +338,47 @@ impl EntryManager {
             entries.len(),
             profile_id
         );
+        let invalid = entries.iter().filter(|e| self.check(e).is_err()).count();
+        if invalid > 0 {
+            tracing::warn!(
+                "Profile '{}' has {} invalid entries, see entry.list_invalid",
+                profile_id,
+                invalid
+            );
+        }
         let mut cache = self.cache.write().await;
         cache.insert(profile_id.to_string(), entries);
+        self.daily.write().await.remove(profile_id);
+        self.stats.write().await.remove(profile_id);
+        self.id_index.write().await.remove(profile_id);
         Ok(())
     }
 
+    /// Validates and stores `entry`, rejecting it if it looks like a
+    /// duplicate of one already stored.
     pub async fn add(&self, profile_id: &str, entry: Entry) -> Result<Entry> {
-        entry
-            .validate()
+        self.add_with(profile_id, entry, false).await
+    }
+
+    pub async fn add_with(
+        &self,
+        profile_id: &str,
+        mut entry: Entry,
+        allow_duplicate: bool,
+    ) -> Result<Entry> {
+        self.stamp_device(&mut entry);
+        self.check(&entry)
             .map_err(|e| EntryManagerError::Invalid(e.to_string()))?;
 
+        if !allow_duplicate {
+            let existing = self.get_all(profile_id).await?;
+            if let Some(duplicate) = existing.iter().find(|e| is_likely_duplicate(e, &entry)) {
+                return Err(EntryManagerError::Duplicate {
+                    existing_id: duplicate.id.clone(),
+                });
+            }
+        }
+
         let data_dir = self.data_dir.clone();
         let profile_id_owned = profile_id.to_string();
         let entry_clone = entry.clone();
@@ -103,10 +392,17 @@ impl EntryManager {
 
         {
             let mut cache = self.cache.write().await;
-            cache
-                .entry(profile_id.to_string())
-                .or_insert_with(Vec::new)
-                .push(entry.clone());
+            let entries = cache.entry(profile_id.to_string()).or_insert_with(Vec::new);
+            entries.push(entry.clone());
+            if let Some(ids) = self.id_index.write().await.get_mut(profile_id) {
+                ids.insert(entry.id.clone(), entries.len() - 1);
+            }
+            if let Some(daily) = self.daily.write().await.get_mut(profile_id) {
+                daily.add(&entry);
+            }
+            if let Some(stats) = self.stats.write().await.get_mut(profile_id) {
+                stats.invalidate_entries([&entry]);
+            }
         }
 
         let event = EntryEvent::added(profile_id.to_string(), entry.clone());
@@ -115,6 +411,98 @@ impl EntryManager {
         Ok(entry)
     }
 
+    /// Stores many entries with one write, checking each as `add_with`
+    /// does and also against the entries before it in the batch. Unless
+    /// `skip_invalid` is set, one bad entry fails the batch and nothing is
+    /// stored.
+    pub async fn add_batch(
+        &self,
+        profile_id: &str,
+        entries: Vec<Entry>,
+        allow_duplicate: bool,
+        skip_invalid: bool,
+    ) -> Result<BatchOutcome> {
+        let mut accepted: Vec<Entry> = Vec::new();
+        let mut errors = Vec::new();
+        // Also loads the profile, so the cache below is extended rather
+        // than started from just this batch.
+        let existing = self.get_all(profile_id).await?;
+
+        for (index, mut entry) in entries.into_iter().enumerate() {
+            self.stamp_device(&mut entry);
+            let problem = match self.check(&entry) {
+                Err(e) => Some(e.to_string()),
+                Ok(()) if allow_duplicate => None,
+                Ok(()) => existing
+                    .iter()
+                    .chain(&accepted)
+                    .find(|e| is_likely_duplicate(e, &entry))
+                    .map(|d| format!("Entry looks like a duplicate of {}", d.id)),
+            };
+            match problem {
+                Some(message) => errors.push(BatchError { index, message }),
+                None => accepted.push(entry),
+            }
+        }
+
+        if !errors.is_empty() && !skip_invalid {
+            return Err(EntryManagerError::InvalidBatch(errors));
+        }
+
+        if !accepted.is_empty() {
+            let data_dir = self.data_dir.clone();
+            let profile_id_owned = profile_id.to_string();
+            let to_store = accepted.clone();
+
+            tokio::task::spawn_blocking(move || {
+                let storage = EntryStorage::new(data_dir);
+                storage.append_all(&profile_id_owned, &to_store)
+            })
+            .await
+            .map_err(|e| EntryManagerError::JoinError(e.to_string()))??;
+
+            let mut cache = self.cache.write().await;
+            let entries = cache.entry(profile_id.to_string()).or_insert_with(Vec::new);
+            let first = entries.len();
+            entries.extend(accepted.iter().cloned());
+            if let Some(ids) = self.id_index.write().await.get_mut(profile_id) {
+                ids.extend(
+                    accepted
+                        .iter()
+                        .enumerate()
+                        .map(|(i, e)| (e.id.clone(), first + i)),
+                );
+            }
+            if let Some(daily) = self.daily.write().await.get_mut(profile_id) {
+                accepted.iter().for_each(|entry| daily.add(entry));
+            }
+            if let Some(stats) = self.stats.write().await.get_mut(profile_id) {
+                stats.invalidate_entries(&accepted);
+            }
+            drop(cache);
+
+            for entry in &accepted {
+                let event = EntryEvent::added(profile_id.to_string(), entry.clone());
+                self.event_manager.emit_entry(event);
+            }
+        }
+
+        Ok(BatchOutcome {
+            created: accepted,
+            errors,
+        })
+    }
+
+    /// Forgets every loaded profile; they are read from disk again on next
+    /// use.
+    pub async fn clear_cache(&self) {
+        let mut cache = self.cache.write().await;
+        cache.clear();
+        self.daily.write().await.clear();
+        self.stats.write().await.clear();
+        self.id_index.write().await.clear();
+    }
+
     pub async fn get_all(&self, profile_id: &str) -> Result<Vec<Entry>> {
         {
             let cache = self.cache.read().await;
@@ -129,98 +517,169 @@ impl EntryManager {
         Ok(cache.get(profile_id).cloned().unwrap_or_default())
     }
 
+    /// One entry, found through the id index rather than a scan.
+    pub async fn get(&self, profile_id: &str, entry_id: &str) -> Result<Entry> {
+        self.ensure_loaded(profile_id).await?;
+        let cache = self.cache.read().await;
+        let entries = cache.get(profile_id).map(Vec::as_slice).unwrap_or_default();
+        self.position_in(profile_id, entries, entry_id)
+            .await
+            .map(|i| entries[i].clone())
+            .ok_or_else(|| EntryManagerError::NotFound(entry_id.to_string()))
+    }
+
+    async fn ensure_loaded(&self, profile_id: &str) -> Result<()> {
+        if !self.cache.read().await.contains_key(profile_id) {
+            self.load_profile(profile_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Where `entry_id` sits in `entries`, the cached entries of
+    /// `profile_id`, which the caller keeps locked.
+    async fn position_in(
+        &self,
+        profile_id: &str,
+        entries: &[Entry],
+        entry_id: &str,
+    ) -> Option<usize> {
+        let mut index = self.id_index.write().await;
+        let ids = index.entry(profile_id.to_string()).or_insert_with(|| {
+            entries
+                .iter()
+                .enumerate()
+                .map(|(i, e)| (e.id.clone(), i))
+                .collect()
+        });
+        ids.get(entry_id)
+            .copied()
+            .filter(|&i| entries.get(i).is_some_and(|e| e.id == entry_id))
+    }
+
+    /// Position of `entry_id` in the cached entries, for edits that then
+    /// work on a copy of them.
+    async fn position(&self, profile_id: &str, entry_id: &str) -> Result<usize> {
+        let cache = self.cache.read().await;
+        let entries = cache.get(profile_id).map(Vec::as_slice).unwrap_or_default();
+        self.position_in(profile_id, entries, entry_id)
+            .await
+            .ok_or_else(|| EntryManagerError::NotFound(entry_id.to_string()))
+    }
+
+    /// The entry that ended most recently, if there is any.
+    pub async fn last(&self, profile_id: &str) -> Result<Option<Entry>> {
+        Ok(self
+            .get_all(profile_id)
+            .await?
+            .into_iter()
+            .filter(|e| e.end_time.is_some())
+            .max_by_key(|e| e.end_time))
+    }
+
+    /// Stored entries that fail validation. They were saved before
+    /// validation existed or edited by hand, and are kept as they are so the
+    /// user can fix or delete them.
+    pub async fn list_invalid(&self, profile_id: &str) -> Result<Vec<InvalidEntry>> {
+        let entries = self.get_all(profile_id).await?;
+        Ok(entries
+            .into_iter()
+            .filter_map(|entry| {
+                let problem = self.check(&entry).err()?.to_string();
+                Some(InvalidEntry { entry, problem })
+            })
+            .collect())
+    }
+
+    /// Entries in the range that look like a later copy of another entry in
+    /// the range, each paired with the earlier one.
+    pub async fn find_duplicates(
+        &self,
+        profile_id: &str,
+        start_date: Option<DateTime<Utc>>,
+        end_date: Option<DateTime<Utc>>,
+    ) -> Result<Vec<DuplicateEntry>> {
+        let entries = self
+            .filter(
+                profile_id,
+                EntryFilter {
+                    start_date,
+                    end_date,
+                    task_id: None,
+                    tags: None,
+                    source: None,
+                    device: None,
+                    mode: None,
+                },
+            )
+            .await?;
+
+        Ok(entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| {
+                let original = entries[..i]
+                    .iter()
+                    .find(|earlier| is_likely_duplicate(earlier, entry))?;
+                Some(DuplicateEntry {
+                    entry: entry.clone(),
+                    duplicate_of: original.id.clone(),
+                })
+            })
+            .collect())
+    }
+
     pub async fn filter(&self, profile_id: &str, filter: EntryFilter) -> Result<Vec<Entry>> {
         let entries = self.get_all(profile_id).await?;
 
         Ok(entries
             .into_iter()
-            .filter(|entry| {
-                if let Some(start) = filter.start_date
-                    && entry.start_time < start
-                {
-                    return false;
-                }
-                if let Some(end) = filter.end_date
-                    && entry.start_time > end
-                {
-                    return false;
-                }
-
-                if let Some(ref task_id) = filter.task_id
-                    && entry.task_id.as_ref() != Some(task_id)
-                {
-                    return false;
-                }
-
-                if let Some(ref tags) = filter.tags
-                    && !tags.iter().any(|tag| entry.has_tag(tag))
-                {
-                    return false;
-                }
-
-                true
-            })
+            .filter(|entry| filter.matches(entry))
             .collect())
     }
 
     pub async fn get_today(&self, profile_id: &str) -> Result<Vec<Entry>> {
-        let now = Utc::now();
-        let start_of_day = now
-            .date_naive()
-            .and_hms_opt(0, 0, 0)
-            .map(|dt| dt.and_utc())
-            .unwrap_or(now);
-
         self.filter(
             profile_id,
             EntryFilter {
-                start_date: Some(start_of_day),
+                start_date: Some(start_of_day()),
                 end_date: None,
                 task_id: None,
                 tags: None,
+                source: None,
+                device: None,
+                mode: None,
             },
         )
         .await
     }
 
     pub async fn get_week(&self, profile_id: &str) -> Result<Vec<Entry>> {
-        let now = Utc::now();
-        let days_from_monday = now.weekday().num_days_from_monday();
-        let start_of_week = now
-            .date_naive()
-            .and_hms_opt(0, 0, 0)
-            .map(|dt| dt.and_utc())
-            .map(|dt| dt - chrono::Duration::days(days_from_monday as i64))
-            .unwrap_or(now);
-
         self.filter(
             profile_id,
             EntryFilter {
-                start_date: Some(start_of_week),
+                start_date: Some(start_of_week()),
                 end_date: None,
                 task_id: None,
                 tags: None,
+                source: None,
+                device: None,
+                mode: None,
             },
         )
         .await
     }
 
     pub async fn get_month(&self, profile_id: &str) -> Result<Vec<Entry>> {
-        let now = Utc::now();
-        let start_of_month = now
-            .date_naive()
-            .with_day(1)
-            .and_then(|d| d.and_hms_opt(0, 0, 0))
-            .map(|dt| dt.and_utc())
-            .unwrap_or(now);
-
         self.filter(
             profile_id,
             EntryFilter {
-                start_date: Some(start_of_month),
+                start_date: Some(start_of_month()),
                 end_date: None,
                 task_id: None,
                 tags: None,
+                source: None,
+                device: None,
+                mode: None,
             },
         )
         .await
@@ -234,6 +693,15 @@ impl EntryManager {
             .count();
         let manual_count = entries.len() - pomodoro_count;
 
+        let mut by_source: BTreeMap<&'static str, SourceStats> = BTreeMap::new();
+        let mut by_task = BTreeMap::new();
+        for entry in entries {
+            let stats = by_source.entry(entry.source.kind()).or_default();
+            stats.entries += 1;
+            stats.duration_seconds += entry.duration_seconds;
+            TaskTotal::add(&mut by_task, entry);
+        }
+
         EntryStats {
             total_entries: entries.len(),
             total_duration_seconds: total_duration,
@@ -245,33 +713,246 @@ impl EntryManager {
             } else {
                 total_duration / entries.len() as u64
             },
+            by_source,
+            pomodoro: PomodoroStats::from_entries(entries),
+            break_compliance: BreakCompliance::default(),
+            top_task: TaskTotal::top(&by_task),
+            working_hours: HoursSplit::default(),
+        }
+    }
+
+    /// One `HourStats` for each hour from 0 to 23, hours without entries
+    /// included.
+    pub fn calculate_hourly(entries: &[Entry]) -> Vec<HourStats> {
+        let mut totals = [(0u64, 0u32); 24];
+        for entry in entries {
+            let hour = entry.start_time.with_timezone(&Local).hour() as usize;
+            totals[hour].0 += entry.duration_seconds;
+            totals[hour].1 += 1;
         }
+        totals
+            .iter()
+            .enumerate()
+            .map(|(hour, &(seconds, sessions))| HourStats {
+                hour: hour as u8,
+                avg_duration_seconds: if sessions == 0 {
+                    0.0
+                } else {
+                    seconds as f64 / sessions as f64
+                },
+                session_count: sessions,
+            })
+            .collect()
+    }
+
+    /// `calculate_hourly` over the entries starting between `from` and `to`,
+    /// both days included and either left open.
+    pub async fn hourly_stats(
+        &self,
+        profile_id: &str,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<HourStats>> {
+        let entries: Vec<Entry> = self
+            .get_all(profile_id)
+            .await?
+            .into_iter()
+            .filter(|entry| {
+                let day = entry.start_time.with_timezone(&Local).date_naive();
+                from.is_none_or(|from| day >= from) && to.is_none_or(|to| day <= to)
+            })
+            .collect();
+        Ok(Self::calculate_hourly(&entries))
     }
 
     pub async fn get_today_stats(&self, profile_id: &str) -> Result<EntryStats> {
-        let entries = self.get_today(profile_id).await?;
-        Ok(Self::calculate_stats(&entries))
+        self.period_stats(profile_id, StatsWindow::Today).await
     }
 
     pub async fn get_week_stats(&self, profile_id: &str) -> Result<EntryStats> {
-        let entries = self.get_week(profile_id).await?;
-        Ok(Self::calculate_stats(&entries))
+        self.period_stats(profile_id, StatsWindow::Week).await
     }
 
     pub async fn get_month_stats(&self, profile_id: &str) -> Result<EntryStats> {
-        let entries = self.get_month(profile_id).await?;
-        Ok(Self::calculate_stats(&entries))
+        self.period_stats(profile_id, StatsWindow::Month).await
     }
 
-    pub async fn delete(&self, profile_id: &str, entry_id: &str) -> Result<()> {
-        let mut entries = self.get_all(profile_id).await?;
+    /// Stats since the start of today, the week or the month, from the
+    /// profile's stats cache unless something in the period has changed
+    /// since they were last worked out.
+    async fn period_stats(&self, profile_id: &str, window: StatsWindow) -> Result<EntryStats> {
+        let today = Utc::now().date_naive();
+        if let Some(stats) = self
+            .stats
+            .read()
+            .await
+            .get(profile_id)
+            .and_then(|cache| cache.get(window, today))
+        {
+            return Ok(stats.clone());
+        }
+
+        // Worked out under the `cache` lock so no change can land between
+        // reading the entries and storing the answer.
+        self.ensure_loaded(profile_id).await?;
+        let cache = self.cache.read().await;
+        let (from, _) = window.bounds(today);
+        let since = from.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let filter = EntryFilter {
+            start_date: Some(since),
+            ..Default::default()
+        };
+        let entries: Vec<Entry> = cache
+            .get(profile_id)
+            .into_iter()
+            .flatten()
+            .filter(|entry| filter.matches(entry))
+            .cloned()
+            .collect();
+        let stats = self.stats_since(profile_id, &entries, since).await?;
+        self.stats
+            .write()
+            .await
+            .entry(profile_id.to_string())
+            .or_default()
+            .put(window, today, stats.clone());
+        drop(cache);
+        Ok(stats)
+    }
+
+    async fn stats_since(
+        &self,
+        profile_id: &str,
+        entries: &[Entry],
+        since: DateTime<Utc>,
+    ) -> Result<EntryStats> {
+        let mut stats = Self::calculate_stats(entries);
+        stats.working_hours = self.working_hours_of(profile_id).split_all(entries, &Local);
+        let breaks = self.breaks_since(profile_id, since).await?;
+        stats.break_compliance = BreakCompliance::from_breaks(&breaks);
+        Ok(stats)
+    }
+
+    /// Stats for each of `windows`, in order, from the profile's daily
+    /// totals and one read of its break log.
+    pub async fn bulk_stats(
+        &self,
+        profile_id: &str,
+        windows: &[StatsWindow],
+    ) -> Result<Vec<EntryStats>> {
+        let today = Utc::now().date_naive();
+        let bounds: Vec<_> = windows.iter().map(|w| w.bounds(today)).collect();
+
+        let mut all_stats = self
+            .with_daily(profile_id, |daily| {
+                bounds
+                    .iter()
+                    .map(|&(from, to)| daily.stats(from, to))
+                    .collect::<Vec<_>>()
+            })
+            .await?;
+
+        if let Some(earliest) = bounds.iter().map(|&(from, _)| from).min() {
+            let since = earliest.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+            let breaks = self.breaks_since(profile_id, since).await?;
+            for (stats, &(from, to)) in all_stats.iter_mut().zip(&bounds) {
+                let in_window: Vec<BreakRecord> = breaks
+                    .iter()
+                    .filter(|b| {
+                        let day = b.started_at.date_naive();
+                        day >= from && to.is_none_or(|to| day <= to)
+                    })
+                    .cloned()
+                    .collect();
+                stats.break_compliance = BreakCompliance::from_breaks(&in_window);
+            }
+        }
+        Ok(all_stats)
+    }
+
+    /// Runs `f` on the profile's daily totals, building them first if this
+    /// is the first use since the profile was loaded.
+    async fn with_daily<T>(&self, profile_id: &str, f: impl FnOnce(&DailyStats) -> T) -> Result<T> {
+        if let Some(daily) = self.daily.read().await.get(profile_id) {
+            self.daily_hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(f(daily));
+        }
+
+        // Loads the profile if needed; the entries are read again below
+        // under the lock so no change can slip in between.
+        self.get_all(profile_id).await?;
+        let cache = self.cache.read().await;
+        let mut daily = self.daily.write().await;
+        let daily = match daily.entry(profile_id.to_string()) {
+            std::collections::hash_map::Entry::Occupied(e) => {
+                self.daily_hits.fetch_add(1, Ordering::Relaxed);
+                e.into_mut()
+            }
+            std::collections::hash_map::Entry::Vacant(e) => {
+                self.daily_misses.fetch_add(1, Ordering::Relaxed);
+                let entries = cache.get(profile_id).map(Vec::as_slice).unwrap_or_default();
+                e.insert(DailyStats::from_entries(
+                    entries,
+                    self.working_hours_of(profile_id),
+                ))
+            }
+        };
+        Ok(f(daily))
+    }
+
+    pub fn stats_cache_counters(&self) -> StatsCacheCounters {
+        StatsCacheCounters {
+            hits: self.daily_hits.load(Ordering::Relaxed),
+            misses: self.daily_misses.load(Ordering::Relaxed),
+        }
+    }
+
+    pub async fn record_break(&self, profile_id: &str, record: &BreakRecord) -> Result<()> {
+        let data_dir = self.data_dir.clone();
+        let profile_id_owned = profile_id.to_string();
+        let day = record.started_at.date_naive();
+        let record = record.clone();
+
+        tokio::task::spawn_blocking(move || {
+            BreakStorage::new(data_dir).append(&profile_id_owned, &record)
+        })
+        .await
+        .map_err(|e| EntryManagerError::JoinError(e.to_string()))??;
+
+        // Break compliance is part of the cached stats. Taking `cache` waits
+        // out any stats read that loaded the break log before this append.
+        let _cache = self.cache.write().await;
+        if let Some(stats) = self.stats.write().await.get_mut(profile_id) {
+            stats.invalidate(day);
+        }
+        Ok(())
+    }
 
-        let initial_len = entries.len();
-        entries.retain(|e| e.id != entry_id);
+    /// Breaks that started at or after `since`, oldest first.
+    pub async fn breaks_since(
+        &self,
+        profile_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<BreakRecord>> {
+        let data_dir = self.data_dir.clone();
+        let profile_id = profile_id.to_string();
+
+        let mut breaks =
+            tokio::task::spawn_blocking(move || BreakStorage::new(data_dir).load(&profile_id))
+                .await
+                .map_err(|e| EntryManagerError::JoinError(e.to_string()))??;
+        breaks.retain(|b| b.started_at >= since);
+        breaks.sort_by_key(|b| b.started_at);
+        Ok(breaks)
+    }
 
-        if entries.len() == initial_len {
+    pub async fn delete(&self, profile_id: &str, entry_id: &str) -> Result<()> {
+        let mut entries = self.get_all(profile_id).await?;
+        let position = self.position(profile_id, entry_id).await?;
+        if entries.get(position).is_none_or(|e| e.id != entry_id) {
             return Err(EntryManagerError::NotFound(entry_id.to_string()));
         }
+        let removed = [entries.remove(position)];
 
         let data_dir = self.data_dir.clone();
         let profile_id_owned = profile_id.to_string();
@@ -287,6 +968,13 @@ impl EntryManager {
         {
             let mut cache = self.cache.write().await;
             cache.insert(profile_id.to_string(), entries);
+            self.id_index.write().await.remove(profile_id);
+            if let Some(daily) = self.daily.write().await.get_mut(profile_id) {
+                removed.iter().for_each(|entry| daily.remove(entry));
+            }
+            if let Some(stats) = self.stats.write().await.get_mut(profile_id) {
+                stats.invalidate_entries(&removed);
+            }
         }
 
         let event = EntryEvent::deleted(profile_id.to_string(), entry_id.to_string());
@@ -297,19 +985,15 @@ impl EntryManager {
 
     pub async fn update(&self, profile_id: &str, entry: Entry) -> Result<()> {
         let mut entries = self.get_all(profile_id).await?;
+        let position = self.position(profile_id, &entry.id).await?;
 
-        let mut found = false;
-        for e in entries.iter_mut() {
-            if e.id == entry.id {
-                *e = entry.clone();
-                found = true;
-                break;
-            }
-        }
-
-        if !found {
+        let Some(previous) = entries
+            .get_mut(position)
+            .filter(|e| e.id == entry.id)
+            .map(|e| std::mem::replace(e, entry.clone()))
+        else {
             return Err(EntryManagerError::NotFound(entry.id));
-        }
+        };
 
         let data_dir = self.data_dir.clone();
         let profile_id_owned = profile_id.to_string();
@@ -325,6 +1009,14 @@ impl EntryManager {
         {
             let mut cache = self.cache.write().await;
             cache.insert(profile_id.to_string(), entries);
+            self.id_index.write().await.remove(profile_id);
+            if let Some(daily) = self.daily.write().await.get_mut(profile_id) {
+                daily.remove(&previous);
+                daily.add(&entry);
+            }
+            if let Some(stats) = self.stats.write().await.get_mut(profile_id) {
+                stats.invalidate_entries([&previous, &entry]);
+            }
         }
 
         let event = EntryEvent::updated(profile_id.to_string(), entry.clone());
@@ -365,6 +1057,13 @@ impl EntryManager {
         {
             let mut cache = self.cache.write().await;
             cache.insert(source_profile_id.to_string(), entries_to_keep);
+            self.id_index.write().await.remove(source_profile_id);
+            if let Some(daily) = self.daily.write().await.get_mut(source_profile_id) {
+                entries_to_move.iter().for_each(|entry| daily.remove(entry));
+            }
+            if let Some(stats) = self.stats.write().await.get_mut(source_profile_id) {
+                stats.invalidate_entries(&entries_to_move);
+            }
         }
 
         let mut target_entries = self.get_all(target_profile_id).await.unwrap_or_default();
@@ -384,6 +1083,13 @@ impl EntryManager {
         {
             let mut cache = self.cache.write().await;
             cache.insert(target_profile_id.to_string(), target_entries);
+            self.id_index.write().await.remove(target_profile_id);
+            if let Some(daily) = self.daily.write().await.get_mut(target_profile_id) {
+                entries_to_move.iter().for_each(|entry| daily.add(entry));
+            }
+            if let Some(stats) = self.stats.write().await.get_mut(target_profile_id) {
+                stats.invalidate_entries(&entries_to_move);
+            }
         }
 
         for entry in &entries_to_move {
@@ -398,6 +1104,29 @@ impl EntryManager {
     }
 }
 
+/// Whether `a` and `b` are probably the same block of work logged twice:
+/// same task, and either mostly overlapping or starting and lasting about
+/// the same. Entries without a task are never taken for duplicates.
+fn is_likely_duplicate(a: &Entry, b: &Entry) -> bool {
+    if a.task_id.is_none() || a.task_id != b.task_id {
+        return false;
+    }
+
+    let end_of = |e: &Entry| {
+        e.end_time
+            .unwrap_or(e.start_time + chrono::Duration::seconds(e.duration_seconds as i64))
+    };
+    let overlap = (end_of(a).min(end_of(b)) - a.start_time.max(b.start_time)).num_seconds();
+    let longer = a.duration_seconds.max(b.duration_seconds);
+    if overlap > 0 && longer > 0 && overlap as f64 >= DUPLICATE_OVERLAP * longer as f64 {
+        return true;
+    }
+
+    let start_diff = (a.start_time - b.start_time).num_seconds().abs();
+    let duration_diff = (a.duration_seconds as i64 - b.duration_seconds as i64).abs();
+    start_diff <= DUPLICATE_TOLERANCE_SECONDS && duration_diff <= DUPLICATE_TOLERANCE_SECONDS
+}
+
 impl Default for EntryManager {
     fn default() -> Self {
         Self::new(Arc::new(crate::event_manager::EventManager::new()))
@@ -405,16 +1134,61 @@ impl Default for EntryManager {
     }
 }
 
+fn start_of_day() -> DateTime<Utc> {
+    let now = Utc::now();
+    now.date_naive()
+        .and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc())
+        .unwrap_or(now)
+}
+
+fn start_of_week() -> DateTime<Utc> {
+    let days_from_monday = Utc::now().weekday().num_days_from_monday();
+    start_of_day() - chrono::Duration::days(days_from_monday as i64)
+}
+
+fn start_of_month() -> DateTime<Utc> {
+    let now = Utc::now();
+    now.date_naive()
+        .with_day(1)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+        .unwrap_or(now)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::event_manager::EventManager;
     use chrono::Duration;
-    use mootimer_core::models::{Entry, TimerMode};
+    use mootimer_core::models::{
+        BreakStatus, Entry, EntrySource, PomodoroOutcome, PomodoroPhase, TimerMode,
+    };
     use serial_test::serial;
     use std::sync::Arc;
     use tempfile::TempDir;
 
+    fn completed(task_id: Option<&str>, task_title: Option<&str>, mode: TimerMode) -> Entry {
+        completed_hours_ago(0, task_id, task_title, mode)
+    }
+
+    fn completed_hours_ago(
+        hours: i64,
+        task_id: Option<&str>,
+        task_title: Option<&str>,
+        mode: TimerMode,
+    ) -> Entry {
+        let end = Utc::now() - Duration::hours(hours);
+        Entry::create_completed(
+            task_id.map(str::to_string),
+            task_title.map(str::to_string),
+            end - Duration::minutes(30),
+            end,
+            mode,
+        )
+        .unwrap()
+    }
+
     fn create_manager(_temp_dir: &TempDir) -> EntryManager {
         let event_manager = Arc::new(EventManager::new());
         unsafe {
@@ -432,14 +1206,166 @@ mod tests {
         let manager = create_manager(&temp_dir);
         let profile_id = "test_entry";
 
-        let entry = Entry::new(
-            Some("task1".to_string()),
-            Some("Task".to_string()),
-            TimerMode::Manual,
-        );
+        let entry = completed(Some("task1"), Some("Task"), TimerMode::Manual);
         let added = manager.add(profile_id, entry).await.unwrap();
 
-        assert!(added.is_active());
+        assert!(added.is_completed());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_add_rejects_impossible_entry_and_lists_stored_ones() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let profile_id = "test_invalid";
+
+        let running = Entry::new(None, None, TimerMode::Manual);
+        assert!(matches!(
+            manager.add(profile_id, running).await,
+            Err(EntryManagerError::Invalid(_))
+        ));
+
+        // Written straight to disk, as an older daemon might have done.
+        let mut zero = completed(None, None, TimerMode::Manual);
+        zero.duration_seconds = 0;
+        let valid = completed(None, None, TimerMode::Manual);
+        EntryStorage::new(manager.data_dir.clone())
+            .save_all(profile_id, &[zero.clone(), valid])
+            .unwrap();
+
+        let invalid = manager.list_invalid(profile_id).await.unwrap();
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].entry.id, zero.id);
+        assert!(invalid[0].problem.contains("Duration"));
+        assert_eq!(manager.get_all(profile_id).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_add_allows_only_a_little_clock_skew() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let profile_id = "test_skew";
+        let ending_in = |seconds: i64| {
+            let end = Utc::now() + Duration::seconds(seconds);
+            let mut entry = Entry::create_completed(
+                None,
+                None,
+                end - Duration::minutes(30),
+                end,
+                TimerMode::Manual,
+            )
+            .unwrap();
+            entry.source = EntrySource::Manual;
+            entry
+        };
+
+        manager.add(profile_id, ending_in(110)).await.unwrap();
+        let err = manager
+            .add(profile_id, ending_in(130))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("end_time"), "{}", err);
+
+        manager.set_earliest_entry_date(Utc::now().date_naive());
+        let err = manager
+            .add_with(
+                profile_id,
+                completed_hours_ago(48, None, None, TimerMode::Manual),
+                true,
+            )
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("start_time"), "{}", err);
+
+        let mut padded = completed_hours_ago(3, None, None, TimerMode::Manual);
+        padded.duration_seconds += 5;
+        manager.set_earliest_entry_date(DaemonConfig::default().earliest_entry_date);
+        let invalid = manager
+            .add(profile_id, padded)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(invalid.contains("duration_seconds"), "{}", invalid);
+    }
+
+    fn block(task_id: &str, start_offset: i64, minutes: i64) -> Entry {
+        let base = Utc::now() - Duration::hours(3);
+        let start = base + Duration::seconds(start_offset);
+        Entry::create_completed(
+            Some(task_id.to_string()),
+            None,
+            start,
+            start + Duration::minutes(minutes),
+            TimerMode::Manual,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_duplicate_thresholds() {
+        let original = block("t1", 0, 100);
+
+        // 100 minute blocks shifted by 9 and 11 minutes overlap 91% and 89%.
+        assert!(is_likely_duplicate(&original, &block("t1", 9 * 60, 100)));
+        assert!(!is_likely_duplicate(&original, &block("t1", 11 * 60, 100)));
+
+        // Short blocks barely overlap, so only the start/duration tolerance
+        // can match them.
+        assert!(is_likely_duplicate(&block("t1", 0, 2), &block("t1", 59, 2)));
+        assert!(!is_likely_duplicate(
+            &block("t1", 0, 2),
+            &block("t1", 61, 2)
+        ));
+        let mut longer = block("t1", 30, 2);
+        longer.duration_seconds += 59;
+        assert!(is_likely_duplicate(&block("t1", 0, 2), &longer));
+        longer.duration_seconds += 2;
+        assert!(!is_likely_duplicate(&block("t1", 0, 2), &longer));
+
+        assert!(!is_likely_duplicate(&original, &block("t2", 0, 100)));
+
+        let mut untasked = original.clone();
+        untasked.task_id = None;
+        assert!(!is_likely_duplicate(&untasked, &untasked.clone()));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_add_rejects_duplicates_unless_allowed() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let profile_id = "test_duplicates";
+
+        let original = manager.add(profile_id, block("t1", 0, 30)).await.unwrap();
+        match manager.add(profile_id, block("t1", 30, 30)).await {
+            Err(EntryManagerError::Duplicate { existing_id }) => {
+                assert_eq!(existing_id, original.id)
+            }
+            other => panic!("expected a duplicate error, got {:?}", other),
+        }
+
+        manager.add(profile_id, block("t2", 30, 30)).await.unwrap();
+        let copy = manager
+            .add_with(profile_id, block("t1", 30, 30), true)
+            .await
+            .unwrap();
+
+        let duplicates = manager
+            .find_duplicates(profile_id, None, None)
+            .await
+            .unwrap();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].entry.id, copy.id);
+        assert_eq!(duplicates[0].duplicate_of, original.id);
+
+        let later = manager
+            .find_duplicates(profile_id, Some(Utc::now() - Duration::hours(1)), None)
+            .await
+            .unwrap();
+        assert!(later.is_empty());
     }
 
     #[tokio::test]
@@ -449,8 +1375,8 @@ mod tests {
         let manager = create_manager(&temp_dir);
         let profile_id = "test_get_all";
 
-        let entry1 = Entry::new(None, None, TimerMode::Manual);
-        let entry2 = Entry::new(None, None, TimerMode::Pomodoro);
+        let entry1 = completed(None, None, TimerMode::Manual);
+        let entry2 = completed_hours_ago(1, None, None, TimerMode::Pomodoro);
 
         manager.add(profile_id, entry1).await.unwrap();
         manager.add(profile_id, entry2).await.unwrap();
@@ -459,6 +1385,61 @@ mod tests {
         assert!(entries.len() >= 2);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_get_by_id_follows_adds_and_deletes() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let profile_id = "test_get_by_id";
+
+        let mut added = Vec::new();
+        for h in 1..=3 {
+            let entry = completed_hours_ago(h * 2, Some("t"), None, TimerMode::Manual);
+            added.push(manager.add(profile_id, entry).await.unwrap());
+        }
+        assert_eq!(
+            manager.get(profile_id, &added[1].id).await.unwrap(),
+            added[1]
+        );
+
+        // Built once, the index has to keep up with later changes.
+        let later = completed_hours_ago(9, Some("t"), None, TimerMode::Manual);
+        let later = manager.add(profile_id, later).await.unwrap();
+        assert_eq!(manager.get(profile_id, &later.id).await.unwrap(), later);
+
+        manager.delete(profile_id, &added[0].id).await.unwrap();
+        assert!(matches!(
+            manager.get(profile_id, &added[0].id).await,
+            Err(EntryManagerError::NotFound(_))
+        ));
+        let mut edited = added[2].clone();
+        edited.description = Some("edited".to_string());
+        manager.update(profile_id, edited.clone()).await.unwrap();
+        assert_eq!(manager.get(profile_id, &added[2].id).await.unwrap(), edited);
+        assert_eq!(manager.get(profile_id, &later.id).await.unwrap(), later);
+
+        assert!(manager.get(profile_id, "missing").await.is_err());
+        assert!(manager.delete(profile_id, "missing").await.is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_last_is_latest_end_not_latest_added() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let profile_id = "test_last";
+
+        assert!(manager.last(profile_id).await.unwrap().is_none());
+
+        let recent = completed_hours_ago(1, Some("t1"), Some("Recent"), TimerMode::Manual);
+        manager.add(profile_id, recent.clone()).await.unwrap();
+        let older = completed_hours_ago(5, Some("t2"), Some("Older"), TimerMode::Manual);
+        manager.add(profile_id, older).await.unwrap();
+
+        let last = manager.last(profile_id).await.unwrap().unwrap();
+        assert_eq!(last.id, recent.id);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_filter_by_task() {
@@ -466,8 +1447,8 @@ mod tests {
         let manager = create_manager(&temp_dir);
         let profile_id = "test_filter";
 
-        let entry1 = Entry::new(Some("task1".to_string()), None, TimerMode::Manual);
-        let entry2 = Entry::new(Some("task2".to_string()), None, TimerMode::Manual);
+        let entry1 = completed(Some("task1"), None, TimerMode::Manual);
+        let entry2 = completed(Some("task2"), None, TimerMode::Manual);
 
         manager.add(profile_id, entry1).await.unwrap();
         manager.add(profile_id, entry2).await.unwrap();
@@ -480,6 +1461,9 @@ mod tests {
                     end_date: None,
                     task_id: Some("task1".to_string()),
                     tags: None,
+                    source: None,
+                    device: None,
+                    mode: None,
                 },
             )
             .await
@@ -489,6 +1473,50 @@ mod tests {
         assert_eq!(filtered[0].task_id, Some("task1".to_string()));
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_entries_are_stamped_with_device() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let profile_id = "test_device";
+
+        let mut synced = completed(None, None, TimerMode::Manual);
+        synced.device_id = Some("desktop-id".to_string());
+        synced.device_name = Some("desktop".to_string());
+        manager.add(profile_id, synced).await.unwrap();
+
+        manager.set_device(Device {
+            id: "laptop-id".to_string(),
+            name: "laptop".to_string(),
+        });
+        let added = manager
+            .add(profile_id, completed(Some("t1"), None, TimerMode::Manual))
+            .await
+            .unwrap();
+        assert_eq!(added.device_id.as_deref(), Some("laptop-id"));
+
+        manager.clear_cache().await;
+        for device in ["laptop", "laptop-id"] {
+            let filtered = manager
+                .filter(
+                    profile_id,
+                    EntryFilter {
+                        start_date: None,
+                        end_date: None,
+                        task_id: None,
+                        tags: None,
+                        source: None,
+                        device: Some(device.to_string()),
+                        mode: None,
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(filtered.len(), 1);
+            assert_eq!(filtered[0].device_name.as_deref(), Some("laptop"));
+        }
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_calculate_stats() {
@@ -498,14 +1526,244 @@ mod tests {
 
         let entry1 = Entry::create_completed(None, None, start, end1, TimerMode::Manual).unwrap();
 
-        let entry2 = Entry::create_completed(None, None, start, end2, TimerMode::Pomodoro).unwrap();
+        let mut entry2 =
+            Entry::create_completed(None, None, start, end2, TimerMode::Pomodoro).unwrap();
+        entry2.pomodoro = Some(PomodoroOutcome {
+            completed_sessions: 3,
+            completed: false,
+            interrupted_at_seconds: Some(600),
+        });
 
-        let stats = EntryManager::calculate_stats(&[entry1, entry2]);
+        let mut entry3 =
+            Entry::create_completed(None, None, start, end1, TimerMode::Manual).unwrap();
+        entry3.source = EntrySource::Manual;
 
-        assert_eq!(stats.total_entries, 2);
+        let stats = EntryManager::calculate_stats(&[entry1, entry2, entry3]);
+
+        assert_eq!(stats.total_entries, 3);
         assert_eq!(stats.pomodoro_count, 1);
-        assert_eq!(stats.manual_count, 1);
-        assert_eq!(stats.total_duration_seconds, 3600 + 7200);
+        assert_eq!(stats.manual_count, 2);
+        assert_eq!(stats.total_duration_seconds, 3600 + 7200 + 3600);
+        assert_eq!(
+            stats.by_source["timer"],
+            SourceStats {
+                entries: 2,
+                duration_seconds: 3600 + 7200
+            }
+        );
+        assert_eq!(stats.by_source["manual"].entries, 1);
+        assert_eq!(
+            stats.pomodoro,
+            PomodoroStats {
+                completed: 3,
+                abandoned: 1,
+                completion_rate: 0.75,
+                avg_interruption_seconds: Some(600),
+            }
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_hourly_stats() {
+        use chrono::TimeZone;
+
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let profile_id = "test_hourly";
+        let at = |day: u32, hour: u32, minutes: i64| {
+            let start = Local
+                .with_ymd_and_hms(2024, 5, day, hour, 15, 0)
+                .unwrap()
+                .with_timezone(&Utc);
+            Entry::create_completed(
+                None,
+                None,
+                start,
+                start + Duration::minutes(minutes),
+                TimerMode::Manual,
+            )
+            .unwrap()
+        };
+        manager
+            .add_batch(
+                profile_id,
+                vec![at(13, 9, 60), at(14, 9, 30), at(14, 15, 45), at(15, 9, 10)],
+                true,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let hours = manager.hourly_stats(profile_id, None, None).await.unwrap();
+        assert_eq!(hours.len(), 24);
+        assert_eq!(hours[9].session_count, 3);
+        assert_eq!(hours[9].avg_duration_seconds, 2000.0);
+        assert_eq!(hours[15].avg_duration_seconds, 2700.0);
+        assert_eq!(
+            hours[10],
+            HourStats {
+                hour: 10,
+                ..Default::default()
+            }
+        );
+
+        let day = NaiveDate::from_ymd_opt(2024, 5, 14);
+        let hours = manager.hourly_stats(profile_id, day, day).await.unwrap();
+        assert_eq!(hours[9].session_count, 1);
+        assert_eq!(hours[9].avg_duration_seconds, 1800.0);
+        assert_eq!(hours.iter().map(|h| h.session_count).sum::<u32>(), 2);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_source_survives_storage_and_old_files_default_to_timer() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let profile_id = "test_source";
+
+        // A file from before the source column existed.
+        let profile_dir = manager.data_dir.join("profiles").join(profile_id);
+        std::fs::create_dir_all(&profile_dir).unwrap();
+        std::fs::write(
+            profile_dir.join("entries.csv"),
+            "id,task_id,task_title,start_time,end_time,duration_seconds,mode,description,tags\n\
+             old,,,2024-01-01T09:00:00+00:00,2024-01-01T09:25:00+00:00,1500,pomodoro,,\n",
+        )
+        .unwrap();
+
+        let mut imported = completed(None, None, TimerMode::Manual);
+        imported.source = EntrySource::Import {
+            origin: "toggl".to_string(),
+        };
+        manager.add(profile_id, imported.clone()).await.unwrap();
+
+        let mut pomodoro = completed_hours_ago(3, None, None, TimerMode::Pomodoro);
+        pomodoro.pomodoro = Some(PomodoroOutcome {
+            completed_sessions: 2,
+            completed: true,
+            interrupted_at_seconds: None,
+        });
+        manager.add(profile_id, pomodoro.clone()).await.unwrap();
+
+        let reloaded = EntryManager::new(Arc::new(EventManager::new())).unwrap();
+        let entries = reloaded.get_all(profile_id).await.unwrap();
+        assert_eq!(
+            entries[0].source,
+            EntrySource::Timer {
+                timer_mode: TimerMode::Pomodoro
+            }
+        );
+        assert_eq!(entries[1].source, imported.source);
+        assert_eq!(entries[0].pomodoro, None);
+        assert_eq!(entries[2].pomodoro, pomodoro.pomodoro);
+
+        let only_imports = reloaded
+            .filter(
+                profile_id,
+                EntryFilter {
+                    start_date: None,
+                    end_date: None,
+                    task_id: None,
+                    tags: None,
+                    source: Some("import".to_string()),
+                    device: None,
+                    mode: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(only_imports.len(), 1);
+        assert_eq!(only_imports[0].id, imported.id);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_add_batch_is_all_or_nothing_unless_partial() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let profile_id = "test_batch";
+
+        let mut backwards = completed_hours_ago(5, None, None, TimerMode::Manual);
+        backwards.end_time = Some(backwards.start_time);
+        let first = completed_hours_ago(3, Some("t1"), None, TimerMode::Manual);
+        let repeat = Entry {
+            id: "repeat".to_string(),
+            ..first.clone()
+        };
+        let batch = vec![
+            first.clone(),
+            backwards,
+            repeat,
+            completed_hours_ago(1, Some("t1"), None, TimerMode::Manual),
+        ];
+
+        let err = manager
+            .add_batch(profile_id, batch.clone(), false, false)
+            .await
+            .unwrap_err();
+        let EntryManagerError::InvalidBatch(errors) = err else {
+            panic!("expected InvalidBatch, got {:?}", err);
+        };
+        assert_eq!(
+            errors.iter().map(|e| e.index).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert!(errors[1].message.contains(&first.id));
+        assert!(manager.get_all(profile_id).await.unwrap().is_empty());
+
+        let outcome = manager
+            .add_batch(profile_id, batch, false, true)
+            .await
+            .unwrap();
+        assert_eq!(outcome.created.len(), 2);
+        assert_eq!(outcome.errors.len(), 2);
+
+        let reloaded = EntryManager::new(Arc::new(EventManager::new())).unwrap();
+        assert_eq!(reloaded.get_all(profile_id).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_break_compliance_counts_breaks_mostly_taken() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let profile_id = "test_breaks";
+
+        let now = Utc::now();
+        let record = |taken_seconds, status, started_at| BreakRecord {
+            timer_id: "t".to_string(),
+            task_id: None,
+            phase: PomodoroPhase::ShortBreak,
+            started_at,
+            ended_at: now,
+            planned_seconds: 300,
+            taken_seconds,
+            status,
+        };
+        let breaks = [
+            record(300, BreakStatus::Completed, now - Duration::minutes(30)),
+            record(250, BreakStatus::Stopped, now - Duration::minutes(20)),
+            record(30, BreakStatus::Skipped, now - Duration::minutes(10)),
+            record(300, BreakStatus::Completed, now - Duration::days(40)),
+        ];
+        for b in &breaks {
+            manager.record_break(profile_id, b).await.unwrap();
+        }
+
+        let recent = manager
+            .breaks_since(profile_id, now - Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(recent, breaks[..3].to_vec());
+
+        let stats = manager
+            .stats_since(profile_id, &[], now - Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(stats.break_compliance.offered, 3);
+        assert_eq!(stats.break_compliance.taken, 2);
+        assert!((stats.break_compliance.rate - 2.0 / 3.0).abs() < 1e-9);
     }
 
     #[tokio::test]
@@ -517,21 +1775,9 @@ mod tests {
         let target_profile = "target_profile";
         let task_id = "task_to_move";
 
-        let entry1 = Entry::new(
-            Some(task_id.to_string()),
-            Some("Task".to_string()),
-            TimerMode::Manual,
-        );
-        let entry2 = Entry::new(
-            Some(task_id.to_string()),
-            Some("Task".to_string()),
-            TimerMode::Manual,
-        );
-        let entry3 = Entry::new(
-            Some("other_task".to_string()),
-            Some("Other".to_string()),
-            TimerMode::Manual,
-        );
+        let entry1 = completed(Some(task_id), Some("Task"), TimerMode::Manual);
+        let entry2 = completed_hours_ago(1, Some(task_id), Some("Task"), TimerMode::Manual);
+        let entry3 = completed(Some("other_task"), Some("Other"), TimerMode::Manual);
 
         manager.add(source_profile, entry1).await.unwrap();
         manager.add(source_profile, entry2).await.unwrap();
@@ -570,4 +1816,129 @@ mod tests {
 
         assert_eq!(moved_count, 0);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_bulk_stats_follow_entry_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let windows = [StatsWindow::Today, StatsWindow::LastDays(7)];
+
+        manager
+            .add("work", completed(Some("a"), None, TimerMode::Pomodoro))
+            .await
+            .unwrap();
+        let old = manager
+            .add(
+                "work",
+                completed_hours_ago(72, Some("b"), None, TimerMode::Manual),
+            )
+            .await
+            .unwrap();
+
+        let stats = manager.bulk_stats("work", &windows).await.unwrap();
+        let today = manager.get_today_stats("work").await.unwrap();
+        assert_eq!(stats[0].total_entries, today.total_entries);
+        assert_eq!(stats[1].total_entries, 2);
+        assert_eq!(stats[1].manual_count, 1);
+
+        manager
+            .add("work", completed(Some("c"), None, TimerMode::Manual))
+            .await
+            .unwrap();
+        let stats = manager.bulk_stats("work", &windows).await.unwrap();
+        assert_eq!(stats[1].total_entries, 3);
+        assert_eq!(stats[1].total_duration_seconds, 3 * 30 * 60);
+
+        manager.delete("work", &old.id).await.unwrap();
+        let stats = manager.bulk_stats("work", &windows).await.unwrap();
+        assert_eq!(stats[1].total_entries, 2);
+        assert_eq!(stats[1].by_source["timer"].entries, 2);
+
+        assert_eq!(
+            manager.stats_cache_counters(),
+            StatsCacheCounters { hits: 2, misses: 1 }
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_period_stats_follow_entry_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let month_total = || async {
+            let stats = manager.get_month_stats("work").await.unwrap();
+            let entries = manager.get_month("work").await.unwrap();
+            assert_eq!(
+                stats.total_duration_seconds,
+                EntryManager::calculate_stats(&entries).total_duration_seconds
+            );
+            stats.total_entries
+        };
+
+        let mut entry = manager
+            .add("work", completed(Some("a"), None, TimerMode::Manual))
+            .await
+            .unwrap();
+        assert_eq!(month_total().await, 1);
+
+        manager
+            .add("work", completed(Some("b"), None, TimerMode::Pomodoro))
+            .await
+            .unwrap();
+        assert_eq!(month_total().await, 2);
+
+        entry.end_time = Some(entry.start_time + Duration::minutes(50));
+        entry.duration_seconds = 50 * 60;
+        manager.update("work", entry.clone()).await.unwrap();
+        assert_eq!(month_total().await, 2);
+        assert_eq!(
+            manager
+                .get_today_stats("work")
+                .await
+                .unwrap()
+                .total_duration_seconds,
+            manager
+                .get_today("work")
+                .await
+                .unwrap()
+                .iter()
+                .map(|e| e.duration_seconds)
+                .sum::<u64>()
+        );
+
+        manager.delete("work", &entry.id).await.unwrap();
+        assert_eq!(month_total().await, 1);
+
+        let now = Utc::now();
+        let skipped = BreakRecord {
+            timer_id: "t".to_string(),
+            task_id: None,
+            phase: PomodoroPhase::ShortBreak,
+            started_at: now,
+            ended_at: now,
+            planned_seconds: 300,
+            taken_seconds: 0,
+            status: BreakStatus::Skipped,
+        };
+        assert_eq!(
+            manager
+                .get_week_stats("work")
+                .await
+                .unwrap()
+                .break_compliance
+                .offered,
+            0
+        );
+        manager.record_break("work", &skipped).await.unwrap();
+        assert_eq!(
+            manager
+                .get_week_stats("work")
+                .await
+                .unwrap()
+                .break_compliance
+                .offered,
+            1
+        );
+    }
 }
@@ -1,3 +1,11 @@
+pub mod daily;
 pub mod manager;
+pub mod stats_cache;
 
-pub use manager::{EntryFilter, EntryManager, EntryStats};
+pub use daily::{DailyStats, StatsWindow};
+pub use manager::{
+    BatchError, BatchOutcome, BreakCompliance, DuplicateEntry, EntryFilter, EntryManager,
+    EntryManagerError, EntryStats, HourStats, InvalidEntry, PomodoroStats, SourceStats,
+    StatsCacheCounters,
+};
+pub use stats_cache::StatsCache;
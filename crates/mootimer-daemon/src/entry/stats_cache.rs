@@ -0,0 +1,120 @@
+//! The last answers to `entry.stats_today`, `stats_week` and `stats_month`
+//! per profile, so clients polling them don't cost a pass over every entry.
+//!
+//! Each bucket remembers the first day of the period it was worked out for
+//! and is stale once the period rolls over. The periods run up to now with
+//! no end, so a change on some day can only affect buckets that started on
+//! or before it; those are dropped and the others kept.
+
+use chrono::NaiveDate;
+
+use super::daily::StatsWindow;
+use super::manager::EntryStats;
+use mootimer_core::models::Entry;
+
+#[derive(Debug, Clone, Default)]
+pub struct StatsCache {
+    today: Option<(EntryStats, NaiveDate)>,
+    week: Option<(EntryStats, NaiveDate)>,
+    month: Option<(EntryStats, NaiveDate)>,
+}
+
+impl StatsCache {
+    /// The bucket for `window`; only today, week and month are cached.
+    fn bucket(&mut self, window: StatsWindow) -> Option<&mut Option<(EntryStats, NaiveDate)>> {
+        match window {
+            StatsWindow::Today => Some(&mut self.today),
+            StatsWindow::Week => Some(&mut self.week),
+            StatsWindow::Month => Some(&mut self.month),
+            StatsWindow::LastDays(_) | StatsWindow::Range { .. } => None,
+        }
+    }
+
+    /// The stats stored for `window`, if they are for the period `today`
+    /// falls in.
+    pub fn get(&self, window: StatsWindow, today: NaiveDate) -> Option<&EntryStats> {
+        let bucket = match window {
+            StatsWindow::Today => &self.today,
+            StatsWindow::Week => &self.week,
+            StatsWindow::Month => &self.month,
+            StatsWindow::LastDays(_) | StatsWindow::Range { .. } => return None,
+        };
+        let (from, _) = window.bounds(today);
+        bucket
+            .as_ref()
+            .filter(|(_, start)| *start == from)
+            .map(|(stats, _)| stats)
+    }
+
+    pub fn put(&mut self, window: StatsWindow, today: NaiveDate, stats: EntryStats) {
+        let (from, _) = window.bounds(today);
+        if let Some(bucket) = self.bucket(window) {
+            *bucket = Some((stats, from));
+        }
+    }
+
+    /// Drops the buckets whose period includes `day`.
+    pub fn invalidate(&mut self, day: NaiveDate) {
+        for bucket in [&mut self.today, &mut self.week, &mut self.month] {
+            if bucket.as_ref().is_some_and(|(_, start)| *start <= day) {
+                *bucket = None;
+            }
+        }
+    }
+
+    /// Drops the buckets any of `entries` counts towards.
+    pub fn invalidate_entries<'a>(&mut self, entries: impl IntoIterator<Item = &'a Entry>) {
+        if let Some(day) = entries.into_iter().map(|e| e.start_time.date_naive()).min() {
+            self.invalidate(day);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::EntryManager;
+
+    fn stats(total_entries: usize) -> EntryStats {
+        EntryStats {
+            total_entries,
+            ..EntryManager::calculate_stats(&[])
+        }
+    }
+
+    fn cached(cache: &StatsCache, window: StatsWindow, today: NaiveDate) -> Option<usize> {
+        cache.get(window, today).map(|s| s.total_entries)
+    }
+
+    #[test]
+    fn test_invalidate_drops_periods_containing_day() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 5, d).unwrap();
+        let today = day(16); // a Thursday; the week began on the 13th
+        let mut cache = StatsCache::default();
+        cache.put(StatsWindow::Today, today, stats(1));
+        cache.put(StatsWindow::Week, today, stats(2));
+        cache.put(StatsWindow::Month, today, stats(3));
+        cache.put(StatsWindow::LastDays(7), today, stats(4));
+        assert_eq!(cached(&cache, StatsWindow::LastDays(7), today), None);
+
+        cache.invalidate(day(14));
+        assert_eq!(cached(&cache, StatsWindow::Today, today), Some(1));
+        assert_eq!(cached(&cache, StatsWindow::Week, today), None);
+        assert_eq!(cached(&cache, StatsWindow::Month, today), None);
+
+        cache.invalidate(day(16));
+        assert_eq!(cached(&cache, StatsWindow::Today, today), None);
+    }
+
+    #[test]
+    fn test_buckets_expire_with_their_period() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 5, d).unwrap();
+        let mut cache = StatsCache::default();
+        cache.put(StatsWindow::Today, day(16), stats(1));
+        cache.put(StatsWindow::Week, day(16), stats(2));
+
+        assert_eq!(cached(&cache, StatsWindow::Today, day(17)), None);
+        assert_eq!(cached(&cache, StatsWindow::Week, day(19)), Some(2));
+        assert_eq!(cached(&cache, StatsWindow::Week, day(20)), None);
+    }
+}
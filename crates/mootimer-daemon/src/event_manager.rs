@@ -1,6 +1,8 @@
 use tokio::sync::broadcast;
 
-use crate::events::{DaemonEvent, EntryEvent, ProfileEvent, TaskEvent};
+use crate::events::{
+    BudgetEvent, ConfigEvent, DaemonEvent, EntryEvent, ProfileEvent, SyncEvent, TaskEvent,
+};
 use crate::timer::TimerEvent;
 
 pub struct EventManager {
@@ -42,6 +44,18 @@ impl EventManager {
     pub fn emit_profile(&self, event: ProfileEvent) {
         let _ = self.event_tx.send(DaemonEvent::Profile(event));
     }
+
+    pub fn emit_config(&self, event: ConfigEvent) {
+        let _ = self.event_tx.send(DaemonEvent::Config(event));
+    }
+
+    pub fn emit_sync(&self, event: SyncEvent) {
+        let _ = self.event_tx.send(DaemonEvent::Sync(event));
+    }
+
+    pub fn emit_budget(&self, event: BudgetEvent) {
+        let _ = self.event_tx.send(DaemonEvent::Budget(event));
+    }
 }
 
 impl Default for EventManager {
@@ -55,7 +69,7 @@ mod tests {
     use super::*;
     use crate::events::TaskEvent;
     use chrono::Utc;
-    use mootimer_core::models::{Task, TaskSource, TaskStatus};
+    use mootimer_core::models::{Task, TaskPriority, TaskSource, TaskStatus};
 
     #[test]
     fn test_event_manager_creation() {
@@ -72,8 +86,17 @@ mod tests {
             id: "task1".to_string(),
             title: "Test".to_string(),
             description: None,
+            notes: None,
             status: TaskStatus::Todo,
             tags: vec![],
+            priority: TaskPriority::None,
+            due_date: None,
+            parent_task_id: None,
+            pinned: false,
+            last_used_at: None,
+            max_session_seconds: None,
+            blocked_by: vec![],
+            order: 0,
             url: None,
             source: TaskSource::Manual,
             source_id: None,
@@ -98,8 +121,17 @@ mod tests {
             id: "task1".to_string(),
             title: "Test".to_string(),
             description: None,
+            notes: None,
             status: TaskStatus::Todo,
             tags: vec![],
+            priority: TaskPriority::None,
+            due_date: None,
+            parent_task_id: None,
+            pinned: false,
+            last_used_at: None,
+            max_session_seconds: None,
+            blocked_by: vec![],
+            order: 0,
             url: None,
             source: TaskSource::Manual,
             source_id: None,
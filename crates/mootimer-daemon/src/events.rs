@@ -1,9 +1,15 @@
 use chrono::{DateTime, Utc};
-use mootimer_core::models::{Entry, Profile, Task};
+use mootimer_core::models::{Budget, Config, Entry, Profile, Task, TaskStatus};
 use serde::{Deserialize, Serialize};
 
 use crate::timer::TimerEvent;
 
+/// Event categories clients can pass to `events.subscribe`. Events in a
+/// category are delivered as `<category>.event` notifications.
+pub const EVENT_CATEGORIES: &[&str] = &[
+    "timer", "task", "entry", "profile", "config", "sync", "budget",
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "category", rename_all = "snake_case")]
 pub enum DaemonEvent {
@@ -11,6 +17,23 @@ pub enum DaemonEvent {
     Task(TaskEvent),
     Entry(EntryEvent),
     Profile(ProfileEvent),
+    Config(ConfigEvent),
+    Sync(SyncEvent),
+    Budget(BudgetEvent),
+}
+
+impl DaemonEvent {
+    pub fn category(&self) -> &'static str {
+        match self {
+            DaemonEvent::Timer(_) => "timer",
+            DaemonEvent::Task(_) => "task",
+            DaemonEvent::Entry(_) => "entry",
+            DaemonEvent::Profile(_) => "profile",
+            DaemonEvent::Config(_) => "config",
+            DaemonEvent::Sync(_) => "sync",
+            DaemonEvent::Budget(_) => "budget",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +42,9 @@ pub struct TaskEvent {
     pub profile_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub task: Option<Task>,
+    /// The status before an `updated` event that changed it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_status: Option<TaskStatus>,
     pub timestamp: DateTime<Utc>,
 }
 
@@ -35,6 +61,15 @@ pub enum TaskEventType {
         target_profile_id: String,
         entries_moved: usize,
     },
+    /// Blockers were completed or deleted and dropped from the task's
+    /// `blocked_by`; it may still have others.
+    Unblocked {
+        blocker_ids: Vec<String>,
+    },
+    /// One batch operation changed several tasks at once.
+    BulkUpdated {
+        task_ids: Vec<String>,
+    },
 }
 
 impl TaskEvent {
@@ -43,6 +78,7 @@ impl TaskEvent {
             event_type: TaskEventType::Created,
             profile_id,
             task: Some(task),
+            previous_status: None,
             timestamp: Utc::now(),
         }
     }
@@ -52,15 +88,46 @@ impl TaskEvent {
             event_type: TaskEventType::Updated,
             profile_id,
             task: Some(task),
+            previous_status: None,
             timestamp: Utc::now(),
         }
     }
 
+    /// An `updated` event for a change that may have moved `task` out of
+    /// `previous` status.
+    pub fn updated_from(profile_id: String, task: Task, previous: TaskStatus) -> Self {
+        Self {
+            previous_status: (task.status != previous).then_some(previous),
+            ..Self::updated(profile_id, task)
+        }
+    }
+
     pub fn deleted(profile_id: String, task_id: String) -> Self {
         Self {
             event_type: TaskEventType::Deleted { task_id },
             profile_id,
             task: None,
+            previous_status: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn bulk_updated(profile_id: String, task_ids: Vec<String>) -> Self {
+        Self {
+            event_type: TaskEventType::BulkUpdated { task_ids },
+            profile_id,
+            task: None,
+            previous_status: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn unblocked(profile_id: String, task: Task, blocker_ids: Vec<String>) -> Self {
+        Self {
+            event_type: TaskEventType::Unblocked { blocker_ids },
+            profile_id,
+            task: Some(task),
+            previous_status: None,
             timestamp: Utc::now(),
         }
     }
@@ -79,6 +146,7 @@ impl TaskEvent {
             },
             profile_id: target_profile_id,
             task: Some(task),
+            previous_status: None,
             timestamp: Utc::now(),
         }
     }
@@ -172,10 +240,162 @@ impl ProfileEvent {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigEvent {
+    pub event_type: ConfigEventType,
+    pub config: Config,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConfigEventType {
+    /// Changed through an RPC.
+    Updated,
+    /// Picked up an edit made to the config file outside the daemon.
+    Reloaded,
+    /// An RPC update overwrote an external edit the daemon hadn't loaded yet.
+    Overwritten { message: String },
+}
+
+impl ConfigEvent {
+    pub fn updated(config: Config) -> Self {
+        Self {
+            event_type: ConfigEventType::Updated,
+            config,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn reloaded(config: Config) -> Self {
+        Self {
+            event_type: ConfigEventType::Reloaded,
+            config,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn overwritten(config: Config, message: String) -> Self {
+        Self {
+            event_type: ConfigEventType::Overwritten { message },
+            config,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncEvent {
+    pub event_type: SyncEventType,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncEventType {
+    /// A pull left records that both sides changed; `count` is how many
+    /// are now waiting for `sync.resolve_conflict`.
+    Conflicts {
+        count: usize,
+    },
+    /// A `sync.sync` moved on to another step.
+    Stage {
+        stage: SyncStage,
+    },
+    Finished {
+        pulled: bool,
+        pushed: bool,
+    },
+    /// A `sync.sync` gave up; `error` is what git said.
+    Failed {
+        error: String,
+    },
+}
+
+/// The steps of a `sync.sync`, in order. `stashing` and `restoring` only
+/// happen when local changes are in the way of a pull.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStage {
+    Pulling,
+    Stashing,
+    Restoring,
+    Pushing,
+}
+
+impl SyncEvent {
+    pub fn conflicts(count: usize) -> Self {
+        Self {
+            event_type: SyncEventType::Conflicts { count },
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn stage(stage: SyncStage) -> Self {
+        Self {
+            event_type: SyncEventType::Stage { stage },
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn finished(pulled: bool, pushed: bool) -> Self {
+        Self {
+            event_type: SyncEventType::Finished { pulled, pushed },
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn failed(error: String) -> Self {
+        Self {
+            event_type: SyncEventType::Failed { error },
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetEvent {
+    pub event_type: BudgetEventType,
+    pub profile_id: String,
+    pub budget: Budget,
+    pub used_seconds: u64,
+    /// What the budget covers and when, e.g. "tag 'meetings' this week".
+    pub description: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BudgetEventType {
+    /// 80% of the budget is used.
+    Warning,
+    /// All of it is used.
+    Exceeded,
+}
+
+impl BudgetEvent {
+    pub fn new(
+        event_type: BudgetEventType,
+        profile_id: String,
+        budget: Budget,
+        used_seconds: u64,
+        description: String,
+    ) -> Self {
+        Self {
+            event_type,
+            profile_id,
+            budget,
+            used_seconds,
+            description,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mootimer_core::models::{TaskSource, TaskStatus, TimerMode};
+    use mootimer_core::models::{EntrySource, TaskPriority, TaskSource, TaskStatus, TimerMode};
 
     #[test]
     fn test_task_event_serialization() {
@@ -183,8 +403,17 @@ mod tests {
             id: "task1".to_string(),
             title: "Test Task".to_string(),
             description: None,
+            notes: None,
             status: TaskStatus::Todo,
             tags: vec![],
+            priority: TaskPriority::None,
+            due_date: None,
+            parent_task_id: None,
+            pinned: false,
+            last_used_at: None,
+            max_session_seconds: None,
+            blocked_by: vec![],
+            order: 0,
             url: None,
             source: TaskSource::Manual,
             source_id: None,
@@ -212,6 +441,12 @@ mod tests {
             task_title: None,
             description: None,
             tags: vec![],
+            source: EntrySource::Manual,
+            pomodoro: None,
+            device_id: None,
+            device_name: None,
+            clock_anomaly: false,
+            truncated_seconds: 0,
         };
 
         let event = EntryEvent::added("profile1".to_string(), entry);
@@ -229,6 +464,9 @@ mod tests {
             name: "Test Profile".to_string(),
             description: None,
             color: None,
+            icon: None,
+            working_hours: Default::default(),
+            auto_start_next_session: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -1,6 +1,7 @@
 pub mod connection;
 pub mod protocol;
 pub mod server;
+pub mod subscriptions;
 
 pub use connection::Connection;
 pub use protocol::{JsonRpcError, Notification, Request, Response};
@@ -1,12 +1,61 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+pub const PARSE_ERROR: i32 = -32700;
+pub const INVALID_REQUEST: i32 = -32600;
+pub const METHOD_NOT_FOUND: i32 = -32601;
+pub const INVALID_PARAMS: i32 = -32602;
+pub const INTERNAL_ERROR: i32 = -32603;
+
+// Codes for a method failing, one per kind of failure so clients can tell
+// them apart without reading the message.
+pub const NOT_FOUND: i32 = -32001;
+pub const CONFLICT: i32 = -32002;
+pub const FORBIDDEN: i32 = -32003;
+pub const STORAGE_ERROR: i32 = -32004;
+pub const NOT_CONFIGURED: i32 = -32005;
+pub const SYNC_FAILED: i32 = -32006;
+
+/// Every error code with what it means, for the protocol reference.
+pub const ERROR_CODES: &[(i32, &str)] = &[
+    (PARSE_ERROR, "The request is not valid JSON"),
+    (INVALID_REQUEST, "The request is not a JSON-RPC 2.0 request"),
+    (METHOD_NOT_FOUND, "No such method"),
+    (
+        INVALID_PARAMS,
+        "Params are missing, malformed or fail validation",
+    ),
+    (INTERNAL_ERROR, "The daemon failed in an unexpected way"),
+    (
+        NOT_FOUND,
+        "The profile, task, entry, timer or budget does not exist",
+    ),
+    (
+        CONFLICT,
+        "The change clashes with the current state, e.g. a duplicate entry or a running timer",
+    ),
+    (FORBIDDEN, "The method is disabled by config"),
+    (
+        STORAGE_ERROR,
+        "Reading or writing the data directory failed",
+    ),
+    (NOT_CONFIGURED, "Sync is not set up"),
+    (SYNC_FAILED, "A git operation during sync failed"),
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Request {
     pub jsonrpc: String,
     pub method: String,
     pub params: Option<Value>,
     pub id: RequestId,
+    /// Encodings the client can read responses in; only `"zstd"` is known.
+    #[serde(
+        rename = "x-accept-encoding",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub accept_encoding: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,7 +94,7 @@ pub struct JsonRpcError {
 impl JsonRpcError {
     pub fn parse_error(message: impl Into<String>) -> Self {
         Self {
-            code: -32700,
+            code: PARSE_ERROR,
             message: message.into(),
             data: None,
         }
@@ -53,7 +102,7 @@ impl JsonRpcError {
 
     pub fn invalid_request(message: impl Into<String>) -> Self {
         Self {
-            code: -32600,
+            code: INVALID_REQUEST,
             message: message.into(),
             data: None,
         }
@@ -61,7 +110,7 @@ impl JsonRpcError {
 
     pub fn method_not_found(method: &str) -> Self {
         Self {
-            code: -32601,
+            code: METHOD_NOT_FOUND,
             message: format!("Method not found: {}", method),
             data: None,
         }
@@ -69,7 +118,7 @@ impl JsonRpcError {
 
     pub fn invalid_params(message: impl Into<String>) -> Self {
         Self {
-            code: -32602,
+            code: INVALID_PARAMS,
             message: message.into(),
             data: None,
         }
@@ -77,7 +126,7 @@ impl JsonRpcError {
 
     pub fn internal_error(message: impl Into<String>) -> Self {
         Self {
-            code: -32603,
+            code: INTERNAL_ERROR,
             message: message.into(),
             data: None,
         }
@@ -99,6 +148,7 @@ impl Request {
             method,
             params,
             id,
+            accept_encoding: None,
         }
     }
 
@@ -1,11 +1,19 @@
+use serde_json::json;
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 use tokio::net::{UnixListener, UnixStream};
-use tokio::sync::mpsc;
+use tokio::sync::{RwLock, mpsc};
+use tracing::Instrument;
+use uuid::Uuid;
 
 use super::connection::ConnectionError;
 use super::protocol::{JsonRpcError, Notification, Request, Response};
+use super::subscriptions::{StatusWatchParams, SubscriptionParams, Subscriptions};
 use crate::api::ApiHandler;
+use crate::api::usage::HelloParams;
 
 #[derive(Debug, thiserror::Error)]
 pub enum IpcServerError {
@@ -24,9 +32,25 @@ pub enum IpcServerError {
 
 pub type Result<T> = std::result::Result<T, IpcServerError>;
 
+type ConnectionId = u64;
+
+/// How a connection gets large responses; see `mootimer_client::compression`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseEncoding {
+    Plain,
+    /// Binary frames, asked for with `x-accept-encoding`.
+    Frames,
+    /// JSON envelopes, agreed in `system.hello`.
+    Envelopes,
+}
+
 pub struct IpcServer {
     socket_path: String,
     api_handler: Arc<ApiHandler>,
+    /// Event categories each open connection has subscribed to. Entries are
+    /// added on connect and removed on disconnect.
+    subscriptions: Arc<RwLock<HashMap<ConnectionId, Subscriptions>>>,
+    next_connection_id: AtomicU64,
 }
 
 impl IpcServer {
@@ -34,6 +58,8 @@ impl IpcServer {
         Self {
             socket_path,
             api_handler,
+            subscriptions: Arc::new(RwLock::new(HashMap::new())),
+            next_connection_id: AtomicU64::new(1),
         }
     }
 
@@ -64,41 +90,77 @@ impl IpcServer {
     }
 
     async fn handle_connection(&self, stream: UnixStream) -> Result<()> {
-        tracing::debug!("New client connected");
+        let connection_id = self.next_connection_id.fetch_add(1, Ordering::Relaxed);
+        tracing::debug!("New client connected ({})", connection_id);
+        self.subscriptions
+            .write()
+            .await
+            .insert(connection_id, Subscriptions::default());
+
+        let result = self.serve_connection(connection_id, stream).await;
 
+        self.subscriptions.write().await.remove(&connection_id);
+        result
+    }
+
+    async fn serve_connection(
+        &self,
+        connection_id: ConnectionId,
+        stream: UnixStream,
+    ) -> Result<()> {
         let (read_half, write_half) = tokio::io::split(stream);
         let mut reader = tokio::io::BufReader::new(read_half);
         let mut writer = tokio::io::BufWriter::new(write_half);
+        // Set once the client asks for compressed responses.
+        let mut encoding = ResponseEncoding::Plain;
+        // Set from the `client` param of `system.hello`, for usage counts.
+        let mut client_name: Option<String> = None;
 
         let mut event_rx = self.api_handler.subscribe_events();
+        let subscriptions = self.subscriptions.clone();
 
         let (notif_tx, mut notif_rx) = mpsc::channel::<Notification>(100);
-        tokio::spawn(async move {
+        let forwarder = tokio::spawn(async move {
             tracing::info!("IPC: Event forwarder task started");
             while let Ok(event) = event_rx.recv().await {
                 use crate::events::DaemonEvent;
-                let (method, params) = match &event {
-                    DaemonEvent::Timer(e) => {
-                        tracing::debug!("IPC: Forwarding timer event");
-                        ("timer.event", serde_json::to_value(e))
-                    }
-                    DaemonEvent::Task(e) => {
-                        tracing::info!("IPC: Forwarding task event to client");
-                        ("task.event", serde_json::to_value(e))
-                    }
-                    DaemonEvent::Entry(e) => {
-                        tracing::debug!("IPC: Forwarding entry event");
-                        ("entry.event", serde_json::to_value(e))
-                    }
-                    DaemonEvent::Profile(e) => {
-                        tracing::debug!("IPC: Forwarding profile event");
-                        ("profile.event", serde_json::to_value(e))
+                let category = event.category();
+                let (admitted, reached) = match subscriptions.write().await.get_mut(&connection_id)
+                {
+                    Some(s) => (
+                        s.admits(&event, Instant::now()),
+                        s.take_status_watch(&event).is_some(),
+                    ),
+                    None => (false, false),
+                };
+                if reached && let DaemonEvent::Task(task_event) = &event {
+                    let notification = Notification {
+                        jsonrpc: "2.0".to_string(),
+                        method: "task.status_reached".to_string(),
+                        params: serde_json::to_value(task_event).unwrap_or(serde_json::Value::Null),
+                    };
+                    if notif_tx.send(notification).await.is_err() {
+                        break;
                     }
+                }
+                if !admitted {
+                    continue;
+                }
+
+                let params = match &event {
+                    DaemonEvent::Timer(e) => serde_json::to_value(e),
+                    DaemonEvent::Task(e) => serde_json::to_value(e),
+                    DaemonEvent::Entry(e) => serde_json::to_value(e),
+                    DaemonEvent::Profile(e) => serde_json::to_value(e),
+                    DaemonEvent::Config(e) => serde_json::to_value(e),
+                    DaemonEvent::Sync(e) => serde_json::to_value(e),
+                    DaemonEvent::Budget(e) => serde_json::to_value(e),
                 };
+                tracing::debug!("IPC: Forwarding {} event", category);
 
                 let notification = Notification {
                     jsonrpc: "2.0".to_string(),
-                    method: method.to_string(),
+                    method: format!("{}.event", category),
                     params: params.unwrap_or(serde_json::Value::Null),
                 };
 
@@ -115,9 +177,32 @@ impl IpcServer {
                 result = Self::read_request_from(&mut reader) => {
                     match result {
                         Ok(request) => {
-                            tracing::info!("handler: received request: {}", request.method);
-                            let response = self.handle_request(request).await;
-                            if let Err(e) = Self::write_response_to(&mut writer, &response).await {
+                            if let Some(accepted) = Self::accepted_encoding(&request)
+                                && accepted != encoding
+                            {
+                                tracing::debug!("Compressing large responses for connection {} as {:?}", connection_id, accepted);
+                                encoding = accepted;
+                            }
+                            if let Some(name) = Self::hello_client_name(&request) {
+                                client_name = Some(name);
+                            }
+                            let span = tracing::info_span!(
+                                "request",
+                                request_id = tracing::field::Empty,
+                                connection = connection_id,
+                                method = %request.method,
+                            );
+                            let served = self
+                                .serve_request(
+                                    &mut writer,
+                                    connection_id,
+                                    request,
+                                    client_name.as_deref(),
+                                    encoding,
+                                )
+                                .instrument(span)
+                                .await;
+                            if let Err(e) = served {
                                 tracing::error!("Failed to write response: {}", e);
                                 break;
                             }
@@ -143,6 +228,7 @@ impl IpcServer {
             }
         }
 
+        forwarder.abort();
         Ok(())
     }
 
@@ -162,13 +248,76 @@ impl IpcServer {
         Ok(request)
     }
 
+    /// The name a client gives for itself in `system.hello`.
+    fn hello_client_name(request: &Request) -> Option<String> {
+        if request.method != "system.hello" {
+            return None;
+        }
+        let params: HelloParams = serde_json::from_value(request.params.clone()?).ok()?;
+        let name = params.client?.trim().to_string();
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// The compression `request` asks for, if any this build can give.
+    fn accepted_encoding(request: &Request) -> Option<ResponseEncoding> {
+        if !cfg!(feature = "compression") {
+            return None;
+        }
+        if request.method == "system.hello" {
+            let params: HelloParams = serde_json::from_value(request.params.clone()?).ok()?;
+            return params
+                .accept_encoding
+                .iter()
+                .any(|e| e == "zstd")
+                .then_some(ResponseEncoding::Envelopes);
+        }
+        (request.accept_encoding.as_deref() == Some("zstd")).then_some(ResponseEncoding::Frames)
+    }
+
+    /// Adds `compression` to a `system.hello` result, so the client knows
+    /// envelopes are coming.
+    #[cfg_attr(not(feature = "compression"), allow(unused_variables))]
+    fn confirm_compression(response: &mut Response) {
+        #[cfg(feature = "compression")]
+        if let Some(serde_json::Value::Object(result)) = response.result.as_mut() {
+            result.insert(
+                mootimer_client::compression::HELLO_COMPRESSION.to_string(),
+                mootimer_client::compression::hello_compression(),
+            );
+        }
+    }
+
+    /// Writes `response` as a JSON line, or compressed when the client
+    /// accepts it and the response is large enough to be worth it.
+    #[cfg_attr(not(feature = "compression"), allow(unused_variables))]
     async fn write_response_to(
         writer: &mut tokio::io::BufWriter<tokio::io::WriteHalf<UnixStream>>,
         response: &Response,
+        encoding: ResponseEncoding,
     ) -> Result<()> {
         use tokio::io::AsyncWriteExt;
 
         let json = serde_json::to_string(response)?;
+        #[cfg(feature = "compression")]
+        if json.len() >= mootimer_client::compression::COMPRESSION_THRESHOLD {
+            use mootimer_client::compression::{encode_envelope, encode_frame};
+            match encoding {
+                ResponseEncoding::Plain => {}
+                ResponseEncoding::Frames => {
+                    writer.write_all(&encode_frame(json.as_bytes())?).await?;
+                    writer.flush().await?;
+                    return Ok(());
+                }
+                ResponseEncoding::Envelopes => {
+                    writer
+                        .write_all(encode_envelope(json.as_bytes())?.as_bytes())
+                        .await?;
+                    writer.write_all(b"\n").await?;
+                    writer.flush().await?;
+                    return Ok(());
+                }
+            }
+        }
         writer.write_all(json.as_bytes()).await?;
         writer.write_all(b"\n").await?;
         writer.flush().await?;
@@ -188,11 +337,70 @@ impl IpcServer {
         Ok(())
     }
 
-    async fn handle_request(&self, request: Request) -> Response {
+    /// Answers one request inside its `request` span, which gets a fresh
+    /// `request_id` so every line logged on the way can be found by it.
+    /// Ends with one line giving the params size, time taken and outcome.
+    async fn serve_request(
+        &self,
+        writer: &mut tokio::io::BufWriter<tokio::io::WriteHalf<UnixStream>>,
+        connection_id: ConnectionId,
+        request: Request,
+        client_name: Option<&str>,
+        encoding: ResponseEncoding,
+    ) -> Result<()> {
+        tracing::Span::current().record("request_id", tracing::field::display(Uuid::new_v4()));
+        let started = Instant::now();
+        let params_bytes = request.params.as_ref().map_or(0, |p| p.to_string().len());
+        tracing::debug!(params_bytes, "Request received");
+
+        let confirm_compression =
+            request.method == "system.hello" && encoding == ResponseEncoding::Envelopes;
+        let mut response = self
+            .handle_request(connection_id, request, client_name)
+            .await;
+        if confirm_compression {
+            Self::confirm_compression(&mut response);
+        }
+        let written = Self::write_response_to(writer, &response, encoding).await;
+
+        let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+        match &response.error {
+            None => tracing::info!(params_bytes, elapsed_ms, outcome = "ok", "Request handled"),
+            Some(error) => tracing::info!(
+                params_bytes,
+                elapsed_ms,
+                outcome = "error",
+                code = error.code,
+                "Request handled"
+            ),
+        }
+        written
+    }
+
+    async fn handle_request(
+        &self,
+        connection_id: ConnectionId,
+        request: Request,
+        client_name: Option<&str>,
+    ) -> Response {
         if let Err(error) = request.validate() {
             return Response::error(error, request.id);
         }
+        self.api_handler
+            .record_usage(&request.method, client_name)
+            .await;
+
+        if matches!(
+            request.method.as_str(),
+            "events.subscribe" | "events.unsubscribe"
+        ) {
+            return self.handle_subscription(connection_id, request).await;
+        }
+        if request.method == "task.watch_status" {
+            return self.handle_status_watch(connection_id, request).await;
+        }
 
+        let digest = request.params.as_ref().map(params_digest);
         match self
             .api_handler
             .handle(&request.method, request.params)
@@ -200,13 +408,140 @@ impl IpcServer {
         {
             Ok(result) => Response::success(result, request.id),
             Err(error) => {
-                let json_rpc_error = JsonRpcError::application_error(-32000, error.to_string());
+                tracing::warn!(
+                    method = %request.method,
+                    params = digest.as_deref().unwrap_or("none"),
+                    code = error.code(),
+                    "Request failed: {}",
+                    error_chain(&error)
+                );
+                let json_rpc_error =
+                    JsonRpcError::application_error(error.code(), error.to_string());
                 Response::error(json_rpc_error, request.id)
             }
         }
     }
 }
 
+impl IpcServer {
+    /// `events.subscribe` and `events.unsubscribe` change state of the
+    /// connection itself, so they are answered here rather than by
+    /// `ApiHandler`.
+    async fn handle_subscription(&self, connection_id: ConnectionId, request: Request) -> Response {
+        let params: SubscriptionParams = match request.params {
+            Some(params) => match serde_json::from_value(params) {
+                Ok(params) => params,
+                Err(e) => {
+                    return Response::error(
+                        JsonRpcError::invalid_params(e.to_string()),
+                        request.id,
+                    );
+                }
+            },
+            None => SubscriptionParams::default(),
+        };
+
+        let mut all = self.subscriptions.write().await;
+        let subscriptions = all.entry(connection_id).or_default();
+        let result = match (request.method.as_str(), params.categories) {
+            ("events.subscribe", Some(categories)) => {
+                let result = subscriptions.subscribe(&categories);
+                if result.is_ok()
+                    && let Some(seconds) = params.tick_interval_seconds
+                {
+                    subscriptions.set_tick_interval(seconds);
+                }
+                result
+            }
+            ("events.subscribe", None) => Err("Missing categories".to_string()),
+            (_, Some(categories)) => subscriptions.unsubscribe(&categories),
+            (_, None) => {
+                subscriptions.clear();
+                Ok(())
+            }
+        };
+
+        match result {
+            Ok(()) => Response::success(
+                json!({
+                    "categories": subscriptions.categories(),
+                    "tick_interval_seconds": subscriptions.tick_interval_seconds(),
+                }),
+                request.id,
+            ),
+            Err(message) => Response::error(JsonRpcError::invalid_params(message), request.id),
+        }
+    }
+}
+
+impl IpcServer {
+    /// `task.watch_status` is kept with the connection's subscriptions, so
+    /// the watch ends with the connection.
+    async fn handle_status_watch(&self, connection_id: ConnectionId, request: Request) -> Response {
+        let params: StatusWatchParams = match request
+            .params
+            .ok_or_else(|| "Missing params".to_string())
+            .and_then(|p| serde_json::from_value(p).map_err(|e| e.to_string()))
+        {
+            Ok(params) => params,
+            Err(message) => {
+                return Response::error(JsonRpcError::invalid_params(message), request.id);
+            }
+        };
+
+        self.subscriptions
+            .write()
+            .await
+            .entry(connection_id)
+            .or_default()
+            .watch_status(params.task_id.clone(), params.target_status);
+        Response::success(
+            json!({
+                "task_id": params.task_id,
+                "target_status": params.target_status,
+            }),
+            request.id,
+        )
+    }
+}
+
+/// Names the params without logging their values, which may hold entry
+/// descriptions or tokens: the top-level keys and a hash of the whole.
+fn params_digest(params: &serde_json::Value) -> String {
+    let keys = match params.as_object() {
+        Some(object) => object
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(","),
+        None => String::new(),
+    };
+    // FNV-1a, so the same params hash alike across daemon restarts.
+    let hash = params
+        .to_string()
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    format!("{{{}}}#{:08x}", keys, hash as u32)
+}
+
+/// `error` and each of its sources, outermost first, skipping a source
+/// whose text its wrapper already repeats.
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut chain = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        let text = cause.to_string();
+        if !chain.ends_with(&text) {
+            chain.push_str(": ");
+            chain.push_str(&text);
+        }
+        source = cause.source();
+    }
+    chain
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,7 +565,8 @@ mod tests {
         let task_manager = Arc::new(crate::task::TaskManager::new(event_manager.clone()).unwrap());
         let entry_manager =
             Arc::new(crate::entry::EntryManager::new(event_manager.clone()).unwrap());
-        let config_manager = Arc::new(crate::config::ConfigManager::new().unwrap());
+        let config_manager =
+            Arc::new(crate::config::ConfigManager::new(event_manager.clone()).unwrap());
         let sync_manager = Arc::new(crate::sync::SyncManager::new().unwrap());
 
         let api_handler = Arc::new(ApiHandler::new(
@@ -245,4 +581,17 @@ mod tests {
 
         let _server = IpcServer::new(socket_path, api_handler);
     }
+
+    #[test]
+    fn test_error_chain_and_params_digest() {
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let error = crate::api::ApiError::from(crate::entry::EntryManagerError::from(
+            mootimer_core::Error::from(io),
+        ));
+        assert_eq!(error_chain(&error), "Storage error: IO error: denied");
+
+        let digest = params_digest(&json!({ "profile_id": "work", "secret": "hunter2" }));
+        assert!(digest.starts_with("{profile_id,secret}#"), "{}", digest);
+        assert!(!digest.contains("hunter2"));
+    }
 }
@@ -0,0 +1,295 @@
+//! Per-connection event subscriptions.
+//!
+//! A connection gets no notifications until it calls `events.subscribe`
+//! with some of the categories in `EVENT_CATEGORIES`, or `*` for all of
+//! them. `events.unsubscribe` takes the same list; without one it drops
+//! everything.
+//!
+//! Subscribers that render coarse time, like a status bar showing minutes,
+//! can pass `tick_interval_seconds` to receive at most one tick per timer
+//! per interval. Other timer events are never held back.
+//!
+//! `task.watch_status` asks for a single `task.status_reached`
+//! notification when one task moves into a given status, whatever the
+//! connection's categories. Each watch fires once and is then dropped.
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::events::{DaemonEvent, EVENT_CATEGORIES, TaskEvent, TaskEventType};
+use crate::timer::TimerEventType;
+use mootimer_core::models::TaskStatus;
+
+/// Wildcard accepted in place of a category list.
+pub const ALL_CATEGORIES: &str = "*";
+
+/// Ticks arrive roughly once a second, not exactly; without some slack a
+/// tick landing a hair early would be dropped and the next one delivered a
+/// second late, drifting every interval.
+const TICK_SLACK: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct SubscriptionParams {
+    #[serde(default)]
+    pub categories: Option<Vec<String>>,
+    /// Deliver at most one tick per timer this often. `0` means every tick.
+    #[serde(default)]
+    pub tick_interval_seconds: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct StatusWatchParams {
+    pub task_id: String,
+    pub target_status: TaskStatus,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Subscriptions {
+    categories: BTreeSet<&'static str>,
+    tick_interval: Option<Duration>,
+    /// When each timer's last tick was delivered.
+    last_tick: HashMap<String, Instant>,
+    /// Pending `task.watch_status` requests.
+    status_watches: HashSet<(String, TaskStatus)>,
+}
+
+impl Subscriptions {
+    pub fn subscribe(&mut self, categories: &[String]) -> Result<(), String> {
+        self.categories.extend(resolve(categories)?);
+        Ok(())
+    }
+
+    pub fn set_tick_interval(&mut self, seconds: u64) {
+        self.tick_interval = (seconds > 0).then(|| Duration::from_secs(seconds));
+        self.last_tick.clear();
+    }
+
+    pub fn tick_interval_seconds(&self) -> u64 {
+        self.tick_interval.map_or(0, |i| i.as_secs())
+    }
+
+    /// Whether `event`, arriving at `now`, should be sent on this
+    /// connection. Ticks count against the tick interval when they pass.
+    pub fn admits(&mut self, event: &DaemonEvent, now: Instant) -> bool {
+        if !self.contains(event.category()) {
+            return false;
+        }
+        let DaemonEvent::Timer(timer_event) = event else {
+            return true;
+        };
+
+        match timer_event.event_type {
+            TimerEventType::Tick { .. } => {
+                let Some(interval) = self.tick_interval else {
+                    return true;
+                };
+                let due = self
+                    .last_tick
+                    .get(&timer_event.timer_id)
+                    .is_none_or(|last| {
+                        now.saturating_duration_since(*last) + TICK_SLACK >= interval
+                    });
+                if due {
+                    self.last_tick.insert(timer_event.timer_id.clone(), now);
+                }
+                due
+            }
+            TimerEventType::Stopped { .. } | TimerEventType::Cancelled => {
+                self.last_tick.remove(&timer_event.timer_id);
+                true
+            }
+            _ => true,
+        }
+    }
+
+    pub fn watch_status(&mut self, task_id: String, status: TaskStatus) {
+        self.status_watches.insert((task_id, status));
+    }
+
+    /// The task event if it moved a watched task into its target status,
+    /// dropping that watch.
+    pub fn take_status_watch<'a>(&mut self, event: &'a DaemonEvent) -> Option<&'a TaskEvent> {
+        let DaemonEvent::Task(
+            task_event @ TaskEvent {
+                event_type: TaskEventType::Updated,
+                previous_status: Some(_),
+                task: Some(task),
+                ..
+            },
+        ) = event
+        else {
+            return None;
+        };
+        self.status_watches
+            .remove(&(task.id.clone(), task.status))
+            .then_some(task_event)
+    }
+
+    pub fn unsubscribe(&mut self, categories: &[String]) -> Result<(), String> {
+        for category in resolve(categories)? {
+            self.categories.remove(category);
+        }
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.categories.clear();
+        self.last_tick.clear();
+    }
+
+    pub fn contains(&self, category: &str) -> bool {
+        self.categories.contains(category)
+    }
+
+    pub fn categories(&self) -> Vec<&'static str> {
+        self.categories.iter().copied().collect()
+    }
+}
+
+/// Maps requested names onto `EVENT_CATEGORIES`, rejecting the whole list
+/// if any name is unknown.
+fn resolve(categories: &[String]) -> Result<Vec<&'static str>, String> {
+    let mut resolved = Vec::new();
+    for name in categories {
+        if name == ALL_CATEGORIES {
+            resolved.extend_from_slice(EVENT_CATEGORIES);
+            continue;
+        }
+        match EVENT_CATEGORIES.iter().find(|c| **c == name) {
+            Some(category) => resolved.push(*category),
+            None => {
+                return Err(format!(
+                    "Unknown event category '{}'. Must be one of: {}, or {}",
+                    name,
+                    EVENT_CATEGORIES.join(", "),
+                    ALL_CATEGORIES
+                ));
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::TimerEvent;
+    use mootimer_core::models::TimerMode;
+
+    fn timer_event(event_type: TimerEventType) -> DaemonEvent {
+        DaemonEvent::Timer(TimerEvent::new(
+            event_type,
+            "work".to_string(),
+            "t1".to_string(),
+        ))
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_subscribe_and_unsubscribe() {
+        let mut subs = Subscriptions::default();
+        assert!(!subs.contains("timer"));
+
+        subs.subscribe(&names(&["timer", "task"])).unwrap();
+        assert_eq!(subs.categories(), vec!["task", "timer"]);
+
+        subs.unsubscribe(&names(&["task"])).unwrap();
+        assert_eq!(subs.categories(), vec!["timer"]);
+
+        subs.subscribe(&names(&["*"])).unwrap();
+        assert_eq!(subs.categories().len(), EVENT_CATEGORIES.len());
+
+        let err = subs.unsubscribe(&names(&["timer", "weather"])).unwrap_err();
+        assert!(err.contains("weather"));
+        assert!(subs.contains("timer"), "a bad list must change nothing");
+    }
+
+    #[test]
+    fn test_ticks_are_throttled_but_transitions_are_not() {
+        let mut subs = Subscriptions::default();
+        subs.subscribe(&names(&["timer"])).unwrap();
+        subs.set_tick_interval(10);
+
+        let start = Instant::now();
+        let mut ticks = 0;
+        let mut transitions = 0;
+        let started = timer_event(TimerEventType::Started {
+            task_id: None,
+            mode: TimerMode::Manual,
+        });
+        transitions += subs.admits(&started, start) as u32;
+
+        // A minute of ticks, each a little late or early like the real ones.
+        for second in 0..60u64 {
+            let jitter = Duration::from_millis(if second % 2 == 0 { 30 } else { 0 });
+            let now = start + Duration::from_secs(second) + jitter;
+            let tick = timer_event(TimerEventType::Tick {
+                elapsed_seconds: second,
+                remaining_seconds: None,
+            });
+            ticks += subs.admits(&tick, now) as u32;
+
+            if second == 25 {
+                let paused = timer_event(TimerEventType::Paused {
+                    elapsed_seconds: second,
+                });
+                transitions += subs.admits(&paused, now) as u32;
+                transitions += subs.admits(&timer_event(TimerEventType::Resumed), now) as u32;
+            }
+        }
+        let stopped = timer_event(TimerEventType::Stopped {
+            duration_seconds: 60,
+        });
+        transitions += subs.admits(&stopped, start + Duration::from_secs(60)) as u32;
+
+        assert_eq!(ticks, 6);
+        assert_eq!(transitions, 4);
+
+        subs.set_tick_interval(0);
+        let tick = timer_event(TimerEventType::Tick {
+            elapsed_seconds: 61,
+            remaining_seconds: None,
+        });
+        assert!(subs.admits(&tick, start));
+        assert!(subs.admits(&tick, start));
+    }
+
+    #[test]
+    fn test_status_watch_fires_once_on_transition() {
+        let mut subs = Subscriptions::default();
+        subs.watch_status("build".to_string(), TaskStatus::Done);
+
+        let mut task = mootimer_core::models::Task::new("Build".to_string()).unwrap();
+        task.id = "build".to_string();
+        let event = |task: &mootimer_core::models::Task, previous| {
+            DaemonEvent::Task(TaskEvent::updated_from(
+                "work".to_string(),
+                task.clone(),
+                previous,
+            ))
+        };
+
+        task.update_status(TaskStatus::InProgress);
+        assert!(
+            subs.take_status_watch(&event(&task, TaskStatus::Todo))
+                .is_none()
+        );
+
+        // Saving a done task again is not a transition.
+        task.update_status(TaskStatus::Done);
+        assert!(
+            subs.take_status_watch(&event(&task, TaskStatus::Done))
+                .is_none()
+        );
+
+        let done = event(&task, TaskStatus::InProgress);
+        assert!(subs.take_status_watch(&done).is_some());
+        assert!(subs.take_status_watch(&done).is_none());
+        assert!(!subs.contains("task"), "a watch is not a subscription");
+    }
+}
@@ -0,0 +1,542 @@
+//! A short in-memory record of the last mutating API calls, with what it
+//! takes to reverse each one, so scripted clients without an undo of their
+//! own can take back a mistake with `admin.undo`.
+//!
+//! The journal is not persisted; it starts empty with every daemon.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use tokio::sync::RwLock;
+
+use crate::budget::BudgetManager;
+use crate::entry::EntryManager;
+use crate::profile::ProfileManager;
+use crate::task::TaskManager;
+use mootimer_core::models::{Budget, Entry, Profile, Task};
+
+/// How many operations are kept; older ones drop off.
+pub const JOURNAL_CAPACITY: usize = 100;
+
+/// Methods that change stored data and are therefore journaled.
+pub const JOURNALED_METHODS: &[&str] = &[
+    "profile.create",
+    "profile.update",
+    "profile.delete",
+    "task.create",
+    "task.add_subtask",
+    "task.copy",
+    "task.update",
+    "task.set_description",
+    "task.set_notes",
+    "task.delete",
+    "task.move",
+    "task.reorder",
+    "task.bulk_status_update",
+    "task.bulk_tag",
+    "task.bulk_untag",
+    "entry.create",
+    "entry.create_batch",
+    "entry.delete",
+    "entry.update",
+    "entry.copy_to_profile",
+    "budget.create",
+    "budget.delete",
+];
+
+pub fn is_journaled(method: &str) -> bool {
+    JOURNALED_METHODS.contains(&method)
+}
+
+/// One step of reversing an operation.
+#[derive(Debug, Clone)]
+pub enum UndoStep {
+    DeleteEntry {
+        profile_id: String,
+        entry_id: String,
+    },
+    RestoreEntry {
+        profile_id: String,
+        entry: Entry,
+    },
+    ReplaceEntry {
+        profile_id: String,
+        entry: Entry,
+    },
+    DeleteTask {
+        profile_id: String,
+        task_id: String,
+    },
+    RestoreTask {
+        profile_id: String,
+        task: Task,
+    },
+    ReplaceTask {
+        profile_id: String,
+        task: Task,
+    },
+    /// Only while the profile is still empty.
+    DeleteProfile {
+        profile_id: String,
+    },
+    ReplaceProfile {
+        profile: Profile,
+    },
+    DeleteBudget {
+        profile_id: String,
+        budget_id: String,
+    },
+    RestoreBudget {
+        profile_id: String,
+        budget: Budget,
+    },
+}
+
+impl UndoStep {
+    async fn apply(
+        &self,
+        entries: &EntryManager,
+        tasks: &TaskManager,
+        profiles: &ProfileManager,
+        budgets: &BudgetManager,
+    ) -> Result<(), String> {
+        match self {
+            Self::DeleteEntry {
+                profile_id,
+                entry_id,
+            } => entries
+                .delete(profile_id, entry_id)
+                .await
+                .map_err(|e| e.to_string()),
+            Self::RestoreEntry { profile_id, entry } => entries
+                .add_with(profile_id, entry.clone(), true)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Self::ReplaceEntry { profile_id, entry } => entries
+                .update(profile_id, entry.clone())
+                .await
+                .map_err(|e| e.to_string()),
+            Self::DeleteTask {
+                profile_id,
+                task_id,
+            } => tasks
+                .delete(profile_id, task_id)
+                .await
+                .map_err(|e| e.to_string()),
+            Self::RestoreTask { profile_id, task } => tasks
+                .create(profile_id, task.clone())
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Self::ReplaceTask { profile_id, task } => tasks
+                .update(profile_id, task.clone())
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Self::DeleteProfile { profile_id } => {
+                let has_tasks = !tasks.list(profile_id).await.unwrap_or_default().is_empty();
+                let has_entries = !entries
+                    .get_all(profile_id)
+                    .await
+                    .unwrap_or_default()
+                    .is_empty();
+                if has_tasks || has_entries {
+                    return Err(format!(
+                        "Profile {} has tasks or entries now; delete it with profile.delete",
+                        profile_id
+                    ));
+                }
+                profiles.delete(profile_id).await.map_err(|e| e.to_string())
+            }
+            Self::ReplaceProfile { profile } => profiles
+                .update(profile.clone())
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Self::DeleteBudget {
+                profile_id,
+                budget_id,
+            } => budgets
+                .delete(profile_id, budget_id)
+                .await
+                .map_err(|e| e.to_string()),
+            Self::RestoreBudget { profile_id, budget } => budgets
+                .create(profile_id, budget.clone())
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// What a journaled method is about to change, read before it runs.
+#[derive(Debug, Clone)]
+pub enum Before {
+    Nothing,
+    Entry(Entry),
+    /// `dependents` are the tasks it blocks, which lose it from their
+    /// `blocked_by` when it is completed or deleted.
+    Task {
+        task: Task,
+        dependents: Vec<Task>,
+    },
+    Tasks(Vec<Task>),
+    Profile(Profile),
+    Budget(Budget),
+}
+
+impl Before {
+    /// Reads whatever `method` is going to overwrite or delete.
+    pub async fn capture(
+        method: &str,
+        params: &Value,
+        entries: &EntryManager,
+        tasks: &TaskManager,
+        profiles: &ProfileManager,
+        budgets: &BudgetManager,
+    ) -> Self {
+        let str_at = |pointer: &str| params.pointer(pointer).and_then(|v| v.as_str());
+        let captured = match method {
+            "entry.delete" | "entry.update" | "entry.copy_to_profile" => {
+                let (profile, id) = match method {
+                    "entry.delete" => (str_at("/profile_id"), str_at("/entry_id")),
+                    "entry.update" => (str_at("/profile_id"), str_at("/entry/id")),
+                    _ => (str_at("/source_profile_id"), str_at("/entry_id")),
+                };
+                match (profile, id) {
+                    (Some(profile), Some(id)) => {
+                        entries.get(profile, id).await.ok().map(Self::Entry)
+                    }
+                    _ => None,
+                }
+            }
+            "task.update" | "task.set_description" | "task.set_notes" | "task.delete" => {
+                let id = str_at("/task_id").or_else(|| str_at("/task/id"));
+                match (str_at("/profile_id"), id) {
+                    (Some(profile), Some(id)) => match tasks.get(profile, id).await {
+                        Ok(task) => {
+                            let dependents = tasks
+                                .list(profile)
+                                .await
+                                .unwrap_or_default()
+                                .into_iter()
+                                .filter(|t| t.blocked_by.contains(&task.id))
+                                .collect();
+                            Some(Self::Task { task, dependents })
+                        }
+                        Err(_) => None,
+                    },
+                    _ => None,
+                }
+            }
+            "task.bulk_status_update" | "task.bulk_tag" | "task.bulk_untag" | "task.reorder" => {
+                match str_at("/profile_id") {
+                    Some(profile) => tasks.list(profile).await.ok().map(Self::Tasks),
+                    None => None,
+                }
+            }
+            "profile.update" => match str_at("/profile/id") {
+                Some(id) => profiles.get(id).await.ok().map(Self::Profile),
+                None => None,
+            },
+            "budget.delete" => match (str_at("/profile_id"), str_at("/budget_id")) {
+                (Some(profile), Some(id)) => budgets
+                    .list(profile)
+                    .await
+                    .ok()
+                    .and_then(|budgets| budgets.into_iter().find(|b| b.id == id))
+                    .map(Self::Budget),
+                _ => None,
+            },
+            _ => None,
+        };
+        captured.unwrap_or(Self::Nothing)
+    }
+}
+
+/// The steps that reverse a successful call, or `None` if it cannot be
+/// reversed.
+pub fn undo_steps(
+    method: &str,
+    params: &Value,
+    before: Before,
+    result: &Value,
+) -> Option<Vec<UndoStep>> {
+    let param = |key: &str| params.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let result_id = || {
+        result
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+
+    let steps = match (method, before) {
+        ("entry.create", _) => vec![UndoStep::DeleteEntry {
+            profile_id: param("profile_id")?,
+            entry_id: result_id()?,
+        }],
+        ("entry.create_batch", _) => {
+            let profile_id = param("profile_id")?;
+            result
+                .get("ids")?
+                .as_array()?
+                .iter()
+                .filter_map(|id| id.as_str())
+                .map(|id| UndoStep::DeleteEntry {
+                    profile_id: profile_id.clone(),
+                    entry_id: id.to_string(),
+                })
+                .collect()
+        }
+        ("entry.delete", Before::Entry(entry)) => vec![UndoStep::RestoreEntry {
+            profile_id: param("profile_id")?,
+            entry,
+        }],
+        ("entry.update", Before::Entry(entry)) => vec![UndoStep::ReplaceEntry {
+            profile_id: param("profile_id")?,
+            entry,
+        }],
+        ("entry.copy_to_profile", Before::Entry(entry)) => {
+            let mut steps = vec![UndoStep::DeleteEntry {
+                profile_id: param("target_profile_id")?,
+                entry_id: result.pointer("/entry/id")?.as_str()?.to_string(),
+            }];
+            if result.get("moved").and_then(|v| v.as_bool()) == Some(true) {
+                steps.push(UndoStep::RestoreEntry {
+                    profile_id: param("source_profile_id")?,
+                    entry,
+                });
+            }
+            steps
+        }
+        ("task.create" | "task.add_subtask" | "task.copy", _) => vec![UndoStep::DeleteTask {
+            profile_id: param("profile_id")?,
+            task_id: result_id()?,
+        }],
+        ("task.update", Before::Task { task, dependents }) => {
+            let profile_id = param("profile_id")?;
+            let completed = serde_json::from_value::<Task>(result.clone())
+                .is_ok_and(|updated| updated.is_completed());
+            let released = completed && !task.is_completed();
+            let mut steps = vec![UndoStep::ReplaceTask {
+                profile_id: profile_id.clone(),
+                task,
+            }];
+            if released {
+                steps.extend(dependents.into_iter().map(|task| UndoStep::ReplaceTask {
+                    profile_id: profile_id.clone(),
+                    task,
+                }));
+            }
+            steps
+        }
+        ("task.set_description" | "task.set_notes", Before::Task { task, .. }) => {
+            vec![UndoStep::ReplaceTask {
+                profile_id: param("profile_id")?,
+                task,
+            }]
+        }
+        // The task comes back before its dependents point at it again.
+        ("task.delete", Before::Task { task, dependents }) => {
+            let profile_id = param("profile_id")?;
+            std::iter::once(UndoStep::RestoreTask {
+                profile_id: profile_id.clone(),
+                task,
+            })
+            .chain(dependents.into_iter().map(|task| UndoStep::ReplaceTask {
+                profile_id: profile_id.clone(),
+                task,
+            }))
+            .collect()
+        }
+        (
+            "task.bulk_status_update" | "task.bulk_tag" | "task.bulk_untag" | "task.reorder",
+            Before::Tasks(tasks),
+        ) => {
+            let profile_id = param("profile_id")?;
+            let updated: Vec<&str> = result
+                .get("task_ids")?
+                .as_array()?
+                .iter()
+                .filter_map(|id| id.as_str())
+                .collect();
+            // Moving tasks to done unblocked the tasks waiting on them;
+            // those get their blockers back too.
+            let released = method == "task.bulk_status_update"
+                && param("to_status").as_deref() == Some("done")
+                && param("from_status").as_deref() != Some("done");
+            let (changed, rest): (Vec<Task>, Vec<Task>) = tasks
+                .into_iter()
+                .partition(|t| updated.contains(&t.id.as_str()));
+            let dependents = rest.into_iter().filter(|t| {
+                released && t.blocked_by.iter().any(|id| updated.contains(&id.as_str()))
+            });
+            changed
+                .into_iter()
+                .chain(dependents)
+                .map(|task| UndoStep::ReplaceTask {
+                    profile_id: profile_id.clone(),
+                    task,
+                })
+                .collect()
+        }
+        ("profile.create", _) => vec![UndoStep::DeleteProfile {
+            profile_id: result_id()?,
+        }],
+        ("profile.update", Before::Profile(profile)) => {
+            vec![UndoStep::ReplaceProfile { profile }]
+        }
+        ("budget.create", _) => vec![UndoStep::DeleteBudget {
+            profile_id: param("profile_id")?,
+            budget_id: result_id()?,
+        }],
+        ("budget.delete", Before::Budget(budget)) => vec![UndoStep::RestoreBudget {
+            profile_id: param("profile_id")?,
+            budget,
+        }],
+        // `profile.delete` removes the profile's files and `task.move`
+        // rewrites two profiles; neither is kept around to restore.
+        _ => return None,
+    };
+    Some(steps)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JournalRecord {
+    pub op_id: u64,
+    pub method: String,
+    pub at: DateTime<Utc>,
+    pub undoable: bool,
+    pub undone: bool,
+    /// An undo stopped part way through. Undoing it again carries on with
+    /// the steps that are left.
+    pub partial: bool,
+    #[serde(skip)]
+    steps: Vec<UndoStep>,
+    /// How many of `steps` were applied.
+    #[serde(skip)]
+    applied: usize,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UndoError {
+    #[error("No operation {0} in the journal")]
+    NotFound(u64),
+
+    #[error("Nothing left to undo")]
+    Empty,
+
+    #[error("{method} (op {op_id}) cannot be undone")]
+    NotUndoable { op_id: u64, method: String },
+
+    #[error("Operation {0} was already undone")]
+    AlreadyUndone(u64),
+
+    #[error("Undoing operation {op_id} stopped after {applied} of {total} steps: {message}")]
+    Failed {
+        op_id: u64,
+        applied: usize,
+        total: usize,
+        message: String,
+    },
+}
+
+#[derive(Debug, Default)]
+struct JournalState {
+    next_op_id: u64,
+    records: VecDeque<JournalRecord>,
+}
+
+#[derive(Debug, Default)]
+pub struct Journal {
+    state: RwLock<JournalState>,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a record and returns its op id. `steps` is `None` for calls
+    /// that cannot be reversed.
+    pub async fn record(&self, method: &str, steps: Option<Vec<UndoStep>>) -> u64 {
+        let mut state = self.state.write().await;
+        state.next_op_id += 1;
+        let op_id = state.next_op_id;
+        state.records.push_back(JournalRecord {
+            op_id,
+            method: method.to_string(),
+            at: Utc::now(),
+            undoable: steps.is_some(),
+            undone: false,
+            partial: false,
+            steps: steps.unwrap_or_default(),
+            applied: 0,
+        });
+        if state.records.len() > JOURNAL_CAPACITY {
+            state.records.pop_front();
+        }
+        op_id
+    }
+
+    /// Newest first.
+    pub async fn records(&self) -> Vec<JournalRecord> {
+        let state = self.state.read().await;
+        state.records.iter().rev().cloned().collect()
+    }
+
+    /// Reverses operation `op_id`, or the newest one not yet undone. The
+    /// journal stays locked meanwhile so two undos cannot interleave.
+    pub async fn undo(
+        &self,
+        op_id: Option<u64>,
+        entries: &EntryManager,
+        tasks: &TaskManager,
+        profiles: &ProfileManager,
+        budgets: &BudgetManager,
+    ) -> Result<JournalRecord, UndoError> {
+        let mut state = self.state.write().await;
+        let record = match op_id {
+            Some(op_id) => state
+                .records
+                .iter_mut()
+                .find(|r| r.op_id == op_id)
+                .ok_or(UndoError::NotFound(op_id))?,
+            None => state
+                .records
+                .iter_mut()
+                .rev()
+                .find(|r| r.undoable && !r.undone)
+                .ok_or(UndoError::Empty)?,
+        };
+
+        if !record.undoable {
+            return Err(UndoError::NotUndoable {
+                op_id: record.op_id,
+                method: record.method.clone(),
+            });
+        }
+        if record.undone {
+            return Err(UndoError::AlreadyUndone(record.op_id));
+        }
+
+        // Steps applied by an earlier, failed attempt are not run again.
+        while let Some(step) = record.steps.get(record.applied) {
+            if let Err(message) = step.apply(entries, tasks, profiles, budgets).await {
+                record.partial = record.applied > 0;
+                return Err(UndoError::Failed {
+                    op_id: record.op_id,
+                    applied: record.applied,
+                    total: record.steps.len(),
+                    message,
+                });
+            }
+            record.applied += 1;
+        }
+        record.undone = true;
+        record.partial = false;
+        Ok(record.clone())
+    }
+}
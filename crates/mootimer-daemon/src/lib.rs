@@ -1,15 +1,22 @@
 pub mod api;
+pub mod budget;
 pub mod config;
 pub mod entry;
 pub mod event_manager;
 pub mod events;
 pub mod ipc;
+pub mod journal;
 pub mod profile;
+pub mod protocol;
 pub mod sync;
 pub mod task;
+#[cfg(feature = "test-util")]
+pub mod test_util;
 pub mod timer;
+pub mod usage;
 
 pub use api::ApiHandler;
+pub use budget::BudgetManager;
 pub use config::ConfigManager;
 pub use entry::EntryManager;
 pub use event_manager::EventManager;
@@ -19,3 +26,4 @@ pub use profile::ProfileManager;
 pub use sync::SyncManager;
 pub use task::TaskManager;
 pub use timer::{TimerEngine, TimerEvent, TimerManager};
+pub use usage::UsageManager;
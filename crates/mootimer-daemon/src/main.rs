@@ -1,18 +1,28 @@
 use anyhow::Result;
-use clap::Parser;
-use mootimer_core::storage::init_data_dir;
+use clap::{Parser, Subcommand};
+use mootimer_core::storage::{
+    ConfigStorage, check, durability, get_config_dir, get_data_dir, init_data_dir,
+};
 use mootimer_daemon::{
     ApiHandler, ConfigManager, EntryManager, EventManager, IpcServer, ProfileManager, SyncManager,
     TaskManager, TimerManager,
 };
 use std::fs;
 use std::sync::Arc;
+use std::time::Duration;
 
+#[cfg(feature = "encrypt")]
+mod encryption;
 mod mcp;
 
 #[derive(Parser, Debug)]
 #[command(name = "mootimerd")]
-#[command(about = "MooTimer daemon - work timer backend", long_about = None)]
+#[command(about = "MooTimer daemon - work timer backend")]
+#[command(long_about = "MooTimer daemon - work timer backend\n\n\
+    Signals:\n  \
+    SIGINT, SIGTERM  stop active timers, save their entries and exit\n                   \
+    (as does the daemon.shutdown RPC)\n  \
+    SIGHUP           reload config.json from disk without restarting")]
 struct Args {
     #[arg(short, long, default_value = "/tmp/mootimer.sock")]
     socket: String,
@@ -20,30 +30,84 @@ struct Args {
     #[arg(short, long, default_value = "info")]
     log_level: String,
 
+    /// Write log lines as tracing's text format or as JSON objects.
+    #[arg(long, default_value = "text", value_parser = ["text", "json"])]
+    log_format: String,
+
     #[arg(long)]
     mcp: bool,
+
+    /// Keep entries encrypted, asking for the passphrase at startup.
+    /// Also turned on by `daemon.encryption` in the config.
+    #[arg(long)]
+    encrypted: bool,
+
+    /// Look over the data directory for broken or inconsistent files,
+    /// print what was found and exit: 0 if clean, 1 if not.
+    #[arg(long)]
+    check: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Maintenance of the data directory; run with the daemon stopped.
+    Storage {
+        #[command(subcommand)]
+        action: StorageAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum StorageAction {
+    /// Convert the entries of every profile to or from encrypted files.
+    Migrate {
+        #[arg(long, conflicts_with = "decrypt", required_unless_present = "decrypt")]
+        encrypt: bool,
+        #[arg(long)]
+        decrypt: bool,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
 
-    if args.mcp {
+    if let Some(Command::Storage {
+        action: StorageAction::Migrate { encrypt, .. },
+    }) = args.command
+    {
+        migrate_storage(encrypt)
+    } else if args.check {
+        check_storage()
+    } else if args.mcp {
         mcp::run_mcp_server(args.socket).await
     } else {
         let event_manager = Arc::new(EventManager::new());
+        // Entries are read as the managers start, so the key goes in first.
+        let config_manager = Arc::new(ConfigManager::new(event_manager.clone())?);
+        let config = config_manager.get().await;
+        apply_timezone(config.tui.timezone.as_deref());
+        let daemon_config = config.daemon;
+        if args.encrypted || daemon_config.encryption {
+            unlock_entries()?;
+        }
+        durability::install(daemon_config.durability);
 
         let mut timer_manager = Arc::new(TimerManager::new(event_manager.clone()));
         let profile_manager = Arc::new(ProfileManager::new(event_manager.clone())?);
         profile_manager.load_all().await?;
         let task_manager = Arc::new(TaskManager::new(event_manager.clone())?);
+        task_manager.spawn_usage_tracker();
 
         if let Some(tm) = Arc::get_mut(&mut timer_manager) {
             tm.set_task_manager(task_manager.clone());
         }
 
         let entry_manager = Arc::new(EntryManager::new(event_manager.clone())?);
-        let config_manager = Arc::new(ConfigManager::new()?);
+        config_manager.spawn_watcher(Duration::from_secs(2));
         let sync_manager = Arc::new(SyncManager::new()?);
 
         let api_handler = Arc::new(ApiHandler::new(
@@ -52,15 +116,94 @@ async fn main() -> Result<()> {
             profile_manager,
             task_manager,
             entry_manager,
-            config_manager,
+            config_manager.clone(),
             sync_manager,
         ));
 
-        run_daemon(args, api_handler).await
+        run_daemon(args, api_handler, config_manager).await
     }
 }
 
-async fn run_daemon(args: Args, api_handler: Arc<ApiHandler>) -> Result<()> {
+#[cfg(feature = "encrypt")]
+fn unlock_entries() -> Result<()> {
+    let cipher = encryption::unlock(&init_data_dir()?)?;
+    mootimer_core::storage::crypto::install(cipher)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "encrypt"))]
+fn unlock_entries() -> Result<()> {
+    anyhow::bail!("Encryption needs mootimerd built with the `encrypt` feature")
+}
+
+#[cfg(feature = "encrypt")]
+fn migrate_storage(encrypt: bool) -> Result<()> {
+    use mootimer_core::storage::crypto::{self, Migration};
+
+    let data_dir = init_data_dir()?;
+    let cipher = Arc::new(encryption::unlock(&data_dir)?);
+    let migration = if encrypt {
+        Migration::Encrypt
+    } else {
+        Migration::Decrypt
+    };
+    let profiles = crypto::migrate(&data_dir, cipher, migration)?;
+    if profiles.is_empty() {
+        println!("Nothing to convert");
+    } else {
+        println!("Converted entries of: {}", profiles.join(", "));
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "encrypt"))]
+fn migrate_storage(_encrypt: bool) -> Result<()> {
+    unlock_entries()
+}
+
+/// Points chrono's `Local` at `tui.timezone`, if one is configured, so
+/// working hours are read in the zone the TUI shows times in. Runs before
+/// any manager starts reading the clock.
+fn apply_timezone(timezone: Option<&str>) {
+    if let Some(timezone) = timezone {
+        unsafe {
+            std::env::set_var("TZ", timezone);
+        }
+    }
+}
+
+fn check_storage() -> Result<()> {
+    let data_dir = get_data_dir();
+    println!("Checking {}", data_dir.display());
+    let earliest = ConfigStorage::new(get_config_dir())
+        .load()?
+        .daemon
+        .earliest_entry_date;
+    let report = check::check_data_dir(&data_dir, earliest);
+    for skipped in &report.skipped {
+        println!("skipped {}", skipped);
+    }
+    for issue in &report.issues {
+        println!("{}", issue);
+    }
+    println!(
+        "{} profiles, {} tasks, {} entries: {} issues",
+        report.profiles,
+        report.tasks,
+        report.entries,
+        report.issues.len()
+    );
+    if !report.is_clean() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn run_daemon(
+    args: Args,
+    api_handler: Arc<ApiHandler>,
+    config_manager: Arc<ConfigManager>,
+) -> Result<()> {
     let data_dir = init_data_dir()?;
     let log_file_path = data_dir.join("daemon.log");
 
@@ -73,11 +216,15 @@ async fn run_daemon(args: Args, api_handler: Arc<ApiHandler>) -> Result<()> {
     let stdout_writer = std::io::stdout.with_max_level(tracing::Level::INFO);
     let file_writer = log_file.with_max_level(tracing::Level::DEBUG);
 
-    tracing_subscriber::fmt()
+    let subscriber = tracing_subscriber::fmt()
         .with_writer(stdout_writer.and(file_writer))
         .with_env_filter(&args.log_level)
-        .with_ansi(false)
-        .init();
+        .with_ansi(false);
+    if args.log_format == "json" {
+        subscriber.json().init();
+    } else {
+        subscriber.init();
+    }
 
     tracing::info!("MooTimer daemon starting...");
     tracing::info!("Socket path: {}", args.socket);
@@ -108,10 +255,31 @@ async fn run_daemon(args: Args, api_handler: Arc<ApiHandler>) -> Result<()> {
     use tokio::signal::unix::{SignalKind, signal};
     let mut sigint = signal(SignalKind::interrupt())?;
     let mut sigterm = signal(SignalKind::terminate())?;
+    let mut sighup = signal(SignalKind::hangup())?;
 
-    tokio::select! {
-        _ = sigint.recv() => tracing::info!("Received SIGINT, shutting down..."),
-        _ = sigterm.recv() => tracing::info!("Received SIGTERM, shutting down..."),
+    loop {
+        tokio::select! {
+            _ = sigint.recv() => {
+                tracing::info!("Received SIGINT, shutting down...");
+                break;
+            }
+            _ = sigterm.recv() => {
+                tracing::info!("Received SIGTERM, shutting down...");
+                break;
+            }
+            _ = api_handler.shutdown_requested() => {
+                tracing::info!("Shutting down on request...");
+                break;
+            }
+            _ = sighup.recv() => {
+                tracing::info!("Received SIGHUP, reloading config...");
+                match config_manager.reload().await {
+                    Ok(true) => tracing::info!("Config reloaded"),
+                    Ok(false) => tracing::info!("Config unchanged"),
+                    Err(e) => tracing::error!("Failed to reload config: {}", e),
+                }
+            }
+        }
     }
 
     api_handler.shutdown().await;
@@ -61,6 +61,7 @@ impl ProfileManager {
         profile
             .validate()
             .map_err(|e| ProfileManagerError::Invalid(e.to_string()))?;
+        let profile = profile.with_defaults();
 
         {
             let cache = self.cache.read().await;
@@ -121,6 +122,7 @@ impl ProfileManager {
         }
 
         profile.touch();
+        let profile = profile.with_defaults();
 
         self.storage.save(&profile)?;
 
@@ -1,3 +1,3 @@
 pub mod manager;
 
-pub use manager::ProfileManager;
+pub use manager::{ProfileManager, ProfileManagerError};
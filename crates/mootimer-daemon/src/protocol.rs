@@ -0,0 +1,527 @@
+//! The JSON-RPC protocol: its version and every method the daemon answers,
+//! with the schema of its params and what it returns.
+//!
+//! `ApiHandler::handle` refuses methods missing from [`METHODS`], so a
+//! method can't be added without declaring it here. `system.hello` lists
+//! the names and `system.describe` returns the whole registry.
+//! `tests/protocol_reference.rs` renders it to `PROTOCOL.md`.
+
+use schemars::{JsonSchema, Schema, schema_for};
+use serde_json::{Value, json};
+
+use crate::api::{admin, budget, config, entry, profile, stats, status, sync, task, timer, usage};
+use crate::ipc::protocol::ERROR_CODES;
+use crate::ipc::subscriptions::{StatusWatchParams, SubscriptionParams};
+
+/// Bumped when a method is removed or changes incompatibly. Additions don't
+/// need a bump: clients check `system.hello` for the methods they use.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+pub struct MethodSpec {
+    pub name: &'static str,
+    /// What a successful call returns.
+    pub result: &'static str,
+    params: fn() -> Option<Schema>,
+}
+
+impl MethodSpec {
+    /// JSON schema of the params object, or `None` for methods that ignore
+    /// their params.
+    pub fn params_schema(&self) -> Option<Schema> {
+        (self.params)()
+    }
+}
+
+fn params<T: JsonSchema>() -> Option<Schema> {
+    Some(schema_for!(T))
+}
+
+fn no_params() -> Option<Schema> {
+    None
+}
+
+macro_rules! method {
+    ($name:literal, (), $result:literal) => {
+        MethodSpec {
+            name: $name,
+            result: $result,
+            params: no_params,
+        }
+    };
+    ($name:literal, $params:ty, $result:literal) => {
+        MethodSpec {
+            name: $name,
+            result: $result,
+            params: params::<$params>,
+        }
+    };
+}
+
+/// Every method the daemon answers. All of them go through
+/// `ApiHandler::handle` except `events.*`, which act on the connection and
+/// are answered by `IpcServer`.
+pub static METHODS: &[MethodSpec] = &[
+    method!(
+        "system.hello",
+        usage::HelloParams,
+        "`{ name, version, protocol_version, methods, compression? }`, listing the methods not disabled by config; `compression` is `{ encoding, threshold_bytes }` when large responses will come as `{ compressed, encoding, data }` envelopes"
+    ),
+    method!(
+        "system.describe",
+        (),
+        "`{ protocol_version, methods: [{ name, params, result }] }` for every method"
+    ),
+    method!(
+        "system.messages",
+        config::MessagesParams,
+        "`{ locale, messages }`: notification text by key, English where the locale has none"
+    ),
+    method!(
+        "daemon.info",
+        (),
+        "`{ name, version, pid, device, durability }`; `durability` is the write policy in effect"
+    ),
+    method!(
+        "daemon.shutdown",
+        (),
+        "`{ status: \"shutting_down\" }`, sent before the daemon exits"
+    ),
+    method!(
+        "events.subscribe",
+        SubscriptionParams,
+        "`{ categories, tick_interval_seconds }` for the connection"
+    ),
+    method!(
+        "events.unsubscribe",
+        SubscriptionParams,
+        "`{ categories, tick_interval_seconds }`; no categories clears them all"
+    ),
+    method!(
+        "timer.start_manual",
+        timer::StartManualParams,
+        "`{ timer_id, status: \"started\" }`, with `warning` if the task is blocked"
+    ),
+    method!(
+        "timer.start_pomodoro",
+        timer::StartPomodoroParams,
+        "`{ timer_id, status: \"started\" }`, with `warning` if the task is blocked"
+    ),
+    method!(
+        "timer.start_countdown",
+        timer::StartCountdownParams,
+        "`{ timer_id, status: \"started\", duration_minutes }`, with `warning` if the task is blocked"
+    ),
+    method!(
+        "timer.pause",
+        timer::TimerParams,
+        "`{ status: \"paused\" }`"
+    ),
+    method!(
+        "timer.resume",
+        timer::TimerParams,
+        "`{ status: \"resumed\" }`"
+    ),
+    method!(
+        "timer.set_auto_start",
+        timer::SetAutoStartParams,
+        "`{ auto_start_next_session }`"
+    ),
+    method!(
+        "timer.skip_phase",
+        timer::TimerParams,
+        "`{ status: \"skipped\", break }`"
+    ),
+    method!("timer.stop", timer::TimerParams, "The saved entry"),
+    method!(
+        "timer.cancel",
+        timer::TimerParams,
+        "`{ status: \"cancelled\" }`"
+    ),
+    method!(
+        "timer.get",
+        timer::TimerParams,
+        "The active timer, plus `display`: `{ elapsed_seconds, remaining_seconds, total_seconds, progress_ratio, phase_display, next_phase_display }` computed now"
+    ),
+    method!(
+        "timer.get_by_profile",
+        timer::ProfileParams,
+        "The profile's first active timer with `display` as in `timer.get`, or null"
+    ),
+    method!(
+        "timer.peek",
+        timer::ProfileParams,
+        "`{ running, elapsed_seconds, remaining_seconds?, mode, task_title? }`, up to 200 ms old"
+    ),
+    method!(
+        "timer.list_by_profile",
+        timer::ProfileParams,
+        "The profile's active timers, each with `display` as in `timer.get`"
+    ),
+    method!(
+        "timer.list",
+        (),
+        "Active timers across all profiles by id, each with `display` as in `timer.get`"
+    ),
+    method!(
+        "status.summary",
+        status::SummaryParams,
+        "`{ text, timer_id, profile_color, profile_icon }` for status bars"
+    ),
+    method!(
+        "profile.create",
+        profile::CreateProfileParams,
+        "The new profile"
+    ),
+    method!("profile.get", profile::ProfileIdParams, "The profile"),
+    method!("profile.list", (), "All profiles"),
+    method!(
+        "profile.update",
+        profile::UpdateProfileParams,
+        "The updated profile"
+    ),
+    method!(
+        "profile.delete",
+        profile::ProfileIdParams,
+        "`{ status: \"deleted\" }`"
+    ),
+    method!("task.create", task::CreateTaskParams, "The new task"),
+    method!(
+        "task.add_subtask",
+        task::AddSubtaskParams,
+        "The new subtask"
+    ),
+    method!("task.copy", task::CopyTaskParams, "The new task, as `todo`"),
+    method!("task.get", task::TaskIdParams, "The task"),
+    method!(
+        "task.blockers",
+        task::TaskIdParams,
+        "`{ task_id, blocked, blockers }`"
+    ),
+    method!(
+        "task.get_active",
+        task::ProfileIdParams,
+        "The task with a running timer, or null"
+    ),
+    method!(
+        "task.list",
+        task::ListTasksParams,
+        "The profile's tasks, nested under `subtasks` if asked"
+    ),
+    method!("task.update", task::UpdateTaskParams, "The updated task"),
+    method!(
+        "task.set_description",
+        task::SetDescriptionParams,
+        "The updated task"
+    ),
+    method!("task.set_notes", task::SetNotesParams, "The updated task"),
+    method!(
+        "task.delete",
+        task::TaskIdParams,
+        "`{ status: \"deleted\" }`"
+    ),
+    method!(
+        "task.search",
+        task::SearchTasksParams,
+        "Matching tasks, best first, each with a `match` object: `field` (`title`, `description` or `{\"tag\": index}`), `score`, and `spans` of matched characters as `[start, end)` character offsets"
+    ),
+    method!(
+        "task.stale",
+        task::StaleTasksParams,
+        "Open tasks not worked on for `days`"
+    ),
+    method!(
+        "task.list_by_due_date",
+        task::DueDateParams,
+        "Tasks by due date, soonest first"
+    ),
+    method!(
+        "task.move",
+        task::MoveTaskParams,
+        "`{ status: \"moved\", task, entries_moved }`"
+    ),
+    method!(
+        "task.reorder",
+        task::ReorderTaskParams,
+        "`{ task, task_ids }`, the moved task and every task whose order changed"
+    ),
+    method!(
+        "task.bulk_status_update",
+        task::BulkStatusUpdateParams,
+        "`{ updated_count, task_ids }`"
+    ),
+    method!(
+        "task.bulk_tag",
+        task::BulkTagParams,
+        "`{ updated_count, task_ids }`, counting tasks that gained a tag"
+    ),
+    method!(
+        "task.bulk_untag",
+        task::BulkTagParams,
+        "`{ updated_count, task_ids }`, counting tasks that lost a tag"
+    ),
+    method!(
+        "task.watch_status",
+        StatusWatchParams,
+        "`{ task_id, target_status }`; one `task.status_reached` notification follows when the task moves into that status"
+    ),
+    method!(
+        "budget.list",
+        budget::ProfileIdParams,
+        "Budgets with their use in the current period"
+    ),
+    method!(
+        "budget.create",
+        budget::CreateBudgetParams,
+        "The new budget"
+    ),
+    method!("budget.delete", budget::DeleteBudgetParams, "`{ deleted }`"),
+    method!("entry.list", entry::ListEntriesParams, "All entries"),
+    method!(
+        "entry.list_invalid",
+        entry::ListEntriesParams,
+        "Stored entries that fail validation, as `[{ entry, problem }]`"
+    ),
+    method!(
+        "entry.get_by_id",
+        entry::EntryIdParams,
+        "The entry; an error if there is none with that id"
+    ),
+    method!(
+        "entry.last",
+        entry::ListEntriesParams,
+        "The most recently completed entry, or null"
+    ),
+    method!(
+        "entry.find_duplicates",
+        entry::FindDuplicatesParams,
+        "Likely duplicates, as `[{ entry, duplicate_of }]`"
+    ),
+    method!(
+        "entry.filter",
+        entry::FilterEntriesParams,
+        "Matching entries"
+    ),
+    method!("entry.create", entry::CreateEntryParams, "The new entry"),
+    method!(
+        "entry.create_batch",
+        entry::CreateBatchParams,
+        "`{ created, ids, skipped }`"
+    ),
+    method!(
+        "entry.delete",
+        entry::EntryIdParams,
+        "`{ status: \"deleted\", id }`"
+    ),
+    method!(
+        "entry.update",
+        entry::UpdateEntryParams,
+        "`{ status: \"updated\" }`"
+    ),
+    method!(
+        "entry.copy_to_profile",
+        entry::CopyToProfileParams,
+        "`{ entry, moved }`"
+    ),
+    method!("entry.today", entry::StatsParams, "Today's entries"),
+    method!("entry.week", entry::StatsParams, "This week's entries"),
+    method!("entry.month", entry::StatsParams, "This month's entries"),
+    method!("entry.stats_today", entry::StatsParams, "Totals for today"),
+    method!(
+        "entry.stats_week",
+        entry::StatsParams,
+        "Totals for this week"
+    ),
+    method!(
+        "entry.stats_month",
+        entry::StatsParams,
+        "Totals for this month"
+    ),
+    method!(
+        "entry.stats_compare",
+        entry::StatsCompareParams,
+        "`{ period_a, period_b, delta }`"
+    ),
+    method!(
+        "entry.stats_hourly",
+        entry::StatsHourlyParams,
+        "`[{ hour, avg_duration_seconds, session_count }]` for hours 0 to 23"
+    ),
+    method!(
+        "entry.today_all_profiles",
+        (),
+        "Today's entries from every profile"
+    ),
+    method!(
+        "entry.week_all_profiles",
+        (),
+        "This week's entries from every profile"
+    ),
+    method!(
+        "entry.month_all_profiles",
+        (),
+        "This month's entries from every profile"
+    ),
+    method!(
+        "stats.bulk",
+        stats::BulkParams,
+        "`{ profiles }`, totals per profile and window"
+    ),
+    method!("config.get", (), "The config"),
+    method!(
+        "config.describe",
+        (),
+        "Every config key with its type, default and description"
+    ),
+    method!(
+        "config.set_default_profile",
+        config::SetDefaultProfileParams,
+        "The updated config"
+    ),
+    method!(
+        "config.set_current_user",
+        config::SetCurrentUserParams,
+        "The updated config"
+    ),
+    method!(
+        "config.set_device_name",
+        config::SetDeviceNameParams,
+        "The device entries are stamped with"
+    ),
+    method!(
+        "config.update_daemon",
+        config::UpdateDaemonParams,
+        "The updated config"
+    ),
+    method!(
+        "config.update_pomodoro",
+        config::UpdatePomodoroParams,
+        "The updated config"
+    ),
+    method!(
+        "config.update_sync",
+        config::UpdateSyncParams,
+        "The updated config"
+    ),
+    method!(
+        "config.update_tui",
+        config::UpdateTuiParams,
+        "The updated config"
+    ),
+    method!("config.export", (), "The whole config, for `config.import`"),
+    method!(
+        "config.import",
+        config::ImportParams,
+        "`{ config, clamped }`, the config now in use and a note per value moved into range"
+    ),
+    method!("config.reset", (), "The default config"),
+    method!("sync.init", (), "`{ status: \"initialized\" }`"),
+    method!("sync.status", sync::StatusParams, "The sync status"),
+    method!("sync.sync", (), "`{ pulled, pushed, stashed, conflicts }`"),
+    method!(
+        "sync.commit",
+        sync::CommitParams,
+        "`{ commit_id, status }`; `commit_id` is null with nothing to commit"
+    ),
+    method!(
+        "sync.set_remote",
+        sync::SetRemoteParams,
+        "`{ status: \"remote_set\", url }`"
+    ),
+    method!("sync.diff", (), "Uncommitted changes per file, with totals"),
+    method!("sync.diff_full", (), "`{ diff }`, the uncommitted patch"),
+    method!(
+        "sync.history",
+        sync::HistoryParams,
+        "The latest commits, newest first"
+    ),
+    method!("sync.conflicts", (), "Unresolved sync conflicts"),
+    method!(
+        "sync.resolve_conflict",
+        sync::ResolveConflictParams,
+        "`{ status: \"resolved\", conflict_id, remaining }`"
+    ),
+    method!("sync.list_branches", (), "Local branches"),
+    method!(
+        "sync.switch_branch",
+        sync::SwitchBranchParams,
+        "`{ status: \"switched\", branch }`"
+    ),
+    method!(
+        "sync.stash",
+        (),
+        "`{ status, stash_id }`; `status` is `no_changes` with nothing to stash"
+    ),
+    method!(
+        "sync.stash_pop",
+        (),
+        "`{ status: \"restored\", conflicts }`"
+    ),
+    method!(
+        "usage.summary",
+        usage::UsageSummaryParams,
+        "`{ enabled, month, total, by_method, by_client, by_day, months, retention_months }`"
+    ),
+    method!("admin.stats", (), "`{ stats_cache }` counters"),
+    method!(
+        "admin.journal",
+        (),
+        "`{ capacity, operations }`, newest first"
+    ),
+    method!(
+        "admin.check",
+        (),
+        "`{ profiles, tasks, entries, issues: [{ location, problem }], skipped }`"
+    ),
+    method!(
+        "admin.undo",
+        admin::UndoParams,
+        "`{ status: \"undone\", operation }`"
+    ),
+];
+
+pub fn find(name: &str) -> Option<&'static MethodSpec> {
+    METHODS.iter().find(|m| m.name == name)
+}
+
+/// The answer to `system.describe`.
+pub fn describe() -> Value {
+    let methods: Vec<Value> = METHODS
+        .iter()
+        .map(|m| {
+            json!({
+                "name": m.name,
+                "params": m.params_schema(),
+                "result": m.result,
+            })
+        })
+        .collect();
+    json!({
+        "protocol_version": PROTOCOL_VERSION,
+        "methods": methods,
+    })
+}
+
+/// The registry as a Markdown reference.
+pub fn reference_markdown() -> String {
+    let mut doc = format!(
+        "# mootimerd JSON-RPC protocol\n\n\
+         Protocol version {}. Generated from `src/protocol.rs`; do not edit.\n\n",
+        PROTOCOL_VERSION
+    );
+    for method in METHODS {
+        doc.push_str(&format!("## `{}`\n\n", method.name));
+        doc.push_str(&format!("Returns: {}\n\n", method.result));
+        match method.params_schema() {
+            Some(schema) => {
+                let schema = serde_json::to_string_pretty(&schema).unwrap_or_default();
+                doc.push_str(&format!("Params:\n\n```json\n{}\n```\n\n", schema));
+            }
+            None => doc.push_str("No params.\n\n"),
+        }
+    }
+    doc.push_str("## Errors\n\n| Code | Meaning |\n|------|---------|\n");
+    for (code, meaning) in ERROR_CODES {
+        doc.push_str(&format!("| {} | {} |\n", code, meaning));
+    }
+    doc
+}
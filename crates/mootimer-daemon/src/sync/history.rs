@@ -0,0 +1,170 @@
+//! Auto-commit messages end in git trailers naming what they recorded, so
+//! scripts and `sync.history` can read the history back without parsing
+//! the summary line:
+//!
+//! ```text
+//! Add entry: task 9c1b... - 25m (2024-05-01 14:02)
+//!
+//! MooTimer-Profile: work
+//! MooTimer-Entry-Id: 3f2a...
+//! MooTimer-Task-Id: 9c1b...
+//! MooTimer-Duration-Seconds: 1500
+//! ```
+//!
+//! `MooTimer-Task-Id` is left out for entries without a task.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use mootimer_core::git::CommitInfo;
+use mootimer_core::messages;
+use mootimer_core::models::Entry;
+
+pub const PROFILE_TRAILER: &str = "MooTimer-Profile";
+pub const ENTRY_ID_TRAILER: &str = "MooTimer-Entry-Id";
+pub const TASK_ID_TRAILER: &str = "MooTimer-Task-Id";
+pub const DURATION_TRAILER: &str = "MooTimer-Duration-Seconds";
+
+/// The auto-commit message for saving `entry` to `profile_id`. The summary
+/// line is written in `locale`; the trailers never are.
+pub fn entry_commit_message(locale: &str, profile_id: &str, entry: &Entry) -> String {
+    let task_info = match &entry.task_id {
+        Some(id) => messages::render(locale, "sync.entry_commit.task", &[("task_id", id)]),
+        None => messages::lookup(locale, "sync.entry_commit.no_task").to_string(),
+    };
+    let mut message = messages::render(
+        locale,
+        "sync.entry_commit.summary",
+        &[
+            ("task", &task_info),
+            ("minutes", &(entry.duration_seconds / 60).to_string()),
+            (
+                "time",
+                &chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+            ),
+        ],
+    );
+    message.push_str("\n\n");
+
+    message.push_str(&format!("{}: {}\n", PROFILE_TRAILER, profile_id));
+    message.push_str(&format!("{}: {}\n", ENTRY_ID_TRAILER, entry.id));
+    if let Some(task_id) = &entry.task_id {
+        message.push_str(&format!("{}: {}\n", TASK_ID_TRAILER, task_id));
+    }
+    message.push_str(&format!(
+        "{}: {}\n",
+        DURATION_TRAILER, entry.duration_seconds
+    ));
+    message
+}
+
+/// The `MooTimer-*` trailers in the last paragraph of `message`.
+pub fn parse_trailers(message: &str) -> Vec<(&str, &str)> {
+    let Some(last_paragraph) = message.trim_end().rsplit("\n\n").next() else {
+        return Vec::new();
+    };
+    last_paragraph
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .filter(|(key, _)| key.starts_with("MooTimer-"))
+        .map(|(key, value)| (key, value.trim()))
+        .collect()
+}
+
+/// One commit of the sync history, with its trailers picked apart. The
+/// trailer fields are `None` for commits that did not record an entry.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryEntry {
+    pub commit_id: String,
+    pub author: String,
+    pub time: DateTime<Utc>,
+    pub summary: String,
+    pub profile_id: Option<String>,
+    pub entry_id: Option<String>,
+    pub task_id: Option<String>,
+    pub duration_seconds: Option<u64>,
+}
+
+impl From<CommitInfo> for HistoryEntry {
+    fn from(commit: CommitInfo) -> Self {
+        let mut entry = Self {
+            summary: commit
+                .message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            commit_id: commit.id,
+            author: commit.author,
+            time: DateTime::from_timestamp(commit.time, 0).unwrap_or_default(),
+            profile_id: None,
+            entry_id: None,
+            task_id: None,
+            duration_seconds: None,
+        };
+        for (key, value) in parse_trailers(&commit.message) {
+            match key {
+                PROFILE_TRAILER => entry.profile_id = Some(value.to_string()),
+                ENTRY_ID_TRAILER => entry.entry_id = Some(value.to_string()),
+                TASK_ID_TRAILER => entry.task_id = Some(value.to_string()),
+                DURATION_TRAILER => entry.duration_seconds = value.parse().ok(),
+                _ => {}
+            }
+        }
+        entry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use mootimer_core::models::TimerMode;
+
+    #[test]
+    fn test_commit_message_round_trips() {
+        let start = Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap();
+        let entry = Entry::create_completed(
+            Some("task-1".to_string()),
+            None,
+            start,
+            start + Duration::minutes(25),
+            TimerMode::Pomodoro,
+        )
+        .unwrap();
+
+        let message = entry_commit_message("en", "work", &entry);
+        assert!(message.starts_with("Add entry: task task-1 - 25m ("));
+
+        let parsed = HistoryEntry::from(CommitInfo {
+            id: "abc".to_string(),
+            author: "MooTimer".to_string(),
+            time: start.timestamp(),
+            message,
+        });
+        assert_eq!(parsed.time, start);
+        assert_eq!(parsed.profile_id.as_deref(), Some("work"));
+        assert_eq!(parsed.entry_id.as_deref(), Some(entry.id.as_str()));
+        assert_eq!(parsed.task_id.as_deref(), Some("task-1"));
+        assert_eq!(parsed.duration_seconds, Some(1500));
+
+        let message = entry_commit_message("de", "work", &entry);
+        assert!(message.starts_with("Eintrag hinzugefügt: Aufgabe task-1 - 25m ("));
+        let parsed = HistoryEntry::from(CommitInfo {
+            id: "abd".to_string(),
+            author: "MooTimer".to_string(),
+            time: start.timestamp(),
+            message,
+        });
+        assert_eq!(parsed.entry_id.as_deref(), Some(entry.id.as_str()));
+
+        let plain = HistoryEntry::from(CommitInfo {
+            id: "def".to_string(),
+            author: "MooTimer".to_string(),
+            time: start.timestamp(),
+            message: "Auto-commit on TUI exit".to_string(),
+        });
+        assert_eq!(plain.summary, "Auto-commit on TUI exit");
+        assert_eq!(plain.profile_id, None);
+    }
+}
@@ -1,7 +1,17 @@
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
 
+use super::history::HistoryEntry;
+use super::merge::{self, SyncConflict};
+use crate::events::SyncStage;
 use mootimer_core::{
-    Result as CoreResult, git::GitOperations, models::SyncConfig, storage::init_data_dir,
+    Result as CoreResult,
+    git::{BranchInfo, FileChange, GitOperations, PullOutcome},
+    models::SyncConfig,
+    storage::{durability, init_data_dir},
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -15,23 +25,52 @@ pub enum SyncManagerError {
     #[error("Not configured: {0}")]
     NotConfigured(String),
 
+    #[error("No sync conflict {0}")]
+    ConflictNotFound(String),
+
     #[error("Task join error: {0}")]
     JoinError(String),
 }
 
 pub type Result<T> = std::result::Result<T, SyncManagerError>;
 
+/// How long a computed status is reused. Local writes drop it sooner; this
+/// bounds how stale ahead/behind and changes made outside the daemon get.
+const STATUS_TTL: Duration = Duration::from_secs(60);
+
+struct CachedStatus {
+    status: SyncStatus,
+    remote_url: Option<String>,
+    computed_at: Instant,
+}
+
+/// Where unresolved conflicts are kept, inside `.git` so they are never
+/// committed.
+const CONFLICTS_FILE: &str = ".git/mootimer-conflicts.json";
+
 pub struct SyncManager {
     git_ops: Arc<GitOperations>,
+    /// `get_status` walks the whole work tree, which is slow on network
+    /// home directories, so its result is kept until something changes.
+    status_cache: RwLock<Option<CachedStatus>>,
+    status_computations: AtomicU64,
+    conflicts_path: PathBuf,
+    /// Held while the conflicts file is read and rewritten.
+    conflicts_lock: Mutex<()>,
 }
 
 impl SyncManager {
     pub fn new() -> CoreResult<Self> {
         let data_dir = init_data_dir()?;
+        let conflicts_path = data_dir.join(CONFLICTS_FILE);
         let git_ops = GitOperations::new(data_dir);
 
         Ok(Self {
             git_ops: Arc::new(git_ops),
+            status_cache: RwLock::new(None),
+            status_computations: AtomicU64::new(0),
+            conflicts_path,
+            conflicts_lock: Mutex::new(()),
         })
     }
 
@@ -40,6 +79,7 @@ impl SyncManager {
         tokio::task::spawn_blocking(move || git_ops.init())
             .await
             .map_err(|e| SyncManagerError::JoinError(e.to_string()))??;
+        self.invalidate_status().await;
         Ok(())
     }
 
@@ -54,7 +94,7 @@ impl SyncManager {
         let git_ops = self.git_ops.clone();
         let message = message.to_string();
 
-        tokio::task::spawn_blocking(move || {
+        let result = tokio::task::spawn_blocking(move || {
             if !git_ops.is_initialized() {
                 return Err(SyncManagerError::NotConfigured(
                     "Git repository not initialized".to_string(),
@@ -72,17 +112,33 @@ impl SyncManager {
             Ok(Some(commit_id.to_string()))
         })
         .await
-        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?
+        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?;
+        self.invalidate_status().await;
+        result
     }
 
+    /// Pulls and, if enabled, pushes. When both sides changed the same
+    /// task or entry the local version is kept and a conflict recorded;
+    /// `SyncResult::conflicts` counts the new ones. A pull refused over
+    /// uncommitted changes is retried with them stashed, and they are put
+    /// back the same way `stash_pop` does.
     pub async fn sync(&self, config: &SyncConfig) -> Result<SyncResult> {
+        self.sync_with_progress(config, |_| {}).await
+    }
+
+    /// `sync`, calling `progress` as each step starts.
+    pub async fn sync_with_progress(
+        &self,
+        config: &SyncConfig,
+        progress: impl Fn(SyncStage) + Send + 'static,
+    ) -> Result<SyncResult> {
         let git_ops = self.git_ops.clone();
         let remote_url = config.remote_url.clone().ok_or_else(|| {
             SyncManagerError::NotConfigured("Remote URL not configured".to_string())
         })?;
         let auto_push = config.auto_push;
 
-        tokio::task::spawn_blocking(move || {
+        let result = tokio::task::spawn_blocking(move || {
             if !git_ops.is_initialized() {
                 return Err(SyncManagerError::NotConfigured(
                     "Git repository not initialized".to_string(),
@@ -95,17 +151,49 @@ impl SyncManager {
 
             let mut pulled = false;
             let mut pushed = false;
-
-            match git_ops.pull("origin", &branch) {
-                Ok(_) => {
-                    pulled = true;
+            let mut stashed = false;
+            let mut conflicts = Vec::new();
+
+            progress(SyncStage::Pulling);
+            let mut pull = git_ops.pull("origin", &branch, |file| {
+                merge::merge_file(file, &mut conflicts)
+            });
+            if let Err(e) = &pull
+                && git_ops.has_changes()?
+            {
+                tracing::info!("Pull refused ({}); retrying with local changes stashed", e);
+                conflicts.clear();
+                progress(SyncStage::Stashing);
+                git_ops.stash("mootimer: stashed for sync")?;
+                stashed = true;
+                progress(SyncStage::Pulling);
+                pull = git_ops.pull("origin", &branch, |file| {
+                    merge::merge_file(file, &mut conflicts)
+                });
+                if pull.is_err() {
+                    conflicts.clear();
+                }
+                progress(SyncStage::Restoring);
+                git_ops
+                    .stash_pop(|file| merge::merge_file(file, &mut conflicts))
+                    .map_err(|e| {
+                        SyncManagerError::Git(format!("Failed to restore stashed changes: {}", e))
+                    })?;
+            }
+            match pull {
+                Ok(outcome) => {
+                    pulled = outcome != PullOutcome::UpToDate;
                 }
                 Err(e) => {
                     tracing::warn!("Failed to pull: {}", e);
+                    if !stashed {
+                        conflicts.clear();
+                    }
                 }
             }
 
             if auto_push {
+                progress(SyncStage::Pushing);
                 match git_ops.push("origin", &branch) {
                     Ok(_) => {
                         pushed = true;
@@ -116,15 +204,154 @@ impl SyncManager {
                 }
             }
 
-            Ok(SyncResult { pulled, pushed })
+            Ok((pulled, pushed, stashed, conflicts))
         })
         .await
-        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?
+        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?;
+        self.invalidate_status().await;
+        let (pulled, pushed, stashed, conflicts) = result?;
+
+        Ok(SyncResult {
+            pulled,
+            pushed,
+            stashed,
+            conflicts: self.record_conflicts(conflicts).await?,
+        })
+    }
+
+    /// Puts uncommitted changes aside, e.g. to pull. Returns the stash
+    /// commit, or `None` if there was nothing to stash.
+    pub async fn stash(&self) -> Result<Option<String>> {
+        let git_ops = self.git_ops.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            if !git_ops.is_initialized() {
+                return Err(SyncManagerError::NotConfigured(
+                    "Git repository not initialized".to_string(),
+                ));
+            }
+            Ok(git_ops.stash("mootimer: stashed by sync.stash")?)
+        })
+        .await
+        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?;
+        self.invalidate_status().await;
+        result
+    }
+
+    /// Brings the newest stash back. Tasks and entries changed both there
+    /// and in commits since are merged as in `sync`; returns how many
+    /// conflicts that recorded.
+    pub async fn stash_pop(&self) -> Result<usize> {
+        let git_ops = self.git_ops.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            if !git_ops.is_initialized() {
+                return Err(SyncManagerError::NotConfigured(
+                    "Git repository not initialized".to_string(),
+                ));
+            }
+            let mut conflicts = Vec::new();
+            git_ops.stash_pop(|file| merge::merge_file(file, &mut conflicts))?;
+            Ok(conflicts)
+        })
+        .await
+        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?;
+        self.invalidate_status().await;
+        self.record_conflicts(result?).await
+    }
+
+    /// Stores new conflicts, replacing older ones for the same record.
+    async fn record_conflicts(&self, conflicts: Vec<SyncConflict>) -> Result<usize> {
+        let count = conflicts.len();
+        if count > 0 {
+            let _guard = self.conflicts_lock.lock().await;
+            let mut stored = self.read_conflicts()?;
+            for conflict in conflicts {
+                stored.retain(|c| c.id != conflict.id);
+                stored.push(conflict);
+            }
+            self.write_conflicts(&stored)?;
+        }
+        Ok(count)
     }
 
+    /// Conflicts left by earlier syncs, oldest first.
+    pub async fn conflicts(&self) -> Result<Vec<SyncConflict>> {
+        let _guard = self.conflicts_lock.lock().await;
+        self.read_conflicts()
+    }
+
+    /// Drops a conflict once its record has been settled.
+    pub async fn remove_conflict(&self, id: &str) -> Result<SyncConflict> {
+        let _guard = self.conflicts_lock.lock().await;
+        let mut stored = self.read_conflicts()?;
+        let index = stored
+            .iter()
+            .position(|c| c.id == id)
+            .ok_or_else(|| SyncManagerError::ConflictNotFound(id.to_string()))?;
+        let conflict = stored.remove(index);
+        self.write_conflicts(&stored)?;
+        self.invalidate_status().await;
+        Ok(conflict)
+    }
+
+    fn read_conflicts(&self) -> Result<Vec<SyncConflict>> {
+        match std::fs::read(&self.conflicts_path) {
+            Ok(content) => {
+                serde_json::from_slice(&content).map_err(|e| SyncManagerError::Storage(e.into()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(SyncManagerError::Storage(e.into())),
+        }
+    }
+
+    fn write_conflicts(&self, conflicts: &[SyncConflict]) -> Result<()> {
+        if conflicts.is_empty() {
+            return match std::fs::remove_file(&self.conflicts_path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(SyncManagerError::Storage(e.into()))
+                }
+                _ => Ok(()),
+            };
+        }
+        let content = serde_json::to_vec_pretty(conflicts)
+            .map_err(|e| SyncManagerError::Storage(e.into()))?;
+        durability::write_file(&self.conflicts_path, content).map_err(SyncManagerError::Storage)
+    }
+
+    /// The repository status, reused from the last call unless something
+    /// was written since, the remote changed or it is older than
+    /// `STATUS_TTL`.
     pub async fn get_status(&self, config: &SyncConfig) -> Result<SyncStatus> {
+        if let Some(cached) = self.status_cache.read().await.as_ref()
+            && cached.remote_url == config.remote_url
+            && cached.computed_at.elapsed() < STATUS_TTL
+        {
+            return Ok(cached.status.clone());
+        }
+        self.refresh_status(config).await
+    }
+
+    /// Asks git for the status, for callers that need ground truth.
+    pub async fn refresh_status(&self, config: &SyncConfig) -> Result<SyncStatus> {
+        let status = self.compute_status(config).await?;
+        *self.status_cache.write().await = Some(CachedStatus {
+            status: status.clone(),
+            remote_url: config.remote_url.clone(),
+            computed_at: Instant::now(),
+        });
+        Ok(status)
+    }
+
+    /// Forgets the cached status; called on every write to the data
+    /// directory.
+    pub async fn invalidate_status(&self) {
+        self.status_cache.write().await.take();
+    }
+
+    async fn compute_status(&self, config: &SyncConfig) -> Result<SyncStatus> {
+        self.status_computations.fetch_add(1, Ordering::Relaxed);
         let git_ops = self.git_ops.clone();
         let remote_url = config.remote_url.clone();
+        let conflicts = self.conflicts().await.map(|c| c.len()).unwrap_or(0);
 
         tokio::task::spawn_blocking(move || {
             if !git_ops.is_initialized() {
@@ -135,6 +362,7 @@ impl SyncManager {
                     behind: 0,
                     current_branch: None,
                     last_commit: None,
+                    conflicts: 0,
                 });
             }
 
@@ -159,17 +387,111 @@ impl SyncManager {
                 behind,
                 current_branch,
                 last_commit,
+                conflicts,
             })
         })
         .await
         .map_err(|e| SyncManagerError::JoinError(e.to_string()))?
     }
 
+    /// Per-file line counts of the changes not yet committed.
+    pub async fn diff(&self) -> Result<Vec<FileChange>> {
+        let git_ops = self.git_ops.clone();
+
+        tokio::task::spawn_blocking(move || {
+            if !git_ops.is_initialized() {
+                return Err(SyncManagerError::NotConfigured(
+                    "Git repository not initialized".to_string(),
+                ));
+            }
+
+            Ok(git_ops.diff_stats()?)
+        })
+        .await
+        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?
+    }
+
+    /// The uncommitted changes as a unified diff.
+    pub async fn diff_full(&self) -> Result<String> {
+        let git_ops = self.git_ops.clone();
+
+        tokio::task::spawn_blocking(move || {
+            if !git_ops.is_initialized() {
+                return Err(SyncManagerError::NotConfigured(
+                    "Git repository not initialized".to_string(),
+                ));
+            }
+
+            Ok(git_ops.diff_full()?)
+        })
+        .await
+        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?
+    }
+
+    /// The last `limit` commits, newest first, with their trailers parsed.
+    pub async fn history(&self, limit: usize) -> Result<Vec<HistoryEntry>> {
+        let git_ops = self.git_ops.clone();
+
+        tokio::task::spawn_blocking(move || {
+            if !git_ops.is_initialized() {
+                return Err(SyncManagerError::NotConfigured(
+                    "Git repository not initialized".to_string(),
+                ));
+            }
+
+            Ok(git_ops
+                .log(limit)?
+                .into_iter()
+                .map(HistoryEntry::from)
+                .collect())
+        })
+        .await
+        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?
+    }
+
+    pub async fn list_branches(&self) -> Result<Vec<BranchInfo>> {
+        let git_ops = self.git_ops.clone();
+
+        tokio::task::spawn_blocking(move || {
+            if !git_ops.is_initialized() {
+                return Err(SyncManagerError::NotConfigured(
+                    "Git repository not initialized".to_string(),
+                ));
+            }
+
+            Ok(git_ops.list_branches()?)
+        })
+        .await
+        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?
+    }
+
+    /// Checks out `branch`. The data files on disk change with it, so
+    /// callers must drop anything they cached from them.
+    pub async fn switch_branch(&self, branch: &str) -> Result<()> {
+        let git_ops = self.git_ops.clone();
+        let branch = branch.to_string();
+
+        let result = tokio::task::spawn_blocking(move || {
+            if !git_ops.is_initialized() {
+                return Err(SyncManagerError::NotConfigured(
+                    "Git repository not initialized".to_string(),
+                ));
+            }
+
+            git_ops.switch_branch(&branch)?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?;
+        self.invalidate_status().await;
+        result
+    }
+
     pub async fn set_remote(&self, url: &str) -> Result<()> {
         let git_ops = self.git_ops.clone();
         let url = url.to_string();
 
-        tokio::task::spawn_blocking(move || {
+        let result = tokio::task::spawn_blocking(move || {
             if !git_ops.is_initialized() {
                 return Err(SyncManagerError::NotConfigured(
                     "Git repository not initialized. Call init_repo() first.".to_string(),
@@ -180,7 +502,9 @@ impl SyncManager {
             Ok(())
         })
         .await
-        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?
+        .map_err(|e| SyncManagerError::JoinError(e.to_string()))?;
+        self.invalidate_status().await;
+        result
     }
 }
 
@@ -188,6 +512,12 @@ impl SyncManager {
 pub struct SyncResult {
     pub pulled: bool,
     pub pushed: bool,
+    /// Uncommitted changes were stashed for the pull and put back.
+    #[serde(default)]
+    pub stashed: bool,
+    /// Conflicts this sync recorded.
+    #[serde(default)]
+    pub conflicts: usize,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -198,6 +528,9 @@ pub struct SyncStatus {
     pub behind: usize,
     pub current_branch: Option<String>,
     pub last_commit: Option<String>,
+    /// Unresolved conflicts from earlier syncs.
+    #[serde(default)]
+    pub conflicts: usize,
 }
 
 #[cfg(test)]
@@ -262,4 +595,74 @@ mod tests {
         let status = manager.get_status(&config).await.unwrap();
         assert!(status.initialized);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_status_is_cached_until_invalidated() {
+        let temp_dir = TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+        }
+
+        let manager = SyncManager::new().unwrap();
+        manager.init_repo().await.unwrap();
+        let config = SyncConfig::default();
+        let computations = || manager.status_computations.load(Ordering::Relaxed);
+
+        for _ in 0..5 {
+            manager.get_status(&config).await.unwrap();
+        }
+        assert_eq!(computations(), 1);
+
+        let data_dir = init_data_dir().unwrap();
+        std::fs::write(data_dir.join("test.txt"), "Hello").unwrap();
+        manager.invalidate_status().await;
+        assert!(manager.get_status(&config).await.unwrap().has_changes);
+        assert_eq!(computations(), 2);
+
+        manager.refresh_status(&config).await.unwrap();
+        assert_eq!(computations(), 3);
+
+        let with_remote = SyncConfig {
+            remote_url: Some("https://example.com/repo.git".to_string()),
+            ..SyncConfig::default()
+        };
+        manager.get_status(&with_remote).await.unwrap();
+        assert_eq!(computations(), 4);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_sync_reports_each_stage() {
+        let temp_dir = TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+        }
+
+        let manager = SyncManager::new().unwrap();
+        manager.init_repo().await.unwrap();
+        std::fs::write(init_data_dir().unwrap().join("test.txt"), "Hello").unwrap();
+        manager.auto_commit("Test commit").await.unwrap();
+
+        let config = SyncConfig {
+            remote_url: Some(temp_dir.path().join("missing.git").display().to_string()),
+            auto_push: true,
+            ..SyncConfig::default()
+        };
+        let stages = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = stages.clone();
+        let result = manager
+            .sync_with_progress(&config, move |stage| seen.lock().unwrap().push(stage))
+            .await;
+
+        // The pull failing is only logged; the push failing ends the sync.
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("Failed to push"), "{}", error);
+        assert_eq!(
+            *stages.lock().unwrap(),
+            [SyncStage::Pulling, SyncStage::Pushing]
+        );
+    }
 }
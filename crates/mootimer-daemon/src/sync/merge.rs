@@ -0,0 +1,294 @@
+//! Record-level merging of the data files when a pull finds that this
+//! machine and the remote both committed, and the conflicts it leaves.
+//!
+//! `tasks.json` and `entries.csv` are merged task by task and entry by
+//! entry against the common ancestor; `entries.csv.enc` too, opened and
+//! sealed again with the installed key. A record changed on one side takes
+//! that side's version; a record both sides changed differently keeps the
+//! local version and is noted as a `SyncConflict` until
+//! `sync.resolve_conflict` settles it. Conflicts are kept in the `.git`
+//! directory so they are never committed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+use mootimer_core::{
+    Result as CoreResult,
+    git::ConflictedFile,
+    models::{Entry, Task},
+    storage::{entry, task},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    Task,
+    Entry,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncConflict {
+    /// `<kind>:<profile_id>:<record_id>`.
+    pub id: String,
+    pub kind: ConflictKind,
+    pub profile_id: String,
+    pub record_id: String,
+    /// `None` on the side that deleted the record.
+    pub local: Option<Value>,
+    pub remote: Option<Value>,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl SyncConflict {
+    fn new<T: Serialize>(
+        kind: ConflictKind,
+        profile_id: &str,
+        record_id: &str,
+        local: Option<&T>,
+        remote: Option<&T>,
+    ) -> Self {
+        let kind_name = match kind {
+            ConflictKind::Task => "task",
+            ConflictKind::Entry => "entry",
+        };
+        let to_value = |record: Option<&T>| record.and_then(|r| serde_json::to_value(r).ok());
+        Self {
+            id: format!("{}:{}:{}", kind_name, profile_id, record_id),
+            kind,
+            profile_id: profile_id.to_string(),
+            record_id: record_id.to_string(),
+            local: to_value(local),
+            remote: to_value(remote),
+            detected_at: Utc::now(),
+        }
+    }
+}
+
+/// A record id both sides changed differently, with the local and remote
+/// versions.
+type Clash<'a, T> = (&'a str, Option<&'a T>, Option<&'a T>);
+
+/// Merged records in local order, then records only the remote has, and
+/// the clashes.
+fn merge_records<'a, T: Clone + PartialEq>(
+    ancestor: &'a [T],
+    ours: &'a [T],
+    theirs: &'a [T],
+    id: fn(&T) -> &str,
+) -> (Vec<T>, Vec<Clash<'a, T>>) {
+    let index = |records: &'a [T]| -> HashMap<&'a str, &'a T> {
+        records.iter().map(|r| (id(r), r)).collect()
+    };
+    let (base, local, remote) = (index(ancestor), index(ours), index(theirs));
+
+    let order = ours
+        .iter()
+        .map(id)
+        .chain(theirs.iter().map(id).filter(|i| !local.contains_key(i)));
+
+    let mut merged = Vec::new();
+    let mut conflicts = Vec::new();
+    for record_id in order {
+        let (b, o, t) = (
+            base.get(record_id).copied(),
+            local.get(record_id).copied(),
+            remote.get(record_id).copied(),
+        );
+        let chosen = if o == t || t == b {
+            o
+        } else if o == b {
+            t
+        } else {
+            conflicts.push((record_id, o, t));
+            o
+        };
+        merged.extend(chosen.cloned());
+    }
+    (merged, conflicts)
+}
+
+/// The merged contents of a data file both sides changed. Conflicts the
+/// merge could not settle are added to `conflicts`. Files other than
+/// `tasks.json` and `entries.csv(.enc)`, files that fail to parse, and
+/// encrypted entries without a key installed keep the local version.
+pub fn merge_file(
+    file: &ConflictedFile,
+    conflicts: &mut Vec<SyncConflict>,
+) -> CoreResult<Option<Vec<u8>>> {
+    let path = Path::new(&file.path);
+    let profile_id = match (path.parent(), path.parent().and_then(Path::parent)) {
+        (Some(dir), Some(root)) if root == Path::new("profiles") => {
+            dir.file_name().map(|n| n.to_string_lossy().into_owned())
+        }
+        _ => None,
+    };
+    let file_name = path.file_name().and_then(|n| n.to_str());
+
+    let merged = match (profile_id, file_name) {
+        (Some(profile_id), Some("tasks.json")) => merge_tasks(file, &profile_id, conflicts),
+        (Some(profile_id), Some("entries.csv")) => merge_entries(file, &profile_id, conflicts),
+        #[cfg(feature = "encrypt")]
+        (Some(profile_id), Some("entries.csv.enc")) => mootimer_core::storage::crypto::installed()
+            .and_then(|cipher| merge_sealed_entries(file, &profile_id, &cipher, conflicts)),
+        _ => None,
+    };
+    if let Some(merged) = merged {
+        return merged.map(Some);
+    }
+
+    tracing::warn!("Keeping the local version of {} after a sync", file.path);
+    Ok(file.ours.clone().or_else(|| file.theirs.clone()))
+}
+
+fn parse_sides<T>(
+    file: &ConflictedFile,
+    parse: impl Fn(&[u8]) -> CoreResult<Vec<T>>,
+) -> Option<(Vec<T>, Vec<T>, Vec<T>)> {
+    let side = |content: &Option<Vec<u8>>| match content {
+        Some(content) => parse(content).ok(),
+        None => Some(Vec::new()),
+    };
+    Some((
+        side(&file.ancestor)?,
+        side(&file.ours)?,
+        side(&file.theirs)?,
+    ))
+}
+
+fn merge_tasks(
+    file: &ConflictedFile,
+    profile_id: &str,
+    conflicts: &mut Vec<SyncConflict>,
+) -> Option<CoreResult<Vec<u8>>> {
+    let (ancestor, ours, theirs) = parse_sides(file, task::tasks_from_json)?;
+    let (merged, clashes) = merge_records(&ancestor, &ours, &theirs, |t: &Task| &t.id);
+    conflicts.extend(clashes.into_iter().map(|(id, local, remote)| {
+        SyncConflict::new(ConflictKind::Task, profile_id, id, local, remote)
+    }));
+    Some(task::tasks_to_json(&merged))
+}
+
+fn merge_entries(
+    file: &ConflictedFile,
+    profile_id: &str,
+    conflicts: &mut Vec<SyncConflict>,
+) -> Option<CoreResult<Vec<u8>>> {
+    let sides = parse_sides(file, entry::entries_from_csv)?;
+    let merged = merge_entry_sides(sides, profile_id, conflicts);
+    Some(entry::entries_to_csv(&merged))
+}
+
+#[cfg(feature = "encrypt")]
+fn merge_sealed_entries(
+    file: &ConflictedFile,
+    profile_id: &str,
+    cipher: &mootimer_core::storage::crypto::Cipher,
+    conflicts: &mut Vec<SyncConflict>,
+) -> Option<CoreResult<Vec<u8>>> {
+    let sides = parse_sides(file, |content| {
+        entry::entries_from_sealed(content, profile_id, cipher)
+    })?;
+    let merged = merge_entry_sides(sides, profile_id, conflicts);
+    Some(entry::entries_to_sealed(&merged, profile_id, cipher))
+}
+
+fn merge_entry_sides(
+    (ancestor, ours, theirs): (Vec<Entry>, Vec<Entry>, Vec<Entry>),
+    profile_id: &str,
+    conflicts: &mut Vec<SyncConflict>,
+) -> Vec<Entry> {
+    let (merged, clashes) = merge_records(&ancestor, &ours, &theirs, |e: &Entry| &e.id);
+    conflicts.extend(clashes.into_iter().map(|(id, local, remote)| {
+        SyncConflict::new(ConflictKind::Entry, profile_id, id, local, remote)
+    }));
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, title: &str) -> Task {
+        let mut task = Task::new(title.to_string()).unwrap();
+        task.id = id.to_string();
+        task
+    }
+
+    #[test]
+    fn test_merge_tasks_by_record() {
+        let base = vec![task("a", "A"), task("b", "B"), task("c", "C")];
+
+        // Local renames b and c and deletes nothing; remote renames c
+        // differently, deletes a and adds d.
+        let mut ours = base.clone();
+        ours[1].title = "B local".to_string();
+        ours[2].title = "C local".to_string();
+        let mut theirs = vec![base[1].clone(), base[2].clone(), task("d", "D")];
+        theirs[1].title = "C remote".to_string();
+
+        let file = ConflictedFile {
+            path: "profiles/work/tasks.json".to_string(),
+            ancestor: Some(task::tasks_to_json(&base).unwrap()),
+            ours: Some(task::tasks_to_json(&ours).unwrap()),
+            theirs: Some(task::tasks_to_json(&theirs).unwrap()),
+        };
+        let mut conflicts = Vec::new();
+        let merged = merge_file(&file, &mut conflicts).unwrap().unwrap();
+        let merged = task::tasks_from_json(&merged).unwrap();
+
+        let titles: Vec<&str> = merged.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["B local", "C local", "D"]);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].id, "task:work:c");
+        assert_eq!(conflicts[0].local.as_ref().unwrap()["title"], "C local");
+        assert_eq!(conflicts[0].remote.as_ref().unwrap()["title"], "C remote");
+    }
+
+    #[cfg(feature = "encrypt")]
+    #[test]
+    fn test_merge_encrypted_entries_by_record() {
+        use mootimer_core::models::TimerMode;
+        use mootimer_core::storage::crypto::Cipher;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let cipher = Cipher::unlock(dir.path(), "pass").unwrap();
+        let entry = |id: &str, minutes: i64| {
+            let start = Utc::now() - chrono::Duration::hours(2);
+            let mut entry = Entry::create_completed(
+                None,
+                None,
+                start,
+                start + chrono::Duration::minutes(minutes),
+                TimerMode::Manual,
+            )
+            .unwrap();
+            entry.id = id.to_string();
+            entry
+        };
+        let seal = |entries: &[Entry]| entry::entries_to_sealed(entries, "work", &cipher).unwrap();
+
+        // Each side adds one entry.
+        let base = vec![entry("a", 10)];
+        let ours = vec![base[0].clone(), entry("b", 20)];
+        let theirs = vec![base[0].clone(), entry("c", 30)];
+        let file = ConflictedFile {
+            path: "profiles/work/entries.csv.enc".to_string(),
+            ancestor: Some(seal(&base)),
+            ours: Some(seal(&ours)),
+            theirs: Some(seal(&theirs)),
+        };
+        let mut conflicts = Vec::new();
+        let merged = merge_sealed_entries(&file, "work", &cipher, &mut conflicts)
+            .unwrap()
+            .unwrap();
+        assert!(conflicts.is_empty());
+
+        let merged = entry::entries_from_sealed(&merged, "work", &cipher).unwrap();
+        let ids: Vec<&str> = merged.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+    }
+}
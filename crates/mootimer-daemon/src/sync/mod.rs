@@ -1,3 +1,6 @@
+pub mod history;
 pub mod manager;
+pub mod merge;
 
-pub use manager::SyncManager;
+pub use history::HistoryEntry;
+pub use manager::{SyncManager, SyncManagerError};
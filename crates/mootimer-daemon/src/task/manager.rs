@@ -1,11 +1,18 @@
-use std::collections::HashMap;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tokio::sync::broadcast::error::RecvError;
 
+use crate::entry::EntryManager;
 use crate::event_manager::EventManager;
-use crate::events::TaskEvent;
+use crate::events::{DaemonEvent, EntryEventType, TaskEvent};
 use mootimer_core::{
-    Result as CoreResult, models::Task, storage::TaskStorage, storage::init_data_dir,
+    Result as CoreResult,
+    models::{Entry, Task, TaskStatus},
+    search::{TagMode, TaskMatch, rank_tasks},
+    storage::TaskStorage,
+    storage::init_data_dir,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -22,9 +29,63 @@ pub enum TaskManagerError {
 
 pub type Result<T> = std::result::Result<T, TaskManagerError>;
 
+/// Spacing of `Task::order` values, leaving room to move tasks between
+/// neighbours without touching the rest of the column.
+pub const ORDER_GAP: i64 = 1024;
+
+/// Where `reorder` puts a task, relative to another in its column.
+#[derive(Debug, Clone)]
+pub enum ReorderAnchor {
+    Before(String),
+    After(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TaskFilter {
+    pub tags: Option<Vec<String>>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+impl TaskFilter {
+    pub fn matches(&self, task: &Task) -> bool {
+        if let Some(ref tags) = self.tags
+            && !tags.iter().any(|tag| task.has_tag(tag))
+        {
+            return false;
+        }
+
+        if let Some(after) = self.created_after
+            && task.created_at < after
+        {
+            return false;
+        }
+
+        if let Some(before) = self.created_before
+            && task.created_at > before
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// How `TaskManager::search` matches.
+#[derive(Debug, Clone, Default)]
+pub struct TaskSearch {
+    pub case_sensitive: bool,
+    pub exclude_archived: bool,
+    /// Only tasks with these tags, all or any of them by `tag_mode`.
+    pub tags: Vec<String>,
+    pub tag_mode: TagMode,
+}
+
 pub struct TaskManager {
     storage: TaskStorage,
     cache: Arc<RwLock<HashMap<String, HashMap<String, Task>>>>,
+    /// Profiles whose `last_used_at` values have been filled in from entries.
+    backfilled: RwLock<HashSet<String>>,
     event_manager: Arc<EventManager>,
 }
 
@@ -36,10 +97,42 @@ impl TaskManager {
         Ok(Self {
             storage,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            backfilled: RwLock::new(HashSet::new()),
             event_manager,
         })
     }
 
+    /// Keeps `last_used_at` current by watching for new entries.
+    pub fn spawn_usage_tracker(self: &Arc<Self>) {
+        let manager = self.clone();
+        let mut events = self.event_manager.subscribe();
+        tokio::spawn(async move {
+            loop {
+                let event = match events.recv().await {
+                    Ok(DaemonEvent::Entry(event)) => event,
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Usage tracker missed {} events", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                let (EntryEventType::Added, Some(entry)) = (&event.event_type, &event.entry) else {
+                    continue;
+                };
+                let Some(task_id) = &entry.task_id else {
+                    continue;
+                };
+                if let Err(e) = manager
+                    .record_use(&event.profile_id, task_id, entry_used_at(entry))
+                    .await
+                {
+                    tracing::debug!("Not recording use of task {}: {}", task_id, e);
+                }
+            }
+        });
+    }
+
     pub async fn load_profile(&self, profile_id: &str) -> Result<()> {
         let tasks = self.storage.load(profile_id)?;
         let mut cache = self.cache.write().await;
@@ -59,6 +152,7 @@ impl TaskManager {
             .map_err(|e| TaskManagerError::Invalid(e.to_string()))?;
 
         let tasks = self.get_all(profile_id).await?;
+        check_blockers(&tasks, &task)?;
         let mut task_list: Vec<Task> = tasks.values().cloned().collect();
         task_list.push(task.clone());
 
@@ -77,6 +171,19 @@ impl TaskManager {
         Ok(task)
     }
 
+    /// Creates `task` at the bottom of its status column.
+    pub async fn append(&self, profile_id: &str, mut task: Task) -> Result<Task> {
+        let tasks = self.get_all(profile_id).await?;
+        task.order = bottom_order(&tasks, task.status);
+        self.create(profile_id, task).await
+    }
+
+    /// Forgets every loaded profile; they are read from disk again on next
+    /// use.
+    pub async fn clear_cache(&self) {
+        self.cache.write().await.clear();
+    }
+
     pub async fn get(&self, profile_id: &str, task_id: &str) -> Result<Task> {
         {
             let cache = self.cache.read().await;
@@ -115,9 +222,106 @@ impl TaskManager {
         Ok(cache.get(profile_id).cloned().unwrap_or_default())
     }
 
+    /// The profile's tasks in board order; see `Task::board_cmp`.
     pub async fn list(&self, profile_id: &str) -> Result<Vec<Task>> {
         let tasks = self.get_all(profile_id).await?;
-        Ok(tasks.values().cloned().collect())
+        let mut tasks: Vec<Task> = tasks.values().cloned().collect();
+        tasks.sort_by(Task::board_cmp);
+        Ok(tasks)
+    }
+
+    /// Moves `task_id` next to the anchor task, which must be in the same
+    /// status column, in one write of tasks.json. The task takes an order
+    /// between its new neighbours; when they leave no room the column is
+    /// renumbered in steps of `ORDER_GAP`. Returns the moved task and the
+    /// ids of every task whose order changed.
+    pub async fn reorder(
+        &self,
+        profile_id: &str,
+        task_id: &str,
+        anchor: &ReorderAnchor,
+    ) -> Result<(Task, Vec<String>)> {
+        self.get_all(profile_id).await?;
+
+        let (moved, changed) = {
+            let mut cache = self.cache.write().await;
+            let mut tasks = cache.get(profile_id).cloned().unwrap_or_default();
+
+            let (anchor_id, after) = match anchor {
+                ReorderAnchor::Before(id) => (id, false),
+                ReorderAnchor::After(id) => (id, true),
+            };
+            let task = tasks
+                .get(task_id)
+                .ok_or_else(|| TaskManagerError::NotFound(task_id.to_string()))?;
+            let anchor = tasks
+                .get(anchor_id)
+                .ok_or_else(|| TaskManagerError::NotFound(anchor_id.clone()))?;
+            if anchor.id == task.id {
+                return Err(TaskManagerError::Invalid(
+                    "A task cannot be moved next to itself".to_string(),
+                ));
+            }
+            if anchor.status != task.status {
+                return Err(TaskManagerError::Invalid(format!(
+                    "Task {} is not in the same column as {}",
+                    anchor.id, task.id
+                )));
+            }
+
+            let mut column: Vec<&Task> = tasks
+                .values()
+                .filter(|t| t.status == task.status && t.id != task.id)
+                .collect();
+            column.sort_by(|a, b| a.board_cmp(b));
+            let index = column
+                .iter()
+                .position(|t| t.id == anchor.id)
+                .map_or(0, |i| i + usize::from(after));
+
+            let below = index.checked_sub(1).map(|i| column[i].order);
+            let above = column.get(index).map(|t| t.order);
+            let orders: Vec<(String, i64)> = match (below, above) {
+                (Some(low), Some(high)) if high - low >= 2 => {
+                    vec![(task.id.clone(), low + (high - low) / 2)]
+                }
+                (Some(low), None) => vec![(task.id.clone(), low + ORDER_GAP)],
+                (None, Some(high)) => vec![(task.id.clone(), high - ORDER_GAP)],
+                _ => {
+                    let mut ids: Vec<String> = column.iter().map(|t| t.id.clone()).collect();
+                    ids.insert(index, task.id.clone());
+                    ids.into_iter().zip((1..).map(|n| n * ORDER_GAP)).collect()
+                }
+            };
+
+            let mut changed = Vec::new();
+            for (id, order) in orders {
+                if let Some(task) = tasks.get_mut(&id)
+                    && task.order != order
+                {
+                    task.order = order;
+                    task.touch();
+                    changed.push(id);
+                }
+            }
+            let moved = tasks[task_id].clone();
+
+            if !changed.is_empty() {
+                let task_list: Vec<Task> = tasks.values().cloned().collect();
+                self.storage.save(profile_id, &task_list)?;
+                cache.insert(profile_id.to_string(), tasks);
+            }
+            (moved, changed)
+        };
+
+        if !changed.is_empty() {
+            self.event_manager.emit_task(TaskEvent::bulk_updated(
+                profile_id.to_string(),
+                changed.clone(),
+            ));
+        }
+
+        Ok((moved, changed))
     }
 
     pub async fn update(&self, profile_id: &str, mut task: Task) -> Result<Task> {
@@ -128,9 +332,22 @@ impl TaskManager {
 
         let mut tasks = self.get_all(profile_id).await?;
 
-        if !tasks.contains_key(&task.id) {
+        let Some(existing) = tasks.get(&task.id) else {
             return Err(TaskManagerError::NotFound(task.id.clone()));
+        };
+
+        // Clients send back whatever copy they hold; never lose a newer use.
+        if let Some(last_used) = existing.last_used_at {
+            task.mark_used(last_used);
+        }
+        let previous_status = existing.status;
+        let completed = !existing.is_completed() && task.is_completed();
+        // A task moved to another column without a place of its own there
+        // goes to the bottom.
+        if task.status != existing.status && task.order == existing.order {
+            task.order = bottom_order(&tasks, task.status);
         }
+        check_blockers(&tasks, &task)?;
 
         tasks.insert(task.id.clone(), task.clone());
         let task_list: Vec<Task> = tasks.values().cloned().collect();
@@ -144,12 +361,238 @@ impl TaskManager {
             }
         }
 
-        let event = TaskEvent::updated(profile_id.to_string(), task.clone());
+        let event = TaskEvent::updated_from(profile_id.to_string(), task.clone(), previous_status);
         self.event_manager.emit_task(event);
 
+        if completed {
+            self.release_blocked(profile_id, std::slice::from_ref(&task.id))
+                .await?;
+        }
+
         Ok(task)
     }
 
+    /// Drops `blocker_ids` from every task blocked by them and emits an
+    /// `unblocked` event for each task that changed.
+    async fn release_blocked(&self, profile_id: &str, blocker_ids: &[String]) -> Result<()> {
+        self.get_all(profile_id).await?;
+
+        let released = {
+            let mut cache = self.cache.write().await;
+            let mut tasks = cache.get(profile_id).cloned().unwrap_or_default();
+
+            let mut released = Vec::new();
+            for task in tasks.values_mut() {
+                let (cleared, kept): (Vec<String>, Vec<String>) = task
+                    .blocked_by
+                    .drain(..)
+                    .partition(|id| blocker_ids.contains(id));
+                task.blocked_by = kept;
+                if !cleared.is_empty() {
+                    task.touch();
+                    released.push((task.clone(), cleared));
+                }
+            }
+
+            if !released.is_empty() {
+                let task_list: Vec<Task> = tasks.values().cloned().collect();
+                self.storage.save(profile_id, &task_list)?;
+                cache.insert(profile_id.to_string(), tasks);
+            }
+            released
+        };
+
+        for (task, cleared) in released {
+            let event = TaskEvent::unblocked(profile_id.to_string(), task, cleared);
+            self.event_manager.emit_task(event);
+        }
+        Ok(())
+    }
+
+    /// Every task standing in the way of `task_id`, nearest first, with how
+    /// many `blocked_by` links away it is.
+    pub async fn blockers(&self, profile_id: &str, task_id: &str) -> Result<Vec<(usize, Task)>> {
+        let tasks = self.get_all(profile_id).await?;
+        let task = tasks
+            .get(task_id)
+            .ok_or_else(|| TaskManagerError::NotFound(task_id.to_string()))?;
+
+        let mut seen: HashSet<&str> = HashSet::from([task_id]);
+        let mut queue: VecDeque<(usize, &str)> =
+            task.blocked_by.iter().map(|id| (1, id.as_str())).collect();
+        let mut chain = Vec::new();
+        while let Some((depth, id)) = queue.pop_front() {
+            if !seen.insert(id) {
+                continue;
+            }
+            let Some(blocker) = tasks.get(id) else {
+                continue;
+            };
+            queue.extend(blocker.blocked_by.iter().map(|id| (depth + 1, id.as_str())));
+            chain.push((depth, blocker.clone()));
+        }
+        Ok(chain)
+    }
+
+    /// Replaces only the description, so callers holding an older copy of
+    /// the task cannot overwrite other fields.
+    pub async fn set_description(
+        &self,
+        profile_id: &str,
+        task_id: &str,
+        description: Option<String>,
+    ) -> Result<Task> {
+        let mut task = self.get(profile_id, task_id).await?;
+        task.update_description(description);
+        self.update(profile_id, task).await
+    }
+
+    /// Replaces only the notes; see [`TaskManager::set_description`].
+    pub async fn set_notes(
+        &self,
+        profile_id: &str,
+        task_id: &str,
+        notes: Option<String>,
+    ) -> Result<Task> {
+        let mut task = self.get(profile_id, task_id).await?;
+        task.update_notes(notes);
+        self.update(profile_id, task).await
+    }
+
+    /// Moves the task's `last_used_at` forward to `at`. Returns the task if
+    /// it changed.
+    pub async fn record_use(
+        &self,
+        profile_id: &str,
+        task_id: &str,
+        at: DateTime<Utc>,
+    ) -> Result<Option<Task>> {
+        self.get_all(profile_id).await?;
+
+        let updated = {
+            let mut cache = self.cache.write().await;
+            let mut tasks = cache.get(profile_id).cloned().unwrap_or_default();
+            let task = tasks
+                .get_mut(task_id)
+                .ok_or_else(|| TaskManagerError::NotFound(task_id.to_string()))?;
+            if !task.mark_used(at) {
+                return Ok(None);
+            }
+            let task = task.clone();
+
+            let task_list: Vec<Task> = tasks.values().cloned().collect();
+            self.storage.save(profile_id, &task_list)?;
+            cache.insert(profile_id.to_string(), tasks);
+            task
+        };
+
+        let event = TaskEvent::updated(profile_id.to_string(), updated.clone());
+        self.event_manager.emit_task(event);
+
+        Ok(Some(updated))
+    }
+
+    /// Fills in `last_used_at` from existing entries, once per profile.
+    /// Tasks created before the field existed would otherwise look unused.
+    pub async fn backfill_last_used(
+        &self,
+        profile_id: &str,
+        entry_manager: &EntryManager,
+    ) -> Result<()> {
+        if self.backfilled.read().await.contains(profile_id) {
+            return Ok(());
+        }
+
+        let entries = match entry_manager.get_all(profile_id).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("Cannot backfill task usage for {}: {}", profile_id, e);
+                return Ok(());
+            }
+        };
+
+        let mut latest: HashMap<&str, DateTime<Utc>> = HashMap::new();
+        for entry in &entries {
+            if let Some(task_id) = &entry.task_id {
+                let used_at = entry_used_at(entry);
+                latest
+                    .entry(task_id)
+                    .and_modify(|at| *at = (*at).max(used_at))
+                    .or_insert(used_at);
+            }
+        }
+
+        self.get_all(profile_id).await?;
+        {
+            let mut cache = self.cache.write().await;
+            let mut tasks = cache.get(profile_id).cloned().unwrap_or_default();
+
+            let mut changed = false;
+            for task in tasks.values_mut() {
+                if let Some(at) = latest.get(task.id.as_str()) {
+                    changed |= task.mark_used(*at);
+                }
+            }
+
+            if changed {
+                let task_list: Vec<Task> = tasks.values().cloned().collect();
+                self.storage.save(profile_id, &task_list)?;
+                cache.insert(profile_id.to_string(), tasks);
+            }
+        }
+
+        self.backfilled.write().await.insert(profile_id.to_string());
+        Ok(())
+    }
+
+    /// Open tasks with no use since `cutoff`, least recently used first.
+    /// Tasks that were never used count from their creation.
+    pub async fn stale(&self, profile_id: &str, cutoff: DateTime<Utc>) -> Result<Vec<Task>> {
+        let mut tasks: Vec<Task> = self
+            .list(profile_id)
+            .await?
+            .into_iter()
+            .filter(|task| !matches!(task.status, TaskStatus::Done | TaskStatus::Archived))
+            .filter(|task| task.last_used_at.unwrap_or(task.created_at) < cutoff)
+            .collect();
+        tasks.sort_by_key(|task| task.last_used_at.unwrap_or(task.created_at));
+        Ok(tasks)
+    }
+
+    /// Open tasks due within `days_ahead` days of `today` (any time ahead
+    /// when `None`), soonest first, so overdue ones lead. Tasks without a
+    /// due date follow at the end if asked for.
+    pub async fn by_due_date(
+        &self,
+        profile_id: &str,
+        today: NaiveDate,
+        include_overdue: bool,
+        include_no_due_date: bool,
+        days_ahead: Option<u32>,
+    ) -> Result<Vec<Task>> {
+        let horizon = days_ahead.map(|days| today + chrono::Duration::days(days as i64));
+        let mut tasks: Vec<Task> = self
+            .list(profile_id)
+            .await?
+            .into_iter()
+            .filter(|task| !matches!(task.status, TaskStatus::Done | TaskStatus::Archived))
+            .filter(|task| match task.due_date {
+                None => include_no_due_date,
+                Some(due) if due < today => include_overdue,
+                Some(due) => horizon.is_none_or(|h| due <= h),
+            })
+            .collect();
+        // `None` sorts first, so put it behind every date explicitly.
+        tasks.sort_by(|a, b| {
+            (a.due_date.is_none(), a.due_date, &a.title).cmp(&(
+                b.due_date.is_none(),
+                b.due_date,
+                &b.title,
+            ))
+        });
+        Ok(tasks)
+    }
+
     pub async fn delete(&self, profile_id: &str, task_id: &str) -> Result<()> {
         let mut tasks = self.get_all(profile_id).await?;
 
@@ -172,26 +615,161 @@ impl TaskManager {
         let event = TaskEvent::deleted(profile_id.to_string(), task_id.to_string());
         self.event_manager.emit_task(event);
 
+        self.release_blocked(profile_id, &[task_id.to_string()])
+            .await?;
+
         Ok(())
     }
 
-    pub async fn search(&self, profile_id: &str, query: &str) -> Result<Vec<Task>> {
-        let tasks = self.list(profile_id).await?;
-        let query_lower = query.to_lowercase();
+    /// Moves every task in `from` (optionally narrowed by `filter`) to `to`.
+    ///
+    /// The cache lock is held across the read-modify-save so the whole batch
+    /// lands in a single write of tasks.json.
+    pub async fn bulk_update_status(
+        &self,
+        profile_id: &str,
+        from: TaskStatus,
+        to: TaskStatus,
+        filter: Option<&TaskFilter>,
+    ) -> Result<Vec<Task>> {
+        self.get_all(profile_id).await?;
+
+        let updated = {
+            let mut cache = self.cache.write().await;
+            let mut tasks = cache.get(profile_id).cloned().unwrap_or_default();
+
+            let mut updated = Vec::new();
+            for task in tasks.values_mut() {
+                if task.status == from && filter.is_none_or(|f| f.matches(task)) {
+                    task.update_status(to);
+                    updated.push(task.clone());
+                }
+            }
+
+            if !updated.is_empty() {
+                let task_list: Vec<Task> = tasks.values().cloned().collect();
+                self.storage.save(profile_id, &task_list)?;
+                cache.insert(profile_id.to_string(), tasks);
+            }
+
+            updated
+        };
+
+        for task in &updated {
+            let event = TaskEvent::updated_from(profile_id.to_string(), task.clone(), from);
+            self.event_manager.emit_task(event);
+        }
+
+        if to == TaskStatus::Done && from != TaskStatus::Done {
+            let completed: Vec<String> = updated.iter().map(|t| t.id.clone()).collect();
+            self.release_blocked(profile_id, &completed).await?;
+        }
+
+        Ok(updated)
+    }
+
+    /// Adds `tags` to each of `task_ids`, skipping tags a task already has.
+    pub async fn bulk_tag(
+        &self,
+        profile_id: &str,
+        task_ids: &[String],
+        tags: &[String],
+    ) -> Result<Vec<Task>> {
+        self.bulk_edit_tags(profile_id, task_ids, |task| {
+            for tag in tags {
+                task.add_tag(tag.clone());
+            }
+        })
+        .await
+    }
+
+    /// Removes `tags` from each of `task_ids`.
+    pub async fn bulk_untag(
+        &self,
+        profile_id: &str,
+        task_ids: &[String],
+        tags: &[String],
+    ) -> Result<Vec<Task>> {
+        self.bulk_edit_tags(profile_id, task_ids, |task| {
+            for tag in tags {
+                task.remove_tag(tag);
+            }
+        })
+        .await
+    }
+
+    /// Applies `edit` to every task in `task_ids` in one write of
+    /// tasks.json, and announces the tasks it changed with a single
+    /// `bulk_updated` event. Fails without changing anything if any id is
+    /// unknown.
+    async fn bulk_edit_tags(
+        &self,
+        profile_id: &str,
+        task_ids: &[String],
+        edit: impl Fn(&mut Task),
+    ) -> Result<Vec<Task>> {
+        self.get_all(profile_id).await?;
+
+        let updated = {
+            let mut cache = self.cache.write().await;
+            let mut tasks = cache.get(profile_id).cloned().unwrap_or_default();
 
-        Ok(tasks
+            if let Some(missing) = task_ids.iter().find(|id| !tasks.contains_key(*id)) {
+                return Err(TaskManagerError::NotFound(missing.clone()));
+            }
+
+            let mut updated: Vec<Task> = Vec::new();
+            for id in task_ids {
+                if updated.iter().any(|t| &t.id == id) {
+                    continue;
+                }
+                let Some(task) = tasks.get_mut(id) else {
+                    continue;
+                };
+                let before = task.tags.clone();
+                edit(task);
+                if task.tags != before {
+                    updated.push(task.clone());
+                }
+            }
+
+            if !updated.is_empty() {
+                let task_list: Vec<Task> = tasks.values().cloned().collect();
+                self.storage.save(profile_id, &task_list)?;
+                cache.insert(profile_id.to_string(), tasks);
+            }
+
+            updated
+        };
+
+        if !updated.is_empty() {
+            let task_ids = updated.iter().map(|t| t.id.clone()).collect();
+            self.event_manager
+                .emit_task(TaskEvent::bulk_updated(profile_id.to_string(), task_ids));
+        }
+
+        Ok(updated)
+    }
+
+    /// Tasks fuzzily matching `query` in their title, tags or description
+    /// and carrying the tags `options` asks for, best match first.
+    pub async fn search(
+        &self,
+        profile_id: &str,
+        query: &str,
+        options: TaskSearch,
+    ) -> Result<Vec<(Task, TaskMatch)>> {
+        let tasks = self.list(profile_id).await?;
+        let candidates = tasks.iter().filter(|task| {
+            !(options.exclude_archived && task.status == TaskStatus::Archived)
+                && options
+                    .tag_mode
+                    .matches(&options.tags, |tag| task.has_tag(tag))
+        });
+
+        Ok(rank_tasks(query, candidates, options.case_sensitive)
             .into_iter()
-            .filter(|task| {
-                task.title.to_lowercase().contains(&query_lower)
-                    || task
-                        .description
-                        .as_ref()
-                        .is_some_and(|d| d.to_lowercase().contains(&query_lower))
-                    || task
-                        .tags
-                        .iter()
-                        .any(|t| t.to_lowercase().contains(&query_lower))
-            })
+            .map(|(task, matched)| (task.clone(), matched))
             .collect())
     }
 
@@ -221,7 +799,9 @@ impl TaskManager {
             ));
         }
 
-        let task = self.get(source_profile_id, task_id).await?;
+        // Blockers only link tasks within one profile.
+        let mut task = self.get(source_profile_id, task_id).await?;
+        task.blocked_by.clear();
 
         {
             let mut source_tasks = self.get_all(source_profile_id).await?;
@@ -260,10 +840,59 @@ impl TaskManager {
         );
         self.event_manager.emit_task(event);
 
+        self.release_blocked(source_profile_id, &[task_id.to_string()])
+            .await?;
+
         Ok((task, entries_moved))
     }
 }
 
+/// An order below every task in the `status` column.
+fn bottom_order(tasks: &HashMap<String, Task>, status: TaskStatus) -> i64 {
+    tasks
+        .values()
+        .filter(|t| t.status == status)
+        .map(|t| t.order + ORDER_GAP)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Checks that `task`'s blockers are other tasks in `tasks` (its profile)
+/// and that none of them is, directly or through others, blocked by `task`.
+fn check_blockers(tasks: &HashMap<String, Task>, task: &Task) -> Result<()> {
+    for id in &task.blocked_by {
+        if !tasks.contains_key(id) || *id == task.id {
+            return Err(TaskManagerError::Invalid(format!(
+                "Blocking task {} not found in this profile",
+                id
+            )));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut stack: Vec<&str> = task.blocked_by.iter().map(String::as_str).collect();
+    while let Some(id) = stack.pop() {
+        if id == task.id {
+            return Err(TaskManagerError::Invalid(format!(
+                "Blocking tasks would form a cycle through {}",
+                task.id
+            )));
+        }
+        if seen.insert(id)
+            && let Some(blocker) = tasks.get(id)
+        {
+            stack.extend(blocker.blocked_by.iter().map(String::as_str));
+        }
+    }
+    Ok(())
+}
+
+/// When an entry counts as use of its task: its end, or its start while it
+/// is still running.
+fn entry_used_at(entry: &Entry) -> DateTime<Utc> {
+    entry.end_time.unwrap_or(entry.start_time)
+}
+
 impl Default for TaskManager {
     fn default() -> Self {
         Self::new(Arc::new(crate::event_manager::EventManager::new()))
@@ -275,6 +904,7 @@ impl Default for TaskManager {
 mod tests {
     use super::*;
     use crate::event_manager::EventManager;
+    use crate::events::TaskEventType;
     use mootimer_core::models::Task;
     use serial_test::serial;
     use std::sync::Arc;
@@ -343,6 +973,219 @@ mod tests {
         assert_eq!(updated.title, "New Title");
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_set_description_keeps_other_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let task = Task::new("Original".to_string()).unwrap();
+        let stale = manager.create(TEST_PROFILE, task).await.unwrap();
+
+        let mut renamed = stale.clone();
+        renamed.title = "Renamed".to_string();
+        manager.update(TEST_PROFILE, renamed).await.unwrap();
+
+        let updated = manager
+            .set_description(TEST_PROFILE, &stale.id, Some("Details".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(updated.title, "Renamed");
+        assert_eq!(updated.description.as_deref(), Some("Details"));
+
+        let updated = manager
+            .set_notes(TEST_PROFILE, &stale.id, Some("Remember".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(updated.description.as_deref(), Some("Details"));
+        assert_eq!(updated.notes.as_deref(), Some("Remember"));
+
+        assert!(
+            manager
+                .set_notes(TEST_PROFILE, "missing", None)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_usage_tracking_and_stale() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = Arc::new(create_manager(&temp_dir));
+        let entry_manager = EntryManager::new(manager.event_manager.clone()).unwrap();
+
+        let now = Utc::now();
+        let old = manager
+            .create(TEST_PROFILE, Task::new("Old".to_string()).unwrap())
+            .await
+            .unwrap();
+        let fresh = manager
+            .create(TEST_PROFILE, Task::new("Fresh".to_string()).unwrap())
+            .await
+            .unwrap();
+
+        // Entries logged before the tracker existed are picked up by backfill.
+        let start = now - chrono::Duration::days(40);
+        let entry = Entry::create_completed(
+            Some(old.id.clone()),
+            None,
+            start,
+            start + chrono::Duration::hours(1),
+            mootimer_core::models::TimerMode::Manual,
+        )
+        .unwrap();
+        entry_manager.add(TEST_PROFILE, entry).await.unwrap();
+        manager
+            .backfill_last_used(TEST_PROFILE, &entry_manager)
+            .await
+            .unwrap();
+        let backfilled = manager.get(TEST_PROFILE, &old.id).await.unwrap();
+        assert_eq!(
+            backfilled.last_used_at,
+            Some(start + chrono::Duration::hours(1))
+        );
+
+        // A client saving an older copy must not roll usage back.
+        manager.update(TEST_PROFILE, old.clone()).await.unwrap();
+        let reloaded = manager.get(TEST_PROFILE, &old.id).await.unwrap();
+        assert_eq!(reloaded.last_used_at, backfilled.last_used_at);
+
+        manager.spawn_usage_tracker();
+        let entry = Entry::create_completed(
+            Some(fresh.id.clone()),
+            None,
+            now - chrono::Duration::hours(1),
+            now,
+            mootimer_core::models::TimerMode::Manual,
+        )
+        .unwrap();
+        entry_manager.add(TEST_PROFILE, entry).await.unwrap();
+        for _ in 0..50 {
+            if manager
+                .get(TEST_PROFILE, &fresh.id)
+                .await
+                .unwrap()
+                .last_used_at
+                .is_some()
+            {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let tracked = manager.get(TEST_PROFILE, &fresh.id).await.unwrap();
+        assert_eq!(tracked.last_used_at, Some(now));
+
+        let stale = manager
+            .stale(TEST_PROFILE, now - chrono::Duration::days(30))
+            .await
+            .unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].id, old.id);
+
+        let mut done = reloaded;
+        done.update_status(TaskStatus::Done);
+        manager.update(TEST_PROFILE, done).await.unwrap();
+        assert!(
+            manager
+                .stale(TEST_PROFILE, now - chrono::Duration::days(30))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_by_due_date() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let today = NaiveDate::from_ymd_opt(2024, 6, 10).unwrap();
+
+        let mut ids = HashMap::new();
+        for (title, days) in [
+            ("overdue", Some(-2)),
+            ("today", Some(0)),
+            ("soon", Some(3)),
+            ("later", Some(30)),
+            ("someday", None),
+        ] {
+            let mut task = Task::new(title.to_string()).unwrap();
+            task.due_date = days.map(|d| today + chrono::Duration::days(d));
+            ids.insert(title, manager.create(TEST_PROFILE, task).await.unwrap().id);
+        }
+        let mut done = Task::new("done".to_string()).unwrap();
+        done.due_date = Some(today);
+        done.update_status(TaskStatus::Done);
+        manager.create(TEST_PROFILE, done).await.unwrap();
+
+        let titles = |tasks: Vec<Task>| tasks.into_iter().map(|t| t.title).collect::<Vec<_>>();
+
+        let all = manager
+            .by_due_date(TEST_PROFILE, today, true, true, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            titles(all),
+            ["overdue", "today", "soon", "later", "someday"]
+        );
+
+        let upcoming = manager
+            .by_due_date(TEST_PROFILE, today, false, false, Some(7))
+            .await
+            .unwrap();
+        assert_eq!(titles(upcoming), ["today", "soon"]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_blockers() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let create = |title: &str, blocked_by: Vec<String>| {
+            let mut task = Task::new(title.to_string()).unwrap();
+            task.blocked_by = blocked_by;
+            manager.create(TEST_PROFILE, task)
+        };
+
+        let design = create("Design", vec![]).await.unwrap();
+        let build = create("Build", vec![design.id.clone()]).await.unwrap();
+        let ship = create("Ship", vec![build.id.clone()]).await.unwrap();
+        assert!(create("Orphan", vec!["missing".to_string()]).await.is_err());
+
+        let chain = manager.blockers(TEST_PROFILE, &ship.id).await.unwrap();
+        let chain: Vec<(usize, &str)> = chain.iter().map(|(d, t)| (*d, t.title.as_str())).collect();
+        assert_eq!(chain, [(1, "Build"), (2, "Design")]);
+
+        let mut cyclic = design.clone();
+        cyclic.blocked_by = vec![ship.id.clone()];
+        assert!(matches!(
+            manager.update(TEST_PROFILE, cyclic).await,
+            Err(TaskManagerError::Invalid(_))
+        ));
+
+        let mut events = manager.event_manager.subscribe();
+        let mut done = manager.get(TEST_PROFILE, &design.id).await.unwrap();
+        done.complete();
+        manager.update(TEST_PROFILE, done).await.unwrap();
+
+        let build = manager.get(TEST_PROFILE, &build.id).await.unwrap();
+        assert!(!build.is_blocked());
+        let unblocked = std::iter::from_fn(|| events.try_recv().ok()).any(|event| {
+            matches!(
+                event,
+                DaemonEvent::Task(TaskEvent {
+                    event_type: TaskEventType::Unblocked { ref blocker_ids },
+                    ..
+                }) if *blocker_ids == [design.id.clone()]
+            )
+        });
+        assert!(unblocked);
+
+        manager.delete(TEST_PROFILE, &build.id).await.unwrap();
+        let ship = manager.get(TEST_PROFILE, &ship.id).await.unwrap();
+        assert!(ship.blocked_by.is_empty());
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_delete_task() {
@@ -370,9 +1213,108 @@ mod tests {
         manager.create(TEST_PROFILE, task1).await.unwrap();
         manager.create(TEST_PROFILE, task2).await.unwrap();
 
-        let results = manager.search(TEST_PROFILE, "First").await.unwrap();
+        let results = manager
+            .search(TEST_PROFILE, "First", TaskSearch::default())
+            .await
+            .unwrap();
         assert_eq!(results.len(), 1);
-        assert_eq!(results[0].title, "My First Task");
+        assert_eq!(results[0].0.title, "My First Task");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_search_tasks_fuzzy_ranked() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        for title in ["Fix login bug", "Log billing hours", "Plan next sprint"] {
+            manager
+                .create(TEST_PROFILE, Task::new(title.to_string()).unwrap())
+                .await
+                .unwrap();
+        }
+        let mut archived = Task::new("Old login bug report".to_string()).unwrap();
+        archived.status = TaskStatus::Archived;
+        manager.create(TEST_PROFILE, archived).await.unwrap();
+
+        let titles = |results: Vec<(Task, TaskMatch)>| {
+            results
+                .into_iter()
+                .map(|(task, _)| task.title)
+                .collect::<Vec<_>>()
+        };
+        let results = manager
+            .search(TEST_PROFILE, "lgn bug", TaskSearch::default())
+            .await
+            .unwrap();
+        assert_eq!(titles(results), ["Fix login bug", "Old login bug report"]);
+
+        let options = TaskSearch {
+            exclude_archived: true,
+            ..Default::default()
+        };
+        let results = manager
+            .search(TEST_PROFILE, "lgn bug", options)
+            .await
+            .unwrap();
+        assert_eq!(
+            results[0].1.matched.spans,
+            [(4, 5), (6, 7), (8, 9), (10, 13)]
+        );
+        assert_eq!(titles(results), ["Fix login bug"]);
+
+        let options = TaskSearch {
+            case_sensitive: true,
+            ..Default::default()
+        };
+        let results = manager.search(TEST_PROFILE, "log", options).await.unwrap();
+        assert_eq!(titles(results), ["Fix login bug", "Old login bug report"]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_search_tasks_by_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        for (title, tags) in [
+            ("Refactor auth", vec!["backend", "urgent"]),
+            ("Refactor styles", vec!["frontend", "urgent"]),
+            ("Refactor queue", vec!["backend"]),
+            ("Write changelog", vec!["backend", "urgent"]),
+        ] {
+            let mut task = Task::new(title.to_string()).unwrap();
+            task.tags = tags.into_iter().map(str::to_string).collect();
+            manager.create(TEST_PROFILE, task).await.unwrap();
+        }
+
+        let search = |tags: &[&str], tag_mode| {
+            let options = TaskSearch {
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+                tag_mode,
+                ..Default::default()
+            };
+            let manager = &manager;
+            async move {
+                let mut titles: Vec<String> = manager
+                    .search(TEST_PROFILE, "refactor", options)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|(task, _)| task.title)
+                    .collect();
+                titles.sort();
+                titles
+            }
+        };
+
+        assert_eq!(
+            search(&["backend", "urgent"], TagMode::All).await,
+            ["Refactor auth"]
+        );
+        assert_eq!(
+            search(&["frontend", "backend"], TagMode::Any).await,
+            ["Refactor auth", "Refactor queue", "Refactor styles"]
+        );
+        assert_eq!(search(&["ops"], TagMode::Any).await, Vec::<String>::new());
     }
 
     #[tokio::test]
@@ -454,4 +1396,104 @@ mod tests {
                 .contains("Source and target profiles must be different")
         );
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_bulk_update_status() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+        let profile = "bulk_profile";
+
+        let mut done = Task::new("Done".to_string()).unwrap();
+        done.update_status(TaskStatus::Done);
+        done.add_tag("sprint".to_string());
+        let mut done_untagged = Task::new("Done untagged".to_string()).unwrap();
+        done_untagged.update_status(TaskStatus::Done);
+        let todo = Task::new("Todo".to_string()).unwrap();
+
+        let done = manager.create(profile, done).await.unwrap();
+        let done_untagged = manager.create(profile, done_untagged).await.unwrap();
+        let todo = manager.create(profile, todo).await.unwrap();
+
+        let filter = TaskFilter {
+            tags: Some(vec!["sprint".to_string()]),
+            ..Default::default()
+        };
+        let updated = manager
+            .bulk_update_status(
+                profile,
+                TaskStatus::Done,
+                TaskStatus::Archived,
+                Some(&filter),
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].id, done.id);
+
+        let updated = manager
+            .bulk_update_status(profile, TaskStatus::Done, TaskStatus::Archived, None)
+            .await
+            .unwrap();
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].id, done_untagged.id);
+
+        let reloaded = TaskStorage::new(init_data_dir().unwrap())
+            .load(profile)
+            .unwrap();
+        let status_of = |id: &str| reloaded.iter().find(|t| t.id == id).unwrap().status;
+        assert_eq!(status_of(&done.id), TaskStatus::Archived);
+        assert_eq!(status_of(&done_untagged.id), TaskStatus::Archived);
+        assert_eq!(status_of(&todo.id), TaskStatus::Todo);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_bulk_tag_and_untag() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+
+        let mut tagged = Task::new("Tagged".to_string()).unwrap();
+        tagged.add_tag("q3".to_string());
+        let tagged = manager.create(TEST_PROFILE, tagged).await.unwrap();
+        let plain = manager
+            .create(TEST_PROFILE, Task::new("Plain".to_string()).unwrap())
+            .await
+            .unwrap();
+        let ids = vec![tagged.id.clone(), plain.id.clone()];
+
+        let mut events = manager.event_manager.subscribe();
+        let tags = vec!["q3".to_string(), "infra".to_string()];
+        let updated = manager.bulk_tag(TEST_PROFILE, &ids, &tags).await.unwrap();
+        assert_eq!(updated.len(), 2);
+        let plain_now = manager.get(TEST_PROFILE, &plain.id).await.unwrap();
+        assert_eq!(plain_now.tags, vec!["q3", "infra"]);
+        let tagged_now = manager.get(TEST_PROFILE, &tagged.id).await.unwrap();
+        assert_eq!(tagged_now.tags, vec!["q3", "infra"]);
+
+        let bulk_events: Vec<_> = std::iter::from_fn(|| events.try_recv().ok())
+            .filter_map(|event| match event {
+                DaemonEvent::Task(TaskEvent {
+                    event_type: TaskEventType::BulkUpdated { task_ids },
+                    ..
+                }) => Some(task_ids),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(bulk_events, vec![ids.clone()]);
+
+        let updated = manager
+            .bulk_untag(TEST_PROFILE, &ids, &["infra".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(updated.len(), 2);
+        let plain_now = manager.get(TEST_PROFILE, &plain.id).await.unwrap();
+        assert_eq!(plain_now.tags, vec!["q3"]);
+
+        let unknown = vec![plain.id.clone(), "missing".to_string()];
+        assert!(matches!(
+            manager.bulk_tag(TEST_PROFILE, &unknown, &tags).await,
+            Err(TaskManagerError::NotFound(_))
+        ));
+    }
 }
@@ -1,3 +1,3 @@
 pub mod manager;
 
-pub use manager::TaskManager;
+pub use manager::{ReorderAnchor, TaskFilter, TaskManager, TaskManagerError, TaskSearch};
@@ -0,0 +1,140 @@
+//! An in-process daemon for end-to-end tests (feature `test-util`).
+//!
+//! [`TestDaemon::start`] points `HOME` and the XDG directories at a fresh
+//! temp dir, wires the managers together the same way `mootimerd` does and
+//! serves the API on a socket inside that dir. Because the data directory is
+//! taken from the environment, tests in the same binary that start a daemon
+//! must not run concurrently; mark them `#[serial]`.
+//!
+//! Other crates can use it through a dev-dependency:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! mootimer-daemon = { workspace = true, features = ["test-util"] }
+//! ```
+
+use anyhow::{Context, Result, bail};
+use mootimer_client::{MooTimerClient, Notification};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::{
+    ApiHandler, ConfigManager, EntryManager, EventManager, IpcServer, ProfileManager, SyncManager,
+    TaskManager, TimerManager,
+};
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A running daemon with its own data directory and a connected client.
+/// Dropping it stops the server and deletes the data directory.
+pub struct TestDaemon {
+    pub client: MooTimerClient,
+    socket_path: PathBuf,
+    server: JoinHandle<()>,
+    data_dir: TempDir,
+}
+
+impl TestDaemon {
+    pub async fn start() -> Result<Self> {
+        let data_dir = TempDir::new()?;
+        unsafe {
+            std::env::set_var("HOME", data_dir.path());
+            std::env::set_var("XDG_DATA_HOME", data_dir.path().join("data"));
+            std::env::set_var("XDG_CONFIG_HOME", data_dir.path().join("config"));
+        }
+
+        let event_manager = Arc::new(EventManager::new());
+        let mut timer_manager = Arc::new(TimerManager::new(event_manager.clone()));
+        let profile_manager = Arc::new(ProfileManager::new(event_manager.clone())?);
+        profile_manager.load_all().await?;
+        let task_manager = Arc::new(TaskManager::new(event_manager.clone())?);
+        task_manager.spawn_usage_tracker();
+        if let Some(tm) = Arc::get_mut(&mut timer_manager) {
+            tm.set_task_manager(task_manager.clone());
+        }
+        let entry_manager = Arc::new(EntryManager::new(event_manager.clone())?);
+        let config_manager = Arc::new(ConfigManager::new(event_manager.clone())?);
+        let sync_manager = Arc::new(SyncManager::new()?);
+
+        let api_handler = Arc::new(ApiHandler::new(
+            event_manager,
+            timer_manager,
+            profile_manager,
+            task_manager,
+            entry_manager,
+            config_manager,
+            sync_manager,
+        ));
+
+        let socket_path = data_dir.path().join("mootimer-test.sock");
+        let server = Arc::new(IpcServer::new(
+            socket_path.to_string_lossy().to_string(),
+            api_handler,
+        ));
+        let server = tokio::spawn(async move {
+            if let Err(e) = server.start().await {
+                tracing::error!("Test daemon stopped: {}", e);
+            }
+        });
+
+        let client = MooTimerClient::new(socket_path.to_string_lossy());
+        let deadline = tokio::time::Instant::now() + STARTUP_TIMEOUT;
+        while client.health_check().await.is_err() {
+            if tokio::time::Instant::now() >= deadline {
+                server.abort();
+                bail!("Test daemon did not start on {}", socket_path.display());
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        Ok(Self {
+            client,
+            socket_path,
+            server,
+            data_dir,
+        })
+    }
+
+    /// A second, independent client for the same daemon.
+    pub fn connect(&self) -> MooTimerClient {
+        MooTimerClient::new(self.socket_path.to_string_lossy())
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        self.data_dir.path()
+    }
+}
+
+impl Drop for TestDaemon {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+/// Waits up to `timeout` for a notification named `method` whose params
+/// satisfy `matches`, skipping everything else.
+pub async fn wait_for_notification(
+    notifications: &mut mpsc::Receiver<Notification>,
+    method: &str,
+    timeout: Duration,
+    mut matches: impl FnMut(&serde_json::Value) -> bool,
+) -> Result<Notification> {
+    tokio::time::timeout(timeout, async {
+        while let Some(notification) = notifications.recv().await {
+            if notification.method == method && matches(&notification.params) {
+                return Ok(notification);
+            }
+        }
+        bail!("Notification stream closed")
+    })
+    .await
+    .with_context(|| format!("Timed out waiting for {}", method))?
+}
@@ -0,0 +1,110 @@
+//! Time sources for running timers.
+//!
+//! Durations are measured on the monotonic clock, which only moves forward
+//! at a steady rate. The wall clock is read for the timestamps stored on
+//! entries, and compared against the monotonic one to notice when it was
+//! changed under a running timer: an NTP correction after resume, or the
+//! user setting the time by hand. On Linux the monotonic clock also stands
+//! still while the machine is suspended, so a timer left running over a
+//! suspend doesn't count the time asleep.
+
+use chrono::{DateTime, Utc};
+use std::time::{Duration, Instant};
+
+/// Smallest gap between wall and monotonic time treated as the clock being
+/// changed, rather than a late tick or ordinary NTP slewing.
+pub const CLOCK_JUMP_THRESHOLD_SECONDS: i64 = 30;
+
+pub trait Clock: Send + Sync {
+    /// Wall-clock time.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Time since an arbitrary fixed point, unaffected by clock changes.
+    fn monotonic(&self) -> Duration;
+}
+
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn monotonic(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// Wall and monotonic readings taken together, to measure how far the two
+/// clocks drift apart afterwards.
+#[derive(Debug, Clone, Copy)]
+pub struct ClockReading {
+    wall: DateTime<Utc>,
+    monotonic: Duration,
+}
+
+impl ClockReading {
+    pub fn take(clock: &dyn Clock) -> Self {
+        Self {
+            wall: clock.now(),
+            monotonic: clock.monotonic(),
+        }
+    }
+
+    /// Seconds the wall clock moved beyond the time that passed between
+    /// `self` and `later`: positive when it was set forward.
+    pub fn skew_to(&self, later: &ClockReading) -> i64 {
+        let wall = later.wall.signed_duration_since(self.wall);
+        let monotonic = chrono::Duration::from_std(later.monotonic.saturating_sub(self.monotonic))
+            .unwrap_or(chrono::Duration::MAX);
+        ((wall - monotonic).num_milliseconds() as f64 / 1000.0).round() as i64
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// The system clock, with the wall clock and monotonic time movable by
+    /// hand.
+    #[derive(Default)]
+    pub struct ManualClock {
+        system: SystemClock,
+        wall_offset: Mutex<chrono::Duration>,
+        monotonic_offset: Mutex<Duration>,
+    }
+
+    impl ManualClock {
+        /// Sets the wall clock `seconds` forward, or back if negative.
+        pub fn jump(&self, seconds: i64) {
+            *self.wall_offset.lock().unwrap() += chrono::Duration::seconds(seconds);
+        }
+
+        /// Lets `seconds` pass on both clocks.
+        pub fn advance(&self, seconds: u64) {
+            self.jump(seconds as i64);
+            *self.monotonic_offset.lock().unwrap() += Duration::from_secs(seconds);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.system.now() + *self.wall_offset.lock().unwrap()
+        }
+
+        fn monotonic(&self) -> Duration {
+            self.system.monotonic() + *self.monotonic_offset.lock().unwrap()
+        }
+    }
+}
@@ -1,9 +1,13 @@
-use chrono::Utc;
-use mootimer_core::models::{ActiveTimer, Entry, PomodoroConfig};
-use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use mootimer_core::models::{
+    ActiveTimer, BreakRecord, BreakStatus, Entry, PomodoroConfig, TimerMode,
+    entry::MAX_ENTRY_SECONDS,
+};
+use std::sync::{Arc, Mutex};
 use tokio::sync::{RwLock, broadcast};
 use tokio::time::{Duration, interval};
 
+use super::clock::{CLOCK_JUMP_THRESHOLD_SECONDS, Clock, ClockReading, SystemClock};
 use super::events::{TimerEvent, TimerEventType};
 
 #[derive(Debug, thiserror::Error)]
@@ -23,10 +27,25 @@ pub enum TimerEngineError {
 
 pub type Result<T> = std::result::Result<T, TimerEngineError>;
 
+/// The few fields of a timer a status bar shows, read without copying the
+/// whole `ActiveTimer`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerPeek {
+    pub running: bool,
+    pub elapsed_seconds: u64,
+    pub remaining_seconds: Option<u64>,
+    pub mode: TimerMode,
+    pub task_title: Option<String>,
+    pub start_time: DateTime<Utc>,
+}
+
 pub struct TimerEngine {
     timer: Arc<RwLock<ActiveTimer>>,
     event_tx: broadcast::Sender<TimerEvent>,
     tick_interval: Duration,
+    clock: Arc<dyn Clock>,
+    /// Both clocks as of the last check for a clock change.
+    last_reading: Mutex<ClockReading>,
 }
 
 impl TimerEngine {
@@ -37,11 +56,7 @@ impl TimerEngine {
         event_tx: broadcast::Sender<TimerEvent>,
     ) -> Self {
         let timer = ActiveTimer::new_manual(profile_id, task_id, task_title);
-        Self {
-            timer: Arc::new(RwLock::new(timer)),
-            event_tx,
-            tick_interval: Duration::from_secs(1),
-        }
+        Self::from_timer(timer, event_tx)
     }
 
     pub fn new_pomodoro(
@@ -52,11 +67,7 @@ impl TimerEngine {
         event_tx: broadcast::Sender<TimerEvent>,
     ) -> Self {
         let timer = ActiveTimer::new_pomodoro(profile_id, task_id, task_title, config);
-        Self {
-            timer: Arc::new(RwLock::new(timer)),
-            event_tx,
-            tick_interval: Duration::from_secs(1),
-        }
+        Self::from_timer(timer, event_tx)
     }
 
     pub fn new_countdown(
@@ -67,11 +78,55 @@ impl TimerEngine {
         event_tx: broadcast::Sender<TimerEvent>,
     ) -> Self {
         let timer = ActiveTimer::new_countdown(profile_id, task_id, task_title, duration_minutes);
+        Self::from_timer(timer, event_tx)
+    }
+
+    fn from_timer(timer: ActiveTimer, event_tx: broadcast::Sender<TimerEvent>) -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock::default());
         Self {
             timer: Arc::new(RwLock::new(timer)),
             event_tx,
             tick_interval: Duration::from_secs(1),
+            last_reading: Mutex::new(ClockReading::take(clock.as_ref())),
+            clock,
+        }
+    }
+
+    /// Reads time from `clock` instead of the system clocks.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.last_reading = Mutex::new(ClockReading::take(clock.as_ref()));
+        self.clock = clock;
+        self
+    }
+
+    /// Notices a wall-clock change since the last check and shifts the
+    /// timer's timestamps by it, so it keeps counting the time that really
+    /// passed. Run on every tick and before anything that reads the timer's
+    /// times for good.
+    fn check_clock(&self, timer: &mut ActiveTimer) {
+        let reading = ClockReading::take(self.clock.as_ref());
+        let skew = {
+            let mut last = self.last_reading.lock().unwrap_or_else(|e| e.into_inner());
+            let skew = last.skew_to(&reading);
+            *last = reading;
+            skew
+        };
+        if skew.abs() < CLOCK_JUMP_THRESHOLD_SECONDS {
+            return;
         }
+
+        timer.shift_clock(skew);
+        tracing::warn!(
+            %timer,
+            timer_id = %timer.id,
+            "Wall clock moved {}s against monotonic time; timer keeps counting real time",
+            skew
+        );
+        let _ = self.event_tx.send(TimerEvent::new(
+            TimerEventType::ClockJumped { skew_seconds: skew },
+            timer.profile_id.clone(),
+            timer.id.clone(),
+        ));
     }
 
     pub async fn timer_id(&self) -> String {
@@ -92,12 +147,32 @@ impl TimerEngine {
         timer_copy
     }
 
+    pub async fn peek(&self) -> TimerPeek {
+        let timer = self.timer.read().await;
+        let elapsed_seconds = timer.current_elapsed();
+        // `remaining_seconds()` only covers pomodoro phases.
+        let remaining_seconds = timer.remaining_seconds().or_else(|| {
+            timer
+                .target_duration
+                .map(|target| target.saturating_sub(elapsed_seconds))
+        });
+        TimerPeek {
+            running: timer.is_running(),
+            elapsed_seconds,
+            remaining_seconds,
+            mode: timer.mode,
+            task_title: timer.task_title.clone(),
+            start_time: timer.start_time,
+        }
+    }
+
     pub async fn start_tick_loop(self: Arc<Self>) {
         let mut tick_interval = interval(self.tick_interval);
 
         loop {
             tick_interval.tick().await;
 
+            self.check_clock(&mut *self.timer.write().await);
             let timer = self.timer.read().await;
 
             if !timer.is_running() {
@@ -122,6 +197,7 @@ impl TimerEngine {
                 };
                 let current_phase = pomo_state.phase;
                 let current_session = pomo_state.current_session;
+                let finished_break = timer.break_record(BreakStatus::Completed);
 
                 drop(timer);
 
@@ -134,10 +210,15 @@ impl TimerEngine {
                     timer_id.clone(),
                 );
                 let _ = self.event_tx.send(event);
+                if let Some(record) = finished_break {
+                    let _ = self
+                        .event_tx
+                        .send(TimerEvent::break_ended(record, profile_id.clone()));
+                }
 
                 let mut timer = self.timer.write().await;
                 if let Err(e) = timer.next_phase() {
-                    tracing::error!("Failed to transition to next phase: {}", e);
+                    tracing::error!(%timer, timer_id = %timer.id, "Failed to transition to next phase: {}", e);
                     continue;
                 }
 
@@ -147,11 +228,40 @@ impl TimerEngine {
                 };
                 let new_phase = pomo_state.phase;
                 let new_session = pomo_state.current_session;
+                tracing::info!(%timer, timer_id = %timer.id, "Pomodoro phase changed");
+                let hold_for_user =
+                    new_phase.is_work() && !pomo_state.config.auto_start_next_session;
+
+                let paused_elapsed = if hold_for_user {
+                    match timer.pause() {
+                        Ok(()) => Some(timer.current_elapsed()),
+                        Err(e) => {
+                            tracing::error!(%timer, timer_id = %timer.id, "Failed to hold timer after break: {}", e);
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
 
                 drop(timer);
 
-                let event = TimerEvent::phase_changed(profile_id, timer_id, new_phase, new_session);
+                let event = TimerEvent::phase_changed(
+                    profile_id.clone(),
+                    timer_id.clone(),
+                    new_phase,
+                    new_session,
+                );
                 let _ = self.event_tx.send(event);
+
+                if let Some(elapsed_seconds) = paused_elapsed {
+                    let event = TimerEvent::new(
+                        TimerEventType::Paused { elapsed_seconds },
+                        profile_id,
+                        timer_id,
+                    );
+                    let _ = self.event_tx.send(event);
+                }
             } else {
                 drop(timer);
             }
@@ -179,14 +289,14 @@ impl TimerEngine {
                     continue;
                 };
                 let elapsed = timer.current_elapsed();
-                drop(timer);
-
                 tracing::info!(
-                    "Countdown completed for {}, elapsed={} target={}",
-                    countdown_profile,
+                    %timer, timer_id = %timer.id,
+                    profile_id = %countdown_profile,
+                    "Countdown completed, elapsed={} target={}",
                     elapsed,
                     target
                 );
+                drop(timer);
 
                 let event = TimerEvent::new(
                     TimerEventType::CountdownCompleted,
@@ -211,7 +321,9 @@ impl TimerEngine {
 
     pub async fn pause(&self) -> Result<()> {
         let mut timer = self.timer.write().await;
+        self.check_clock(&mut timer);
         timer.pause()?;
+        tracing::info!(%timer, timer_id = %timer.id, "Timer paused");
 
         let elapsed = timer.current_elapsed();
         let event = TimerEvent::new(
@@ -228,7 +340,9 @@ impl TimerEngine {
 
     pub async fn resume(&self) -> Result<()> {
         let mut timer = self.timer.write().await;
+        self.check_clock(&mut timer);
         timer.resume()?;
+        tracing::info!(%timer, timer_id = %timer.id, "Timer resumed");
 
         let event = TimerEvent::new(
             TimerEventType::Resumed,
@@ -240,29 +354,138 @@ impl TimerEngine {
         Ok(())
     }
 
+    pub async fn set_auto_start(&self, enabled: bool) -> Result<()> {
+        let mut timer = self.timer.write().await;
+        let pomo = timer.pomodoro_state.as_mut().ok_or_else(|| {
+            TimerEngineError::InvalidOperation("Not a pomodoro timer".to_string())
+        })?;
+        pomo.config.auto_start_next_session = enabled;
+        Ok(())
+    }
+
+    /// Ends the current break early and starts the next work phase. Work
+    /// cannot be skipped: stopping the timer is how a pomodoro is abandoned.
+    pub async fn skip_phase(&self) -> Result<BreakRecord> {
+        let mut timer = self.timer.write().await;
+        self.check_clock(&mut timer);
+        if !timer.is_pomodoro() {
+            return Err(TimerEngineError::InvalidOperation(
+                "Not a pomodoro timer".to_string(),
+            ));
+        }
+        let record = timer.break_record(BreakStatus::Skipped).ok_or_else(|| {
+            TimerEngineError::InvalidOperation("Only breaks can be skipped".to_string())
+        })?;
+
+        // Resume first: resuming shifts the phase clock by the pause.
+        if timer.is_paused() {
+            timer.resume()?;
+        }
+        timer.next_phase()?;
+        let (new_phase, new_session) = timer
+            .pomodoro_state
+            .as_ref()
+            .map(|p| (p.phase, p.current_session))
+            .ok_or_else(|| TimerEngineError::InvalidOperation("Not a pomodoro timer".into()))?;
+        let profile_id = timer.profile_id.clone();
+        let timer_id = timer.id.clone();
+        drop(timer);
+
+        let _ = self
+            .event_tx
+            .send(TimerEvent::break_ended(record.clone(), profile_id.clone()));
+        let _ = self.event_tx.send(TimerEvent::phase_changed(
+            profile_id,
+            timer_id,
+            new_phase,
+            new_session,
+        ));
+
+        Ok(record)
+    }
+
+    /// Reports a break cut short by stopping or cancelling the timer.
+    fn end_break_with_timer(&self, timer: &ActiveTimer) {
+        if let Some(record) = timer.break_record(BreakStatus::Stopped) {
+            let _ = self
+                .event_tx
+                .send(TimerEvent::break_ended(record, timer.profile_id.clone()));
+        }
+    }
+
     pub async fn stop(&self) -> Result<Entry> {
         let mut timer = self.timer.write().await;
+        self.check_clock(&mut timer);
+        // Built before anything changes, so a timer whose entry could not
+        // be saved keeps running.
+        let mut entry = self.entry_for(&timer)?;
+        // Before stopping, while the phase clock still reads the phase the
+        // user stopped in: work is abandoned, a break is not.
+        entry.pomodoro = timer.pomodoro_outcome();
+        self.end_break_with_timer(&timer);
         timer.stop();
+        tracing::info!(%timer, timer_id = %timer.id, "Timer stopped");
 
         let duration = timer.elapsed_seconds;
 
-        let entry = Entry::create_completed(
+        let event = TimerEvent::stopped(timer.profile_id.clone(), timer.id.clone(), duration);
+        let _ = self.event_tx.send(event);
+
+        Ok(entry)
+    }
+
+    /// The entry `timer` would save if stopped now. Refused for timers
+    /// under a second old; ones past `MAX_ENTRY_SECONDS` are cut to it and
+    /// record what was cut in `truncated_seconds`.
+    fn entry_for(&self, timer: &ActiveTimer) -> Result<Entry> {
+        let span = self
+            .clock
+            .now()
+            .signed_duration_since(timer.start_time)
+            .num_seconds();
+        if span < 1 {
+            return Err(TimerEngineError::InvalidOperation(
+                "Timer has run for less than a second; cancel it instead".to_string(),
+            ));
+        }
+        let truncated_seconds = (span as u64).saturating_sub(MAX_ENTRY_SECONDS);
+        if truncated_seconds > 0 {
+            tracing::warn!(
+                %timer,
+                timer_id = %timer.id,
+                "Timer ran {}s, cutting its entry to {}s",
+                span,
+                MAX_ENTRY_SECONDS
+            );
+        }
+
+        // After a clock change `start_time` was shifted to keep counting
+        // real time. Set forward, the entry keeps the start as it was
+        // recorded and ends that much later; set back, that would end in
+        // the future, so the entry ends now on the new clock instead.
+        let start_time = if timer.clock_skew_seconds < 0 {
+            timer.start_time
+        } else {
+            timer.original_start_time()
+        };
+        let end_time = start_time + chrono::Duration::seconds(span - truncated_seconds as i64);
+        let mut entry = Entry::create_completed(
             timer.task_id.clone(),
             timer.task_title.clone(),
-            timer.start_time,
-            Utc::now(),
+            start_time,
+            end_time,
             timer.mode,
         )?;
-
-        let event = TimerEvent::stopped(timer.profile_id.clone(), timer.id.clone(), duration);
-        let _ = self.event_tx.send(event);
-
+        entry.clock_anomaly = timer.clock_skew_seconds != 0;
+        entry.truncated_seconds = truncated_seconds;
         Ok(entry)
     }
 
     pub async fn cancel(&self) -> Result<()> {
         let mut timer = self.timer.write().await;
+        self.end_break_with_timer(&timer);
         timer.stop();
+        tracing::info!(%timer, timer_id = %timer.id, "Timer cancelled");
 
         let event = TimerEvent::new(
             TimerEventType::Cancelled,
@@ -278,6 +501,7 @@ impl TimerEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::timer::clock::testing::ManualClock;
     use mootimer_core::models::TimerMode;
     use tokio::time::sleep;
 
@@ -310,6 +534,106 @@ mod tests {
         assert!(timer.is_pomodoro());
     }
 
+    #[tokio::test]
+    async fn test_set_auto_start() {
+        let (tx, _rx) = broadcast::channel(100);
+        let engine = TimerEngine::new_pomodoro(
+            "test".to_string(),
+            None,
+            None,
+            PomodoroConfig::default(),
+            tx.clone(),
+        );
+
+        engine.set_auto_start(true).await.unwrap();
+        let timer = engine.get_timer().await;
+        assert!(timer.pomodoro_state.unwrap().config.auto_start_next_session);
+
+        let manual = TimerEngine::new_manual("test".to_string(), None, None, tx);
+        assert!(manual.set_auto_start(true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_break_end_waits_without_auto_start() {
+        let (tx, mut rx) = broadcast::channel(100);
+        let config = PomodoroConfig {
+            work_duration: 1,
+            short_break: 1,
+            ..PomodoroConfig::default()
+        };
+        let engine = Arc::new(TimerEngine::new_pomodoro(
+            "test".to_string(),
+            None,
+            None,
+            config,
+            tx,
+        ));
+        let tick_task = tokio::spawn(engine.clone().start_tick_loop());
+
+        let held = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let event = rx.recv().await.unwrap();
+                if let TimerEventType::Paused { .. } = event.event_type {
+                    return engine.get_timer().await;
+                }
+            }
+        })
+        .await
+        .expect("timer should pause when the break ends");
+
+        assert!(held.is_paused());
+        assert!(held.pomodoro_state.unwrap().phase.is_work());
+
+        engine.cancel().await.unwrap();
+        tick_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_skip_phase_only_skips_breaks() {
+        let (tx, mut rx) = broadcast::channel(100);
+        let config = PomodoroConfig {
+            work_duration: 1,
+            short_break: 60,
+            ..PomodoroConfig::default()
+        };
+        let engine = Arc::new(TimerEngine::new_pomodoro(
+            "test".to_string(),
+            None,
+            None,
+            config,
+            tx,
+        ));
+        assert!(engine.skip_phase().await.is_err());
+
+        let tick_task = tokio::spawn(engine.clone().start_tick_loop());
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let event = rx.recv().await.unwrap();
+                if let TimerEventType::PhaseChanged { new_phase, .. } = event.event_type
+                    && new_phase.is_break()
+                {
+                    return;
+                }
+            }
+        })
+        .await
+        .expect("work phase should end");
+
+        let record = engine.skip_phase().await.unwrap();
+        assert_eq!(record.status, BreakStatus::Skipped);
+        assert!(!record.is_taken());
+
+        let timer = engine.get_timer().await;
+        assert!(timer.is_running());
+        assert!(timer.pomodoro_state.unwrap().phase.is_work());
+        let ended = std::iter::from_fn(|| rx.try_recv().ok())
+            .find(|e| matches!(e.event_type, TimerEventType::BreakEnded { .. }));
+        assert!(ended.is_some());
+
+        engine.cancel().await.unwrap();
+        tick_task.abort();
+    }
+
     #[tokio::test]
     async fn test_pause_resume() {
         let (tx, _rx) = broadcast::channel(100);
@@ -343,6 +667,113 @@ mod tests {
         assert!(entry.duration_seconds >= 1);
     }
 
+    #[tokio::test]
+    async fn test_stop_during_work_abandons_the_pomodoro() {
+        let (tx, _rx) = broadcast::channel(100);
+        let engine = TimerEngine::new_pomodoro(
+            "test".to_string(),
+            None,
+            None,
+            PomodoroConfig::default(),
+            tx,
+        );
+
+        sleep(Duration::from_millis(1100)).await;
+
+        let entry = engine.stop().await.unwrap();
+        let outcome = entry.pomodoro.expect("pomodoro entries record an outcome");
+        assert!(!outcome.completed);
+        assert_eq!(outcome.completed_sessions, 0);
+        assert!(outcome.interrupted_at_seconds.unwrap() >= 1);
+    }
+
+    #[tokio::test]
+    async fn test_clock_jump_keeps_real_duration() {
+        let (tx, mut rx) = broadcast::channel(100);
+        let clock = Arc::new(ManualClock::default());
+        let engine =
+            TimerEngine::new_manual("test".to_string(), None, None, tx).with_clock(clock.clone());
+        let started = engine.get_timer().await.start_time;
+
+        clock.advance(600);
+        clock.jump(2 * 3600);
+
+        let entry = engine.stop().await.unwrap();
+        assert!(entry.clock_anomaly);
+        assert_eq!(entry.start_time, started);
+        assert_eq!(entry.duration_seconds, 600);
+        let end_time = entry.end_time.unwrap();
+        assert_eq!(end_time.signed_duration_since(started).num_seconds(), 600);
+
+        let event = rx.recv().await.unwrap();
+        assert_eq!(
+            event.event_type,
+            TimerEventType::ClockJumped { skew_seconds: 7200 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clock_set_back_ends_entry_now() {
+        let (tx, _rx) = broadcast::channel(100);
+        let clock = Arc::new(ManualClock::default());
+        let engine =
+            TimerEngine::new_manual("test".to_string(), None, None, tx).with_clock(clock.clone());
+
+        clock.advance(600);
+        clock.jump(-2 * 3600);
+
+        let entry = engine.stop().await.unwrap();
+        assert!(entry.clock_anomaly);
+        assert_eq!(entry.duration_seconds, 600);
+        assert!(entry.end_time.unwrap() <= clock.now());
+    }
+
+    #[tokio::test]
+    async fn test_overlong_timer_is_cut_to_a_day() {
+        let (tx, _rx) = broadcast::channel(100);
+        let clock = Arc::new(ManualClock::default());
+        let engine =
+            TimerEngine::new_manual("test".to_string(), None, None, tx).with_clock(clock.clone());
+        let started = engine.get_timer().await.start_time;
+
+        clock.advance(MAX_ENTRY_SECONDS + 3600);
+
+        let entry = engine.stop().await.unwrap();
+        assert_eq!(entry.start_time, started);
+        assert_eq!(entry.duration_seconds, MAX_ENTRY_SECONDS);
+        assert_eq!(entry.truncated_seconds, 3600);
+        entry.validate().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stop_under_a_second_leaves_timer_running() {
+        let (tx, _rx) = broadcast::channel(100);
+        let engine = TimerEngine::new_manual("test".to_string(), None, None, tx);
+
+        assert!(engine.stop().await.is_err());
+        assert!(engine.get_timer().await.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_small_drift_is_not_a_clock_jump() {
+        let (tx, _rx) = broadcast::channel(100);
+        let clock = Arc::new(ManualClock::default());
+        let engine =
+            TimerEngine::new_manual("test".to_string(), None, None, tx).with_clock(clock.clone());
+        let started = engine.get_timer().await.start_time;
+
+        clock.advance(600);
+        clock.jump(CLOCK_JUMP_THRESHOLD_SECONDS - 1);
+
+        let entry = engine.stop().await.unwrap();
+        assert!(!entry.clock_anomaly);
+        assert_eq!(entry.start_time, started);
+        assert_eq!(
+            entry.duration_seconds,
+            600 + CLOCK_JUMP_THRESHOLD_SECONDS as u64 - 1
+        );
+    }
+
     #[tokio::test]
     async fn test_timer_events() {
         let (tx, mut rx) = broadcast::channel(100);
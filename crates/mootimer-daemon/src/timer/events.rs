@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use mootimer_core::models::{PomodoroPhase, TimerMode};
+use mootimer_core::models::{BreakRecord, Entry, PomodoroPhase, TimerMode};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -38,6 +38,26 @@ pub enum TimerEventType {
         session_number: u32,
     },
     CountdownCompleted,
+    /// The wall clock was changed by `skew_seconds` while the timer ran.
+    /// The timer kept counting the time that really passed, and the entry
+    /// it saves is flagged `clock_anomaly`.
+    ClockJumped {
+        skew_seconds: i64,
+    },
+    /// A pomodoro break finished, was skipped or was cut short.
+    BreakEnded {
+        record: BreakRecord,
+    },
+    /// A stopped session ran past its task's `max_session_seconds`. Only
+    /// the capped part was saved; the overflow is described here for the
+    /// client to discard or log against another task.
+    SessionCapped {
+        task_id: String,
+        cap_seconds: u64,
+        overflow_start: DateTime<Utc>,
+        overflow_end: DateTime<Utc>,
+        overflow_seconds: u64,
+    },
 }
 
 impl TimerEvent {
@@ -87,6 +107,31 @@ impl TimerEvent {
         )
     }
 
+    pub fn session_capped(
+        profile_id: String,
+        timer_id: String,
+        task_id: String,
+        cap_seconds: u64,
+        overflow: &Entry,
+    ) -> Self {
+        Self::new(
+            TimerEventType::SessionCapped {
+                task_id,
+                cap_seconds,
+                overflow_start: overflow.start_time,
+                overflow_end: overflow.end_time.unwrap_or(overflow.start_time),
+                overflow_seconds: overflow.duration_seconds,
+            },
+            profile_id,
+            timer_id,
+        )
+    }
+
+    pub fn break_ended(record: BreakRecord, profile_id: String) -> Self {
+        let timer_id = record.timer_id.clone();
+        Self::new(TimerEventType::BreakEnded { record }, profile_id, timer_id)
+    }
+
     pub fn phase_changed(
         profile_id: String,
         timer_id: String,
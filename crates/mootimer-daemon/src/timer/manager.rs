@@ -1,11 +1,14 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::{RwLock, broadcast};
 
-use mootimer_core::models::{ActiveTimer, Entry, PomodoroConfig};
+use mootimer_core::models::{ActiveTimer, BreakRecord, DaemonConfig, Entry, PomodoroConfig};
 
-use super::engine::{TimerEngine, TimerEngineError};
-use super::events::TimerEvent;
+use super::clock::{Clock, SystemClock};
+use super::engine::{TimerEngine, TimerEngineError, TimerPeek};
+use super::events::{TimerEvent, TimerEventType};
 use crate::event_manager::EventManager;
 
 #[derive(Debug, thiserror::Error)]
@@ -13,12 +16,21 @@ pub enum TimerManagerError {
     #[error("Timer not found: {0}")]
     NotFound(String),
 
+    #[error("Profile {profile_id} already has {max} active timers")]
+    LimitReached { profile_id: String, max: usize },
+
     #[error("Timer engine error: {0}")]
     Engine(#[from] TimerEngineError),
 }
 
 pub type Result<T> = std::result::Result<T, TimerManagerError>;
 
+/// How long a `peek_by_profile` answer is reused, so status bars polling
+/// several times a second don't each walk every timer.
+const PEEK_CACHE_TTL: Duration = Duration::from_millis(200);
+
+type PeekCache = Mutex<HashMap<String, (Instant, Option<TimerPeek>)>>;
+
 use crate::task::TaskManager;
 
 #[derive(Clone)]
@@ -28,17 +40,30 @@ pub struct TimerManager {
     event_tx: broadcast::Sender<TimerEvent>,
     completed_entries: Arc<RwLock<Vec<(String, Entry)>>>,
     task_manager: Option<Arc<TaskManager>>,
+    /// `daemon.max_timers_per_profile`; `ApiHandler` refreshes it from the
+    /// config before each start.
+    max_timers_per_profile: Arc<AtomicUsize>,
+    /// `peek_by_profile` answers by profile. Cleared on every timer event
+    /// but ticks, so only the elapsed time can be out of date.
+    peeks: Arc<PeekCache>,
+    /// Handed to every engine started from now on.
+    clock: Arc<dyn Clock>,
 }
 
 impl TimerManager {
     pub fn new(event_manager: Arc<EventManager>) -> Self {
-        let (event_tx, mut event_rx) = broadcast::channel(1000);
+        let (event_tx, mut event_rx) = broadcast::channel::<TimerEvent>(1000);
         let timers = Arc::new(RwLock::new(HashMap::new()));
         let completed_entries = Arc::new(RwLock::new(Vec::new()));
+        let peeks: Arc<PeekCache> = Arc::default();
 
         let event_manager_clone = event_manager.clone();
+        let peeks_clone = peeks.clone();
         tokio::spawn(async move {
             while let Ok(timer_event) = event_rx.recv().await {
+                if !matches!(timer_event.event_type, TimerEventType::Tick { .. }) {
+                    peeks_clone.lock().unwrap().clear();
+                }
                 event_manager_clone.emit_timer(timer_event);
             }
         });
@@ -49,13 +74,98 @@ impl TimerManager {
             event_tx,
             completed_entries,
             task_manager: None,
+            max_timers_per_profile: Arc::new(AtomicUsize::new(
+                DaemonConfig::default().max_timers_per_profile,
+            )),
+            peeks,
+            clock: Arc::new(SystemClock::default()),
         }
     }
 
+    /// Starts timers on `clock` instead of the system clocks.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     pub fn set_task_manager(&mut self, task_manager: Arc<TaskManager>) {
         self.task_manager = Some(task_manager);
     }
 
+    pub fn max_timers_per_profile(&self) -> usize {
+        self.max_timers_per_profile.load(Ordering::Relaxed)
+    }
+
+    /// Applies to timers started from now on; running ones are left alone.
+    pub fn set_max_timers_per_profile(&self, max: usize) {
+        self.max_timers_per_profile.store(max, Ordering::Relaxed);
+    }
+
+    /// Adds a new engine unless its profile is already at the timer limit.
+    /// Counting and inserting share one write lock, so concurrent starts
+    /// cannot overshoot.
+    async fn register(&self, engine: &Arc<TimerEngine>) -> Result<String> {
+        let timer_id = engine.timer_id().await;
+        let profile_id = engine.profile_id().await;
+        let max = self.max_timers_per_profile();
+
+        let mut timers = self.timers.write().await;
+        let mut active = 0;
+        for other in timers.values() {
+            if other.profile_id().await == profile_id {
+                active += 1;
+            }
+        }
+        if active >= max {
+            return Err(TimerManagerError::LimitReached { profile_id, max });
+        }
+
+        timers.insert(timer_id.clone(), engine.clone());
+        self.forget_peeks();
+        Ok(timer_id)
+    }
+
+    /// Drops cached peeks as soon as a call changes a timer, rather than
+    /// when its event comes through. Call it after the change: a peek
+    /// taken in between would cache the old state again.
+    fn forget_peeks(&self) {
+        self.peeks.lock().unwrap().clear();
+    }
+
+    /// The profile's running timer, else its newest paused one, at most
+    /// `PEEK_CACHE_TTL` old. `None` when the profile has no timer.
+    pub async fn peek_by_profile(&self, profile_id: &str) -> Option<TimerPeek> {
+        if let Some((at, peek)) = self.peeks.lock().unwrap().get(profile_id)
+            && at.elapsed() < PEEK_CACHE_TTL
+        {
+            return peek.clone();
+        }
+
+        let engines: Vec<_> = {
+            let timers = self.timers.read().await;
+            timers.values().cloned().collect()
+        };
+        let mut best: Option<TimerPeek> = None;
+        for engine in engines {
+            if engine.profile_id().await != profile_id {
+                continue;
+            }
+            let peek = engine.peek().await;
+            if best
+                .as_ref()
+                .is_none_or(|b| (peek.running, peek.start_time) > (b.running, b.start_time))
+            {
+                best = Some(peek);
+            }
+        }
+
+        self.peeks
+            .lock()
+            .unwrap()
+            .insert(profile_id.to_string(), (Instant::now(), best.clone()));
+        best
+    }
+
     pub async fn take_completed_entries(&self) -> Vec<(String, Entry)> {
         let mut entries = self.completed_entries.write().await;
         std::mem::take(&mut *entries)
@@ -130,14 +240,17 @@ impl TimerManager {
     ) -> Result<String> {
         let task_title = self.get_task_title(&profile_id, task_id.as_ref()).await;
 
-        let engine = Arc::new(TimerEngine::new_manual(
-            profile_id.clone(),
-            task_id.clone(),
-            task_title,
-            self.event_tx.clone(),
-        ));
+        let engine = Arc::new(
+            TimerEngine::new_manual(
+                profile_id.clone(),
+                task_id.clone(),
+                task_title,
+                self.event_tx.clone(),
+            )
+            .with_clock(self.clock.clone()),
+        );
 
-        let timer_id = engine.timer_id().await;
+        let timer_id = self.register(&engine).await?;
 
         let event = TimerEvent::started(
             profile_id,
@@ -153,11 +266,6 @@ impl TimerManager {
             engine_clone.start_tick_loop().await;
         });
 
-        {
-            let mut timers = self.timers.write().await;
-            timers.insert(timer_id.clone(), engine);
-        }
-
         Ok(timer_id)
     }
 
@@ -169,15 +277,18 @@ impl TimerManager {
     ) -> Result<String> {
         let task_title = self.get_task_title(&profile_id, task_id.as_ref()).await;
 
-        let engine = Arc::new(TimerEngine::new_pomodoro(
-            profile_id.clone(),
-            task_id.clone(),
-            task_title,
-            config,
-            self.event_tx.clone(),
-        ));
+        let engine = Arc::new(
+            TimerEngine::new_pomodoro(
+                profile_id.clone(),
+                task_id.clone(),
+                task_title,
+                config,
+                self.event_tx.clone(),
+            )
+            .with_clock(self.clock.clone()),
+        );
 
-        let timer_id = engine.timer_id().await;
+        let timer_id = self.register(&engine).await?;
 
         let event = TimerEvent::started(
             profile_id,
@@ -193,11 +304,6 @@ impl TimerManager {
             engine_clone.start_tick_loop().await;
         });
 
-        {
-            let mut timers = self.timers.write().await;
-            timers.insert(timer_id.clone(), engine);
-        }
-
         Ok(timer_id)
     }
 
@@ -209,15 +315,18 @@ impl TimerManager {
     ) -> Result<String> {
         let task_title = self.get_task_title(&profile_id, task_id.as_ref()).await;
 
-        let engine = Arc::new(TimerEngine::new_countdown(
-            profile_id.clone(),
-            task_id.clone(),
-            task_title,
-            duration_minutes,
-            self.event_tx.clone(),
-        ));
+        let engine = Arc::new(
+            TimerEngine::new_countdown(
+                profile_id.clone(),
+                task_id.clone(),
+                task_title,
+                duration_minutes,
+                self.event_tx.clone(),
+            )
+            .with_clock(self.clock.clone()),
+        );
 
-        let timer_id = engine.timer_id().await;
+        let timer_id = self.register(&engine).await?;
 
         let event = TimerEvent::started(
             profile_id.clone(),
@@ -244,11 +353,6 @@ impl TimerManager {
             .await;
         });
 
-        {
-            let mut timers = self.timers.write().await;
-            timers.insert(timer_id.clone(), engine);
-        }
-
         Ok(timer_id)
     }
 
@@ -325,6 +429,7 @@ impl TimerManager {
                 .ok_or_else(|| TimerManagerError::NotFound(timer_id.to_string()))?
         };
         engine.pause().await?;
+        self.forget_peeks();
         Ok(())
     }
 
@@ -337,19 +442,52 @@ impl TimerManager {
                 .ok_or_else(|| TimerManagerError::NotFound(timer_id.to_string()))?
         };
         engine.resume().await?;
+        self.forget_peeks();
         Ok(())
     }
 
-    pub async fn stop(&self, timer_id: &str) -> Result<(String, Entry)> {
+    pub async fn set_auto_start(&self, timer_id: &str, enabled: bool) -> Result<()> {
         let engine = {
-            let mut timers = self.timers.write().await;
+            let timers = self.timers.read().await;
             timers
-                .remove(timer_id)
+                .get(timer_id)
+                .cloned()
+                .ok_or_else(|| TimerManagerError::NotFound(timer_id.to_string()))?
+        };
+        engine.set_auto_start(enabled).await?;
+        Ok(())
+    }
+
+    pub async fn skip_phase(&self, timer_id: &str) -> Result<BreakRecord> {
+        let engine = {
+            let timers = self.timers.read().await;
+            timers
+                .get(timer_id)
+                .cloned()
                 .ok_or_else(|| TimerManagerError::NotFound(timer_id.to_string()))?
         };
+        let record = engine.skip_phase().await?;
+        self.forget_peeks();
+        Ok(record)
+    }
+
+    /// Stops the timer and removes it, unless its entry can't be made, in
+    /// which case it is left running. The map stays locked throughout so
+    /// concurrent stops make one entry.
+    pub async fn stop(&self, timer_id: &str) -> Result<(String, Entry)> {
+        let mut timers = self.timers.write().await;
+        let engine = timers
+            .get(timer_id)
+            .cloned()
+            .ok_or_else(|| TimerManagerError::NotFound(timer_id.to_string()))?;
 
         let profile_id = engine.profile_id().await;
         let entry = engine.stop().await?;
+        timers.remove(timer_id);
+        drop(timers);
+        // Only now, or a peek racing this call could cache the timer as
+        // still running.
+        self.forget_peeks();
         Ok((profile_id, entry))
     }
 
@@ -376,7 +514,9 @@ impl TimerManager {
                 .ok_or_else(|| TimerManagerError::NotFound(timer_id.to_string()))?
         };
 
-        engine.cancel().await?;
+        let cancelled = engine.cancel().await;
+        self.forget_peeks();
+        cancelled?;
         Ok(())
     }
 
@@ -426,6 +566,38 @@ mod tests {
         assert_eq!(manager.active_timer_count().await, 1);
     }
 
+    #[tokio::test]
+    async fn test_peek_prefers_running_timer_and_follows_pauses() {
+        let manager = create_manager();
+        assert_eq!(manager.peek_by_profile("profile1").await, None);
+
+        let countdown = manager
+            .start_countdown("profile1".to_string(), None, 25)
+            .await
+            .unwrap();
+        sleep(Duration::from_millis(10)).await;
+        let manual = manager
+            .start_manual("profile1".to_string(), None)
+            .await
+            .unwrap();
+
+        let peek = manager.peek_by_profile("profile1").await.unwrap();
+        assert!(peek.running);
+        assert_eq!(peek.mode, mootimer_core::models::TimerMode::Manual);
+        assert_eq!(peek.remaining_seconds, None);
+
+        // Cached, but a pause is seen at once.
+        manager.pause(&manual).await.unwrap();
+        let peek = manager.peek_by_profile("profile1").await.unwrap();
+        assert_eq!(peek.mode, mootimer_core::models::TimerMode::Countdown);
+        assert!(peek.remaining_seconds.unwrap() > 24 * 60);
+
+        manager.pause(&countdown).await.unwrap();
+        let peek = manager.peek_by_profile("profile1").await.unwrap();
+        assert!(!peek.running);
+        assert_eq!(peek.mode, mootimer_core::models::TimerMode::Manual);
+    }
+
     #[tokio::test]
     async fn test_can_start_multiple_timers_for_profile() {
         let manager = create_manager();
@@ -445,6 +617,40 @@ mod tests {
         assert_eq!(manager.active_timer_count_by_profile("profile1").await, 2);
     }
 
+    #[tokio::test]
+    async fn test_timer_limit_per_profile() {
+        let manager = create_manager();
+        manager.set_max_timers_per_profile(2);
+
+        for _ in 0..2 {
+            manager
+                .start_manual("profile1".to_string(), None)
+                .await
+                .unwrap();
+        }
+        let result = manager
+            .start_countdown("profile1".to_string(), None, 5)
+            .await;
+        assert!(matches!(
+            result,
+            Err(TimerManagerError::LimitReached { max: 2, .. })
+        ));
+        assert_eq!(manager.active_timer_count_by_profile("profile1").await, 2);
+
+        // Other profiles have their own allowance.
+        manager
+            .start_manual("profile2".to_string(), None)
+            .await
+            .unwrap();
+
+        manager.set_max_timers_per_profile(3);
+        manager
+            .start_manual("profile1".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(manager.active_timer_count().await, 4);
+    }
+
     #[tokio::test]
     async fn test_pause_resume() {
         let manager = create_manager();
@@ -474,6 +680,12 @@ mod tests {
 
         sleep(Duration::from_millis(100)).await;
 
+        // Too short to save; the timer is kept rather than lost.
+        assert!(manager.stop(&timer_id).await.is_err());
+        assert!(manager.has_timer(&timer_id).await);
+
+        sleep(Duration::from_millis(1000)).await;
+
         let (profile_id, entry) = manager.stop(&timer_id).await.unwrap();
         assert_eq!(profile_id, "profile1");
         assert!(entry.is_completed());
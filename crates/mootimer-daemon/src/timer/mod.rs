@@ -1,7 +1,9 @@
+pub mod clock;
 pub mod engine;
 pub mod events;
 pub mod manager;
 
-pub use engine::TimerEngine;
+pub use clock::{Clock, SystemClock};
+pub use engine::{TimerEngine, TimerEngineError, TimerPeek};
 pub use events::{TimerEvent, TimerEventType};
-pub use manager::TimerManager;
+pub use manager::{TimerManager, TimerManagerError};
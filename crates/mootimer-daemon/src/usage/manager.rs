@@ -0,0 +1,175 @@
+use chrono::Local;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use mootimer_core::storage::usage::{UNKNOWN_CLIENT, UsageMonth};
+use mootimer_core::storage::{UsageStorage, get_data_dir};
+
+/// Months of usage files kept; older ones are deleted on the next write.
+pub const USAGE_RETENTION_MONTHS: usize = 12;
+
+/// Counts are written at most this often, plus on shutdown, so a chatty
+/// client doesn't turn every call into a file write.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, thiserror::Error)]
+pub enum UsageManagerError {
+    #[error("Storage error: {0}")]
+    Storage(#[from] mootimer_core::Error),
+}
+
+pub type Result<T> = std::result::Result<T, UsageManagerError>;
+
+#[derive(Default)]
+struct UsageState {
+    /// The month being counted, as loaded from disk plus unsaved calls.
+    current: Option<UsageMonth>,
+    dirty: bool,
+    last_flush: Option<Instant>,
+}
+
+/// Counts RPC calls per day, method and client, for the user's own
+/// curiosity. Only ever written to the local data directory.
+pub struct UsageManager {
+    storage: UsageStorage,
+    state: Mutex<UsageState>,
+}
+
+impl Default for UsageManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UsageManager {
+    pub fn new() -> Self {
+        Self {
+            storage: UsageStorage::new(get_data_dir()),
+            state: Mutex::new(UsageState::default()),
+        }
+    }
+
+    /// Counts one call of `method`. `client` is the name the connection
+    /// gave in `system.hello`, if any.
+    pub async fn record(&self, method: &str, client: Option<&str>) -> Result<()> {
+        let now = Local::now();
+        let month = now.format("%Y-%m").to_string();
+        let day = now.format("%Y-%m-%d").to_string();
+
+        let mut state = self.state.lock().await;
+        if state.current.as_ref().is_none_or(|c| c.month != month) {
+            self.flush_locked(&mut state)?;
+            state.current = Some(self.storage.load(&month)?);
+        }
+        if let Some(current) = state.current.as_mut() {
+            current.add(&day, method, client.unwrap_or(UNKNOWN_CLIENT));
+            state.dirty = true;
+        }
+
+        if state
+            .last_flush
+            .is_none_or(|at| at.elapsed() >= FLUSH_INTERVAL)
+        {
+            self.flush_locked(&mut state)?;
+        }
+        Ok(())
+    }
+
+    /// Writes counts not yet on disk.
+    pub async fn flush(&self) -> Result<()> {
+        let mut state = self.state.lock().await;
+        self.flush_locked(&mut state)
+    }
+
+    fn flush_locked(&self, state: &mut UsageState) -> Result<()> {
+        if let Some(current) = &state.current
+            && state.dirty
+        {
+            self.storage.save(current)?;
+            self.storage.prune(USAGE_RETENTION_MONTHS)?;
+            state.dirty = false;
+            state.last_flush = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    /// The counts for `month` (`YYYY-MM`), including unsaved ones.
+    pub async fn month(&self, month: &str) -> Result<UsageMonth> {
+        self.flush().await?;
+        Ok(self.storage.load(month)?)
+    }
+
+    /// Months with recorded usage, oldest first.
+    pub fn months(&self) -> Result<Vec<String>> {
+        Ok(self.storage.months()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mootimer_core::storage::init_data_dir;
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    fn setup(temp_dir: &TempDir) {
+        unsafe {
+            std::env::set_var("HOME", temp_dir.path());
+            std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+            std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config"));
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_record_writes_month_file() {
+        let temp_dir = TempDir::new().unwrap();
+        setup(&temp_dir);
+        let manager = UsageManager::new();
+
+        manager
+            .record("timer.get", Some("mootimer-tui"))
+            .await
+            .unwrap();
+        manager
+            .record("timer.get", Some("mootimer-tui"))
+            .await
+            .unwrap();
+        manager.record("timer.get", None).await.unwrap();
+        manager.flush().await.unwrap();
+
+        let now = Local::now();
+        let month = now.format("%Y-%m").to_string();
+        let day = now.format("%Y-%m-%d").to_string();
+        let path = init_data_dir()
+            .unwrap()
+            .join("usage")
+            .join(format!("{}.json", month));
+        let file: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+        assert_eq!(file["month"], month.as_str());
+        assert_eq!(file["days"][&day]["timer.get"]["mootimer-tui"], 2);
+        assert_eq!(file["days"][&day]["timer.get"][UNKNOWN_CLIENT], 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_old_months_are_pruned() {
+        let temp_dir = TempDir::new().unwrap();
+        setup(&temp_dir);
+        let storage = UsageStorage::new(get_data_dir());
+        for month in 1..=USAGE_RETENTION_MONTHS + 2 {
+            storage
+                .save(&UsageMonth::new(&format!("2000-{:02}", month)))
+                .unwrap();
+        }
+
+        let manager = UsageManager::new();
+        manager.record("timer.get", None).await.unwrap();
+
+        let months = manager.months().unwrap();
+        assert_eq!(months.len(), USAGE_RETENTION_MONTHS);
+        assert!(!months.contains(&"2000-01".to_string()));
+        assert!(months.contains(&Local::now().format("%Y-%m").to_string()));
+    }
+}
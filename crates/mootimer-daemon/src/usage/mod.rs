@@ -0,0 +1,3 @@
+pub mod manager;
+
+pub use manager::{USAGE_RETENTION_MONTHS, UsageManager, UsageManagerError};
@@ -0,0 +1,349 @@
+use anyhow::Result;
+use chrono::{Duration as ChronoDuration, Utc};
+use mootimer_core::models::TaskStatus;
+use mootimer_daemon::test_util::{TestDaemon, wait_for_notification};
+use serial_test::serial;
+use std::time::Duration;
+
+const PROFILE: &str = "e2e";
+
+async fn start_with_profile() -> Result<TestDaemon> {
+    let daemon = TestDaemon::start().await?;
+    daemon.client.profile_create(PROFILE, "E2E", None).await?;
+    Ok(daemon)
+}
+
+#[tokio::test]
+#[serial]
+async fn test_timer_lifecycle_persists_entry() -> Result<()> {
+    let daemon = start_with_profile().await?;
+    let mut notifications = daemon.client.subscribe_notifications().await?;
+    daemon.client.events_subscribe(&["timer"]).await?;
+
+    let started = daemon.client.timer_start_manual(PROFILE, None).await?;
+    let timer_id = started["timer_id"].as_str().unwrap().to_string();
+
+    let is_ours = |params: &serde_json::Value, kind: &str| {
+        params["timer_id"] == timer_id.as_str() && params["event_type"]["type"] == kind
+    };
+    wait_for_notification(
+        &mut notifications,
+        "timer.event",
+        Duration::from_secs(2),
+        |p| is_ours(p, "started"),
+    )
+    .await?;
+
+    // The engine ticks once a second; wait until time has visibly passed.
+    wait_for_notification(
+        &mut notifications,
+        "timer.event",
+        Duration::from_secs(3),
+        |p| is_ours(p, "tick") && p["event_type"]["elapsed_seconds"].as_u64() >= Some(1),
+    )
+    .await?;
+
+    let entry = daemon.client.timer_stop(&timer_id).await?;
+    assert!(entry["duration_seconds"].as_u64().unwrap() >= 1);
+    wait_for_notification(
+        &mut notifications,
+        "timer.event",
+        Duration::from_secs(2),
+        |p| is_ours(p, "stopped"),
+    )
+    .await?;
+
+    // A fresh connection sees the entry, so it was persisted, not cached
+    // per client.
+    let entries = daemon.connect().entry_list(PROFILE).await?;
+    assert!(
+        entries
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|e| e["id"] == entry["id"])
+    );
+    assert!(daemon.data_dir().join("data").exists());
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_notifications_follow_subscriptions() -> Result<()> {
+    let daemon = start_with_profile().await?;
+    let watcher = daemon.connect();
+    let mut notifications = watcher.subscribe_notifications().await?;
+
+    let subscribed = watcher.events_subscribe(&["profile"]).await?;
+    assert_eq!(subscribed["categories"], serde_json::json!(["profile"]));
+    assert!(watcher.events_subscribe(&["weather"]).await.is_err());
+
+    // The task is created first, so had its event been sent it would be
+    // the first to arrive.
+    daemon.client.task_create(PROFILE, "Quiet", None).await?;
+    daemon.client.profile_create("other", "Other", None).await?;
+    let first = tokio::time::timeout(Duration::from_secs(2), notifications.recv())
+        .await?
+        .expect("notification");
+    assert_eq!(first.method, "profile.event");
+    assert_eq!(first.params["event_type"]["type"], "created");
+
+    watcher.events_unsubscribe(None).await?;
+    daemon.client.profile_delete("other").await?;
+    let silence = tokio::time::timeout(Duration::from_millis(300), notifications.recv()).await;
+    assert!(
+        silence.is_err(),
+        "unsubscribed connection got {:?}",
+        silence
+    );
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_status_watch_fires_on_target_status() -> Result<()> {
+    let daemon = start_with_profile().await?;
+    let watcher = daemon.connect();
+    let mut notifications = watcher.subscribe_notifications().await?;
+
+    let mut task = daemon.client.task_create(PROFILE, "Build", None).await?;
+    let task_id = task["id"].as_str().unwrap().to_string();
+    watcher
+        .task_watch_status(&task_id, TaskStatus::Done)
+        .await?;
+
+    task["status"] = "in_progress".into();
+    let mut task = daemon.client.task_update(PROFILE, task).await?;
+    task["status"] = "done".into();
+    daemon.client.task_update(PROFILE, task).await?;
+
+    // The watcher has no subscriptions, so this is the only notification.
+    let reached = tokio::time::timeout(Duration::from_secs(2), notifications.recv())
+        .await?
+        .expect("notification");
+    assert_eq!(reached.method, "task.status_reached");
+    assert_eq!(reached.params["task"]["id"], task_id.as_str());
+    assert_eq!(reached.params["task"]["status"], "done");
+    assert_eq!(reached.params["previous_status"], "in_progress");
+
+    // Watches fire once.
+    daemon
+        .client
+        .task_bulk_status_update(PROFILE, TaskStatus::Done, TaskStatus::Todo, None)
+        .await?;
+    daemon
+        .client
+        .task_bulk_status_update(PROFILE, TaskStatus::Todo, TaskStatus::Done, None)
+        .await?;
+    let silence = tokio::time::timeout(Duration::from_millis(300), notifications.recv()).await;
+    assert!(silence.is_err(), "spent watch sent {:?}", silence);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_profile_crud() -> Result<()> {
+    let daemon = start_with_profile().await?;
+    let client = &daemon.client;
+
+    let mut profile = client.profile_get(PROFILE).await?;
+    assert_eq!(profile["name"], "E2E");
+
+    profile["name"] = "Renamed".into();
+    client.profile_update(profile).await?;
+    assert_eq!(client.profile_get(PROFILE).await?["name"], "Renamed");
+
+    let profiles = client.profile_list().await?;
+    assert!(
+        profiles
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|p| p["id"] == PROFILE)
+    );
+
+    client.profile_delete(PROFILE).await?;
+    assert!(client.profile_get(PROFILE).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_entry_stats_today() -> Result<()> {
+    let daemon = start_with_profile().await?;
+    let client = &daemon.client;
+
+    let now = Utc::now();
+    for (start, end) in [(300, 240), (120, 60)] {
+        client
+            .entry_create(
+                PROFILE,
+                &(now - ChronoDuration::seconds(start)).to_rfc3339(),
+                &(now - ChronoDuration::seconds(end)).to_rfc3339(),
+                None,
+                None,
+            )
+            .await?;
+    }
+
+    let stats = client.entry_stats_today(PROFILE).await?;
+    assert_eq!(stats["total_entries"], 2);
+    assert_eq!(stats["total_duration_seconds"], 120);
+    assert_eq!(stats["manual_count"], 2);
+    assert_eq!(stats["by_source"]["manual"]["entries"], 2);
+
+    let entries = client.entry_list(PROFILE).await?;
+    assert_eq!(entries[0]["source"]["type"], "manual");
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_large_responses_compressed_on_request() -> Result<()> {
+    use mootimer_client::compression::FRAME_MARKER;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+
+    let daemon = start_with_profile().await?;
+    let start = Utc::now() - ChronoDuration::days(30);
+    let entries: Vec<_> = (0..200)
+        .map(|i| {
+            let begin = start + ChronoDuration::hours(i);
+            serde_json::json!({
+                "start_time": begin,
+                "end_time": begin + ChronoDuration::minutes(30),
+                "description": format!("Entry {} with a reasonably long description", i),
+            })
+        })
+        .collect();
+    daemon
+        .client
+        .entry_create_batch(PROFILE, &entries, false)
+        .await?;
+
+    // A raw connection that asks for zstd gets a frame, not a JSON line.
+    let mut raw = tokio::net::UnixStream::connect(daemon.socket_path()).await?;
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "entry.list",
+        "params": { "profile_id": PROFILE },
+        "id": 1,
+        "x-accept-encoding": "zstd",
+    });
+    raw.write_all(format!("{}\n", request).as_bytes()).await?;
+    assert_eq!(raw.read_u8().await?, FRAME_MARKER);
+
+    // One that asks in system.hello gets it confirmed, then envelopes.
+    let raw = tokio::net::UnixStream::connect(daemon.socket_path()).await?;
+    let (read_half, mut write_half) = tokio::io::split(raw);
+    let mut lines = tokio::io::BufReader::new(read_half).lines();
+    let hello = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "system.hello",
+        "params": { "accept_encoding": ["zstd"] },
+        "id": 1,
+    });
+    let list = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "entry.list",
+        "params": { "profile_id": PROFILE },
+        "id": 2,
+    });
+    write_half
+        .write_all(format!("{}\n{}\n", hello, list).as_bytes())
+        .await?;
+    let hello: serde_json::Value = serde_json::from_str(&lines.next_line().await?.unwrap())?;
+    assert_eq!(hello["result"]["compression"]["encoding"], "zstd");
+    let envelope: serde_json::Value = serde_json::from_str(&lines.next_line().await?.unwrap())?;
+    assert_eq!(envelope["compressed"], true);
+    assert_eq!(envelope["encoding"], "zstd");
+    assert!(envelope["data"].is_string());
+
+    // Through the client it is transparent, and small replies stay plain.
+    let compressed = daemon.connect().with_compression(true);
+    let plain = daemon.connect();
+    assert_eq!(
+        compressed.entry_list(PROFILE).await?,
+        plain.entry_list(PROFILE).await?
+    );
+    assert_eq!(
+        compressed
+            .entry_list(PROFILE)
+            .await?
+            .as_array()
+            .unwrap()
+            .len(),
+        200
+    );
+    compressed.profile_list().await?;
+    Ok(())
+}
+
+/// Collects what the daemon logs, as JSON lines.
+#[derive(Clone, Default)]
+struct LogBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_request_logs_share_a_request_id() -> Result<()> {
+    let logs = LogBuffer::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .json()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_writer(move || writer.clone())
+        .finish();
+    // The test runtime is single-threaded, so the daemon's tasks log here.
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let daemon = start_with_profile().await?;
+    assert!(daemon.client.task_get(PROFILE, "missing").await.is_err());
+    daemon.client.task_list(PROFILE).await?;
+
+    let lines: Vec<serde_json::Value> = String::from_utf8(logs.0.lock().unwrap().clone())?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<std::result::Result<_, _>>()?;
+    let of_method = |method: &str| -> Vec<&serde_json::Value> {
+        lines
+            .iter()
+            .filter(|line| line["span"]["method"] == method)
+            .collect()
+    };
+
+    let failed = of_method("task.get");
+    let messages: Vec<&str> = failed
+        .iter()
+        .filter_map(|line| line["fields"]["message"].as_str())
+        .collect();
+    assert!(messages.iter().any(|m| m.starts_with("Request failed")));
+    assert_eq!(messages.last(), Some(&"Request handled"));
+    let request_id = &failed[0]["span"]["request_id"];
+    assert!(request_id.is_string());
+    assert!(
+        failed
+            .iter()
+            .all(|line| line["span"]["request_id"] == *request_id)
+    );
+
+    let handled = failed.last().unwrap();
+    assert_eq!(handled["fields"]["outcome"], "error");
+    assert!(handled["fields"]["code"].is_i64());
+    assert!(handled["fields"]["params_bytes"].as_u64().unwrap() > 0);
+    assert!(handled["fields"]["elapsed_ms"].is_f64());
+
+    let listed = of_method("task.list");
+    assert_eq!(listed.last().unwrap()["fields"]["outcome"], "ok");
+    assert_ne!(listed[0]["span"]["request_id"], *request_id);
+    Ok(())
+}
@@ -0,0 +1,25 @@
+//! Keeps `PROTOCOL.md` in step with the method registry. After changing a
+//! method, regenerate it with
+//! `UPDATE_PROTOCOL_DOC=1 cargo test -p mootimer-daemon --test protocol_reference`.
+
+use mootimer_daemon::protocol;
+use std::path::Path;
+
+#[test]
+fn test_protocol_reference_is_up_to_date() {
+    let generated = protocol::reference_markdown();
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("PROTOCOL.md");
+    std::fs::write(&out, &generated).unwrap();
+
+    let snapshot = Path::new(env!("CARGO_MANIFEST_DIR")).join("PROTOCOL.md");
+    if std::env::var_os("UPDATE_PROTOCOL_DOC").is_some() {
+        std::fs::write(&snapshot, &generated).unwrap();
+        return;
+    }
+    let committed = std::fs::read_to_string(&snapshot).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "PROTOCOL.md is out of date; compare with {} or rerun with UPDATE_PROTOCOL_DOC=1",
+        out.display()
+    );
+}
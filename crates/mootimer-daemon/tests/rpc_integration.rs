@@ -24,7 +24,7 @@ async fn test_rpc_crud_operations() -> Result<()> {
     let profile_manager = Arc::new(ProfileManager::new(event_manager.clone())?);
     let task_manager = Arc::new(TaskManager::new(event_manager.clone())?);
     let entry_manager = Arc::new(EntryManager::new(event_manager.clone())?);
-    let config_manager = Arc::new(ConfigManager::new()?);
+    let config_manager = Arc::new(ConfigManager::new(event_manager.clone())?);
     let sync_manager = Arc::new(SyncManager::new()?);
 
     let api_handler = Arc::new(ApiHandler::new(
@@ -88,7 +88,8 @@ async fn test_rpc_crud_operations() -> Result<()> {
         .as_str()
         .expect("Timer ID should be string")
         .to_string();
-    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    // Entries shorter than a second are rejected as zero-length.
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
     let stop_res = client.timer_stop(&timer_id).await?;
     let entry_id = stop_res["id"].as_str().expect("Entry ID").to_string();
 
@@ -0,0 +1,308 @@
+//! Desktop notifications for daemon events, shared by the TUI and the
+//! `mootimer-notify` sidecar.
+//!
+//! [`Notifier`] turns the daemon's event notifications into the desktop
+//! notifications the TUI shows: the five-minute warning, finished
+//! countdowns, pomodoro phases and budget alerts. Text comes from
+//! `mootimer_core::messages` in `tui.locale`, or `daemon.locale` when that
+//! is unset.
+
+use mootimer_client::Notification;
+use mootimer_core::messages;
+use mootimer_core::models::Config;
+use serde_json::Value;
+
+pub fn send_os_notification(title: &str, body: &str) {
+    if let Err(e) = notify_rust::Notification::new()
+        .summary(title)
+        .body(body)
+        .icon("clock")
+        .timeout(notify_rust::Timeout::Milliseconds(5000))
+        .show()
+    {
+        tracing::error!("Failed to send notification: {}", e);
+    }
+}
+
+pub fn send_urgent_notification(title: &str, body: &str) {
+    let mut notification = notify_rust::Notification::new();
+    notification
+        .summary(title)
+        .body(body)
+        .icon("alarm-clock")
+        .timeout(notify_rust::Timeout::Milliseconds(10000));
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    notification.urgency(notify_rust::Urgency::Critical);
+
+    if let Err(e) = notification.show() {
+        tracing::error!("Failed to send urgent notification: {}", e);
+    }
+}
+
+/// A desktop notification to show.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OsNotification {
+    pub title: String,
+    pub body: String,
+    /// Shown with [`send_urgent_notification`] rather than
+    /// [`send_os_notification`].
+    pub urgent: bool,
+}
+
+impl OsNotification {
+    fn normal(title: String, body: String) -> Self {
+        Self {
+            title,
+            body,
+            urgent: false,
+        }
+    }
+
+    pub fn show(&self) {
+        if self.urgent {
+            send_urgent_notification(&self.title, &self.body);
+        } else {
+            send_os_notification(&self.title, &self.body);
+        }
+    }
+}
+
+pub struct Notifier {
+    config: Config,
+    five_min_warning_shown: bool,
+}
+
+impl Notifier {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            five_min_warning_shown: false,
+        }
+    }
+
+    fn message(&self, key: &str, args: &[(&str, &str)]) -> String {
+        let locale = self
+            .config
+            .tui
+            .locale
+            .as_deref()
+            .unwrap_or(&self.config.daemon.locale);
+        messages::render(locale, key, args)
+    }
+
+    /// The desktop notification for a daemon notification, if it calls for
+    /// one. Config events replace the config the text is taken from.
+    pub fn handle(&mut self, notification: &Notification) -> Option<OsNotification> {
+        let params = &notification.params;
+        match notification.method.as_str() {
+            "timer.event" => self.timer_event(params),
+            "budget.event" => Some(budget_event(params)),
+            "config.event" => {
+                match serde_json::from_value(params["config"].clone()) {
+                    Ok(config) => self.config = config,
+                    Err(e) => tracing::warn!("Ignoring unreadable config event: {}", e),
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn timer_event(&mut self, params: &Value) -> Option<OsNotification> {
+        let event_type = params.get("event_type")?.as_object()?;
+        match event_type.get("type")?.as_str()? {
+            "tick" => {
+                let remaining = event_type.get("remaining_seconds")?.as_u64()?;
+                if remaining <= 300 && remaining > 295 && !self.five_min_warning_shown {
+                    self.five_min_warning_shown = true;
+                    return Some(OsNotification::normal(
+                        self.message("timer.five_minutes_left.title", &[]),
+                        self.message("timer.five_minutes_left.body", &[]),
+                    ));
+                }
+                None
+            }
+            "started" => {
+                self.five_min_warning_shown = false;
+                None
+            }
+            "countdown_completed" => Some(OsNotification {
+                title: self.message("timer.countdown_completed.title", &[]),
+                body: self.message("timer.countdown_completed.body", &[]),
+                urgent: true,
+            }),
+            "phase_completed" => {
+                let key = match event_type.get("phase")?.as_str().unwrap_or("unknown") {
+                    "work" => "phase.work_completed",
+                    "short_break" => "phase.short_break_completed",
+                    "long_break" => "phase.long_break_completed",
+                    _ => "phase.completed",
+                };
+                Some(OsNotification::normal(
+                    self.message(&format!("{}.title", key), &[]),
+                    self.message(&format!("{}.body", key), &[]),
+                ))
+            }
+            "phase_changed" => {
+                let phase = event_type.get("new_phase")?.as_str()?;
+                let seconds = match phase {
+                    "short_break" => self.config.pomodoro.short_break,
+                    "long_break" => self.config.pomodoro.long_break,
+                    _ => return None,
+                };
+                let minutes = (seconds / 60).to_string();
+                let args = [("minutes", minutes.as_str())];
+                Some(OsNotification::normal(
+                    self.message(&format!("phase.{}_started.title", phase), &args),
+                    self.message(&format!("phase.{}_started.body", phase), &args),
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn budget_event(params: &Value) -> OsNotification {
+    let description = params
+        .get("description")
+        .and_then(|v| v.as_str())
+        .unwrap_or("a budget");
+    let used = params
+        .get("used_seconds")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let limit = params
+        .pointer("/budget/limit_seconds")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let exceeded = params.pointer("/event_type/type").and_then(|v| v.as_str()) == Some("exceeded");
+
+    let title = if exceeded {
+        "⛔ Budget Used Up"
+    } else {
+        "⚠️  Budget at 80%"
+    };
+    let body = format!(
+        "{}: {} of {}",
+        description,
+        format_duration_hm(used),
+        format_duration_hm(limit)
+    );
+    OsNotification::normal(title.to_string(), body)
+}
+
+fn format_duration_hm(seconds: u64) -> String {
+    format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn notification(method: &str, params: Value) -> Notification {
+        Notification {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    fn timer(event_type: Value) -> Notification {
+        notification(
+            "timer.event",
+            json!({ "profile_id": "work", "timer_id": "t1", "event_type": event_type }),
+        )
+    }
+
+    #[test]
+    fn test_five_minute_warning_once_per_timer() {
+        let mut notifier = Notifier::new(Config::default());
+        let tick =
+            |remaining: u64| timer(json!({ "type": "tick", "remaining_seconds": remaining }));
+
+        assert_eq!(notifier.handle(&tick(301)), None);
+        let warning = notifier.handle(&tick(300)).unwrap();
+        assert_eq!(
+            warning.title,
+            messages::lookup("en", "timer.five_minutes_left.title")
+        );
+        assert!(!warning.urgent);
+        assert_eq!(notifier.handle(&tick(299)), None);
+
+        notifier.handle(&timer(json!({ "type": "started" })));
+        assert!(notifier.handle(&tick(298)).is_some());
+    }
+
+    #[test]
+    fn test_breaks_and_countdowns() {
+        let mut config = Config::default();
+        config.pomodoro.short_break = 7 * 60;
+        let mut notifier = Notifier::new(config);
+
+        let countdown = notifier
+            .handle(&timer(json!({ "type": "countdown_completed" })))
+            .unwrap();
+        assert!(countdown.urgent);
+
+        let short_break = notifier
+            .handle(&timer(
+                json!({ "type": "phase_changed", "new_phase": "short_break" }),
+            ))
+            .unwrap();
+        assert_eq!(
+            short_break.body,
+            messages::render("en", "phase.short_break_started.body", &[("minutes", "7")])
+        );
+        assert_eq!(
+            notifier.handle(&timer(
+                json!({ "type": "phase_changed", "new_phase": "work" })
+            )),
+            None
+        );
+        assert_eq!(
+            notifier.handle(&notification("task.event", json!({}))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_follows_config_events() {
+        let mut notifier = Notifier::new(Config::default());
+        let mut config = Config::default();
+        config.daemon.locale = "de".to_string();
+        notifier.handle(&notification(
+            "config.event",
+            json!({ "event_type": { "type": "updated" }, "config": config }),
+        ));
+
+        let completed = notifier
+            .handle(&timer(
+                json!({ "type": "phase_completed", "phase": "work" }),
+            ))
+            .unwrap();
+        assert_eq!(
+            completed.title,
+            messages::lookup("de", "phase.work_completed.title")
+        );
+    }
+
+    #[test]
+    fn test_budget_alert() {
+        let mut notifier = Notifier::new(Config::default());
+        let alert = notifier
+            .handle(&notification(
+                "budget.event",
+                json!({
+                    "event_type": { "type": "exceeded" },
+                    "description": "Client X",
+                    "used_seconds": 3600 * 10 + 300,
+                    "budget": { "limit_seconds": 36000 },
+                }),
+            ))
+            .unwrap();
+        assert_eq!(alert.title, "⛔ Budget Used Up");
+        assert_eq!(alert.body, "Client X: 10h 05m of 10h 00m");
+    }
+}
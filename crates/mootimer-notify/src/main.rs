@@ -0,0 +1,106 @@
+use anyhow::Result;
+use clap::Parser;
+use mootimer_client::{MooTimerClient, Notification};
+use mootimer_core::models::Config;
+use mootimer_core::storage::get_config_dir;
+use mootimer_notify::Notifier;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+use tokio::time::Duration;
+
+/// How often a lost daemon connection is retried.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Parser, Debug)]
+#[command(name = "mootimer-notify")]
+#[command(
+    about = "Sends desktop notifications for MooTimer events without the TUI running",
+    long_about = None
+)]
+struct Args {
+    #[arg(short, long, default_value = "/tmp/mootimer.sock")]
+    socket: String,
+
+    /// Config file the notification text and break lengths are read from
+    /// until the daemon sends a config change. Defaults to the daemon's.
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+}
+
+/// The config at `path`, or the defaults if it can't be read.
+fn load_config(path: &Path) -> Config {
+    let config = std::fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(serde_json::from_str(&content)?));
+    match config {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::warn!(
+                "Using default config, {} is unusable: {}",
+                path.display(),
+                e
+            );
+            Config::default()
+        }
+    }
+}
+
+async fn connect(client: &MooTimerClient) -> Result<mpsc::Receiver<Notification>> {
+    let notifications = client.subscribe_notifications().await?;
+    client.events_subscribe(&["*"]).await?;
+    Ok(notifications)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "mootimer_notify=info".into()),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    let args = Args::parse();
+    let config_path = args
+        .config
+        .unwrap_or_else(|| get_config_dir().join("config.json"));
+
+    let client = MooTimerClient::new(args.socket.clone()).with_client_name("mootimer-notify");
+    let mut notifier = Notifier::new(load_config(&config_path));
+
+    let mut warned = false;
+    let mut notifications = loop {
+        match connect(&client).await {
+            Ok(notifications) => break notifications,
+            Err(e) if !warned => {
+                tracing::warn!("Waiting for the daemon at {}: {}", args.socket, e);
+                warned = true;
+            }
+            Err(e) => tracing::debug!("Daemon still unreachable: {}", e),
+        }
+        tokio::time::sleep(RECONNECT_INTERVAL).await;
+    };
+    tracing::info!("Connected to {}", args.socket);
+
+    let mut reconnect = tokio::time::interval(RECONNECT_INTERVAL);
+    loop {
+        tokio::select! {
+            Some(notification) = notifications.recv() => {
+                if let Some(os_notification) = notifier.handle(&notification) {
+                    os_notification.show();
+                }
+            }
+            _ = reconnect.tick() => {
+                // The client subscribes again on the new connection by itself.
+                if !client.is_connected().await && client.health_check().await.is_ok() {
+                    tracing::info!("Reconnected to {}", args.socket);
+                    notifier = Notifier::new(load_config(&config_path));
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    Ok(())
+}
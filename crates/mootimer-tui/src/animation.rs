@@ -0,0 +1,401 @@
+//! Dashboard animations loaded from the `animations/` config folder.
+//!
+//! Besides the built-in tomato and cow, the Dashboard can play any
+//! `animations/<name>.txt` file, picked per timer mode with
+//! `tui.pomodoro_animation` and `tui.countdown_animation`. A file looks
+//! like this:
+//!
+//! ```text
+//! # Lines before the first section are comments or settings.
+//! frame_ms = 250
+//!
+//! [running]
+//!  (o_o)
+//! ---
+//!  (-_-)
+//!
+//! [paused]
+//!  (u_u) zZ
+//! ```
+//!
+//! Frames are separated by `---` lines. `[running]` is required; without a
+//! `[paused]` section a paused timer holds the first running frame. Files
+//! that don't parse or exceed the size limits are skipped with a warning.
+
+use ratatui::text::Line;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Names the TUI draws itself; files with these names are ignored.
+pub const BUILT_IN: [&str; 3] = ["tomato", "cow", "none"];
+
+pub const MAX_WIDTH: usize = 60;
+pub const MAX_HEIGHT: usize = 20;
+pub const MAX_FRAMES: usize = 64;
+/// Larger files are skipped without being read.
+const MAX_FILE_BYTES: u64 = 64 * 1024;
+
+const DEFAULT_FRAME_MS: u64 = 200;
+const MIN_FRAME_MS: u64 = 50;
+const MAX_FRAME_MS: u64 = 5000;
+
+const SEPARATOR: &str = "---";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    pub name: String,
+    pub frame_ms: u64,
+    running: Vec<Vec<String>>,
+    paused: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Running,
+    Paused,
+}
+
+impl Animation {
+    /// Parses an animation file. Errors name the offending line.
+    pub fn parse(name: &str, text: &str) -> Result<Self, String> {
+        let mut frame_ms = DEFAULT_FRAME_MS;
+        let mut section = None;
+        let mut running = Vec::new();
+        let mut paused = Vec::new();
+        let mut frame: Vec<String> = Vec::new();
+
+        for (index, raw) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = raw.trim_end();
+            let header = match line.trim() {
+                "[running]" => Some(Section::Running),
+                "[paused]" => Some(Section::Paused),
+                _ => None,
+            };
+
+            if let Some(next) = header {
+                if let Some(current) = section {
+                    let frames = match current {
+                        Section::Running => &mut running,
+                        Section::Paused => &mut paused,
+                    };
+                    push_frame(frames, &mut frame, number)?;
+                }
+                let seen = match next {
+                    Section::Running => !running.is_empty(),
+                    Section::Paused => !paused.is_empty(),
+                };
+                if seen || section == Some(next) {
+                    return Err(format!(
+                        "line {}: duplicate {} section",
+                        number,
+                        line.trim()
+                    ));
+                }
+                section = Some(next);
+                continue;
+            }
+
+            let Some(current) = section else {
+                frame_ms = parse_setting(line, number, frame_ms)?;
+                continue;
+            };
+
+            if line.contains('\t') {
+                return Err(format!("line {}: tabs are not allowed, use spaces", number));
+            }
+            if line == SEPARATOR {
+                let frames = match current {
+                    Section::Running => &mut running,
+                    Section::Paused => &mut paused,
+                };
+                push_frame(frames, &mut frame, number)?;
+            } else {
+                frame.push(line.to_string());
+                let width = Line::raw(line).width();
+                if width > MAX_WIDTH {
+                    return Err(format!(
+                        "line {}: {} columns wide, at most {} are allowed",
+                        number, width, MAX_WIDTH
+                    ));
+                }
+            }
+        }
+
+        match section {
+            Some(Section::Running) => push_frame(&mut running, &mut frame, text.lines().count())?,
+            Some(Section::Paused) => push_frame(&mut paused, &mut frame, text.lines().count())?,
+            None => {}
+        }
+        if running.is_empty() {
+            return Err("no [running] section".to_string());
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            frame_ms,
+            running,
+            paused,
+        })
+    }
+
+    /// The frame to show after `elapsed` of playback.
+    pub fn frame(&self, paused: bool, elapsed: Duration) -> &[String] {
+        let frames = match (paused, self.paused.is_empty()) {
+            (true, true) => return &self.running[0],
+            (true, false) => &self.paused,
+            (false, _) => &self.running,
+        };
+        let index = (elapsed.as_millis() / self.frame_ms as u128) as usize % frames.len();
+        &frames[index]
+    }
+
+    /// Width and height of the largest frame, so the animation doesn't
+    /// shift around when frames differ in size.
+    pub fn size(&self) -> (u16, u16) {
+        let frames = self.running.iter().chain(&self.paused);
+        let width = frames
+            .clone()
+            .flatten()
+            .map(|line| Line::raw(line.as_str()).width())
+            .max()
+            .unwrap_or(0);
+        let height = frames.map(Vec::len).max().unwrap_or(0);
+        (width as u16, height as u16)
+    }
+}
+
+/// Reads a `key = value` line from the top of the file.
+fn parse_setting(line: &str, number: usize, frame_ms: u64) -> Result<u64, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(frame_ms);
+    }
+    match line.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+        Some(("frame_ms", value)) => {
+            let ms: u64 = value
+                .parse()
+                .map_err(|_| format!("line {}: frame_ms must be a number", number))?;
+            if !(MIN_FRAME_MS..=MAX_FRAME_MS).contains(&ms) {
+                return Err(format!(
+                    "line {}: frame_ms must be between {} and {}",
+                    number, MIN_FRAME_MS, MAX_FRAME_MS
+                ));
+            }
+            Ok(ms)
+        }
+        Some((key, _)) => Err(format!("line {}: unknown setting '{}'", number, key)),
+        None => Err(format!(
+            "line {}: expected a setting or a [running] section",
+            number
+        )),
+    }
+}
+
+/// Ends the frame being read, trimming blank lines around it.
+fn push_frame(
+    frames: &mut Vec<Vec<String>>,
+    frame: &mut Vec<String>,
+    number: usize,
+) -> Result<(), String> {
+    let lines = std::mem::take(frame);
+    let start = lines.iter().position(|l| !l.is_empty());
+    let end = lines.iter().rposition(|l| !l.is_empty());
+    let (Some(start), Some(end)) = (start, end) else {
+        return Err(format!("line {}: empty frame", number));
+    };
+    if end - start + 1 > MAX_HEIGHT {
+        return Err(format!(
+            "line {}: frame is {} lines tall, at most {} are allowed",
+            number,
+            end - start + 1,
+            MAX_HEIGHT
+        ));
+    }
+    if frames.len() == MAX_FRAMES {
+        return Err(format!(
+            "line {}: at most {} frames per section are allowed",
+            number, MAX_FRAMES
+        ));
+    }
+    frames.push(lines[start..=end].to_vec());
+    Ok(())
+}
+
+/// The animations found in the config folder.
+#[derive(Debug, Default)]
+pub struct Animations {
+    custom: Vec<Animation>,
+}
+
+impl Animations {
+    /// Reads every `*.txt` file in `dir`, in name order. A missing folder
+    /// is fine; each skipped file comes back as a warning.
+    pub fn load(dir: &Path) -> (Self, Vec<String>) {
+        let mut custom = Vec::new();
+        let mut warnings = Vec::new();
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return (Self { custom }, warnings);
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if BUILT_IN.contains(&name) {
+                warnings.push(format!(
+                    "Ignoring animation {}: '{}' is a built-in name",
+                    path.display(),
+                    name
+                ));
+                continue;
+            }
+            let text = match std::fs::metadata(&path) {
+                Ok(meta) if meta.len() > MAX_FILE_BYTES => {
+                    Err(format!("larger than {} KiB", MAX_FILE_BYTES / 1024))
+                }
+                _ => std::fs::read_to_string(&path).map_err(|e| e.to_string()),
+            };
+            match text.and_then(|text| Animation::parse(name, &text)) {
+                Ok(animation) => custom.push(animation),
+                Err(e) => warnings.push(format!("Ignoring animation {}: {}", path.display(), e)),
+            }
+        }
+
+        (Self { custom }, warnings)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Animation> {
+        self.custom.iter().find(|a| a.name == name)
+    }
+
+    /// Built-in names followed by the loaded files, as offered in Settings.
+    pub fn names(&self) -> Vec<&str> {
+        BUILT_IN
+            .iter()
+            .copied()
+            .chain(self.custom.iter().map(|a| a.name.as_str()))
+            .collect()
+    }
+}
+
+pub fn dir() -> PathBuf {
+    mootimer_core::storage::get_config_dir().join("animations")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLINK: &str = "\
+# A blinking face.
+frame_ms = 100
+
+[running]
+ (o_o)
+---
+ (-_-)
+
+[paused]
+ (u_u)
+";
+
+    #[test]
+    fn test_parses_sections_and_frames() {
+        let animation = Animation::parse("blink", BLINK).unwrap();
+        assert_eq!(animation.frame_ms, 100);
+        assert_eq!(animation.frame(false, Duration::ZERO), [" (o_o)"]);
+        assert_eq!(
+            animation.frame(false, Duration::from_millis(150)),
+            [" (-_-)"]
+        );
+        assert_eq!(
+            animation.frame(false, Duration::from_millis(250)),
+            [" (o_o)"]
+        );
+        assert_eq!(
+            animation.frame(true, Duration::from_millis(150)),
+            [" (u_u)"]
+        );
+        assert_eq!(animation.size(), (6, 1));
+    }
+
+    #[test]
+    fn test_paused_falls_back_to_first_running_frame() {
+        let animation = Animation::parse("dots", "[running]\n.\n---\n..\n").unwrap();
+        assert_eq!(animation.frame_ms, DEFAULT_FRAME_MS);
+        assert_eq!(animation.frame(true, Duration::from_secs(1)), ["."]);
+    }
+
+    #[test]
+    fn test_keeps_inner_blank_lines_and_indentation() {
+        let animation = Animation::parse("box", "[running]\n\n  /\\\n\n  \\/  \n\n").unwrap();
+        assert_eq!(
+            animation.frame(false, Duration::ZERO),
+            ["  /\\", "", "  \\/"]
+        );
+    }
+
+    #[test]
+    fn test_rejects_malformed_files() {
+        let wide = format!("[running]\n{}\n", "x".repeat(MAX_WIDTH + 1));
+        let tall = format!("[running]\n{}", "x\n".repeat(MAX_HEIGHT + 1));
+        let many = format!("[running]\n{}", "x\n---\n".repeat(MAX_FRAMES) + "x\n");
+        let cases = [
+            ("", "no [running] section"),
+            ("# only a comment\n", "no [running] section"),
+            ("[paused]\nzz\n", "no [running] section"),
+            ("hello\n[running]\nx\n", "line 1: expected a setting"),
+            (
+                "speed = 3\n[running]\nx\n",
+                "line 1: unknown setting 'speed'",
+            ),
+            (
+                "frame_ms = fast\n[running]\nx\n",
+                "line 1: frame_ms must be a number",
+            ),
+            (
+                "frame_ms = 10\n[running]\nx\n",
+                "line 1: frame_ms must be between",
+            ),
+            ("[running]\n", "line 1: empty frame"),
+            ("[running]\nx\n---\n---\ny\n", "line 4: empty frame"),
+            ("[running]\nx\n---\n", "line 3: empty frame"),
+            (
+                "[running]\nx\n[running]\ny\n",
+                "line 3: duplicate [running] section",
+            ),
+            ("[running]\n\tx\n", "line 2: tabs are not allowed"),
+            (wide.as_str(), "line 2: 61 columns wide"),
+            (tall.as_str(), "frame is 21 lines tall"),
+            (many.as_str(), "at most 64 frames"),
+        ];
+
+        for (text, expected) in cases {
+            let error = Animation::parse("bad", text).unwrap_err();
+            assert!(error.contains(expected), "{:?}: {}", text, error);
+        }
+    }
+
+    #[test]
+    fn test_measures_width_in_columns() {
+        let line = "🍅".repeat(MAX_WIDTH / 2);
+        assert!(Animation::parse("ok", &format!("[running]\n{}\n", line)).is_ok());
+        let error = Animation::parse("wide", &format!("[running]\n{}🍅\n", line)).unwrap_err();
+        assert!(error.contains("62 columns"), "{}", error);
+    }
+
+    #[test]
+    fn test_missing_folder_loads_nothing() {
+        let (animations, warnings) = Animations::load(Path::new("/nonexistent/animations"));
+        assert!(warnings.is_empty());
+        assert_eq!(animations.names(), BUILT_IN);
+        assert!(animations.get("tomato").is_none());
+    }
+}
@@ -1,10 +1,31 @@
+use crate::animation::{self, Animations};
+use crate::confirm::{ConfirmAction, ConfirmDialog, Danger};
+use crate::entry_range::{self, EntryRange, Granularity};
+use crate::input::CountPrefix;
+use crate::keybindings::{Action, Keybindings};
+use crate::keymap::HelpState;
+use crate::log_record::{LogColumns, LogRecord};
+use crate::settings::{self, LocalSetting, SettingAction, SettingDef, SettingKind, SettingTarget};
+use crate::sync_progress::SyncProgress;
+use crate::task_detail::TaskDetail;
+use crate::terminal_title::TerminalTitle;
+use crate::timer_clock::TimerClock;
 use crate::ui::cow::CowState;
 use crate::ui::tomato::TomatoState;
 use anyhow::Result;
-use chrono::{DateTime, Local, NaiveTime, Utc};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, Utc};
 use mootimer_client::MooTimerClient;
+use mootimer_core::messages;
+use mootimer_core::models::config::{DEFAULT_STATUS_BAR_FORMAT, TuiConfig};
+use mootimer_core::models::{
+    ActiveTimer, DEFAULT_SUMMARY_FORMAT, TaskPriority, TaskStatus, TimerDisplay, TimerState,
+    default_color_and_icon,
+};
+use mootimer_core::report::{TaskBreakdownRow, task_breakdown, task_breakdown_csv};
+use mootimer_core::search::{FuzzyMatch, TagMode, fuzzy_match, split_tags};
+use ratatui::style::Color;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,6 +39,15 @@ pub enum AppView {
     Logs,
 }
 
+/// Where a task with `blocked_by` links stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockState {
+    /// At least one blocker is unfinished or not loaded.
+    Blocked,
+    /// Every blocker is done, but the links haven't been dropped yet.
+    Cleared,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DashboardPane {
     TimerConfig,
@@ -25,6 +55,21 @@ pub enum DashboardPane {
     ProfileList,
 }
 
+/// A side of the split view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitPane {
+    Left,
+    Right,
+}
+
+/// What the unfocused side of the split view shows, and where its
+/// selection was left. The focused side lives in `App` itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaneState {
+    pub view: AppView,
+    pub selected_index: usize,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputMode {
     Normal,
@@ -32,50 +77,86 @@ pub enum InputMode {
     QuickAddTask,
     EditTask,
     SearchTasks,
-    DeleteTaskConfirm,
+    /// A `ConfirmDialog` is open; see `App::confirm_dialog`.
+    Confirm,
     FilterEntries,
-    ConfigPomodoro,
-    ConfigShortBreak,
-    ConfigLongBreak,
-    ConfigCountdown,
+    EditSetting,
     NewProfile,
     RenameProfile,
-    DeleteProfileConfirm,
     EditEntryDuration,
-    ConfirmQuit,
     PomodoroBreakFinished,
     MoveTask,
+    MoveEntry,
     NewEntryStart,
     NewEntryEnd,
     NewEntryTask,
     NewEntryDescription,
+    BulkStatusFrom,
+    BulkStatusTo,
+    BulkTag,
+    BulkUntag,
+    TaskDetail,
+    SelectBranch,
+    SyncDiff,
+    SyncConflicts,
+    EntriesDate,
+    /// `45m fix login bug`: an entry that just ended; see `parse_quick_log`.
+    QuickLog,
+    ReportCompare,
+    SessionCapped,
+    CountdownPresets,
+    EditEntryTimestamp,
+    ExportConfig,
+    ImportConfig,
 }
 
+/// Which of an entry's times `[`/`]` move.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SettingsItem {
-    PomodoroWork,
-    PomodoroShortBreak,
-    PomodoroLongBreak,
-    CountdownDefault,
-    AudioAlerts,
-    CowModal,
-    SyncAutoCommit,
-    SyncInitRepo,
-    SyncNow,
+pub enum TimestampField {
+    Start,
+    End,
+}
+
+/// An entry whose start and end are being nudged in
+/// `InputMode::EditEntryTimestamp`; saved on Enter.
+#[derive(Debug, Clone)]
+pub struct EntryTimestampEdit {
+    pub entry: Value,
+    pub field: TimestampField,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Digits typed before `[`/`]`, multiplying the nudge.
+    pub count: String,
+}
+
+/// The part of a session cut off by its task's `max_session_seconds`,
+/// from a `session_capped` timer event.
+#[derive(Debug, Clone)]
+pub struct CappedSession {
+    pub task_id: String,
+    pub cap_seconds: u64,
+    pub overflow_start: String,
+    pub overflow_end: String,
+    pub overflow_seconds: u64,
+}
+
+/// An `entry.stats_compare` result with the periods it covers.
+#[derive(Debug, Clone)]
+pub struct ReportComparison {
+    pub label_a: String,
+    pub label_b: String,
+    pub result: Value,
 }
 
-impl SettingsItem {
-    pub const ALL: [Self; 9] = [
-        Self::PomodoroWork,
-        Self::PomodoroShortBreak,
-        Self::PomodoroLongBreak,
-        Self::CountdownDefault,
-        Self::AudioAlerts,
-        Self::CowModal,
-        Self::SyncAutoCommit,
-        Self::SyncInitRepo,
-        Self::SyncNow,
-    ];
+/// One profile's row in the Dashboard's all-profiles panel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FleetRow {
+    pub profile_id: String,
+    /// The profile's running timer, else its newest paused one.
+    pub timer: Option<ActiveTimer>,
+    pub today_seconds: u64,
+    /// Title, or id when untitled, of the task with the most time today.
+    pub top_task: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -94,7 +175,16 @@ pub struct App {
     pub profile_id: String,
     pub current_view: AppView,
     pub focused_pane: DashboardPane,
+    /// Two views side by side: the left one as chosen with the number
+    /// keys, the right one with Alt and a number.
+    pub split_mode: bool,
+    pub split_focus: SplitPane,
+    pub other_pane: PaneState,
     pub show_help: bool,
+    /// False after a refresh found the daemon unreachable, until the
+    /// reconnect loop in `main` gets through again.
+    pub daemon_connected: bool,
+    pub help: HelpState,
     pub input_mode: InputMode,
     pub input_buffer: String,
     pub input_buffer_2: String,
@@ -104,19 +194,61 @@ pub struct App {
     pub entry_filter: String,
     pub task_search: String,
     pub show_archived: bool,
+    /// Task copied with `Y`, as (profile id, task id), for `P` to paste.
+    pub yanked_task: Option<(String, String)>,
     pub selected_setting_index: usize,
 
     pub timer_info: Option<Value>,
+    /// Interpolates `timer_info`'s elapsed time between daemon ticks.
+    pub timer_clock: Option<TimerClock>,
+    /// The task linked to the running timer, as returned by
+    /// `task.get_active`.
+    pub active_task: Option<Value>,
+    /// The profile's timers, oldest first.
     pub active_timers: Vec<Value>,
     pub selected_timer_index: usize,
+    /// Which of `active_timers` the Dashboard shows as `timer_info`; the
+    /// oldest one when unset or gone.
+    pub focused_timer_id: Option<String>,
+    /// Whether the Dashboard shows every profile's timers side by side.
+    pub multi_dashboard: bool,
+    /// Timers across all profiles from `timer.list`, fetched only while
+    /// `multi_dashboard` is on.
+    pub all_timers: Vec<Value>,
+    /// Position in `multi_dashboard_columns`, counted across columns.
+    pub selected_multi_timer: usize,
+    /// Whether the Dashboard's left column shows every profile at once.
+    pub fleet_view: bool,
+    pub fleet_rows: Vec<FleetRow>,
+    pub selected_fleet_row: usize,
+    fleet_fetched_at: Option<Instant>,
     pub stats_today: Option<Value>,
+    /// From `entry.last`, shown under "Ready to Start".
+    pub last_entry: Option<Value>,
     pub tasks: Vec<Value>,
     pub entries: Vec<Value>,
+    /// The days the Entries view shows.
+    pub entry_range: EntryRange,
+    /// Stored entries that fail validation, by id, with the reason.
+    pub invalid_entries: HashMap<String, String>,
     pub report_entries: Vec<Value>,
     pub report_stats: Option<Value>,
     pub sync_status: Option<Value>,
+    /// The latest commits from `sync.history`, shown under the settings.
+    pub sync_history: Vec<Value>,
+    /// A manual sync the TUI is waiting on.
+    pub sync_progress: Option<SyncProgress>,
+    /// Esc stopped the wait for a manual sync, so its outcome is taken
+    /// from the daemon's `finished` or `failed` event instead.
+    pub sync_detached: bool,
     pub config: Option<Value>,
-    pub log_lines: Vec<String>,
+    /// Daemon log, newest first.
+    pub log_records: Vec<LogRecord>,
+    pub log_columns: LogColumns,
+    /// Only records from this target are listed, when set.
+    pub log_target_filter: Option<String>,
+    /// Shows every field of the selected record below the list.
+    pub log_detail_open: bool,
     pub profiles: Vec<Value>,
 
     cross_profile_cache: HashMap<String, (Vec<Value>, Instant)>,
@@ -129,20 +261,77 @@ pub struct App {
     pub selected_kanban_card_index: usize,
     pub report_period: String,
     pub report_profile: String,
+    /// Groups the report breakdown by the device entries were made on
+    /// instead of by task.
+    pub report_by_device: bool,
+    /// Set by `[V]` in Reports; cleared when the report is refreshed.
+    pub report_compare: Option<ReportComparison>,
+    /// `entry.stats_hourly` for the report period, shown after `[H]`;
+    /// fetched again when the report is refreshed.
+    pub report_hourly: Option<Vec<Value>>,
+    /// `budget.list` for the reported profile; empty for all profiles.
+    pub report_budgets: Vec<Value>,
+    pub capped_session: Option<CappedSession>,
+    pub timestamp_edit: Option<EntryTimestampEdit>,
+    pub last_timer_start: Option<TimerStart>,
+    pub selected_preset_index: usize,
     pub selected_timer_type: TimerType,
     pub pomodoro_minutes: u64,
     pub countdown_minutes: u64,
     pub should_quit: bool,
     pub status_message: String,
+    pub count: CountPrefix,
     pub five_min_warning_shown: bool,
+    /// Notification text from `system.messages`, in `tui.locale` or the
+    /// daemon's locale. Empty until the config is loaded.
+    pub messages: HashMap<String, String>,
     pub audio_alerts_enabled: bool,
     pub cow_modal_enabled: bool,
+    pub terminal_title: TerminalTitle,
+    /// List pinned, then recently used tasks first on the Dashboard.
+    pub smart_task_order: bool,
+    /// Ask before deleting an entry.
+    pub confirm_entry_delete: bool,
+    pub confirm_dialog: Option<ConfirmDialog>,
+    /// Task ids in due date order while the Dashboard shows tasks by due
+    /// date; `None` otherwise.
+    pub due_order: Option<Vec<String>>,
+    /// When each task last had an entry, over the last `RECENT_TASK_DAYS`.
+    pub recent_task_use: HashMap<String, DateTime<Utc>>,
     pub show_cow_modal: bool,
     pub show_task_description: bool,
+    /// Ids of the tasks marked in the Dashboard's multi-select mode; `None`
+    /// outside it.
+    pub task_selection: Option<BTreeSet<String>>,
+    /// From `tui.priority_colors`, resolved when the config is loaded.
+    pub priority_colors: HashMap<TaskPriority, Color>,
+    /// From `tui.keybindings`, resolved when the config is loaded.
+    pub keybindings: Keybindings,
     pub tomato_state: TomatoState,
     pub cow_state: CowState,
+    /// Custom animations from the `animations/` config folder.
+    pub animations: Animations,
+    /// Custom animations pick their frame from the time since this.
+    pub animation_clock: Instant,
     pub selected_timer_button: usize,
     pub move_task_target_index: usize,
+    /// Filled by `open_branch_picker`, from `sync.list_branches`.
+    pub branches: Vec<Value>,
+    pub selected_branch_index: usize,
+    /// Filled by `open_sync_diff`, from `sync.diff`. The full diff is only
+    /// fetched once `show_full_diff` is first switched on.
+    pub sync_diff: Option<Value>,
+    pub sync_diff_text: Option<String>,
+    pub show_full_diff: bool,
+    pub sync_diff_scroll: usize,
+    /// Filled by `open_sync_conflicts`, from `sync.conflicts`.
+    pub sync_conflicts: Vec<Value>,
+    pub selected_conflict_index: usize,
+    /// The highlighted row of the field table, once Tab moves focus there.
+    pub conflict_field_index: Option<usize>,
+    /// Fields of the selected conflict to take from the remote side when
+    /// resolving as merged; the rest come from the local side.
+    pub conflict_remote_fields: HashSet<String>,
 
     pub new_entry_start: Option<String>,
     pub new_entry_end: Option<String>,
@@ -151,6 +340,11 @@ pub struct App {
     pub new_entry_show_archived: bool,
 
     pub kanban_drag: Option<KanbanDragState>,
+    /// The blocked Dashboard task under the mouse, with the pointer's
+    /// column and row, for the blockers popup.
+    pub blocked_task_hover: Option<(usize, u16, u16)>,
+    pub bulk_from_status: Option<TaskStatus>,
+    pub task_detail: Option<TaskDetail>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -160,6 +354,17 @@ pub enum TimerType {
     Countdown,
 }
 
+/// The parameters a timer was started with, kept so `R` can start the
+/// same timer again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimerStart {
+    pub profile_id: String,
+    pub timer_type: TimerType,
+    /// Work length for pomodoros, total length for countdowns.
+    pub minutes: Option<u64>,
+    pub task_id: Option<String>,
+}
+
 impl App {
     pub fn new(client: MooTimerClient, profile_id: String) -> Self {
         let report_profile = profile_id.clone();
@@ -168,7 +373,15 @@ impl App {
             profile_id,
             current_view: AppView::Dashboard,
             focused_pane: DashboardPane::TimerConfig,
+            split_mode: false,
+            split_focus: SplitPane::Left,
+            other_pane: PaneState {
+                view: AppView::Entries,
+                selected_index: 0,
+            },
             show_help: false,
+            daemon_connected: true,
+            help: HelpState::default(),
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
             input_buffer_2: String::new(),
@@ -177,19 +390,39 @@ impl App {
             entry_filter: String::new(),
             task_search: String::new(),
             show_archived: false,
+            yanked_task: None,
             selected_setting_index: 0,
 
             timer_info: None,
+            timer_clock: None,
+            active_task: None,
             active_timers: Vec::new(),
             selected_timer_index: 0,
+            focused_timer_id: None,
+            multi_dashboard: false,
+            all_timers: Vec::new(),
+            selected_multi_timer: 0,
+            fleet_view: false,
+            fleet_rows: Vec::new(),
+            selected_fleet_row: 0,
+            fleet_fetched_at: None,
             stats_today: None,
+            last_entry: None,
             tasks: Vec::new(),
             entries: Vec::new(),
+            entry_range: EntryRange::today(),
+            invalid_entries: HashMap::new(),
             report_entries: Vec::new(),
             report_stats: None,
             sync_status: None,
+            sync_history: Vec::new(),
+            sync_progress: None,
+            sync_detached: false,
             config: None,
-            log_lines: Vec::new(),
+            log_records: Vec::new(),
+            log_columns: LogColumns::default(),
+            log_target_filter: None,
+            log_detail_open: false,
             profiles: Vec::new(),
             cross_profile_cache: HashMap::new(),
 
@@ -201,20 +434,51 @@ impl App {
             selected_kanban_card_index: 0,
             report_period: "day".to_string(),
             report_profile,
+            report_by_device: false,
+            report_compare: None,
+            report_hourly: None,
+            report_budgets: Vec::new(),
+            capped_session: None,
+            timestamp_edit: None,
+            last_timer_start: None,
+            selected_preset_index: 0,
             selected_timer_type: TimerType::Manual,
             pomodoro_minutes: 25,
             countdown_minutes: 30,
             should_quit: false,
             status_message: String::new(),
+            count: CountPrefix::default(),
             five_min_warning_shown: false,
+            messages: HashMap::new(),
             audio_alerts_enabled: true,
             cow_modal_enabled: true,
+            terminal_title: TerminalTitle::default(),
+            smart_task_order: true,
+            confirm_entry_delete: true,
+            confirm_dialog: None,
+            due_order: None,
+            recent_task_use: HashMap::new(),
             show_cow_modal: false,
             show_task_description: false,
+            task_selection: None,
+            priority_colors: resolve_priority_colors(&TuiConfig::default()),
+            keybindings: Keybindings::default(),
             tomato_state: TomatoState::new(),
             cow_state: CowState::new(),
+            animations: load_animations(),
+            animation_clock: Instant::now(),
             selected_timer_button: 0,
             move_task_target_index: 0,
+            branches: Vec::new(),
+            selected_branch_index: 0,
+            sync_diff: None,
+            sync_diff_text: None,
+            show_full_diff: false,
+            sync_diff_scroll: 0,
+            sync_conflicts: Vec::new(),
+            selected_conflict_index: 0,
+            conflict_field_index: None,
+            conflict_remote_fields: HashSet::new(),
 
             new_entry_start: None,
             new_entry_end: None,
@@ -223,17 +487,41 @@ impl App {
             new_entry_show_archived: false,
 
             kanban_drag: None,
+            blocked_task_hover: None,
+            bulk_from_status: None,
+            task_detail: None,
         }
     }
 
     pub fn handle_input_char(&mut self, c: char) {
-        if self.focused_input_field == 0 {
+        if self.input_mode == InputMode::TaskDetail
+            && let Some(detail) = self.task_detail.as_mut()
+        {
+            detail.insert_char(c);
+        } else if self.focused_input_field == 0 {
             self.input_buffer.push(c);
         } else {
             self.input_buffer_2.push(c);
         }
     }
 
+    /// Appends pasted text to the focused input field.
+    pub fn paste_text(&mut self, text: &str) {
+        let pasted = sanitize_paste(text);
+        let count = pasted.chars().count();
+        for c in pasted.chars() {
+            self.handle_input_char(c);
+        }
+        self.status_message = format!("Pasted {} chars", count);
+    }
+
+    pub fn paste_from_clipboard(&mut self) {
+        match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+            Ok(text) => self.paste_text(&text),
+            Err(e) => self.status_message = format!("Clipboard unavailable: {}", e),
+        }
+    }
+
     pub fn handle_input_backspace(&mut self) {
         if self.focused_input_field == 0 {
             self.input_buffer.pop();
@@ -246,6 +534,12 @@ impl App {
         self.get_profile_name_by_id(&self.profile_id)
     }
 
+    fn current_profile(&self) -> Option<&Value> {
+        self.profiles
+            .iter()
+            .find(|p| p.get("id").and_then(|v| v.as_str()) == Some(self.profile_id.as_str()))
+    }
+
     pub fn get_profile_name_by_id<'a>(&'a self, profile_id: &'a str) -> &'a str {
         self.profiles
             .iter()
@@ -255,16 +549,41 @@ impl App {
             .unwrap_or(profile_id)
     }
 
+    /// The color and icon `profile_id` is shown with. Profiles not loaded
+    /// yet get the same defaults the daemon would give them.
+    pub fn profile_style(&self, profile_id: &str) -> (Color, &str) {
+        let (default_color, default_icon) = default_color_and_icon(profile_id);
+        let profile = self
+            .profiles
+            .iter()
+            .find(|p| p.get("id").and_then(|v| v.as_str()) == Some(profile_id));
+        let field = |key| profile.and_then(|p| p.get(key)).and_then(|v| v.as_str());
+        (
+            crate::ui::helpers::profile_color(field("color").unwrap_or(default_color)),
+            field("icon").unwrap_or(default_icon),
+        )
+    }
+
+    /// How the task's title matches the task search, the same fuzzy match
+    /// `task.search` uses; `None` when it doesn't, or when the task lacks
+    /// one of the search's `#tag` words.
+    pub fn task_search_match(&self, task: &Value) -> Option<FuzzyMatch> {
+        let (query, tags) = split_tags(&self.task_search);
+        let task_tags = task.get("tags").and_then(|v| v.as_array());
+        let has_tag = |tag: &str| task_tags.is_some_and(|t| t.iter().any(|t| t == tag));
+        if !TagMode::All.matches(&tags, has_tag) {
+            return None;
+        }
+        let title = task.get("title").and_then(|v| v.as_str())?;
+        fuzzy_match(&query, title, false)
+    }
+
     pub fn get_filtered_tasks(&self) -> Vec<&Value> {
-        let search = self.task_search.to_lowercase();
-        self.tasks
+        let mut tasks: Vec<&Value> = self
+            .tasks
             .iter()
             .filter(|task| {
-                let status = task
-                    .get("status")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("todo");
-                let is_archived = status == "archived";
+                let is_archived = TaskStatus::of(task) == TaskStatus::Archived;
 
                 if self.show_archived {
                     if !is_archived {
@@ -274,15 +593,31 @@ impl App {
                     return false;
                 }
 
-                if !self.task_search.is_empty()
-                    && let Some(title) = task.get("title").and_then(|v| v.as_str())
-                    && !title.to_lowercase().contains(&search)
-                {
-                    return false;
-                }
-                true
+                self.task_search.is_empty() || self.task_search_match(task).is_some()
             })
-            .collect()
+            .collect();
+
+        if self.show_archived {
+            return tasks;
+        }
+        if let Some(order) = &self.due_order {
+            let position = |task: &Value| {
+                let id = task.get("id").and_then(|v| v.as_str());
+                order.iter().position(|o| Some(o.as_str()) == id)
+            };
+            tasks.retain(|task| position(task).is_some());
+            tasks.sort_by_key(|task| position(task));
+        } else {
+            if self.smart_task_order {
+                smart_order(&mut tasks, &self.recent_task_use);
+            }
+            if !self.task_search.is_empty() {
+                tasks.sort_by_cached_key(|task| {
+                    std::cmp::Reverse(self.task_search_match(task).map_or(0, |m| m.score))
+                });
+            }
+        }
+        tasks
     }
 
     pub fn get_filtered_entries(&self) -> Vec<&Value> {
@@ -339,43 +674,42 @@ impl App {
         }
     }
 
-    pub fn get_kanban_tasks(&self, column_index: usize) -> Vec<&Value> {
-        let search = self.task_search.to_lowercase();
+    /// Whether `task` is a subtask of a loaded task, and so shown on its
+    /// parent's card rather than as a card of its own.
+    fn is_listed_subtask(&self, task: &Value) -> bool {
+        task.get("parent_task_id")
+            .and_then(|v| v.as_str())
+            .is_some_and(|parent| {
+                self.tasks
+                    .iter()
+                    .any(|t| t.get("id").and_then(|v| v.as_str()) == Some(parent))
+            })
+    }
+
+    pub fn subtasks_of(&self, task_id: &str) -> Vec<&Value> {
+        self.tasks
+            .iter()
+            .filter(|t| t.get("parent_task_id").and_then(|v| v.as_str()) == Some(task_id))
+            .collect()
+    }
 
+    pub fn get_kanban_tasks(&self, column_index: usize) -> Vec<&Value> {
         self.tasks
             .iter()
             .filter(|t| {
-                let status = t.get("status").and_then(|v| v.as_str()).unwrap_or("todo");
+                let status = TaskStatus::of(t);
 
                 let matches_status = if self.show_archived {
-                    column_index == 0 && status == "archived"
+                    column_index == 0 && status == TaskStatus::Archived
                 } else {
-                    if status == "archived" {
-                        return false;
-                    }
-                    match column_index {
-                        0 => status == "todo",
-                        1 => status == "in_progress",
-                        2 => status == "done" || status == "completed",
-                        _ => false,
-                    }
+                    kanban_column(status) == Some(column_index)
                 };
 
-                if !matches_status {
+                if !matches_status || self.is_listed_subtask(t) {
                     return false;
                 }
 
-                if !search.is_empty() {
-                    if let Some(title) = t.get("title").and_then(|v| v.as_str()) {
-                        if !title.to_lowercase().contains(&search) {
-                            return false;
-                        }
-                    } else {
-                        return false;
-                    }
-                }
-
-                true
+                self.task_search.is_empty() || self.task_search_match(t).is_some()
             })
             .collect()
     }
@@ -396,18 +730,10 @@ impl App {
         if let Some(task) = tasks.get(self.selected_kanban_card_index) {
             let mut task_clone = (*task).clone();
 
-            let new_status = match new_col {
-                0 => "todo",
-                1 => "in_progress",
-                2 => "done",
-                _ => "todo",
-            };
+            let new_status = kanban_status(new_col);
 
             if let Some(obj) = task_clone.as_object_mut() {
-                obj.insert(
-                    "status".to_string(),
-                    serde_json::Value::String(new_status.to_string()),
-                );
+                obj.insert("status".to_string(), serde_json::json!(new_status));
 
                 self.client
                     .task_update(&self.profile_id, task_clone)
@@ -422,8 +748,123 @@ impl App {
         Ok(())
     }
 
+    /// Moves the selected card one place up (`-1`) or down (`1`) in its
+    /// column with `task.reorder`, so every client sees the new order.
+    pub async fn reorder_kanban_card(&mut self, direction: i32) -> Result<()> {
+        let index = self.selected_kanban_card_index;
+        let Some(target) = (index as i64 + direction as i64).try_into().ok() else {
+            return Ok(());
+        };
+        let (task_id, anchor_id) = {
+            let tasks = self.get_kanban_tasks(self.selected_column_index);
+            let id = |i: usize| {
+                tasks
+                    .get(i)
+                    .and_then(|t| t.get("id"))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            };
+            match (id(index), id(target)) {
+                (Some(task_id), Some(anchor_id)) => (task_id, anchor_id),
+                _ => return Ok(()),
+            }
+        };
+
+        let (before, after) = if direction < 0 {
+            (Some(anchor_id.as_str()), None)
+        } else {
+            (None, Some(anchor_id.as_str()))
+        };
+        match self
+            .client
+            .task_reorder(&self.profile_id, &task_id, before, after)
+            .await
+        {
+            Ok(_) => {
+                self.refresh_tasks().await?;
+                self.selected_kanban_card_index = self
+                    .get_kanban_tasks(self.selected_column_index)
+                    .iter()
+                    .position(|t| t.get("id").and_then(|v| v.as_str()) == Some(&task_id))
+                    .unwrap_or(target);
+                self.status_message = "Card moved".to_string();
+            }
+            Err(e) => {
+                self.status_message = format!("Error moving card: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// The selection of the current view, for views that have one list.
+    fn view_selection(&mut self) -> Option<&mut usize> {
+        match self.current_view {
+            AppView::Dashboard => Some(&mut self.selected_task_index),
+            AppView::Timers => Some(&mut self.selected_timer_index),
+            AppView::Kanban => Some(&mut self.selected_kanban_card_index),
+            AppView::Entries => Some(&mut self.selected_entry_index),
+            AppView::Settings => Some(&mut self.selected_setting_index),
+            AppView::Logs => Some(&mut self.selected_log_index),
+            AppView::Reports => None,
+        }
+    }
+
+    /// Trades the focused pane's view and selection for the other pane's.
+    fn swap_panes(&mut self) {
+        let focused = PaneState {
+            view: self.current_view,
+            selected_index: self.view_selection().map_or(0, |i| *i),
+        };
+        self.current_view = self.other_pane.view;
+        let index = self.other_pane.selected_index;
+        if let Some(selection) = self.view_selection() {
+            *selection = index;
+        }
+        self.other_pane = focused;
+    }
+
+    /// Runs `f` as if the unfocused pane had focus, such as to draw it.
+    pub fn with_other_pane<R>(&mut self, f: impl FnOnce(&mut App) -> R) -> R {
+        self.swap_panes();
+        let result = f(self);
+        self.swap_panes();
+        result
+    }
+
+    /// Opens or closes the split view. Closing it keeps the left pane.
+    pub fn toggle_split(&mut self) {
+        if self.split_mode && self.split_focus == SplitPane::Right {
+            self.switch_split_focus();
+        }
+        self.split_mode = !self.split_mode;
+    }
+
+    pub fn switch_split_focus(&mut self) {
+        self.swap_panes();
+        self.split_focus = match self.split_focus {
+            SplitPane::Left => SplitPane::Right,
+            SplitPane::Right => SplitPane::Left,
+        };
+    }
+
+    /// Shows `view` in the right pane, opening the split view if needed.
+    pub fn set_split_view(&mut self, view: AppView) {
+        self.split_mode = true;
+        match self.split_focus {
+            SplitPane::Right => self.current_view = view,
+            SplitPane::Left if self.other_pane.view != view => {
+                self.other_pane = PaneState {
+                    view,
+                    selected_index: 0,
+                };
+            }
+            SplitPane::Left => {}
+        }
+    }
+
     pub fn toggle_help(&mut self) {
         self.show_help = !self.show_help;
+        self.help = HelpState::new(self.keybindings.clone());
     }
 
     pub fn list_next(&mut self) {
@@ -495,122 +936,737 @@ impl App {
         }
     }
 
+    /// Reloads everything from the daemon. Never fails: if the daemon is
+    /// unreachable the app is marked disconnected and keeps its last data
+    /// until the reconnect loop succeeds.
     pub async fn refresh_all(&mut self) -> Result<()> {
-        self.refresh_timer().await?;
-        self.refresh_stats().await?;
-        self.refresh_tasks().await?;
-        self.refresh_entries().await?;
-        self.refresh_sync().await?;
-        self.refresh_config().await?;
-        self.refresh_profiles().await?;
+        if let Err(e) = self.client.health_check().await {
+            self.mark_disconnected(&e);
+            return Ok(());
+        }
+
+        let results = [
+            self.refresh_timer().await,
+            self.refresh_stats().await,
+            self.refresh_tasks().await,
+            self.refresh_entries().await,
+            self.refresh_sync().await,
+            self.refresh_config().await,
+            self.refresh_profiles().await,
+        ];
+        if let Some(Err(e)) = results.into_iter().find(|r| r.is_err()) {
+            self.mark_disconnected(&e);
+        }
         Ok(())
     }
 
-    pub async fn refresh_timer(&mut self) -> Result<()> {
-        // Get single "primary" timer for dashboard (backward compat)
-        self.timer_info = self.client.timer_get(&self.profile_id).await.ok();
+    pub fn mark_disconnected(&mut self, error: &anyhow::Error) {
+        if self.daemon_connected {
+            tracing::warn!("Lost connection to daemon: {}", error);
+        }
+        self.daemon_connected = false;
+    }
 
-        // Get all timers for this profile for the Timers tab
-        if let Ok(timers) = self.client.timer_list_by_profile(&self.profile_id).await {
-            self.active_timers = timers.as_array().cloned().unwrap_or_default();
+    /// Called by the reconnect loop; reloads everything once the daemon
+    /// answers again.
+    pub async fn try_reconnect(&mut self) -> Result<()> {
+        if self.client.health_check().await.is_err() {
+            return Ok(());
+        }
+        self.daemon_connected = true;
+        self.refresh_all().await?;
+        if self.daemon_connected {
+            self.status_message = "Reconnected to daemon".to_string();
         }
         Ok(())
     }
 
-    pub fn get_selected_timer(&self) -> Option<&Value> {
-        self.active_timers.get(self.selected_timer_index)
-    }
+    pub async fn refresh_timer(&mut self) -> Result<()> {
+        self.active_timers = match self.client.timer_list_by_profile(&self.profile_id).await {
+            Ok(timers) => timers.as_array().cloned().unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+        self.active_timers.sort_by_key(|t| {
+            t.get("start_time")
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+        });
+        self.timer_info = self.focused_timer().cloned();
+        self.sync_timer_clock(None);
+        if self.multi_dashboard {
+            self.refresh_all_timers().await;
+        }
+        if self.fleet_view {
+            self.refresh_fleet(false).await;
+        }
 
-    pub fn get_selected_timer_id(&self) -> Option<String> {
-        self.get_selected_timer()
+        // Ticks refresh the timer every second; only ask for the task again
+        // when the running timer points at a different one.
+        let running_task = self
+            .active_timer()
+            .filter(|t| t.state == TimerState::Running)
+            .and_then(|t| t.task_id);
+        let shown_task = self
+            .active_task
+            .as_ref()
             .and_then(|t| t.get("id"))
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
+            .and_then(|id| id.as_str());
+        if running_task.as_deref() != shown_task {
+            self.refresh_active_task().await;
+        }
+        Ok(())
     }
 
-    pub async fn refresh_stats(&mut self) -> Result<()> {
-        self.stats_today = self.client.entry_stats_today(&self.profile_id).await.ok();
-        Ok(())
+    pub async fn refresh_all_timers(&mut self) {
+        self.all_timers = match self.client.timer_list().await {
+            Ok(timers) => listed_timers(timers),
+            Err(_) => Vec::new(),
+        };
+        let count: usize = self
+            .multi_dashboard_columns()
+            .iter()
+            .map(|c| c.1.len())
+            .sum();
+        self.selected_multi_timer = self.selected_multi_timer.min(count.max(1) - 1);
     }
 
-    pub async fn refresh_tasks(&mut self) -> Result<()> {
-        if let Ok(tasks) = self.client.task_list(&self.profile_id).await {
-            self.tasks = tasks.as_array().cloned().unwrap_or_default();
-            self.status_message = format!("Loaded {} tasks", self.tasks.len());
+    pub async fn toggle_multi_dashboard(&mut self) {
+        self.multi_dashboard = !self.multi_dashboard;
+        if self.multi_dashboard {
+            self.selected_multi_timer = 0;
+            self.refresh_all_timers().await;
+            self.status_message = "Showing timers across all profiles".to_string();
+        } else {
+            self.all_timers.clear();
+            self.status_message = "Showing this profile".to_string();
         }
-        Ok(())
     }
 
-    pub async fn refresh_entries(&mut self) -> Result<()> {
-        if let Ok(entries) = self.client.entry_today(&self.profile_id).await {
-            self.entries = entries.as_array().cloned().unwrap_or_default();
+    pub async fn toggle_fleet_view(&mut self) {
+        self.fleet_view = !self.fleet_view;
+        if self.fleet_view {
+            self.focused_pane = DashboardPane::TimerConfig;
+            self.refresh_fleet(true).await;
+            self.selected_fleet_row = self
+                .fleet_rows
+                .iter()
+                .position(|r| r.profile_id == self.profile_id)
+                .unwrap_or(0);
+            self.status_message = "Showing all profiles".to_string();
+        } else {
+            self.fleet_rows.clear();
+            self.fleet_fetched_at = None;
+            self.status_message = "Showing this profile".to_string();
         }
-        Ok(())
     }
 
-    pub async fn refresh_sync(&mut self) -> Result<()> {
-        self.sync_status = self.client.sync_status().await.ok();
-        Ok(())
+    /// Refetches the all-profiles panel with one `timer.list` and one
+    /// `stats.bulk`, however many profiles there are. Unless `force`d,
+    /// a fetch younger than `FLEET_CACHE_TTL` is kept.
+    pub async fn refresh_fleet(&mut self, force: bool) {
+        if !force
+            && self
+                .fleet_fetched_at
+                .is_some_and(|at| at.elapsed() < FLEET_CACHE_TTL)
+        {
+            return;
+        }
+        let timers = match self.client.timer_list().await {
+            Ok(timers) => listed_timers(timers),
+            Err(_) => Vec::new(),
+        };
+        let stats = self
+            .client
+            .stats_bulk(None, &["today"])
+            .await
+            .unwrap_or(Value::Null);
+        self.fleet_rows = fleet_rows(&self.profiles, &timers, &stats);
+        self.fleet_fetched_at = Some(Instant::now());
+        self.selected_fleet_row = self
+            .selected_fleet_row
+            .min(self.fleet_rows.len().saturating_sub(1));
     }
 
-    pub async fn refresh_config(&mut self) -> Result<()> {
-        self.config = self.client.call("config.get", None).await.ok();
+    pub fn select_fleet_row(&mut self, delta: isize) {
+        if self.fleet_rows.is_empty() {
+            return;
+        }
+        self.selected_fleet_row = (self.selected_fleet_row as isize + delta)
+            .rem_euclid(self.fleet_rows.len() as isize) as usize;
+    }
 
-        if let Some(config) = &self.config
-            && let Some(pomodoro) = config.get("pomodoro")
-        {
-            if let Some(countdown_seconds) =
-                pomodoro.get("countdown_default").and_then(|v| v.as_u64())
-            {
-                self.countdown_minutes = countdown_seconds / 60;
-            }
+    /// Makes the selected all-profiles row's profile the active one.
+    pub async fn switch_to_fleet_row(&mut self) -> Result<()> {
+        let Some(row) = self.fleet_rows.get(self.selected_fleet_row) else {
+            return Ok(());
+        };
+        let Some(index) = self
+            .profiles
+            .iter()
+            .position(|p| p.get("id").and_then(|v| v.as_str()) == Some(&row.profile_id))
+        else {
+            return Ok(());
+        };
+        self.selected_profile_index = index;
+        self.switch_to_selected_profile().await
+    }
 
-            if let Some(work_seconds) = pomodoro.get("work_duration").and_then(|v| v.as_u64()) {
-                self.pomodoro_minutes = work_seconds / 60;
+    /// `all_timers` grouped by profile name, one column per profile,
+    /// oldest timers first.
+    pub fn multi_dashboard_columns(&self) -> Vec<(String, Vec<ActiveTimer>)> {
+        let mut timers: Vec<ActiveTimer> = self
+            .all_timers
+            .iter()
+            .filter_map(|t| serde_json::from_value(t.clone()).ok())
+            .collect();
+        timers.sort_by_key(|t| t.start_time);
+
+        let mut columns: Vec<(String, Vec<ActiveTimer>)> = Vec::new();
+        for timer in timers {
+            let name = self.profile_name(&timer.profile_id);
+            match columns.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, column)) => column.push(timer),
+                None => columns.push((name, vec![timer])),
             }
         }
+        columns
+    }
 
-        Ok(())
+    fn profile_name(&self, profile_id: &str) -> String {
+        self.profiles
+            .iter()
+            .find(|p| p.get("id").and_then(|v| v.as_str()) == Some(profile_id))
+            .and_then(|p| p.get("name"))
+            .and_then(|v| v.as_str())
+            .unwrap_or(profile_id)
+            .to_string()
     }
 
-    pub async fn refresh_profiles(&mut self) -> Result<()> {
-        if let Ok(profiles) = self.client.profile_list().await {
-            self.profiles = profiles.as_array().cloned().unwrap_or_default();
-            self.status_message = format!("Loaded {} profiles", self.profiles.len());
+    /// Moves the multi-profile selection to the previous (`-1`) or next
+    /// (`1`) timer, wrapping around.
+    pub fn select_multi_timer(&mut self, delta: isize) {
+        let count: usize = self
+            .multi_dashboard_columns()
+            .iter()
+            .map(|c| c.1.len())
+            .sum();
+        if count == 0 {
+            return;
         }
-        Ok(())
+        self.selected_multi_timer =
+            (self.selected_multi_timer as isize + delta).rem_euclid(count as isize) as usize;
     }
 
-    pub async fn refresh_reports(&mut self) -> Result<()> {
-        if self.report_profile == "all" {
-            self.refresh_all_profile_reports().await?;
-        } else {
-            let params = Some(serde_json::json!({"profile_id": self.report_profile}));
+    /// Pauses or resumes the timer selected on the multi-profile Dashboard.
+    pub async fn toggle_multi_timer(&mut self) -> Result<()> {
+        let Some(timer) = self
+            .multi_dashboard_columns()
+            .into_iter()
+            .flat_map(|(_, column)| column)
+            .nth(self.selected_multi_timer)
+        else {
+            return Ok(());
+        };
 
-            let stats_method = match self.report_period.as_str() {
-                "week" => "entry.stats_week",
-                "month" => "entry.stats_month",
-                _ => "entry.stats_today",
-            };
+        let result = match timer.state {
+            TimerState::Paused => self.client.timer_resume(&timer.id).await,
+            TimerState::Running => self.client.timer_pause(&timer.id).await,
+            TimerState::Stopped => return Ok(()),
+        };
+        match result {
+            Ok(_) => {
+                let verb = if timer.state == TimerState::Paused {
+                    "Resumed"
+                } else {
+                    "Paused"
+                };
+                self.status_message =
+                    format!("{} timer in {}", verb, self.profile_name(&timer.profile_id));
+                self.refresh_timer().await?;
+            }
+            Err(e) => self.status_message = format!("Error: {}", e),
+        }
+        Ok(())
+    }
 
-            self.report_stats = self.client.call(stats_method, params.clone()).await.ok();
+    pub async fn refresh_active_task(&mut self) {
+        self.active_task = self
+            .client
+            .task_get_active(&self.profile_id)
+            .await
+            .ok()
+            .filter(|task| !task.is_null());
+    }
 
-            let entries_method = match self.report_period.as_str() {
-                "week" => "entry.week",
-                "month" => "entry.month",
-                _ => "entry.today",
-            };
+    fn timer_id_of(timer: &Value) -> Option<&str> {
+        timer.get("id").and_then(|v| v.as_str())
+    }
 
-            self.report_entries = self
-                .client
-                .call(entries_method, params)
-                .await
-                .ok()
-                .and_then(|v| v.as_array().cloned())
-                .unwrap_or_default();
+    fn focused_timer(&self) -> Option<&Value> {
+        self.active_timers
+            .iter()
+            .find(|t| Self::timer_id_of(t) == self.focused_timer_id.as_deref())
+            .or_else(|| self.active_timers.first())
+    }
+
+    /// Moves the Dashboard to the previous (`-1`) or next (`1`) concurrent
+    /// timer, wrapping around.
+    pub fn focus_timer(&mut self, delta: isize) {
+        let count = self.active_timers.len();
+        if count < 2 {
+            return;
         }
+        let current = self
+            .active_timers
+            .iter()
+            .position(|t| Some(t) == self.focused_timer())
+            .unwrap_or(0);
+        let next = (current as isize + delta).rem_euclid(count as isize) as usize;
+
+        self.focused_timer_id = Self::timer_id_of(&self.active_timers[next]).map(str::to_string);
+        self.timer_info = self.focused_timer().cloned();
+        self.selected_timer_button = 0;
+        self.sync_timer_clock(None);
+        self.status_message = format!("Timer {} of {}", next + 1, count);
+    }
 
-        let profile_label = if self.report_profile == "all" {
+    pub fn active_task_title(&self) -> Option<&str> {
+        self.active_task.as_ref()?.get("title")?.as_str()
+    }
+
+    pub fn active_timer(&self) -> Option<ActiveTimer> {
+        self.timer_info
+            .clone()
+            .and_then(|v| serde_json::from_value(v).ok())
+    }
+
+    /// Re-anchors the timer clock to the fetched timer's `display`.
+    /// `ticked` is the timer id of a tick that was just fetched; without
+    /// one, an existing clock for the same running timer is kept.
+    pub fn sync_timer_clock(&mut self, ticked: Option<&str>) {
+        let Some(timer) = self
+            .active_timer()
+            .filter(|t| t.state == TimerState::Running)
+        else {
+            self.timer_clock = None;
+            return;
+        };
+
+        let keep = match ticked {
+            Some(timer_id) => timer_id != timer.id,
+            None => self
+                .timer_clock
+                .as_ref()
+                .is_some_and(|clock| clock.is_for(&timer.id)),
+        };
+        if !keep {
+            let reported = self.fetched_display(&timer);
+            self.timer_clock = TimerClock::from_timer(&timer, reported);
+        }
+    }
+
+    /// The `display` the daemon sent with `timer`, or one computed here if
+    /// the daemon predates it.
+    fn fetched_display(&self, timer: &ActiveTimer) -> TimerDisplay {
+        self.timer_info
+            .iter()
+            .chain(&self.active_timers)
+            .chain(&self.all_timers)
+            .find(|t| t.get("id").and_then(|v| v.as_str()) == Some(timer.id.as_str()))
+            .and_then(reported_display)
+            .unwrap_or_else(|| timer.display())
+    }
+
+    /// The timer's derived values, advanced locally between ticks while it
+    /// runs.
+    pub fn timer_display(&self, timer: &ActiveTimer) -> TimerDisplay {
+        match self
+            .timer_clock
+            .as_ref()
+            .filter(|clock| timer.state == TimerState::Running && clock.is_for(&timer.id))
+        {
+            Some(clock) => clock.display_at(Instant::now()),
+            None => self.fetched_display(timer),
+        }
+    }
+
+    /// Elapsed seconds for display, advanced locally between ticks.
+    pub fn timer_elapsed(&self, timer: &ActiveTimer) -> u64 {
+        self.timer_display(timer).elapsed_seconds
+    }
+
+    pub fn get_selected_timer(&self) -> Option<&Value> {
+        self.active_timers.get(self.selected_timer_index)
+    }
+
+    pub fn get_selected_timer_id(&self) -> Option<String> {
+        self.get_selected_timer()
+            .and_then(|t| t.get("id"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    }
+
+    pub async fn refresh_stats(&mut self) -> Result<()> {
+        self.stats_today = self.client.entry_stats_today(&self.profile_id).await.ok();
+        self.last_entry = self
+            .client
+            .entry_last(&self.profile_id)
+            .await
+            .ok()
+            .filter(|entry| !entry.is_null());
+        Ok(())
+    }
+
+    pub async fn refresh_tasks(&mut self) -> Result<()> {
+        if let Ok(tasks) = self.client.task_list(&self.profile_id).await {
+            self.tasks = tasks.as_array().cloned().unwrap_or_default();
+            self.status_message = format!("Loaded {} tasks", self.tasks.len());
+        }
+        if self.smart_task_order {
+            self.refresh_recent_task_use().await;
+        }
+        if self.due_order.is_some() {
+            self.refresh_due_order().await;
+        }
+        Ok(())
+    }
+
+    async fn refresh_due_order(&mut self) {
+        if let Ok(tasks) = self
+            .client
+            .task_list_by_due_date(&self.profile_id, true, false, None)
+            .await
+        {
+            self.due_order = Some(
+                tasks
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|t| t.get("id").and_then(|v| v.as_str()))
+                    .map(str::to_string)
+                    .collect(),
+            );
+        }
+    }
+
+    /// Switches the Dashboard task list between its usual order and only
+    /// the tasks with a due date, soonest first.
+    pub async fn toggle_due_view(&mut self) {
+        self.selected_task_index = 0;
+        if self.due_order.take().is_some() {
+            self.status_message = "Tasks: usual order".to_string();
+            return;
+        }
+        self.refresh_due_order().await;
+        self.status_message = match &self.due_order {
+            Some(_) => "Tasks: by due date, overdue in red".to_string(),
+            None => "Could not load tasks by due date".to_string(),
+        };
+    }
+
+    /// Whether `task` is due before today.
+    pub fn is_overdue(task: &Value) -> bool {
+        task.get("due_date")
+            .and_then(|v| v.as_str())
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .is_some_and(|due| due < Local::now().date_naive())
+    }
+
+    /// Whether `task` is waiting on other tasks to be finished.
+    pub fn is_blocked(task: &Value) -> bool {
+        task.get("blocked_by")
+            .and_then(|v| v.as_array())
+            .is_some_and(|ids| !ids.is_empty())
+    }
+
+    /// Titles of the tasks `task` is directly blocked by; ids that are not
+    /// loaded are shown as-is.
+    pub fn blocker_titles(&self, task: &Value) -> Vec<String> {
+        self.blockers(task)
+            .into_iter()
+            .map(|(title, _)| title)
+            .collect()
+    }
+
+    /// The tasks `task` is directly blocked by, as title and whether that
+    /// blocker is done.
+    pub fn blockers(&self, task: &Value) -> Vec<(String, bool)> {
+        task.get("blocked_by")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|id| id.as_str())
+            .map(|id| {
+                let blocker = self
+                    .tasks
+                    .iter()
+                    .find(|t| t.get("id").and_then(|v| v.as_str()) == Some(id));
+                let title = blocker
+                    .and_then(|t| t.get("title"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or(id)
+                    .to_string();
+                let done = blocker.is_some_and(|t| TaskStatus::of(t) == TaskStatus::Done);
+                (title, done)
+            })
+            .collect()
+    }
+
+    /// `None` for tasks without blockers. The daemon drops blockers as
+    /// they are finished, so `Cleared` only shows until the list catches up,
+    /// or after a sync brought in finished blockers.
+    pub fn block_state(&self, task: &Value) -> Option<BlockState> {
+        if !Self::is_blocked(task) {
+            return None;
+        }
+        if self.blockers(task).iter().all(|(_, done)| *done) {
+            Some(BlockState::Cleared)
+        } else {
+            Some(BlockState::Blocked)
+        }
+    }
+
+    async fn refresh_recent_task_use(&mut self) {
+        let since = Utc::now() - chrono::Duration::days(RECENT_TASK_DAYS);
+        if let Ok(entries) = self
+            .client
+            .entry_filter(&self.profile_id, Some(since.to_rfc3339()), None, None, None)
+            .await
+        {
+            self.recent_task_use = last_use_by_task(entries.as_array().into_iter().flatten());
+        }
+    }
+
+    /// Pins or unpins a task so smart ordering lists it first.
+    pub async fn toggle_pin(&mut self, task_id: &str) -> Result<()> {
+        let Some(mut task) = self
+            .tasks
+            .iter()
+            .find(|t| t.get("id").and_then(|v| v.as_str()) == Some(task_id))
+            .cloned()
+        else {
+            return Ok(());
+        };
+
+        let pinned = !task
+            .get("pinned")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if let Some(obj) = task.as_object_mut() {
+            obj.insert("pinned".to_string(), Value::Bool(pinned));
+        }
+        match self.client.task_update(&self.profile_id, task).await {
+            Ok(_) => {
+                self.refresh_tasks().await?;
+                self.status_message = if pinned {
+                    "Task pinned 📌".to_string()
+                } else {
+                    "Task unpinned".to_string()
+                };
+                self.select_task(task_id);
+            }
+            Err(e) => {
+                self.status_message = format!("Error updating task: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the Dashboard selection to `task_id`, e.g. after reordering.
+    pub fn yank_selected_task(&mut self) {
+        let Some(task_id) = self.selected_task_id() else {
+            return;
+        };
+        self.yanked_task = Some((self.profile_id.clone(), task_id));
+        self.status_message = "Task yanked; [P] pastes a copy".to_string();
+    }
+
+    /// Pastes the yanked task as a new `todo` copy via `task.copy`, which
+    /// only copies within a profile.
+    pub async fn paste_yanked_task(&mut self) -> Result<()> {
+        let Some((profile_id, task_id)) = self.yanked_task.clone() else {
+            self.status_message = "Nothing yanked; [Y] yanks the selected task".to_string();
+            return Ok(());
+        };
+        if profile_id != self.profile_id {
+            self.status_message = format!(
+                "The yanked task is in profile '{}'; paste it there",
+                profile_id
+            );
+            return Ok(());
+        }
+
+        match self.client.task_copy(&profile_id, &task_id, None).await {
+            Ok(task) => {
+                self.refresh_tasks().await?;
+                if let Some(id) = task.get("id").and_then(|v| v.as_str()) {
+                    self.select_task(id);
+                }
+                self.status_message = format!(
+                    "Pasted '{}'",
+                    task.get("title").and_then(|v| v.as_str()).unwrap_or("task")
+                );
+            }
+            Err(e) => {
+                self.status_message = format!("Error copying task: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn select_task(&mut self, task_id: &str) {
+        if let Some(index) = self
+            .get_filtered_tasks()
+            .iter()
+            .position(|t| t.get("id").and_then(|v| v.as_str()) == Some(task_id))
+        {
+            self.selected_task_index = index;
+        }
+    }
+
+    pub async fn refresh_entries(&mut self) -> Result<()> {
+        let (start, end) = self.entry_range.utc_bounds();
+        if let Ok(entries) = self
+            .client
+            .entry_filter(
+                &self.profile_id,
+                Some(start.to_rfc3339()),
+                Some(end.to_rfc3339()),
+                None,
+                None,
+            )
+            .await
+        {
+            self.entries = entries.as_array().cloned().unwrap_or_default();
+        }
+        if let Ok(invalid) = self.client.entry_list_invalid(&self.profile_id).await {
+            self.invalid_entries = invalid
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|item| {
+                    let id = item["entry"]["id"].as_str()?;
+                    let problem = item["problem"].as_str().unwrap_or_default();
+                    Some((id.to_string(), problem.to_string()))
+                })
+                .collect();
+        }
+        Ok(())
+    }
+
+    pub async fn refresh_sync(&mut self) -> Result<()> {
+        self.sync_status = self.client.sync_status().await.ok();
+        let initialized = self
+            .sync_status
+            .as_ref()
+            .and_then(|s| s.get("initialized"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        self.sync_history = if initialized {
+            self.client
+                .sync_history(SYNC_HISTORY_LIMIT)
+                .await
+                .ok()
+                .and_then(|h| h.as_array().cloned())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        Ok(())
+    }
+
+    pub async fn refresh_config(&mut self) -> Result<()> {
+        self.config = self.client.call("config.get", None).await.ok();
+
+        if let Some(config) = &self.config
+            && let Some(pomodoro) = config.get("pomodoro")
+        {
+            if let Some(countdown_seconds) =
+                pomodoro.get("countdown_default").and_then(|v| v.as_u64())
+            {
+                self.countdown_minutes = countdown_seconds / 60;
+            }
+
+            if let Some(work_seconds) = pomodoro.get("work_duration").and_then(|v| v.as_u64()) {
+                self.pomodoro_minutes = work_seconds / 60;
+            }
+        }
+
+        let tui_config: TuiConfig = self
+            .config
+            .as_ref()
+            .and_then(|c| c.get("tui"))
+            .and_then(|t| serde_json::from_value(t.clone()).ok())
+            .unwrap_or_default();
+        self.priority_colors = resolve_priority_colors(&tui_config);
+        self.keybindings = resolve_keybindings(&tui_config);
+
+        if let Ok(result) = self
+            .client
+            .system_messages(tui_config.locale.as_deref())
+            .await
+            && let Ok(catalog) = serde_json::from_value(result["messages"].clone())
+        {
+            self.messages = catalog;
+        }
+
+        Ok(())
+    }
+
+    /// The notification text for `key` with its `{name}` placeholders
+    /// filled in. Uses the built-in English when the daemon didn't send it.
+    pub fn message(&self, key: &str, args: &[(&str, &str)]) -> String {
+        let text = self
+            .messages
+            .get(key)
+            .map(String::as_str)
+            .unwrap_or_else(|| messages::lookup(messages::DEFAULT_LOCALE, key));
+        messages::fill(text, args)
+    }
+
+    pub async fn refresh_profiles(&mut self) -> Result<()> {
+        if let Ok(profiles) = self.client.profile_list().await {
+            self.profiles = profiles.as_array().cloned().unwrap_or_default();
+            self.status_message = format!("Loaded {} profiles", self.profiles.len());
+        }
+        Ok(())
+    }
+
+    pub async fn refresh_reports(&mut self) -> Result<()> {
+        self.report_compare = None;
+        if self.report_profile == "all" {
+            self.refresh_all_profile_reports().await?;
+        } else {
+            let params = Some(serde_json::json!({"profile_id": self.report_profile}));
+
+            let stats_method = match self.report_period.as_str() {
+                "week" => "entry.stats_week",
+                "month" => "entry.stats_month",
+                _ => "entry.stats_today",
+            };
+
+            self.report_stats = self.client.call(stats_method, params.clone()).await.ok();
+
+            let entries_method = match self.report_period.as_str() {
+                "week" => "entry.week",
+                "month" => "entry.month",
+                _ => "entry.today",
+            };
+
+            self.report_entries = self
+                .client
+                .call(entries_method, params)
+                .await
+                .ok()
+                .and_then(|v| v.as_array().cloned())
+                .unwrap_or_default();
+        }
+        self.refresh_report_budgets().await;
+        if self.report_hourly.is_some() {
+            self.load_report_hourly().await;
+        }
+
+        let profile_label = if self.report_profile == "all" {
             "all profiles"
         } else {
             &self.report_profile
@@ -622,6 +1678,19 @@ impl App {
         Ok(())
     }
 
+    pub async fn refresh_report_budgets(&mut self) {
+        self.report_budgets = if self.report_profile == "all" {
+            Vec::new()
+        } else {
+            self.client
+                .budget_list(&self.report_profile)
+                .await
+                .ok()
+                .and_then(|v| v.as_array().cloned())
+                .unwrap_or_default()
+        };
+    }
+
     async fn refresh_all_profile_reports(&mut self) -> Result<()> {
         let cache_key = format!("all_{}", self.report_period);
         if let Some((cached_entries, timestamp)) = self.cross_profile_cache.get(&cache_key)
@@ -692,51 +1761,110 @@ impl App {
 
         if log_file_path.exists() {
             let content = fs::read_to_string(&log_file_path)?;
-            self.log_lines = content
+            self.log_records = content
                 .lines()
                 .rev()
                 .take(1000)
-                .map(|s| s.to_string())
+                .map(LogRecord::parse)
                 .collect();
         } else {
-            self.log_lines = vec!["Log file not found".to_string()];
+            self.log_records = vec![LogRecord::plain("Log file not found")];
         }
+        self.selected_log_index = self
+            .selected_log_index
+            .min(self.visible_logs().len().saturating_sub(1));
 
         self.status_message = "Logs refreshed".to_string();
         Ok(())
     }
 
-    pub async fn start_timer(
-        &mut self,
-        pomodoro: bool,
-        duration_minutes: Option<u64>,
-    ) -> Result<()> {
-        if let Some(timer) = &self.timer_info
-            && let Some(state) = timer.get("state").and_then(|v| v.as_str())
-            && (state == "running" || state == "paused")
-        {
-            self.status_message = "Timer already active! Stop it first with [x]".to_string();
-            return Ok(());
-        }
+    /// The log records passing the target filter.
+    pub fn visible_logs(&self) -> Vec<&LogRecord> {
+        self.log_records
+            .iter()
+            .filter(|r| {
+                self.log_target_filter
+                    .as_deref()
+                    .is_none_or(|target| r.in_target(target))
+            })
+            .collect()
+    }
 
-        let filtered_tasks = self.get_filtered_tasks();
-        let task_id = filtered_tasks
+    /// Steps the target filter through every target in the loaded log,
+    /// then back to showing everything.
+    pub fn cycle_log_target_filter(&mut self) {
+        let targets: BTreeSet<&str> = self
+            .log_records
+            .iter()
+            .filter_map(|r| r.target.as_deref())
+            .collect();
+        let next = match &self.log_target_filter {
+            None => targets.first(),
+            Some(current) => targets
+                .range::<str, _>((
+                    std::ops::Bound::Excluded(current.as_str()),
+                    std::ops::Bound::Unbounded,
+                ))
+                .next(),
+        };
+        self.log_target_filter = next.map(|t| t.to_string());
+        self.selected_log_index = 0;
+        self.status_message = match &self.log_target_filter {
+            Some(target) => format!("Showing logs from {}", target),
+            None => "Showing logs from every target".to_string(),
+        };
+    }
+
+    /// What starting the selected timer type would run, on the selected
+    /// task.
+    fn selected_timer_start(&self) -> TimerStart {
+        let task_id = self
+            .get_filtered_tasks()
             .get(self.selected_task_index)
             .and_then(|t| t.get("id"))
-            .and_then(|v| v.as_str());
-
-        let result = if pomodoro {
-            self.client
-                .timer_start_pomodoro(&self.profile_id, task_id, duration_minutes)
-                .await
-        } else {
-            self.client
-                .timer_start_manual(&self.profile_id, task_id)
-                .await
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        let minutes = match self.selected_timer_type {
+            TimerType::Manual => None,
+            TimerType::Pomodoro => Some(self.pomodoro_minutes),
+            TimerType::Countdown => Some(self.countdown_minutes),
         };
+        TimerStart {
+            profile_id: self.profile_id.clone(),
+            timer_type: self.selected_timer_type,
+            minutes,
+            task_id,
+        }
+    }
 
-        match result {
-            Ok(_) => {
+    /// Starts `start` and remembers it for `repeat_last_timer`.
+    pub async fn start_timer(&mut self, start: TimerStart) -> Result<()> {
+        let task_id = start.task_id.as_deref();
+        let result = match start.timer_type {
+            TimerType::Pomodoro => {
+                let mut overrides = serde_json::Map::new();
+                if let Some(minutes) = start.minutes {
+                    overrides.insert("work_duration".to_string(), serde_json::json!(minutes * 60));
+                }
+                self.client
+                    .timer_start_pomodoro_with_config(&start.profile_id, task_id, overrides.into())
+                    .await
+            }
+            TimerType::Countdown => {
+                let minutes = start.minutes.unwrap_or(self.countdown_minutes);
+                self.client
+                    .timer_start_countdown(&start.profile_id, task_id, minutes)
+                    .await
+            }
+            TimerType::Manual => {
+                self.client
+                    .timer_start_manual(&start.profile_id, task_id)
+                    .await
+            }
+        };
+
+        match result {
+            Ok(started) => {
                 let task_name = task_id.and_then(|id| {
                     self.tasks
                         .iter()
@@ -744,19 +1872,22 @@ impl App {
                         .and_then(|t| t.get("title"))
                         .and_then(|v| v.as_str())
                 });
+                let kind = match (start.timer_type, start.minutes) {
+                    (TimerType::Countdown, Some(minutes)) => format!("{}m countdown", minutes),
+                    (TimerType::Pomodoro, _) => "pomodoro timer".to_string(),
+                    _ => "manual timer".to_string(),
+                };
 
                 self.status_message = if let Some(name) = task_name {
-                    format!(
-                        "Started {} timer for: {}",
-                        if pomodoro { "pomodoro" } else { "manual" },
-                        name
-                    )
+                    format!("Started {} for: {}", kind, name)
                 } else {
-                    format!(
-                        "Started {} timer (no task)",
-                        if pomodoro { "pomodoro" } else { "manual" }
-                    )
+                    format!("Started {} (no task)", kind)
                 };
+                if let Some(warning) = started.get("warning").and_then(|v| v.as_str()) {
+                    self.status_message = format!("{} ⚠ {}", self.status_message, warning);
+                }
+                self.focus_started_timer(&started);
+                self.last_timer_start = Some(start);
                 self.refresh_timer().await?;
             }
             Err(e) => {
@@ -767,52 +1898,73 @@ impl App {
         Ok(())
     }
 
-    pub async fn start_countdown_timer(&mut self) -> Result<()> {
-        if let Some(timer) = &self.timer_info
-            && let Some(state) = timer.get("state").and_then(|v| v.as_str())
-            && (state == "running" || state == "paused")
-        {
-            self.status_message = "Timer already active! Stop it first with [x]".to_string();
-            return Ok(());
+    fn focus_started_timer(&mut self, started: &Value) {
+        if let Some(timer_id) = started.get("timer_id").and_then(|v| v.as_str()) {
+            self.focused_timer_id = Some(timer_id.to_string());
         }
+    }
 
-        let filtered_tasks = self.get_filtered_tasks();
-        let task_id = filtered_tasks
-            .get(self.selected_task_index)
-            .and_then(|t| t.get("id"))
-            .and_then(|v| v.as_str());
+    /// Starts the last timer started from this TUI again: same type,
+    /// length, task and profile.
+    pub async fn repeat_last_timer(&mut self) -> Result<()> {
+        let Some(start) = self.last_timer_start.clone() else {
+            self.status_message = "No timer started yet to repeat".to_string();
+            return Ok(());
+        };
+        self.start_timer(start).await
+    }
 
-        let result = self
-            .client
-            .timer_start_countdown(&self.profile_id, task_id, self.countdown_minutes)
-            .await;
+    /// The configured countdown presets, in minutes.
+    pub fn countdown_presets(&self) -> Vec<u64> {
+        self.config
+            .as_ref()
+            .and_then(|c| c.get("tui"))
+            .and_then(|t| t.get("countdown_presets"))
+            .and_then(|p| serde_json::from_value(p.clone()).ok())
+            .unwrap_or_default()
+    }
 
-        match result {
-            Ok(_) => {
-                let task_name = task_id.and_then(|id| {
-                    self.tasks
-                        .iter()
-                        .find(|t| t.get("id").and_then(|v| v.as_str()) == Some(id))
-                        .and_then(|t| t.get("title"))
-                        .and_then(|v| v.as_str())
-                });
+    /// The share of time outside working hours above which Reports
+    /// highlights it, in percent.
+    pub fn outside_hours_warn_percent(&self) -> f64 {
+        self.config
+            .as_ref()
+            .and_then(|c| c.pointer("/tui/outside_hours_warn_percent"))
+            .and_then(|p| p.as_f64())
+            .unwrap_or(20.0)
+    }
 
-                self.status_message = if let Some(name) = task_name {
-                    format!(
-                        "Started {}m countdown for: {}",
-                        self.countdown_minutes, name
-                    )
-                } else {
-                    format!("Started {}m countdown (no task)", self.countdown_minutes)
-                };
-                self.refresh_timer().await?;
-            }
-            Err(e) => {
-                self.status_message = format!("Error: {}", e);
-            }
+    /// The animation `tui.<key>` picks, e.g. for `pomodoro_animation`.
+    pub fn animation_choice(&self, key: &str) -> &str {
+        self.config
+            .as_ref()
+            .and_then(|c| c.get("tui"))
+            .and_then(|tui| tui.get(key))
+            .and_then(|v| v.as_str())
+            .unwrap_or(match key {
+                "countdown_animation" => "cow",
+                _ => "tomato",
+            })
+    }
+
+    pub fn open_countdown_presets(&mut self) {
+        if self.countdown_presets().is_empty() {
+            self.status_message = "No countdown presets; add some in Settings".to_string();
+            return;
         }
+        self.selected_preset_index = 0;
+        self.input_mode = InputMode::CountdownPresets;
+    }
 
-        Ok(())
+    /// Starts a countdown of the chosen preset on the selected task.
+    pub async fn start_countdown_preset(&mut self, index: usize) -> Result<()> {
+        let Some(minutes) = self.countdown_presets().get(index).copied() else {
+            return Ok(());
+        };
+        self.input_mode = InputMode::Normal;
+        self.selected_timer_type = TimerType::Countdown;
+        self.countdown_minutes = minutes;
+        self.start_selected_timer().await
     }
 
     pub fn cycle_timer_type(&mut self) {
@@ -873,12 +2025,25 @@ impl App {
         }
     }
 
+    /// Starts the selected timer type, unless a timer is already active;
+    /// see `start_another_timer` for running timers side by side.
     pub async fn start_selected_timer(&mut self) -> Result<()> {
-        match self.selected_timer_type {
-            TimerType::Manual => self.start_timer(false, None).await,
-            TimerType::Pomodoro => self.start_timer(true, Some(self.pomodoro_minutes)).await,
-            TimerType::Countdown => self.start_countdown_timer().await,
+        if self.active_timer().is_some_and(|t| !t.is_stopped()) {
+            self.status_message = format!(
+                "Timer already active! Stop it with [{}] or start another with [{}]",
+                self.keybindings.label(Action::TimerStop),
+                self.keybindings.label(Action::TimerStartAnother)
+            );
+            return Ok(());
         }
+        self.start_another_timer().await
+    }
+
+    /// Starts the selected timer type next to any that are running. The
+    /// daemon refuses once the profile reaches `daemon.max_timers_per_profile`.
+    pub async fn start_another_timer(&mut self) -> Result<()> {
+        let start = self.selected_timer_start();
+        self.start_timer(start).await
     }
 
     pub async fn toggle_pause(&mut self) -> Result<()> {
@@ -920,6 +2085,40 @@ impl App {
         Ok(())
     }
 
+    /// Whether the active timer is in a pomodoro break.
+    pub fn in_break(&self) -> bool {
+        self.active_timer()
+            .filter(|t| t.is_running() || t.is_paused())
+            .and_then(|t| t.pomodoro_state)
+            .is_some_and(|p| p.phase.is_break())
+    }
+
+    pub async fn skip_break(&mut self) -> Result<()> {
+        let timer_id = self
+            .timer_info
+            .as_ref()
+            .and_then(|t| t.get("id"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let Some(timer_id) = timer_id else {
+            self.status_message = "No break to skip".to_string();
+            return Ok(());
+        };
+
+        match self.client.timer_skip_phase(&timer_id).await {
+            Ok(_) => {
+                self.status_message = "Break skipped, back to work".to_string();
+                self.selected_timer_button = 0;
+                self.refresh_timer().await?;
+            }
+            Err(e) => {
+                self.status_message = format!("Error: {}", e);
+            }
+        }
+        Ok(())
+    }
+
     pub async fn resume(&mut self) -> Result<()> {
         let timer_id = self
             .timer_info
@@ -989,6 +2188,64 @@ impl App {
         Ok(())
     }
 
+    /// Whether the next work session starts on its own after a break. The
+    /// running pomodoro wins, then this profile's choice, then the config.
+    pub fn auto_start_enabled(&self) -> bool {
+        self.timer_info
+            .as_ref()
+            .and_then(|t| t.pointer("/pomodoro_state/config/auto_start_next_session"))
+            .and_then(|v| v.as_bool())
+            .or_else(|| {
+                self.current_profile()
+                    .and_then(|p| p.get("auto_start_next_session"))
+                    .and_then(|v| v.as_bool())
+            })
+            .or_else(|| {
+                self.config
+                    .as_ref()
+                    .and_then(|c| c.pointer("/pomodoro/auto_start_next_session"))
+                    .and_then(|v| v.as_bool())
+            })
+            .unwrap_or(false)
+    }
+
+    pub async fn toggle_auto_start(&mut self) -> Result<()> {
+        let enabled = !self.auto_start_enabled();
+
+        // Saved on the profile, so it outlives this timer and the TUI.
+        if let Some(mut profile) = self.current_profile().cloned() {
+            profile["auto_start_next_session"] = serde_json::json!(enabled);
+            if let Err(e) = self.client.profile_update(profile).await {
+                self.status_message = format!("Error: {}", e);
+                return Ok(());
+            }
+            self.refresh_profiles().await?;
+        }
+
+        let timer_id = self
+            .timer_info
+            .as_ref()
+            .filter(|t| t.get("pomodoro_state").is_some_and(|p| !p.is_null()))
+            .and_then(|t| t.get("id"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        if let Some(timer_id) = timer_id
+            && let Err(e) = self.client.timer_set_auto_start(&timer_id, enabled).await
+        {
+            self.status_message = format!("Error: {}", e);
+            return Ok(());
+        }
+
+        self.status_message = if enabled {
+            "Next session will start automatically".to_string()
+        } else {
+            "Next session will wait after breaks".to_string()
+        };
+        self.refresh_timer().await?;
+        Ok(())
+    }
+
     /// Pause/Resume a timer by its ID (for the Timers list view)
     pub async fn toggle_pause_by_id(&mut self, timer_id: &str) -> Result<()> {
         let timer = self.active_timers.iter().find(|t| {
@@ -1053,24 +2310,17 @@ impl App {
         {
             let mut task_clone = task.clone();
 
-            let current_status = task_clone
-                .get("status")
-                .and_then(|v| v.as_str())
-                .unwrap_or("todo");
-            let new_status = if current_status == "archived" {
-                "todo"
+            let new_status = if TaskStatus::of(&task_clone) == TaskStatus::Archived {
+                TaskStatus::Todo
             } else {
-                "archived"
+                TaskStatus::Archived
             };
 
             if let Some(obj) = task_clone.as_object_mut() {
-                obj.insert(
-                    "status".to_string(),
-                    serde_json::Value::String(new_status.to_string()),
-                );
+                obj.insert("status".to_string(), serde_json::json!(new_status));
                 match self.client.task_update(&self.profile_id, task_clone).await {
                     Ok(_) => {
-                        self.status_message = if new_status == "archived" {
+                        self.status_message = if new_status == TaskStatus::Archived {
                             "Task archived".to_string()
                         } else {
                             "Task restored to To Do".to_string()
@@ -1123,35 +2373,16 @@ impl App {
                     let new_title = self.input_buffer.clone();
                     let new_desc = self.input_buffer_2.clone();
 
-                    let task_opt = {
-                        let filtered_tasks = self.get_filtered_tasks();
-                        filtered_tasks
-                            .get(self.selected_task_index)
-                            .map(|t| (*t).clone())
-                    };
-
-                    if let Some(mut task) = task_opt
-                        && let Some(obj) = task.as_object_mut()
-                    {
-                        obj.insert(
-                            "title".to_string(),
-                            serde_json::Value::String(new_title.clone()),
-                        );
-
-                        if new_desc.trim().is_empty() {
-                            obj.insert(
-                                "description".to_string(),
-                                serde_json::Value::String("".to_string()),
-                            );
-                        } else {
-                            obj.insert(
-                                "description".to_string(),
-                                serde_json::Value::String(new_desc),
-                            );
-                        }
+                    let task_id = self
+                        .get_filtered_tasks()
+                        .get(self.selected_task_index)
+                        .and_then(|t| t.get("id"))
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
 
-                        match self.client.task_update(&self.profile_id, task).await {
-                            Ok(_) => {
+                    if let Some(task_id) = task_id {
+                        match self.save_task_edit(&task_id, &new_title, &new_desc).await {
+                            Ok(()) => {
                                 self.status_message = format!("Updated task: {}", new_title);
                                 self.refresh_tasks().await?;
                             }
@@ -1171,6 +2402,40 @@ impl App {
                 };
                 self.selected_task_index = 0;
             }
+            InputMode::QuickLog => {
+                match parse_quick_log(&self.input_buffer) {
+                    Ok((minutes, description)) => {
+                        self.input_mode = InputMode::Normal;
+                        self.input_buffer.clear();
+                        self.quick_log(minutes, description.as_deref()).await?;
+                    }
+                    Err(e) => self.status_message = e,
+                }
+                return Ok(());
+            }
+            InputMode::EntriesDate => {
+                let today = Local::now().date_naive();
+                match entry_range::parse_date(&self.input_buffer, today) {
+                    Ok(date) => {
+                        self.entry_range = EntryRange {
+                            granularity: Granularity::Day,
+                            anchor: date,
+                        };
+                        self.input_mode = InputMode::Normal;
+                        self.input_buffer.clear();
+                        self.show_entry_range().await?;
+                    }
+                    Err(e) => self.status_message = e,
+                }
+                return Ok(());
+            }
+            InputMode::ReportCompare => {
+                let input = self.input_buffer.clone();
+                self.input_mode = InputMode::Normal;
+                self.input_buffer.clear();
+                self.compare_report(&input).await?;
+                return Ok(());
+            }
             InputMode::FilterEntries => {
                 self.entry_filter = self.input_buffer.clone();
                 self.status_message = if self.entry_filter.is_empty() {
@@ -1180,21 +2445,21 @@ impl App {
                 };
                 self.selected_entry_index = 0;
             }
-            InputMode::ConfigPomodoro => {
-                self.update_config_duration("work_duration", "Work duration")
-                    .await?;
-            }
-            InputMode::ConfigShortBreak => {
-                self.update_config_duration("short_break", "Short break")
-                    .await?;
-            }
-            InputMode::ConfigLongBreak => {
-                self.update_config_duration("long_break", "Long break")
-                    .await?;
+            InputMode::EditSetting => {
+                if let Some(def) = settings::item(self.selected_setting_index) {
+                    match def.parse_input(&self.input_buffer) {
+                        Ok(value) => self.apply_setting(def, value).await?,
+                        Err(e) => self.status_message = format!("{}: {}", def.label, e),
+                    }
+                }
             }
-            InputMode::ConfigCountdown => {
-                self.update_config_duration("countdown_default", "Countdown default")
-                    .await?;
+            InputMode::ExportConfig | InputMode::ImportConfig => {
+                let path = config_file_path(&self.input_buffer);
+                if self.input_mode == InputMode::ExportConfig {
+                    self.export_config(&path).await;
+                } else {
+                    self.import_config(&path).await?;
+                }
             }
             InputMode::NewProfile => {
                 if !self.input_buffer.is_empty() {
@@ -1333,233 +2598,911 @@ impl App {
                         }
                     }
                 }
-                self.reset_new_entry_state();
+                self.reset_new_entry_state();
+            }
+            InputMode::BulkStatusFrom => {
+                let Some(from) = parse_task_status(&self.input_buffer) else {
+                    self.status_message =
+                        "Unknown status. Try: todo, in_progress, done, archived".to_string();
+                    return Ok(());
+                };
+                self.bulk_from_status = Some(from);
+                self.input_mode = InputMode::BulkStatusTo;
+                self.input_buffer.clear();
+                self.status_message = format!(" Move all '{}' tasks to: ", from.key());
+                return Ok(());
+            }
+            InputMode::BulkStatusTo => {
+                let Some(to) = parse_task_status(&self.input_buffer) else {
+                    self.status_message =
+                        "Unknown status. Try: todo, in_progress, done, archived".to_string();
+                    return Ok(());
+                };
+                if let Some(from) = self.bulk_from_status.take() {
+                    self.input_buffer.clear();
+                    self.open_confirm(ConfirmDialog::new(
+                        ConfirmAction::BulkStatus { from, to },
+                        "Move Tasks?",
+                        format!("Move every '{}' task to '{}'?", from.key(), to.key()),
+                        "Move",
+                        Danger::Caution,
+                    ));
+                    return Ok(());
+                }
+            }
+            InputMode::BulkTag | InputMode::BulkUntag => {
+                let untag = self.input_mode == InputMode::BulkUntag;
+                let tags: Vec<String> = self
+                    .input_buffer
+                    .split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect();
+                let count = self.task_selection.as_ref().map_or(0, |s| s.len());
+                if tags.is_empty() {
+                    self.status_message = "No tags given".to_string();
+                } else {
+                    let message = if untag {
+                        format!("Remove {} from {} tasks?", tags.join(", "), count)
+                    } else {
+                        format!("Tag {} tasks with {}?", count, tags.join(", "))
+                    };
+                    self.input_buffer.clear();
+                    self.open_confirm(ConfirmDialog::new(
+                        ConfirmAction::BulkTag { tags, untag },
+                        if untag { "Remove Tags?" } else { "Add Tags?" },
+                        message,
+                        if untag { "Remove" } else { "Tag" },
+                        Danger::Caution,
+                    ));
+                    return Ok(());
+                }
+            }
+            _ => {}
+        }
+
+        self.input_mode = InputMode::Normal;
+        self.input_buffer.clear();
+        Ok(())
+    }
+
+    pub fn toggle_task_selection_mode(&mut self) {
+        if self.task_selection.take().is_some() {
+            self.status_message = "Multi-select off".to_string();
+        } else {
+            self.task_selection = Some(BTreeSet::new());
+            self.status_message =
+                "Multi-select: [Space] mark, [t] tag, [u] untag, [V] done".to_string();
+        }
+    }
+
+    /// Marks or unmarks the highlighted task and moves to the next one.
+    pub fn toggle_task_mark(&mut self) {
+        let task_id = self
+            .get_filtered_tasks()
+            .get(self.selected_task_index)
+            .and_then(|t| t.get("id"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        if let (Some(selection), Some(task_id)) = (self.task_selection.as_mut(), task_id) {
+            if !selection.remove(&task_id) {
+                selection.insert(task_id);
+            }
+            self.status_message = format!("{} tasks marked", selection.len());
+        }
+        self.list_next();
+    }
+
+    pub fn start_bulk_tag(&mut self, untag: bool) {
+        let count = self.task_selection.as_ref().map_or(0, |s| s.len());
+        if count == 0 {
+            self.status_message = "Mark tasks with [Space] first".to_string();
+            return;
+        }
+        self.input_mode = if untag {
+            InputMode::BulkUntag
+        } else {
+            InputMode::BulkTag
+        };
+        self.input_buffer.clear();
+        self.status_message = format!(
+            " {} tags on {} tasks (comma-separated): ",
+            if untag { "Remove" } else { "Add" },
+            count
+        );
+    }
+
+    async fn bulk_edit_tags(&mut self, tags: &[String], untag: bool) -> Result<()> {
+        let Some(selection) = &self.task_selection else {
+            return Ok(());
+        };
+        let task_ids: Vec<String> = selection.iter().cloned().collect();
+
+        let result = if untag {
+            self.client
+                .task_bulk_untag(&self.profile_id, &task_ids, tags)
+                .await
+        } else {
+            self.client
+                .task_bulk_tag(&self.profile_id, &task_ids, tags)
+                .await
+        };
+        match result {
+            Ok(result) => {
+                let count = result
+                    .get("updated_count")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0);
+                self.status_message = if untag {
+                    format!("Removed {} from {} tasks", tags.join(", "), count)
+                } else {
+                    format!("Tagged {} tasks with {}", count, tags.join(", "))
+                };
+                self.task_selection = None;
+                self.refresh_tasks().await?;
+            }
+            Err(e) => {
+                self.status_message = format!("Error: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn start_bulk_status_update(&mut self) {
+        let current = if self.show_archived {
+            TaskStatus::Archived
+        } else {
+            kanban_status(self.selected_column_index)
+        };
+        self.input_mode = InputMode::BulkStatusFrom;
+        self.input_buffer = current.key().to_string();
+        self.bulk_from_status = None;
+        self.status_message = " Bulk move tasks with status: ".to_string();
+    }
+
+    async fn bulk_update_status(&mut self, from: TaskStatus, to: TaskStatus) -> Result<()> {
+        match self
+            .client
+            .task_bulk_status_update(&self.profile_id, from, to, None)
+            .await
+        {
+            Ok(result) => {
+                let count = result
+                    .get("updated_count")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0);
+                self.status_message =
+                    format!("Moved {} tasks from {} to {}", count, from.key(), to.key());
+                self.refresh_tasks().await?;
+                self.selected_kanban_card_index = 0;
+            }
+            Err(e) => {
+                self.status_message = format!("Error: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Asks before deleting the highlighted entry, unless that question
+    /// was turned off in Settings.
+    pub async fn confirm_delete_entry(&mut self) -> Result<()> {
+        let filtered_entries = self.get_filtered_entries();
+        let Some(entry) = filtered_entries.get(self.selected_entry_index) else {
+            return Ok(());
+        };
+        let Some(id) = entry.get("id").and_then(|v| v.as_str()).map(str::to_string) else {
+            return Ok(());
+        };
+        if !self.confirm_entry_delete {
+            return self.delete_entry(&id).await;
+        }
+
+        let start = entry
+            .get("start_time")
+            .and_then(|v| v.as_str())
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        let duration = entry
+            .get("duration_seconds")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        self.open_confirm(ConfirmDialog::new(
+            ConfirmAction::DeleteEntry { id },
+            "Delete Entry?",
+            format!(
+                "Delete the {} entry from {}?",
+                crate::ui::helpers::format_duration_hm(duration),
+                start
+            ),
+            "Delete",
+            Danger::Destructive,
+        ));
+        Ok(())
+    }
+
+    async fn delete_entry(&mut self, id: &str) -> Result<()> {
+        match self.client.entry_delete(&self.profile_id, id).await {
+            Ok(_) => {
+                self.status_message = "Entry deleted".to_string();
+                self.refresh_entries().await?;
+                let new_len = self.get_filtered_entries().len();
+                if self.selected_entry_index >= new_len {
+                    self.selected_entry_index = new_len.saturating_sub(1);
+                }
+            }
+            Err(e) => {
+                self.status_message = format!("Error: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the selected entry to the profile picked in the move dialog, or
+    /// copies it there when `move_entry` is false.
+    pub async fn move_selected_entry(&mut self, move_entry: bool) -> Result<()> {
+        let entry_id = self
+            .get_filtered_entries()
+            .get(self.selected_entry_index)
+            .and_then(|e| e.get("id"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let target_profile_id = self
+            .get_selected_move_target_profile()
+            .and_then(|p| p.get("id"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let (Some(entry_id), Some(target_id)) = (entry_id, target_profile_id) else {
+            self.status_message = "No entry or target profile selected".to_string();
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+
+        let target_name = self.get_profile_name_by_id(&target_id).to_string();
+
+        match self
+            .client
+            .entry_copy_to_profile(&self.profile_id, &entry_id, &target_id, move_entry, None)
+            .await
+        {
+            Ok(_) => {
+                let verb = if move_entry { "Moved" } else { "Copied" };
+                self.status_message = format!("{} entry to {}", verb, target_name);
+                self.refresh_entries().await?;
+                let new_len = self.get_filtered_entries().len();
+                if self.selected_entry_index >= new_len {
+                    self.selected_entry_index = new_len.saturating_sub(1);
+                }
+            }
+            Err(e) => {
+                self.status_message = format!("Error: {}", e);
+            }
+        }
+
+        self.input_mode = InputMode::Normal;
+        Ok(())
+    }
+
+    pub async fn edit_selected_entry(&mut self) -> Result<()> {
+        let filtered_entries = self.get_filtered_entries();
+        if let Some(entry) = filtered_entries.get(self.selected_entry_index) {
+            let duration_secs = entry
+                .get("duration_seconds")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0);
+            let minutes = duration_secs / 60;
+
+            self.input_mode = InputMode::EditEntryDuration;
+            self.input_buffer = minutes.to_string();
+            self.status_message = "Edit duration (minutes):".to_string();
+        }
+        Ok(())
+    }
+
+    /// Opens the selected entry's start and end for nudging with `[`/`]`.
+    pub fn edit_selected_entry_times(&mut self) {
+        let filtered_entries = self.get_filtered_entries();
+        let Some(entry) = filtered_entries.get(self.selected_entry_index) else {
+            return;
+        };
+        let time = |key: &str| {
+            entry
+                .get(key)
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+        };
+        let (Some(start), Some(end)) = (time("start_time"), time("end_time")) else {
+            self.status_message = "Only finished entries can be edited".to_string();
+            return;
+        };
+
+        self.timestamp_edit = Some(EntryTimestampEdit {
+            entry: (*entry).clone(),
+            field: TimestampField::Start,
+            start,
+            end,
+            count: String::new(),
+        });
+        self.input_mode = InputMode::EditEntryTimestamp;
+        self.status_message = "[ ] ±1m, { } ±5m, digits first for N×".to_string();
+    }
+
+    /// Moves the edited timestamp by `step` minutes times the typed count.
+    pub fn nudge_entry_timestamp(&mut self, step: i64) {
+        let Some(edit) = self.timestamp_edit.as_mut() else {
+            return;
+        };
+        let count = edit.count.parse::<i64>().unwrap_or(1);
+        edit.count.clear();
+        let delta = chrono::Duration::minutes(step * count);
+        match edit.field {
+            TimestampField::Start => edit.start += delta,
+            TimestampField::End => edit.end += delta,
+        }
+    }
+
+    /// Saves the nudged times with the duration recomputed from them.
+    pub async fn save_entry_timestamps(&mut self) -> Result<()> {
+        let Some(edit) = self.timestamp_edit.clone() else {
+            return Ok(());
+        };
+        if edit.end <= edit.start {
+            self.status_message = "End must be after start".to_string();
+            return Ok(());
+        }
+
+        let mut entry = edit.entry;
+        let duration = (edit.end - edit.start).num_seconds() as u64;
+        entry["start_time"] = serde_json::json!(edit.start.to_rfc3339());
+        entry["end_time"] = serde_json::json!(edit.end.to_rfc3339());
+        entry["duration_seconds"] = serde_json::json!(duration);
+
+        match self.client.entry_update(&self.profile_id, entry).await {
+            Ok(_) => {
+                self.timestamp_edit = None;
+                self.input_mode = InputMode::Normal;
+                self.status_message = format!("Updated entry times ({}m)", duration / 60);
+                self.refresh_entries().await?;
+            }
+            Err(e) => self.status_message = format!("Error updating entry: {}", e),
+        }
+        Ok(())
+    }
+
+    /// Asks before deleting the task with `task_id`.
+    pub fn confirm_delete_task(&mut self, task_id: &str) {
+        let title = self
+            .tasks
+            .iter()
+            .find(|t| t.get("id").and_then(|v| v.as_str()) == Some(task_id))
+            .and_then(|t| t.get("title"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("this task");
+        let message = format!("Are you sure you want to delete \"{}\"?", title);
+        self.open_confirm(ConfirmDialog::new(
+            ConfirmAction::DeleteTask {
+                id: task_id.to_string(),
+            },
+            "Delete Task?",
+            message,
+            "Delete",
+            Danger::Destructive,
+        ));
+    }
+
+    /// The id of the task highlighted in the Dashboard's list.
+    pub fn selected_task_id(&self) -> Option<String> {
+        self.get_filtered_tasks()
+            .get(self.selected_task_index)
+            .and_then(|t| t.get("id"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    }
+
+    async fn delete_task(&mut self, id: &str) -> Result<()> {
+        match self.client.task_delete(&self.profile_id, id).await {
+            Ok(_) => {
+                self.status_message = "Task deleted".to_string();
+                self.refresh_tasks().await?;
+                let new_len = self.get_filtered_tasks().len();
+                if self.selected_task_index >= new_len {
+                    self.selected_task_index = new_len.saturating_sub(1);
+                }
+            }
+            Err(e) => {
+                self.status_message = format!("Error: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Opens the detail panel for a task from `self.tasks`.
+    pub fn open_task_detail(&mut self, task_id: &str) {
+        let task = self
+            .tasks
+            .iter()
+            .find(|t| t.get("id").and_then(|v| v.as_str()) == Some(task_id))
+            .cloned();
+
+        if let Some(task) = task {
+            self.task_detail = Some(TaskDetail::new(task));
+            self.input_mode = InputMode::TaskDetail;
+            self.status_message.clear();
+        }
+    }
+
+    pub fn close_task_detail(&mut self) {
+        self.task_detail = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Validates and saves the detail panel. Errors stay in the panel so the
+    /// user can fix them; on success the panel closes and tasks reload.
+    pub async fn save_task_detail(&mut self) -> Result<()> {
+        let Some(detail) = self.task_detail.as_mut() else {
+            return Ok(());
+        };
+        let Some(task) = detail.build(Local::now().date_naive()) else {
+            return Ok(());
+        };
+        let title = detail.title.trim().to_string();
+
+        match self.client.task_update(&self.profile_id, task).await {
+            Ok(_) => {
+                self.close_task_detail();
+                self.status_message = format!("Updated task: {}", title);
+                self.refresh_tasks().await?;
+            }
+            Err(e) => {
+                if let Some(detail) = self.task_detail.as_mut() {
+                    detail.error = Some((None, e.to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn edit_selected_task(&mut self) -> Result<()> {
+        let task_data = {
+            let filtered_tasks = self.get_filtered_tasks();
+            if let Some(task) = filtered_tasks.get(self.selected_task_index) {
+                let title = task
+                    .get("title")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                let desc = task
+                    .get("description")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                Some((title, desc))
+            } else {
+                None
+            }
+        };
+
+        if let Some((title, desc)) = task_data {
+            self.input_mode = InputMode::EditTask;
+            self.input_buffer = title;
+            self.input_buffer_2 = desc;
+            self.focused_input_field = 0;
+            self.status_message = "Edit Task".to_string();
+        }
+        Ok(())
+    }
+
+    /// Saves the edit-task form. The title goes through `task.update` on a
+    /// freshly fetched copy, and only when it changed; the description uses
+    /// `task.set_description` so nothing else on the task is overwritten.
+    async fn save_task_edit(&self, task_id: &str, title: &str, description: &str) -> Result<()> {
+        let mut task = self.client.task_get(&self.profile_id, task_id).await?;
+        if task.get("title").and_then(|v| v.as_str()) != Some(title) {
+            task["title"] = serde_json::Value::String(title.to_string());
+            self.client.task_update(&self.profile_id, task).await?;
+        }
+        self.client
+            .task_set_description(&self.profile_id, task_id, description)
+            .await?;
+        Ok(())
+    }
+
+    /// Shows the day, week or month around the current anchor date.
+    pub async fn set_entry_granularity(&mut self, granularity: Granularity) -> Result<()> {
+        self.entry_range.granularity = granularity;
+        self.show_entry_range().await
+    }
+
+    pub async fn show_entries_for_today(&mut self) -> Result<()> {
+        self.entry_range = EntryRange::today();
+        self.show_entry_range().await
+    }
+
+    /// Moves the Entries view `periods` days, weeks or months.
+    pub async fn step_entry_range(&mut self, periods: i32) -> Result<()> {
+        self.entry_range.step(periods);
+        self.show_entry_range().await
+    }
+
+    async fn show_entry_range(&mut self) -> Result<()> {
+        self.selected_entry_index = 0;
+        self.refresh_entries().await?;
+        self.status_message = format!("Showing {}", self.entry_range.label());
+        Ok(())
+    }
+
+    /// The `tui.status_bar_format` template from the daemon config.
+    pub fn status_bar_format(&self) -> &str {
+        self.config
+            .as_ref()
+            .and_then(|c| c.get("tui"))
+            .and_then(|t| t.get("status_bar_format"))
+            .and_then(|v| v.as_str())
+            .unwrap_or(DEFAULT_STATUS_BAR_FORMAT)
+    }
+
+    pub fn setting_value(&self, def: &SettingDef) -> Option<Value> {
+        match def.target {
+            SettingTarget::Config { .. } => self.config.as_ref().and_then(|c| def.read(c)),
+            SettingTarget::Local(LocalSetting::AudioAlerts) => {
+                Some(Value::Bool(self.audio_alerts_enabled))
+            }
+            SettingTarget::Local(LocalSetting::CowModal) => {
+                Some(Value::Bool(self.cow_modal_enabled))
+            }
+            SettingTarget::Local(LocalSetting::SmartTaskOrder) => {
+                Some(Value::Bool(self.smart_task_order))
+            }
+            SettingTarget::Local(LocalSetting::TerminalTitle) => {
+                Some(Value::Bool(self.terminal_title.is_enabled()))
+            }
+            SettingTarget::Local(LocalSetting::ConfirmEntryDelete) => {
+                Some(Value::Bool(self.confirm_entry_delete))
+            }
+            SettingTarget::Action(_) => None,
+        }
+    }
+
+    pub async fn apply_setting(&mut self, def: &SettingDef, value: Value) -> Result<()> {
+        match def.target {
+            SettingTarget::Local(local) => {
+                if self.setting_value(def).as_ref() != Some(&value) {
+                    match local {
+                        LocalSetting::AudioAlerts => self.toggle_audio_alerts(),
+                        LocalSetting::CowModal => self.toggle_cow_modal(),
+                        LocalSetting::SmartTaskOrder => self.toggle_smart_task_order().await?,
+                        LocalSetting::TerminalTitle => self.toggle_terminal_title(),
+                        LocalSetting::ConfirmEntryDelete => self.toggle_confirm_entry_delete(),
+                    }
+                }
+            }
+            SettingTarget::Config { .. } => {
+                let message = if value.is_null() {
+                    format!("{} cleared", def.label)
+                } else {
+                    format!("{} set to {}", def.label, def.display(Some(&value)))
+                };
+                let Some((method, params)) = def.update_request(value) else {
+                    return Ok(());
+                };
+                match self.client.call(method, Some(params)).await {
+                    Ok(_) => {
+                        self.status_message = message;
+                        self.refresh_config().await?;
+                    }
+                    Err(e) => {
+                        self.status_message = format!("Error: {}", e);
+                    }
+                }
+            }
+            SettingTarget::Action(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Handles Enter/Space on the selected setting: toggles booleans, runs
+    /// actions and opens the input modal for everything else.
+    pub async fn activate_selected_setting(&mut self) -> Result<()> {
+        let Some(def) = settings::item(self.selected_setting_index) else {
+            return Ok(());
+        };
+
+        match (def.kind, def.target) {
+            (_, SettingTarget::Action(SettingAction::SyncInit)) => self.init_git_sync().await?,
+            (_, SettingTarget::Action(SettingAction::SyncNow)) => self.sync_now(),
+            (_, SettingTarget::Action(SettingAction::BranchPicker)) => {
+                self.open_branch_picker().await?
+            }
+            (_, SettingTarget::Action(SettingAction::SyncChanges)) => self.open_sync_diff().await?,
+            (_, SettingTarget::Action(SettingAction::SyncConflicts)) => {
+                self.open_sync_conflicts().await?
+            }
+            (SettingKind::Bool, _) => {
+                let current = self.setting_value(def);
+                if let Some(value) = def.step(current.as_ref(), 1) {
+                    self.apply_setting(def, value).await?;
+                }
+            }
+            (SettingKind::Animation, _) => {
+                self.animations = load_animations();
+                self.input_mode = InputMode::EditSetting;
+                self.input_buffer = def.edit_text(self.setting_value(def).as_ref());
+                self.status_message = format!(
+                    "Enter {} ({}):",
+                    def.label,
+                    self.animations.names().join("/")
+                );
+            }
+            _ => {
+                let current = self.setting_value(def);
+                self.input_mode = InputMode::EditSetting;
+                self.input_buffer = def.edit_text(current.as_ref());
+                self.status_message = match def.kind {
+                    SettingKind::Minutes { .. } => format!("Enter {} in minutes:", def.label),
+                    SettingKind::MinuteList { .. } => {
+                        format!("Enter {} in minutes, separated by commas:", def.label)
+                    }
+                    SettingKind::Enum(options) => {
+                        format!("Enter {} ({}):", def.label, options.join("/"))
+                    }
+                    SettingKind::OptionalText => {
+                        format!("Enter {} (empty to clear):", def.label)
+                    }
+                    _ => format!("Enter {}:", def.label),
+                };
             }
-            _ => {}
         }
+        Ok(())
+    }
 
-        self.input_mode = InputMode::Normal;
-        self.input_buffer.clear();
+    pub async fn adjust_selected_setting(&mut self, delta: i64) -> Result<()> {
+        let Some(def) = settings::item(self.selected_setting_index) else {
+            return Ok(());
+        };
+        if def.kind == SettingKind::Bool {
+            return Ok(());
+        }
+        if def.kind == SettingKind::Animation {
+            return self.cycle_animation(def, delta).await;
+        }
+
+        let current = self.setting_value(def);
+        if let Some(value) = def.step(current.as_ref(), delta) {
+            self.apply_setting(def, value).await?;
+        }
         Ok(())
     }
 
-    pub async fn delete_selected_entry(&mut self) -> Result<()> {
-        let filtered_entries = self.get_filtered_entries();
-        if let Some(entry) = filtered_entries.get(self.selected_entry_index)
-            && let Some(id) = entry.get("id").and_then(|v| v.as_str())
+    /// Steps an animation setting through the built-ins and the files in
+    /// the animations folder, re-reading the folder so new files show up.
+    async fn cycle_animation(&mut self, def: &SettingDef, delta: i64) -> Result<()> {
+        self.animations = load_animations();
+        let current = self.setting_value(def);
+        let names = self.animations.names();
+        let next = match current
+            .as_ref()
+            .and_then(|v| v.as_str())
+            .and_then(|name| names.iter().position(|n| *n == name))
         {
-            match self.client.entry_delete(&self.profile_id, id).await {
-                Ok(_) => {
-                    self.status_message = "Entry deleted".to_string();
-                    self.refresh_entries().await?;
-                    let new_len = self.get_filtered_entries().len();
-                    if self.selected_entry_index >= new_len {
-                        self.selected_entry_index = new_len.saturating_sub(1);
-                    }
-                }
-                Err(e) => {
-                    self.status_message = format!("Error: {}", e);
-                }
+            Some(index) => (index as i64 + delta).rem_euclid(names.len() as i64) as usize,
+            None => 0,
+        };
+        let value = Value::from(names[next]);
+        self.apply_setting(def, value).await
+    }
+
+    pub async fn init_git_sync(&mut self) -> Result<()> {
+        match self.client.call("sync.init", None).await {
+            Ok(_) => {
+                self.status_message = "Git sync initialized!".to_string();
+                self.refresh_sync().await?;
+            }
+            Err(e) => {
+                self.status_message = format!("Error: {}", e);
             }
         }
         Ok(())
     }
 
-    pub async fn edit_selected_entry(&mut self) -> Result<()> {
-        let filtered_entries = self.get_filtered_entries();
-        if let Some(entry) = filtered_entries.get(self.selected_entry_index) {
-            let duration_secs = entry
-                .get("duration_seconds")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0);
-            let minutes = duration_secs / 60;
+    pub async fn open_branch_picker(&mut self) -> Result<()> {
+        match self.client.sync_list_branches().await {
+            Ok(branches) => {
+                self.branches = branches.as_array().cloned().unwrap_or_default();
+                if self.branches.is_empty() {
+                    self.status_message = "No branches yet; commit something first".to_string();
+                    return Ok(());
+                }
+                self.selected_branch_index = self
+                    .branches
+                    .iter()
+                    .position(|b| b["current"].as_bool() == Some(true))
+                    .unwrap_or(0);
+                self.input_mode = InputMode::SelectBranch;
+            }
+            Err(e) => {
+                self.status_message = format!("Error: {}", e);
+            }
+        }
+        Ok(())
+    }
 
-            self.input_mode = InputMode::EditEntryDuration;
-            self.input_buffer = minutes.to_string();
-            self.status_message = "Edit duration (minutes):".to_string();
+    pub async fn open_sync_diff(&mut self) -> Result<()> {
+        match self.client.sync_diff().await {
+            Ok(stats) => {
+                self.sync_diff = Some(stats);
+                self.sync_diff_text = None;
+                self.show_full_diff = false;
+                self.sync_diff_scroll = 0;
+                self.input_mode = InputMode::SyncDiff;
+            }
+            Err(e) => {
+                self.status_message = format!("Error: {}", e);
+            }
         }
         Ok(())
     }
 
-    pub async fn delete_selected_task(&mut self) -> Result<()> {
-        let filtered_tasks = self.get_filtered_tasks();
-        if let Some(task) = filtered_tasks.get(self.selected_task_index)
-            && let Some(id) = task.get("id").and_then(|v| v.as_str())
-        {
-            match self.client.task_delete(&self.profile_id, id).await {
-                Ok(_) => {
-                    self.status_message = "Task deleted".to_string();
-                    self.refresh_tasks().await?;
-                    let new_len = self.get_filtered_tasks().len();
-                    if self.selected_task_index >= new_len {
-                        self.selected_task_index = new_len.saturating_sub(1);
-                    }
+    /// Switches the changes modal between per-file stats and the full diff.
+    pub async fn toggle_full_diff(&mut self) -> Result<()> {
+        if !self.show_full_diff && self.sync_diff_text.is_none() {
+            match self.client.sync_diff_full().await {
+                Ok(result) => {
+                    self.sync_diff_text = result["diff"].as_str().map(str::to_string);
                 }
                 Err(e) => {
                     self.status_message = format!("Error: {}", e);
+                    return Ok(());
                 }
             }
         }
+        self.show_full_diff = !self.show_full_diff;
+        self.sync_diff_scroll = 0;
         Ok(())
     }
 
-    pub async fn edit_selected_task(&mut self) -> Result<()> {
-        let task_data = {
-            let filtered_tasks = self.get_filtered_tasks();
-            if let Some(task) = filtered_tasks.get(self.selected_task_index) {
-                let title = task
-                    .get("title")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let desc = task
-                    .get("description")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                Some((title, desc))
-            } else {
-                None
-            }
-        };
+    /// Lines in the changes modal's current view, to bound scrolling.
+    pub fn sync_diff_line_count(&self) -> usize {
+        match (&self.sync_diff_text, self.show_full_diff) {
+            (Some(text), true) => text.lines().count(),
+            _ => self
+                .sync_diff
+                .as_ref()
+                .and_then(|s| s["files"].as_array())
+                .map_or(1, |files| files.len() + 2),
+        }
+    }
 
-        if let Some((title, desc)) = task_data {
-            self.input_mode = InputMode::EditTask;
-            self.input_buffer = title;
-            self.input_buffer_2 = desc;
-            self.focused_input_field = 0;
-            self.status_message = "Edit Task".to_string();
+    pub async fn open_sync_conflicts(&mut self) -> Result<()> {
+        match self.client.sync_conflicts().await {
+            Ok(conflicts) => {
+                self.sync_conflicts = conflicts.as_array().cloned().unwrap_or_default();
+                if self.sync_conflicts.is_empty() {
+                    self.status_message = "No sync conflicts".to_string();
+                    return Ok(());
+                }
+                self.select_conflict(0);
+                self.input_mode = InputMode::SyncConflicts;
+            }
+            Err(e) => {
+                self.status_message = format!("Error: {}", e);
+            }
         }
         Ok(())
     }
 
-    pub async fn show_entries_for_day(&mut self) -> Result<()> {
-        self.refresh_entries().await?;
-        self.status_message = "Showing today's entries".to_string();
-        Ok(())
+    /// Selects conflict `index` and forgets field picks made for another.
+    pub fn select_conflict(&mut self, index: usize) {
+        self.selected_conflict_index = index.min(self.sync_conflicts.len().saturating_sub(1));
+        self.conflict_field_index = None;
+        self.conflict_remote_fields.clear();
     }
 
-    pub async fn show_entries_for_week(&mut self) -> Result<()> {
-        if let Ok(entries) = self
-            .client
-            .call(
-                "entry.week",
-                Some(serde_json::json!({"profile_id": self.profile_id})),
-            )
-            .await
+    /// Every field of the selected conflict's record with its local and
+    /// remote value, sorted by name. A side that deleted the record has
+    /// `Null` for every field.
+    pub fn conflict_fields(&self) -> Vec<(String, Value, Value)> {
+        let Some(conflict) = self.sync_conflicts.get(self.selected_conflict_index) else {
+            return Vec::new();
+        };
+        let (local, remote) = (&conflict["local"], &conflict["remote"]);
+        let names: BTreeSet<&String> = [local, remote]
+            .into_iter()
+            .filter_map(|side| side.as_object())
+            .flat_map(|fields| fields.keys())
+            .collect();
+        names
+            .into_iter()
+            .map(|name| (name.clone(), local[name].clone(), remote[name].clone()))
+            .collect()
+    }
+
+    /// Flips which side the highlighted field is taken from when merging.
+    pub fn toggle_conflict_field(&mut self) {
+        let Some(index) = self.conflict_field_index else {
+            return;
+        };
+        if let Some((name, _, _)) = self.conflict_fields().into_iter().nth(index)
+            && !self.conflict_remote_fields.remove(&name)
         {
-            self.entries = entries.as_array().cloned().unwrap_or_default();
-            self.status_message = "Showing this week's entries".to_string();
+            self.conflict_remote_fields.insert(name);
         }
-        Ok(())
     }
 
-    pub async fn show_entries_for_month(&mut self) -> Result<()> {
-        if let Ok(entries) = self
+    /// Resolves the selected conflict as `local`, `remote` or `merged`,
+    /// the last built from the field picks.
+    pub async fn resolve_selected_conflict(&mut self, resolution: &str) -> Result<()> {
+        let Some(conflict) = self.sync_conflicts.get(self.selected_conflict_index) else {
+            return Ok(());
+        };
+        let Some(id) = conflict["id"].as_str().map(str::to_string) else {
+            return Ok(());
+        };
+
+        let merged = if resolution == "merged" {
+            let (Some(local), Some(remote)) = (
+                conflict["local"].as_object(),
+                conflict["remote"].as_object(),
+            ) else {
+                self.status_message =
+                    "One side deleted this record; keep [l]ocal or [r]emote".to_string();
+                return Ok(());
+            };
+            let mut merged = local.clone();
+            for name in &self.conflict_remote_fields {
+                match remote.get(name) {
+                    Some(value) => merged.insert(name.clone(), value.clone()),
+                    None => merged.remove(name),
+                };
+            }
+            Some(Value::Object(merged))
+        } else {
+            None
+        };
+
+        match self
             .client
-            .call(
-                "entry.month",
-                Some(serde_json::json!({"profile_id": self.profile_id})),
-            )
+            .sync_resolve_conflict(&id, resolution, merged)
             .await
         {
-            self.entries = entries.as_array().cloned().unwrap_or_default();
-            self.status_message = "Showing this month's entries".to_string();
-        }
-        Ok(())
-    }
-
-    pub async fn adjust_pomodoro_setting(&mut self, item: SettingsItem, delta: i32) -> Result<()> {
-        if let Some(config) = &self.config {
-            let (key, current_val) = match item {
-                SettingsItem::PomodoroWork => (
-                    "work_duration",
-                    config
-                        .get("pomodoro")
-                        .and_then(|p| p.get("work_duration"))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(1500),
-                ),
-                SettingsItem::PomodoroShortBreak => (
-                    "short_break",
-                    config
-                        .get("pomodoro")
-                        .and_then(|p| p.get("short_break"))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(300),
-                ),
-                SettingsItem::PomodoroLongBreak => (
-                    "long_break",
-                    config
-                        .get("pomodoro")
-                        .and_then(|p| p.get("long_break"))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(900),
-                ),
-                _ => return Ok(()),
-            };
-
-            let current_minutes = current_val / 60;
-            let new_minutes = (current_minutes as i32 + delta).max(1) as u64;
-            let new_seconds = new_minutes * 60;
-
-            match self
-                .client
-                .call(
-                    "config.update_pomodoro",
-                    Some(serde_json::json!({key: new_seconds})),
-                )
-                .await
-            {
-                Ok(_) => {
-                    self.status_message =
-                        format!("Set {} to {} minutes", key.replace('_', " "), new_minutes);
-                    self.refresh_config().await?;
-                }
-                Err(e) => {
-                    self.status_message = format!("Error: {}", e);
+            Ok(_) => {
+                self.sync_conflicts.remove(self.selected_conflict_index);
+                self.select_conflict(self.selected_conflict_index);
+                if self.sync_conflicts.is_empty() {
+                    self.input_mode = InputMode::Normal;
                 }
+                self.refresh_tasks().await?;
+                self.refresh_entries().await?;
+                self.refresh_sync().await?;
+                self.status_message = format!(
+                    "Conflict resolved ({}), {} left",
+                    resolution,
+                    self.sync_conflicts.len()
+                );
             }
-        }
-        Ok(())
-    }
-
-    pub async fn toggle_git_sync(&mut self) -> Result<()> {
-        if let Some(config) = &self.config {
-            let auto_commit = config
-                .get("sync")
-                .and_then(|s| s.get("auto_commit"))
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false);
-
-            match self
-                .client
-                .call(
-                    "config.update_sync",
-                    Some(serde_json::json!({"auto_commit": !auto_commit})),
-                )
-                .await
-            {
-                Ok(_) => {
-                    self.status_message = format!(
-                        "Auto-commit {}",
-                        if !auto_commit { "enabled" } else { "disabled" }
-                    );
-                    self.refresh_config().await?;
-                }
-                Err(e) => {
-                    self.status_message = format!("Error: {}", e);
-                }
+            Err(e) => {
+                self.status_message = format!("Error: {}", e);
             }
         }
         Ok(())
     }
 
-    pub async fn init_git_sync(&mut self) -> Result<()> {
-        match self.client.call("sync.init", None).await {
+    pub async fn switch_to_selected_branch(&mut self) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+        let Some(branch) = self
+            .branches
+            .get(self.selected_branch_index)
+            .and_then(|b| b["name"].as_str())
+            .map(str::to_string)
+        else {
+            return Ok(());
+        };
+
+        match self.client.sync_switch_branch(&branch).await {
             Ok(_) => {
-                self.status_message = "Git sync initialized!".to_string();
-                self.refresh_sync().await?;
+                self.status_message = format!("Switched to branch {}", branch);
+                self.refresh_all().await?;
             }
             Err(e) => {
                 self.status_message = format!("Error: {}", e);
@@ -1568,9 +3511,24 @@ impl App {
         Ok(())
     }
 
-    pub async fn sync_now(&mut self) -> Result<()> {
-        self.status_message = "Syncing...".to_string();
-        match self.client.call("sync.sync", None).await {
+    /// Starts a sync without waiting for it; `poll_sync` picks up the reply.
+    pub fn sync_now(&mut self) {
+        if self.sync_progress.is_some() {
+            self.status_message = "A sync is already running".to_string();
+            return;
+        }
+        self.status_message.clear();
+        self.sync_detached = false;
+        self.sync_progress = Some(SyncProgress::start(self.client.clone()));
+    }
+
+    /// Reports the running sync's outcome, once the daemon has replied.
+    pub async fn poll_sync(&mut self) -> Result<()> {
+        let Some(reply) = self.sync_progress.as_mut().and_then(|p| p.try_reply()) else {
+            return Ok(());
+        };
+        self.sync_progress = None;
+        match reply {
             Ok(result) => {
                 let pulled = result
                     .get("pulled")
@@ -1587,6 +3545,14 @@ impl App {
                     (false, true) => "Sync complete (Pushed)".to_string(),
                     (false, false) => "Sync complete (Already up to date)".to_string(),
                 };
+                let conflicts = result
+                    .get("conflicts")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0);
+                if conflicts > 0 {
+                    self.status_message
+                        .push_str(&format!(", {} conflict(s) to resolve", conflicts));
+                }
                 self.refresh_all().await?;
             }
             Err(e) => {
@@ -1596,35 +3562,43 @@ impl App {
         Ok(())
     }
 
-    async fn update_config_duration(&mut self, config_key: &str, display_name: &str) -> Result<()> {
-        let Ok(minutes) = self.input_buffer.parse::<u64>() else {
-            return Ok(());
-        };
-
-        if minutes == 0 {
-            self.status_message = "Duration cannot be zero.".to_string();
-            return Ok(());
+    /// Esc while syncing: stop waiting, but the daemon goes on.
+    pub fn stop_waiting_for_sync(&mut self) {
+        if let Some(progress) = self.sync_progress.take() {
+            progress.abandon();
+            self.sync_detached = true;
+            self.status_message =
+                "Stopped waiting; the daemon is still syncing and will report when done"
+                    .to_string();
         }
+    }
 
-        let seconds = minutes * 60;
-        match self
-            .client
-            .call(
-                "config.update_pomodoro",
-                Some(serde_json::json!({ config_key: seconds })),
-            )
-            .await
-        {
-            Ok(_) => {
-                self.status_message = format!("{} set to {} minutes", display_name, minutes);
-                if config_key == "countdown_default" {
-                    self.countdown_minutes = minutes;
+    /// A `sync.event` about a manual sync: the step it reached, or how it
+    /// ended if Esc stopped the wait for its reply.
+    pub async fn on_sync_event(&mut self, event: &Value) -> Result<()> {
+        match event.get("type").and_then(|v| v.as_str()) {
+            Some("stage") => {
+                if let (Some(progress), Some(stage)) = (
+                    self.sync_progress.as_mut(),
+                    event.get("stage").and_then(|v| v.as_str()),
+                ) {
+                    progress.set_stage(stage);
                 }
-                self.refresh_config().await?;
             }
-            Err(e) => {
-                self.status_message = format!("Error: {}", e);
+            Some("finished") if self.sync_detached => {
+                self.sync_detached = false;
+                self.status_message = "Sync complete".to_string();
+                self.refresh_all().await?;
+            }
+            Some("failed") if self.sync_detached => {
+                self.sync_detached = false;
+                let error = event
+                    .get("error")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown error");
+                self.status_message = format!("Sync failed: {}", error);
             }
+            _ => {}
         }
         Ok(())
     }
@@ -1638,6 +3612,59 @@ impl App {
         };
     }
 
+    pub async fn toggle_smart_task_order(&mut self) -> Result<()> {
+        self.smart_task_order = !self.smart_task_order;
+        self.selected_task_index = 0;
+        if self.smart_task_order {
+            self.refresh_recent_task_use().await;
+        }
+        self.status_message = if self.smart_task_order {
+            "Smart Task Order: Pinned and recent first".to_string()
+        } else {
+            "Smart Task Order: Disabled".to_string()
+        };
+        Ok(())
+    }
+
+    pub fn toggle_terminal_title(&mut self) {
+        let enabled = !self.terminal_title.is_enabled();
+        if enabled && !TerminalTitle::supported() {
+            self.status_message = "Terminal Title: stdout is not a terminal".to_string();
+            return;
+        }
+        self.terminal_title
+            .set_enabled(&mut std::io::stdout(), enabled);
+        self.status_message = if enabled {
+            "Terminal Title: Showing the timer".to_string()
+        } else {
+            "Terminal Title: Disabled".to_string()
+        };
+    }
+
+    /// Mirrors the active timer into the terminal title, at most once a
+    /// second, and restores the original title once no timer is left.
+    pub fn update_terminal_title(&mut self) {
+        let now = Instant::now();
+        if !self.terminal_title.is_due(now) {
+            return;
+        }
+        let title = self
+            .active_timer()
+            .filter(|t| !t.is_stopped())
+            .map(|t| t.summary(DEFAULT_SUMMARY_FORMAT));
+        self.terminal_title
+            .update(&mut std::io::stdout(), title.as_deref(), now);
+    }
+
+    pub fn toggle_confirm_entry_delete(&mut self) {
+        self.confirm_entry_delete = !self.confirm_entry_delete;
+        self.status_message = if self.confirm_entry_delete {
+            "Entry deletion: asks first".to_string()
+        } else {
+            "Entry deletion: no confirmation".to_string()
+        };
+    }
+
     pub fn toggle_cow_modal(&mut self) {
         self.cow_modal_enabled = !self.cow_modal_enabled;
         self.status_message = if self.cow_modal_enabled {
@@ -1661,29 +3688,93 @@ impl App {
         Ok(())
     }
 
-    pub async fn delete_selected_profile(&mut self) -> Result<()> {
-        if let Some(profile) = self.profiles.get(self.selected_profile_index) {
-            let id = profile.get("id").and_then(|v| v.as_str()).unwrap_or("");
-            let name = profile.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    /// Asks before deleting the highlighted profile. The active profile
+    /// can't be deleted, so that is refused without asking.
+    pub fn confirm_delete_profile(&mut self) {
+        let Some(profile) = self.profiles.get(self.selected_profile_index) else {
+            return;
+        };
+        let id = profile
+            .get("id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        let name = profile
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("this profile");
 
-            if id == self.profile_id {
-                self.status_message = "Cannot delete active profile!".to_string();
-                return Ok(());
-            }
+        if id == self.profile_id {
+            self.status_message = "Cannot delete active profile!".to_string();
+            return;
+        }
 
-            match self.client.profile_delete(id).await {
-                Ok(_) => {
-                    self.status_message = format!("Deleted profile: {}", name);
-                    self.refresh_profiles().await?;
-                }
-                Err(e) => {
-                    self.status_message = format!("Error deleting profile: {}", e);
-                }
+        let message = format!("Delete \"{}\" with all its tasks and entries?", name);
+        self.open_confirm(ConfirmDialog::new(
+            ConfirmAction::DeleteProfile { id },
+            "Delete Profile?",
+            message,
+            "Delete",
+            Danger::Destructive,
+        ));
+    }
+
+    async fn delete_profile(&mut self, id: &str) -> Result<()> {
+        let name = self
+            .profiles
+            .iter()
+            .find(|p| p.get("id").and_then(|v| v.as_str()) == Some(id))
+            .and_then(|p| p.get("name"))
+            .and_then(|v| v.as_str())
+            .unwrap_or(id)
+            .to_string();
+        match self.client.profile_delete(id).await {
+            Ok(_) => {
+                self.status_message = format!("Deleted profile: {}", name);
+                self.refresh_profiles().await?;
+            }
+            Err(e) => {
+                self.status_message = format!("Error deleting profile: {}", e);
             }
         }
         Ok(())
     }
 
+    pub fn confirm_quit(&mut self) {
+        self.open_confirm(ConfirmDialog::new(
+            ConfirmAction::Quit,
+            "Quit MooTimer?",
+            "A timer is currently running. It will work in the background until the daemon is stopped. Quit?",
+            "Quit",
+            Danger::Caution,
+        ));
+    }
+
+    pub fn open_confirm(&mut self, dialog: ConfirmDialog) {
+        self.confirm_dialog = Some(dialog);
+        self.input_mode = InputMode::Confirm;
+    }
+
+    /// Closes the dialog, running its action if `confirmed`.
+    pub async fn close_confirm(&mut self, confirmed: bool) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+        let Some(dialog) = self.confirm_dialog.take() else {
+            return Ok(());
+        };
+        if !confirmed {
+            return Ok(());
+        }
+        match dialog.action {
+            ConfirmAction::Quit => self.should_quit = true,
+            ConfirmAction::DeleteTask { id } => self.delete_task(&id).await?,
+            ConfirmAction::DeleteProfile { id } => self.delete_profile(&id).await?,
+            ConfirmAction::DeleteEntry { id } => self.delete_entry(&id).await?,
+            ConfirmAction::BulkStatus { from, to } => self.bulk_update_status(from, to).await?,
+            ConfirmAction::BulkTag { tags, untag } => self.bulk_edit_tags(&tags, untag).await?,
+        }
+        Ok(())
+    }
+
     pub async fn switch_to_selected_profile(&mut self) -> Result<()> {
         if let Some(profile) = self.profiles.get(self.selected_profile_index) {
             let id = profile.get("id").and_then(|v| v.as_str()).unwrap_or("");
@@ -1732,6 +3823,271 @@ impl App {
         Ok(())
     }
 
+    /// Starts `[V]Compare`, or leaves it if a comparison is showing.
+    pub fn start_report_compare(&mut self) {
+        if self.report_compare.take().is_some() {
+            self.status_message = "Comparison closed".to_string();
+            return;
+        }
+        if self.report_profile == "all" {
+            self.status_message = "Comparisons are per profile; press [p] first".to_string();
+            return;
+        }
+        self.input_mode = InputMode::ReportCompare;
+        self.input_buffer.clear();
+        self.status_message = format!(
+            "Compare with ([Enter] for the previous {}, or 2024-06-01..2024-06-07):",
+            self.report_period
+        );
+    }
+
+    /// Shows `[H]Hourly` for the current period, or goes back to the
+    /// breakdown if it is showing.
+    pub async fn toggle_report_hourly(&mut self) {
+        if self.report_hourly.take().is_some() {
+            self.status_message = "Hourly view closed".to_string();
+            return;
+        }
+        self.load_report_hourly().await;
+        if self.report_hourly.is_some() {
+            self.status_message = "Average session by hour; [H] to close".to_string();
+        }
+    }
+
+    async fn load_report_hourly(&mut self) {
+        if self.report_profile == "all" {
+            self.report_hourly = None;
+            self.status_message = "The hourly view is per profile; press [p] first".to_string();
+            return;
+        }
+        let (start, end) = self.report_period_bounds(Local::now().date_naive());
+        let (start, end) = (
+            start.format("%Y-%m-%d").to_string(),
+            end.format("%Y-%m-%d").to_string(),
+        );
+        match self
+            .client
+            .entry_stats_hourly(&self.report_profile, Some(&start), Some(&end))
+            .await
+        {
+            Ok(hours) => self.report_hourly = Some(hours.as_array().cloned().unwrap_or_default()),
+            Err(e) => {
+                self.report_hourly = None;
+                self.status_message = format!("Error: {}", e);
+            }
+        }
+    }
+
+    /// The current report period, from its first day to today.
+    fn report_period_bounds(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let start = match self.report_period.as_str() {
+            "week" => today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64),
+            "month" => today.with_day(1).unwrap_or(today),
+            _ => today,
+        };
+        (start, today)
+    }
+
+    /// The whole period before the current one: yesterday, last week or
+    /// last month.
+    fn previous_report_period(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let (start, _) = self.report_period_bounds(today);
+        let end = start - chrono::Duration::days(1);
+        let start = match self.report_period.as_str() {
+            "week" => end - chrono::Duration::days(6),
+            "month" => end.with_day(1).unwrap_or(end),
+            _ => end,
+        };
+        (start, end)
+    }
+
+    /// Compares the current report period with the period typed in, or
+    /// with the previous one if nothing was.
+    pub async fn compare_report(&mut self, input: &str) -> Result<()> {
+        let today = Local::now().date_naive();
+        let previous = if input.trim().is_empty() {
+            self.previous_report_period(today)
+        } else {
+            let parsed = input
+                .split_once("..")
+                .ok_or_else(|| "Enter a range such as 2024-06-01..2024-06-07".to_string());
+            let parsed = parsed.and_then(|(start, end)| {
+                Ok((
+                    entry_range::parse_date(start, today)?,
+                    entry_range::parse_date(end, today)?,
+                ))
+            });
+            match parsed {
+                Ok(range) => range,
+                Err(e) => {
+                    self.status_message = e;
+                    return Ok(());
+                }
+            }
+        };
+        let current = self.report_period_bounds(today);
+
+        let day = |d: NaiveDate| d.format("%Y-%m-%d").to_string();
+        let (a, b) = (
+            (day(previous.0), day(previous.1)),
+            (day(current.0), day(current.1)),
+        );
+        match self
+            .client
+            .entry_stats_compare(&self.report_profile, (&a.0, &a.1), (&b.0, &b.1))
+            .await
+        {
+            Ok(result) => {
+                let label = |(start, end): &(String, String)| {
+                    if start == end {
+                        start.clone()
+                    } else {
+                        format!("{}..{}", start, end)
+                    }
+                };
+                self.report_compare = Some(ReportComparison {
+                    label_a: label(&a),
+                    label_b: label(&b),
+                    result,
+                });
+                self.status_message = "Comparing periods; [V] to close".to_string();
+            }
+            Err(e) => self.status_message = format!("Error: {}", e),
+        }
+        Ok(())
+    }
+
+    /// The Reports view's per-task table for `report_entries`.
+    pub fn report_task_breakdown(&self) -> Vec<TaskBreakdownRow> {
+        task_breakdown(self.report_entries.iter().map(|entry| {
+            let text = |key| entry.get(key).and_then(|v| v.as_str()).map(str::to_string);
+            (
+                text("profile_id"),
+                text("task_id"),
+                entry
+                    .get("duration_seconds")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0),
+            )
+        }))
+    }
+
+    /// The task's current title, falling back to the title an entry
+    /// recorded for tasks since deleted.
+    pub fn report_task_title(&self, row: &TaskBreakdownRow) -> String {
+        let Some(task_id) = row.task_id.as_deref() else {
+            return "No task".to_string();
+        };
+        let has_id = |v: &&Value| v.get("id").and_then(|v| v.as_str()) == Some(task_id);
+        let entry_task_id = |v: &&Value| v.get("task_id").and_then(|v| v.as_str()) == Some(task_id);
+        self.tasks
+            .iter()
+            .find(has_id)
+            .and_then(|t| t.get("title"))
+            .or_else(|| {
+                self.report_entries
+                    .iter()
+                    .find(entry_task_id)
+                    .and_then(|e| e.get("task_title"))
+            })
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown")
+            .to_string()
+    }
+
+    /// Writes the task breakdown on screen to `reports/` in the data
+    /// directory and shows where.
+    pub fn export_report_breakdown(&mut self) {
+        let rows = self.report_task_breakdown();
+        if rows.is_empty() {
+            self.status_message = "Nothing to export for this period".to_string();
+            return;
+        }
+
+        let written = task_breakdown_csv(&rows, |row| self.report_task_title(row))
+            .map_err(anyhow::Error::from)
+            .and_then(|csv| {
+                let dir = mootimer_core::storage::init_data_dir()?.join("reports");
+                std::fs::create_dir_all(&dir)?;
+                let path = dir.join(format!(
+                    "tasks-{}-{}-{}.csv",
+                    self.report_profile,
+                    self.report_period,
+                    Local::now().format("%Y-%m-%d")
+                ));
+                std::fs::write(&path, csv)?;
+                Ok(path)
+            });
+        self.status_message = match written {
+            Ok(path) => format!("Exported task breakdown to {}", path.display()),
+            Err(e) => format!("Export failed: {}", e),
+        };
+    }
+
+    /// Asks where to write the config to, or read it from.
+    pub fn open_config_file_prompt(&mut self, import: bool) {
+        self.input_mode = if import {
+            InputMode::ImportConfig
+        } else {
+            InputMode::ExportConfig
+        };
+        self.input_buffer = "~/mootimer-config.json".to_string();
+        self.status_message = if import {
+            "Import config from file:".to_string()
+        } else {
+            "Export config to file:".to_string()
+        };
+    }
+
+    async fn export_config(&mut self, path: &std::path::Path) {
+        let written = self.client.config_export().await.and_then(|config| {
+            std::fs::write(path, serde_json::to_string_pretty(&config)? + "\n")?;
+            Ok(())
+        });
+        self.status_message = match written {
+            Ok(()) => format!("Exported config to {}", path.display()),
+            Err(e) => format!("Export failed: {}", e),
+        };
+    }
+
+    async fn import_config(&mut self, path: &std::path::Path) -> Result<()> {
+        let config = std::fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| Ok(serde_json::from_str::<Value>(&text)?));
+        let imported = match config {
+            Ok(config) => self.client.config_import(config).await,
+            Err(e) => Err(e),
+        };
+        match imported {
+            Ok(result) => {
+                let clamped = result
+                    .get("clamped")
+                    .and_then(|c| c.as_array())
+                    .map_or(0, |c| c.len());
+                self.status_message = match clamped {
+                    0 => format!("Imported config from {}", path.display()),
+                    n => format!(
+                        "Imported config from {}; {} out-of-range value(s) clamped",
+                        path.display(),
+                        n
+                    ),
+                };
+                self.refresh_config().await?;
+            }
+            Err(e) => self.status_message = format!("Import failed: {}", e),
+        }
+        Ok(())
+    }
+
+    pub fn toggle_report_breakdown(&mut self) {
+        self.report_by_device = !self.report_by_device;
+        self.status_message = if self.report_by_device {
+            "Reports: Breakdown by Device".to_string()
+        } else {
+            "Reports: Breakdown by Task".to_string()
+        };
+    }
+
     pub fn get_move_task_profiles(&self) -> Vec<&Value> {
         self.profiles
             .iter()
@@ -1879,13 +4235,121 @@ impl App {
     pub fn get_tasks_for_entry_selection(&self) -> Vec<&Value> {
         self.tasks
             .iter()
-            .filter(|t| {
-                let status = t.get("status").and_then(|v| v.as_str()).unwrap_or("");
-                self.new_entry_show_archived || status != "archived"
-            })
+            .filter(|t| self.new_entry_show_archived || TaskStatus::of(t) != TaskStatus::Archived)
             .collect()
     }
 
+    /// Asks what to do with the overflow of a capped session. The task
+    /// picker shares its selection with the new-entry flow.
+    pub fn open_capped_session(&mut self, profile_id: &str, event_type: &Value) {
+        let (Some(task_id), Some(overflow_start), Some(overflow_end)) = (
+            event_type["task_id"].as_str(),
+            event_type["overflow_start"].as_str(),
+            event_type["overflow_end"].as_str(),
+        ) else {
+            return;
+        };
+        let overflow_seconds = event_type["overflow_seconds"].as_u64().unwrap_or(0);
+        if profile_id != self.profile_id {
+            self.status_message = format!(
+                "Session capped in profile {}; {} over was not logged",
+                profile_id,
+                crate::ui::helpers::format_duration_hm(overflow_seconds)
+            );
+            return;
+        }
+
+        self.capped_session = Some(CappedSession {
+            task_id: task_id.to_string(),
+            cap_seconds: event_type["cap_seconds"].as_u64().unwrap_or(0),
+            overflow_start: overflow_start.to_string(),
+            overflow_end: overflow_end.to_string(),
+            overflow_seconds,
+        });
+        self.new_entry_task_index = 0;
+        self.new_entry_show_archived = false;
+        self.input_mode = InputMode::SessionCapped;
+    }
+
+    /// Logs the overflow against the selected task, or drops it.
+    pub async fn resolve_capped_session(&mut self, reassign: bool) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+        let Some(capped) = self.capped_session.take() else {
+            return Ok(());
+        };
+        if !reassign {
+            self.status_message = "Overflow discarded".to_string();
+            return Ok(());
+        }
+
+        let task_id = match self.new_entry_task_index {
+            0 => None,
+            index => self
+                .get_tasks_for_entry_selection()
+                .get(index - 1)
+                .and_then(|t| t.get("id"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        };
+        match self
+            .client
+            .entry_create(
+                &self.profile_id,
+                &capped.overflow_start,
+                &capped.overflow_end,
+                task_id.as_deref(),
+                None,
+            )
+            .await
+        {
+            Ok(_) => {
+                self.status_message = "Overflow logged as a separate entry".to_string();
+                self.refresh_entries().await?;
+            }
+            Err(e) => self.status_message = format!("Error: {}", e),
+        }
+        Ok(())
+    }
+
+    pub fn start_quick_log(&mut self) {
+        self.input_mode = InputMode::QuickLog;
+        self.input_buffer.clear();
+        let task = self
+            .active_task
+            .as_ref()
+            .and_then(|t| t.get("title"))
+            .and_then(|v| v.as_str());
+        self.status_message = match task {
+            Some(title) => format!("Log time on {} (45m fix login bug):", title),
+            None => "Log time (45m fix login bug):".to_string(),
+        };
+    }
+
+    /// Logs `minutes` ending now on the active task, if there is one.
+    async fn quick_log(&mut self, minutes: u64, description: Option<&str>) -> Result<()> {
+        let task_id = self
+            .active_task
+            .as_ref()
+            .and_then(|t| t.get("id"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        match self
+            .client
+            .entry_log(&self.profile_id, task_id.as_deref(), minutes, description)
+            .await
+        {
+            Ok(_) => {
+                self.status_message = format!(
+                    "Logged {}",
+                    crate::ui::helpers::format_duration_hm(minutes * 60)
+                );
+                self.refresh_entries().await?;
+            }
+            Err(e) => self.status_message = format!("Error: {}", e),
+        }
+        Ok(())
+    }
+
     pub fn reset_new_entry_state(&mut self) {
         self.new_entry_start = None;
         self.new_entry_end = None;
@@ -1894,3 +4358,576 @@ impl App {
         self.new_entry_show_archived = false;
     }
 }
+
+fn parse_task_status(input: &str) -> Option<TaskStatus> {
+    match input
+        .trim()
+        .to_lowercase()
+        .replace([' ', '-'], "_")
+        .as_str()
+    {
+        "to_do" => Some(TaskStatus::Todo),
+        "doing" => Some(TaskStatus::InProgress),
+        name => serde_json::from_value(Value::String(name.to_string())).ok(),
+    }
+}
+
+/// The statuses of the Kanban columns, left to right.
+pub const KANBAN_STATUSES: [TaskStatus; 3] =
+    [TaskStatus::Todo, TaskStatus::InProgress, TaskStatus::Done];
+
+pub fn kanban_status(column: usize) -> TaskStatus {
+    KANBAN_STATUSES
+        .get(column)
+        .copied()
+        .unwrap_or(TaskStatus::Todo)
+}
+
+/// The Kanban column `status` is shown in; archived tasks have none.
+pub fn kanban_column(status: TaskStatus) -> Option<usize> {
+    KANBAN_STATUSES.iter().position(|s| *s == status)
+}
+
+/// Splits quick-log input into minutes and description. The duration
+/// comes first, as `45m`, `2h`, `1h30m` or plain minutes.
+fn parse_quick_log(input: &str) -> Result<(u64, Option<String>), String> {
+    let input = input.trim();
+    let (duration, description) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    let minutes = parse_minutes(duration)
+        .ok_or_else(|| "Start with a duration: 45m, 2h, 1h30m".to_string())?;
+    if minutes == 0 {
+        return Err("Duration must be at least a minute".to_string());
+    }
+    let description = description.trim();
+    Ok((
+        minutes,
+        (!description.is_empty()).then(|| description.to_string()),
+    ))
+}
+
+fn parse_minutes(text: &str) -> Option<u64> {
+    let text = text.to_lowercase();
+    if let Ok(minutes) = text.parse::<u64>() {
+        return Some(minutes);
+    }
+    let (hours, rest) = match text.split_once('h') {
+        Some((hours, rest)) => (hours.parse::<u64>().ok()?, rest),
+        None => (0, text.as_str()),
+    };
+    let minutes = match rest.strip_suffix('m') {
+        Some(minutes) => minutes.parse::<u64>().ok()?,
+        None if rest.is_empty() => 0,
+        None => return None,
+    };
+    hours.checked_mul(60)?.checked_add(minutes)
+}
+
+/// How far back entries count towards "recently used" in smart ordering.
+const RECENT_TASK_DAYS: i64 = 7;
+
+/// The `display` object the daemon attaches to each timer it returns.
+pub fn reported_display(timer: &Value) -> Option<TimerDisplay> {
+    serde_json::from_value(timer.get("display")?.clone()).ok()
+}
+
+/// The timers in a `timer.list` result, which maps timer ids to timers.
+fn listed_timers(timers: Value) -> Vec<Value> {
+    match timers {
+        Value::Object(timers) => timers.into_iter().map(|(_, t)| t).collect(),
+        Value::Array(timers) => timers,
+        _ => Vec::new(),
+    }
+}
+
+/// How long the Dashboard's all-profiles panel reuses its last fetch.
+const FLEET_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// One row per profile in `profiles` order, from `timer.list` and a
+/// `stats.bulk` of the `today` window.
+fn fleet_rows(profiles: &[Value], timers: &[Value], stats: &Value) -> Vec<FleetRow> {
+    let timers: Vec<ActiveTimer> = timers
+        .iter()
+        .filter_map(|t| serde_json::from_value(t.clone()).ok())
+        .collect();
+    profiles
+        .iter()
+        .filter_map(|p| p.get("id").and_then(|v| v.as_str()))
+        .map(|profile_id| {
+            let today = &stats["profiles"][profile_id]["today"];
+            let top_task = &today["top_task"];
+            FleetRow {
+                profile_id: profile_id.to_string(),
+                timer: timers
+                    .iter()
+                    .filter(|t| t.profile_id == profile_id)
+                    .filter(|t| t.is_running() || t.is_paused())
+                    .max_by_key(|t| (t.is_running(), t.start_time))
+                    .cloned(),
+                today_seconds: today["total_duration_seconds"].as_u64().unwrap_or(0),
+                top_task: top_task["task_title"]
+                    .as_str()
+                    .or_else(|| top_task["task_id"].as_str())
+                    .map(str::to_string),
+            }
+        })
+        .collect()
+}
+
+/// Commits listed under Recent Sync Activity in the settings.
+const SYNC_HISTORY_LIMIT: usize = 8;
+
+/// The latest entry start per task id.
+fn last_use_by_task<'a>(
+    entries: impl Iterator<Item = &'a Value>,
+) -> HashMap<String, DateTime<Utc>> {
+    let mut last_use: HashMap<String, DateTime<Utc>> = HashMap::new();
+    for entry in entries {
+        let Some(task_id) = entry.get("task_id").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let Some(started) = entry
+            .get("start_time")
+            .and_then(|v| v.as_str())
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+        else {
+            continue;
+        };
+        let latest = last_use.entry(task_id.to_string()).or_insert(started);
+        *latest = (*latest).max(started);
+    }
+    last_use
+}
+
+/// Orders tasks as pinned first, then by most recent use, then the rest,
+/// keeping the existing order within each group.
+fn smart_order(tasks: &mut [&Value], recent: &HashMap<String, DateTime<Utc>>) {
+    tasks.sort_by_key(|task| {
+        let pinned = task
+            .get("pinned")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let last_used = task
+            .get("id")
+            .and_then(|v| v.as_str())
+            .and_then(|id| recent.get(id));
+        (!pinned, std::cmp::Reverse(last_used.copied()))
+    });
+}
+
+const MAX_PASTE_CHARS: usize = 256;
+
+/// Input fields are single-line, so pasted text loses its line breaks and
+/// other control characters, and is capped at `MAX_PASTE_CHARS`.
+fn sanitize_paste(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_PASTE_CHARS)
+        .collect()
+}
+
+/// Converts `tui.priority_colors`, logging any color that had to fall back
+/// to its default.
+fn resolve_priority_colors(config: &TuiConfig) -> HashMap<TaskPriority, Color> {
+    let (colors, warnings) = config.resolved_priority_colors();
+    for warning in warnings {
+        tracing::warn!("{}", warning);
+    }
+    colors
+        .iter()
+        .map(|(priority, color)| (*priority, crate::ui::helpers::tui_color(color)))
+        .collect()
+}
+
+/// A path typed into the config export or import prompt, with a leading
+/// `~/` read as the home directory.
+fn config_file_path(input: &str) -> std::path::PathBuf {
+    let input = input.trim();
+    match (input.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => std::path::Path::new(&home).join(rest),
+        _ => std::path::PathBuf::from(input),
+    }
+}
+
+/// Reads the animations folder, logging any file that was skipped.
+fn load_animations() -> Animations {
+    let (animations, warnings) = Animations::load(&animation::dir());
+    for warning in warnings {
+        tracing::warn!("{}", warning);
+    }
+    animations
+}
+
+/// Converts `tui.keybindings`, logging any entry that was ignored.
+fn resolve_keybindings(config: &TuiConfig) -> Keybindings {
+    let (keybindings, warnings) = Keybindings::from_config(&config.keybindings);
+    for warning in warnings {
+        tracing::warn!("{}", warning);
+    }
+    keybindings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_status_input_and_columns() {
+        assert_eq!(parse_task_status("Completed"), Some(TaskStatus::Done));
+        assert_eq!(
+            parse_task_status("in progress"),
+            Some(TaskStatus::InProgress)
+        );
+        assert_eq!(parse_task_status("to-do"), Some(TaskStatus::Todo));
+        assert_eq!(parse_task_status("blocked"), None);
+
+        for (column, status) in KANBAN_STATUSES.iter().enumerate() {
+            assert_eq!(kanban_column(*status), Some(column));
+            assert_eq!(kanban_status(column), *status);
+        }
+        assert_eq!(kanban_column(TaskStatus::Archived), None);
+    }
+
+    #[test]
+    fn test_config_file_path_expands_home() {
+        let home = std::path::PathBuf::from(std::env::var_os("HOME").unwrap());
+        assert_eq!(
+            config_file_path(" ~/backup/config.json "),
+            home.join("backup/config.json")
+        );
+        assert_eq!(
+            config_file_path("/tmp/~/config.json"),
+            std::path::PathBuf::from("/tmp/~/config.json")
+        );
+    }
+
+    #[test]
+    fn test_sanitize_paste() {
+        assert_eq!(
+            sanitize_paste("PROJ-123 Fix login\r\n"),
+            "PROJ-123 Fix login"
+        );
+        assert_eq!(sanitize_paste("09:\n30"), "09:30");
+        assert_eq!(sanitize_paste(&"x".repeat(300)).len(), MAX_PASTE_CHARS);
+    }
+
+    #[tokio::test]
+    async fn test_focus_timer_wraps_and_falls_back() {
+        let mut app = App::new(MooTimerClient::new("/nonexistent.sock"), "p".to_string());
+        app.active_timers = ["a", "b", "c"]
+            .iter()
+            .map(|id| serde_json::json!({ "id": id }))
+            .collect();
+        assert_eq!(app.focused_timer().unwrap()["id"], "a");
+
+        app.focus_timer(-1);
+        assert_eq!(app.timer_info.as_ref().unwrap()["id"], "c");
+        app.focus_timer(1);
+        app.focus_timer(1);
+        assert_eq!(app.timer_info.as_ref().unwrap()["id"], "b");
+
+        // A stopped focused timer hands over to the oldest one.
+        app.active_timers.remove(1);
+        assert_eq!(app.focused_timer().unwrap()["id"], "a");
+    }
+
+    #[tokio::test]
+    async fn test_sync_runs_in_background() {
+        let mut app = App::new(MooTimerClient::new("/nonexistent.sock"), "p".to_string());
+        app.sync_now();
+        app.on_sync_event(&serde_json::json!({"type": "stage", "stage": "pulling"}))
+            .await
+            .unwrap();
+        let label = app.sync_progress.as_ref().unwrap().label(Instant::now());
+        assert!(label.ends_with("Syncing: pulling…"), "{}", label);
+
+        // The reply, here a connection error, is picked up by polling.
+        for _ in 0..200 {
+            app.poll_sync().await.unwrap();
+            if app.sync_progress.is_none() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(app.sync_progress.is_none());
+        assert!(app.status_message.starts_with("Sync failed: "));
+
+        // After Esc the daemon's event reports the outcome instead.
+        app.sync_now();
+        app.stop_waiting_for_sync();
+        assert!(app.sync_progress.is_none());
+        assert!(app.status_message.contains("still syncing"));
+        app.on_sync_event(
+            &serde_json::json!({"type": "failed", "error": "Failed to push: denied"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(app.status_message, "Sync failed: Failed to push: denied");
+        assert!(!app.sync_detached);
+    }
+
+    #[test]
+    fn test_smart_order() {
+        let now = Utc::now();
+        let tasks = [
+            serde_json::json!({"id": "old"}),
+            serde_json::json!({"id": "recent"}),
+            serde_json::json!({"id": "pinned", "pinned": true}),
+            serde_json::json!({"id": "latest"}),
+            serde_json::json!({"id": "unused"}),
+        ];
+        let entries = [
+            serde_json::json!({"task_id": "recent", "start_time": (now - chrono::Duration::days(2)).to_rfc3339()}),
+            serde_json::json!({"task_id": "latest", "start_time": (now - chrono::Duration::days(3)).to_rfc3339()}),
+            serde_json::json!({"task_id": "latest", "start_time": now.to_rfc3339()}),
+            serde_json::json!({"start_time": now.to_rfc3339()}),
+        ];
+        let recent = last_use_by_task(entries.iter());
+        assert_eq!(recent.len(), 2);
+
+        let mut ordered: Vec<&Value> = tasks.iter().collect();
+        smart_order(&mut ordered, &recent);
+        let ids: Vec<&str> = ordered.iter().map(|t| t["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, ["pinned", "latest", "recent", "old", "unused"]);
+    }
+
+    #[tokio::test]
+    async fn test_paste_stays_within_the_yanked_profile() {
+        let mut app = App::new(MooTimerClient::new("/nonexistent.sock"), "p".to_string());
+        app.paste_yanked_task().await.unwrap();
+        assert!(app.status_message.starts_with("Nothing yanked"));
+
+        app.tasks = vec![serde_json::json!({"id": "review", "title": "Weekly review"})];
+        app.yank_selected_task();
+        assert_eq!(
+            app.yanked_task,
+            Some(("p".to_string(), "review".to_string()))
+        );
+
+        app.profile_id = "other".to_string();
+        app.paste_yanked_task().await.unwrap();
+        assert!(
+            app.status_message.contains("profile 'p'"),
+            "{}",
+            app.status_message
+        );
+    }
+
+    #[tokio::test]
+    async fn test_block_state() {
+        let mut app = App::new(MooTimerClient::new("/nonexistent.sock"), "p".to_string());
+        app.tasks = vec![
+            serde_json::json!({"id": "design", "title": "Design", "status": "done"}),
+            serde_json::json!({"id": "api", "title": "API", "status": "in_progress"}),
+        ];
+        let free = serde_json::json!({"id": "free"});
+        let waiting = serde_json::json!({"id": "ui", "blocked_by": ["design", "api"]});
+        let cleared = serde_json::json!({"id": "docs", "blocked_by": ["design"]});
+        let missing = serde_json::json!({"id": "ship", "blocked_by": ["gone"]});
+
+        assert_eq!(app.block_state(&free), None);
+        assert_eq!(app.block_state(&waiting), Some(BlockState::Blocked));
+        assert_eq!(app.block_state(&cleared), Some(BlockState::Cleared));
+        assert_eq!(app.block_state(&missing), Some(BlockState::Blocked));
+        assert_eq!(
+            app.blockers(&waiting),
+            vec![("Design".to_string(), true), ("API".to_string(), false)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_task_search_is_fuzzy_and_ranked() {
+        let mut app = App::new(MooTimerClient::new("/nonexistent.sock"), "p".to_string());
+        app.smart_task_order = false;
+        app.tasks = vec![
+            serde_json::json!({"id": "plan", "title": "Plan: long bug hunt", "status": "todo"}),
+            serde_json::json!({"id": "fix", "title": "Fix login bug", "status": "todo"}),
+            serde_json::json!({"id": "docs", "title": "Docs", "status": "todo"}),
+        ];
+        app.task_search = "lgn bug".to_string();
+
+        let ids: Vec<&str> = app
+            .get_filtered_tasks()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["fix", "plan"]);
+        assert_eq!(app.get_kanban_tasks(0).len(), 2);
+        assert_eq!(
+            app.task_search_match(&app.tasks[1]).unwrap().spans,
+            [(4, 5), (6, 7), (8, 9), (10, 13)]
+        );
+
+        app.tasks[0]["tags"] = serde_json::json!(["backend", "urgent"]);
+        app.tasks[1]["tags"] = serde_json::json!(["backend"]);
+        app.task_search = "bug #backend #urgent".to_string();
+        let ids: Vec<&str> = app
+            .get_filtered_tasks()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["plan"]);
+    }
+
+    #[test]
+    fn test_fleet_rows() {
+        let profiles = [
+            serde_json::json!({"id": "work", "name": "Work"}),
+            serde_json::json!({"id": "home", "name": "Home"}),
+        ];
+        let timer = |id: &str, state| {
+            let mut timer = ActiveTimer::new_manual("work".to_string(), None, None);
+            timer.id = id.to_string();
+            timer.state = state;
+            serde_json::to_value(timer).unwrap()
+        };
+        let timers = [
+            timer("paused", TimerState::Paused),
+            timer("running", TimerState::Running),
+        ];
+        let stats = serde_json::json!({"profiles": {
+            "work": {"today": {
+                "total_duration_seconds": 5400,
+                "top_task": {"task_id": "api", "task_title": "API"},
+            }},
+            "home": {"today": {"total_duration_seconds": 0, "top_task": null}},
+        }});
+
+        let rows = fleet_rows(&profiles, &timers, &stats);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0].timer.as_ref().map(|t| t.id.as_str()),
+            Some("running")
+        );
+        assert_eq!(rows[0].today_seconds, 5400);
+        assert_eq!(rows[0].top_task.as_deref(), Some("API"));
+        assert_eq!(
+            rows[1],
+            FleetRow {
+                profile_id: "home".to_string(),
+                timer: None,
+                today_seconds: 0,
+                top_task: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_split_panes_keep_their_own_view_and_selection() {
+        let mut app = App::new(MooTimerClient::new("/nonexistent.sock"), "p".to_string());
+        app.selected_task_index = 3;
+        app.toggle_split();
+        assert!(app.split_mode);
+        app.set_split_view(AppView::Entries);
+
+        app.switch_split_focus();
+        assert_eq!(app.current_view, AppView::Entries);
+        app.selected_entry_index = 5;
+        // Alt+5 while on the right swaps the right pane's view in place.
+        app.set_split_view(AppView::Dashboard);
+        assert_eq!(app.current_view, AppView::Dashboard);
+        app.selected_task_index = 1;
+
+        // Both panes on the Dashboard, each with its own selected task.
+        app.switch_split_focus();
+        assert_eq!(app.split_focus, SplitPane::Left);
+        assert_eq!(app.selected_task_index, 3);
+        let other = app.with_other_pane(|app| (app.current_view, app.selected_task_index));
+        assert_eq!(other, (AppView::Dashboard, 1));
+        assert_eq!(app.selected_task_index, 3);
+
+        // Closing from the right pane goes back to the left one.
+        app.switch_split_focus();
+        app.toggle_split();
+        assert!(!app.split_mode);
+        assert_eq!(app.split_focus, SplitPane::Left);
+        assert_eq!(app.selected_task_index, 3);
+    }
+
+    #[tokio::test]
+    async fn test_timer_display_comes_from_daemon() {
+        let mut app = App::new(MooTimerClient::new("/nonexistent.sock"), "p".to_string());
+        let mut timer = ActiveTimer::new_countdown("p".to_string(), None, None, 10);
+        timer.state = TimerState::Paused;
+        let mut value = serde_json::to_value(&timer).unwrap();
+        value["display"] = serde_json::json!({
+            "elapsed_seconds": 120,
+            "remaining_seconds": 480,
+            "total_seconds": 600,
+            "progress_ratio": 0.2,
+            "phase_display": "Countdown",
+            "next_phase_display": null,
+        });
+
+        // timer.list maps timer ids to timers.
+        let listed = listed_timers(serde_json::json!({ timer.id.clone(): value }));
+        assert_eq!(listed.len(), 1);
+        app.active_timers = listed;
+        app.timer_info = app.active_timers.first().cloned();
+
+        let display = app.timer_display(&app.active_timer().unwrap());
+        assert_eq!(display.elapsed_seconds, 120);
+        assert_eq!(display.remaining_seconds, Some(480));
+        assert_eq!(display.progress_ratio, Some(0.2));
+
+        // Without one from the daemon it is worked out locally.
+        app.active_timers.clear();
+        app.timer_info = Some(serde_json::to_value(&timer).unwrap());
+        assert_eq!(app.timer_display(&timer), timer.display());
+    }
+
+    #[tokio::test]
+    async fn test_report_task_breakdown_titles() {
+        let mut app = App::new(MooTimerClient::new("/nonexistent.sock"), "p".to_string());
+        app.tasks = vec![serde_json::json!({"id": "api", "title": "API, v2"})];
+        app.report_entries = vec![
+            serde_json::json!({"task_id": "api", "task_title": "Old", "duration_seconds": 3600}),
+            serde_json::json!({"task_id": "gone", "task_title": "Deleted", "duration_seconds": 1800}),
+            serde_json::json!({"task_id": null, "duration_seconds": 1800}),
+            serde_json::json!({"task_id": "api", "duration_seconds": 1800}),
+        ];
+
+        let rows = app.report_task_breakdown();
+        let titles: Vec<_> = rows.iter().map(|r| app.report_task_title(r)).collect();
+        assert_eq!(titles, vec!["API, v2", "No task", "Deleted"]);
+        assert_eq!(rows[0].sessions, 2);
+    }
+
+    #[tokio::test]
+    async fn test_profile_style() {
+        let mut app = App::new(MooTimerClient::new("/nonexistent.sock"), "p".to_string());
+        app.profiles = vec![
+            serde_json::json!({"id": "work", "color": "#F73", "icon": "💼"}),
+            serde_json::json!({"id": "home", "color": "light_green"}),
+        ];
+        assert_eq!(app.profile_style("work"), (Color::Rgb(255, 119, 51), "💼"));
+        assert_eq!(app.profile_style("home").0, Color::LightGreen);
+
+        assert_eq!(
+            app.profile_style("home").1,
+            default_color_and_icon("home").1
+        );
+        let (color, icon) = default_color_and_icon("gone");
+        assert_eq!(
+            app.profile_style("gone"),
+            (crate::ui::helpers::profile_color(color), icon)
+        );
+    }
+
+    #[test]
+    fn test_parse_quick_log() {
+        assert_eq!(
+            parse_quick_log("45m fix login bug"),
+            Ok((45, Some("fix login bug".to_string())))
+        );
+        assert_eq!(parse_quick_log(" 1h30m "), Ok((90, None)));
+        assert_eq!(
+            parse_quick_log("2H review"),
+            Ok((120, Some("review".to_string())))
+        );
+        assert_eq!(parse_quick_log("20"), Ok((20, None)));
+        for input in ["", "fix login bug", "0m", "1h30", "m"] {
+            assert!(parse_quick_log(input).is_err(), "{}", input);
+        }
+    }
+}
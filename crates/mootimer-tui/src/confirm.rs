@@ -0,0 +1,132 @@
+//! The confirmation dialog every destructive action goes through.
+//!
+//! A dialog carries the action it guards, so confirming runs exactly what
+//! was shown even if the list behind it refreshed in the meantime. The
+//! focused button starts on "No" for destructive actions, so a stray Enter
+//! never deletes anything.
+
+use crossterm::event::KeyCode;
+use mootimer_core::models::TaskStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Danger {
+    /// Changes data in a way that is tedious but possible to undo.
+    Caution,
+    /// Loses data or work.
+    Destructive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmButton {
+    Yes,
+    No,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfirmAction {
+    Quit,
+    DeleteTask { id: String },
+    DeleteProfile { id: String },
+    DeleteEntry { id: String },
+    BulkStatus { from: TaskStatus, to: TaskStatus },
+    BulkTag { tags: Vec<String>, untag: bool },
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfirmDialog {
+    pub title: String,
+    pub message: String,
+    /// Text of the "Yes" button, naming what it does.
+    pub yes_label: &'static str,
+    pub danger: Danger,
+    pub focused: ConfirmButton,
+    pub action: ConfirmAction,
+}
+
+impl ConfirmDialog {
+    pub fn new(
+        action: ConfirmAction,
+        title: impl Into<String>,
+        message: impl Into<String>,
+        yes_label: &'static str,
+        danger: Danger,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+            yes_label,
+            danger,
+            focused: match danger {
+                Danger::Caution => ConfirmButton::Yes,
+                Danger::Destructive => ConfirmButton::No,
+            },
+            action,
+        }
+    }
+
+    /// `Some(true)` to run the action, `Some(false)` to close the dialog,
+    /// `None` while it stays open.
+    pub fn handle_key(&mut self, code: KeyCode) -> Option<bool> {
+        match code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => Some(true),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Char('q') | KeyCode::Esc => {
+                Some(false)
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => Some(self.focused == ConfirmButton::Yes),
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.focused = ConfirmButton::Yes;
+                None
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.focused = ConfirmButton::No;
+                None
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.focused = match self.focused {
+                    ConfirmButton::Yes => ConfirmButton::No,
+                    ConfirmButton::No => ConfirmButton::Yes,
+                };
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dialog(danger: Danger) -> ConfirmDialog {
+        ConfirmDialog::new(
+            ConfirmAction::DeleteEntry {
+                id: "e1".to_string(),
+            },
+            "Delete Entry?",
+            "Delete this entry?",
+            "Delete",
+            danger,
+        )
+    }
+
+    #[test]
+    fn test_enter_follows_focused_button() {
+        let mut destructive = dialog(Danger::Destructive);
+        assert_eq!(destructive.handle_key(KeyCode::Enter), Some(false));
+        assert_eq!(destructive.handle_key(KeyCode::Left), None);
+        assert_eq!(destructive.focused, ConfirmButton::Yes);
+        assert_eq!(destructive.handle_key(KeyCode::Enter), Some(true));
+
+        let mut caution = dialog(Danger::Caution);
+        assert_eq!(caution.handle_key(KeyCode::Tab), None);
+        assert_eq!(caution.handle_key(KeyCode::Char(' ')), Some(false));
+    }
+
+    #[test]
+    fn test_letter_keys_answer_directly() {
+        let mut d = dialog(Danger::Destructive);
+        assert_eq!(d.handle_key(KeyCode::Char('y')), Some(true));
+        assert_eq!(d.handle_key(KeyCode::Esc), Some(false));
+        assert_eq!(d.handle_key(KeyCode::Char('x')), None);
+    }
+}
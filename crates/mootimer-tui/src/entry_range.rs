@@ -0,0 +1,181 @@
+//! The span of days shown in the Entries view.
+//!
+//! A range is a granularity (day, week or month) anchored at a date. `[`
+//! and `]` step the anchor by one period, and `D` jumps to a typed date.
+//! Dates are local: the TUI sets `TZ` from `tui.timezone` at startup, so
+//! "today" and "yesterday" follow the configured timezone.
+
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, TimeZone, Utc, Weekday};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryRange {
+    pub granularity: Granularity,
+    pub anchor: NaiveDate,
+}
+
+impl EntryRange {
+    pub fn today() -> Self {
+        Self {
+            granularity: Granularity::Day,
+            anchor: Local::now().date_naive(),
+        }
+    }
+
+    /// First and last day of the period containing the anchor. Weeks start
+    /// on Monday, as in the daemon's `entry.week`.
+    pub fn days(&self) -> (NaiveDate, NaiveDate) {
+        match self.granularity {
+            Granularity::Day => (self.anchor, self.anchor),
+            Granularity::Week => {
+                let start = self.anchor
+                    - Duration::days(self.anchor.weekday().num_days_from_monday() as i64);
+                (start, start + Duration::days(6))
+            }
+            Granularity::Month => {
+                let start = self.anchor.with_day(1).unwrap_or(self.anchor);
+                let end = start
+                    .checked_add_months(Months::new(1))
+                    .map(|next| next - Duration::days(1))
+                    .unwrap_or(start);
+                (start, end)
+            }
+        }
+    }
+
+    /// Moves the anchor by `periods` whole periods.
+    pub fn step(&mut self, periods: i32) {
+        self.anchor = match self.granularity {
+            Granularity::Day => self.anchor + Duration::days(periods as i64),
+            Granularity::Week => self.anchor + Duration::weeks(periods as i64),
+            Granularity::Month => {
+                let months = Months::new(periods.unsigned_abs());
+                let moved = if periods < 0 {
+                    self.anchor.checked_sub_months(months)
+                } else {
+                    self.anchor.checked_add_months(months)
+                };
+                moved.unwrap_or(self.anchor)
+            }
+        };
+    }
+
+    /// Bounds for `entry.filter`: local midnight at the start of the first
+    /// day to the last instant of the last day.
+    pub fn utc_bounds(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        let (first, last) = self.days();
+        let start = local_midnight(first);
+        let end = local_midnight(last + Duration::days(1)) - Duration::nanoseconds(1);
+        (start, end)
+    }
+
+    pub fn label(&self) -> String {
+        let (first, last) = self.days();
+        match self.granularity {
+            Granularity::Day => first.format("%a %Y-%m-%d").to_string(),
+            Granularity::Week | Granularity::Month => format!(
+                "{} – {}",
+                first.format("%a %Y-%m-%d"),
+                last.format("%a %Y-%m-%d")
+            ),
+        }
+    }
+}
+
+fn local_midnight(date: NaiveDate) -> DateTime<Utc> {
+    let naive = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    // A DST change can skip midnight; fall back to reading it as UTC.
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| naive.and_utc())
+}
+
+/// Parses a date typed into the Entries view: `2024-06-11`, `today`,
+/// `yesterday`, a weekday such as `tue`, or `last tue`. A bare weekday is
+/// the most recent one, today included; `last` skips today.
+pub fn parse_date(input: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let input = input.trim().to_lowercase();
+    match input.as_str() {
+        "" | "today" => return Ok(today),
+        "yesterday" => return Ok(today - Duration::days(1)),
+        _ => {}
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Ok(date);
+    }
+
+    let (day, skip_today) = match input.strip_prefix("last ") {
+        Some(day) => (day.trim(), true),
+        None => (input.as_str(), false),
+    };
+    let weekday: Weekday = day.parse().map_err(|_| {
+        format!(
+            "Unknown date '{}'. Try 2024-06-11, yesterday or last tue",
+            input
+        )
+    })?;
+
+    let mut back = (today.weekday().num_days_from_monday() as i64
+        - weekday.num_days_from_monday() as i64)
+        .rem_euclid(7);
+    if back == 0 && skip_today {
+        back = 7;
+    }
+    Ok(today - Duration::days(back))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_parse_date() {
+        // A Thursday.
+        let today = date("2024-06-13");
+
+        assert_eq!(parse_date("2024-06-11", today), Ok(date("2024-06-11")));
+        assert_eq!(parse_date(" Today ", today), Ok(today));
+        assert_eq!(parse_date("yesterday", today), Ok(date("2024-06-12")));
+        assert_eq!(parse_date("tue", today), Ok(date("2024-06-11")));
+        assert_eq!(parse_date("last tuesday", today), Ok(date("2024-06-11")));
+        assert_eq!(parse_date("thu", today), Ok(today));
+        assert_eq!(parse_date("last thu", today), Ok(date("2024-06-06")));
+        assert_eq!(parse_date("fri", today), Ok(date("2024-06-07")));
+
+        assert!(parse_date("next week", today).is_err());
+        assert!(parse_date("2024-13-01", today).is_err());
+    }
+
+    #[test]
+    fn test_days_and_stepping() {
+        let mut range = EntryRange {
+            granularity: Granularity::Week,
+            anchor: date("2024-06-13"),
+        };
+        assert_eq!(range.days(), (date("2024-06-10"), date("2024-06-16")));
+        range.step(-1);
+        assert_eq!(range.days(), (date("2024-06-03"), date("2024-06-09")));
+
+        range.granularity = Granularity::Month;
+        range.anchor = date("2024-03-31");
+        range.step(-1);
+        assert_eq!(range.days(), (date("2024-02-01"), date("2024-02-29")));
+
+        range.granularity = Granularity::Day;
+        range.step(1);
+        assert_eq!(range.label(), "Fri 2024-03-01");
+    }
+}
@@ -0,0 +1,246 @@
+//! Vim-style count prefixes (`5j`, `3>`) and `.` repeat for Normal mode.
+//!
+//! Digits are buffered into a count that the next repeatable key consumes.
+//! Because `1`–`7` also switch views, a lone digit that is not followed by a
+//! repeatable key (or that sits for `COUNT_TIMEOUT`) is replayed as a plain
+//! key, the same way vim resolves ambiguous mappings.
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::time::{Duration, Instant};
+
+pub const COUNT_TIMEOUT: Duration = Duration::from_millis(600);
+const MAX_COUNT: u32 = 999;
+
+/// A key to hand to the regular Normal-mode handler, `count` times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPress {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+    pub count: u32,
+}
+
+impl KeyPress {
+    fn once(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self {
+            code,
+            modifiers,
+            count: 1,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CountPrefix {
+    pending: Option<(u32, Instant)>,
+    digits: usize,
+    last: Option<KeyPress>,
+}
+
+/// Navigation and adjustment keys, i.e. the ones where a count makes sense.
+fn is_repeatable(code: KeyCode, modifiers: KeyModifiers) -> bool {
+    if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+        return false;
+    }
+    match code {
+        // Some terminals report these with SHIFT held.
+        KeyCode::Char('>' | '<') => true,
+        _ if modifiers.contains(KeyModifiers::SHIFT) => false,
+        KeyCode::Up
+        | KeyCode::Down
+        | KeyCode::Left
+        | KeyCode::Right
+        | KeyCode::PageUp
+        | KeyCode::PageDown
+        | KeyCode::Char('j' | 'k' | 'h' | 'l') => true,
+        _ => false,
+    }
+}
+
+impl CountPrefix {
+    /// The count typed so far, for the status bar.
+    pub fn pending(&self) -> Option<u32> {
+        self.pending.map(|(count, _)| count)
+    }
+
+    /// Feeds one Normal-mode key and returns the keys to dispatch, in order.
+    pub fn feed(&mut self, code: KeyCode, modifiers: KeyModifiers, now: Instant) -> Vec<KeyPress> {
+        if let KeyCode::Char(c @ '0'..='9') = code
+            && modifiers.is_empty()
+            && (c != '0' || self.pending.is_some())
+        {
+            let digit = c.to_digit(10).unwrap_or(0);
+            let count = self.pending().unwrap_or(0);
+            self.pending = Some(((count * 10 + digit).min(MAX_COUNT), now));
+            self.digits += 1;
+            return Vec::new();
+        }
+
+        if code == KeyCode::Esc && self.pending.is_some() {
+            self.take();
+            return Vec::new();
+        }
+
+        if code == KeyCode::Char('.') && modifiers.is_empty() {
+            let count = self.take();
+            let Some(mut last) = self.last else {
+                return Vec::new();
+            };
+            if let Some(count) = count {
+                last.count = count;
+                self.last = Some(last);
+            }
+            return vec![last];
+        }
+
+        if is_repeatable(code, modifiers) {
+            let press = KeyPress {
+                code,
+                modifiers,
+                count: self.take().unwrap_or(1),
+            };
+            self.last = Some(press);
+            return vec![press];
+        }
+
+        let mut keys: Vec<KeyPress> = self.flush().into_iter().collect();
+        keys.push(KeyPress::once(code, modifiers));
+        keys
+    }
+
+    /// Gives up on a count that has waited longer than `COUNT_TIMEOUT`,
+    /// returning the lone digit to replay, if that is what it was.
+    pub fn expire(&mut self, now: Instant) -> Option<KeyPress> {
+        match self.pending {
+            Some((_, started)) if now.duration_since(started) >= COUNT_TIMEOUT => self.flush(),
+            _ => None,
+        }
+    }
+
+    fn take(&mut self) -> Option<u32> {
+        self.digits = 0;
+        self.pending.take().map(|(count, _)| count)
+    }
+
+    /// Drops the pending count. A single digit is handed back as a plain key
+    /// so view switching keeps working; longer counts are discarded.
+    fn flush(&mut self) -> Option<KeyPress> {
+        let digits = self.digits;
+        let count = self.take()?;
+        let digit = char::from_digit(count, 10).filter(|_| digits == 1)?;
+        Some(KeyPress::once(KeyCode::Char(digit), KeyModifiers::NONE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Key = (KeyCode, KeyModifiers);
+
+    fn key(c: char) -> Key {
+        (KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn press(c: char, count: u32) -> KeyPress {
+        KeyPress {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE,
+            count,
+        }
+    }
+
+    #[test]
+    fn test_feed_sequences() {
+        let cases: &[(&str, &[Key], &[KeyPress])] = &[
+            ("plain motion", &[key('j')], &[press('j', 1)]),
+            ("counted motion", &[key('5'), key('j')], &[press('j', 5)]),
+            (
+                "multi-digit count",
+                &[key('1'), key('2'), key('k')],
+                &[press('k', 12)],
+            ),
+            ("count with adjust", &[key('3'), key('>')], &[press('>', 3)]),
+            (
+                "page keys take counts",
+                &[key('2'), (KeyCode::PageDown, KeyModifiers::NONE)],
+                &[KeyPress {
+                    code: KeyCode::PageDown,
+                    modifiers: KeyModifiers::NONE,
+                    count: 2,
+                }],
+            ),
+            ("leading zero is a key", &[key('0')], &[press('0', 1)]),
+            (
+                "lone digit replays before other key",
+                &[key('3'), key('r')],
+                &[press('3', 1), press('r', 1)],
+            ),
+            (
+                "long count before other key is dropped",
+                &[key('4'), key('2'), key('r')],
+                &[press('r', 1)],
+            ),
+            (
+                "shifted keys are not counted",
+                &[key('2'), (KeyCode::Char('H'), KeyModifiers::SHIFT)],
+                &[
+                    press('2', 1),
+                    KeyPress {
+                        code: KeyCode::Char('H'),
+                        modifiers: KeyModifiers::SHIFT,
+                        count: 1,
+                    },
+                ],
+            ),
+            (
+                "escape cancels a count",
+                &[key('3'), (KeyCode::Esc, KeyModifiers::NONE), key('j')],
+                &[press('j', 1)],
+            ),
+            ("repeat with nothing to repeat", &[key('.')], &[]),
+            (
+                "repeat reuses last count",
+                &[key('4'), key('j'), key('.')],
+                &[press('j', 4), press('j', 4)],
+            ),
+            (
+                "count on repeat replaces it",
+                &[key('4'), key('j'), key('2'), key('.')],
+                &[press('j', 4), press('j', 2)],
+            ),
+            (
+                "other keys do not reset repeat",
+                &[key('3'), key('k'), key('r'), key('.')],
+                &[press('k', 3), press('r', 1), press('k', 3)],
+            ),
+        ];
+
+        for (name, keys, expected) in cases {
+            let mut prefix = CountPrefix::default();
+            let now = Instant::now();
+            let actual: Vec<KeyPress> = keys
+                .iter()
+                .flat_map(|(code, modifiers)| prefix.feed(*code, *modifiers, now))
+                .collect();
+            assert_eq!(&actual, expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_pending_count_and_expiry() {
+        let mut prefix = CountPrefix::default();
+        let start = Instant::now();
+
+        prefix.feed(KeyCode::Char('4'), KeyModifiers::NONE, start);
+        assert_eq!(prefix.pending(), Some(4));
+        assert_eq!(prefix.expire(start), None);
+        assert_eq!(prefix.expire(start + COUNT_TIMEOUT), Some(press('4', 1)));
+        assert_eq!(prefix.pending(), None);
+
+        prefix.feed(KeyCode::Char('1'), KeyModifiers::NONE, start);
+        prefix.feed(KeyCode::Char('5'), KeyModifiers::NONE, start);
+        assert_eq!(prefix.pending(), Some(15));
+        assert_eq!(prefix.expire(start + COUNT_TIMEOUT), None);
+        assert_eq!(prefix.pending(), None);
+    }
+}
@@ -0,0 +1,386 @@
+//! Keys the user can remap with `tui.keybindings`.
+//!
+//! Each [`Action`] has a default key; the config maps action names such as
+//! `"timer.stop"` to key specs such as `"s"`, `"ctrl+s"` or `"f5"`. Handlers
+//! ask [`Keybindings::is`] instead of matching the key themselves, so a
+//! remapped action answers only to its new key. Entries that don't parse,
+//! name no action, or clash with another action keep the default and are
+//! reported as warnings.
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Help,
+    ViewDashboard,
+    ViewTimers,
+    ViewKanban,
+    ViewEntries,
+    ViewReports,
+    ViewSettings,
+    ViewLogs,
+    TimerRepeat,
+    TimerStop,
+    TimerPause,
+    TimerStartAnother,
+    TaskNew,
+    TaskQuickAdd,
+    TaskEdit,
+    TaskDelete,
+    TaskArchive,
+    TaskMove,
+    TaskSearch,
+}
+
+impl Action {
+    pub const ALL: [Action; 20] = [
+        Action::Quit,
+        Action::Help,
+        Action::ViewDashboard,
+        Action::ViewTimers,
+        Action::ViewKanban,
+        Action::ViewEntries,
+        Action::ViewReports,
+        Action::ViewSettings,
+        Action::ViewLogs,
+        Action::TimerRepeat,
+        Action::TimerStop,
+        Action::TimerPause,
+        Action::TimerStartAnother,
+        Action::TaskNew,
+        Action::TaskQuickAdd,
+        Action::TaskEdit,
+        Action::TaskDelete,
+        Action::TaskArchive,
+        Action::TaskMove,
+        Action::TaskSearch,
+    ];
+
+    /// The name used in `tui.keybindings`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "app.quit",
+            Action::Help => "app.help",
+            Action::ViewDashboard => "view.dashboard",
+            Action::ViewTimers => "view.timers",
+            Action::ViewKanban => "view.kanban",
+            Action::ViewEntries => "view.entries",
+            Action::ViewReports => "view.reports",
+            Action::ViewSettings => "view.settings",
+            Action::ViewLogs => "view.logs",
+            Action::TimerRepeat => "timer.repeat",
+            Action::TimerStop => "timer.stop",
+            Action::TimerPause => "timer.pause",
+            Action::TimerStartAnother => "timer.start_another",
+            Action::TaskNew => "task.new",
+            Action::TaskQuickAdd => "task.quick_add",
+            Action::TaskEdit => "task.edit",
+            Action::TaskDelete => "task.delete",
+            Action::TaskArchive => "task.archive",
+            Action::TaskMove => "task.move",
+            Action::TaskSearch => "task.search",
+        }
+    }
+
+    /// The built-in key, written the way the help shows it.
+    pub const fn default_key(self) -> &'static str {
+        match self {
+            Action::Quit => "q",
+            Action::Help => "?",
+            Action::ViewDashboard => "1",
+            Action::ViewTimers => "2",
+            Action::ViewKanban => "3",
+            Action::ViewEntries => "4",
+            Action::ViewReports => "5",
+            Action::ViewSettings => "6",
+            Action::ViewLogs => "7",
+            Action::TimerRepeat => "R",
+            Action::TimerStop => "x",
+            Action::TimerPause => "Space",
+            Action::TimerStartAnother => "+",
+            Action::TaskNew => "n",
+            Action::TaskQuickAdd => "N",
+            Action::TaskEdit => "e",
+            Action::TaskDelete => "d",
+            Action::TaskArchive => "a",
+            Action::TaskMove => "m",
+            Action::TaskSearch => "/",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.name() == name)
+    }
+}
+
+/// One key with its Ctrl and Alt modifiers. Shift is part of the key
+/// itself (`N`, `BackTab`), since terminals don't report it consistently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeySpec {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+const MODIFIER_MASK: KeyModifiers = KeyModifiers::CONTROL.union(KeyModifiers::ALT);
+
+impl KeySpec {
+    /// Parses `x`, `N`, `ctrl+s`, `alt+shift+n`, `f5`, `space`, `pgdn` and
+    /// the like. Named keys and modifiers are case-insensitive; a single
+    /// character is taken as written.
+    pub fn parse(spec: &str) -> Result<KeySpec, String> {
+        let spec = spec.trim();
+        let (prefix, key) = match spec.strip_suffix('+') {
+            Some(rest) if rest.is_empty() || rest.ends_with('+') => {
+                (rest.strip_suffix('+').unwrap_or(""), "+")
+            }
+            _ => spec.rsplit_once('+').unwrap_or(("", spec)),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        let mut shift = false;
+        for part in prefix.split('+').filter(|p| !p.is_empty()) {
+            match part.trim().to_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" | "meta" => modifiers |= KeyModifiers::ALT,
+                "shift" => shift = true,
+                other => return Err(format!("unknown modifier '{}' in '{}'", other, spec)),
+            }
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (None, _) => return Err("empty key".to_string()),
+            (Some(c), None) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" if shift => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key '{}'", key)),
+                },
+            },
+        };
+
+        Ok(KeySpec { code, modifiers })
+    }
+
+    pub fn matches(&self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        self.code == code && self.modifiers == modifiers & MODIFIER_MASK
+    }
+
+    /// The key as the help shows it: `Ctrl+s`, `Space`, `F5`, `N`.
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("Alt+");
+        }
+        match self.code {
+            KeyCode::Char(' ') => label.push_str("Space"),
+            KeyCode::Char(c) => label.push(c),
+            KeyCode::F(n) => label.push_str(&format!("F{}", n)),
+            KeyCode::BackTab => label.push_str("Shift+Tab"),
+            KeyCode::PageUp => label.push_str("PgUp"),
+            KeyCode::PageDown => label.push_str("PgDn"),
+            KeyCode::Up => label.push('↑'),
+            KeyCode::Down => label.push('↓'),
+            KeyCode::Left => label.push('←'),
+            KeyCode::Right => label.push('→'),
+            other => label.push_str(&format!("{:?}", other)),
+        }
+        label
+    }
+}
+
+/// Keys the TUI handles before any binding, so they can't be given to an
+/// action.
+const RESERVED: [KeySpec; 2] = [
+    KeySpec {
+        code: KeyCode::Char('c'),
+        modifiers: KeyModifiers::CONTROL,
+    },
+    KeySpec {
+        code: KeyCode::Esc,
+        modifiers: KeyModifiers::NONE,
+    },
+];
+
+/// The key of every [`Action`], after applying `tui.keybindings`.
+#[derive(Debug, Clone)]
+pub struct Keybindings {
+    keys: HashMap<Action, KeySpec>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            keys: Action::ALL
+                .into_iter()
+                .map(|action| {
+                    let key = KeySpec::parse(action.default_key())
+                        .expect("default keys are valid key specs");
+                    (action, key)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Keybindings {
+    /// The bindings for `config`, plus a message for each entry that was
+    /// ignored, for logging.
+    pub fn from_config(config: &HashMap<String, String>) -> (Self, Vec<String>) {
+        let defaults = Self::default();
+        let mut bindings = defaults.clone();
+        let mut warnings = Vec::new();
+        let mut remapped = Vec::new();
+
+        let mut entries: Vec<_> = config.iter().collect();
+        entries.sort();
+        for (name, spec) in entries {
+            let Some(action) = Action::from_name(name) else {
+                warnings.push(format!("Unknown keybinding action '{}'", name));
+                continue;
+            };
+            match KeySpec::parse(spec) {
+                Ok(key) if RESERVED.contains(&key) => warnings.push(format!(
+                    "Key '{}' for {} is reserved, using the default",
+                    spec, name
+                )),
+                Ok(key) => {
+                    bindings.keys.insert(action, key);
+                    remapped.push(action);
+                }
+                Err(e) => warnings.push(format!(
+                    "Invalid key '{}' for {}: {}, using the default",
+                    spec, name, e
+                )),
+            }
+        }
+
+        // Give clashing remaps their default back until no two actions
+        // share a key. Each pass drops one remap, so this ends.
+        while let Some(action) = remapped.iter().copied().find(|a| {
+            Action::ALL
+                .iter()
+                .any(|b| b != a && bindings.keys[b] == bindings.keys[a])
+        }) {
+            warnings.push(format!(
+                "Key '{}' for {} is already bound, using the default",
+                bindings.label(action),
+                action.name()
+            ));
+            bindings.keys.insert(action, defaults.keys[&action]);
+            remapped.retain(|a| *a != action);
+        }
+
+        (bindings, warnings)
+    }
+
+    /// Whether the key pressed is the one bound to `action`.
+    pub fn is(&self, action: Action, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        self.keys[&action].matches(code, modifiers)
+    }
+
+    pub fn label(&self, action: Action) -> String {
+        self.keys[&action].label()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(spec: &str) -> KeySpec {
+        KeySpec::parse(spec).unwrap()
+    }
+
+    #[test]
+    fn test_parse_key_specs() {
+        assert_eq!(key("x").code, KeyCode::Char('x'));
+        assert_eq!(key("N").code, KeyCode::Char('N'));
+        assert_eq!(key("shift+n"), key("N"));
+        assert_eq!(
+            key("Ctrl+S"),
+            KeySpec {
+                code: KeyCode::Char('S'),
+                modifiers: KeyModifiers::CONTROL
+            }
+        );
+        assert_eq!(key("ctrl+s").modifiers, KeyModifiers::CONTROL);
+        assert_eq!(key("f5").code, KeyCode::F(5));
+        assert_eq!(key("+").code, KeyCode::Char('+'));
+        assert_eq!(key("alt++").code, KeyCode::Char('+'));
+        assert_eq!(key("space").code, KeyCode::Char(' '));
+        assert_eq!(key("shift+tab").code, KeyCode::BackTab);
+        for bad in ["", "f13", "hyper+x", "spacebar"] {
+            assert!(KeySpec::parse(bad).is_err(), "{:?} should be rejected", bad);
+        }
+
+        assert_eq!(key("ctrl+s").label(), "Ctrl+s");
+        assert_eq!(key("space").label(), "Space");
+        for action in Action::ALL {
+            assert_eq!(key(action.default_key()).label(), action.default_key());
+        }
+    }
+
+    #[test]
+    fn test_remap_replaces_default_key() {
+        let config = HashMap::from([
+            ("timer.stop".to_string(), "s".to_string()),
+            ("task.new".to_string(), "ctrl+n".to_string()),
+        ]);
+        let (bindings, warnings) = Keybindings::from_config(&config);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        let none = KeyModifiers::NONE;
+        assert!(bindings.is(Action::TimerStop, KeyCode::Char('s'), none));
+        assert!(!bindings.is(Action::TimerStop, KeyCode::Char('x'), none));
+        assert!(bindings.is(Action::TaskNew, KeyCode::Char('n'), KeyModifiers::CONTROL));
+        assert!(!bindings.is(Action::TaskNew, KeyCode::Char('n'), none));
+        assert!(bindings.is(
+            Action::TaskQuickAdd,
+            KeyCode::Char('N'),
+            KeyModifiers::SHIFT
+        ));
+        assert_eq!(bindings.label(Action::TimerStop), "s");
+    }
+
+    #[test]
+    fn test_bad_entries_keep_defaults() {
+        let config = HashMap::from([
+            ("timer.explode".to_string(), "z".to_string()),
+            ("timer.pause".to_string(), "ctrl+c".to_string()),
+            ("task.edit".to_string(), "hyper+e".to_string()),
+            ("task.delete".to_string(), "x".to_string()),
+            ("task.archive".to_string(), "D".to_string()),
+        ]);
+        let (bindings, warnings) = Keybindings::from_config(&config);
+        assert_eq!(warnings.len(), 4, "{:?}", warnings);
+        assert_eq!(bindings.label(Action::TimerPause), "Space");
+        assert_eq!(bindings.label(Action::TaskEdit), "e");
+        assert_eq!(bindings.label(Action::TaskDelete), "d");
+        assert_eq!(bindings.label(Action::TimerStop), "x");
+        assert_eq!(bindings.label(Action::TaskArchive), "D");
+    }
+}
@@ -0,0 +1,617 @@
+//! Keybinding registry and the help modal built from it.
+//!
+//! Every Normal-mode binding is described once here, grouped by view. The
+//! help modal renders this table directly, so documenting a new key means
+//! adding a `Binding` next to the handler's section rather than editing
+//! free-form help text. Bindings of a remappable [`Action`] show the key
+//! configured in `tui.keybindings`.
+
+use crate::app::AppView;
+use crate::keybindings::{Action, Keybindings};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding {
+    pub keys: &'static str,
+    pub action: &'static str,
+    /// The action whose configured key replaces `keys` in the help.
+    pub remap: Option<Action>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct KeymapSection {
+    pub title: &'static str,
+    pub bindings: &'static [Binding],
+}
+
+const fn bind(keys: &'static str, action: &'static str) -> Binding {
+    Binding {
+        keys,
+        action,
+        remap: None,
+    }
+}
+
+const fn bind_action(remap: Action, action: &'static str) -> Binding {
+    Binding {
+        keys: remap.default_key(),
+        action,
+        remap: Some(remap),
+    }
+}
+
+const GLOBAL: KeymapSection = KeymapSection {
+    title: "GLOBAL",
+    bindings: &[
+        bind_action(Action::ViewDashboard, "Dashboard (📊)"),
+        bind_action(Action::ViewTimers, "Active Timers (⏱️)"),
+        bind_action(Action::ViewKanban, "Kanban Board (📋)"),
+        bind_action(Action::ViewEntries, "Entries Log (📝)"),
+        bind_action(Action::ViewReports, "Reports (📈)"),
+        bind_action(Action::ViewSettings, "Settings (⚙️)"),
+        bind_action(Action::ViewLogs, "System Logs (📋)"),
+        bind("Ctrl+|", "Split view: show a second view beside this one"),
+        bind("Alt+1..6", "Choose the split view's right-hand view"),
+        bind("Ctrl+w", "Split view: focus the other side"),
+        bind("N<key>", "Repeat a move N times, e.g. 5j, 3>"),
+        bind(".", "Repeat the last move"),
+        bind("PgUp / PgDn", "Page through the current list"),
+        bind_action(
+            Action::TimerRepeat,
+            "Repeat the last timer started (type, length, task)",
+        ),
+        bind_action(Action::Help, "Toggle this help"),
+        bind_action(Action::Quit, "Quit MooTimer"),
+        bind("Esc", "Quit MooTimer"),
+        bind("Ctrl+c", "Quit immediately"),
+    ],
+};
+
+const DASHBOARD_TIMER: KeymapSection = KeymapSection {
+    title: "DASHBOARD - TIMER (focus with Ctrl+w)",
+    bindings: &[
+        bind("Ctrl+w", "Cycle focus: Timer → Tasks → Profiles"),
+        bind(
+            "Space / Enter",
+            "Start timer, or activate the selected button",
+        ),
+        bind(
+            "h/l / Tab",
+            "Select Pause, Stop or Skip button while running",
+        ),
+        bind_action(Action::TimerStop, "Stop and save timer entry"),
+        bind("t / T", "Cycle timer type (Manual → Pomodoro → Countdown)"),
+        bind(
+            "↑↓ / j/k / >/<",
+            "Adjust duration (Pomodoro/Countdown, when idle)",
+        ),
+        bind(
+            "↑↓ / j/k",
+            "Switch between concurrent timers (while running)",
+        ),
+        bind_action(
+            Action::TimerStartAnother,
+            "Start another timer alongside the running ones",
+        ),
+        bind("A", "Toggle pomodoro auto-start after breaks"),
+        bind("p", "Pick a countdown preset (1-9 to start)"),
+        bind("k", "Skip the current pomodoro break (during breaks)"),
+        bind("r", "Refresh everything"),
+        bind("m", "Moo! (🐮)"),
+        bind("M", "Show running timers across all profiles"),
+        bind("*", "Swap this column for every profile's timer and today"),
+    ],
+};
+
+const DASHBOARD_FLEET: KeymapSection = KeymapSection {
+    title: "DASHBOARD - PROFILES OVERVIEW (toggle with *)",
+    bindings: &[
+        bind("↑↓ / j/k", "Select a profile"),
+        bind("Enter", "Switch to the selected profile"),
+        bind("r", "Refresh now"),
+        bind("*", "Back to this profile's timer"),
+    ],
+};
+
+const DASHBOARD_MULTI: KeymapSection = KeymapSection {
+    title: "DASHBOARD - ALL PROFILES (toggle with M)",
+    bindings: &[
+        bind("h/l / ←→", "Select a profile's timer"),
+        bind("Space / Enter", "Pause or resume the selected timer"),
+        bind("r", "Refresh the timers"),
+        bind("M", "Back to this profile's Dashboard"),
+    ],
+};
+
+const DASHBOARD_TASKS: KeymapSection = KeymapSection {
+    title: "DASHBOARD - TASKS (focus with Ctrl+w)",
+    bindings: &[
+        bind("↑↓ / j/k", "Navigate tasks"),
+        bind("g / G", "Jump to top / bottom"),
+        bind_action(Action::TimerPause, "Start timer on task, or pause / resume"),
+        bind_action(
+            Action::TimerStartAnother,
+            "Start another timer on task, alongside running ones",
+        ),
+        bind(
+            "Enter / i",
+            "Open task details (description, tags, priority, due)",
+        ),
+        bind_action(Action::TimerStop, "Stop and save timer entry"),
+        bind_action(Action::TaskNew, "Create new task (title + description)"),
+        bind_action(Action::TaskQuickAdd, "Quick add task (title only)"),
+        bind_action(Action::TaskEdit, "Edit selected task"),
+        bind("p", "Pin / unpin task (pinned tasks are listed first)"),
+        bind("Y / P", "Yank task / paste a copy of it as a new todo"),
+        bind_action(Action::TaskDelete, "Delete selected task"),
+        bind_action(Action::TaskArchive, "Archive / restore selected task"),
+        bind("A", "Toggle view: active vs. archived tasks"),
+        bind("D", "Toggle view: tasks by due date, overdue in red"),
+        bind("v", "Toggle task descriptions"),
+        bind_action(Action::TaskSearch, "Search tasks"),
+        bind_action(Action::TaskMove, "Move task to another profile"),
+        bind("V", "Multi-select: Space marks, t / u tags / untags marked"),
+        bind("r", "Refresh everything"),
+    ],
+};
+
+const DASHBOARD_PROFILES: KeymapSection = KeymapSection {
+    title: "DASHBOARD - PROFILES (focus with Ctrl+w)",
+    bindings: &[
+        bind("↑↓ / j/k", "Navigate profiles"),
+        bind("Enter / s", "Switch to selected profile"),
+        bind("n", "Create new profile"),
+        bind("d", "Delete selected profile"),
+        bind("r", "Rename selected profile"),
+        bind("m", "Moo! (🐮)"),
+    ],
+};
+
+const TIMERS: KeymapSection = KeymapSection {
+    title: "ACTIVE TIMERS",
+    bindings: &[
+        bind("↑↓ / j/k", "Navigate timers"),
+        bind("g / G", "Jump to top / bottom"),
+        bind_action(Action::TimerPause, "Pause / resume selected timer"),
+        bind_action(Action::TimerStop, "Stop selected timer"),
+        bind("r", "Refresh timers"),
+        bind("m", "Moo! (🐮)"),
+    ],
+};
+
+const KANBAN: KeymapSection = KeymapSection {
+    title: "KANBAN BOARD",
+    bindings: &[
+        bind("h/l / ←→", "Switch column (To Do / In Progress / Done)"),
+        bind("j/k / ↑↓", "Navigate cards in column"),
+        bind("H/L", "Move card to adjacent column"),
+        bind("J/K", "Move card down / up within its column"),
+        bind("Space", "Start timer on selected card"),
+        bind_action(
+            Action::TimerStartAnother,
+            "Start another timer on card, alongside running ones",
+        ),
+        bind(
+            "Enter / i",
+            "Open card details (description, tags, priority, due)",
+        ),
+        bind("Shift+Enter", "Move all cards from one status to another"),
+        bind_action(Action::TaskNew, "Create new task (title + description)"),
+        bind_action(Action::TaskQuickAdd, "Quick add task (title only)"),
+        bind_action(Action::TaskEdit, "Edit selected card"),
+        bind_action(Action::TaskDelete, "Delete selected card"),
+        bind_action(Action::TaskArchive, "Archive / restore selected card"),
+        bind("A", "Toggle view: active vs. archived cards"),
+        bind("v", "Toggle card descriptions"),
+        bind_action(Action::TaskMove, "Move card to another profile"),
+    ],
+};
+
+const ENTRIES: KeymapSection = KeymapSection {
+    title: "ENTRIES LOG",
+    bindings: &[
+        bind("↑↓ / j/k", "Navigate entries"),
+        bind("g / G", "Jump to top / bottom"),
+        bind(
+            "T / W / M",
+            "Show one day / week / month (T jumps to today)",
+        ),
+        bind("[ / ]", "Previous / next day, week or month"),
+        bind("D", "Go to a date: 2024-06-11, yesterday, last tue"),
+        bind("f", "Filter by text"),
+        bind("n", "Add a manual entry"),
+        bind("L", "Log time that just ended: 45m fix login bug"),
+        bind("e", "Edit selected entry duration"),
+        bind(
+            "E",
+            "Edit start/end: [ ] ±1m, { } ±5m, N[ for N minutes, Tab switches",
+        ),
+        bind("d", "Delete selected entry"),
+        bind("m", "Move or copy selected entry to another profile"),
+        bind("r", "Refresh entries"),
+    ],
+};
+
+const REPORTS: KeymapSection = KeymapSection {
+    title: "REPORTS",
+    bindings: &[
+        bind("d / w / m", "Report period: daily / weekly / monthly"),
+        bind("p", "Toggle all profiles vs. current profile"),
+        bind("v", "Break down by task or by device"),
+        bind("V", "Compare with another period"),
+        bind("H", "Average session by hour of day"),
+        bind("E", "Export the task breakdown as CSV"),
+        bind("r", "Refresh report data"),
+    ],
+};
+
+const SETTINGS: KeymapSection = KeymapSection {
+    title: "SETTINGS",
+    bindings: &[
+        bind("↑↓ / j/k", "Navigate settings"),
+        bind("Space / Enter", "Toggle, edit or run the selected setting"),
+        bind("h/l / ←→", "Decrease / increase or cycle the value"),
+        bind("B", "Pick a sync branch to check out"),
+        bind("E / I", "Export the config to a file / import it from one"),
+        bind(
+            "Pending Changes",
+            "Uncommitted changes; [F] full diff, j/k scroll",
+        ),
+        bind(
+            "Conflicts",
+            "[l]ocal / [r]emote per record; Tab, Space, [m] to merge fields",
+        ),
+    ],
+};
+
+const LOGS: KeymapSection = KeymapSection {
+    title: "SYSTEM LOGS",
+    bindings: &[
+        bind("↑↓ / j/k", "Navigate log lines"),
+        bind("g / G", "Jump to top / bottom"),
+        bind("Enter", "Show every field of the selected line"),
+        bind("t", "Filter by target, cycling through them"),
+        bind("s / l / o", "Show or hide timestamps / levels / targets"),
+        bind("r", "Refresh logs"),
+        bind("c", "Clear the daemon log"),
+        bind("m", "Moo! (🐮)"),
+    ],
+};
+
+const HELP: KeymapSection = KeymapSection {
+    title: "THIS HELP",
+    bindings: &[
+        bind("/", "Search; Enter keeps the filter, Esc clears it"),
+        bind("n / N", "Jump to next / previous match"),
+        bind("↑↓ / j/k", "Scroll"),
+        bind("PgUp / PgDn", "Scroll a page"),
+        bind("g / G", "Jump to top / bottom"),
+        bind("q / Esc / ?", "Close"),
+    ],
+};
+
+const TASK_DETAIL: KeymapSection = KeymapSection {
+    title: "TASK DETAILS (Enter on a task)",
+    bindings: &[
+        bind("Tab / Shift+Tab", "Next / previous field"),
+        bind("Enter", "New line in description, otherwise save"),
+        bind("Ctrl+s", "Save"),
+        bind("←→ / Space", "Cycle priority"),
+        bind("Ctrl+u", "Clear the current line"),
+        bind("Esc", "Cancel"),
+    ],
+};
+
+/// The sections documenting a view's own keys. The match is exhaustive so a
+/// new view cannot be added without deciding what its help says.
+pub fn view_sections(view: AppView) -> &'static [KeymapSection] {
+    match view {
+        AppView::Dashboard => &[
+            DASHBOARD_TIMER,
+            DASHBOARD_TASKS,
+            DASHBOARD_PROFILES,
+            DASHBOARD_MULTI,
+            DASHBOARD_FLEET,
+        ],
+        AppView::Timers => &[TIMERS],
+        AppView::Kanban => &[KANBAN],
+        AppView::Entries => &[ENTRIES],
+        AppView::Reports => &[REPORTS],
+        AppView::Settings => &[SETTINGS],
+        AppView::Logs => &[LOGS],
+    }
+}
+
+const VIEWS: [AppView; 7] = [
+    AppView::Dashboard,
+    AppView::Timers,
+    AppView::Kanban,
+    AppView::Entries,
+    AppView::Reports,
+    AppView::Settings,
+    AppView::Logs,
+];
+
+/// All sections in display order: global keys, then each view in tab
+/// order, then the keys of the task detail panel and of the help modal.
+pub fn sections() -> Vec<KeymapSection> {
+    std::iter::once(GLOBAL)
+        .chain(
+            VIEWS
+                .iter()
+                .flat_map(|view| view_sections(*view).iter().copied()),
+        )
+        .chain([TASK_DETAIL, HELP])
+        .collect()
+}
+
+const REMAP_TITLE: &str = "REMAPPABLE ACTIONS (tui.keybindings)";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HelpLine {
+    Header(&'static str),
+    Binding { keys: String, action: &'static str },
+    Blank,
+}
+
+impl HelpLine {
+    fn matches(&self, needle: &str) -> bool {
+        match self {
+            HelpLine::Binding { keys, action } => {
+                keys.to_lowercase().contains(needle) || action.to_lowercase().contains(needle)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Binding {
+    fn line(&self, keybindings: &Keybindings) -> HelpLine {
+        HelpLine::Binding {
+            keys: self
+                .remap
+                .map_or_else(|| self.keys.to_string(), |a| keybindings.label(a)),
+            action: self.action,
+        }
+    }
+}
+
+/// The help lines to show for `query`, with the keys set in
+/// `keybindings`. An empty query lists everything; otherwise only matching
+/// bindings are kept, under their section header. A header that matches
+/// keeps its whole section.
+pub fn help_lines(query: &str, keybindings: &Keybindings) -> Vec<HelpLine> {
+    let needle = query.trim().to_lowercase();
+    let mut lines = Vec::new();
+
+    for section in sections() {
+        let bindings = section.bindings.iter().map(|b| b.line(keybindings));
+        push_section(&mut lines, section.title, bindings, &needle);
+    }
+    let actions = Action::ALL.iter().map(|a| HelpLine::Binding {
+        keys: keybindings.label(*a),
+        action: a.name(),
+    });
+    push_section(&mut lines, REMAP_TITLE, actions, &needle);
+
+    lines
+}
+
+fn push_section(
+    lines: &mut Vec<HelpLine>,
+    title: &'static str,
+    bindings: impl Iterator<Item = HelpLine>,
+    needle: &str,
+) {
+    let title_matches = title.to_lowercase().contains(needle);
+    let bindings: Vec<HelpLine> = bindings
+        .filter(|line| title_matches || line.matches(needle))
+        .collect();
+    if bindings.is_empty() {
+        return;
+    }
+    if !lines.is_empty() {
+        lines.push(HelpLine::Blank);
+    }
+    lines.push(HelpLine::Header(title));
+    lines.extend(bindings);
+}
+
+/// Search and scroll state of the help modal.
+#[derive(Debug, Default)]
+pub struct HelpState {
+    pub query: String,
+    pub searching: bool,
+    pub scroll: usize,
+    /// Index into `match_rows()` of the highlighted match.
+    pub current_match: Option<usize>,
+    pub keybindings: Keybindings,
+}
+
+impl HelpState {
+    pub fn new(keybindings: Keybindings) -> Self {
+        Self {
+            keybindings,
+            ..Self::default()
+        }
+    }
+
+    pub fn lines(&self) -> Vec<HelpLine> {
+        help_lines(&self.query, &self.keybindings)
+    }
+
+    /// Rows holding bindings that match the query; empty when not filtering.
+    pub fn match_rows(&self) -> Vec<usize> {
+        if self.query.trim().is_empty() {
+            return Vec::new();
+        }
+        self.lines()
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| matches!(line, HelpLine::Binding { .. }))
+            .map(|(row, _)| row)
+            .collect()
+    }
+
+    pub fn current_row(&self) -> Option<usize> {
+        let index = self.current_match?;
+        self.match_rows().get(index).copied()
+    }
+
+    pub fn start_search(&mut self) {
+        self.searching = true;
+        self.query.clear();
+        self.refilter();
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.query.push(c);
+        self.refilter();
+    }
+
+    pub fn pop_char(&mut self) {
+        self.query.pop();
+        self.refilter();
+    }
+
+    pub fn clear_search(&mut self) {
+        self.searching = false;
+        self.query.clear();
+        self.refilter();
+    }
+
+    /// Moves to the next (`forward`) or previous match, wrapping around, and
+    /// scrolls it into a viewport of `height` rows.
+    pub fn jump(&mut self, forward: bool, height: usize) {
+        let count = self.match_rows().len();
+        if count == 0 {
+            return;
+        }
+        let next = match (self.current_match, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+        };
+        self.current_match = Some(next);
+        if let Some(row) = self.current_row() {
+            if row < self.scroll {
+                self.scroll = row;
+            } else if height > 0 && row >= self.scroll + height {
+                self.scroll = row + 1 - height;
+            }
+        }
+        self.clamp_scroll(height);
+    }
+
+    pub fn scroll_by(&mut self, delta: isize, height: usize) {
+        self.scroll = self.scroll.saturating_add_signed(delta);
+        self.clamp_scroll(height);
+    }
+
+    pub fn scroll_to_end(&mut self, height: usize) {
+        self.scroll = usize::MAX;
+        self.clamp_scroll(height);
+    }
+
+    fn clamp_scroll(&mut self, height: usize) {
+        let max = self.lines().len().saturating_sub(height);
+        self.scroll = self.scroll.min(max);
+    }
+
+    fn refilter(&mut self) {
+        self.scroll = 0;
+        self.current_match = if self.match_rows().is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_every_view_is_documented() {
+        for view in VIEWS {
+            let sections = view_sections(view);
+            assert!(!sections.is_empty(), "{:?} has no help section", view);
+            assert!(sections.iter().all(|s| !s.bindings.is_empty()));
+        }
+    }
+
+    #[test]
+    fn test_search_filters_and_keeps_headers() {
+        let keys = Keybindings::default();
+        let all = help_lines("", &keys);
+        assert!(all.contains(&HelpLine::Header("KANBAN BOARD")));
+
+        let lines = help_lines("ARCHIVE", &keys);
+        assert!(lines.contains(&HelpLine::Header("DASHBOARD - TASKS (focus with Ctrl+w)")));
+        assert!(lines.contains(&HelpLine::Header("KANBAN BOARD")));
+        assert!(!lines.contains(&HelpLine::Header("REPORTS")));
+        assert!(lines.iter().all(|line| match line {
+            HelpLine::Binding { .. } => line.matches("archive"),
+            _ => true,
+        }));
+
+        // The section, the Reports tab key and the `view.reports` action.
+        let section = help_lines("reports", &keys);
+        let bindings = section
+            .iter()
+            .filter(|l| matches!(l, HelpLine::Binding { .. }))
+            .count();
+        assert_eq!(bindings, REPORTS.bindings.len() + 2);
+
+        assert!(help_lines("no such binding", &keys).is_empty());
+    }
+
+    #[test]
+    fn test_help_shows_remapped_keys() {
+        let config = HashMap::from([("timer.stop".to_string(), "ctrl+s".to_string())]);
+        let (keys, _) = Keybindings::from_config(&config);
+        let stop = |keys: &str| HelpLine::Binding {
+            keys: keys.to_string(),
+            action: "Stop selected timer",
+        };
+
+        let lines = help_lines("stop", &keys);
+        assert!(lines.contains(&stop("Ctrl+s")));
+        assert!(!lines.contains(&stop("x")));
+        assert!(lines.contains(&HelpLine::Binding {
+            keys: "Ctrl+s".to_string(),
+            action: "timer.stop",
+        }));
+    }
+
+    #[test]
+    fn test_match_navigation_wraps_and_scrolls() {
+        let mut state = HelpState::default();
+        state.start_search();
+        for c in "refresh".chars() {
+            state.push_char(c);
+        }
+        let rows = state.match_rows();
+        assert!(rows.len() > 3);
+        assert_eq!(state.current_match, Some(0));
+
+        state.jump(false, 4);
+        assert_eq!(state.current_row(), rows.last().copied());
+        let row = state.current_row().unwrap();
+        assert!(state.scroll <= row && row < state.scroll + 4);
+
+        state.jump(true, 4);
+        assert_eq!(state.current_match, Some(0));
+        let row = state.current_row().unwrap();
+        assert!(state.scroll <= row && row < state.scroll + 4);
+
+        state.clear_search();
+        assert_eq!(state.current_match, None);
+        state.scroll_to_end(10);
+        assert_eq!(state.scroll, state.lines().len() - 10);
+        state.scroll_by(-3, 10);
+        assert_eq!(state.scroll, state.lines().len() - 13);
+    }
+}
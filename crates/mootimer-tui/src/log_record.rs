@@ -0,0 +1,210 @@
+//! Parsing the daemon's log lines for the Logs view.
+//!
+//! `mootimerd` writes either tracing's text format or, with
+//! `--log-format json`, one JSON object per line. Both are split into the
+//! same columns; anything else is kept as a bare message.
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ERROR" => Some(Self::Error),
+            "WARN" => Some(Self::Warn),
+            "INFO" => Some(Self::Info),
+            "DEBUG" => Some(Self::Debug),
+            "TRACE" => Some(Self::Trace),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+            Self::Trace => "TRACE",
+        }
+    }
+}
+
+/// Which optional columns the Logs view shows. The message always is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogColumns {
+    pub timestamp: bool,
+    pub level: bool,
+    pub target: bool,
+}
+
+impl Default for LogColumns {
+    fn default() -> Self {
+        Self {
+            timestamp: true,
+            level: true,
+            target: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub timestamp: Option<String>,
+    pub level: Option<LogLevel>,
+    /// Module the event came from, e.g. `mootimer_daemon::sync`.
+    pub target: Option<String>,
+    pub message: String,
+    /// Structured fields other than the message: the event's own first,
+    /// then span data.
+    pub fields: Vec<(String, String)>,
+    pub raw: String,
+}
+
+impl LogRecord {
+    pub fn parse(line: &str) -> Self {
+        Self::parse_json(line)
+            .or_else(|| Self::parse_text(line))
+            .unwrap_or_else(|| Self::plain(line))
+    }
+
+    pub fn plain(line: &str) -> Self {
+        Self {
+            timestamp: None,
+            level: None,
+            target: None,
+            message: line.to_string(),
+            fields: Vec::new(),
+            raw: line.to_string(),
+        }
+    }
+
+    /// Whether the record comes from `target` or a module inside it.
+    pub fn in_target(&self, target: &str) -> bool {
+        self.target.as_deref().is_some_and(|t| {
+            t == target
+                || t.strip_prefix(target)
+                    .is_some_and(|rest| rest.starts_with("::"))
+        })
+    }
+
+    fn parse_json(line: &str) -> Option<Self> {
+        let Value::Object(mut record) = serde_json::from_str(line).ok()? else {
+            return None;
+        };
+        let mut fields = match record.remove("fields") {
+            Some(Value::Object(fields)) => fields,
+            _ => Default::default(),
+        };
+        let message = fields
+            .remove("message")
+            .map(|m| display_value(&m))
+            .unwrap_or_default();
+        let timestamp = record
+            .remove("timestamp")
+            .and_then(|v| v.as_str().map(String::from));
+        let level = record
+            .remove("level")
+            .and_then(|v| v.as_str().and_then(LogLevel::parse));
+        let target = record
+            .remove("target")
+            .and_then(|v| v.as_str().map(String::from));
+
+        let fields = fields
+            .iter()
+            .chain(record.iter())
+            .map(|(k, v)| (k.clone(), display_value(v)))
+            .collect();
+
+        Some(Self {
+            timestamp,
+            level,
+            target,
+            message,
+            fields,
+            raw: line.to_string(),
+        })
+    }
+
+    /// `<timestamp> <LEVEL> [span{..}: ]<target>: <message>`, as written by
+    /// tracing's default formatter.
+    fn parse_text(line: &str) -> Option<Self> {
+        let (timestamp, rest) = line.split_once(' ')?;
+        if !timestamp.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let rest = rest.trim_start();
+        let (level, mut rest) = rest.split_once(' ')?;
+        let level = LogLevel::parse(level)?;
+
+        let mut target = None;
+        while let Some((head, tail)) = rest.split_once(": ") {
+            if head.contains('{') {
+                rest = tail;
+                continue;
+            }
+            if is_module_path(head) {
+                target = Some(head.to_string());
+                rest = tail;
+            }
+            break;
+        }
+
+        Some(Self {
+            timestamp: Some(timestamp.to_string()),
+            level: Some(level),
+            target,
+            message: rest.to_string(),
+            fields: Vec::new(),
+            raw: line.to_string(),
+        })
+    }
+}
+
+fn is_module_path(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_and_text_lines() {
+        let record = LogRecord::parse(
+            r#"{"timestamp":"2024-05-01T14:02:03.123Z","level":"WARN","fields":{"message":"Failed to auto-commit","files":3},"target":"mootimer_daemon::sync"}"#,
+        );
+        assert_eq!(record.level, Some(LogLevel::Warn));
+        assert_eq!(record.target.as_deref(), Some("mootimer_daemon::sync"));
+        assert_eq!(record.message, "Failed to auto-commit");
+        assert_eq!(record.fields, vec![("files".to_string(), "3".to_string())]);
+
+        let record = LogRecord::parse(
+            "2024-05-01T14:02:03.123456Z  INFO mootimer_daemon::api: Saved entry with ERROR in its title",
+        );
+        assert_eq!(record.level, Some(LogLevel::Info));
+        assert_eq!(record.target.as_deref(), Some("mootimer_daemon::api"));
+        assert_eq!(record.message, "Saved entry with ERROR in its title");
+
+        let record = LogRecord::parse("Log file not found");
+        assert_eq!(record.level, None);
+        assert_eq!(record.message, "Log file not found");
+    }
+}
@@ -1,24 +1,45 @@
+mod animation;
 mod app;
+mod confirm;
+mod entry_range;
+mod input;
+mod keybindings;
+mod keymap;
+mod log_record;
+mod settings;
+mod sync_progress;
+mod task_detail;
+mod terminal_title;
+mod timer_clock;
 mod ui;
+mod wizard;
 
 use anyhow::Result;
 use app::{App, AppView, DashboardPane, InputMode};
 use clap::Parser;
 use crossterm::{
     event::{
-        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyEventKind, KeyModifiers,
     },
     execute,
     terminal::{
         EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode, size,
     },
 };
+use entry_range::Granularity;
+use keybindings::Action;
 use mootimer_client::MooTimerClient;
+use mootimer_client::daemon::{SpawnOptions, ensure_daemon};
+use mootimer_core::models::TaskStatus;
+use mootimer_notify::{send_os_notification, send_urgent_notification};
 use ratatui::{Terminal, backend::CrosstermBackend};
 use serde_json::json;
 use std::io;
+use terminal_title::TerminalTitle;
 use tokio::time::Duration;
 use tracing::info;
+use ui::helpers::format_duration_hm;
 
 fn setup_logging() -> Result<()> {
     let mut log_path = std::env::temp_dir();
@@ -38,7 +59,12 @@ fn setup_panic_hook() {
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
         let _ = disable_raw_mode();
-        let _ = execute!(std::io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+        let _ = execute!(
+            std::io::stdout(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableBracketedPaste
+        );
         let _ = crossterm::execute!(std::io::stdout(), crossterm::cursor::Show);
 
         tracing::error!(?panic_info, "Application panicked");
@@ -58,34 +84,29 @@ struct Args {
 
     #[arg(short, long)]
     profile: Option<String>,
-}
 
-fn send_os_notification(title: &str, body: &str) {
-    if let Err(e) = notify_rust::Notification::new()
-        .summary(title)
-        .body(body)
-        .icon("clock")
-        .timeout(notify_rust::Timeout::Milliseconds(5000))
-        .show()
-    {
-        tracing::error!("Failed to send notification: {}", e);
-    }
-}
+    /// Show the running timer in the terminal title, e.g. "🍅 17:32 — Fix login bug".
+    #[arg(long)]
+    title: bool,
 
-fn send_urgent_notification(title: &str, body: &str) {
-    let mut notification = notify_rust::Notification::new();
-    notification
-        .summary(title)
-        .body(body)
-        .icon("alarm-clock")
-        .timeout(notify_rust::Timeout::Milliseconds(10000));
+    /// Print a one-line timer summary and exit, for tmux's status-right.
+    /// Prints nothing when no timer is active.
+    #[arg(long)]
+    status: bool,
 
-    #[cfg(all(unix, not(target_os = "macos")))]
-    notification.urgency(notify_rust::Urgency::Critical);
+    /// Template for --status; placeholders: {icon} {time} {task} {phase} {state}.
+    #[arg(long, requires = "status")]
+    status_format: Option<String>,
+}
 
-    if let Err(e) = notification.show() {
-        tracing::error!("Failed to send urgent notification: {}", e);
-    }
+/// `--status`: print `status.summary` for tmux and friends. Never starts the
+/// daemon, so a status bar polling every few seconds stays cheap.
+async fn print_status(client: &MooTimerClient, args: &Args) -> Result<()> {
+    let summary = client
+        .status_summary(args.profile.as_deref(), args.status_format.as_deref())
+        .await?;
+    println!("{}", summary["text"].as_str().unwrap_or_default());
+    Ok(())
 }
 
 fn audio_alert(app: &App) {
@@ -118,16 +139,22 @@ async fn handle_daemon_notification(
                             app.status_message = "⚠️  5 minutes remaining!".to_string();
                             audio_alert(app);
                             send_os_notification(
-                                "⏰ 5 Minutes Left",
-                                "Your countdown timer is almost complete!",
+                                &app.message("timer.five_minutes_left.title", &[]),
+                                &app.message("timer.five_minutes_left.body", &[]),
                             );
                         }
 
                         app.refresh_timer().await?;
+                        if let Some(timer_id) =
+                            notification.params.get("timer_id").and_then(|v| v.as_str())
+                        {
+                            app.sync_timer_clock(Some(timer_id));
+                        }
                     }
                     "started" => {
                         app.status_message = "Timer started".to_string();
                         app.five_min_warning_shown = false;
+                        app.timer_clock = None;
                         app.refresh_timer().await?;
                     }
                     "stopped" => {
@@ -138,18 +165,40 @@ async fn handle_daemon_notification(
                         app.refresh_stats().await?;
                         app.refresh_entries().await?;
                     }
+                    "session_capped" => {
+                        let profile_id = notification
+                            .params
+                            .get("profile_id")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string();
+                        app.open_capped_session(&profile_id, event_type);
+                    }
                     "paused" => {
                         app.status_message = "Timer paused".to_string();
                         app.refresh_timer().await?;
                     }
                     "resumed" => {
                         app.status_message = "Timer resumed".to_string();
+                        app.timer_clock = None;
                         app.refresh_timer().await?;
                     }
                     "cancelled" => {
                         app.status_message = "Timer cancelled".to_string();
                         app.refresh_timer().await?;
                     }
+                    "clock_jumped" => {
+                        let skew = event_type_obj
+                            .get("skew_seconds")
+                            .and_then(|v| v.as_i64())
+                            .unwrap_or_default();
+                        app.status_message = format!(
+                            "⚠️  System clock changed by {}m; timer kept real time",
+                            skew / 60
+                        );
+                        app.timer_clock = None;
+                        app.refresh_timer().await?;
+                    }
                     "countdown_completed" => {
                         app.status_message = "🔔 COUNTDOWN COMPLETED! 🔔".to_string();
 
@@ -160,8 +209,8 @@ async fn handle_daemon_notification(
                         audio_alert(app);
 
                         send_urgent_notification(
-                            "⏰ Countdown Complete!",
-                            "Your countdown timer has finished.",
+                            &app.message("timer.countdown_completed.title", &[]),
+                            &app.message("timer.countdown_completed.body", &[]),
                         );
 
                         app.refresh_timer().await?;
@@ -175,17 +224,22 @@ async fn handle_daemon_notification(
 
                             audio_alert(app);
 
-                            if phase == "short_break" || phase == "long_break" {
+                            if (phase == "short_break" || phase == "long_break")
+                                && !app.auto_start_enabled()
+                            {
                                 app.input_mode = InputMode::PomodoroBreakFinished;
                             }
 
-                            let (title, body) = match phase {
-                                "work" => ("🍅 Work Complete!", "Time for a break!"),
-                                "short_break" => ("☕ Break Over", "Ready to focus again?"),
-                                "long_break" => ("🎉 Long Break Over", "Let's get back to work!"),
-                                _ => ("🍅 Pomodoro", "Phase completed"),
+                            let key = match phase {
+                                "work" => "phase.work_completed",
+                                "short_break" => "phase.short_break_completed",
+                                "long_break" => "phase.long_break_completed",
+                                _ => "phase.completed",
                             };
-                            send_os_notification(title, body);
+                            send_os_notification(
+                                &app.message(&format!("{}.title", key), &[]),
+                                &app.message(&format!("{}.body", key), &[]),
+                            );
                         }
                         app.refresh_timer().await?;
                     }
@@ -195,14 +249,21 @@ async fn handle_daemon_notification(
                             app.status_message = format!("🍅 Starting {} phase", phase);
 
                             if phase == "short_break" || phase == "long_break" {
-                                let (title, body) = if phase == "short_break" {
-                                    ("☕ Short Break", "Take a quick 5-minute break!")
-                                } else {
-                                    ("🎉 Long Break!", "You've earned a longer break!")
-                                };
-                                send_os_notification(title, body);
+                                let minutes = app
+                                    .config
+                                    .as_ref()
+                                    .and_then(|c| c.pointer(&format!("/pomodoro/{}", phase)))
+                                    .and_then(|v| v.as_u64())
+                                    .map(|seconds| (seconds / 60).to_string())
+                                    .unwrap_or_default();
+                                let args = [("minutes", minutes.as_str())];
+                                send_os_notification(
+                                    &app.message(&format!("phase.{}_started.title", phase), &args),
+                                    &app.message(&format!("phase.{}_started.body", phase), &args),
+                                );
                             }
                         }
+                        app.timer_clock = None;
                         app.refresh_timer().await?;
                     }
                     _ => {}
@@ -224,9 +285,26 @@ async fn handle_daemon_notification(
                     "deleted" => {
                         app.status_message = "Task deleted".to_string();
                     }
+                    "bulk_updated" => {
+                        let count = event_type_obj
+                            .get("task_ids")
+                            .and_then(|v| v.as_array())
+                            .map_or(0, |ids| ids.len());
+                        app.status_message = format!("{} tasks updated", count);
+                    }
+                    "unblocked" => {
+                        let title = notification
+                            .params
+                            .get("task")
+                            .and_then(|t| t.get("title"))
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("Task");
+                        app.status_message = format!("🔓 {} is no longer blocked", title);
+                    }
                     _ => {}
                 }
                 app.refresh_tasks().await?;
+                app.refresh_active_task().await;
             }
         }
         "entry.event" => {
@@ -253,6 +331,76 @@ async fn handle_daemon_notification(
                 app.refresh_profiles().await?;
             }
         }
+        "config.event" => {
+            if let Some(event_type) = notification.params.get("event_type")
+                && let Some(event_type_obj) = event_type.as_object()
+                && let Some(type_str) = event_type_obj.get("type").and_then(|v| v.as_str())
+            {
+                match type_str {
+                    "reloaded" => {
+                        app.status_message = "Config reloaded from disk".to_string();
+                    }
+                    "overwritten" => {
+                        app.status_message = event_type_obj
+                            .get("message")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("Config file edits were overwritten")
+                            .to_string();
+                    }
+                    _ => {}
+                }
+            }
+            app.refresh_config().await?;
+        }
+        "sync.event" => {
+            let event = &notification.params["event_type"];
+            match event.get("type").and_then(|v| v.as_str()) {
+                Some("conflicts") => {
+                    if app.input_mode == InputMode::Normal {
+                        app.open_sync_conflicts().await?;
+                    }
+                    app.refresh_sync().await?;
+                }
+                Some("stage") => app.on_sync_event(event).await?,
+                _ => {
+                    app.on_sync_event(event).await?;
+                    app.refresh_sync().await?;
+                }
+            }
+        }
+        "budget.event" => {
+            let params = &notification.params;
+            let description = params
+                .get("description")
+                .and_then(|v| v.as_str())
+                .unwrap_or("a budget");
+            let used = params
+                .get("used_seconds")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0);
+            let limit = params
+                .pointer("/budget/limit_seconds")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0);
+            let exceeded =
+                params.pointer("/event_type/type").and_then(|v| v.as_str()) == Some("exceeded");
+
+            let title = if exceeded {
+                "⛔ Budget Used Up"
+            } else {
+                "⚠️  Budget at 80%"
+            };
+            let body = format!(
+                "{}: {} of {}",
+                description,
+                format_duration_hm(used),
+                format_duration_hm(limit)
+            );
+            app.status_message = format!("{} — {}", title, body);
+            audio_alert(app);
+            send_os_notification(title, &body);
+            app.refresh_report_budgets().await;
+        }
         _ => {}
     }
 
@@ -266,12 +414,30 @@ async fn handle_mouse_event(
 ) -> Result<()> {
     let content_start_y = 3;
     let content_end_y = term_size.height.saturating_sub(3);
-    let content_area = ratatui::layout::Rect {
+    let mut content_area = ratatui::layout::Rect {
         x: 0,
         y: content_start_y,
         width: term_size.width,
         height: content_end_y - content_start_y,
     };
+    if app.split_mode {
+        let (focused, other) = ui::split_panes(app, content_area);
+        // A click in the other pane focuses it first.
+        let clicked_other = mouse.kind == event::MouseEventKind::Down(event::MouseButton::Left)
+            && other.contains(ratatui::layout::Position::new(mouse.column, mouse.row));
+        content_area = if clicked_other {
+            app.switch_split_focus();
+            other
+        } else {
+            focused
+        };
+        if mouse.kind == event::MouseEventKind::Moved
+            && !content_area.contains(ratatui::layout::Position::new(mouse.column, mouse.row))
+        {
+            app.blocked_task_hover = None;
+            return Ok(());
+        }
+    }
 
     match mouse.kind {
         event::MouseEventKind::Down(event::MouseButton::Left) => {
@@ -288,6 +454,16 @@ async fn handle_mouse_event(
                 }
             }
         }
+        event::MouseEventKind::Moved => {
+            app.blocked_task_hover = if app.current_view == AppView::Dashboard
+                && mouse.row >= content_start_y
+                && mouse.row < content_end_y
+            {
+                hovered_blocked_task(app, mouse, content_area)
+            } else {
+                None
+            };
+        }
         event::MouseEventKind::Drag(event::MouseButton::Left) => {
             if app.current_view == AppView::Kanban {
                 handle_kanban_mouse_drag(app, mouse, content_area);
@@ -364,17 +540,7 @@ async fn handle_tab_click(
                     if let Some(task) = filtered.get(app.selected_task_index)
                         && let Some(tid) = task.get("id").and_then(|v| v.as_str())
                     {
-                        let status = task
-                            .get("status")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("todo");
-                        let col_idx = match status {
-                            "todo" => Some(0),
-                            "in_progress" => Some(1),
-                            "done" | "completed" => Some(2),
-                            _ => None,
-                        };
-                        col_idx.map(|c| (c, tid.to_string()))
+                        app::kanban_column(TaskStatus::of(task)).map(|c| (c, tid.to_string()))
                     } else {
                         None
                     }
@@ -404,19 +570,7 @@ async fn handle_tab_click(
             }
 
             app.current_view = *view;
-            match view {
-                AppView::Dashboard => app.refresh_tasks().await?,
-                AppView::Timers => app.refresh_timer().await?,
-                AppView::Kanban => app.refresh_tasks().await?,
-                AppView::Entries => app.refresh_entries().await?,
-                AppView::Reports => {
-                    app.refresh_tasks().await?;
-                    app.refresh_reports().await?;
-                }
-                AppView::Settings => {}
-                AppView::Logs => app.refresh_logs().await?,
-            }
-            return Ok(());
+            return refresh_view(app, *view).await;
         }
 
         current_x += width;
@@ -533,11 +687,8 @@ async fn handle_kanban_mouse_up(
         return Ok(());
     }
 
-    let new_status = match drag.current_hover_column {
-        0 => "todo",
-        1 => "in_progress",
-        2 => "done",
-        _ => return Ok(()),
+    let Some(new_status) = app::KANBAN_STATUSES.get(drag.current_hover_column).copied() else {
+        return Ok(());
     };
 
     let target_column_name = match drag.current_hover_column {
@@ -559,10 +710,7 @@ async fn handle_kanban_mouse_up(
     };
 
     if let Some(obj) = task.as_object_mut() {
-        obj.insert(
-            "status".to_string(),
-            serde_json::Value::String(new_status.to_string()),
-        );
+        obj.insert("status".to_string(), json!(new_status));
     }
 
     match app.client.task_update(&app.profile_id, task).await {
@@ -601,7 +749,14 @@ async fn handle_dashboard_mouse(
     let click_x = mouse.column.saturating_sub(area.x);
     let click_y = mouse.row.saturating_sub(area.y);
 
-    if click_x < half_width {
+    if click_x < half_width && app.fleet_view {
+        app.focused_pane = DashboardPane::TimerConfig;
+        // Two lines per row below the border.
+        let row = click_y.saturating_sub(1) as usize / 2;
+        if click_y >= 1 && row < app.fleet_rows.len() {
+            app.selected_fleet_row = row;
+        }
+    } else if click_x < half_width {
         let left_height = area.height;
         let timer_height = left_height.saturating_sub(9 + 7);
         let profiles_start = timer_height;
@@ -634,6 +789,23 @@ async fn handle_dashboard_mouse(
     Ok(())
 }
 
+/// The blocked task in the Dashboard's task list under the pointer, laid
+/// out as in `handle_dashboard_mouse`.
+fn hovered_blocked_task(
+    app: &App,
+    mouse: event::MouseEvent,
+    area: ratatui::layout::Rect,
+) -> Option<(usize, u16, u16)> {
+    let x = mouse.column.saturating_sub(area.x);
+    let y = mouse.row.saturating_sub(area.y);
+    if x < area.width / 2 || y < 1 {
+        return None;
+    }
+    let index = (y - 1) as usize;
+    let task = app.get_filtered_tasks().get(index).copied()?;
+    App::is_blocked(task).then_some((index, mouse.column, mouse.row))
+}
+
 fn handle_entries_mouse(app: &mut App, mouse: event::MouseEvent, area: ratatui::layout::Rect) {
     let click_y = mouse.row.saturating_sub(area.y);
     if click_y >= 1 {
@@ -662,11 +834,19 @@ fn handle_timers_mouse(app: &mut App, mouse: event::MouseEvent, area: ratatui::l
 
 fn handle_settings_mouse(app: &mut App, mouse: event::MouseEvent, area: ratatui::layout::Rect) {
     let click_y = mouse.row.saturating_sub(area.y);
-    if click_y >= 1 {
-        let item_idx = (click_y - 1) as usize;
-        if item_idx < crate::app::SettingsItem::ALL.len() {
-            app.selected_setting_index = item_idx;
-        }
+    if click_y >= 1
+        && let Some(item_idx) = settings::item_at_row((click_y - 1) as usize)
+    {
+        app.selected_setting_index = item_idx;
+    }
+}
+
+/// Shows a failed action in the status bar instead of tearing down the UI;
+/// a lost daemon connection is picked up by the reconnect loop.
+fn report_error(app: &mut App, result: Result<()>) {
+    if let Err(e) = result {
+        tracing::error!("Action failed: {}", e);
+        app.status_message = format!("Error: {}", e);
     }
 }
 
@@ -676,34 +856,31 @@ async fn handle_key_event(app: &mut App, code: KeyCode, modifiers: KeyModifiers)
         return Ok(());
     }
 
-    if app.input_mode == InputMode::DeleteTaskConfirm
-        || app.input_mode == InputMode::DeleteProfileConfirm
-        || app.input_mode == InputMode::ConfirmQuit
-        || app.input_mode == InputMode::PomodoroBreakFinished
-    {
+    if code == KeyCode::Esc && app.sync_progress.is_some() && app.input_mode == InputMode::Normal {
+        app.stop_waiting_for_sync();
+        return Ok(());
+    }
+
+    if app.input_mode == InputMode::Confirm {
+        let answer = app
+            .confirm_dialog
+            .as_mut()
+            .map_or(Some(false), |dialog| dialog.handle_key(code));
+        if let Some(confirmed) = answer {
+            app.close_confirm(confirmed).await?;
+        }
+        return Ok(());
+    }
+
+    if app.input_mode == InputMode::PomodoroBreakFinished {
         match code {
-            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter | KeyCode::Char(' ') => {
-                if app.input_mode == InputMode::ConfirmQuit {
-                    app.should_quit = true;
-                } else if app.input_mode == InputMode::PomodoroBreakFinished {
-                    app.resume().await?;
-                    app.input_mode = InputMode::Normal;
-                } else if app.input_mode == InputMode::DeleteTaskConfirm {
-                    app.delete_selected_task().await?;
-                    app.input_mode = InputMode::Normal;
-                } else {
-                    app.delete_selected_profile().await?;
-                    app.input_mode = InputMode::Normal;
-                }
-            }
-            KeyCode::Char('x') if app.input_mode == InputMode::PomodoroBreakFinished => {
+            _ if app.keybindings.is(Action::TimerStop, code, modifiers) => {
                 app.stop_timer().await?;
                 app.input_mode = InputMode::Normal;
             }
-            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Char('q') => {
-                if app.input_mode != InputMode::PomodoroBreakFinished {
-                    app.input_mode = InputMode::Normal;
-                }
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter | KeyCode::Char(' ') => {
+                app.resume().await?;
+                app.input_mode = InputMode::Normal;
             }
             _ => {}
         }
@@ -732,6 +909,96 @@ async fn handle_key_event(app: &mut App, code: KeyCode, modifiers: KeyModifiers)
         return Ok(());
     }
 
+    if app.input_mode == InputMode::MoveEntry {
+        let profile_count = app.get_move_task_profiles().len();
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                app.move_task_target_index = app.move_task_target_index.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j')
+                if app.move_task_target_index + 1 < profile_count =>
+            {
+                app.move_task_target_index += 1;
+            }
+            KeyCode::Enter => app.move_selected_entry(true).await?,
+            KeyCode::Char('c') => app.move_selected_entry(false).await?,
+            KeyCode::Esc => {
+                app.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    if app.input_mode == InputMode::SyncDiff {
+        let last_line = app.sync_diff_line_count().saturating_sub(1);
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                app.sync_diff_scroll = app.sync_diff_scroll.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                app.sync_diff_scroll = (app.sync_diff_scroll + 1).min(last_line);
+            }
+            KeyCode::Char('g') => app.sync_diff_scroll = 0,
+            KeyCode::Char('G') => app.sync_diff_scroll = last_line,
+            KeyCode::Char('F') => app.toggle_full_diff().await?,
+            KeyCode::Esc | KeyCode::Char('q') => {
+                app.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    if app.input_mode == InputMode::SyncConflicts {
+        let last_field = app.conflict_fields().len().saturating_sub(1);
+        match (code, app.conflict_field_index) {
+            (KeyCode::Up | KeyCode::Char('k'), None) => {
+                app.select_conflict(app.selected_conflict_index.saturating_sub(1));
+            }
+            (KeyCode::Down | KeyCode::Char('j'), None) => {
+                app.select_conflict(app.selected_conflict_index + 1);
+            }
+            (KeyCode::Up | KeyCode::Char('k'), Some(field)) => {
+                app.conflict_field_index = Some(field.saturating_sub(1));
+            }
+            (KeyCode::Down | KeyCode::Char('j'), Some(field)) => {
+                app.conflict_field_index = Some((field + 1).min(last_field));
+            }
+            (KeyCode::Tab, None) => app.conflict_field_index = Some(0),
+            (KeyCode::Tab, Some(_)) => app.conflict_field_index = None,
+            (KeyCode::Char(' '), _) => app.toggle_conflict_field(),
+            (KeyCode::Char('l'), _) => app.resolve_selected_conflict("local").await?,
+            (KeyCode::Char('r'), _) => app.resolve_selected_conflict("remote").await?,
+            (KeyCode::Char('m'), _) => app.resolve_selected_conflict("merged").await?,
+            (KeyCode::Esc | KeyCode::Char('q'), _) => {
+                app.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    if app.input_mode == InputMode::SelectBranch {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                app.selected_branch_index = app.selected_branch_index.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                app.selected_branch_index =
+                    (app.selected_branch_index + 1).min(app.branches.len().saturating_sub(1));
+            }
+            KeyCode::Enter => {
+                app.switch_to_selected_branch().await?;
+            }
+            KeyCode::Esc => {
+                app.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
     if app.input_mode == InputMode::NewEntryTask {
         let task_count = app.get_tasks_for_entry_selection().len() + 1;
         match code {
@@ -761,6 +1028,89 @@ async fn handle_key_event(app: &mut App, code: KeyCode, modifiers: KeyModifiers)
         return Ok(());
     }
 
+    if app.input_mode == InputMode::EditEntryTimestamp {
+        let shift = modifiers.contains(KeyModifiers::SHIFT);
+        match code {
+            KeyCode::Char('[') if shift => app.nudge_entry_timestamp(-5),
+            KeyCode::Char(']') if shift => app.nudge_entry_timestamp(5),
+            KeyCode::Char('[') => app.nudge_entry_timestamp(-1),
+            KeyCode::Char(']') => app.nudge_entry_timestamp(1),
+            KeyCode::Char('{') => app.nudge_entry_timestamp(-5),
+            KeyCode::Char('}') => app.nudge_entry_timestamp(5),
+            KeyCode::Char(c) if c.is_ascii_digit() => {
+                if let Some(edit) = app.timestamp_edit.as_mut()
+                    && edit.count.len() < 4
+                {
+                    edit.count.push(c);
+                }
+            }
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+                if let Some(edit) = app.timestamp_edit.as_mut() {
+                    edit.count.clear();
+                    edit.field = match edit.field {
+                        app::TimestampField::Start => app::TimestampField::End,
+                        app::TimestampField::End => app::TimestampField::Start,
+                    };
+                }
+            }
+            KeyCode::Enter => app.save_entry_timestamps().await?,
+            KeyCode::Esc => {
+                app.timestamp_edit = None;
+                app.input_mode = InputMode::Normal;
+                app.status_message = "Entry unchanged".to_string();
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    if app.input_mode == InputMode::CountdownPresets {
+        let preset_count = app.countdown_presets().len();
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                app.selected_preset_index = app.selected_preset_index.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if app.selected_preset_index + 1 < preset_count => {
+                app.selected_preset_index += 1;
+            }
+            KeyCode::Enter => {
+                app.start_countdown_preset(app.selected_preset_index)
+                    .await?
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                app.start_countdown_preset(c as usize - '1' as usize)
+                    .await?;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => app.input_mode = InputMode::Normal,
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    if app.input_mode == InputMode::SessionCapped {
+        let task_count = app.get_tasks_for_entry_selection().len() + 1;
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                app.new_entry_task_index = app.new_entry_task_index.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if app.new_entry_task_index + 1 < task_count => {
+                app.new_entry_task_index += 1;
+            }
+            KeyCode::Char('a') => {
+                app.new_entry_show_archived = !app.new_entry_show_archived;
+                app.new_entry_task_index = 0;
+            }
+            KeyCode::Enter => app.resolve_capped_session(true).await?,
+            KeyCode::Char('d') | KeyCode::Esc => app.resolve_capped_session(false).await?,
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    if app.input_mode == InputMode::TaskDetail {
+        return handle_task_detail_keys(app, code, modifiers).await;
+    }
+
     if app.input_mode != InputMode::Normal {
         match code {
             KeyCode::Tab | KeyCode::Down | KeyCode::Up
@@ -781,8 +1131,10 @@ async fn handle_key_event(app: &mut App, code: KeyCode, modifiers: KeyModifiers)
             }
             KeyCode::Char(c) => {
                 if modifiers.contains(KeyModifiers::CONTROL) {
-                    if c == 'c' {
-                        app.should_quit = true;
+                    match c {
+                        'c' => app.should_quit = true,
+                        'v' | 'V' => app.paste_from_clipboard(),
+                        _ => {}
                     }
                 } else {
                     app.handle_input_char(c);
@@ -796,31 +1148,95 @@ async fn handle_key_event(app: &mut App, code: KeyCode, modifiers: KeyModifiers)
         return Ok(());
     }
 
+    if app.show_help {
+        handle_help_keys(app, code, modifiers);
+        return Ok(());
+    }
+
+    let presses = app.count.feed(code, modifiers, std::time::Instant::now());
+    for press in presses {
+        for _ in 0..press.count {
+            handle_normal_key(app, press.code, press.modifiers).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The views Alt+1 to Alt+6 put in the right pane of the split view.
+const SPLIT_VIEWS: [AppView; 6] = [
+    AppView::Dashboard,
+    AppView::Timers,
+    AppView::Kanban,
+    AppView::Entries,
+    AppView::Reports,
+    AppView::Settings,
+];
+
+/// Loads what `view` shows.
+async fn refresh_view(app: &mut App, view: AppView) -> Result<()> {
+    match view {
+        AppView::Dashboard | AppView::Kanban => app.refresh_tasks().await?,
+        AppView::Timers => app.refresh_timer().await?,
+        AppView::Entries => app.refresh_entries().await?,
+        AppView::Reports => {
+            app.refresh_tasks().await?;
+            app.refresh_reports().await?;
+        }
+        AppView::Settings => {}
+        AppView::Logs => app.refresh_logs().await?,
+    }
+    Ok(())
+}
+
+fn timer_pane_focused(app: &App) -> bool {
+    app.current_view == AppView::Dashboard && app.focused_pane == DashboardPane::TimerConfig
+}
+
+async fn handle_normal_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    let keys = app.keybindings.clone();
+    let is = |action| keys.is(action, code, modifiers);
     match code {
-        KeyCode::Char('q') | KeyCode::Esc => {
-            if app.show_help {
-                app.toggle_help();
+        KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => app.should_quit = true,
+        _ if code == KeyCode::Esc || is(Action::Quit) => {
+            let is_running = if let Some(timer) = &app.timer_info
+                && let Some(state) = timer.get("state").and_then(|v| v.as_str())
+            {
+                state == "running" || state == "paused"
             } else {
-                let is_running = if let Some(timer) = &app.timer_info
-                    && let Some(state) = timer.get("state").and_then(|v| v.as_str())
-                {
-                    state == "running" || state == "paused"
-                } else {
-                    false
-                };
+                false
+            };
 
-                if is_running {
-                    app.input_mode = InputMode::ConfirmQuit;
-                    print!("\x07");
-                } else {
-                    app.should_quit = true;
-                }
+            if is_running {
+                app.confirm_quit();
+                print!("\x07");
+            } else {
+                app.should_quit = true;
             }
         }
-        KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => app.should_quit = true,
-        KeyCode::Char('?') => app.toggle_help(),
+        _ if is(Action::Help) => app.toggle_help(),
+        // Terminals send Ctrl+| and Ctrl+\\ as the same byte, which
+        // crossterm reads as Ctrl+4.
+        KeyCode::Char('|' | '\\' | '4') if modifiers.contains(KeyModifiers::CONTROL) => {
+            app.toggle_split();
+            if app.split_mode {
+                refresh_view(app, app.other_pane.view).await?;
+            }
+        }
+        KeyCode::Char(c @ '1'..='6') if modifiers.contains(KeyModifiers::ALT) => {
+            let view = SPLIT_VIEWS[c as usize - '1' as usize];
+            app.set_split_view(view);
+            refresh_view(app, view).await?;
+        }
         KeyCode::Char('w') if modifiers.contains(KeyModifiers::CONTROL) => {
-            if app.current_view == AppView::Dashboard {
+            // In the split view the Dashboard's last pane hands focus on
+            // to the other side, as do views without panes.
+            let leaves = app.current_view != AppView::Dashboard
+                || app.focused_pane == app::DashboardPane::ProfileList;
+            if app.split_mode && leaves {
+                app.focused_pane = app::DashboardPane::TimerConfig;
+                app.switch_split_focus();
+            } else if app.current_view == AppView::Dashboard {
                 app.focused_pane = match app.focused_pane {
                     app::DashboardPane::TimerConfig => app::DashboardPane::TasksList,
                     app::DashboardPane::TasksList => app::DashboardPane::ProfileList,
@@ -829,21 +1245,9 @@ async fn handle_key_event(app: &mut App, code: KeyCode, modifiers: KeyModifiers)
             }
         }
 
-        KeyCode::Char('t') => {
-            if app.current_view == AppView::Dashboard
-                && app.focused_pane == app::DashboardPane::TimerConfig
-            {
-                app.cycle_timer_type();
-            }
-        }
-        KeyCode::Char('T') if modifiers.contains(KeyModifiers::SHIFT) => {
-            if app.current_view == AppView::Dashboard
-                && app.focused_pane == app::DashboardPane::TimerConfig
-            {
-                app.cycle_timer_type_reverse();
-            }
-        }
-        KeyCode::Char('1') => {
+        KeyCode::Char('t') if timer_pane_focused(app) => app.cycle_timer_type(),
+        KeyCode::Char('T') if timer_pane_focused(app) => app.cycle_timer_type_reverse(),
+        _ if is(Action::ViewDashboard) => {
             if app.current_view == AppView::Kanban
                 && let Some(tid) = app.get_selected_kanban_task_id()
             {
@@ -858,28 +1262,18 @@ async fn handle_key_event(app: &mut App, code: KeyCode, modifiers: KeyModifiers)
             app.current_view = AppView::Dashboard;
             app.refresh_tasks().await?;
         }
-        KeyCode::Char('2') => {
+        _ if is(Action::ViewTimers) => {
             app.current_view = AppView::Timers;
             app.refresh_timer().await?;
         }
-        KeyCode::Char('3') => {
+        _ if is(Action::ViewKanban) => {
             if app.current_view == AppView::Dashboard {
                 let sync_target = {
                     let filtered = app.get_filtered_tasks();
                     if let Some(task) = filtered.get(app.selected_task_index)
                         && let Some(tid) = task.get("id").and_then(|v| v.as_str())
                     {
-                        let status = task
-                            .get("status")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("todo");
-                        let col_idx = match status {
-                            "todo" => Some(0),
-                            "in_progress" => Some(1),
-                            "done" | "completed" => Some(2),
-                            _ => None,
-                        };
-                        col_idx.map(|c| (c, tid.to_string()))
+                        app::kanban_column(TaskStatus::of(task)).map(|c| (c, tid.to_string()))
                     } else {
                         None
                     }
@@ -899,27 +1293,28 @@ async fn handle_key_event(app: &mut App, code: KeyCode, modifiers: KeyModifiers)
             app.current_view = AppView::Kanban;
             app.refresh_tasks().await?;
         }
-        KeyCode::Char('4') => {
+        _ if is(Action::ViewEntries) => {
             app.current_view = AppView::Entries;
             app.refresh_entries().await?;
         }
-        KeyCode::Char('5') => {
+        _ if is(Action::ViewReports) => {
             app.current_view = AppView::Reports;
             app.refresh_tasks().await?;
             app.refresh_reports().await?;
         }
-        KeyCode::Char('6') => app.current_view = AppView::Settings,
-        KeyCode::Char('7') => {
+        _ if is(Action::ViewSettings) => app.current_view = AppView::Settings,
+        _ if is(Action::ViewLogs) => {
             app.current_view = AppView::Logs;
             app.refresh_logs().await?;
         }
 
         KeyCode::PageUp => app.list_page_up(),
         KeyCode::PageDown => app.list_page_down(),
+        _ if is(Action::TimerRepeat) => app.repeat_last_timer().await?,
 
         _ => match app.current_view {
             AppView::Dashboard => handle_dashboard_keys(app, code, modifiers).await?,
-            AppView::Timers => handle_timers_keys(app, code).await?,
+            AppView::Timers => handle_timers_keys(app, code, modifiers).await?,
             AppView::Kanban => handle_kanban_keys(app, code, modifiers).await?,
             AppView::Entries => handle_entries_keys(app, code).await?,
             AppView::Reports => handle_reports_keys(app, code).await?,
@@ -931,40 +1326,170 @@ async fn handle_key_event(app: &mut App, code: KeyCode, modifiers: KeyModifiers)
     Ok(())
 }
 
-fn get_active_timer_button_count(app: &App) -> usize {
-    if let Some(timer) = &app.timer_info
-        && let Some(state) = timer.get("state").and_then(|v| v.as_str())
-        && (state == "running" || state == "paused")
-    {
-        return 2;
-    }
-    1
-}
-
-async fn handle_dashboard_keys(
+async fn handle_task_detail_keys(
     app: &mut App,
     code: KeyCode,
     modifiers: KeyModifiers,
 ) -> Result<()> {
-    let is_timer_active = if let Some(timer) = &app.timer_info {
-        if let Some(state) = timer.get("state").and_then(|v| v.as_str()) {
-            state == "running" || state == "paused"
-        } else {
-            false
-        }
-    } else {
-        false
+    let control = modifiers.contains(KeyModifiers::CONTROL);
+    let Some(detail) = app.task_detail.as_mut() else {
+        app.input_mode = InputMode::Normal;
+        return Ok(());
     };
 
-    match app.focused_pane {
-        DashboardPane::TimerConfig => match code {
-            KeyCode::Up | KeyCode::Char('k') => {
-                if app.timer_info.is_none() {
-                    app.adjust_timer_duration_up();
-                }
-            }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if app.timer_info.is_none() {
+    match code {
+        KeyCode::Esc => app.close_task_detail(),
+        KeyCode::Char('s') if control => app.save_task_detail().await?,
+        KeyCode::Char('c') if control => app.should_quit = true,
+        KeyCode::Char('u') if control => detail.clear_line(),
+        KeyCode::Char('v' | 'V') if control => app.paste_from_clipboard(),
+        KeyCode::Tab | KeyCode::Down => detail.next_field(),
+        KeyCode::BackTab | KeyCode::Up => detail.previous_field(),
+        KeyCode::Enter if detail.field == task_detail::DetailField::Description => {
+            detail.newline();
+        }
+        KeyCode::Enter => app.save_task_detail().await?,
+        KeyCode::Left | KeyCode::Right | KeyCode::Char(' ')
+            if detail.field == task_detail::DetailField::Priority =>
+        {
+            detail.cycle_priority(code != KeyCode::Left);
+        }
+        KeyCode::Char(c) if !control => detail.insert_char(c),
+        KeyCode::Backspace => detail.backspace(),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_help_keys(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    let height = crossterm::terminal::size()
+        .map(|(width, height)| {
+            ui::help_viewport_height(ratatui::layout::Rect::new(0, 0, width, height))
+        })
+        .unwrap_or(0);
+    let help = &mut app.help;
+
+    if help.searching {
+        match code {
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                app.should_quit = true;
+            }
+            KeyCode::Char(c) => help.push_char(c),
+            KeyCode::Backspace => help.pop_char(),
+            KeyCode::Enter => help.searching = false,
+            KeyCode::Esc => help.clear_search(),
+            _ => {}
+        }
+        return;
+    }
+
+    match code {
+        KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => app.should_quit = true,
+        KeyCode::Esc if !help.query.is_empty() => help.clear_search(),
+        KeyCode::Char('q') | KeyCode::Char('?') | KeyCode::Esc => app.toggle_help(),
+        KeyCode::Char('/') => help.start_search(),
+        KeyCode::Char('n') => help.jump(true, height),
+        KeyCode::Char('N') => help.jump(false, height),
+        KeyCode::Down | KeyCode::Char('j') => help.scroll_by(1, height),
+        KeyCode::Up | KeyCode::Char('k') => help.scroll_by(-1, height),
+        KeyCode::PageDown => help.scroll_by(height as isize, height),
+        KeyCode::PageUp => help.scroll_by(-(height as isize), height),
+        KeyCode::Char('g') | KeyCode::Home => help.scroll = 0,
+        KeyCode::Char('G') | KeyCode::End => help.scroll_to_end(height),
+        _ => {}
+    }
+}
+
+fn get_active_timer_button_count(app: &App) -> usize {
+    if app.in_break() {
+        return 3;
+    }
+    if let Some(timer) = &app.timer_info
+        && let Some(state) = timer.get("state").and_then(|v| v.as_str())
+        && (state == "running" || state == "paused")
+    {
+        return 2;
+    }
+    1
+}
+
+async fn handle_multi_dashboard_keys(app: &mut App, code: KeyCode) -> Result<()> {
+    match code {
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::BackTab => app.select_multi_timer(-1),
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => app.select_multi_timer(1),
+        KeyCode::Char(' ') | KeyCode::Enter => app.toggle_multi_timer().await?,
+        KeyCode::Char('r') => {
+            app.refresh_all_timers().await;
+            app.status_message = "Refreshed!".to_string();
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Keys for the all-profiles panel that replaces the left column.
+async fn handle_fleet_keys(app: &mut App, code: KeyCode) -> Result<()> {
+    match code {
+        KeyCode::Up | KeyCode::Char('k') => app.select_fleet_row(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.select_fleet_row(1),
+        KeyCode::Enter => app.switch_to_fleet_row().await?,
+        KeyCode::Char('r') => {
+            app.refresh_fleet(true).await;
+            app.status_message = "Refreshed!".to_string();
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+async fn handle_dashboard_keys(
+    app: &mut App,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+) -> Result<()> {
+    if code == KeyCode::Char('M') {
+        app.toggle_multi_dashboard().await;
+        return Ok(());
+    }
+    if app.multi_dashboard {
+        return handle_multi_dashboard_keys(app, code).await;
+    }
+    if code == KeyCode::Char('*') {
+        app.toggle_fleet_view().await;
+        return Ok(());
+    }
+    if app.fleet_view && app.focused_pane != DashboardPane::TasksList {
+        return handle_fleet_keys(app, code).await;
+    }
+    let keys = app.keybindings.clone();
+    let is = |action| keys.is(action, code, modifiers);
+
+    let is_timer_active = if let Some(timer) = &app.timer_info {
+        if let Some(state) = timer.get("state").and_then(|v| v.as_str()) {
+            state == "running" || state == "paused"
+        } else {
+            false
+        }
+    } else {
+        false
+    };
+
+    match app.focused_pane {
+        DashboardPane::TimerConfig => match code {
+            _ if is(Action::TimerStop) => app.stop_timer().await?,
+            _ if is(Action::TimerStartAnother) => app.start_another_timer().await?,
+            KeyCode::Char('k') if app.in_break() => {
+                app.skip_break().await?;
+            }
+            KeyCode::Up | KeyCode::Char('k') if app.timer_info.is_some() => app.focus_timer(-1),
+            KeyCode::Down | KeyCode::Char('j') if app.timer_info.is_some() => app.focus_timer(1),
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('>') => {
+                if app.timer_info.is_none() {
+                    app.adjust_timer_duration_up();
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('<') => {
+                if app.timer_info.is_none() {
                     app.adjust_timer_duration_down();
                 }
             }
@@ -989,20 +1514,22 @@ async fn handle_dashboard_keys(
                     match app.selected_timer_button {
                         0 => app.toggle_pause().await?,
                         1 => app.stop_timer().await?,
+                        2 => app.skip_break().await?,
                         _ => {}
                     }
                     return Ok(());
                 }
                 app.start_selected_timer().await?;
             }
-            KeyCode::Char('x') => {
-                app.stop_timer().await?;
-            }
             KeyCode::Char('r') => {
                 app.status_message = "Refreshing...".to_string();
                 app.refresh_all().await?;
                 app.status_message = "Refreshed!".to_string();
             }
+            KeyCode::Char('A') => {
+                app.toggle_auto_start().await?;
+            }
+            KeyCode::Char('p') => app.open_countdown_presets(),
             KeyCode::Char('m') => {
                 app.status_message = "MOOOOO! 🐮".to_string();
                 audio_alert(app);
@@ -1010,50 +1537,58 @@ async fn handle_dashboard_keys(
             _ => {}
         },
         DashboardPane::TasksList => match code {
-            KeyCode::Up | KeyCode::Char('k') => {
-                if is_timer_active {
-                    app.status_message = "Cannot change task while timer is running!".to_string();
-                    print!("\x07");
-                } else {
-                    app.list_previous();
+            KeyCode::Char('V') => app.toggle_task_selection_mode(),
+            KeyCode::Char(' ') if app.task_selection.is_some() => app.toggle_task_mark(),
+            KeyCode::Char('t') if app.task_selection.is_some() => app.start_bulk_tag(false),
+            KeyCode::Char('u') if app.task_selection.is_some() => app.start_bulk_tag(true),
+            _ if is(Action::TimerPause) => {
+                if let Some(timer) = &app.timer_info
+                    && let Some(state) = timer.get("state").and_then(|v| v.as_str())
+                    && (state == "running" || state == "paused")
+                {
+                    app.toggle_pause().await?;
+                    return Ok(());
                 }
+                app.start_selected_timer().await?;
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if is_timer_active {
-                    app.status_message = "Cannot change task while timer is running!".to_string();
-                    print!("\x07");
-                } else {
-                    app.list_next();
-                }
+            _ if is(Action::TimerStop) => app.stop_timer().await?,
+            _ if is(Action::TimerStartAnother) => app.start_another_timer().await?,
+            _ if is(Action::TaskNew) => start_new_task(app),
+            _ if is(Action::TaskQuickAdd) => start_quick_add_task(app),
+            _ if is(Action::TaskSearch) => {
+                app.input_mode = InputMode::SearchTasks;
+                app.input_buffer.clear();
+                app.status_message = "Search tasks (#tag to filter by tag):".to_string();
             }
-            KeyCode::Char('g') => {
-                if is_timer_active {
-                    app.status_message = "Cannot change task while timer is running!".to_string();
-                    print!("\x07");
-                } else {
-                    app.selected_task_index = 0;
+            _ if is(Action::TaskDelete) => {
+                if let Some(task_id) = app.selected_task_id() {
+                    app.confirm_delete_task(&task_id);
                 }
             }
-            KeyCode::Char('G') => {
-                if is_timer_active {
-                    app.status_message = "Cannot change task while timer is running!".to_string();
-                    print!("\x07");
-                } else {
-                    app.selected_task_index = app.tasks.len().saturating_sub(1);
+            _ if is(Action::TaskArchive) => {
+                let filtered_tasks = app.get_filtered_tasks();
+                if let Some(task) = filtered_tasks.get(app.selected_task_index)
+                    && let Some(id) = task.get("id").and_then(|v| v.as_str())
+                {
+                    let id_owned = id.to_string();
+                    app.archive_task(&id_owned).await?;
                 }
             }
-            KeyCode::Char('n') => {
-                app.input_mode = InputMode::NewTask;
-                app.input_buffer.clear();
-                app.input_buffer_2.clear();
-                app.focused_input_field = 0;
-                app.status_message = "New Task".to_string();
-            }
-            KeyCode::Char('N') if modifiers.contains(KeyModifiers::SHIFT) => {
-                app.input_mode = InputMode::QuickAddTask;
-                app.input_buffer.clear();
-                app.status_message = "Quick Add Task:".to_string();
+            _ if is(Action::TaskEdit) => app.edit_selected_task().await?,
+            _ if is(Action::TaskMove) => {
+                let filtered_tasks = app.get_filtered_tasks();
+                if !filtered_tasks.is_empty() && app.profiles.len() > 1 {
+                    app.input_mode = InputMode::MoveTask;
+                    app.move_task_target_index = 0;
+                    app.status_message = "Move task to profile".to_string();
+                } else if app.profiles.len() <= 1 {
+                    app.status_message = "Need multiple profiles to move tasks".to_string();
+                }
             }
+            KeyCode::Up | KeyCode::Char('k') => app.list_previous(),
+            KeyCode::Down | KeyCode::Char('j') => app.list_next(),
+            KeyCode::Char('g') => app.selected_task_index = 0,
+            KeyCode::Char('G') => app.selected_task_index = app.tasks.len().saturating_sub(1),
             KeyCode::Char('v') => {
                 app.show_task_description = !app.show_task_description;
                 app.status_message = if app.show_task_description {
@@ -1062,25 +1597,6 @@ async fn handle_dashboard_keys(
                     "Hidden task descriptions".to_string()
                 };
             }
-            KeyCode::Char('/') => {
-                app.input_mode = InputMode::SearchTasks;
-                app.input_buffer.clear();
-                app.status_message = "Search tasks:".to_string();
-            }
-            KeyCode::Char('d') => {
-                if !app.tasks.is_empty() {
-                    app.input_mode = InputMode::DeleteTaskConfirm;
-                }
-            }
-            KeyCode::Char('a') => {
-                let filtered_tasks = app.get_filtered_tasks();
-                if let Some(task) = filtered_tasks.get(app.selected_task_index)
-                    && let Some(id) = task.get("id").and_then(|v| v.as_str())
-                {
-                    let id_owned = id.to_string();
-                    app.archive_task(&id_owned).await?;
-                }
-            }
             KeyCode::Char('A') if modifiers.contains(KeyModifiers::SHIFT) => {
                 app.show_archived = !app.show_archived;
                 app.selected_task_index = 0;
@@ -1090,37 +1606,38 @@ async fn handle_dashboard_keys(
                     "Viewing ACTIVE tasks".to_string()
                 };
             }
-            KeyCode::Char('e') => {
-                app.edit_selected_task().await?;
-            }
-            KeyCode::Enter | KeyCode::Char(' ') => {
-                if let Some(timer) = &app.timer_info
-                    && let Some(state) = timer.get("state").and_then(|v| v.as_str())
-                    && (state == "running" || state == "paused")
-                {
-                    app.toggle_pause().await?;
-                    return Ok(());
+            KeyCode::Char('D') if modifiers.contains(KeyModifiers::SHIFT) => {
+                app.toggle_due_view().await;
+            }
+            KeyCode::Char('Y') => app.yank_selected_task(),
+            KeyCode::Char('P') => app.paste_yanked_task().await?,
+            KeyCode::Char('p') => {
+                let task_id = app
+                    .get_filtered_tasks()
+                    .get(app.selected_task_index)
+                    .and_then(|t| t.get("id"))
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+                if let Some(task_id) = task_id {
+                    app.toggle_pin(&task_id).await?;
                 }
-                app.start_selected_timer().await?;
             }
-            KeyCode::Char('x') => {
-                app.stop_timer().await?;
+            KeyCode::Enter | KeyCode::Char('i') => {
+                let task_id = app
+                    .get_filtered_tasks()
+                    .get(app.selected_task_index)
+                    .and_then(|t| t.get("id"))
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+                if let Some(task_id) = task_id {
+                    app.open_task_detail(&task_id);
+                }
             }
             KeyCode::Char('r') => {
                 app.status_message = "Refreshing...".to_string();
                 app.refresh_all().await?;
                 app.status_message = "Refreshed!".to_string();
             }
-            KeyCode::Char('m') => {
-                let filtered_tasks = app.get_filtered_tasks();
-                if !filtered_tasks.is_empty() && app.profiles.len() > 1 {
-                    app.input_mode = InputMode::MoveTask;
-                    app.move_task_target_index = 0;
-                    app.status_message = "Move task to profile".to_string();
-                } else if app.profiles.len() <= 1 {
-                    app.status_message = "Need multiple profiles to move tasks".to_string();
-                }
-            }
             _ => {}
         },
         DashboardPane::ProfileList => match code {
@@ -1142,11 +1659,7 @@ async fn handle_dashboard_keys(
                 app.input_buffer.clear();
                 app.status_message = "Enter profile name:".to_string();
             }
-            KeyCode::Char('d') => {
-                if !app.profiles.is_empty() {
-                    app.input_mode = InputMode::DeleteProfileConfirm;
-                }
-            }
+            KeyCode::Char('d') => app.confirm_delete_profile(),
             KeyCode::Char('r') => {
                 app.input_mode = InputMode::RenameProfile;
                 app.input_buffer.clear();
@@ -1162,6 +1675,20 @@ async fn handle_dashboard_keys(
     Ok(())
 }
 
+fn start_new_task(app: &mut App) {
+    app.input_mode = InputMode::NewTask;
+    app.input_buffer.clear();
+    app.input_buffer_2.clear();
+    app.focused_input_field = 0;
+    app.status_message = "New Task".to_string();
+}
+
+fn start_quick_add_task(app: &mut App) {
+    app.input_mode = InputMode::QuickAddTask;
+    app.input_buffer.clear();
+    app.status_message = "Quick Add Task:".to_string();
+}
+
 async fn handle_kanban_keys(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Result<()> {
     let col_len = app.get_kanban_tasks(app.selected_column_index).len();
     if app.selected_kanban_card_index >= col_len && col_len > 0 {
@@ -1170,7 +1697,62 @@ async fn handle_kanban_keys(app: &mut App, code: KeyCode, modifiers: KeyModifier
         app.selected_kanban_card_index = 0;
     }
 
+    let keys = app.keybindings.clone();
+    let is = |action| keys.is(action, code, modifiers);
     match code {
+        _ if is(Action::TimerStartAnother) => {
+            if let Some(tid) = app.get_selected_kanban_task_id() {
+                app.sync_kanban_to_task_index(&tid);
+                app.start_another_timer().await?;
+            }
+        }
+        _ if is(Action::TaskNew) => start_new_task(app),
+        _ if is(Action::TaskQuickAdd) => start_quick_add_task(app),
+        _ if is(Action::TaskEdit) => {
+            let title_to_edit = {
+                let tasks = app.get_kanban_tasks(app.selected_column_index);
+                if let Some(task) = tasks.get(app.selected_kanban_card_index) {
+                    task.get("title")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string())
+                } else {
+                    None
+                }
+            };
+
+            if let Some(title) = title_to_edit {
+                app.input_mode = InputMode::EditTask;
+                if let Some(tid) = app.get_selected_kanban_task_id() {
+                    app.sync_kanban_to_task_index(&tid);
+                }
+                app.input_buffer = title;
+                app.status_message = "Edit task title:".to_string();
+            }
+        }
+        _ if is(Action::TaskDelete) => {
+            if let Some(tid) = app.get_selected_kanban_task_id() {
+                app.sync_kanban_to_task_index(&tid);
+                app.confirm_delete_task(&tid);
+            }
+        }
+        _ if is(Action::TaskArchive) => {
+            if let Some(tid) = app.get_selected_kanban_task_id() {
+                app.archive_task(&tid).await?;
+            }
+        }
+        _ if is(Action::TaskMove) => {
+            let tasks = app.get_kanban_tasks(app.selected_column_index);
+            if !tasks.is_empty() && app.profiles.len() > 1 {
+                if let Some(tid) = app.get_selected_kanban_task_id() {
+                    app.sync_kanban_to_task_index(&tid);
+                }
+                app.input_mode = InputMode::MoveTask;
+                app.move_task_target_index = 0;
+                app.status_message = "Move task to profile".to_string();
+            } else if app.profiles.len() <= 1 {
+                app.status_message = "Need multiple profiles to move tasks".to_string();
+            }
+        }
         KeyCode::Left | KeyCode::Char('h') => {
             if modifiers.contains(KeyModifiers::SHIFT) {
                 app.move_kanban_card(-1).await?;
@@ -1200,6 +1782,8 @@ async fn handle_kanban_keys(app: &mut App, code: KeyCode, modifiers: KeyModifier
         }
         KeyCode::Char('H') => app.move_kanban_card(-1).await?,
         KeyCode::Char('L') => app.move_kanban_card(1).await?,
+        KeyCode::Char('K') => app.reorder_kanban_card(-1).await?,
+        KeyCode::Char('J') => app.reorder_kanban_card(1).await?,
 
         KeyCode::Char('A') if modifiers.contains(KeyModifiers::SHIFT) => {
             app.show_archived = !app.show_archived;
@@ -1211,18 +1795,6 @@ async fn handle_kanban_keys(app: &mut App, code: KeyCode, modifiers: KeyModifier
                 "Viewing ACTIVE tasks".to_string()
             };
         }
-        KeyCode::Char('n') => {
-            app.input_mode = InputMode::NewTask;
-            app.input_buffer.clear();
-            app.input_buffer_2.clear();
-            app.focused_input_field = 0;
-            app.status_message = "New Task".to_string();
-        }
-        KeyCode::Char('N') if modifiers.contains(KeyModifiers::SHIFT) => {
-            app.input_mode = InputMode::QuickAddTask;
-            app.input_buffer.clear();
-            app.status_message = "Quick Add Task:".to_string();
-        }
         KeyCode::Char('v') => {
             app.show_task_description = !app.show_task_description;
             app.status_message = if app.show_task_description {
@@ -1231,57 +1803,21 @@ async fn handle_kanban_keys(app: &mut App, code: KeyCode, modifiers: KeyModifier
                 "Hidden task descriptions".to_string()
             };
         }
-        KeyCode::Char('e') => {
-            let title_to_edit = {
-                let tasks = app.get_kanban_tasks(app.selected_column_index);
-                if let Some(task) = tasks.get(app.selected_kanban_card_index) {
-                    task.get("title")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string())
-                } else {
-                    None
-                }
-            };
-
-            if let Some(title) = title_to_edit {
-                app.input_mode = InputMode::EditTask;
-                if let Some(tid) = app.get_selected_kanban_task_id() {
-                    app.sync_kanban_to_task_index(&tid);
-                }
-                app.input_buffer = title;
-                app.status_message = "Edit task title:".to_string();
-            }
+        KeyCode::Enter if modifiers.contains(KeyModifiers::SHIFT) => {
+            app.start_bulk_status_update();
         }
-        KeyCode::Char('d') => {
+        KeyCode::Enter | KeyCode::Char('i') => {
             if let Some(tid) = app.get_selected_kanban_task_id() {
                 app.sync_kanban_to_task_index(&tid);
-                app.input_mode = InputMode::DeleteTaskConfirm;
-            }
-        }
-        KeyCode::Char('a') => {
-            if let Some(tid) = app.get_selected_kanban_task_id() {
-                app.archive_task(&tid).await?;
+                app.open_task_detail(&tid);
             }
         }
-        KeyCode::Enter | KeyCode::Char(' ') => {
+        KeyCode::Char(' ') => {
             if let Some(tid) = app.get_selected_kanban_task_id() {
                 app.sync_kanban_to_task_index(&tid);
                 app.start_selected_timer().await?;
             }
         }
-        KeyCode::Char('m') => {
-            let tasks = app.get_kanban_tasks(app.selected_column_index);
-            if !tasks.is_empty() && app.profiles.len() > 1 {
-                if let Some(tid) = app.get_selected_kanban_task_id() {
-                    app.sync_kanban_to_task_index(&tid);
-                }
-                app.input_mode = InputMode::MoveTask;
-                app.move_task_target_index = 0;
-                app.status_message = "Move task to profile".to_string();
-            } else if app.profiles.len() <= 1 {
-                app.status_message = "Need multiple profiles to move tasks".to_string();
-            }
-        }
         _ => {}
     }
     Ok(())
@@ -1295,12 +1831,20 @@ async fn handle_entries_keys(app: &mut App, code: KeyCode) -> Result<()> {
         KeyCode::Char('G') => {
             app.selected_entry_index = app.entries.len().saturating_sub(1);
         }
-        KeyCode::Char('D') => app.show_entries_for_day().await?,
-        KeyCode::Char('W') => app.show_entries_for_week().await?,
-        KeyCode::Char('M') => app.show_entries_for_month().await?,
+        KeyCode::Char('T') => app.show_entries_for_today().await?,
+        KeyCode::Char('W') => app.set_entry_granularity(Granularity::Week).await?,
+        KeyCode::Char('M') => app.set_entry_granularity(Granularity::Month).await?,
+        KeyCode::Char('[') => app.step_entry_range(-1).await?,
+        KeyCode::Char(']') => app.step_entry_range(1).await?,
+        KeyCode::Char('L') => app.start_quick_log(),
+        KeyCode::Char('D') => {
+            app.input_mode = InputMode::EntriesDate;
+            app.input_buffer.clear();
+            app.status_message = "Show date (2024-06-11, yesterday, last tue):".to_string();
+        }
         KeyCode::Char('d') => {
             if !app.entries.is_empty() {
-                app.delete_selected_entry().await?;
+                app.confirm_delete_entry().await?;
             }
         }
         KeyCode::Char('e') => {
@@ -1308,6 +1852,16 @@ async fn handle_entries_keys(app: &mut App, code: KeyCode) -> Result<()> {
                 app.edit_selected_entry().await?;
             }
         }
+        KeyCode::Char('E') if !app.entries.is_empty() => app.edit_selected_entry_times(),
+        KeyCode::Char('m') if !app.entries.is_empty() => {
+            if app.profiles.len() > 1 {
+                app.input_mode = InputMode::MoveEntry;
+                app.move_task_target_index = 0;
+                app.status_message = "Move or copy entry to profile".to_string();
+            } else {
+                app.status_message = "Need multiple profiles to move entries".to_string();
+            }
+        }
         KeyCode::Char('r') => app.refresh_entries().await?,
         KeyCode::Char('f') => {
             app.input_mode = InputMode::FilterEntries;
@@ -1340,6 +1894,10 @@ async fn handle_reports_keys(app: &mut App, code: KeyCode) -> Result<()> {
             app.refresh_reports().await?;
         }
         KeyCode::Char('p') => app.toggle_report_profile().await?,
+        KeyCode::Char('v') => app.toggle_report_breakdown(),
+        KeyCode::Char('V') => app.start_report_compare(),
+        KeyCode::Char('H') => app.toggle_report_hourly().await,
+        KeyCode::Char('E') => app.export_report_breakdown(),
         KeyCode::Char('r') => app.refresh_reports().await?,
         _ => {}
     }
@@ -1347,8 +1905,7 @@ async fn handle_reports_keys(app: &mut App, code: KeyCode) -> Result<()> {
 }
 
 async fn handle_settings_keys(app: &mut App, code: KeyCode) -> Result<()> {
-    use app::SettingsItem;
-    let num_settings = SettingsItem::ALL.len();
+    let num_settings = settings::item_count();
 
     match code {
         KeyCode::Up | KeyCode::Char('k') => {
@@ -1357,93 +1914,36 @@ async fn handle_settings_keys(app: &mut App, code: KeyCode) -> Result<()> {
         KeyCode::Down | KeyCode::Char('j') => {
             app.selected_setting_index = (app.selected_setting_index + 1).min(num_settings - 1);
         }
-        KeyCode::Char(' ') | KeyCode::Enter => {
-            let selected_item = SettingsItem::ALL[app.selected_setting_index];
-            match selected_item {
-                SettingsItem::AudioAlerts => app.toggle_audio_alerts(),
-                SettingsItem::CowModal => app.toggle_cow_modal(),
-                SettingsItem::SyncAutoCommit => app.toggle_git_sync().await?,
-                SettingsItem::SyncInitRepo => app.init_git_sync().await?,
-                SettingsItem::SyncNow => app.sync_now().await?,
-                SettingsItem::PomodoroWork => {
-                    app.input_mode = InputMode::ConfigPomodoro;
-                    app.input_buffer.clear();
-                    app.status_message = "Enter work duration in minutes:".to_string();
-                }
-                SettingsItem::PomodoroShortBreak => {
-                    app.input_mode = InputMode::ConfigShortBreak;
-                    app.input_buffer.clear();
-                    app.status_message = "Enter short break duration in minutes:".to_string();
-                }
-                SettingsItem::PomodoroLongBreak => {
-                    app.input_mode = InputMode::ConfigLongBreak;
-                    app.input_buffer.clear();
-                    app.status_message = "Enter long break duration in minutes:".to_string();
-                }
-                SettingsItem::CountdownDefault => {
-                    app.input_mode = InputMode::ConfigCountdown;
-                    app.input_buffer.clear();
-                    app.status_message = "Enter default countdown duration in minutes:".to_string();
-                }
-            }
-        }
-        KeyCode::Left | KeyCode::Char('h') => {
-            let selected_item = SettingsItem::ALL[app.selected_setting_index];
-            match selected_item {
-                SettingsItem::PomodoroWork
-                | SettingsItem::PomodoroShortBreak
-                | SettingsItem::PomodoroLongBreak => {
-                    app.adjust_pomodoro_setting(selected_item, -1).await?;
-                }
-                SettingsItem::CountdownDefault => {
-                    if app.countdown_minutes > 1 {
-                        app.countdown_minutes -= 1;
-                        let seconds = app.countdown_minutes * 60;
-                        let _ = app
-                            .client
-                            .call(
-                                "config.update_pomodoro",
-                                Some(serde_json::json!({"countdown_default": seconds})),
-                            )
-                            .await;
-                        app.status_message =
-                            format!("Countdown default: {} minutes", app.countdown_minutes);
-                    }
-                }
-                _ => {}
-            }
-        }
-        KeyCode::Right | KeyCode::Char('l') => {
-            let selected_item = SettingsItem::ALL[app.selected_setting_index];
-            match selected_item {
-                SettingsItem::PomodoroWork
-                | SettingsItem::PomodoroShortBreak
-                | SettingsItem::PomodoroLongBreak => {
-                    app.adjust_pomodoro_setting(selected_item, 1).await?;
-                }
-                SettingsItem::CountdownDefault => {
-                    app.countdown_minutes += 1;
-                    let seconds = app.countdown_minutes * 60;
-                    let _ = app
-                        .client
-                        .call(
-                            "config.update_pomodoro",
-                            Some(serde_json::json!({"countdown_default": seconds})),
-                        )
-                        .await;
-                    app.status_message =
-                        format!("Countdown default: {} minutes", app.countdown_minutes);
-                }
-                _ => {}
-            }
-        }
+        KeyCode::Char(' ') | KeyCode::Enter => app.activate_selected_setting().await?,
+        KeyCode::Left | KeyCode::Char('h') => app.adjust_selected_setting(-1).await?,
+        KeyCode::Right | KeyCode::Char('l') => app.adjust_selected_setting(1).await?,
+        KeyCode::Char('B') => app.open_branch_picker().await?,
+        KeyCode::Char('E') => app.open_config_file_prompt(false),
+        KeyCode::Char('I') => app.open_config_file_prompt(true),
         _ => {}
     }
     Ok(())
 }
 
-async fn handle_timers_keys(app: &mut App, code: KeyCode) -> Result<()> {
+async fn handle_timers_keys(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    let keys = app.keybindings.clone();
+    let is = |action| keys.is(action, code, modifiers);
     match code {
+        _ if is(Action::TimerPause) => {
+            if let Some(timer_id) = app.get_selected_timer_id() {
+                app.toggle_pause_by_id(&timer_id).await?;
+            }
+        }
+        _ if is(Action::TimerStop) => {
+            if let Some(timer_id) = app.get_selected_timer_id() {
+                app.stop_timer_by_id(&timer_id).await?;
+                if app.selected_timer_index >= app.active_timers.len()
+                    && !app.active_timers.is_empty()
+                {
+                    app.selected_timer_index = app.active_timers.len() - 1;
+                }
+            }
+        }
         KeyCode::Up | KeyCode::Char('k') => {
             app.selected_timer_index = app.selected_timer_index.saturating_sub(1);
         }
@@ -1460,21 +1960,6 @@ async fn handle_timers_keys(app: &mut App, code: KeyCode) -> Result<()> {
             app.refresh_timer().await?;
             app.status_message = "Timers refreshed".to_string();
         }
-        KeyCode::Char(' ') => {
-            if let Some(timer_id) = app.get_selected_timer_id() {
-                app.toggle_pause_by_id(&timer_id).await?;
-            }
-        }
-        KeyCode::Char('x') => {
-            if let Some(timer_id) = app.get_selected_timer_id() {
-                app.stop_timer_by_id(&timer_id).await?;
-                if app.selected_timer_index >= app.active_timers.len()
-                    && !app.active_timers.is_empty()
-                {
-                    app.selected_timer_index = app.active_timers.len() - 1;
-                }
-            }
-        }
         KeyCode::Char('m') => {
             app.status_message = "MOOOOO! 🐮".to_string();
             audio_alert(app);
@@ -1489,15 +1974,20 @@ async fn handle_logs_keys(app: &mut App, code: KeyCode) -> Result<()> {
         KeyCode::Up | KeyCode::Char('k') => {
             app.selected_log_index = app.selected_log_index.saturating_sub(1);
         }
-        KeyCode::Down | KeyCode::Char('j') => {
-            if app.selected_log_index < app.log_lines.len().saturating_sub(1) {
-                app.selected_log_index += 1;
-            }
+        KeyCode::Down | KeyCode::Char('j')
+            if app.selected_log_index < app.visible_logs().len().saturating_sub(1) =>
+        {
+            app.selected_log_index += 1;
         }
         KeyCode::Char('g') => app.selected_log_index = 0,
         KeyCode::Char('G') => {
-            app.selected_log_index = app.log_lines.len().saturating_sub(1);
+            app.selected_log_index = app.visible_logs().len().saturating_sub(1);
         }
+        KeyCode::Enter => app.log_detail_open = !app.log_detail_open,
+        KeyCode::Char('t') => app.cycle_log_target_filter(),
+        KeyCode::Char('s') => app.log_columns.timestamp = !app.log_columns.timestamp,
+        KeyCode::Char('l') => app.log_columns.level = !app.log_columns.level,
+        KeyCode::Char('o') => app.log_columns.target = !app.log_columns.target,
         KeyCode::Char('r') => {
             app.refresh_logs().await?;
         }
@@ -1510,7 +2000,8 @@ async fn handle_logs_keys(app: &mut App, code: KeyCode) -> Result<()> {
 
             if log_file_path.exists() {
                 fs::write(&log_file_path, "")?;
-                app.log_lines.clear();
+                app.log_records.clear();
+                app.selected_log_index = 0;
                 app.status_message = "Logs cleared".to_string();
             }
         }
@@ -1597,55 +2088,58 @@ async fn main() -> Result<()> {
 
     let args = Args::parse();
 
-    let profile_id = args.profile.unwrap_or_else(|| "default".to_string());
+    let client = MooTimerClient::new(&args.socket).with_client_name("mootimer-tui");
 
-    let client = MooTimerClient::new(&args.socket);
+    if args.status {
+        return print_status(&client, &args).await;
+    }
+
+    if client.health_check().await.is_err() {
+        eprintln!("🐮 MooTimer daemon not running. Starting it...");
+        match ensure_daemon(&args.socket, SpawnOptions::default()).await {
+            Ok(daemon) => {
+                eprintln!("✓ Daemon started (PID: {})", daemon.pid().unwrap_or(0));
+            }
+            Err(e) => {
+                eprintln!("✗ Failed to start daemon: {:#}", e);
+                eprintln!("\nPlease ensure 'mootimerd' is in your PATH or start it manually:");
+                eprintln!("  cargo run --bin mootimerd");
+                std::process::exit(1);
+            }
+        }
+    }
 
     let profiles = match client.profile_list().await {
         Ok(profiles) => profiles,
-        Err(_) => {
-            eprintln!("🐮 MooTimer daemon not running. Starting it...");
-
-            let daemon_result = tokio::process::Command::new("mootimerd")
-                .arg("--socket")
-                .arg(&args.socket)
-                .stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::null())
-                .spawn();
-
-            match daemon_result {
-                Ok(mut child) => {
-                    eprintln!("✓ Daemon started (PID: {})", child.id().unwrap_or(0));
-
-                    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-                    match client.profile_list().await {
-                        Ok(profiles) => profiles,
-                        Err(e) => {
-                            eprintln!("✗ Failed to connect to daemon after starting: {}", e);
-                            eprintln!("\nTry running manually: mootimerd");
-                            let _ = child.kill().await;
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("✗ Failed to start daemon: {}", e);
-                    eprintln!("\nPlease ensure 'mootimerd' is in your PATH or start it manually:");
-                    eprintln!("  cargo run --bin mootimerd");
-                    std::process::exit(1);
-                }
-            }
+        Err(e) => {
+            eprintln!("✗ Failed to connect to daemon: {}", e);
+            std::process::exit(1);
         }
     };
 
-    let profile_exists = profiles
-        .as_array()
-        .map(|arr| {
-            arr.iter()
-                .any(|p| p.get("id").and_then(|id| id.as_str()) == Some(&profile_id))
+    let config = client.call("config.get", None).await.ok();
+    let needs_setup = args.profile.is_none()
+        && profiles
+            .as_array()
+            .is_some_and(|profiles| profiles.is_empty());
+    let mut profile_id = args
+        .profile
+        .or_else(|| {
+            config
+                .as_ref()
+                .and_then(|c| c["default_profile"].as_str())
+                .map(str::to_string)
         })
-        .unwrap_or(false);
+        .unwrap_or_else(|| "default".to_string());
+
+    let profile_exists = needs_setup
+        || profiles
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .any(|p| p.get("id").and_then(|id| id.as_str()) == Some(&profile_id))
+            })
+            .unwrap_or(false);
 
     if !profile_exists {
         eprintln!("Profile '{}' not found. Creating it...", profile_id);
@@ -1669,20 +2163,37 @@ async fn main() -> Result<()> {
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
+    if needs_setup {
+        match run_setup_wizard(&mut terminal, &client).await? {
+            Some(id) => profile_id = id,
+            None => return restore_terminal(&mut terminal),
+        }
+    }
+    apply_timezone(&client).await;
+
     let mut notif_rx = client.subscribe_notifications().await?;
+    client.events_subscribe(&["*"]).await?;
 
     let mut app = App::new(client, profile_id);
+    app.terminal_title = TerminalTitle::new(args.title && TerminalTitle::supported());
 
     app.refresh_all().await?;
 
     let mut last_tick = std::time::Instant::now();
+    let mut reconnect = tokio::time::interval(Duration::from_secs(2));
 
     loop {
         terminal.draw(|f| ui::draw(f, &mut app))?;
+        app.update_terminal_title();
 
         if app.should_quit {
             break;
@@ -1692,6 +2203,14 @@ async fn main() -> Result<()> {
             Some(notification) = notif_rx.recv() => {
                 let _ = handle_daemon_notification(&mut app, notification).await;
             }
+            _ = reconnect.tick() => {
+                if app.daemon_connected && !app.client.is_connected().await {
+                    app.mark_disconnected(&anyhow::anyhow!("connection closed"));
+                }
+                if !app.daemon_connected {
+                    app.try_reconnect().await?;
+                }
+            }
             _ = tokio::time::sleep(Duration::from_millis(16)) => {
                 if last_tick.elapsed() >= Duration::from_millis(30) {
                     app.tomato_state.tick();
@@ -1699,17 +2218,32 @@ async fn main() -> Result<()> {
                     last_tick = std::time::Instant::now();
                 }
 
+                let result = app.poll_sync().await;
+                report_error(&mut app, result);
+
+                if app.input_mode == InputMode::Normal
+                    && let Some(press) = app.count.expire(std::time::Instant::now())
+                {
+                    let result = handle_normal_key(&mut app, press.code, press.modifiers).await;
+                    report_error(&mut app, result);
+                }
+
                 if event::poll(Duration::from_millis(0))? {
                     let event = event::read()?;
                     info!(?event, "Received event");
                     match event {
                         Event::Key(key) if key.kind == KeyEventKind::Press => {
-                            handle_key_event(&mut app, key.code, key.modifiers).await?;
+                            let result = handle_key_event(&mut app, key.code, key.modifiers).await;
+                            report_error(&mut app, result);
+                        }
+                        Event::Paste(text) if app.input_mode != InputMode::Normal => {
+                            app.paste_text(&text);
                         }
                         Event::Mouse(mouse) => {
                             let (w, h) = size()?;
                             let rect = ratatui::layout::Rect::new(0, 0, w, h);
-                            handle_mouse_event(&mut app, mouse, rect).await?;
+                            let result = handle_mouse_event(&mut app, mouse, rect).await;
+                            report_error(&mut app, result);
                         }
                         Event::Resize(width, height) => {
                             info!(width, height, "Terminal resized");
@@ -1726,13 +2260,72 @@ async fn main() -> Result<()> {
 
     shutdown_sync(&app.client).await;
 
+    app.terminal_title.restore(&mut io::stdout());
+    restore_terminal(&mut terminal)
+}
+
+fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
-
     Ok(())
 }
+
+/// Runs the first-run wizard until the user finishes or quits. Returns the
+/// id of the profile it created, or `None` if the user quit.
+async fn run_setup_wizard(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    client: &MooTimerClient,
+) -> Result<Option<String>> {
+    let mut wizard = wizard::SetupWizard::new();
+
+    loop {
+        terminal.draw(|f| ui::draw_wizard(f, &wizard))?;
+
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(None);
+        }
+
+        match wizard.handle_key(key.code) {
+            wizard::WizardAction::Continue => {}
+            wizard::WizardAction::Cancel => return Ok(None),
+            wizard::WizardAction::Done => {
+                let answers = wizard.answers();
+                match wizard::apply(client, &answers).await {
+                    Ok(()) => {
+                        info!(profile = %answers.profile_id, "First-run setup complete");
+                        return Ok(Some(answers.profile_id));
+                    }
+                    Err(e) => wizard.error = Some(format!("Setup failed: {}", e)),
+                }
+            }
+        }
+    }
+}
+
+/// Points chrono's `Local` at `tui.timezone`, if one is configured. Runs
+/// once before the UI starts, while nothing else is reading the environment.
+async fn apply_timezone(client: &MooTimerClient) {
+    let Ok(config) = client.call("config.get", None).await else {
+        return;
+    };
+    if let Some(timezone) = config["tui"]["timezone"].as_str() {
+        unsafe {
+            std::env::set_var("TZ", timezone);
+        }
+    }
+}
@@ -0,0 +1,655 @@
+//! Declarative schema for the Settings view.
+//!
+//! Every editable setting is described once here. The Settings view renders
+//! and edits items generically from this schema, so exposing a new daemon
+//! setting only requires adding a `SettingDef` to the right section.
+
+use mootimer_core::messages::LOCALES;
+use mootimer_core::models::config::{
+    MAX_COUNTDOWN_PRESET_MINUTES, MAX_COUNTDOWN_PRESETS, MAX_TIMERS_PER_PROFILE,
+};
+use serde_json::{Value, json};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingKind {
+    Bool,
+    /// Stored in seconds, edited and displayed in minutes.
+    Minutes {
+        min: u64,
+        max: u64,
+    },
+    Number {
+        min: u64,
+        max: u64,
+    },
+    Enum(&'static [&'static str]),
+    /// A list of whole minutes, typed as `15, 30, 52`.
+    MinuteList {
+        max: u64,
+        max_len: usize,
+    },
+    /// Free text that may not be empty.
+    Text,
+    /// Free text that may be unset. An empty value is sent as
+    /// `clear_<key>: true`, since `null` means "unchanged" to the daemon.
+    OptionalText,
+    /// A dashboard animation name. The choices depend on the files in the
+    /// animations folder, so the app cycles through them itself.
+    Animation,
+    /// Runs an action on activation instead of holding a value.
+    Action,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalSetting {
+    AudioAlerts,
+    CowModal,
+    SmartTaskOrder,
+    TerminalTitle,
+    ConfirmEntryDelete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingAction {
+    SyncInit,
+    SyncNow,
+    /// Shows the checked-out branch and opens the branch picker.
+    BranchPicker,
+    /// Shows whether anything is uncommitted and opens the changes modal.
+    SyncChanges,
+    /// Shows how many records a sync left conflicted and opens the
+    /// conflicts modal.
+    SyncConflicts,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingTarget {
+    /// Lives in the daemon config at `section.key` and is persisted by
+    /// calling `rpc` with `{ key: value }`.
+    Config {
+        rpc: &'static str,
+        section: &'static str,
+        key: &'static str,
+    },
+    /// Lives only in the TUI process.
+    Local(LocalSetting),
+    Action(SettingAction),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SettingDef {
+    pub label: &'static str,
+    pub kind: SettingKind,
+    pub target: SettingTarget,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SettingsSection {
+    pub title: &'static str,
+    pub items: &'static [SettingDef],
+}
+
+const fn pomodoro(label: &'static str, key: &'static str, kind: SettingKind) -> SettingDef {
+    SettingDef {
+        label,
+        kind,
+        target: SettingTarget::Config {
+            rpc: "config.update_pomodoro",
+            section: "pomodoro",
+            key,
+        },
+    }
+}
+
+const fn sync(label: &'static str, key: &'static str, kind: SettingKind) -> SettingDef {
+    SettingDef {
+        label,
+        kind,
+        target: SettingTarget::Config {
+            rpc: "config.update_sync",
+            section: "sync",
+            key,
+        },
+    }
+}
+
+const DURATION: SettingKind = SettingKind::Minutes { min: 1, max: 120 };
+
+pub const SECTIONS: &[SettingsSection] = &[
+    SettingsSection {
+        title: "Pomodoro",
+        items: &[
+            pomodoro("Work Duration", "work_duration", DURATION),
+            pomodoro("Short Break", "short_break", DURATION),
+            pomodoro("Long Break", "long_break", DURATION),
+            pomodoro(
+                "Sessions per Long Break",
+                "sessions_until_long_break",
+                SettingKind::Number { min: 1, max: 12 },
+            ),
+            pomodoro(
+                "Countdown Timer",
+                "countdown_default",
+                SettingKind::Minutes { min: 1, max: 180 },
+            ),
+            SettingDef {
+                label: "Countdown Presets",
+                kind: SettingKind::MinuteList {
+                    max: MAX_COUNTDOWN_PRESET_MINUTES,
+                    max_len: MAX_COUNTDOWN_PRESETS,
+                },
+                target: SettingTarget::Config {
+                    rpc: "config.update_tui",
+                    section: "tui",
+                    key: "countdown_presets",
+                },
+            },
+            pomodoro(
+                "Auto-start Next Session",
+                "auto_start_next_session",
+                SettingKind::Bool,
+            ),
+        ],
+    },
+    SettingsSection {
+        title: "Notifications",
+        items: &[
+            SettingDef {
+                label: "Audio Alerts",
+                kind: SettingKind::Bool,
+                target: SettingTarget::Local(LocalSetting::AudioAlerts),
+            },
+            SettingDef {
+                label: "Cow Modal",
+                kind: SettingKind::Bool,
+                target: SettingTarget::Local(LocalSetting::CowModal),
+            },
+        ],
+    },
+    SettingsSection {
+        title: "Sync",
+        items: &[
+            sync("Auto-Commit", "auto_commit", SettingKind::Bool),
+            sync("Auto-Push", "auto_push", SettingKind::Bool),
+            sync("Remote URL", "remote_url", SettingKind::OptionalText),
+            SettingDef {
+                label: "Git Repository",
+                kind: SettingKind::Action,
+                target: SettingTarget::Action(SettingAction::SyncInit),
+            },
+            SettingDef {
+                label: "Branch",
+                kind: SettingKind::Action,
+                target: SettingTarget::Action(SettingAction::BranchPicker),
+            },
+            SettingDef {
+                label: "Pending Changes",
+                kind: SettingKind::Action,
+                target: SettingTarget::Action(SettingAction::SyncChanges),
+            },
+            SettingDef {
+                label: "Conflicts",
+                kind: SettingKind::Action,
+                target: SettingTarget::Action(SettingAction::SyncConflicts),
+            },
+            SettingDef {
+                label: "Sync Now",
+                kind: SettingKind::Action,
+                target: SettingTarget::Action(SettingAction::SyncNow),
+            },
+        ],
+    },
+    SettingsSection {
+        title: "Daemon",
+        items: &[
+            SettingDef {
+                label: "Log Level",
+                kind: SettingKind::Enum(&["error", "warn", "info", "debug", "trace"]),
+                target: SettingTarget::Config {
+                    rpc: "config.update_daemon",
+                    section: "daemon",
+                    key: "log_level",
+                },
+            },
+            SettingDef {
+                label: "Max Timers per Profile",
+                kind: SettingKind::Number {
+                    min: 1,
+                    max: MAX_TIMERS_PER_PROFILE as u64,
+                },
+                target: SettingTarget::Config {
+                    rpc: "config.update_daemon",
+                    section: "daemon",
+                    key: "max_timers_per_profile",
+                },
+            },
+            SettingDef {
+                label: "Refuse Timers on Blocked Tasks",
+                kind: SettingKind::Bool,
+                target: SettingTarget::Config {
+                    rpc: "config.update_daemon",
+                    section: "daemon",
+                    key: "strict_task_blocking",
+                },
+            },
+            SettingDef {
+                label: "Language",
+                kind: SettingKind::Enum(LOCALES),
+                target: SettingTarget::Config {
+                    rpc: "config.update_daemon",
+                    section: "daemon",
+                    key: "locale",
+                },
+            },
+            SettingDef {
+                label: "Record Local Usage Counts",
+                kind: SettingKind::Bool,
+                target: SettingTarget::Config {
+                    rpc: "config.update_daemon",
+                    section: "daemon",
+                    key: "usage_metrics",
+                },
+            },
+        ],
+    },
+    SettingsSection {
+        title: "Interface",
+        items: &[
+            SettingDef {
+                label: "Status Bar Format",
+                kind: SettingKind::Text,
+                target: SettingTarget::Config {
+                    rpc: "config.update_tui",
+                    section: "tui",
+                    key: "status_bar_format",
+                },
+            },
+            SettingDef {
+                label: "Pomodoro Animation",
+                kind: SettingKind::Animation,
+                target: SettingTarget::Config {
+                    rpc: "config.update_tui",
+                    section: "tui",
+                    key: "pomodoro_animation",
+                },
+            },
+            SettingDef {
+                label: "Countdown Animation",
+                kind: SettingKind::Animation,
+                target: SettingTarget::Config {
+                    rpc: "config.update_tui",
+                    section: "tui",
+                    key: "countdown_animation",
+                },
+            },
+            SettingDef {
+                label: "Smart Task Order",
+                kind: SettingKind::Bool,
+                target: SettingTarget::Local(LocalSetting::SmartTaskOrder),
+            },
+            SettingDef {
+                label: "Terminal Title",
+                kind: SettingKind::Bool,
+                target: SettingTarget::Local(LocalSetting::TerminalTitle),
+            },
+            SettingDef {
+                label: "Confirm Entry Deletion",
+                kind: SettingKind::Bool,
+                target: SettingTarget::Local(LocalSetting::ConfirmEntryDelete),
+            },
+        ],
+    },
+];
+
+/// One rendered row of the Settings view.
+#[derive(Debug, Clone, Copy)]
+pub enum SettingsRow {
+    Header(&'static str),
+    Item(usize, &'static SettingDef),
+}
+
+pub fn items() -> impl Iterator<Item = &'static SettingDef> {
+    SECTIONS.iter().flat_map(|s| s.items.iter())
+}
+
+pub fn item_count() -> usize {
+    items().count()
+}
+
+pub fn item(index: usize) -> Option<&'static SettingDef> {
+    items().nth(index)
+}
+
+pub fn rows() -> Vec<SettingsRow> {
+    let mut rows = Vec::new();
+    let mut index = 0;
+    for section in SECTIONS {
+        rows.push(SettingsRow::Header(section.title));
+        for def in section.items {
+            rows.push(SettingsRow::Item(index, def));
+            index += 1;
+        }
+    }
+    rows
+}
+
+/// Maps a rendered row back to the flat item index, skipping headers.
+pub fn item_at_row(row: usize) -> Option<usize> {
+    match rows().get(row) {
+        Some(SettingsRow::Item(index, _)) => Some(*index),
+        _ => None,
+    }
+}
+
+pub fn row_of_item(index: usize) -> usize {
+    rows()
+        .iter()
+        .position(|r| matches!(r, SettingsRow::Item(i, _) if *i == index))
+        .unwrap_or(0)
+}
+
+impl SettingDef {
+    /// Reads this setting's value from a `config.get` result.
+    pub fn read(&self, config: &Value) -> Option<Value> {
+        match self.target {
+            SettingTarget::Config { section, key, .. } => {
+                config.get(section).and_then(|s| s.get(key)).cloned()
+            }
+            _ => None,
+        }
+    }
+
+    /// Builds the RPC method and params that persist `value`.
+    pub fn update_request(&self, value: Value) -> Option<(&'static str, Value)> {
+        match self.target {
+            SettingTarget::Config { rpc, key, .. }
+                if self.kind == SettingKind::OptionalText && value.is_null() =>
+            {
+                Some((rpc, json!({ format!("clear_{}", key): true })))
+            }
+            SettingTarget::Config { rpc, key, .. } => Some((rpc, json!({ key: value }))),
+            _ => None,
+        }
+    }
+
+    pub fn display(&self, value: Option<&Value>) -> String {
+        match (self.kind, value) {
+            (SettingKind::Bool, Some(v)) => {
+                if v.as_bool().unwrap_or(false) {
+                    "Enabled".to_string()
+                } else {
+                    "Disabled".to_string()
+                }
+            }
+            (SettingKind::Minutes { .. }, Some(v)) => {
+                format!("{} minutes", v.as_u64().unwrap_or(0) / 60)
+            }
+            (SettingKind::Number { .. }, Some(v)) => v.to_string(),
+            (SettingKind::MinuteList { .. }, Some(Value::Array(items))) if items.is_empty() => {
+                "None".to_string()
+            }
+            (SettingKind::MinuteList { .. }, Some(Value::Array(items))) => items
+                .iter()
+                .map(|m| format!("{}m", m))
+                .collect::<Vec<_>>()
+                .join(", "),
+            (SettingKind::Enum(_), Some(Value::String(s)))
+            | (SettingKind::Text, Some(Value::String(s)))
+            | (SettingKind::OptionalText, Some(Value::String(s)))
+            | (SettingKind::Animation, Some(Value::String(s))) => s.clone(),
+            (SettingKind::OptionalText, _) => "Not set".to_string(),
+            _ => "-".to_string(),
+        }
+    }
+
+    /// The value as it should appear pre-filled in the input modal.
+    pub fn edit_text(&self, value: Option<&Value>) -> String {
+        match (self.kind, value) {
+            (SettingKind::Bool, Some(v)) => {
+                if v.as_bool().unwrap_or(false) {
+                    "yes".to_string()
+                } else {
+                    "no".to_string()
+                }
+            }
+            (SettingKind::Minutes { .. }, Some(v)) => (v.as_u64().unwrap_or(0) / 60).to_string(),
+            (SettingKind::Number { .. }, Some(v)) => v.to_string(),
+            (SettingKind::MinuteList { .. }, Some(Value::Array(items))) => items
+                .iter()
+                .map(|m| m.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            (_, Some(Value::String(s))) => s.clone(),
+            _ => String::new(),
+        }
+    }
+
+    pub fn hint(&self) -> &'static str {
+        if self.target == SettingTarget::Action(SettingAction::BranchPicker) {
+            return "<[B] or [Enter] to switch>";
+        }
+        if self.target == SettingTarget::Action(SettingAction::SyncChanges) {
+            return "<[Enter] to view>";
+        }
+        if self.target == SettingTarget::Action(SettingAction::SyncConflicts) {
+            return "<[Enter] to resolve>";
+        }
+        match self.kind {
+            SettingKind::Bool => "<[Space] to toggle>",
+            SettingKind::Minutes { .. } | SettingKind::Number { .. } => {
+                "<[h/l] to change, [Enter] to type>"
+            }
+            SettingKind::Enum(_) | SettingKind::Animation => "<[h/l] to cycle>",
+            SettingKind::MinuteList { .. } | SettingKind::Text | SettingKind::OptionalText => {
+                "<[Enter] to edit>"
+            }
+            SettingKind::Action => "<[Enter] to run>",
+        }
+    }
+
+    /// Parses text typed into the input modal.
+    pub fn parse_input(&self, input: &str) -> Result<Value, String> {
+        let input = input.trim();
+        match self.kind {
+            SettingKind::Bool => match input.to_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Ok(json!(true)),
+                "false" | "no" | "off" | "0" => Ok(json!(false)),
+                _ => Err("Expected yes or no".to_string()),
+            },
+            SettingKind::Minutes { min, max } => {
+                let minutes: u64 = input.parse().map_err(|_| "Expected minutes".to_string())?;
+                if minutes < min || minutes > max {
+                    return Err(format!("Must be between {} and {} minutes", min, max));
+                }
+                Ok(json!(minutes * 60))
+            }
+            SettingKind::Number { min, max } => {
+                let n: u64 = input.parse().map_err(|_| "Expected a number".to_string())?;
+                if n < min || n > max {
+                    return Err(format!("Must be between {} and {}", min, max));
+                }
+                Ok(json!(n))
+            }
+            SettingKind::Enum(options) => options
+                .iter()
+                .find(|o| o.eq_ignore_ascii_case(input))
+                .map(|o| json!(o))
+                .ok_or_else(|| format!("Must be one of: {}", options.join(", "))),
+            SettingKind::MinuteList { max, max_len } => {
+                let minutes = input
+                    .split([',', ' '])
+                    .filter(|part| !part.is_empty())
+                    .map(|part| {
+                        let minutes: u64 = part
+                            .trim_end_matches('m')
+                            .parse()
+                            .map_err(|_| format!("'{}' is not a number of minutes", part))?;
+                        if minutes < 1 || minutes > max {
+                            return Err(format!("Must be between 1 and {} minutes", max));
+                        }
+                        Ok(minutes)
+                    })
+                    .collect::<Result<Vec<u64>, String>>()?;
+                if minutes.len() > max_len {
+                    return Err(format!("At most {} entries", max_len));
+                }
+                Ok(json!(minutes))
+            }
+            SettingKind::Text => {
+                if input.is_empty() {
+                    Err("Cannot be empty".to_string())
+                } else {
+                    Ok(json!(input))
+                }
+            }
+            SettingKind::OptionalText => {
+                if input.is_empty() {
+                    Ok(Value::Null)
+                } else {
+                    Ok(json!(input))
+                }
+            }
+            SettingKind::Animation => {
+                if input.is_empty() || input.contains(['/', '\\']) {
+                    Err("Expected an animation name".to_string())
+                } else {
+                    Ok(json!(input))
+                }
+            }
+            SettingKind::Action => Err("Not editable".to_string()),
+        }
+    }
+
+    /// Steps the value by `delta` (h/l), clamped to the kind's bounds.
+    pub fn step(&self, value: Option<&Value>, delta: i64) -> Option<Value> {
+        match self.kind {
+            SettingKind::Bool => Some(json!(!value.and_then(|v| v.as_bool()).unwrap_or(false))),
+            SettingKind::Minutes { min, max } => {
+                let minutes = value.and_then(|v| v.as_u64()).unwrap_or(0) / 60;
+                let next = (minutes as i64 + delta).clamp(min as i64, max as i64) as u64;
+                Some(json!(next * 60))
+            }
+            SettingKind::Number { min, max } => {
+                let n = value.and_then(|v| v.as_u64()).unwrap_or(min);
+                let next = (n as i64 + delta).clamp(min as i64, max as i64) as u64;
+                Some(json!(next))
+            }
+            SettingKind::Enum(options) => {
+                let current = value
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| options.iter().position(|o| *o == s))
+                    .unwrap_or(0);
+                let len = options.len() as i64;
+                let next = (current as i64 + delta).rem_euclid(len) as usize;
+                Some(json!(options[next]))
+            }
+            SettingKind::MinuteList { .. }
+            | SettingKind::Text
+            | SettingKind::OptionalText
+            | SettingKind::Animation
+            | SettingKind::Action => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mootimer_core::models::Config;
+
+    fn config_with_remote() -> Value {
+        let mut config = Config::default();
+        config.sync.remote_url = Some("git@example.com:me/timers.git".to_string());
+        serde_json::to_value(config).unwrap()
+    }
+
+    /// Mirrors what the daemon's `config.update_*` handlers do: merge the
+    /// params into the matching config section.
+    fn apply(config: &mut Value, def: &SettingDef, params: &Value) {
+        let SettingTarget::Config { section, .. } = def.target else {
+            panic!("{} is not a config setting", def.label);
+        };
+        let section = config.get_mut(section).unwrap().as_object_mut().unwrap();
+        for (k, v) in params.as_object().unwrap() {
+            match k.strip_prefix("clear_") {
+                Some(key) => section.insert(key.to_string(), Value::Null),
+                None => section.insert(k.clone(), v.clone()),
+            };
+        }
+    }
+
+    #[test]
+    fn test_every_config_item_round_trips() {
+        for def in items() {
+            let SettingTarget::Config { rpc, section, key } = def.target else {
+                continue;
+            };
+            assert!(rpc.starts_with("config.update_"), "{}", def.label);
+
+            let mut config = config_with_remote();
+            let current = def
+                .read(&config)
+                .unwrap_or_else(|| panic!("{}.{} missing from config", section, key));
+
+            let next = match def.kind {
+                SettingKind::Text | SettingKind::OptionalText => {
+                    json!("https://example.com/other.git")
+                }
+                SettingKind::Animation => json!("none"),
+                SettingKind::MinuteList { .. } => def.parse_input("15, 30, 52m").unwrap(),
+                _ => def.step(Some(&current), 1).unwrap(),
+            };
+            let (method, params) = def.update_request(next.clone()).unwrap();
+            assert_eq!(method, rpc);
+
+            apply(&mut config, def, &params);
+            let parsed: Config = serde_json::from_value(config.clone()).unwrap();
+            parsed.validate().unwrap();
+            assert_eq!(def.read(&config), Some(next), "{}", def.label);
+        }
+    }
+
+    #[test]
+    fn test_parse_input_matches_display() {
+        let config = config_with_remote();
+        for def in items() {
+            let Some(value) = def.read(&config) else {
+                continue;
+            };
+            let text = def.edit_text(Some(&value));
+            assert_eq!(def.parse_input(&text).unwrap(), value, "{}", def.label);
+        }
+    }
+
+    #[test]
+    fn test_rows_skip_headers() {
+        assert!(item_at_row(0).is_none());
+        assert_eq!(item_at_row(1), Some(0));
+        for i in 0..item_count() {
+            assert_eq!(item_at_row(row_of_item(i)), Some(i));
+        }
+    }
+
+    #[test]
+    fn test_clearing_optional_text() {
+        let def = items().find(|d| d.label == "Remote URL").unwrap();
+        let value = def.parse_input("  ").unwrap();
+        assert_eq!(def.display(Some(&value)), "Not set");
+        let (_, params) = def.update_request(value).unwrap();
+        assert_eq!(params, json!({ "clear_remote_url": true }));
+
+        let mut config = config_with_remote();
+        apply(&mut config, def, &params);
+        assert_eq!(def.read(&config), Some(Value::Null));
+
+        let def = items().find(|d| d.label == "Status Bar Format").unwrap();
+        assert!(def.parse_input("").is_err());
+    }
+
+    #[test]
+    fn test_enum_step_wraps() {
+        let def = items().find(|d| d.label == "Log Level").unwrap();
+        assert_eq!(def.step(Some(&json!("trace")), 1), Some(json!("error")));
+        assert_eq!(def.step(Some(&json!("error")), -1), Some(json!("trace")));
+    }
+}
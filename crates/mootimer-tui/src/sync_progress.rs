@@ -0,0 +1,75 @@
+//! A manual sync while it runs. `sync.sync` is awaited in a spawned task so
+//! the UI keeps drawing, and the status bar shows a spinner with the step
+//! the daemon last announced in a `sync.event`.
+//!
+//! Esc only stops the TUI waiting. The daemon carries on regardless, and
+//! the `finished` or `failed` event it sends at the end is reported then.
+
+use anyhow::Result;
+use mootimer_client::MooTimerClient;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const FRAME: Duration = Duration::from_millis(80);
+
+pub struct SyncProgress {
+    task: JoinHandle<()>,
+    reply: oneshot::Receiver<Result<Value>>,
+    started: Instant,
+    /// The `stage` of the last `sync.event`, e.g. "pulling".
+    stage: Option<String>,
+}
+
+impl SyncProgress {
+    pub fn start(client: MooTimerClient) -> Self {
+        let (tx, reply) = oneshot::channel();
+        let task = tokio::spawn(async move {
+            let _ = tx.send(client.call("sync.sync", None).await);
+        });
+        Self {
+            task,
+            reply,
+            started: Instant::now(),
+            stage: None,
+        }
+    }
+
+    pub fn set_stage(&mut self, stage: &str) {
+        self.stage = Some(stage.to_string());
+    }
+
+    /// The reply to `sync.sync`, once it is in.
+    pub fn try_reply(&mut self) -> Option<Result<Value>> {
+        match self.reply.try_recv() {
+            Ok(reply) => Some(reply),
+            Err(oneshot::error::TryRecvError::Empty) => None,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                Some(Err(anyhow::anyhow!("sync request was dropped")))
+            }
+        }
+    }
+
+    /// Stops waiting for the reply. The sync itself is up to the daemon.
+    pub fn abandon(self) {
+        self.task.abort();
+    }
+
+    /// "⠹ Syncing: pulling…", with the spinner frame for `now`.
+    pub fn label(&self, now: Instant) -> String {
+        let frame = now.duration_since(self.started).as_millis() / FRAME.as_millis();
+        let step = match self.stage.as_deref() {
+            None => "starting",
+            Some("stashing") => "stashing local changes",
+            Some("restoring") => "restoring local changes",
+            Some(stage) => stage,
+        };
+        format!(
+            "{} Syncing: {}…",
+            SPINNER[frame as usize % SPINNER.len()],
+            step
+        )
+    }
+}
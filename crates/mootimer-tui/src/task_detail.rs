@@ -0,0 +1,335 @@
+//! State and parsing for the task detail panel.
+//!
+//! The panel edits a copy of the task's JSON. Fields are kept as text while
+//! editing and only parsed on save, so a half-typed date does not get in
+//! the way; problems are reported next to the field that caused them.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use mootimer_core::models::TaskPriority;
+use serde_json::{Value, json};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailField {
+    Title,
+    Description,
+    Tags,
+    Priority,
+    DueDate,
+}
+
+impl DetailField {
+    pub const ALL: [DetailField; 5] = [
+        DetailField::Title,
+        DetailField::Description,
+        DetailField::Tags,
+        DetailField::Priority,
+        DetailField::DueDate,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DetailField::Title => "Title",
+            DetailField::Description => "Description",
+            DetailField::Tags => "Tags",
+            DetailField::Priority => "Priority",
+            DetailField::DueDate => "Due Date",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskDetail {
+    /// The task as loaded; fields not shown in the panel are sent back as-is.
+    pub task: Value,
+    pub field: DetailField,
+    pub title: String,
+    pub description: String,
+    pub tags: String,
+    pub priority: TaskPriority,
+    pub due_date: String,
+    /// A validation or save error, and the field it belongs to.
+    pub error: Option<(Option<DetailField>, String)>,
+}
+
+impl TaskDetail {
+    pub fn new(task: Value) -> Self {
+        let text = |key: &str| {
+            task.get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        let tags = task
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        let priority = task
+            .get("priority")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        Self {
+            title: text("title"),
+            description: text("description"),
+            tags,
+            priority,
+            due_date: text("due_date"),
+            task,
+            field: DetailField::Title,
+            error: None,
+        }
+    }
+
+    pub fn next_field(&mut self) {
+        self.move_field(1);
+    }
+
+    pub fn previous_field(&mut self) {
+        self.move_field(DetailField::ALL.len() - 1);
+    }
+
+    fn move_field(&mut self, step: usize) {
+        let index = DetailField::ALL
+            .iter()
+            .position(|f| *f == self.field)
+            .unwrap_or(0);
+        self.field = DetailField::ALL[(index + step) % DetailField::ALL.len()];
+    }
+
+    fn text_mut(&mut self) -> Option<&mut String> {
+        match self.field {
+            DetailField::Title => Some(&mut self.title),
+            DetailField::Description => Some(&mut self.description),
+            DetailField::Tags => Some(&mut self.tags),
+            DetailField::DueDate => Some(&mut self.due_date),
+            DetailField::Priority => None,
+        }
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        if let Some(text) = self.text_mut() {
+            text.push(c);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(text) = self.text_mut() {
+            text.pop();
+        }
+    }
+
+    /// Starts a new line in the description; other fields are single-line.
+    pub fn newline(&mut self) {
+        if self.field == DetailField::Description {
+            self.description.push('\n');
+        }
+    }
+
+    /// Clears the current line of the focused field (Ctrl+U).
+    pub fn clear_line(&mut self) {
+        if let Some(text) = self.text_mut() {
+            let keep = text.rfind('\n').map_or(0, |i| i + 1);
+            text.truncate(keep);
+        }
+    }
+
+    pub fn cycle_priority(&mut self, forward: bool) {
+        self.priority = if forward {
+            self.priority.next()
+        } else {
+            self.priority.previous()
+        };
+    }
+
+    /// Builds the updated task for `task.update`, or records an error on the
+    /// offending field.
+    pub fn build(&mut self, today: NaiveDate) -> Option<Value> {
+        self.error = None;
+
+        let title = self.title.trim();
+        if title.is_empty() {
+            self.error = Some((
+                Some(DetailField::Title),
+                "Title cannot be empty".to_string(),
+            ));
+            return None;
+        }
+
+        let due_date = match parse_due_date(&self.due_date, today) {
+            Ok(date) => date,
+            Err(e) => {
+                self.error = Some((Some(DetailField::DueDate), e));
+                return None;
+            }
+        };
+
+        let description = self.description.trim_end();
+        let mut task = self.task.clone();
+        let obj = task.as_object_mut()?;
+        obj.insert("title".to_string(), json!(title));
+        obj.insert(
+            "description".to_string(),
+            if description.trim().is_empty() {
+                Value::Null
+            } else {
+                json!(description)
+            },
+        );
+        obj.insert("tags".to_string(), json!(parse_tags(&self.tags)));
+        obj.insert("priority".to_string(), json!(self.priority));
+        obj.insert("due_date".to_string(), json!(due_date));
+        Some(task)
+    }
+}
+
+/// Splits comma-separated tags, dropping blanks and duplicates.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+fn parse_weekday(input: &str) -> Option<Weekday> {
+    match input {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Parses a due date relative to `today`. Accepts `YYYY-MM-DD`, `today`,
+/// `tomorrow`, `in N days`, and weekday names with an optional `next`
+/// (both meaning the first such day after today). Empty clears the date.
+pub fn parse_due_date(input: &str, today: NaiveDate) -> Result<Option<NaiveDate>, String> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() || input == "none" {
+        return Ok(None);
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Ok(Some(date));
+    }
+
+    match input.as_str() {
+        "today" => return Ok(Some(today)),
+        "tomorrow" => return Ok(Some(today + Duration::days(1))),
+        _ => {}
+    }
+
+    if let Some(days) = input
+        .strip_prefix("in ")
+        .and_then(|rest| rest.strip_suffix(" days").or(rest.strip_suffix(" day")))
+        .and_then(|n| n.trim().parse::<i64>().ok())
+    {
+        return Ok(Some(today + Duration::days(days)));
+    }
+
+    let day_name = input.strip_prefix("next ").unwrap_or(&input);
+    if let Some(weekday) = parse_weekday(day_name.trim()) {
+        let ahead = (weekday.num_days_from_monday() as i64
+            - today.weekday().num_days_from_monday() as i64)
+            .rem_euclid(7);
+        let ahead = if ahead == 0 { 7 } else { ahead };
+        return Ok(Some(today + Duration::days(ahead)));
+    }
+
+    Err(format!(
+        "Unrecognised date '{}'. Try 2024-07-01, tomorrow or next friday",
+        input
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_due_date() {
+        // A Wednesday.
+        let today = date(2024, 6, 26);
+        let cases = [
+            ("2024-07-01", Some(date(2024, 7, 1))),
+            ("", None),
+            ("today", Some(today)),
+            ("Tomorrow", Some(date(2024, 6, 27))),
+            ("in 3 days", Some(date(2024, 6, 29))),
+            ("friday", Some(date(2024, 6, 28))),
+            ("next friday", Some(date(2024, 6, 28))),
+            ("next wed", Some(date(2024, 7, 3))),
+            ("monday", Some(date(2024, 7, 1))),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_due_date(input, today), Ok(expected), "{}", input);
+        }
+
+        assert!(parse_due_date("someday", today).is_err());
+        assert!(parse_due_date("2024-13-01", today).is_err());
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            parse_tags(" backend, urgent,,backend , "),
+            vec!["backend".to_string(), "urgent".to_string()]
+        );
+        assert!(parse_tags("").is_empty());
+    }
+
+    #[test]
+    fn test_build_reports_field_errors() {
+        let today = date(2024, 6, 26);
+        let mut detail = TaskDetail::new(json!({
+            "id": "t1",
+            "title": "Write docs",
+            "status": "todo",
+            "tags": ["docs"],
+        }));
+        assert_eq!(detail.tags, "docs");
+
+        detail.due_date = "whenever".to_string();
+        assert!(detail.build(today).is_none());
+        assert!(matches!(
+            detail.error,
+            Some((Some(DetailField::DueDate), _))
+        ));
+
+        detail.due_date = "next friday".to_string();
+        detail.field = DetailField::Description;
+        for c in "line one".chars() {
+            detail.insert_char(c);
+        }
+        detail.newline();
+        detail.insert_char('2');
+        detail.cycle_priority(true);
+
+        let task = detail.build(today).unwrap();
+        assert!(detail.error.is_none());
+        assert_eq!(task["status"], "todo");
+        assert_eq!(task["description"], "line one\n2");
+        assert_eq!(task["priority"], "low");
+        assert_eq!(task["due_date"], "2024-06-28");
+
+        detail.title = "  ".to_string();
+        assert!(detail.build(today).is_none());
+        assert!(matches!(detail.error, Some((Some(DetailField::Title), _))));
+    }
+}
@@ -0,0 +1,133 @@
+//! Mirrors the active timer into the terminal title (OSC 0), so the time
+//! left stays visible from other windows and tmux tabs.
+//!
+//! Before the first update the terminal's own title is pushed onto its
+//! title stack (XTWINOPS 22) and it is popped back (XTWINOPS 23) when the
+//! timer goes away or the TUI exits. Terminals without a title stack keep
+//! showing the last timer title instead.
+
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+/// Titles change at most this often; terminals redraw tabs on every write.
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+const PUSH_TITLE: &[u8] = b"\x1b[22;0t";
+const POP_TITLE: &[u8] = b"\x1b[23;0t";
+
+#[derive(Debug, Default)]
+pub struct TerminalTitle {
+    enabled: bool,
+    /// The original title is on the stack and ours is showing.
+    showing: bool,
+    last_title: Option<String>,
+    last_check: Option<Instant>,
+}
+
+impl TerminalTitle {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Self::default()
+        }
+    }
+
+    /// Titles are only written when stdout is a terminal.
+    pub fn supported() -> bool {
+        io::stdout().is_terminal()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, out: &mut impl Write, enabled: bool) {
+        if !enabled {
+            self.restore(out);
+        }
+        self.enabled = enabled;
+    }
+
+    /// Whether a second has passed since the last update, so `update` would
+    /// do anything. Lets callers skip building the title.
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.enabled
+            && self
+                .last_check
+                .is_none_or(|at| now.saturating_duration_since(at) >= MIN_INTERVAL)
+    }
+
+    /// Shows `title`, or puts the original title back when there is none.
+    pub fn update(&mut self, out: &mut impl Write, title: Option<&str>, now: Instant) {
+        if !self.is_due(now) {
+            return;
+        }
+        self.last_check = Some(now);
+
+        let Some(title) = title else {
+            self.restore(out);
+            return;
+        };
+        if self.last_title.as_deref() == Some(title) {
+            return;
+        }
+
+        let mut bytes = Vec::new();
+        if !self.showing {
+            bytes.extend_from_slice(PUSH_TITLE);
+        }
+        // Control characters would end the sequence early.
+        let clean: String = title.chars().filter(|c| !c.is_control()).collect();
+        bytes.extend_from_slice(format!("\x1b]0;{}\x07", clean).as_bytes());
+        if out.write_all(&bytes).and_then(|_| out.flush()).is_ok() {
+            self.showing = true;
+            self.last_title = Some(title.to_string());
+        }
+    }
+
+    /// Pops the original title back if ours is showing.
+    pub fn restore(&mut self, out: &mut impl Write) {
+        if self.showing {
+            let _ = out.write_all(POP_TITLE).and_then(|_| out.flush());
+            self.showing = false;
+            self.last_title = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_is_rate_limited_and_restored() {
+        let mut title = TerminalTitle::new(true);
+        let mut out = Vec::new();
+        let start = Instant::now();
+
+        title.update(&mut out, Some("🍅 17:32 — Fix login bug"), start);
+        assert_eq!(
+            out,
+            b"\x1b[22;0t\x1b]0;\xf0\x9f\x8d\x85 17:32 \xe2\x80\x94 Fix login bug\x07"
+        );
+
+        out.clear();
+        title.update(
+            &mut out,
+            Some("🍅 17:31"),
+            start + Duration::from_millis(500),
+        );
+        assert!(out.is_empty());
+        title.update(&mut out, Some("🍅 17:31"), start + Duration::from_secs(1));
+        assert_eq!(out, b"\x1b]0;\xf0\x9f\x8d\x85 17:31\x07");
+
+        out.clear();
+        title.update(&mut out, None, start + Duration::from_secs(2));
+        assert_eq!(out, POP_TITLE);
+
+        out.clear();
+        title.set_enabled(&mut out, false);
+        title.update(&mut out, Some("⏱ 00:01"), start + Duration::from_secs(5));
+        assert!(out.is_empty());
+    }
+}
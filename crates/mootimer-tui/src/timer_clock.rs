@@ -0,0 +1,96 @@
+//! Locally interpolated elapsed time for the running timer.
+//!
+//! The daemon reports elapsed time once a second. Between reports the TUI
+//! advances the last reported value with its own monotonic clock, so the
+//! display follows the render loop instead of jumping on each tick.
+
+use mootimer_core::models::{ActiveTimer, TimerDisplay, TimerState};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct TimerClock {
+    timer_id: String,
+    reported: TimerDisplay,
+    counts_elapsed: bool,
+    local_start: Instant,
+}
+
+impl TimerClock {
+    /// Anchors the clock to a display the daemon reported just now.
+    /// `counts_elapsed` is false during a pomodoro break, which doesn't add
+    /// to the time worked.
+    pub fn new(timer_id: String, reported: TimerDisplay, counts_elapsed: bool) -> Self {
+        Self {
+            timer_id,
+            reported,
+            counts_elapsed,
+            local_start: Instant::now(),
+        }
+    }
+
+    /// Anchors the clock to a freshly fetched timer, if it is running.
+    pub fn from_timer(timer: &ActiveTimer, reported: TimerDisplay) -> Option<Self> {
+        let counts_elapsed = timer
+            .pomodoro_state
+            .as_ref()
+            .is_none_or(|pomo| pomo.phase.is_work());
+        (timer.state == TimerState::Running)
+            .then(|| Self::new(timer.id.clone(), reported, counts_elapsed))
+    }
+
+    pub fn is_for(&self, timer_id: &str) -> bool {
+        self.timer_id == timer_id
+    }
+
+    fn since_report(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.local_start)
+    }
+
+    pub fn display_at(&self, now: Instant) -> TimerDisplay {
+        self.reported
+            .advanced(self.since_report(now).as_secs(), self.counts_elapsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolates_from_last_report() {
+        let reported = TimerDisplay {
+            elapsed_seconds: 90,
+            remaining_seconds: Some(30),
+            total_seconds: Some(120),
+            progress_ratio: Some(0.75),
+            phase_display: "Countdown".to_string(),
+            next_phase_display: None,
+        };
+        let clock = TimerClock::new("t1".to_string(), reported.clone(), true);
+        let start = clock.local_start;
+        let elapsed_at = |now| clock.display_at(now).elapsed_seconds;
+        let remaining_at = |now| clock.display_at(now).remaining_seconds;
+
+        assert_eq!(clock.display_at(start), reported);
+        assert_eq!(elapsed_at(start + Duration::from_millis(999)), 90);
+        assert_eq!(elapsed_at(start + Duration::from_millis(2500)), 92);
+        assert_eq!(remaining_at(start + Duration::from_secs(5)), Some(25));
+        assert_eq!(remaining_at(start + Duration::from_secs(60)), Some(0));
+        assert_eq!(
+            clock
+                .display_at(start + Duration::from_secs(15))
+                .progress_ratio,
+            Some(0.875)
+        );
+        // An instant from before the report never rewinds the clock.
+        assert_eq!(elapsed_at(start - Duration::from_secs(1)), 90);
+        assert!(clock.is_for("t1"));
+        assert!(!clock.is_for("t2"));
+
+        // A pomodoro break counts down without adding to the time worked.
+        let on_break = TimerClock::new("t1".to_string(), reported, false);
+        let later = on_break.display_at(on_break.local_start + Duration::from_secs(10));
+        assert_eq!(later.elapsed_seconds, 90);
+        assert_eq!(later.remaining_seconds, Some(20));
+    }
+}
@@ -1,85 +1,63 @@
-use crate::app::{App, InputMode};
+use crate::app::App;
+use crate::confirm::{ConfirmButton, Danger};
+use crate::keybindings::Action;
+use crate::ui::buttons::{Button, render_button_row};
 use crate::ui::helpers::centered_rect;
 use ratatui::{
     Frame,
-    layout::Alignment,
+    layout::{Alignment, Constraint, Direction, Layout, Margin},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
 };
 
 pub fn draw_confirmation_modal(f: &mut Frame, app: &App) {
+    let Some(dialog) = &app.confirm_dialog else {
+        return;
+    };
     let modal_area = centered_rect(f.area(), 60, 10);
 
     f.render_widget(Clear, modal_area);
 
-    let (title, message, yes_label) = if app.input_mode == InputMode::ConfirmQuit {
-        (
-            "Quit MooTimer?",
-            "A timer is currently running. It will work in the background until the daemon is stopped. Quit?".to_string(),
-            "  [Y]es, Quit    ",
-        )
-    } else if app.input_mode == InputMode::DeleteTaskConfirm {
-        let task_name = app
-            .tasks
-            .get(app.selected_task_index)
-            .and_then(|t| t.get("title"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("this task");
-        (
-            "Delete Task?",
-            format!("Are you sure you want to delete \"{}\" ?", task_name),
-            "  [Y]es, Delete  ",
-        )
-    } else {
-        let profile_name = app
-            .profiles
-            .get(app.selected_profile_index)
-            .and_then(|p| p.get("name"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("this profile");
-        (
-            "Delete Profile?",
-            format!("Are you sure you want to delete \"{}\" ?", profile_name),
-            "  [Y]es, Delete  ",
-        )
+    let color = match dialog.danger {
+        Danger::Destructive => Color::Red,
+        Danger::Caution => Color::Yellow,
     };
-
-    let text = vec![
-        Line::from(""),
-        Line::from(Span::styled(message, Style::default().fg(Color::White))),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled(
-                yes_label,
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-            ),
-            Span::raw("    "),
-            Span::styled(
-                "  [N]o, Cancel   ",
-                Style::default()
-                    .fg(Color::Green)
-                    .add_modifier(Modifier::BOLD),
-            ),
-        ]),
-    ];
-
     let block = Block::default()
         .borders(Borders::ALL)
         .title(Span::styled(
-            title,
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            format!(" {} ", dialog.title),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
         ))
-        .border_style(Style::default().fg(Color::Red));
+        .border_style(Style::default().fg(color));
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
 
-    let paragraph = Paragraph::new(text)
-        .alignment(Alignment::Center)
-        .block(block);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Fill(1), Constraint::Length(3)])
+        .split(inner);
 
-    f.render_widget(paragraph, modal_area);
+    let message = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            dialog.message.as_str(),
+            Style::default().fg(Color::White),
+        )),
+    ])
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+    f.render_widget(message, chunks[0]);
+
+    let buttons = [
+        Button::new(dialog.yes_label, "y", dialog.focused == ConfirmButton::Yes),
+        Button::new("Cancel", "n", dialog.focused == ConfirmButton::No),
+    ];
+    let button_area = chunks[1].inner(Margin::new(4, 0));
+    render_button_row(f, button_area, &buttons, 2);
 }
 
-pub fn draw_break_finished_modal(f: &mut Frame) {
+pub fn draw_break_finished_modal(f: &mut Frame, app: &App) {
     let modal_area = centered_rect(f.area(), 60, 10);
 
     f.render_widget(Clear, modal_area);
@@ -100,7 +78,10 @@ pub fn draw_break_finished_modal(f: &mut Frame) {
             ),
             Span::raw("    "),
             Span::styled(
-                "  [x] Stop Timer  ",
+                format!(
+                    "  [{}] Stop Timer  ",
+                    app.keybindings.label(Action::TimerStop)
+                ),
                 Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
             ),
         ]),
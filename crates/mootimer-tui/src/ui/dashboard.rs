@@ -1,20 +1,28 @@
-use crate::app::{App, DashboardPane, TimerType};
+use crate::app::{App, BlockState, DashboardPane, TimerType};
+use crate::keybindings::Action;
 use crate::ui::big_text::BigText;
 use crate::ui::buttons::{Button, render_button_row};
 use crate::ui::cow::Cow;
 use crate::ui::helpers::{
-    build_hint_line, focused_border_style, format_duration_hm, format_duration_hms,
+    build_hint_line, focused_border_style, format_ago, format_duration_hm, format_duration_hms,
+    format_duration_short, highlight_matches,
 };
+use crate::ui::pomodoro_ring::PomodoroRing;
 use crate::ui::tomato::Tomato;
-use mootimer_core::models::{ActiveTimer, PomodoroPhase, TimerMode, TimerState};
+use mootimer_core::models::{
+    ActiveTimer, DEFAULT_SUMMARY_FORMAT, TaskPriority, TaskStatus, TimerMode, TimerState,
+};
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph},
 };
 
+/// Most concurrent timers listed above the focused one.
+const MAX_STACK_ROWS: usize = 6;
+
 fn format_duration_ms(seconds: u64) -> String {
     let minutes = seconds / 60;
     let secs = seconds % 60;
@@ -22,11 +30,22 @@ fn format_duration_ms(seconds: u64) -> String {
 }
 
 pub fn draw_dashboard(f: &mut Frame, app: &mut App, area: Rect) {
+    if app.multi_dashboard {
+        draw_multi_dashboard(f, app, area);
+        return;
+    }
+
     let main_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(area);
 
+    if app.fleet_view {
+        draw_fleet_panel(f, app, main_chunks[0]);
+        draw_tasks_list(f, app, main_chunks[1]);
+        return;
+    }
+
     let left_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -45,23 +64,44 @@ pub fn draw_dashboard(f: &mut Frame, app: &mut App, area: Rect) {
 
 fn draw_timer_with_config(f: &mut Frame, app: &mut App, area: Rect) {
     let is_focused = app.focused_pane == DashboardPane::TimerConfig;
-    let active_timer: Option<ActiveTimer> = app
-        .timer_info
-        .clone()
-        .and_then(|v| serde_json::from_value(v).ok());
+    let active_timer = app.active_timer();
 
     let hint = build_timer_hint(&active_timer, app);
     let hint_line = build_hint_line(hint, app.focused_pane == DashboardPane::ProfileList);
 
+    let mut title = vec![Span::raw(" ⏱  Timer ")];
+    let task_title = active_timer
+        .as_ref()
+        .and_then(|t| t.task_title.as_deref())
+        .or_else(|| app.active_task_title());
+    if let Some(task) = task_title {
+        title.push(Span::styled(
+            format!("· {} ", task),
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
     let block = Block::default()
         .borders(Borders::ALL)
-        .title(" ⏱  Timer ")
+        .title(Line::from(title))
         .title_bottom(hint_line.right_aligned())
         .border_style(focused_border_style(is_focused));
 
-    let inner_area = block.inner(area);
+    let mut inner_area = block.inner(area);
     f.render_widget(block, area);
 
+    if app.active_timers.len() > 1 {
+        let rows = app.active_timers.len().min(MAX_STACK_ROWS) as u16;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(rows + 1), Constraint::Fill(1)])
+            .split(inner_area);
+        draw_timer_stack(f, app, chunks[0]);
+        inner_area = chunks[1];
+    }
+
     let pane_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -74,15 +114,23 @@ fn draw_timer_with_config(f: &mut Frame, app: &mut App, area: Rect) {
 
     let (main_area, button_area, gauge_area) = (pane_chunks[0], pane_chunks[1], pane_chunks[3]);
 
-    let animation_type = get_animation_type(&active_timer);
-    let (info_area, animation_area) = split_for_animation(main_area, animation_type.is_some());
+    let animation_type = get_animation_type(app, &active_timer);
+    let animation_width = animation_type.as_ref().map(|a| animation_width(app, a));
+    let (info_area, animation_area) = split_for_animation(main_area, animation_width);
 
     if let Some(anim_area) = animation_area {
-        draw_timer_animation(f, app, &active_timer, anim_area, animation_type);
+        draw_timer_animation(f, app, &active_timer, anim_area, animation_type.as_ref());
     }
 
     if let Some(timer) = &active_timer {
-        draw_active_timer_info(f, app, timer, info_area, gauge_area, animation_type);
+        draw_active_timer_info(
+            f,
+            app,
+            timer,
+            info_area,
+            gauge_area,
+            animation_type.as_ref(),
+        );
     } else {
         draw_idle_timer_info(f, app, info_area);
     }
@@ -99,10 +147,210 @@ fn draw_timer_with_config(f: &mut Frame, app: &mut App, area: Rect) {
     render_button_row(f, padded_button_area, &buttons, 1);
 }
 
+/// One line per concurrent timer with the focused one marked; the rest of
+/// the pane shows the focused timer in full.
+fn draw_timer_stack(f: &mut Frame, app: &App, area: Rect) {
+    let focused = app.timer_info.as_ref().and_then(|t| t.get("id"));
+    let lines: Vec<Line> = app
+        .active_timers
+        .iter()
+        .take(MAX_STACK_ROWS)
+        .filter_map(|value| {
+            let timer: ActiveTimer = serde_json::from_value(value.clone()).ok()?;
+            let is_focused = value.get("id") == focused;
+            let style = if is_focused {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            Some(Line::from(vec![
+                Span::styled(if is_focused { " ▶ " } else { "   " }, style),
+                Span::styled(timer.summary(DEFAULT_SUMMARY_FORMAT), style),
+            ]))
+        })
+        .collect();
+
+    let stack = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title_bottom(Line::from(" [↑↓]Switch [+]Another ").right_aligned()),
+    );
+    f.render_widget(stack, area);
+}
+
+/// Every profile on two lines each: its running or paused timer, then
+/// today's total and the task with the most time.
+fn draw_fleet_panel(f: &mut Frame, app: &App, area: Rect) {
+    let is_focused = app.focused_pane != DashboardPane::TasksList;
+    let items: Vec<ListItem> = app
+        .fleet_rows
+        .iter()
+        .map(|row| {
+            let (color, icon) = app.profile_style(&row.profile_id);
+            let is_active = row.profile_id == app.profile_id;
+            let mut name_style = Style::default().fg(color);
+            if is_active {
+                name_style = name_style.add_modifier(Modifier::BOLD);
+            }
+            let timer = match &row.timer {
+                Some(timer) => {
+                    let (mark, mark_color) = if timer.is_running() {
+                        ("▶", Color::Green)
+                    } else {
+                        ("⏸", Color::Yellow)
+                    };
+                    Span::styled(
+                        format!("{} {}", mark, format_duration_hms(app.timer_elapsed(timer))),
+                        Style::default().fg(mark_color),
+                    )
+                }
+                None => Span::styled("idle", Style::default().fg(Color::DarkGray)),
+            };
+            let mut details = format!("    Today {}", format_duration_hm(row.today_seconds));
+            if let Some(task) = &row.top_task {
+                details.push_str(&format!(" · Top: {}", task));
+            }
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::raw(if is_active { "✓ " } else { "  " }),
+                    Span::styled(
+                        format!("{} {}", icon, app.get_profile_name_by_id(&row.profile_id)),
+                        name_style,
+                    ),
+                    Span::raw("  "),
+                    timer,
+                ]),
+                Line::from(Span::styled(details, Style::default().fg(Color::DarkGray))),
+            ])
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" 🌐 All Profiles ({}) ", app.fleet_rows.len()))
+        .title_bottom(
+            build_hint_line(" [Enter]Switch [r]Refresh [*]Back ", !is_focused).right_aligned(),
+        )
+        .border_style(focused_border_style(is_focused));
+
+    let mut state = ratatui::widgets::ListState::default();
+    if is_focused {
+        state.select(Some(app.selected_fleet_row));
+    }
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(Color::DarkGray));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// Every profile's timers side by side, one column per profile.
+fn draw_multi_dashboard(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" ⏱  All Profiles ")
+        .title_bottom(
+            Line::from(" [←→]Select [Space]Pause/Resume [r]Refresh [M]Back ").right_aligned(),
+        )
+        .border_style(focused_border_style(true));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let columns = app.multi_dashboard_columns();
+    if columns.is_empty() {
+        let empty = Paragraph::new("No timers running in any profile")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        f.render_widget(empty, inner);
+        return;
+    }
+
+    let areas = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Fill(1); columns.len()])
+        .split(inner);
+
+    let mut index = 0;
+    for ((profile, timers), column_area) in columns.iter().zip(areas.iter()) {
+        let mut lines = Vec::new();
+        let mut selected_column = false;
+        for timer in timers {
+            let is_selected = index == app.selected_multi_timer;
+            selected_column |= is_selected;
+            index += 1;
+
+            let style = if is_selected {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let (state, state_color) = match timer.state {
+                TimerState::Running => ("▶ Running", Color::Green),
+                TimerState::Paused => ("⏸ Paused", Color::Yellow),
+                TimerState::Stopped => ("■ Stopped", Color::DarkGray),
+            };
+            let mode = match timer.mode {
+                TimerMode::Manual => "⏱  Manual",
+                TimerMode::Pomodoro => "🍅 Pomodoro",
+                TimerMode::Countdown => "⏲  Countdown",
+            };
+            lines.push(Line::from(vec![
+                Span::styled(if is_selected { "▶ " } else { "  " }, style),
+                Span::styled(mode, style),
+            ]));
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(
+                    format_duration_hms(app.timer_elapsed(timer)),
+                    style.add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("  "),
+                Span::styled(state, Style::default().fg(state_color)),
+            ]));
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(
+                    timer
+                        .task_title
+                        .as_deref()
+                        .unwrap_or("(no task)")
+                        .to_string(),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]));
+            lines.push(Line::from(""));
+        }
+
+        let column = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {} ", profile))
+                .border_style(focused_border_style(selected_column)),
+        );
+        f.render_widget(column, *column_area);
+    }
+}
+
 fn build_timer_hint(active_timer: &Option<ActiveTimer>, app: &App) -> &'static str {
     if let Some(timer) = active_timer {
         match timer.state {
-            TimerState::Running | TimerState::Paused => " [←→]Buttons [Enter]Action [r]Refresh ",
+            TimerState::Running if timer.is_pomodoro() => {
+                if app.auto_start_enabled() {
+                    " [←→]Buttons [Enter]Action [A]Auto:on [r]Refresh "
+                } else {
+                    " [←→]Buttons [Enter]Action [A]Auto:off [r]Refresh "
+                }
+            }
+            TimerState::Running | TimerState::Paused if app.in_break() => {
+                " [←→]Buttons [Enter]Action [k]Skip break [r]Refresh "
+            }
+            TimerState::Running | TimerState::Paused => {
+                " [←→]Buttons [Enter]Action [+]Another [r]Refresh "
+            }
             _ => " [t]Type [Space/Enter]Start ",
         }
     } else {
@@ -113,33 +361,57 @@ fn build_timer_hint(active_timer: &Option<ActiveTimer>, app: &App) -> &'static s
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, PartialEq)]
 enum AnimationType {
     Tomato,
     Cow,
     ManualBigText,
+    /// A file from the animations folder, by name.
+    Custom(String),
 }
 
-fn get_animation_type(active_timer: &Option<ActiveTimer>) -> Option<AnimationType> {
+/// The animation `tui.pomodoro_animation` or `tui.countdown_animation`
+/// picks for the timer. A name with no matching file falls back to the
+/// mode's built-in.
+fn get_animation_type(app: &App, active_timer: &Option<ActiveTimer>) -> Option<AnimationType> {
     let timer = active_timer.as_ref()?;
     if !timer.is_running() && !timer.is_paused() {
         return None;
     }
-    match timer.mode {
-        _ if timer.is_pomodoro() => Some(AnimationType::Tomato),
-        TimerMode::Countdown => Some(AnimationType::Cow),
-        TimerMode::Manual => Some(AnimationType::ManualBigText),
-        _ => None,
+    let (key, built_in) = match timer.mode {
+        _ if timer.is_pomodoro() => ("pomodoro_animation", AnimationType::Tomato),
+        TimerMode::Countdown => ("countdown_animation", AnimationType::Cow),
+        TimerMode::Manual => return Some(AnimationType::ManualBigText),
+        _ => return None,
+    };
+    match app.animation_choice(key) {
+        "none" => None,
+        "tomato" => Some(AnimationType::Tomato),
+        "cow" => Some(AnimationType::Cow),
+        name if app.animations.get(name).is_some() => Some(AnimationType::Custom(name.to_string())),
+        _ => Some(built_in),
     }
 }
 
-fn split_for_animation(main_area: Rect, show_animation: bool) -> (Rect, Option<Rect>) {
-    if show_animation {
+const ANIMATION_WIDTH: u16 = 32;
+
+fn animation_width(app: &App, animation_type: &AnimationType) -> u16 {
+    match animation_type {
+        AnimationType::Custom(name) => app
+            .animations
+            .get(name)
+            .map_or(ANIMATION_WIDTH, |a| a.size().0 + 2),
+        _ => ANIMATION_WIDTH,
+    }
+}
+
+fn split_for_animation(main_area: Rect, width: Option<u16>) -> (Rect, Option<Rect>) {
+    if let Some(width) = width {
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
                 Constraint::Fill(1),
-                Constraint::Length(32),
+                Constraint::Length(width.max(ANIMATION_WIDTH)),
                 Constraint::Fill(1),
             ])
             .split(main_area);
@@ -154,7 +426,7 @@ fn draw_timer_animation(
     app: &mut App,
     active_timer: &Option<ActiveTimer>,
     area: Rect,
-    animation_type: Option<AnimationType>,
+    animation_type: Option<&AnimationType>,
 ) {
     match animation_type {
         Some(AnimationType::Tomato) => {
@@ -165,7 +437,7 @@ fn draw_timer_animation(
         }
         Some(AnimationType::ManualBigText) => {
             if let Some(timer) = active_timer {
-                let time_str = format_duration_hms(timer.current_elapsed());
+                let time_str = format_duration_hms(app.timer_elapsed(timer));
                 let (text_width, text_height) = (30, 5);
                 let x = area.x + (area.width.saturating_sub(text_width)) / 2;
                 let y = area.y + (area.height.saturating_sub(text_height)) / 2;
@@ -176,6 +448,22 @@ fn draw_timer_animation(
                 );
             }
         }
+        Some(AnimationType::Custom(name)) => {
+            let Some(animation) = app.animations.get(name) else {
+                return;
+            };
+            let paused = active_timer.as_ref().is_some_and(|t| t.is_paused());
+            let lines: Vec<Line> = animation
+                .frame(paused, app.animation_clock.elapsed())
+                .iter()
+                .map(|line| Line::raw(line.as_str()))
+                .collect();
+            let (width, height) = animation.size();
+            let (width, height) = (width.min(area.width), height.min(area.height));
+            let x = area.x + (area.width - width) / 2;
+            let y = area.y + (area.height - height) / 2;
+            f.render_widget(Paragraph::new(lines), Rect::new(x, y, width, height));
+        }
         None => {}
     }
 }
@@ -186,7 +474,7 @@ fn draw_active_timer_info(
     timer: &ActiveTimer,
     info_area: Rect,
     gauge_area: Rect,
-    animation_type: Option<AnimationType>,
+    animation_type: Option<&AnimationType>,
 ) {
     let color = match timer.state {
         TimerState::Running => Color::Green,
@@ -201,7 +489,7 @@ fn draw_active_timer_info(
     };
 
     let (time_display, ratio, phase_info, next_phase_info) =
-        build_timer_display_info(timer, state_icon, animation_type);
+        build_timer_display_info(app, timer, state_icon, animation_type);
 
     let task_name = timer
         .task_id
@@ -251,11 +539,19 @@ fn draw_active_timer_info(
         );
     }
 
+    let phase = timer.pomodoro_state.as_ref().map(|p| p.phase);
+    let (info_area, ring_area) = match (phase, ratio) {
+        (Some(_), Some(_)) => split_for_ring(info_area),
+        _ => (info_area, None),
+    };
+
     let text_widget = Paragraph::new(text_lines)
         .block(Block::default().padding(ratatui::widgets::Padding::new(2, 2, 1, 1)));
     f.render_widget(text_widget, info_area);
 
-    if let Some(r) = ratio {
+    if let (Some(area), Some(phase), Some(r)) = (ring_area, phase, ratio) {
+        f.render_widget(PomodoroRing::new(r, phase), area);
+    } else if let Some(r) = ratio {
         let gauge = Gauge::default()
             .block(Block::default().padding(ratatui::widgets::Padding::horizontal(2)))
             .gauge_style(Style::default().fg(color).bg(Color::DarkGray))
@@ -265,77 +561,56 @@ fn draw_active_timer_info(
     }
 }
 
+/// Carves a round ring off the right of the info area, leaving the text
+/// at least `MIN_TEXT_WIDTH` columns. `None` if there is no room.
+fn split_for_ring(info_area: Rect) -> (Rect, Option<Rect>) {
+    const MIN_TEXT_WIDTH: u16 = 28;
+    const MAX_RING_HEIGHT: u16 = 11;
+
+    let height = info_area.height.saturating_sub(2).min(MAX_RING_HEIGHT);
+    let width = PomodoroRing::width_for(height);
+    if height < 5 || info_area.width < width + MIN_TEXT_WIDTH {
+        return (info_area, None);
+    }
+
+    let text_area = Rect {
+        width: info_area.width - width - 1,
+        ..info_area
+    };
+    let ring_area = Rect::new(
+        info_area.right() - width - 1,
+        info_area.y + (info_area.height - height) / 2,
+        width,
+        height,
+    );
+    (text_area, Some(ring_area))
+}
+
 fn build_timer_display_info(
+    app: &App,
     timer: &ActiveTimer,
     state_icon: &str,
-    animation_type: Option<AnimationType>,
+    animation_type: Option<&AnimationType>,
 ) -> (String, Option<f64>, String, Option<String>) {
-    if timer.is_pomodoro() {
-        let remaining = timer.remaining_seconds().unwrap_or(0);
-        let elapsed_in_phase = timer.current_phase_elapsed();
-        let phase_duration = remaining + elapsed_in_phase;
-
-        let ratio = if phase_duration > 0 {
-            (elapsed_in_phase as f64 / phase_duration as f64).min(1.0)
-        } else {
-            0.0
-        };
-
-        let Some(pomo) = timer.pomodoro_state.as_ref() else {
-            return (String::new(), None, "Unknown".to_string(), None);
-        };
-
-        let phase_name = match pomo.phase {
-            PomodoroPhase::Work => "Work",
-            PomodoroPhase::ShortBreak => "Short Break",
-            PomodoroPhase::LongBreak => "Long Break",
-        };
-
-        let next_phase = match pomo.phase {
-            PomodoroPhase::Work => {
-                if pomo.current_session % pomo.config.sessions_until_long_break == 0 {
-                    "Long Break"
-                } else {
-                    "Short Break"
-                }
-            }
-            _ => "Work",
-        };
-
-        (
-            format!("{} {}", state_icon, format_duration_ms(remaining)),
-            Some(ratio),
-            format!("{} (Session {})", phase_name, pomo.current_session),
-            Some(next_phase.to_string()),
-        )
-    } else if timer.mode == TimerMode::Countdown {
-        let elapsed = timer.current_elapsed();
-        let target = timer.target_duration.unwrap_or(0);
-        let remaining = target.saturating_sub(elapsed);
-        let ratio = if target > 0 {
-            (elapsed as f64 / target as f64).min(1.0)
-        } else {
-            0.0
-        };
-
-        (
-            format!("{} {}", state_icon, format_duration_hms(remaining)),
-            Some(ratio),
-            "Countdown".to_string(),
-            None,
-        )
-    } else {
-        let time_display = if animation_type == Some(AnimationType::ManualBigText) {
-            String::new()
-        } else {
-            format!(
-                "{} {}",
-                state_icon,
-                format_duration_hms(timer.current_elapsed())
-            )
-        };
-        (time_display, None, "Manual Timer".to_string(), None)
-    }
+    let display = app.timer_display(timer);
+    let time_display = match display.remaining_seconds {
+        Some(remaining) if timer.is_pomodoro() => {
+            format!("{} {}", state_icon, format_duration_ms(remaining))
+        }
+        Some(remaining) => format!("{} {}", state_icon, format_duration_hms(remaining)),
+        None if animation_type == Some(&AnimationType::ManualBigText) => String::new(),
+        None => format!(
+            "{} {}",
+            state_icon,
+            format_duration_hms(display.elapsed_seconds)
+        ),
+    };
+    (
+        time_display,
+        display.progress_ratio,
+        display.phase_display,
+        display.next_phase_display,
+    )
 }
 
 fn build_state_badge(state: TimerState) -> Span<'static> {
@@ -378,7 +653,7 @@ fn draw_idle_timer_info(f: &mut Frame, app: &App, info_area: Rect) {
         TimerType::Countdown => ("Countdown", format!("{}m", app.countdown_minutes)),
     };
 
-    let text_lines = vec![
+    let mut text_lines = vec![
         Line::from(Span::styled(
             "Ready to Start",
             Style::default()
@@ -401,18 +676,45 @@ fn draw_idle_timer_info(f: &mut Frame, app: &App, info_area: Rect) {
         ]),
         Line::from(format!("Task: {}", selected_task)),
     ];
+    if let Some(line) = app.last_entry.as_ref().and_then(last_entry_line) {
+        text_lines.push(Line::from(""));
+        text_lines.push(line);
+    }
 
     let text_widget = Paragraph::new(text_lines)
         .block(Block::default().padding(ratatui::widgets::Padding::new(2, 2, 1, 1)));
     f.render_widget(text_widget, info_area);
 }
 
+/// "Last: Code review — 45m (2h ago)".
+fn last_entry_line(entry: &serde_json::Value) -> Option<Line<'static>> {
+    let end = entry["end_time"]
+        .as_str()
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())?;
+    let title = entry["task_title"]
+        .as_str()
+        .or_else(|| entry["description"].as_str())
+        .unwrap_or("No task");
+    let duration = entry["duration_seconds"].as_u64().unwrap_or(0);
+    let ago = (chrono::Utc::now() - end.to_utc()).num_seconds();
+
+    Some(Line::from(Span::styled(
+        format!(
+            "Last: {} — {} ({})",
+            title,
+            format_duration_short(duration),
+            format_ago(ago)
+        ),
+        Style::default().fg(Color::DarkGray),
+    )))
+}
+
 fn build_timer_buttons(
     active_timer: &Option<ActiveTimer>,
     is_focused: bool,
     selected_button: usize,
 ) -> Vec<Button<'static>> {
-    match active_timer {
+    let mut buttons = match active_timer {
         Some(timer) => match timer.state {
             TimerState::Running => vec![
                 Button::new("Pause", "Space", is_focused && selected_button == 0),
@@ -425,7 +727,17 @@ fn build_timer_buttons(
             _ => vec![Button::new("Start", "Space", is_focused)],
         },
         None => vec![Button::new("Start", "Space", is_focused)],
+    };
+
+    let in_break = active_timer
+        .as_ref()
+        .filter(|t| t.is_running() || t.is_paused())
+        .and_then(|t| t.pomodoro_state.as_ref())
+        .is_some_and(|p| p.phase.is_break());
+    if in_break {
+        buttons.push(Button::new("Skip", "k", is_focused && selected_button == 2));
     }
+    buttons
 }
 
 fn draw_tasks_list(f: &mut Frame, app: &App, area: Rect) {
@@ -456,49 +768,99 @@ fn draw_tasks_list(f: &mut Frame, app: &App, area: Rect) {
                     .get("title")
                     .and_then(|v| v.as_str())
                     .unwrap_or("Untitled");
-                let status = task
-                    .get("status")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("todo");
+                let status = TaskStatus::of(task);
 
                 let status_icon = match status {
-                    "in_progress" => "▶",
-                    "done" | "completed" => "✓",
-                    _ => "○",
+                    TaskStatus::InProgress => "▶",
+                    TaskStatus::Done => "✓",
+                    TaskStatus::Todo | TaskStatus::Archived => "○",
                 };
 
                 let is_selected = i == app.selected_task_index;
                 let mut style = Style::default();
 
                 match status {
-                    "done" | "completed" => {
+                    TaskStatus::Done => {
                         style = style.fg(Color::Green);
                         if !is_selected {
                             style = style.add_modifier(Modifier::DIM);
                         }
                     }
-                    "in_progress" => {
+                    TaskStatus::InProgress => {
                         style = style.fg(Color::Yellow).add_modifier(Modifier::BOLD);
                     }
-                    _ => {
+                    TaskStatus::Todo | TaskStatus::Archived => {
                         if is_selected {
                             style = style.fg(Color::Yellow).add_modifier(Modifier::BOLD);
                         }
                     }
                 }
 
+                if app.due_order.is_some() && App::is_overdue(task) {
+                    style = style.fg(Color::Red);
+                }
+
+                let block_state = app.block_state(task);
+                if block_state == Some(BlockState::Blocked) && !is_selected {
+                    style = style.add_modifier(Modifier::DIM);
+                }
+
                 if is_selected {
                     style = style.bg(Color::DarkGray);
                 }
 
-                let text = format!(
-                    "  {} {} {}",
+                let pinned = task
+                    .get("pinned")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+
+                let mark = match &app.task_selection {
+                    Some(selection) => {
+                        let id = task.get("id").and_then(|v| v.as_str()).unwrap_or_default();
+                        if selection.contains(id) {
+                            "■ "
+                        } else {
+                            "□ "
+                        }
+                    }
+                    None => "",
+                };
+                let prefix = format!(
+                    "  {}{} {} {}",
+                    mark,
                     status_icon,
                     if is_selected { "→" } else { " " },
-                    title
+                    if pinned { "📌 " } else { "" },
                 );
+                let mut spans = vec![Span::raw(prefix)];
+                match block_state {
+                    Some(BlockState::Blocked) => spans.push(Span::raw("🔒 ")),
+                    Some(BlockState::Cleared) => {
+                        spans.push(Span::styled("✓ ", Style::default().fg(Color::Green)))
+                    }
+                    None => {}
+                }
+                let priority: TaskPriority = task
+                    .get("priority")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default();
+                if let Some(color) = app.priority_colors.get(&priority) {
+                    spans.push(Span::styled("● ", Style::default().fg(*color)));
+                }
+                match app.task_search_match(task) {
+                    Some(matched) if !app.task_search.is_empty() => {
+                        spans.extend(highlight_matches(
+                            title,
+                            &matched.spans,
+                            Style::default()
+                                .fg(Color::Cyan)
+                                .add_modifier(Modifier::UNDERLINED),
+                        ));
+                    }
+                    _ => spans.push(Span::raw(title)),
+                }
 
-                let mut lines = vec![Line::from(text)];
+                let mut lines = vec![Line::from(spans)];
 
                 if app.show_task_description
                     && let Some(desc) = task.get("description").and_then(|v| v.as_str())
@@ -519,22 +881,42 @@ fn draw_tasks_list(f: &mut Frame, app: &App, area: Rect) {
 
     let base_title = if app.show_archived {
         " ARCHIVED Tasks "
+    } else if app.due_order.is_some() {
+        " Tasks by Due Date "
     } else {
         " Tasks "
     };
 
-    let title = format!(" {} ({}) ", base_title, filtered_tasks.len());
+    let title = match &app.task_selection {
+        Some(selection) => format!(
+            " {} ({}, {} marked) ",
+            base_title,
+            filtered_tasks.len(),
+            selection.len()
+        ),
+        None => format!(" {} ({}) ", base_title, filtered_tasks.len()),
+    };
 
     let (action_hint, view_hint) = if app.show_archived {
-        ("[a]Restore", "[A]Active")
+        ("Restore", "[A]Active")
     } else {
-        ("[a]Archive", "[A]Archived")
+        ("Archive", "[A]Archived")
     };
 
-    let bottom_hint = format!(
-        " [j/k]Nav [g/G]Jump [n]New [d]Del {} {} [/]Search ",
-        action_hint, view_hint
-    );
+    let keys = &app.keybindings;
+    let bottom_hint = if app.task_selection.is_some() {
+        " [Space]Mark [t]Tag [u]Untag [V]Done ".to_string()
+    } else {
+        format!(
+            " [j/k]Nav [g/G]Jump [{}]New [{}]Del [{}]{} {} [D]Due [{}]Search ",
+            keys.label(Action::TaskNew),
+            keys.label(Action::TaskDelete),
+            keys.label(Action::TaskArchive),
+            action_hint,
+            view_hint,
+            keys.label(Action::TaskSearch)
+        )
+    };
 
     let bottom_hint_line =
         build_hint_line(&bottom_hint, app.focused_pane == DashboardPane::TimerConfig);
@@ -569,10 +951,11 @@ fn draw_profile_selector(f: &mut Frame, app: &App, area: Rect) {
             let is_active = id == app.profile_id;
             let is_selected = i == app.selected_profile_index;
 
-            let mut style = Style::default();
+            let (color, icon) = app.profile_style(id);
+            let mut style = Style::default().fg(color);
 
             let prefix = if is_active {
-                style = style.fg(Color::Green).add_modifier(Modifier::BOLD);
+                style = style.add_modifier(Modifier::BOLD);
                 "✓ "
             } else {
                 "  "
@@ -580,12 +963,9 @@ fn draw_profile_selector(f: &mut Frame, app: &App, area: Rect) {
 
             if app.focused_pane == DashboardPane::ProfileList && is_selected {
                 style = style.bg(Color::DarkGray);
-                if !is_active {
-                    style = style.fg(Color::Yellow);
-                }
             }
 
-            let text = format!("{}{}", prefix, name);
+            let text = format!("{}{} {}", prefix, icon, name);
             ListItem::new(text).style(style)
         })
         .collect();
@@ -666,3 +1046,63 @@ fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
         stats_chunks[1],
     );
 }
+
+/// Lists the blockers of the Dashboard task under the mouse, next to the
+/// pointer.
+pub fn draw_blockers_popup(f: &mut Frame, app: &App) {
+    let Some((index, column, row)) = app.blocked_task_hover else {
+        return;
+    };
+    let Some(task) = app.get_filtered_tasks().get(index).copied() else {
+        return;
+    };
+    let blockers = app.blockers(task);
+    if blockers.is_empty() {
+        return;
+    }
+
+    let lines: Vec<Line> = blockers
+        .iter()
+        .map(|(title, done)| {
+            if *done {
+                Line::from(Span::styled(
+                    format!("✓ {}", title),
+                    Style::default().fg(Color::Green),
+                ))
+            } else {
+                Line::from(format!("🔒 {}", title))
+            }
+        })
+        .collect();
+
+    let screen = f.area();
+    let widest = blockers
+        .iter()
+        .map(|(title, _)| title.chars().count() as u16 + 3)
+        .max()
+        .unwrap_or(0)
+        .max(12);
+    let width = (widest + 2).min(screen.width);
+    let height = (lines.len() as u16 + 2).min(screen.height);
+    // Below and right of the pointer, flipped to stay on screen.
+    let x = if column + 2 + width <= screen.width {
+        column + 2
+    } else {
+        screen.width.saturating_sub(width)
+    };
+    let y = if row + 1 + height <= screen.height {
+        row + 1
+    } else {
+        row.saturating_sub(height)
+    };
+    let area = Rect::new(x, y, width, height);
+
+    f.render_widget(Clear, area);
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Blocked by ")
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+    f.render_widget(popup, area);
+}
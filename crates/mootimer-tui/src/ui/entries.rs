@@ -1,4 +1,5 @@
 use crate::app::App;
+use crate::entry_range::Granularity;
 use crate::ui::helpers::format_duration_hm;
 use ratatui::{
     Frame,
@@ -10,6 +11,10 @@ use ratatui::{
 
 pub fn draw_entries(f: &mut Frame, app: &App, area: Rect) {
     let filtered_entries = app.get_filtered_entries();
+    let time_format = match app.entry_range.granularity {
+        Granularity::Day => "%H:%M:%S",
+        Granularity::Week | Granularity::Month => "%a %d %H:%M",
+    };
 
     let entry_items: Vec<ListItem> = if filtered_entries.is_empty() {
         if !app.entry_filter.is_empty() {
@@ -24,7 +29,9 @@ pub fn draw_entries(f: &mut Frame, app: &App, area: Rect) {
                 ListItem::new(""),
                 ListItem::new("  No entries for selected period."),
                 ListItem::new(""),
-                ListItem::new("  Press [d]/[w]/[m] to change time period."),
+                ListItem::new(
+                    "  Press [ / ] to step, [D] to pick a date, [T]/[W]/[M] for day/week/month.",
+                ),
             ]
         }
     } else {
@@ -39,7 +46,7 @@ pub fn draw_entries(f: &mut Frame, app: &App, area: Rect) {
                 let start_time_display =
                     if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(start_time_str) {
                         dt.with_timezone(&chrono::Local)
-                            .format("%H:%M:%S")
+                            .format(time_format)
                             .to_string()
                     } else {
                         "--:--:--".to_string()
@@ -78,9 +85,18 @@ pub fn draw_entries(f: &mut Frame, app: &App, area: Rect) {
                     .and_then(|v| v.as_str())
                     .unwrap_or("manual");
 
-                let mode_icon = match mode {
-                    "pomodoro" => "🍅",
-                    "countdown" => "⏲ ",
+                let source = entry
+                    .get("source")
+                    .and_then(|s| s.get("type"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("timer");
+
+                let mode_icon = match (source, mode) {
+                    ("manual", _) => "✍ ",
+                    ("import", _) => "📥",
+                    ("calendar", _) => "📅",
+                    (_, "pomodoro") => "🍅",
+                    (_, "countdown") => "⏲ ",
                     _ => "⏱ ",
                 };
                 let time_str = if duration_secs >= 3600 {
@@ -89,16 +105,30 @@ pub fn draw_entries(f: &mut Frame, app: &App, area: Rect) {
                     format!("{}m", duration_secs / 60)
                 };
 
+                let invalid = entry
+                    .get("id")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|id| app.invalid_entries.contains_key(id));
+
                 let style = if i == app.selected_entry_index {
                     Style::default()
                         .fg(Color::Yellow)
                         .add_modifier(Modifier::BOLD)
+                } else if invalid {
+                    Style::default().fg(Color::Red)
                 } else {
                     Style::default()
                 };
 
+                let device = entry
+                    .get("device_name")
+                    .and_then(|v| v.as_str())
+                    .map(|name| format!(" · 💻 {}", name))
+                    .unwrap_or_default();
+
                 let text = format!(
-                    "  {} {} {} │ {:>7} │ {}",
+                    "{} {} {} {} │ {:>7} │ {}{}",
+                    if invalid { "⚠" } else { " " },
                     mode_icon,
                     if i == app.selected_entry_index {
                         "→"
@@ -107,24 +137,31 @@ pub fn draw_entries(f: &mut Frame, app: &App, area: Rect) {
                     },
                     start_time_display,
                     time_str,
-                    task_display
+                    task_display,
+                    device
                 );
                 ListItem::new(text).style(style)
             })
             .collect()
     };
 
-    let title = if app.entry_filter.is_empty() {
-        format!(" 📝 Time Entries ({}) ", filtered_entries.len())
+    let range = app.entry_range.label();
+    let mut title = if app.entry_filter.is_empty() {
+        format!(" 📝 Time Entries · {} ({}) ", range, filtered_entries.len())
     } else {
         format!(
-            " 📝 Time Entries ({} matched filter '{}') ",
+            " 📝 Time Entries · {} ({} matched filter '{}') ",
+            range,
             filtered_entries.len(),
             app.entry_filter
         )
     };
+    if !app.invalid_entries.is_empty() {
+        title.push_str(&format!("⚠ {} invalid ", app.invalid_entries.len()));
+    }
 
-    let bottom_hint = " [n]ew | [d]ay [w]eek [m]onth | [e]dit [f]ilter [del]ete ";
+    let bottom_hint =
+        " [n]ew | [ ] step [D]ate [T]oday [W]eek [M]onth | [e]dit [E] times [f]ilter [d]elete ";
 
     let entries_list = List::new(entry_items).block(
         Block::default()
@@ -0,0 +1,102 @@
+use crate::app::App;
+use crate::keymap::HelpLine;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+const KEYS_WIDTH: usize = 18;
+
+fn help_modal_area(area: Rect) -> Rect {
+    let width = (area.width as f32 * 0.85) as u16;
+    let height = (area.height as f32 * 0.85) as u16;
+    Rect {
+        x: area.width.saturating_sub(width) / 2,
+        y: area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Rows of help text visible in a terminal of `area`, for scrolling.
+pub fn help_viewport_height(area: Rect) -> usize {
+    help_modal_area(area).height.saturating_sub(2) as usize
+}
+
+pub fn draw_help_modal(f: &mut Frame, app: &App) {
+    let modal_area = help_modal_area(f.area());
+    f.render_widget(Clear, modal_area);
+
+    let help = &app.help;
+    let current_row = help.current_row();
+    let lines: Vec<Line> = help
+        .lines()
+        .into_iter()
+        .enumerate()
+        .map(|(row, line)| match line {
+            HelpLine::Header(title) => Line::from(Span::styled(
+                format!("  {}", title),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            HelpLine::Binding { keys, action } => {
+                let line = Line::from(vec![
+                    Span::styled(
+                        format!("    {:<width$}", keys, width = KEYS_WIDTH),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(action),
+                ]);
+                if current_row == Some(row) {
+                    line.style(Style::default().bg(Color::DarkGray))
+                } else {
+                    line
+                }
+            }
+            HelpLine::Blank => Line::from(""),
+        })
+        .collect();
+
+    let lines = if lines.is_empty() {
+        vec![Line::from(Span::styled(
+            "  No bindings match",
+            Style::default().fg(Color::Gray),
+        ))]
+    } else {
+        lines
+    };
+
+    let footer = if help.searching {
+        format!(" /{}_ ", help.query)
+    } else if !help.query.is_empty() {
+        let matches = help.match_rows().len();
+        let position = help.current_match.map_or(0, |i| i + 1);
+        format!(
+            " /{}  [{}/{}]  n/N next/prev  Esc clear ",
+            help.query, position, matches
+        )
+    } else {
+        " / search  j/k scroll  q close ".to_string()
+    };
+
+    let help_paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("❓ Help - 🐮 MooTimer Keyboard Shortcuts")
+                .title_bottom(footer)
+                .border_style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+        )
+        .scroll((help.scroll.min(u16::MAX as usize) as u16, 0))
+        .style(Style::default().bg(Color::Black));
+
+    f.render_widget(help_paragraph, modal_area);
+}
@@ -1,3 +1,4 @@
+use mootimer_core::models::config::TuiColor;
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -32,6 +33,34 @@ pub fn build_hint_line<'a>(hint: &'a str, show_focus_hint: bool) -> Line<'a> {
     }
 }
 
+/// `text` split into spans with the `[start, end)` character ranges in
+/// `matched` drawn in `style`, for showing search matches.
+pub fn highlight_matches<'a>(
+    text: &'a str,
+    matched: &[(usize, usize)],
+    style: Style,
+) -> Vec<Span<'a>> {
+    let byte = |char_offset: usize| {
+        text.char_indices()
+            .nth(char_offset)
+            .map_or(text.len(), |(i, _)| i)
+    };
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for &(start, end) in matched {
+        let (start, end) = (byte(start), byte(end));
+        if start > last {
+            spans.push(Span::raw(&text[last..start]));
+        }
+        spans.push(Span::styled(&text[start..end], style));
+        last = end;
+    }
+    if last < text.len() || spans.is_empty() {
+        spans.push(Span::raw(&text[last..]));
+    }
+    spans
+}
+
 pub fn format_duration_hms(seconds: u64) -> String {
     let hours = seconds / 3600;
     let minutes = (seconds % 3600) / 60;
@@ -44,3 +73,72 @@ pub fn format_duration_hm(seconds: u64) -> String {
     let minutes = (seconds % 3600) / 60;
     format!("{}h {:02}m", hours, minutes)
 }
+
+/// "45m", or "2h 05m" from an hour on.
+pub fn format_duration_short(seconds: u64) -> String {
+    if seconds < 3600 {
+        format!("{}m", seconds / 60)
+    } else {
+        format_duration_hm(seconds)
+    }
+}
+
+/// "just now", "12m ago", "2h ago", "3d ago".
+pub fn format_ago(seconds: i64) -> String {
+    match seconds {
+        ..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", seconds / 60),
+        3600..86400 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+/// Converts a configured color. Names are expected to be validated already;
+/// anything unrecognised renders in the terminal's default color.
+/// A profile's `color`: a `NAMED_COLORS` name, `#RGB` or `#RRGGBB`.
+pub fn profile_color(color: &str) -> Color {
+    let Some(hex) = color.strip_prefix('#') else {
+        return tui_color(&TuiColor::named(color));
+    };
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    let rgb = match hex.len() {
+        3 => (hex.get(0..1), hex.get(1..2), hex.get(2..3)),
+        6 => (hex.get(0..2), hex.get(2..4), hex.get(4..6)),
+        _ => return Color::Reset,
+    };
+    match (
+        rgb.0.and_then(channel),
+        rgb.1.and_then(channel),
+        rgb.2.and_then(channel),
+    ) {
+        // `#F73` means `#FF7733`.
+        (Some(r), Some(g), Some(b)) if hex.len() == 3 => Color::Rgb(r * 17, g * 17, b * 17),
+        (Some(r), Some(g), Some(b)) => Color::Rgb(r, g, b),
+        _ => Color::Reset,
+    }
+}
+
+pub fn tui_color(color: &TuiColor) -> Color {
+    if let TuiColor::Rgb { r, g, b } = *color {
+        return Color::Rgb(r, g, b);
+    }
+    match color.normalized_name().as_deref() {
+        Some("black") => Color::Black,
+        Some("red") => Color::Red,
+        Some("green") => Color::Green,
+        Some("yellow") => Color::Yellow,
+        Some("blue") => Color::Blue,
+        Some("magenta") => Color::Magenta,
+        Some("cyan") => Color::Cyan,
+        Some("gray") => Color::Gray,
+        Some("dark_gray") => Color::DarkGray,
+        Some("light_red") => Color::LightRed,
+        Some("light_green") => Color::LightGreen,
+        Some("light_yellow") => Color::LightYellow,
+        Some("light_blue") => Color::LightBlue,
+        Some("light_magenta") => Color::LightMagenta,
+        Some("light_cyan") => Color::LightCyan,
+        Some("white") => Color::White,
+        _ => Color::Reset,
+    }
+}
@@ -1,4 +1,5 @@
-use crate::app::App;
+use crate::app::{App, BlockState};
+use mootimer_core::models::TaskStatus;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -7,6 +8,10 @@ use ratatui::{
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
 };
 
+/// Border and text color of blocked cards in "In Progress", where work is
+/// stuck until something else is finished.
+const BLOCKED_COLOR: Color = Color::Magenta;
+
 pub fn draw_kanban(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -49,6 +54,14 @@ pub fn draw_kanban(f: &mut Frame, app: &App, area: Rect) {
     for (i, (title, col_idx, color)) in columns.iter().enumerate() {
         let is_col_selected = app.selected_column_index == *col_idx;
         let tasks = app.get_kanban_tasks(*col_idx);
+        let blocked_count = if *col_idx == 1 && !app.show_archived {
+            tasks
+                .iter()
+                .filter(|t| app.block_state(t) == Some(BlockState::Blocked))
+                .count()
+        } else {
+            0
+        };
 
         let is_drag_source_col = drag_source_col == Some(*col_idx);
         let is_drag_target_col =
@@ -73,10 +86,35 @@ pub fn draw_kanban(f: &mut Frame, app: &App, area: Rect) {
                 .iter()
                 .enumerate()
                 .map(|(j, task)| {
-                    let task_title = task
+                    let subtasks = task
+                        .get("id")
+                        .and_then(|v| v.as_str())
+                        .map(|id| app.subtasks_of(id))
+                        .unwrap_or_default();
+                    let title = task
                         .get("title")
                         .and_then(|v| v.as_str())
                         .unwrap_or("Untitled");
+                    let block_state = app.block_state(task);
+                    let lock = match block_state {
+                        Some(BlockState::Blocked) => "🔒 ",
+                        Some(BlockState::Cleared) => "✓ ",
+                        None => "",
+                    };
+                    let card_color = if *col_idx == 1
+                        && !app.show_archived
+                        && block_state == Some(BlockState::Blocked)
+                    {
+                        BLOCKED_COLOR
+                    } else {
+                        *color
+                    };
+                    let task_title = if subtasks.is_empty() {
+                        format!("{}{}", lock, title)
+                    } else {
+                        let done = subtasks.iter().filter(|s| is_done(s)).count();
+                        format!("{}{} ({}/{})", lock, title, done, subtasks.len())
+                    };
                     let is_card_selected = is_col_selected && app.selected_kanban_card_index == j;
 
                     let is_being_dragged =
@@ -89,10 +127,10 @@ pub fn draw_kanban(f: &mut Frame, app: &App, area: Rect) {
                     } else if is_card_selected {
                         Style::default()
                             .fg(Color::Black)
-                            .bg(*color)
+                            .bg(card_color)
                             .add_modifier(Modifier::BOLD)
                     } else {
-                        Style::default().fg(*color)
+                        Style::default().fg(card_color)
                     };
 
                     let line = if is_card_selected && !app.show_archived && !is_dragging {
@@ -106,7 +144,10 @@ pub fn draw_kanban(f: &mut Frame, app: &App, area: Rect) {
 
                         Line::from(vec![
                             Span::raw(left_arrow),
-                            Span::styled(task_title, Style::default().add_modifier(Modifier::BOLD)),
+                            Span::styled(
+                                task_title.clone(),
+                                Style::default().add_modifier(Modifier::BOLD),
+                            ),
                             Span::raw(padding_str),
                             Span::raw(right_arrow),
                         ])
@@ -139,6 +180,15 @@ pub fn draw_kanban(f: &mut Frame, app: &App, area: Rect) {
                         lines.push(Line::from(Span::styled(format!("   {}", desc), desc_style)));
                     }
 
+                    for subtask in &subtasks {
+                        let icon = if is_done(subtask) { "✓" } else { "○" };
+                        let subtask_title = subtask
+                            .get("title")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("Untitled");
+                        lines.push(Line::from(format!("   ↳ {} {}", icon, subtask_title)));
+                    }
+
                     ListItem::new(lines).style(style)
                 })
                 .collect();
@@ -159,6 +209,13 @@ pub fn draw_kanban(f: &mut Frame, app: &App, area: Rect) {
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD)
+        } else if blocked_count > 0 {
+            let style = Style::default().fg(BLOCKED_COLOR);
+            if is_col_selected {
+                style.add_modifier(Modifier::BOLD)
+            } else {
+                style
+            }
         } else if is_col_selected {
             Style::default().fg(*color).add_modifier(Modifier::BOLD)
         } else {
@@ -167,6 +224,8 @@ pub fn draw_kanban(f: &mut Frame, app: &App, area: Rect) {
 
         let block_title = if is_drag_target_col {
             format!(" ▼ {} ({}) ▼ ", title, tasks.len())
+        } else if blocked_count > 0 {
+            format!(" {} ({}, 🔒 {}) ", title, tasks.len(), blocked_count)
         } else {
             format!(" {} ({}) ", title, tasks.len())
         };
@@ -188,6 +247,10 @@ pub fn draw_kanban(f: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+fn is_done(task: &serde_json::Value) -> bool {
+    TaskStatus::of(task) == TaskStatus::Done
+}
+
 fn draw_ghost_card(f: &mut Frame, drag: &crate::app::KanbanDragState, _area: Rect) {
     let ghost_width = (drag.source_task_title.chars().count() + 6).min(30) as u16;
     let ghost_height = 3;
@@ -1,34 +1,157 @@
 use crate::app::App;
+use crate::log_record::{LogLevel, LogRecord};
 use ratatui::{
     Frame,
-    layout::Rect,
-    style::{Color, Style},
-    text::Span,
-    widgets::{Block, Borders, List, ListItem},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
 };
 
+/// Widest the target column gets before targets are cut short.
+const MAX_TARGET_WIDTH: usize = 32;
+
+fn level_color(level: LogLevel) -> Color {
+    match level {
+        LogLevel::Error => Color::Red,
+        LogLevel::Warn => Color::Yellow,
+        LogLevel::Info => Color::Cyan,
+        LogLevel::Debug => Color::Gray,
+        LogLevel::Trace => Color::DarkGray,
+    }
+}
+
+/// RFC 3339 timestamps in local time; anything else as written.
+fn format_timestamp(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+fn log_line<'a>(app: &App, record: &'a LogRecord, target_width: usize) -> Line<'a> {
+    let columns = app.log_columns;
+    let mut spans = Vec::new();
+
+    if columns.timestamp {
+        let timestamp = record
+            .timestamp
+            .as_deref()
+            .map(format_timestamp)
+            .unwrap_or_default();
+        spans.push(Span::styled(
+            format!("{:<14} ", timestamp),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    if columns.level {
+        let (text, style) = match record.level {
+            Some(level) => (
+                level.as_str(),
+                Style::default()
+                    .fg(level_color(level))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            None => ("", Style::default()),
+        };
+        spans.push(Span::styled(format!("{:<5} ", text), style));
+    }
+    if columns.target && target_width > 0 {
+        let target: String = record
+            .target
+            .as_deref()
+            .unwrap_or_default()
+            .chars()
+            .take(target_width)
+            .collect();
+        spans.push(Span::styled(
+            format!("{:<width$} ", target, width = target_width),
+            Style::default().fg(Color::Magenta),
+        ));
+    }
+
+    let message_style = match record.level {
+        Some(LogLevel::Error) => Style::default().fg(Color::Red),
+        Some(LogLevel::Warn) => Style::default().fg(Color::Yellow),
+        _ => Style::default(),
+    };
+    spans.push(Span::styled(record.message.as_str(), message_style));
+    Line::from(spans)
+}
+
+fn draw_log_detail(f: &mut Frame, record: &LogRecord, area: Rect) {
+    let label = Style::default().fg(Color::DarkGray);
+    let mut lines = Vec::new();
+    let mut field = |name: &str, value: String| {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<10} ", name), label),
+            Span::raw(value),
+        ]));
+    };
+
+    if let Some(timestamp) = &record.timestamp {
+        field("timestamp", timestamp.clone());
+    }
+    if let Some(level) = record.level {
+        field("level", level.as_str().to_string());
+    }
+    if let Some(target) = &record.target {
+        field("target", target.clone());
+    }
+    field("message", record.message.clone());
+    for (name, value) in &record.fields {
+        field(name, value.clone());
+    }
+
+    let detail = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title("Log Record"));
+    f.render_widget(detail, area);
+}
+
 pub fn draw_logs(f: &mut Frame, app: &mut App, area: Rect) {
-    let log_items: Vec<ListItem> = app
-        .log_lines
+    let records = app.visible_logs();
+    let selected = records.get(app.selected_log_index).copied();
+
+    let (list_area, detail_area) = match selected {
+        Some(record) if app.log_detail_open => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Fill(1),
+                    Constraint::Length((record.fields.len() as u16).saturating_add(6).min(16)),
+                ])
+                .split(area);
+            (chunks[0], Some((record, chunks[1])))
+        }
+        _ => (area, None),
+    };
+
+    let target_width = records
         .iter()
-        .map(|line| {
-            let colored_line = if line.contains("ERROR") {
-                Span::styled(line.clone(), Style::default().fg(Color::Red))
-            } else if line.contains("WARN") {
-                Span::styled(line.clone(), Style::default().fg(Color::Yellow))
-            } else if line.contains("INFO") {
-                Span::styled(line.clone(), Style::default().fg(Color::Cyan))
-            } else if line.contains("DEBUG") {
-                Span::styled(line.clone(), Style::default().fg(Color::Gray))
-            } else {
-                Span::raw(line.clone())
-            };
-
-            ListItem::new(colored_line)
-        })
+        .filter_map(|r| r.target.as_deref())
+        .map(|t| t.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(MAX_TARGET_WIDTH);
+
+    let log_items: Vec<ListItem> = records
+        .iter()
+        .map(|record| ListItem::new(log_line(app, record, target_width)))
         .collect();
 
-    let title = format!("📋 Daemon Logs ({} lines)", app.log_lines.len());
+    let title = match &app.log_target_filter {
+        Some(target) => format!(
+            "📋 Daemon Logs ({} of {} lines, target {})",
+            records.len(),
+            app.log_records.len(),
+            target
+        ),
+        None => format!("📋 Daemon Logs ({} lines)", records.len()),
+    };
     let logs_list = List::new(log_items)
         .block(Block::default().borders(Borders::ALL).title(title))
         .highlight_style(Style::default().bg(Color::DarkGray))
@@ -37,5 +160,9 @@ pub fn draw_logs(f: &mut Frame, app: &mut App, area: Rect) {
     let mut state = ratatui::widgets::ListState::default();
     state.select(Some(app.selected_log_index));
 
-    f.render_stateful_widget(logs_list, area, &mut state);
+    f.render_stateful_widget(logs_list, list_area, &mut state);
+
+    if let Some((record, detail_area)) = detail_area {
+        draw_log_detail(f, record, detail_area);
+    }
 }
@@ -3,22 +3,34 @@ mod confirmation;
 pub mod cow;
 mod dashboard;
 mod entries;
+mod help;
 pub mod helpers;
 mod input;
 mod kanban;
 mod logs;
+mod pomodoro_ring;
 mod reports;
 mod settings;
+mod status_format;
+mod sync_conflicts;
+mod sync_diff;
+mod task_detail;
 mod timers;
 pub mod tomato;
+mod wizard;
 
-use crate::app::{App, AppView, InputMode};
+use crate::app::{App, AppView, InputMode, SplitPane, TimestampField};
+use crate::keybindings::Action;
 use confirmation::{draw_break_finished_modal, draw_confirmation_modal};
-use dashboard::draw_dashboard;
+use dashboard::{draw_blockers_popup, draw_dashboard};
 use entries::draw_entries;
+use help::draw_help_modal;
+pub use help::help_viewport_height;
+use helpers::{format_duration_hm, format_duration_hms};
 use input::draw_input_modal;
 use kanban::draw_kanban;
 use logs::draw_logs;
+use mootimer_core::models::{ActiveTimer, TaskStatus};
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -28,7 +40,13 @@ use ratatui::{
 };
 use reports::draw_reports;
 use settings::draw_settings;
+use status_format::{StatusSegment, StatusVar, parse_status_format};
+use std::time::Instant;
+use sync_conflicts::draw_sync_conflicts_modal;
+use sync_diff::draw_sync_diff_modal;
+use task_detail::draw_task_detail_modal;
 use timers::draw_timers;
+pub use wizard::draw_wizard;
 
 pub fn draw(f: &mut Frame, app: &mut App) {
     let chunks = Layout::default()
@@ -42,31 +60,32 @@ pub fn draw(f: &mut Frame, app: &mut App) {
 
     draw_title_bar(f, app, chunks[0]);
 
-    match app.current_view {
-        AppView::Dashboard => draw_dashboard(f, app, chunks[1]),
-        AppView::Timers => draw_timers(f, app, chunks[1]),
-        AppView::Kanban => draw_kanban(f, app, chunks[1]),
-        AppView::Entries => draw_entries(f, app, chunks[1]),
-        AppView::Reports => draw_reports(f, app, chunks[1]),
-        AppView::Settings => draw_settings(f, app, chunks[1]),
-        AppView::Logs => draw_logs(f, app, chunks[1]),
+    if app.split_mode {
+        let (focused, other) = split_panes(app, chunks[1]);
+        draw_view(f, app, focused);
+        app.with_other_pane(|app| draw_view(f, app, other));
+        f.buffer_mut()
+            .set_style(other, Style::default().add_modifier(Modifier::DIM));
+    } else {
+        draw_view(f, app, chunks[1]);
     }
 
     draw_status_bar(f, app, chunks[2]);
 
+    if app.current_view == AppView::Dashboard && app.input_mode == InputMode::Normal {
+        draw_blockers_popup(f, app);
+    }
+
     if app.show_help {
         draw_help_modal(f, app);
     }
 
-    if app.input_mode == InputMode::DeleteTaskConfirm
-        || app.input_mode == InputMode::DeleteProfileConfirm
-        || app.input_mode == InputMode::ConfirmQuit
-    {
+    if app.input_mode == InputMode::Confirm {
         draw_confirmation_modal(f, app);
     }
 
     if app.input_mode == InputMode::PomodoroBreakFinished {
-        draw_break_finished_modal(f);
+        draw_break_finished_modal(f, app);
     }
 
     match app.input_mode {
@@ -75,23 +94,58 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         | InputMode::EditTask
         | InputMode::SearchTasks
         | InputMode::FilterEntries
-        | InputMode::ConfigPomodoro
-        | InputMode::ConfigShortBreak
-        | InputMode::ConfigLongBreak
-        | InputMode::ConfigCountdown
+        | InputMode::EntriesDate
+        | InputMode::QuickLog
+        | InputMode::ReportCompare
+        | InputMode::EditSetting
+        | InputMode::ExportConfig
+        | InputMode::ImportConfig
         | InputMode::NewProfile
         | InputMode::RenameProfile
         | InputMode::EditEntryDuration
         | InputMode::NewEntryStart
         | InputMode::NewEntryEnd
-        | InputMode::NewEntryDescription => {
+        | InputMode::NewEntryDescription
+        | InputMode::BulkStatusFrom
+        | InputMode::BulkStatusTo
+        | InputMode::BulkTag
+        | InputMode::BulkUntag => {
             draw_input_modal(f, app);
         }
         InputMode::MoveTask => {
             draw_move_task_modal(f, app);
         }
+        InputMode::MoveEntry => {
+            draw_move_entry_modal(f, app);
+        }
+        InputMode::SelectBranch => {
+            draw_branch_picker_modal(f, app);
+        }
+        InputMode::SyncDiff => {
+            draw_sync_diff_modal(f, app);
+        }
+        InputMode::SyncConflicts => {
+            draw_sync_conflicts_modal(f, app);
+        }
         InputMode::NewEntryTask => {
-            draw_task_select_modal(f, app);
+            draw_task_select_modal(
+                f,
+                app,
+                " Select Task (optional) ".to_string(),
+                "[j/k]Select [Enter]Choose [Esc]Skip",
+            );
+        }
+        InputMode::SessionCapped => {
+            draw_session_capped_modal(f, app);
+        }
+        InputMode::CountdownPresets => {
+            draw_countdown_presets_modal(f, app);
+        }
+        InputMode::EditEntryTimestamp => {
+            draw_entry_timestamp_modal(f, app);
+        }
+        InputMode::TaskDetail => {
+            draw_task_detail_modal(f, app);
         }
         _ => {}
     }
@@ -101,6 +155,30 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     }
 }
 
+fn draw_view(f: &mut Frame, app: &mut App, area: Rect) {
+    match app.current_view {
+        AppView::Dashboard => draw_dashboard(f, app, area),
+        AppView::Timers => draw_timers(f, app, area),
+        AppView::Kanban => draw_kanban(f, app, area),
+        AppView::Entries => draw_entries(f, app, area),
+        AppView::Reports => draw_reports(f, app, area),
+        AppView::Settings => draw_settings(f, app, area),
+        AppView::Logs => draw_logs(f, app, area),
+    }
+}
+
+/// The focused and the unfocused half of the split view's `area`.
+pub fn split_panes(app: &App, area: Rect) -> (Rect, Rect) {
+    let halves = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    match app.split_focus {
+        SplitPane::Left => (halves[0], halves[1]),
+        SplitPane::Right => (halves[1], halves[0]),
+    }
+}
+
 fn draw_title_bar(f: &mut Frame, app: &App, area: Rect) {
     use ratatui::text::{Line, Span};
 
@@ -114,15 +192,19 @@ fn draw_title_bar(f: &mut Frame, app: &App, area: Rect) {
         ("7", "📋", "Logs", AppView::Logs),
     ];
 
-    let mut spans = vec![
-        Span::styled(
-            "🐮 MooTimer ",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw(format!("[{}] │ ", app.get_profile_name())),
-    ];
+    let mut spans = vec![Span::styled(
+        "🐮 MooTimer ",
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    )];
+    let (profile_color, profile_icon) = app.profile_style(&app.profile_id);
+    spans.push(Span::raw("["));
+    spans.push(Span::styled(
+        format!("{} {}", profile_icon, app.get_profile_name()),
+        Style::default().fg(profile_color),
+    ));
+    spans.push(Span::raw("] │ "));
 
     for (i, (key, icon, name, view)) in tabs.iter().enumerate() {
         if i > 0 {
@@ -135,6 +217,10 @@ fn draw_title_bar(f: &mut Frame, app: &App, area: Rect) {
                 .fg(Color::Black)
                 .bg(Color::Cyan)
                 .add_modifier(Modifier::BOLD)
+        } else if app.split_mode && *view == app.other_pane.view {
+            Style::default()
+                .fg(Color::Gray)
+                .add_modifier(Modifier::UNDERLINED)
         } else {
             Style::default().fg(Color::Gray)
         };
@@ -146,7 +232,21 @@ fn draw_title_bar(f: &mut Frame, app: &App, area: Rect) {
         }
     }
 
-    spans.push(Span::raw(" │ [q]Quit"));
+    spans.push(Span::raw(format!(
+        " │ [{}]Quit",
+        app.keybindings.label(Action::Quit)
+    )));
+
+    if !app.daemon_connected {
+        spans.push(Span::raw(" │ "));
+        spans.push(Span::styled(
+            " ⚠ Daemon unreachable, reconnecting… ",
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
 
     let title = Paragraph::new(Line::from(spans))
         .alignment(Alignment::Center)
@@ -155,209 +255,16 @@ fn draw_title_bar(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(title, area);
 }
 
-fn draw_help_modal(f: &mut Frame, _app: &App) {
-    use ratatui::{
-        text::{Line, Span},
-        widgets::Clear,
-    };
-
-    let area = f.area();
-    let modal_width = (area.width as f32 * 0.85) as u16;
-    let modal_height = (area.height as f32 * 0.85) as u16;
-    let modal_x = (area.width.saturating_sub(modal_width)) / 2;
-    let modal_y = (area.height.saturating_sub(modal_height)) / 2;
-
-    let modal_area = Rect {
-        x: modal_x,
-        y: modal_y,
-        width: modal_width,
-        height: modal_height,
-    };
-
-    f.render_widget(Clear, modal_area);
-
-    let help_text = vec![
-        Line::from(Span::styled(
-            "  🐮 MooTimer - Keyboard Shortcuts",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from(""),
-        Line::from(Span::styled(
-            "  GLOBAL NAVIGATION",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from("    [1]          Dashboard (📊)"),
-        Line::from("    [2]          Active Timers (⏱️)"),
-        Line::from("    [3]          Kanban Board (📋)"),
-        Line::from("    [4]          Entries Log (📝)"),
-        Line::from("    [5]          Reports (📈)"),
-        Line::from("    [6]          Settings (⚙️)"),
-        Line::from("    [7]          System Logs (📋)"),
-        Line::from("    [m]          Moo! (🐮)"),
-        Line::from("    [?]          Toggle this Help"),
-        Line::from("    [q] / [Esc]  Quit MooTimer"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "  DASHBOARD - TIMER (Focus with Ctrl+w)",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from("    [Space]      Start / Pause / Resume Timer"),
-        Line::from("    [x]          Stop and Save Timer Entry"),
-        Line::from("    [t]          Cycle Timer Type (Manual → Pomodoro → Countdown)"),
-        Line::from("    [↑↓] / [j/k] Adjust Duration (Pomodoro/Countdown only, when idle)"),
-        Line::from("    [r]          Refresh Timer Status"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "  DASHBOARD - TASKS (Focus with Ctrl+w)",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from("    [n]          Create New Task (Full: Title + Description)"),
-        Line::from("    [N]          Quick Add Task (Title only)"),
-        Line::from("    [e]          Edit Selected Task Title"),
-        Line::from("    [d]          Delete Selected Task"),
-        Line::from("    [a]          Archive / Restore Selected Task"),
-        Line::from("    [Shift+A]    Toggle View: Active vs. Archived Tasks"),
-        Line::from("    [v]          Toggle Visibility of Task Descriptions"),
-        Line::from("    [/]          Search Tasks"),
-        Line::from("    [m]          Move Task to Another Profile"),
-        Line::from("    [↑↓] / [j/k] Navigate Tasks"),
-        Line::from("    [g]g / [G]   Jump to Top / Bottom"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "  KANBAN BOARD",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from("    [h/l]        Switch Column (To Do / In Progress / Done)"),
-        Line::from("    [j/k]        Navigate Cards in Column"),
-        Line::from("    [Shift+h/l]  Move Card to Adjacent Column"),
-        Line::from("    [n]          Create New Task (Full)"),
-        Line::from("    [N]          Quick Add Task (Title only)"),
-        Line::from("    [a]          Archive / Restore Selected Card"),
-        Line::from("    [Shift+A]    Toggle View: Active vs. Archived Cards"),
-        Line::from("    [v]          Toggle Card Descriptions"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "  ENTRIES LOG",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from("    [d] / [w] / [m]  Filter by Today / Week / Month"),
-        Line::from("    [f]              Custom Text Filter"),
-        Line::from("    [e]              Edit Selected Entry Duration"),
-        Line::from("    [d] (Delete)     Delete Selected Entry"),
-        Line::from("    [r]              Refresh Entries"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "  REPORTS",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from("    [d] / [w] / [m]  Switch Report Period (Daily / Weekly / Monthly)"),
-        Line::from("    [p]              Toggle All Profiles vs. Current Profile"),
-        Line::from("    [r]              Refresh Report Data"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "  PROFILE MANAGER (Shift+P)",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from("    [Shift+P]    Open / Close Profile Manager"),
-        Line::from("    [Enter]      Switch to Selected Profile"),
-        Line::from("    [n]          Create New Profile"),
-        Line::from("    [d]          Delete Selected Profile"),
-        Line::from("    [r]          Rename Selected Profile"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "  Press any key to close this help",
-            Style::default()
-                .fg(Color::Gray)
-                .add_modifier(Modifier::ITALIC),
-        )),
-    ];
-
-    let help_paragraph = Paragraph::new(help_text)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("❓ Help")
-                .border_style(
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                ),
-        )
-        .style(Style::default().bg(Color::Black));
-
-    f.render_widget(help_paragraph, modal_area);
-}
-
 fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
-    let profile_name = app.get_profile_name();
-
-    let active_task = if let Some(timer) = &app.timer_info {
-        timer
-            .get("task_id")
-            .and_then(|id| {
-                app.tasks
-                    .iter()
-                    .find(|t| t.get("id") == Some(id))
-                    .and_then(|t| t.get("title"))
-                    .and_then(|v| v.as_str())
-            })
-            .unwrap_or("No active task")
-    } else {
-        "No active timer"
-    };
-
-    let status_content = if !app.status_message.is_empty() {
-        let bg_color = if app.status_message.contains("MOO") {
-            Color::Magenta
-        } else {
-            Color::Yellow
-        };
-        Span::styled(
-            format!(" {} ", app.status_message),
-            Style::default().fg(Color::Black).bg(bg_color),
-        )
-    } else {
-        match app.input_mode {
-            InputMode::Normal => {
-                let hints = match app.current_view {
-                    AppView::Dashboard => "[1-6]Views • [?]Help • [q]Quit",
-                    AppView::Kanban => "[1-6]Views • [h/l]Col • [j/k]Card • [m]Move • [a]Arch",
-                    _ => "[1-6]Views • [↑↓/j/k]Nav • [q]Quit",
-                };
-                Span::raw(hints)
-            }
-            InputMode::DeleteTaskConfirm | InputMode::DeleteProfileConfirm => Span::styled(
-                " Confirm: [Y]es / [N]o ",
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-            ),
-            _ => Span::raw("[Enter] Submit  [Esc] Cancel"),
-        }
-    };
+    let active_timer = app.active_timer();
 
-    let left_info = Span::styled(
-        format!(" 👤 {} ", profile_name),
-        Style::default().fg(Color::Cyan),
-    );
-    let center_info = Span::styled(
-        format!(" 🎯 {} ", active_task),
-        Style::default().fg(Color::Gray),
-    );
+    let mut status_spans: Vec<Span> = parse_status_format(app.status_bar_format())
+        .into_iter()
+        .map(|segment| match segment {
+            StatusSegment::Text(text) => Span::raw(text.to_string()),
+            StatusSegment::Var(var) => status_var_span(app, active_timer.as_ref(), var),
+        })
+        .collect();
 
     let sync_info = if let Some(sync) = &app.sync_status {
         let initialized = sync
@@ -401,18 +308,20 @@ fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
         vec![]
     };
 
-    let mut status_spans = vec![
-        left_info,
-        Span::raw(" │ "),
-        center_info,
-        Span::raw(" │ "),
-        status_content,
-    ];
-
     if !sync_info.is_empty() {
         status_spans.extend(sync_info);
     }
 
+    if let Some(count) = app.count.pending() {
+        status_spans.push(Span::raw(" │ "));
+        status_spans.push(Span::styled(
+            count.to_string(),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
     let status_line = Line::from(status_spans);
 
     let status = Paragraph::new(status_line)
@@ -426,7 +335,253 @@ fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(status, area);
 }
 
-fn draw_task_select_modal(f: &mut Frame, app: &App) {
+fn status_var_span(app: &App, timer: Option<&ActiveTimer>, var: StatusVar) -> Span<'static> {
+    match var {
+        StatusVar::Profile => Span::styled(
+            app.get_profile_name().to_string(),
+            Style::default().fg(app.profile_style(&app.profile_id).0),
+        ),
+        StatusVar::TimerTask => {
+            let task = match &app.timer_info {
+                Some(info) => info
+                    .get("task_id")
+                    .and_then(|id| {
+                        app.tasks
+                            .iter()
+                            .find(|t| t.get("id") == Some(id))
+                            .and_then(|t| t.get("title"))
+                            .and_then(|v| v.as_str())
+                    })
+                    .unwrap_or("No active task"),
+                None => "No active timer",
+            };
+            Span::styled(task.to_string(), Style::default().fg(Color::Gray))
+        }
+        StatusVar::TimerElapsed => Span::styled(
+            timer.map_or_else(
+                || "--:--:--".to_string(),
+                |t| format_duration_hms(app.timer_elapsed(t)),
+            ),
+            Style::default().fg(Color::Cyan),
+        ),
+        StatusVar::TimerState => {
+            let state = app
+                .timer_info
+                .as_ref()
+                .and_then(|t| t.get("state"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("idle");
+            Span::raw(state.to_string())
+        }
+        StatusVar::TodayTotal => {
+            let total = app
+                .stats_today
+                .as_ref()
+                .and_then(|s| s.get("total_duration_seconds"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0);
+            Span::raw(format_duration_hm(total))
+        }
+        StatusVar::View => Span::raw(view_name(app.current_view)),
+        StatusVar::Hints => status_hints_span(app),
+    }
+}
+
+/// A running sync's progress, else the status message if there is one,
+/// otherwise key hints for the mode.
+fn status_hints_span(app: &App) -> Span<'static> {
+    if let Some(progress) = &app.sync_progress {
+        return Span::styled(
+            format!(" {} [Esc] stop waiting ", progress.label(Instant::now())),
+            Style::default().fg(Color::Black).bg(Color::Cyan),
+        );
+    }
+    if !app.status_message.is_empty() {
+        let bg_color = if app.status_message.contains("MOO") {
+            Color::Magenta
+        } else {
+            Color::Yellow
+        };
+        return Span::styled(
+            format!(" {} ", app.status_message),
+            Style::default().fg(Color::Black).bg(bg_color),
+        );
+    }
+
+    match app.input_mode {
+        InputMode::Normal => {
+            let hints = match app.current_view {
+                AppView::Dashboard => "[1-6]Views • [?]Help • [q]Quit",
+                AppView::Kanban => "[1-6]Views • [h/l]Col • [j/k]Card • [m]Move • [a]Arch",
+                _ => "[1-6]Views • [↑↓/j/k]Nav • [q]Quit",
+            };
+            Span::raw(hints)
+        }
+        InputMode::Confirm => Span::styled(
+            " [←→] Choose • [Enter] Select • [y]es / [n]o ",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+        InputMode::TaskDetail => Span::raw("[Tab] Field • [Ctrl+s] Save • [Esc] Cancel"),
+        _ => Span::raw("[Enter] Submit  [Esc] Cancel"),
+    }
+}
+
+fn view_name(view: AppView) -> &'static str {
+    match view {
+        AppView::Dashboard => "Dashboard",
+        AppView::Timers => "Timers",
+        AppView::Kanban => "Kanban",
+        AppView::Entries => "Entries",
+        AppView::Reports => "Reports",
+        AppView::Settings => "Settings",
+        AppView::Logs => "Logs",
+    }
+}
+
+fn draw_countdown_presets_modal(f: &mut Frame, app: &App) {
+    let presets = app.countdown_presets();
+    let area = f.area();
+    let modal_width = 32.min(area.width.saturating_sub(4));
+    let modal_height = (presets.len() as u16 + 2).min(area.height.saturating_sub(4));
+    let modal_area = Rect {
+        x: (area.width.saturating_sub(modal_width)) / 2,
+        y: (area.height.saturating_sub(modal_height)) / 2,
+        width: modal_width,
+        height: modal_height,
+    };
+    f.render_widget(Clear, modal_area);
+
+    let items: Vec<ratatui::widgets::ListItem> = presets
+        .iter()
+        .enumerate()
+        .map(|(i, minutes)| {
+            let style = if i == app.selected_preset_index {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            ratatui::widgets::ListItem::new(format!(" [{}] {} minutes", i + 1, minutes))
+                .style(style)
+        })
+        .collect();
+
+    let list = ratatui::widgets::List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" ⏲ Countdown Presets ")
+            .title_bottom(Line::from(" [1-9/Enter]Start [Esc]Close ").right_aligned())
+            .border_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+    );
+    f.render_widget(list, modal_area);
+}
+
+fn draw_entry_timestamp_modal(f: &mut Frame, app: &App) {
+    let Some(edit) = &app.timestamp_edit else {
+        return;
+    };
+    let area = f.area();
+    let modal_width = 44.min(area.width.saturating_sub(4));
+    let modal_height = 7.min(area.height.saturating_sub(4));
+    let modal_area = Rect {
+        x: (area.width.saturating_sub(modal_width)) / 2,
+        y: (area.height.saturating_sub(modal_height)) / 2,
+        width: modal_width,
+        height: modal_height,
+    };
+    f.render_widget(Clear, modal_area);
+
+    let row = |label: &str, field: TimestampField, time: chrono::DateTime<chrono::Utc>| {
+        let selected = edit.field == field;
+        let style = if selected {
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        Line::from(vec![
+            Span::raw(format!(" {:<7}", label)),
+            Span::styled(
+                format!(
+                    " {} ",
+                    time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                ),
+                style,
+            ),
+        ])
+    };
+    let duration = (edit.end - edit.start).num_minutes();
+    let duration_style = if duration > 0 {
+        Style::default().fg(Color::Green)
+    } else {
+        Style::default().fg(Color::Red)
+    };
+    let mut lines = vec![
+        row("Start", TimestampField::Start, edit.start),
+        row("End", TimestampField::End, edit.end),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw(" Duration "),
+            Span::styled(format!("{}m", duration), duration_style),
+        ]),
+    ];
+    if !edit.count.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!(" × {}", edit.count),
+            Style::default().fg(Color::Cyan),
+        )));
+    }
+
+    let modal = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" 🕑 Edit Entry Times ")
+            .title_bottom(
+                Line::from(" [ ]±1m { }±5m [Tab]Field [Enter]Save [Esc] ").right_aligned(),
+            )
+            .border_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+    );
+    f.render_widget(modal, modal_area);
+}
+
+fn draw_session_capped_modal(f: &mut Frame, app: &App) {
+    let Some(capped) = &app.capped_session else {
+        return;
+    };
+    let task_title = app
+        .tasks
+        .iter()
+        .find(|t| t.get("id").and_then(|v| v.as_str()) == Some(capped.task_id.as_str()))
+        .and_then(|t| t.get("title"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("task");
+
+    draw_task_select_modal(
+        f,
+        app,
+        format!(
+            " {} capped at {}: log {} over to ",
+            task_title,
+            format_duration_hm(capped.cap_seconds),
+            format_duration_hm(capped.overflow_seconds)
+        ),
+        "[j/k]Select [Enter]Log overflow [d/Esc]Discard",
+    );
+}
+
+fn draw_task_select_modal(f: &mut Frame, app: &App, title: String, actions: &str) {
     let area = f.area();
     let modal_width = 60.min(area.width.saturating_sub(4));
     let tasks = app.get_tasks_for_entry_selection();
@@ -462,8 +617,7 @@ fn draw_task_select_modal(f: &mut Frame, app: &App) {
             .get("title")
             .and_then(|v| v.as_str())
             .unwrap_or("Untitled");
-        let status = task.get("status").and_then(|v| v.as_str()).unwrap_or("");
-        let is_archived = status == "archived";
+        let is_archived = TaskStatus::of(task) == TaskStatus::Archived;
         let is_selected = app.new_entry_task_index == i + 1;
 
         let style = if is_selected {
@@ -493,12 +647,12 @@ fn draw_task_select_modal(f: &mut Frame, app: &App) {
     } else {
         "[a] Show archived"
     };
-    let hint = format!(" [j/k]Select [Enter]Choose [Esc]Skip | {} ", archived_hint);
+    let hint = format!(" {} | {} ", actions, archived_hint);
 
     let list = ratatui::widgets::List::new(items).block(
         Block::default()
             .borders(Borders::ALL)
-            .title(" Select Task (optional) ")
+            .title(title)
             .title_bottom(Line::from(hint).right_aligned())
             .border_style(
                 Style::default()
@@ -511,6 +665,43 @@ fn draw_task_select_modal(f: &mut Frame, app: &App) {
 }
 
 fn draw_move_task_modal(f: &mut Frame, app: &App) {
+    let task_title = app
+        .get_filtered_tasks()
+        .get(app.selected_task_index)
+        .and_then(|t| t.get("title"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("Unknown task");
+
+    draw_move_target_modal(
+        f,
+        app,
+        format!(" Move: {} ", task_title),
+        " [j/k]Select [Enter]Move [Esc]Cancel ",
+    );
+}
+
+fn draw_move_entry_modal(f: &mut Frame, app: &App) {
+    let label = app
+        .get_filtered_entries()
+        .get(app.selected_entry_index)
+        .copied()
+        .and_then(|e| {
+            e.get("task_title")
+                .and_then(|v| v.as_str())
+                .or_else(|| e.get("description").and_then(|v| v.as_str()))
+        })
+        .unwrap_or("Entry");
+
+    draw_move_target_modal(
+        f,
+        app,
+        format!(" Move or copy: {} ", label),
+        " [j/k]Select [Enter]Move [c]Copy [Esc]Cancel ",
+    );
+}
+
+/// Lists the other profiles for picking where a task or entry goes.
+fn draw_move_target_modal(f: &mut Frame, app: &App, title: String, hint: &str) {
     let area = f.area();
     let modal_width = 50.min(area.width.saturating_sub(4));
     let profiles = app.get_move_task_profiles();
@@ -525,14 +716,6 @@ fn draw_move_task_modal(f: &mut Frame, app: &App) {
 
     f.render_widget(Clear, modal_area);
 
-    let task_title = app
-        .get_filtered_tasks()
-        .get(app.selected_task_index)
-        .and_then(|t| t.get("title"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("Unknown task");
-
-    let title = format!(" Move: {} ", task_title);
     let truncated_title = if title.len() > modal_width as usize - 2 {
         format!("{}...", &title[..modal_width as usize - 5])
     } else {
@@ -567,7 +750,63 @@ fn draw_move_task_modal(f: &mut Frame, app: &App) {
         Block::default()
             .borders(Borders::ALL)
             .title(truncated_title)
-            .title_bottom(Line::from(" [j/k]Select [Enter]Move [Esc]Cancel ").right_aligned())
+            .title_bottom(Line::from(hint).right_aligned())
+            .border_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+    );
+
+    f.render_widget(list, modal_area);
+}
+
+fn draw_branch_picker_modal(f: &mut Frame, app: &App) {
+    let area = f.area();
+    let modal_width = 50.min(area.width.saturating_sub(4));
+    let modal_height = (app.branches.len() as u16 + 2)
+        .min(area.height.saturating_sub(4))
+        .max(3);
+    let modal_area = Rect {
+        x: (area.width.saturating_sub(modal_width)) / 2,
+        y: (area.height.saturating_sub(modal_height)) / 2,
+        width: modal_width,
+        height: modal_height,
+    };
+
+    f.render_widget(Clear, modal_area);
+
+    let items: Vec<ratatui::widgets::ListItem> = app
+        .branches
+        .iter()
+        .enumerate()
+        .map(|(i, branch)| {
+            let name = branch["name"].as_str().unwrap_or("?");
+            let is_selected = i == app.selected_branch_index;
+            let is_current = branch["current"].as_bool() == Some(true);
+
+            let style = if is_selected {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else if branch["remote"].as_bool() == Some(true) {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White)
+            };
+
+            let prefix = if is_selected { "→ " } else { "  " };
+            let marker = if is_current { " ✓" } else { "" };
+            ratatui::widgets::ListItem::new(format!("{}{}{}", prefix, name, marker)).style(style)
+        })
+        .collect();
+
+    let list = ratatui::widgets::List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Branches ")
+            .title_bottom(Line::from(" [j/k]Select [Enter]Switch [Esc]Cancel ").right_aligned())
             .border_style(
                 Style::default()
                     .fg(Color::Cyan)
@@ -0,0 +1,136 @@
+//! Circular progress for the current pomodoro phase.
+//!
+//! Each cell is split into a 2x2 grid of quarter blocks (`▀ ▄ ▌ ▐` and
+//! friends), which is enough resolution for a recognisable ring in a
+//! dozen rows. Progress runs clockwise from twelve o'clock; the part of the
+//! ring still to go is drawn dimmed.
+
+use mootimer_core::models::PomodoroPhase;
+use ratatui::prelude::*;
+use ratatui::widgets::Widget;
+use std::f64::consts::TAU;
+
+/// Terminal cells are roughly twice as tall as they are wide.
+const CELL_ASPECT: f64 = 2.0;
+
+/// Ring thickness as a share of its outer radius.
+const THICKNESS: f64 = 0.3;
+
+/// Glyphs indexed by a 4-bit mask: top-left, top-right, bottom-left,
+/// bottom-right.
+const QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+pub struct PomodoroRing {
+    ratio: f64,
+    phase: PomodoroPhase,
+}
+
+impl PomodoroRing {
+    pub fn new(ratio: f64, phase: PomodoroPhase) -> Self {
+        Self {
+            ratio: ratio.clamp(0.0, 1.0),
+            phase,
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self.phase {
+            PomodoroPhase::Work => Color::Green,
+            PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak => Color::Yellow,
+        }
+    }
+
+    /// Width in cells of a round ring `height` rows tall.
+    pub fn width_for(height: u16) -> u16 {
+        (height as f64 * CELL_ASPECT).round() as u16
+    }
+}
+
+impl Widget for PomodoroRing {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 4 || area.height < 2 {
+            return;
+        }
+
+        // Work in units of one cell width, so the ring stays round.
+        let cx = area.width as f64 / 2.0;
+        let cy = area.height as f64 * CELL_ASPECT / 2.0;
+        let outer = cx.min(cy);
+        let inner = outer * (1.0 - THICKNESS);
+
+        let done = Style::default().fg(self.color());
+        let todo = Style::default().fg(Color::DarkGray);
+
+        for row in 0..area.height {
+            for col in 0..area.width {
+                let mut mask = 0;
+                for (bit, (qx, qy)) in [(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+                    .into_iter()
+                    .enumerate()
+                {
+                    let dx = col as f64 + qx - cx;
+                    let dy = (row as f64 + qy) * CELL_ASPECT - cy;
+                    let distance = dx.hypot(dy);
+                    if distance >= inner && distance <= outer {
+                        mask |= 1 << bit;
+                    }
+                }
+                if mask == 0 {
+                    continue;
+                }
+
+                // Clockwise from twelve o'clock, measured at the cell centre.
+                let dx = col as f64 + 0.5 - cx;
+                let dy = (row as f64 + 0.5) * CELL_ASPECT - cy;
+                let angle = dx.atan2(-dy).rem_euclid(TAU);
+                let style = if angle / TAU < self.ratio { done } else { todo };
+
+                buf[(area.x + col, area.y + row)]
+                    .set_char(QUADRANTS[mask])
+                    .set_style(style);
+            }
+        }
+
+        let label = format!("{:.0}%", self.ratio * 100.0);
+        let x = area.x + (area.width.saturating_sub(label.len() as u16)) / 2;
+        let y = area.y + area.height / 2;
+        buf.set_string(x, y, label, done.add_modifier(Modifier::BOLD));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn colored(buf: &Buffer, color: Color) -> usize {
+        buf.content()
+            .iter()
+            .filter(|c| c.fg == color && c.symbol().chars().all(|ch| QUADRANTS[1..].contains(&ch)))
+            .count()
+    }
+
+    #[test]
+    fn test_ring_fills_clockwise_with_phase_color() {
+        let area = Rect::new(0, 0, 20, 10);
+
+        let mut buf = Buffer::empty(area);
+        PomodoroRing::new(0.0, PomodoroPhase::Work).render(area, &mut buf);
+        let track = colored(&buf, Color::DarkGray);
+        assert!(track > 0);
+        assert_eq!(colored(&buf, Color::Green), 0);
+
+        let mut buf = Buffer::empty(area);
+        PomodoroRing::new(0.5, PomodoroPhase::Work).render(area, &mut buf);
+        // The right half is done, the left half still to go.
+        assert_eq!(buf[(18, 5)].fg, Color::Green);
+        assert_eq!(buf[(1, 5)].fg, Color::DarkGray);
+        assert_eq!(buf[(8, 5)].symbol(), "5");
+
+        let mut buf = Buffer::empty(area);
+        PomodoroRing::new(1.0, PomodoroPhase::ShortBreak).render(area, &mut buf);
+        assert_eq!(colored(&buf, Color::DarkGray), 0);
+        assert!(colored(&buf, Color::Yellow) >= track);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::app::App;
+use crate::app::{App, ReportComparison};
 use crate::ui::helpers::format_duration_hm;
 use ratatui::{
     Frame,
@@ -8,6 +8,9 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
 };
 
+/// Most budgets listed above the breakdown.
+const MAX_BUDGET_ROWS: usize = 6;
+
 pub fn draw_reports(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -15,7 +18,85 @@ pub fn draw_reports(f: &mut Frame, app: &App, area: Rect) {
         .split(area);
 
     draw_report_summary(f, app, chunks[0]);
-    draw_task_breakdown(f, app, chunks[1]);
+
+    let mut breakdown_area = chunks[1];
+    if !app.report_budgets.is_empty() {
+        let rows = app.report_budgets.len().min(MAX_BUDGET_ROWS) as u16;
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(rows + 2), Constraint::Min(5)])
+            .split(chunks[1]);
+        draw_budgets(f, app, parts[0]);
+        breakdown_area = parts[1];
+    }
+
+    if let Some(comparison) = &app.report_compare {
+        draw_comparison(f, comparison, breakdown_area);
+    } else if let Some(hours) = &app.report_hourly {
+        draw_hourly(f, hours, breakdown_area);
+    } else if app.report_by_device {
+        draw_device_breakdown(f, app, breakdown_area);
+    } else {
+        draw_task_breakdown(f, app, breakdown_area);
+    }
+}
+
+/// One line per budget of the reported profile with its use in the
+/// current period, whatever period the report shows.
+fn draw_budgets(f: &mut Frame, app: &App, area: Rect) {
+    let lines: Vec<Line> = app
+        .report_budgets
+        .iter()
+        .take(MAX_BUDGET_ROWS)
+        .map(|status| {
+            let used = status
+                .get("used_seconds")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0);
+            let limit = status
+                .pointer("/budget/limit_seconds")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0);
+            let percent = status.get("percent").and_then(|v| v.as_u64()).unwrap_or(0);
+            let description = status
+                .get("description")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let blocks = status.pointer("/budget/action").and_then(|v| v.as_str())
+                == Some("block_new_timers");
+
+            let color = match percent {
+                100.. => Color::Red,
+                80..=99 => Color::Yellow,
+                _ => Color::Green,
+            };
+            let filled = (percent.min(100) / 10) as usize;
+            let mut spans = vec![
+                Span::styled(
+                    format!("  {}{} ", "█".repeat(filled), "░".repeat(10 - filled)),
+                    Style::default().fg(color),
+                ),
+                Span::styled(format!("{:>3}%  ", percent), Style::default().fg(color)),
+                Span::raw(format!(
+                    "{} / {}  ",
+                    format_duration_hm(used),
+                    format_duration_hm(limit)
+                )),
+                Span::styled(description.to_string(), Style::default().fg(Color::Gray)),
+            ];
+            if blocks {
+                spans.push(Span::styled(
+                    "  ⛔ blocks timers",
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    let budgets =
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" 🎯 Budgets "));
+    f.render_widget(budgets, area);
 }
 
 fn draw_report_summary(f: &mut Frame, app: &App, area: Rect) {
@@ -47,7 +128,7 @@ fn draw_report_summary(f: &mut Frame, app: &App, area: Rect) {
             app.get_profile_name_by_id(&app.report_profile).to_string()
         };
 
-        vec![
+        let mut lines = vec![
             Line::from(""),
             Line::from(Span::styled(
                 format!(
@@ -69,7 +150,20 @@ fn draw_report_summary(f: &mut Frame, app: &App, area: Rect) {
                 entries, pomodoros, manual
             )),
             Line::from(format!("  Average Session:   {}m", avg_secs / 60)),
-        ]
+        ];
+        if let Some(line) = stats.get("pomodoro").and_then(pomodoro_line) {
+            lines.push(line);
+        }
+        if let Some(line) = stats.get("break_compliance").and_then(break_line) {
+            lines.push(line);
+        }
+        if let Some(line) = stats
+            .get("working_hours")
+            .and_then(|split| outside_hours_line(split, app.outside_hours_warn_percent()))
+        {
+            lines.push(line);
+        }
+        lines
     } else {
         vec![Line::from(""), Line::from("  Loading...")]
     };
@@ -80,8 +174,8 @@ fn draw_report_summary(f: &mut Frame, app: &App, area: Rect) {
         app.get_profile_name_by_id(&app.report_profile)
     };
 
-    let period_hint = "[d]ay [w]eek [m]onth";
-    let profile_hint = "[p]rofile toggle";
+    let period_hint = "[d]ay [w]eek [m]onth [H]ourly";
+    let profile_hint = "[p]rofile toggle | [v] task/device | [V]Compare | [E]xport CSV";
 
     let report = Paragraph::new(report_text).block(
         Block::default()
@@ -99,34 +193,98 @@ fn draw_report_summary(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(report, area);
 }
 
-fn draw_task_breakdown(f: &mut Frame, app: &App, area: Rect) {
-    use std::collections::HashMap;
+/// "🍅 12 completed / 3 abandoned (80%)", or `None` before any pomodoro.
+fn pomodoro_line(pomodoro: &serde_json::Value) -> Option<Line<'static>> {
+    let completed = pomodoro.get("completed").and_then(|v| v.as_u64())?;
+    let abandoned = pomodoro.get("abandoned").and_then(|v| v.as_u64())?;
+    if completed + abandoned == 0 {
+        return None;
+    }
+    let rate = pomodoro
+        .get("completion_rate")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0);
 
-    let mut task_map: HashMap<String, (u64, usize)> = HashMap::new();
+    let mut text = format!(
+        "  Pomodoros:         🍅 {} completed / {} abandoned ({:.0}%)",
+        completed,
+        abandoned,
+        rate * 100.0
+    );
+    if let Some(secs) = pomodoro
+        .get("avg_interruption_seconds")
+        .and_then(|v| v.as_u64())
+    {
+        text.push_str(&format!(", given up after {}m on average", secs / 60));
+    }
+    Some(Line::from(text))
+}
 
-    for entry in &app.report_entries {
-        let duration = entry
-            .get("duration_seconds")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0);
+/// "3h 20m (12%)" outside working hours, in red above `warn_percent`, and
+/// the weekend's share of it. `None` when nothing was logged.
+fn outside_hours_line(split: &serde_json::Value, warn_percent: f64) -> Option<Line<'static>> {
+    let seconds = |key: &str| split.get(key).and_then(|v| v.as_u64());
+    let outside = seconds("outside_seconds")?;
+    let total = outside + seconds("inside_seconds")?;
+    if total == 0 {
+        return None;
+    }
+    let percent = outside as f64 * 100.0 / total as f64;
 
-        let task_id = entry
-            .get("task_id")
-            .and_then(|v| v.as_str())
-            .unwrap_or("No task")
-            .to_string();
+    let color = if percent > warn_percent {
+        Color::Red
+    } else {
+        Color::Green
+    };
+    let mut spans = vec![
+        Span::raw("  Outside hours:     "),
+        Span::styled(
+            format!("{} ({:.0}%)", format_duration_hm(outside), percent),
+            Style::default().fg(color),
+        ),
+    ];
+    if let Some(weekend) = seconds("weekend_seconds").filter(|&s| s > 0) {
+        spans.push(Span::raw(format!(
+            ", {} of it at the weekend",
+            format_duration_hm(weekend)
+        )));
+    }
+    Some(Line::from(spans))
+}
 
-        let entry_data = task_map.entry(task_id).or_insert((0, 0));
-        entry_data.0 += duration;
-        entry_data.1 += 1;
+/// "☕ 2 of 3 breaks taken (67%)", or `None` if no breaks were offered.
+fn break_line(compliance: &serde_json::Value) -> Option<Line<'static>> {
+    let offered = compliance.get("offered").and_then(|v| v.as_u64())?;
+    if offered == 0 {
+        return None;
     }
+    let taken = compliance
+        .get("taken")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let rate = compliance
+        .get("rate")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0);
 
-    let mut task_breakdown: Vec<(String, u64, usize)> = task_map
-        .into_iter()
-        .map(|(task_id, (secs, count))| (task_id, secs, count))
-        .collect();
+    let color = if rate >= 0.8 {
+        Color::Green
+    } else if rate >= 0.5 {
+        Color::Yellow
+    } else {
+        Color::Red
+    };
+    Some(Line::from(vec![
+        Span::raw("  Breaks:            "),
+        Span::styled(
+            format!("☕ {} of {} taken ({:.0}%)", taken, offered, rate * 100.0),
+            Style::default().fg(color),
+        ),
+    ]))
+}
 
-    task_breakdown.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+fn draw_task_breakdown(f: &mut Frame, app: &App, area: Rect) {
+    let task_breakdown = app.report_task_breakdown();
 
     let mut lines = vec![
         Line::from(""),
@@ -142,25 +300,20 @@ fn draw_task_breakdown(f: &mut Frame, app: &App, area: Rect) {
     if task_breakdown.is_empty() {
         lines.push(Line::from("  No sessions recorded for this period"));
     } else {
-        for (task_id, total_secs, count) in task_breakdown.iter().take(10) {
-            let task_display = if task_id == "No task" {
-                "No task".to_string()
-            } else {
-                let task_name = app
-                    .tasks
-                    .iter()
-                    .find(|t| t.get("id").and_then(|v| v.as_str()) == Some(task_id))
-                    .and_then(|t| t.get("title"))
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown");
-
-                format!("{} [{}]", task_name, &task_id[..8])
+        for row in task_breakdown.iter().take(10) {
+            let task_display = match &row.task_id {
+                Some(task_id) => format!(
+                    "{} [{}]",
+                    app.report_task_title(row),
+                    &task_id[..8.min(task_id.len())]
+                ),
+                None => app.report_task_title(row),
             };
 
-            let time_str = if *total_secs >= 3600 {
-                format_duration_hm(*total_secs)
+            let time_str = if row.seconds >= 3600 {
+                format_duration_hm(row.seconds)
             } else {
-                format!("{}m", total_secs / 60)
+                format!("{}m", row.seconds / 60)
             };
 
             let display_name = if task_display.len() > 40 {
@@ -169,10 +322,21 @@ fn draw_task_breakdown(f: &mut Frame, app: &App, area: Rect) {
                 task_display
             };
 
-            lines.push(Line::from(format!(
-                "  {:40} {:>8}  ({} sessions)",
-                display_name, time_str, count
-            )));
+            let text = format!(
+                "{:40} {:>8}  ({} sessions)",
+                display_name, time_str, row.sessions
+            );
+            lines.push(match &row.profile_id {
+                Some(profile_id) => {
+                    let (color, icon) = app.profile_style(profile_id);
+                    Line::from(vec![
+                        Span::raw("  "),
+                        Span::styled(format!("{} ", icon), Style::default().fg(color)),
+                        Span::raw(text),
+                    ])
+                }
+                None => Line::from(format!("  {}", text)),
+            });
         }
 
         if task_breakdown.len() > 10 {
@@ -191,3 +355,190 @@ fn draw_task_breakdown(f: &mut Frame, app: &App, area: Rect) {
     );
     f.render_widget(breakdown, area);
 }
+
+fn draw_device_breakdown(f: &mut Frame, app: &App, area: Rect) {
+    use std::collections::HashMap;
+
+    let mut device_map: HashMap<&str, (u64, usize)> = HashMap::new();
+    for entry in &app.report_entries {
+        let duration = entry
+            .get("duration_seconds")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        let device = entry
+            .get("device_name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown device");
+
+        let totals = device_map.entry(device).or_insert((0, 0));
+        totals.0 += duration;
+        totals.1 += 1;
+    }
+
+    let mut device_breakdown: Vec<(&str, u64, usize)> = device_map
+        .into_iter()
+        .map(|(device, (secs, count))| (device, secs, count))
+        .collect();
+    device_breakdown.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "  By Device:",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    if device_breakdown.is_empty() {
+        lines.push(Line::from("  No sessions recorded for this period"));
+    }
+    for (device, total_secs, count) in device_breakdown {
+        lines.push(Line::from(format!(
+            "  {:40} {:>8}  ({} sessions)",
+            device,
+            format_duration_hm(total_secs),
+            count
+        )));
+    }
+
+    let breakdown = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" 💻 Device Breakdown "),
+    );
+    f.render_widget(breakdown, area);
+}
+
+/// One row per hour of the day, with a bar as long as its average session
+/// relative to the longest.
+fn draw_hourly(f: &mut Frame, hours: &[serde_json::Value], area: Rect) {
+    let average = |hour: &serde_json::Value| hour["avg_duration_seconds"].as_f64().unwrap_or(0.0);
+    let longest = hours.iter().map(average).fold(0.0, f64::max);
+    // Room left for the bar next to "  09:00 " and " 1h 05m  (12)".
+    let bar_width = (area.width as usize).saturating_sub(30).max(10);
+
+    let lines: Vec<Line> = hours
+        .iter()
+        .map(|hour| {
+            let seconds = average(hour);
+            let sessions = hour["session_count"].as_u64().unwrap_or(0);
+            let filled = if longest > 0.0 {
+                (seconds / longest * bar_width as f64).round() as usize
+            } else {
+                0
+            };
+            let label = Span::raw(format!("  {:02}:00 ", hour["hour"].as_u64().unwrap_or(0)));
+            if sessions == 0 {
+                return Line::from(vec![
+                    label,
+                    Span::styled("·", Style::default().fg(Color::DarkGray)),
+                ]);
+            }
+            Line::from(vec![
+                label,
+                Span::styled("█".repeat(filled), Style::default().fg(Color::Cyan)),
+                Span::raw(format!(
+                    " {}  ({})",
+                    format_duration_hm(seconds.round() as u64),
+                    sessions
+                )),
+            ])
+        })
+        .collect();
+
+    let chart = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" 🕘 Average Session by Hour "),
+    );
+    f.render_widget(chart, area);
+}
+
+/// A signed difference, green when the second period is ahead and red
+/// when it is behind.
+fn delta_span(delta: i64, text: String) -> Span<'static> {
+    let color = match delta.signum() {
+        1 => Color::Green,
+        -1 => Color::Red,
+        _ => Color::DarkGray,
+    };
+    Span::styled(text, Style::default().fg(color))
+}
+
+fn draw_comparison(f: &mut Frame, comparison: &ReportComparison, area: Rect) {
+    let (a, b) = (
+        &comparison.result["period_a"],
+        &comparison.result["period_b"],
+    );
+    let number = |stats: &serde_json::Value, key: &str| stats[key].as_u64().unwrap_or(0);
+    let signed_duration = |delta: i64| {
+        let sign = if delta < 0 { "-" } else { "+" };
+        format!("{}{}", sign, format_duration_hm(delta.unsigned_abs()))
+    };
+
+    let rows = [
+        ("Total Time", "total_duration_seconds", true),
+        ("Sessions", "total_entries", false),
+        ("Pomodoros", "pomodoro_count", false),
+        ("Avg Session", "avg_duration_seconds", true),
+    ];
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "  {:14} {:>24} {:>24} {:>12}",
+                "", comparison.label_a, comparison.label_b, "Δ"
+            ),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    for (label, key, is_duration) in rows {
+        let (value_a, value_b) = (number(a, key), number(b, key));
+        let delta = value_b as i64 - value_a as i64;
+        let (text_a, text_b, text_delta) = if is_duration {
+            (
+                format_duration_hm(value_a),
+                format_duration_hm(value_b),
+                signed_duration(delta),
+            )
+        } else {
+            (
+                value_a.to_string(),
+                value_b.to_string(),
+                format!("{:+}", delta),
+            )
+        };
+        lines.push(Line::from(vec![
+            Span::raw(format!("  {:14} {:>24} {:>24} ", label, text_a, text_b)),
+            delta_span(delta, format!("{:>12}", text_delta)),
+        ]));
+    }
+
+    let delta = &comparison.result["delta"];
+    let change = match delta["percent_change"].as_f64() {
+        Some(percent) => delta_span(
+            percent.signum() as i64,
+            format!("{:+.0}% total time", percent),
+        ),
+        None => Span::styled(
+            "no time in the earlier period",
+            Style::default().fg(Color::DarkGray),
+        ),
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![Span::raw("  Change: "), change]));
+
+    let table = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" ⚖ Period Comparison "),
+    );
+    f.render_widget(table, area);
+}
@@ -1,223 +1,184 @@
 use crate::app::App;
+use crate::settings::{self, SettingAction, SettingDef, SettingTarget, SettingsRow};
+use chrono::{DateTime, Local};
 use ratatui::{
     Frame,
-    layout::Rect,
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem},
 };
 
+const LABEL_WIDTH: usize = 25;
+
 pub fn draw_settings(f: &mut Frame, app: &App, area: Rect) {
-    use crate::app::SettingsItem;
+    let area = if app.sync_history.is_empty() {
+        area
+    } else {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(app.sync_history.len() as u16 + 2),
+            ])
+            .split(area);
+        draw_sync_history(f, app, chunks[1]);
+        chunks[0]
+    };
 
-    let items: Vec<ListItem> = SettingsItem::ALL
-        .iter()
-        .enumerate()
-        .map(|(i, &item)| {
-            let is_selected = i == app.selected_setting_index;
-            let style = if is_selected {
-                Style::default().bg(Color::DarkGray)
-            } else {
+    let items: Vec<ListItem> = settings::rows()
+        .into_iter()
+        .map(|row| match row {
+            SettingsRow::Header(title) => ListItem::new(Line::from(Span::styled(
+                title,
                 Style::default()
-            };
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ))),
+            SettingsRow::Item(index, def) => {
+                let is_selected = index == app.selected_setting_index;
+                let style = if is_selected {
+                    Style::default().bg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(setting_line(app, def, is_selected)).style(style)
+            }
+        })
+        .collect();
 
-            let line = match item {
-                SettingsItem::PomodoroWork => {
-                    let val = app
-                        .config
-                        .as_ref()
-                        .and_then(|c| c.get("pomodoro").and_then(|p| p.get("work_duration")))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(1500)
-                        / 60;
-                    Line::from(vec![
-                        Span::styled(
-                            "Work Duration   ",
-                            Style::default().add_modifier(Modifier::BOLD),
-                        ),
-                        Span::raw(format!("{} minutes", val)),
-                        if is_selected {
-                            Span::raw("  <[h/l] to change>")
-                        } else {
-                            Span::raw("")
-                        },
-                    ])
-                }
-                SettingsItem::PomodoroShortBreak => {
-                    let val = app
-                        .config
-                        .as_ref()
-                        .and_then(|c| c.get("pomodoro").and_then(|p| p.get("short_break")))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(300)
-                        / 60;
-                    Line::from(vec![
-                        Span::styled(
-                            "Short Break     ",
-                            Style::default().add_modifier(Modifier::BOLD),
-                        ),
-                        Span::raw(format!("{} minutes", val)),
-                        if is_selected {
-                            Span::raw("  <[h/l] to change>")
-                        } else {
-                            Span::raw("")
-                        },
-                    ])
-                }
-                SettingsItem::PomodoroLongBreak => {
-                    let val = app
-                        .config
-                        .as_ref()
-                        .and_then(|c| c.get("pomodoro").and_then(|p| p.get("long_break")))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(900)
-                        / 60;
-                    Line::from(vec![
-                        Span::styled(
-                            "Long Break      ",
-                            Style::default().add_modifier(Modifier::BOLD),
-                        ),
-                        Span::raw(format!("{} minutes", val)),
-                        if is_selected {
-                            Span::raw("  <[h/l] to change>")
-                        } else {
-                            Span::raw("")
-                        },
-                    ])
-                }
-                SettingsItem::AudioAlerts => {
-                    let val = if app.audio_alerts_enabled {
-                        "Enabled"
-                    } else {
-                        "Disabled"
-                    };
-                    Line::from(vec![
-                        Span::styled(
-                            "Audio Alerts    ",
-                            Style::default().add_modifier(Modifier::BOLD),
-                        ),
-                        Span::raw(val),
-                        if is_selected {
-                            Span::raw("  <[Space] to toggle>")
-                        } else {
-                            Span::raw("")
-                        },
-                    ])
-                }
-                SettingsItem::CountdownDefault => {
-                    let val = app.countdown_minutes;
-                    Line::from(vec![
-                        Span::styled(
-                            "Countdown Timer ",
-                            Style::default().add_modifier(Modifier::BOLD),
-                        ),
-                        Span::raw(format!("{} minutes", val)),
-                        if is_selected {
-                            Span::raw("  <[h/l] to change>")
-                        } else {
-                            Span::raw("")
-                        },
-                    ])
-                }
-                SettingsItem::CowModal => {
-                    let val = if app.cow_modal_enabled {
-                        "Enabled 🐮"
-                    } else {
-                        "Disabled"
-                    };
-                    Line::from(vec![
-                        Span::styled(
-                            "Cow Modal       ",
-                            Style::default().add_modifier(Modifier::BOLD),
-                        ),
-                        Span::raw(val),
-                        if is_selected {
-                            Span::raw("  <[Space] to toggle>")
-                        } else {
-                            Span::raw("")
-                        },
-                    ])
-                }
-                SettingsItem::SyncAutoCommit => {
-                    let val = app
-                        .config
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("⚙️ Settings")
+                .title_bottom(
+                    Line::from(" [E]Export config [I]Import config [B]Branches ").right_aligned(),
+                ),
+        )
+        .highlight_symbol("→ ");
+
+    let mut state = ratatui::widgets::ListState::default();
+    state.select(Some(settings::row_of_item(app.selected_setting_index)));
+
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// The latest sync commits, with what each auto-commit recorded.
+fn draw_sync_history(f: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .sync_history
+        .iter()
+        .map(|commit| {
+            let when = commit["time"]
+                .as_str()
+                .and_then(|t| t.parse::<DateTime<Local>>().ok())
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            let mut spans = vec![
+                Span::styled(format!("  {}  ", when), Style::default().fg(Color::Gray)),
+                Span::raw(commit["summary"].as_str().unwrap_or("").to_string()),
+            ];
+            if let Some(profile) = commit["profile_id"].as_str() {
+                spans.push(Span::styled(
+                    format!("  [{}]", profile),
+                    Style::default().fg(Color::Cyan),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Recent Sync Activity "),
+    );
+    f.render_widget(list, area);
+}
+
+fn setting_line<'a>(app: &'a App, def: &'a SettingDef, is_selected: bool) -> Line<'a> {
+    let label = Span::styled(
+        format!("  {:<width$}", def.label, width = LABEL_WIDTH),
+        Style::default().add_modifier(Modifier::BOLD),
+    );
+
+    let (value, show_hint) = match def.target {
+        SettingTarget::Action(action) => {
+            let initialized = app
+                .sync_status
+                .as_ref()
+                .and_then(|s| s.get("initialized"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            match (action, initialized) {
+                (SettingAction::SyncInit, true) => (Span::raw("Initialized"), false),
+                (SettingAction::SyncInit, false) => (Span::raw("Not Initialized"), true),
+                (SettingAction::SyncNow, true) => (Span::raw("Ready"), true),
+                (SettingAction::SyncChanges, true) => {
+                    let has_changes = app
+                        .sync_status
                         .as_ref()
-                        .and_then(|c| c.get("sync").and_then(|s| s.get("auto_commit")))
+                        .and_then(|s| s.get("has_changes"))
                         .and_then(|v| v.as_bool())
                         .unwrap_or(false);
-                    Line::from(vec![
-                        Span::styled(
-                            "Auto-Commit     ",
-                            Style::default().add_modifier(Modifier::BOLD),
-                        ),
-                        Span::raw(if val { "Enabled" } else { "Disabled" }),
-                        if is_selected {
-                            Span::raw("  <[Space] to toggle>")
-                        } else {
-                            Span::raw("")
-                        },
-                    ])
+                    if has_changes {
+                        (
+                            Span::styled("Uncommitted", Style::default().fg(Color::Yellow)),
+                            true,
+                        )
+                    } else {
+                        (Span::raw("None"), true)
+                    }
                 }
-                SettingsItem::SyncInitRepo => {
-                    let val = app
+                (SettingAction::SyncConflicts, true) => {
+                    let conflicts = app
                         .sync_status
                         .as_ref()
-                        .and_then(|s| s.get("initialized"))
-                        .and_then(|v| v.as_bool())
-                        .unwrap_or(false);
-                    Line::from(vec![
-                        Span::styled(
-                            "Git Repository  ",
-                            Style::default().add_modifier(Modifier::BOLD),
-                        ),
-                        Span::raw(if val {
-                            "Initialized"
-                        } else {
-                            "Not Initialized"
-                        }),
-                        if is_selected && !val {
-                            Span::raw("  <[Enter] to initialize>")
-                        } else {
-                            Span::raw("")
-                        },
-                    ])
+                        .and_then(|s| s.get("conflicts"))
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0);
+                    if conflicts > 0 {
+                        (
+                            Span::styled(
+                                format!("{} unresolved", conflicts),
+                                Style::default().fg(Color::Red),
+                            ),
+                            true,
+                        )
+                    } else {
+                        (Span::raw("None"), false)
+                    }
                 }
-                SettingsItem::SyncNow => {
-                    let initialized = app
+                (SettingAction::SyncNow, false)
+                | (SettingAction::BranchPicker, false)
+                | (SettingAction::SyncChanges, false)
+                | (SettingAction::SyncConflicts, false) => (
+                    Span::styled("Not Initialized", Style::default().fg(Color::Red)),
+                    false,
+                ),
+                (SettingAction::BranchPicker, true) => {
+                    let branch = app
                         .sync_status
                         .as_ref()
-                        .and_then(|s| s.get("initialized"))
-                        .and_then(|v| v.as_bool())
-                        .unwrap_or(false);
-
-                    Line::from(vec![
-                        Span::styled(
-                            "Sync Now        ",
-                            Style::default().add_modifier(Modifier::BOLD),
-                        ),
-                        if initialized {
-                            Span::raw("Ready")
-                        } else {
-                            Span::styled("Not Initialized", Style::default().fg(Color::Red))
-                        },
-                        if is_selected && initialized {
-                            Span::raw("  <[Enter] to sync>")
-                        } else {
-                            Span::raw("")
-                        },
-                    ])
+                        .and_then(|s| s.get("current_branch"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("(no commits yet)");
+                    (Span::styled(branch, Style::default().fg(Color::Cyan)), true)
                 }
-            };
-            ListItem::new(line).style(style)
-        })
-        .collect();
-
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("⚙️ Settings"))
-        .highlight_symbol("→ ");
+            }
+        }
+        _ => {
+            let value = app.setting_value(def);
+            (Span::raw(def.display(value.as_ref())), true)
+        }
+    };
 
-    let mut state = ratatui::widgets::ListState::default();
-    state.select(Some(app.selected_setting_index));
+    let hint = if is_selected && show_hint {
+        Span::raw(format!("  {}", def.hint()))
+    } else {
+        Span::raw("")
+    };
 
-    f.render_stateful_widget(list, area, &mut state);
+    Line::from(vec![label, value, hint])
 }
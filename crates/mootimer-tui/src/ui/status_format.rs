@@ -0,0 +1,98 @@
+//! Parsing for the `tui.status_bar_format` template.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusVar {
+    TimerElapsed,
+    TimerTask,
+    TimerState,
+    TodayTotal,
+    Profile,
+    View,
+    Hints,
+}
+
+impl StatusVar {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "timer_elapsed" => Some(Self::TimerElapsed),
+            "timer_task" => Some(Self::TimerTask),
+            "timer_state" => Some(Self::TimerState),
+            "today_total" => Some(Self::TodayTotal),
+            "profile" => Some(Self::Profile),
+            "view" => Some(Self::View),
+            "hints" => Some(Self::Hints),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusSegment<'a> {
+    Text(&'a str),
+    Var(StatusVar),
+}
+
+/// Splits `template` into literal text and `{variable}` references. Unknown
+/// or unterminated braces are kept as text so a typo stays visible.
+pub fn parse_status_format(template: &str) -> Vec<StatusSegment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let Some(var) = StatusVar::from_name(&rest[start + 1..start + len]) else {
+            segments.push(StatusSegment::Text(&rest[..start + len + 1]));
+            rest = &rest[start + len + 1..];
+            continue;
+        };
+        if start > 0 {
+            segments.push(StatusSegment::Text(&rest[..start]));
+        }
+        segments.push(StatusSegment::Var(var));
+        rest = &rest[start + len + 1..];
+    }
+
+    if !rest.is_empty() {
+        segments.push(StatusSegment::Text(rest));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mootimer_core::models::config::{DEFAULT_STATUS_BAR_FORMAT, STATUS_BAR_VARIABLES};
+
+    #[test]
+    fn test_parse_status_format() {
+        assert_eq!(
+            parse_status_format("⏱ {timer_elapsed} on {timer_task}"),
+            vec![
+                StatusSegment::Text("⏱ "),
+                StatusSegment::Var(StatusVar::TimerElapsed),
+                StatusSegment::Text(" on "),
+                StatusSegment::Var(StatusVar::TimerTask),
+            ]
+        );
+        assert_eq!(
+            parse_status_format("{view}{oops} {unclosed"),
+            vec![
+                StatusSegment::Var(StatusVar::View),
+                StatusSegment::Text("{oops}"),
+                StatusSegment::Text(" {unclosed"),
+            ]
+        );
+        assert_eq!(
+            parse_status_format(DEFAULT_STATUS_BAR_FORMAT)
+                .iter()
+                .filter(|s| matches!(s, StatusSegment::Var(_)))
+                .count(),
+            3
+        );
+        for name in STATUS_BAR_VARIABLES {
+            assert!(StatusVar::from_name(name).is_some(), "{}", name);
+        }
+    }
+}
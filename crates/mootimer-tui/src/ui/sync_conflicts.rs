@@ -0,0 +1,160 @@
+use crate::app::App;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+use serde_json::Value;
+
+fn sync_conflicts_modal_area(area: Rect) -> Rect {
+    let width = (area.width as f32 * 0.9) as u16;
+    let height = (area.height as f32 * 0.85) as u16;
+    Rect {
+        x: area.width.saturating_sub(width) / 2,
+        y: area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    }
+}
+
+/// The conflicted record's title or description, whichever side has one.
+fn conflict_label(conflict: &Value) -> String {
+    let name = |side: &Value| {
+        side["title"]
+            .as_str()
+            .or_else(|| side["description"].as_str())
+            .map(str::to_string)
+    };
+    let kind = conflict["kind"].as_str().unwrap_or("?");
+    let name = name(&conflict["local"])
+        .or_else(|| name(&conflict["remote"]))
+        .unwrap_or_else(|| {
+            let id = conflict["record_id"].as_str().unwrap_or("?");
+            id.chars().take(8).collect()
+        });
+    format!("{}: {}", kind, name)
+}
+
+fn field_text(value: &Value, width: usize) -> String {
+    let text = match value {
+        Value::Null => "-".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let text = text.replace('\n', " ");
+    if text.chars().count() > width {
+        let mut short: String = text.chars().take(width.saturating_sub(1)).collect();
+        short.push('…');
+        short
+    } else {
+        text
+    }
+}
+
+fn field_lines(app: &App, width: u16) -> Vec<Line<'static>> {
+    let Some(conflict) = app.sync_conflicts.get(app.selected_conflict_index) else {
+        return Vec::new();
+    };
+    let value_width = (width.saturating_sub(24) / 2).max(8) as usize;
+
+    let side = |value: &Value| {
+        if value.is_null() {
+            "deleted"
+        } else {
+            "changed"
+        }
+    };
+    let mut lines = vec![
+        Line::from(format!(
+            "  Local {}, remote {} ({})",
+            side(&conflict["local"]),
+            side(&conflict["remote"]),
+            conflict["detected_at"].as_str().unwrap_or("?"),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "  {:<18} {:<w$} {:<w$}",
+                "Field",
+                "Local",
+                "Remote",
+                w = value_width
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+    ];
+
+    for (index, (name, local, remote)) in app.conflict_fields().into_iter().enumerate() {
+        let differs = local != remote;
+        let takes_remote = app.conflict_remote_fields.contains(&name);
+        let picked = Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::BOLD);
+        let (local_style, remote_style) = match (differs, takes_remote) {
+            (false, _) => (Style::default(), Style::default()),
+            (true, false) => (picked, Style::default().fg(Color::DarkGray)),
+            (true, true) => (Style::default().fg(Color::DarkGray), picked),
+        };
+        let name_style = match (app.conflict_field_index == Some(index), differs) {
+            (true, _) => Style::default().add_modifier(Modifier::REVERSED),
+            (false, true) => Style::default().fg(Color::Yellow),
+            (false, false) => Style::default(),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<18} ", field_text(&Value::String(name), 18)),
+                name_style,
+            ),
+            Span::styled(
+                format!("{:<w$} ", field_text(&local, value_width), w = value_width),
+                local_style,
+            ),
+            Span::styled(field_text(&remote, value_width), remote_style),
+        ]));
+    }
+    lines
+}
+
+pub fn draw_sync_conflicts_modal(f: &mut Frame, app: &App) {
+    let modal_area = sync_conflicts_modal_area(f.area());
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Sync Conflicts ({}) ", app.sync_conflicts.len()))
+        .title_bottom(
+            Line::from(" [l]Local [r]Remote [Tab]Fields [Space]Pick side [m]Merged [Esc]Close ")
+                .right_aligned(),
+        )
+        .border_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .split(inner);
+
+    let items: Vec<ListItem> = app
+        .sync_conflicts
+        .iter()
+        .map(|conflict| ListItem::new(format!(" {}", conflict_label(conflict))))
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::RIGHT))
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+    let mut state = ListState::default().with_selected(Some(app.selected_conflict_index));
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    let scroll = app.conflict_field_index.map_or(0, |index| {
+        (index + 4).saturating_sub(chunks[1].height as usize)
+    });
+    let fields = Paragraph::new(field_lines(app, chunks[1].width)).scroll((scroll as u16, 0));
+    f.render_widget(fields, chunks[1]);
+}
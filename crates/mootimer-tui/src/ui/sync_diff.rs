@@ -0,0 +1,107 @@
+use crate::app::App;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+fn sync_diff_modal_area(area: Rect) -> Rect {
+    let width = (area.width as f32 * 0.85) as u16;
+    let height = (area.height as f32 * 0.85) as u16;
+    Rect {
+        x: area.width.saturating_sub(width) / 2,
+        y: area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    }
+}
+
+fn stats_lines(app: &App) -> Vec<Line<'static>> {
+    let Some(stats) = &app.sync_diff else {
+        return vec![Line::from("  Loading...")];
+    };
+    let files = stats["files"].as_array().cloned().unwrap_or_default();
+    if files.is_empty() {
+        return vec![Line::from("  Nothing to commit")];
+    }
+
+    let mut lines: Vec<Line> = files
+        .iter()
+        .map(|file| {
+            Line::from(vec![
+                Span::raw(format!("  {:<50} ", file["path"].as_str().unwrap_or("?"))),
+                Span::styled(
+                    format!("+{:<5}", file["insertions"].as_u64().unwrap_or(0)),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!("-{}", file["deletions"].as_u64().unwrap_or(0)),
+                    Style::default().fg(Color::Red),
+                ),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!(
+            "  {} file(s) changed, {} insertion(s), {} deletion(s)",
+            files.len(),
+            stats["insertions"].as_u64().unwrap_or(0),
+            stats["deletions"].as_u64().unwrap_or(0),
+        ),
+        Style::default().add_modifier(Modifier::BOLD),
+    )));
+    lines
+}
+
+fn diff_line(line: &str) -> Line<'_> {
+    let style =
+        if line.starts_with("diff --git") || line.starts_with("+++") || line.starts_with("---") {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else if line.starts_with("@@") {
+            Style::default().fg(Color::Cyan)
+        } else if line.starts_with('+') {
+            Style::default().fg(Color::Green)
+        } else if line.starts_with('-') {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default()
+        };
+    Line::from(Span::styled(line, style))
+}
+
+pub fn draw_sync_diff_modal(f: &mut Frame, app: &App) {
+    let modal_area = sync_diff_modal_area(f.area());
+    f.render_widget(Clear, modal_area);
+
+    let (title, hint, lines) = match (&app.sync_diff_text, app.show_full_diff) {
+        (Some(text), true) => (
+            " Uncommitted Changes: Full Diff ",
+            " [j/k]Scroll [F]Stats [Esc]Close ",
+            text.lines().map(diff_line).collect(),
+        ),
+        _ => (
+            " Uncommitted Changes ",
+            " [j/k]Scroll [F]Full diff [Esc]Close ",
+            stats_lines(app),
+        ),
+    };
+
+    let paragraph = Paragraph::new(lines)
+        .scroll((app.sync_diff_scroll as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .title_bottom(Line::from(hint).right_aligned())
+                .border_style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+        );
+
+    f.render_widget(paragraph, modal_area);
+}
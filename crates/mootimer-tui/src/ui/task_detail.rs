@@ -0,0 +1,155 @@
+use crate::app::App;
+use crate::task_detail::{DetailField, TaskDetail};
+use crate::ui::helpers::centered_rect;
+use ratatui::{
+    Frame,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+const LABEL_WIDTH: usize = 13;
+const DESCRIPTION_ROWS: usize = 6;
+
+pub fn draw_task_detail_modal(f: &mut Frame, app: &App) {
+    let Some(detail) = &app.task_detail else {
+        return;
+    };
+
+    let area = f.area();
+    let width = 70.min(area.width.saturating_sub(4));
+    let height = 24.min(area.height.saturating_sub(2));
+    let modal_area = centered_rect(area, width, height);
+    f.render_widget(Clear, modal_area);
+
+    let mut lines = vec![Line::from("")];
+    for field in DetailField::ALL {
+        lines.extend(field_lines(detail, field));
+        if let Some((Some(error_field), message)) = &detail.error
+            && *error_field == field
+        {
+            lines.push(error_line(message));
+        }
+    }
+
+    let blockers = app.blocker_titles(&detail.task);
+    if !blockers.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<width$}", "🔒 Blocked by", width = LABEL_WIDTH),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(blockers.join(", ")),
+        ]));
+    }
+
+    if let Some((None, message)) = &detail.error {
+        lines.push(Line::from(""));
+        lines.push(error_line(message));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  [Tab] Next field  [Ctrl+s] Save  [Esc] Cancel",
+        Style::default().fg(Color::Gray),
+    )));
+
+    let title = detail
+        .task
+        .get("title")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Task");
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("📝 {}", title))
+                .border_style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+        )
+        .style(Style::default().bg(Color::Black));
+
+    f.render_widget(paragraph, modal_area);
+}
+
+fn field_lines<'a>(detail: &'a TaskDetail, field: DetailField) -> Vec<Line<'a>> {
+    let focused = detail.field == field;
+    let label_style = if focused {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().add_modifier(Modifier::BOLD)
+    };
+    let value_style = if focused {
+        Style::default().bg(Color::DarkGray)
+    } else {
+        Style::default()
+    };
+    let marker = if focused { "→ " } else { "  " };
+    let label = Span::styled(
+        format!("{}{:<width$}", marker, field.label(), width = LABEL_WIDTH),
+        label_style,
+    );
+    let cursor = if focused { "_" } else { "" };
+
+    let value = match field {
+        DetailField::Title => detail.title.as_str(),
+        DetailField::Tags => detail.tags.as_str(),
+        DetailField::DueDate => detail.due_date.as_str(),
+        DetailField::Priority => {
+            let hint = if focused { "  ←/→ to change" } else { "" };
+            return vec![Line::from(vec![
+                label,
+                Span::styled(detail.priority.as_str(), value_style),
+                Span::styled(hint, Style::default().fg(Color::Gray)),
+            ])];
+        }
+        DetailField::Description => {
+            let indent = " ".repeat(LABEL_WIDTH + 2);
+            let text_lines: Vec<&str> = detail.description.split('\n').collect();
+            let start = text_lines.len().saturating_sub(DESCRIPTION_ROWS);
+            let last = text_lines.len() - 1;
+            return text_lines[start..]
+                .iter()
+                .enumerate()
+                .map(|(i, text)| {
+                    let prefix = if i == 0 {
+                        label.clone()
+                    } else {
+                        Span::raw(indent.clone())
+                    };
+                    let cursor = if start + i == last { cursor } else { "" };
+                    Line::from(vec![
+                        prefix,
+                        Span::styled(format!("{}{}", text, cursor), value_style),
+                    ])
+                })
+                .collect();
+        }
+    };
+
+    let placeholder = match field {
+        DetailField::Tags if value.is_empty() && focused => "comma, separated",
+        DetailField::DueDate if value.is_empty() && focused => "2024-07-01, tomorrow, next friday",
+        _ => "",
+    };
+
+    vec![Line::from(vec![
+        label,
+        Span::styled(format!("{}{}", value, cursor), value_style),
+        Span::styled(placeholder, Style::default().fg(Color::DarkGray)),
+    ])]
+}
+
+fn error_line(message: &str) -> Line<'_> {
+    Line::from(Span::styled(
+        format!("  ⚠ {}", message),
+        Style::default().fg(Color::Red),
+    ))
+}
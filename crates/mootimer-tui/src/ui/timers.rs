@@ -1,4 +1,5 @@
-use crate::app::App;
+use crate::app::{App, reported_display};
+use crate::keybindings::Action;
 use crate::ui::helpers::{focused_border_style, format_duration_hms};
 use ratatui::{
     Frame,
@@ -49,10 +50,7 @@ fn draw_timer_list(f: &mut Frame, app: &App, area: Rect) {
                     .get("state")
                     .and_then(|v| v.as_str())
                     .unwrap_or("stopped");
-                let elapsed = timer
-                    .get("elapsed_seconds")
-                    .and_then(|v| v.as_u64())
-                    .unwrap_or(0);
+                let elapsed = reported_display(timer).map_or(0, |d| d.elapsed_seconds);
 
                 let task_title = timer
                     .get("task_title")
@@ -102,7 +100,11 @@ fn draw_timer_list(f: &mut Frame, app: &App, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
         .title(title)
-        .title_bottom(" [j/k]Nav [Space]Pause [x]Stop [r]Refresh ".to_string())
+        .title_bottom(format!(
+            " [j/k]Nav [{}]Pause [{}]Stop [r]Refresh ",
+            app.keybindings.label(Action::TimerPause),
+            app.keybindings.label(Action::TimerStop)
+        ))
         .border_style(focused_border_style(true));
 
     let list = List::new(items).block(block);
@@ -145,10 +147,8 @@ fn draw_timer_detail_content(f: &mut Frame, timer: &Value, area: Rect) {
         .get("state")
         .and_then(|v| v.as_str())
         .unwrap_or("stopped");
-    let elapsed = timer
-        .get("elapsed_seconds")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(0);
+    let display = reported_display(timer);
+    let elapsed = display.as_ref().map_or(0, |d| d.elapsed_seconds);
 
     let task_title = timer
         .get("task_title")
@@ -237,42 +237,28 @@ fn draw_timer_detail_content(f: &mut Frame, timer: &Value, area: Rect) {
     ];
 
     // Add Pomodoro-specific info
-    if let Some(pomo_state) = timer.get("pomodoro_state")
-        && let Some(phase) = pomo_state.get("phase").and_then(|v| v.as_str())
-    {
-        let session = pomo_state
-            .get("current_session")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(1);
+    if let Some(display) = &display {
+        if timer.get("pomodoro_state").is_some_and(|p| !p.is_null()) {
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::styled("  Phase:    ", Style::default().add_modifier(Modifier::DIM)),
+                Span::styled(
+                    display.phase_display.clone(),
+                    Style::default().fg(Color::Magenta),
+                ),
+            ]));
+        }
 
-        let phase_display = match phase {
-            "work" => "Work",
-            "short_break" => "Short Break",
-            "long_break" => "Long Break",
-            _ => phase,
-        };
-
-        lines.push(Line::from(""));
-        lines.push(Line::from(vec![
-            Span::styled("  Phase:    ", Style::default().add_modifier(Modifier::DIM)),
-            Span::styled(
-                format!("{} (Session {})", phase_display, session),
-                Style::default().fg(Color::Magenta),
-            ),
-        ]));
-    }
-
-    // Add Countdown-specific info
-    if let Some(target) = timer.get("target_duration").and_then(|v| v.as_u64()) {
-        let remaining = target.saturating_sub(elapsed);
-        lines.push(Line::from(""));
-        lines.push(Line::from(vec![
-            Span::styled("  Remaining:", Style::default().add_modifier(Modifier::DIM)),
-            Span::styled(
-                format!(" {}", format_duration_hms(remaining)),
-                Style::default().fg(Color::Cyan),
-            ),
-        ]));
+        if let Some(remaining) = display.remaining_seconds {
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::styled("  Remaining:", Style::default().add_modifier(Modifier::DIM)),
+                Span::styled(
+                    format!(" {}", format_duration_hms(remaining)),
+                    Style::default().fg(Color::Cyan),
+                ),
+            ]));
+        }
     }
 
     let paragraph = Paragraph::new(lines);
@@ -0,0 +1,99 @@
+use crate::wizard::{SetupWizard, WizardStep};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph, Wrap},
+};
+
+pub fn draw_wizard(f: &mut Frame, wizard: &SetupWizard) {
+    let area = f.area();
+    let step = wizard.step;
+
+    let outer = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(" 🐮 Welcome to MooTimer ")
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = outer.inner(area);
+    f.render_widget(outer, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(25),
+            Constraint::Length(2),
+            Constraint::Length(3),
+            Constraint::Length(5),
+            Constraint::Length(2),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let heading = Paragraph::new(vec![
+        Line::from(Span::styled(
+            format!("Step {} of {}", step.index() + 1, WizardStep::ALL.len()),
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(Span::styled(
+            step.title(),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+    ])
+    .alignment(Alignment::Center);
+    f.render_widget(heading, chunks[1]);
+
+    let help = Paragraph::new(step.help())
+        .style(Style::default().fg(Color::Gray))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(help, chunks[2]);
+
+    let input_area = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .split(chunks[3])[1];
+
+    let field = wizard.field();
+    let text = if field.value.is_empty() {
+        Span::styled(field.placeholder, Style::default().fg(Color::DarkGray))
+    } else {
+        Span::styled(
+            field.value.as_str(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+    };
+    let input = Paragraph::new(vec![Line::default(), Line::from(text)]).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(input, input_area);
+
+    let cursor_x = (input_area.x + 1 + field.value.chars().count() as u16)
+        .min(input_area.x + input_area.width.saturating_sub(2));
+    f.set_cursor_position((cursor_x, input_area.y + 2));
+
+    if let Some(ref error) = wizard.error {
+        let error = Paragraph::new(error.as_str())
+            .style(Style::default().fg(Color::Red))
+            .alignment(Alignment::Center);
+        f.render_widget(error, chunks[4]);
+    }
+
+    let back = if step.index() == 0 { "Quit" } else { "Back" };
+    let hints = Paragraph::new(format!(" [Enter] Next  [Esc] {} ", back))
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+    f.render_widget(hints, chunks[6]);
+}
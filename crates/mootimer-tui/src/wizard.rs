@@ -0,0 +1,349 @@
+//! First-run setup wizard.
+//!
+//! Shown when the daemon has no profiles yet and no `--profile` was given.
+//! It asks for the user's name, a first profile, a timezone and an optional
+//! git remote, one full-screen step at a time, then saves the answers to
+//! the daemon config (the same file the Settings view edits) and creates
+//! the profile.
+
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use mootimer_client::MooTimerClient;
+use serde_json::json;
+use std::path::Path;
+
+const ZONEINFO_DIR: &str = "/usr/share/zoneinfo";
+
+/// A single-line text input.
+#[derive(Debug, Clone, Default)]
+pub struct InputField {
+    pub value: String,
+    pub placeholder: &'static str,
+}
+
+impl InputField {
+    pub fn new(placeholder: &'static str) -> Self {
+        Self {
+            value: String::new(),
+            placeholder,
+        }
+    }
+
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        self.value = value.into();
+        self
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.value.push(c);
+    }
+
+    pub fn backspace(&mut self) {
+        self.value.pop();
+    }
+
+    /// The trimmed value, or `None` if only whitespace was entered.
+    pub fn text(&self) -> Option<&str> {
+        Some(self.value.trim()).filter(|v| !v.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WizardStep {
+    Name,
+    Profile,
+    Timezone,
+    Sync,
+}
+
+impl WizardStep {
+    pub const ALL: [WizardStep; 4] = [
+        WizardStep::Name,
+        WizardStep::Profile,
+        WizardStep::Timezone,
+        WizardStep::Sync,
+    ];
+
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|s| *s == self).unwrap_or(0)
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            WizardStep::Name => "What's your name?",
+            WizardStep::Profile => "Name your first profile",
+            WizardStep::Timezone => "Which timezone are you in?",
+            WizardStep::Sync => "Sync with a git remote (optional)",
+        }
+    }
+
+    pub fn help(self) -> &'static str {
+        match self {
+            WizardStep::Name => "Used to label your work. You can change it later.",
+            WizardStep::Profile => {
+                "Profiles keep separate tasks and time entries, e.g. \"Work\" or \"Side project\"."
+            }
+            WizardStep::Timezone => {
+                "An IANA name such as Europe/Berlin. Leave empty to follow the system."
+            }
+            WizardStep::Sync => {
+                "Your data is committed to a local git repository and pushed here. Leave empty to skip."
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WizardAction {
+    Continue,
+    Done,
+    Cancel,
+}
+
+/// What the user entered, ready to be saved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetupAnswers {
+    pub user_name: String,
+    pub profile_id: String,
+    pub profile_name: String,
+    /// `None` when the user kept the system timezone.
+    pub timezone: Option<String>,
+    pub remote_url: Option<String>,
+}
+
+pub struct SetupWizard {
+    pub step: WizardStep,
+    pub fields: [InputField; 4],
+    pub error: Option<String>,
+    system_timezone: Option<String>,
+}
+
+impl SetupWizard {
+    pub fn new() -> Self {
+        let system_timezone = detect_timezone();
+        let timezone = InputField::new("system default")
+            .with_value(system_timezone.clone().unwrap_or_default());
+
+        Self {
+            step: WizardStep::Name,
+            fields: [
+                InputField::new("Your name"),
+                InputField::new("Work"),
+                timezone,
+                InputField::new("git@github.com:you/mootimer-data.git"),
+            ],
+            error: None,
+            system_timezone,
+        }
+    }
+
+    pub fn field(&self) -> &InputField {
+        &self.fields[self.step.index()]
+    }
+
+    fn field_mut(&mut self) -> &mut InputField {
+        &mut self.fields[self.step.index()]
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> WizardAction {
+        match code {
+            KeyCode::Char(c) => {
+                self.field_mut().insert(c);
+                self.error = None;
+            }
+            KeyCode::Backspace => {
+                self.field_mut().backspace();
+                self.error = None;
+            }
+            KeyCode::Enter => {
+                if let Err(e) = self.check_step() {
+                    self.error = Some(e);
+                    return WizardAction::Continue;
+                }
+                self.error = None;
+                match WizardStep::ALL.get(self.step.index() + 1) {
+                    Some(next) => self.step = *next,
+                    None => return WizardAction::Done,
+                }
+            }
+            KeyCode::Esc => {
+                self.error = None;
+                match self.step.index().checked_sub(1) {
+                    Some(prev) => self.step = WizardStep::ALL[prev],
+                    None => return WizardAction::Cancel,
+                }
+            }
+            _ => {}
+        }
+        WizardAction::Continue
+    }
+
+    fn check_step(&self) -> Result<(), String> {
+        let value = self.field().text();
+        match self.step {
+            WizardStep::Name if value.is_none() => Err("Please enter a name".to_string()),
+            WizardStep::Profile => match value {
+                Some(name) if !profile_id_for(name).is_empty() => Ok(()),
+                Some(_) => Err("Use at least one letter or digit".to_string()),
+                None => Err("Please name your profile".to_string()),
+            },
+            WizardStep::Timezone => match value {
+                Some(tz) if !is_known_timezone(tz) => Err(format!("Unknown timezone '{}'", tz)),
+                _ => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    pub fn answers(&self) -> SetupAnswers {
+        let text = |step: WizardStep| self.fields[step.index()].text().map(str::to_string);
+        let profile_name = text(WizardStep::Profile).unwrap_or_default();
+
+        SetupAnswers {
+            user_name: text(WizardStep::Name).unwrap_or_default(),
+            profile_id: profile_id_for(&profile_name),
+            profile_name,
+            timezone: text(WizardStep::Timezone)
+                .filter(|tz| Some(tz) != self.system_timezone.as_ref()),
+            remote_url: text(WizardStep::Sync),
+        }
+    }
+}
+
+impl Default for SetupWizard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Turns a display name into a profile id: lowercase letters and digits,
+/// with runs of anything else collapsed to a single `-`.
+pub fn profile_id_for(name: &str) -> String {
+    let mut id = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            id.push(c);
+        } else if !id.is_empty() && !id.ends_with('-') {
+            id.push('-');
+        }
+    }
+    id.trim_end_matches('-').to_string()
+}
+
+/// The system timezone, from `TZ` or the `/etc/localtime` symlink.
+fn detect_timezone() -> Option<String> {
+    if let Ok(tz) = std::env::var("TZ") {
+        let tz = tz.trim_start_matches(':');
+        if !tz.is_empty() {
+            return Some(tz.to_string());
+        }
+    }
+
+    let target = std::fs::read_link("/etc/localtime").ok()?;
+    let target = target.to_string_lossy();
+    let (_, name) = target.split_once("zoneinfo/")?;
+    Some(name.to_string())
+}
+
+fn is_known_timezone(name: &str) -> bool {
+    !name.contains("..") && Path::new(ZONEINFO_DIR).join(name).is_file()
+}
+
+/// Saves the answers through the daemon and creates the profile.
+pub async fn apply(client: &MooTimerClient, answers: &SetupAnswers) -> Result<()> {
+    client
+        .call(
+            "config.set_current_user",
+            Some(json!({ "name": answers.user_name })),
+        )
+        .await?;
+
+    // A retry after a later step failed finds the profile already there.
+    if client.profile_get(&answers.profile_id).await.is_err() {
+        client
+            .profile_create(&answers.profile_id, &answers.profile_name, None)
+            .await?;
+    }
+    client
+        .call(
+            "config.set_default_profile",
+            Some(json!({ "profile_id": answers.profile_id })),
+        )
+        .await?;
+
+    if let Some(ref timezone) = answers.timezone {
+        client
+            .call("config.update_tui", Some(json!({ "timezone": timezone })))
+            .await?;
+    }
+
+    if let Some(ref url) = answers.remote_url {
+        client.call("sync.init", None).await?;
+        client
+            .call("sync.set_remote", Some(json!({ "url": url })))
+            .await?;
+        client
+            .call("config.update_sync", Some(json!({ "remote_url": url })))
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_text(wizard: &mut SetupWizard, text: &str) {
+        for c in text.chars() {
+            wizard.handle_key(KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_profile_id_for() {
+        assert_eq!(profile_id_for("Work"), "work");
+        assert_eq!(profile_id_for("  Side Project #2 "), "side-project-2");
+        assert_eq!(profile_id_for("!!!"), "");
+    }
+
+    #[test]
+    fn test_walks_through_steps() {
+        let mut wizard = SetupWizard::new();
+
+        assert_eq!(wizard.handle_key(KeyCode::Enter), WizardAction::Continue);
+        assert!(wizard.error.is_some());
+        assert_eq!(wizard.step, WizardStep::Name);
+
+        type_text(&mut wizard, "Ada");
+        wizard.handle_key(KeyCode::Enter);
+        type_text(&mut wizard, "Client Work");
+        wizard.handle_key(KeyCode::Enter);
+        assert_eq!(wizard.step, WizardStep::Timezone);
+
+        wizard.fields[WizardStep::Timezone.index()].value = "Nowhere/Special".to_string();
+        wizard.handle_key(KeyCode::Enter);
+        assert!(wizard.error.as_deref().unwrap().contains("Nowhere/Special"));
+        wizard.fields[WizardStep::Timezone.index()].value.clear();
+        wizard.handle_key(KeyCode::Enter);
+
+        assert_eq!(wizard.step, WizardStep::Sync);
+        assert_eq!(wizard.handle_key(KeyCode::Enter), WizardAction::Done);
+
+        let answers = wizard.answers();
+        assert_eq!(answers.user_name, "Ada");
+        assert_eq!(answers.profile_id, "client-work");
+        assert_eq!(answers.profile_name, "Client Work");
+        assert_eq!(answers.timezone, None);
+        assert_eq!(answers.remote_url, None);
+
+        wizard.handle_key(KeyCode::Esc);
+        assert_eq!(wizard.step, WizardStep::Timezone);
+    }
+
+    #[test]
+    fn test_escape_on_first_step_cancels() {
+        let mut wizard = SetupWizard::new();
+        assert_eq!(wizard.handle_key(KeyCode::Esc), WizardAction::Cancel);
+    }
+}